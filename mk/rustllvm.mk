@@ -38,6 +38,11 @@ ifeq ($$(findstring msvc,$(1)),msvc)
 EXTRA_RUSTLLVM_CXXFLAGS_$(1) := //EHsc
 endif
 
+# Our own LLVM exposes a few more APIs than upstream LLVM does.
+ifeq ($$(CFG_LLVM_ROOT),)
+EXTRA_RUSTLLVM_CXXFLAGS_$(1) += -DLLVM_RUSTLLVM
+endif
+
 $$(RT_OUTPUT_DIR_$(1))/$$(call CFG_STATIC_LIB_NAME_$(1),rustllvm): \
 	    $$(RUSTLLVM_OBJS_OBJS_$(1))
 	@$$(call E, link: $$@)
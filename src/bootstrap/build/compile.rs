@@ -198,6 +198,7 @@ pub fn rustc<'a>(build: &'a Build, target: &str, compiler: &Compiler<'a>) {
         let llvm_config = build.llvm_out(&build.config.build).join("bin")
                                .join(exe("llvm-config", target));
         cargo.env("LLVM_CONFIG", llvm_config);
+        cargo.env("LLVM_RUSTLLVM", "1");
     }
     if build.config.llvm_static_stdcpp {
         cargo.env("LLVM_STATIC_STDCPP",
@@ -304,6 +304,7 @@ extern "rust-intrinsic" {
     /// assert_eq!(array, [82, 117, 115, 116]);
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), rustc_diagnostic_item = "transmute")]
     pub fn transmute<T, U>(e: T) -> U;
 
     /// Gives the address for the return value of the enclosing function.
@@ -546,6 +547,10 @@ extern "rust-intrinsic" {
     /// Returns the nearest integer to an `f64`. Rounds half-way cases away from zero.
     pub fn roundf64(x: f64) -> f64;
 
+    // The `*_fast` intrinsics below only get LLVM's fast-math flags when
+    // the crate using them is compiled with `-Z fast-math`; otherwise they
+    // behave exactly like the ordinary operators.
+
     /// Float addition that allows optimizations based on algebraic rules.
     /// May assume inputs are finite.
     #[cfg(not(stage0))]
@@ -611,6 +616,14 @@ extern "rust-intrinsic" {
     /// cast to a `u64`; if `T` has no discriminant, returns 0.
     pub fn discriminant_value<T>(v: &T) -> u64;
 
+    /// Returns the number of variants of the enum `T`; if `T` is not an
+    /// enum, returns 0.
+    ///
+    /// Unlike other intrinsics, this may be called in constant
+    /// expressions, such as array lengths and repeat counts.
+    #[cfg(not(stage0))]
+    pub fn variant_count<T>() -> usize;
+
     /// Rust's "try catch" construct which invokes the function pointer `f` with
     /// the data pointer `data`.
     ///
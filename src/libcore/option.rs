@@ -159,6 +159,7 @@ use result::Result;
 /// The `Option` type. See [the module level documentation](index.html) for more.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
 #[stable(feature = "rust1", since = "1.0.0")]
+#[cfg_attr(not(stage0), rustc_diagnostic_item = "Option")]
 pub enum Option<T> {
     /// No value
     #[stable(feature = "rust1", since = "1.0.0")]
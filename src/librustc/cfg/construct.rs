@@ -284,6 +284,13 @@ impl<'a, 'tcx> CFGBuilder<'a, 'tcx> {
                 self.add_unreachable_node()
             }
 
+            hir::ExprBecome(ref v) => {
+                let v_exit = self.expr(&v, pred);
+                let b = self.add_ast_node(expr.id, &[v_exit]);
+                self.add_returning_edge(expr, b);
+                self.add_unreachable_node()
+            }
+
             hir::ExprBreak(label) => {
                 let loop_scope = self.find_scope(expr, label.map(|l| l.node.name));
                 let b = self.add_ast_node(expr.id, &[pred]);
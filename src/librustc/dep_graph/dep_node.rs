@@ -32,9 +32,16 @@ pub enum DepNode<D: Clone + Debug> {
     // Represents the HIR node with the given node-id
     Hir(D),
 
+    // Represents the bodies (fn bodies, const and static initializers)
+    // of the item with the given def-id, as distinct from its
+    // signature. Hashed separately, so that editing a body does not
+    // invalidate work that only depends on the signature.
+    HirBody(D),
+
     // Represents different phases in the compiler.
     CrateReader,
     CollectLanguageItems,
+    CollectDiagnosticItems,
     CheckStaticRecursion,
     ResolveLifetimes,
     RegionResolveCrate,
@@ -109,6 +116,7 @@ pub enum DepNode<D: Clone + Debug> {
     // which would yield an overly conservative dep-graph.
     TraitItems(D),
     ReprHints(D),
+    CodegenFnAttrs(D),
     TraitSelect(D),
 }
 
@@ -130,6 +138,7 @@ impl<D: Clone + Debug> DepNode<D> {
             TransCrateItem,
             TypeckItemType,
             TypeckItemBody,
+            WfCheck,
             ImplOrTraitItems,
             ItemSignature,
             FieldTy,
@@ -138,6 +147,7 @@ impl<D: Clone + Debug> DepNode<D> {
             ImplItems,
             TraitImpls,
             ReprHints,
+            CodegenFnAttrs,
         }
     }
 
@@ -150,6 +160,7 @@ impl<D: Clone + Debug> DepNode<D> {
             Krate => Some(Krate),
             CrateReader => Some(CrateReader),
             CollectLanguageItems => Some(CollectLanguageItems),
+            CollectDiagnosticItems => Some(CollectDiagnosticItems),
             CheckStaticRecursion => Some(CheckStaticRecursion),
             ResolveLifetimes => Some(ResolveLifetimes),
             RegionResolveCrate => Some(RegionResolveCrate),
@@ -173,6 +184,7 @@ impl<D: Clone + Debug> DepNode<D> {
             TransCrate => Some(TransCrate),
             TransWriteMetadata => Some(TransWriteMetadata),
             Hir(ref d) => op(d).map(Hir),
+            HirBody(ref d) => op(d).map(HirBody),
             CollectItem(ref d) => op(d).map(CollectItem),
             CoherenceCheckImpl(ref d) => op(d).map(CoherenceCheckImpl),
             CoherenceOverlapCheck(ref d) => op(d).map(CoherenceOverlapCheck),
@@ -201,6 +213,7 @@ impl<D: Clone + Debug> DepNode<D> {
             TraitImpls(ref d) => op(d).map(TraitImpls),
             TraitItems(ref d) => op(d).map(TraitItems),
             ReprHints(ref d) => op(d).map(ReprHints),
+            CodegenFnAttrs(ref d) => op(d).map(CodegenFnAttrs),
             TraitSelect(ref d) => op(d).map(TraitSelect),
         }
     }
@@ -106,7 +106,9 @@ impl<M: DepTrackingMapConfig> MemoizationMap for RefCell<DepTrackingMap<M>> {
     ///
     /// The key is the line marked `(*)`: the closure implicitly
     /// accesses the body of the item `item`, so we register a read
-    /// from `Hir(item_def_id)`.
+    /// from `Hir(item_def_id)`. (The signature is all it needs; code
+    /// that also looks at fn bodies or const initializers should read
+    /// `HirBody(item_def_id)` as well.)
     fn memoize<OP>(&self, key: M::Key, op: OP) -> M::Value
         where OP: FnOnce() -> M::Value
     {
@@ -22,6 +22,10 @@ use super::dep_node::DepNode;
 /// read edge from the corresponding AST node. This is used in
 /// compiler passes to automatically record the item that they are
 /// working on.
+///
+/// Only a read of the item's `Hir` is recorded up front. Visitors that
+/// walk bodies must record reads of their `HirBody` themselves, by
+/// overriding `Visitor::visit_body_id` to call `Map::read_body`.
 pub fn visit_all_items_in_krate<'tcx,V,F>(tcx: &TyCtxt<'tcx>,
                                           mut dep_node_fn: F,
                                           visitor: &mut V)
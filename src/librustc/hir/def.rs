@@ -31,6 +31,7 @@ pub enum Def {
     TyAlias(DefId),
     AssociatedTy(DefId /* trait */, DefId),
     Trait(DefId),
+    TraitAlias(DefId),
     PrimTy(hir::PrimTy),
     TyParam(ParamSpace, u32, DefId, ast::Name),
     Upvar(DefId,        // def id of closed over local
@@ -113,7 +114,7 @@ impl Def {
 
             Def::Fn(..) | Def::Mod(..) | Def::ForeignMod(..) | Def::Static(..) |
             Def::Variant(..) | Def::Enum(..) | Def::TyAlias(..) | Def::AssociatedTy(..) |
-            Def::TyParam(..) | Def::Struct(..) | Def::Trait(..) |
+            Def::TyParam(..) | Def::Struct(..) | Def::Trait(..) | Def::TraitAlias(..) |
             Def::Method(..) | Def::Const(..) | Def::AssociatedConst(..) |
             Def::PrimTy(..) | Def::Label(..) | Def::SelfTy(..) | Def::Err => {
                 bug!("attempted .var_id() on invalid {:?}", self)
@@ -125,7 +126,7 @@ impl Def {
         match *self {
             Def::Fn(id) | Def::Mod(id) | Def::ForeignMod(id) | Def::Static(id, _) |
             Def::Variant(_, id) | Def::Enum(id) | Def::TyAlias(id) | Def::AssociatedTy(_, id) |
-            Def::TyParam(_, _, id, _) | Def::Struct(id) | Def::Trait(id) |
+            Def::TyParam(_, _, id, _) | Def::Struct(id) | Def::Trait(id) | Def::TraitAlias(id) |
             Def::Method(id) | Def::Const(id) | Def::AssociatedConst(id) |
             Def::Local(id, _) | Def::Upvar(id, _, _, _) => {
                 id
@@ -161,6 +162,7 @@ impl Def {
             Def::AssociatedTy(..) => "associated type",
             Def::Struct(..) => "struct",
             Def::Trait(..) => "trait",
+            Def::TraitAlias(..) => "trait alias",
             Def::Method(..) => "method",
             Def::Const(..) => "const",
             Def::AssociatedConst(..) => "associated const",
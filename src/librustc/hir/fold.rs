@@ -565,13 +565,14 @@ pub fn noop_fold_ty_param_bound<T>(tpb: TyParamBound, fld: &mut T) -> TyParamBou
 }
 
 pub fn noop_fold_ty_param<T: Folder>(tp: TyParam, fld: &mut T) -> TyParam {
-    let TyParam {id, name, bounds, default, span} = tp;
+    let TyParam {id, name, bounds, default, span, pure_wrt_drop} = tp;
     TyParam {
         id: fld.new_id(id),
         name: name,
         bounds: fld.fold_bounds(bounds),
         default: default.map(|x| fld.fold_ty(x)),
         span: span,
+        pure_wrt_drop: pure_wrt_drop,
     }
 }
 
@@ -593,6 +594,7 @@ pub fn noop_fold_lifetime_def<T: Folder>(l: LifetimeDef, fld: &mut T) -> Lifetim
     LifetimeDef {
         lifetime: fld.fold_lifetime(l.lifetime),
         bounds: fld.fold_lifetimes(l.bounds),
+        pure_wrt_drop: l.pure_wrt_drop,
     }
 }
 
@@ -806,6 +808,9 @@ pub fn noop_fold_item_underscore<T: Folder>(i: Item_, folder: &mut T) -> Item_ {
             let items = items.move_map(|item| folder.fold_trait_item(item));
             ItemTrait(unsafety, folder.fold_generics(generics), bounds, items)
         }
+        ItemTraitAlias(generics, bounds) => {
+            ItemTraitAlias(folder.fold_generics(generics), folder.fold_bounds(bounds))
+        }
     }
 }
 
@@ -1099,6 +1104,7 @@ pub fn noop_fold_expr<T: Folder>(Expr { id, node, span, attrs }: Expr, folder: &
                 respan(folder.new_span(label.span), folder.fold_ident(label.node))
             })),
             ExprRet(e) => ExprRet(e.map(|x| folder.fold_expr(x))),
+            ExprBecome(e) => ExprBecome(folder.fold_expr(e)),
             ExprInlineAsm(asm, outputs, inputs) => {
                 ExprInlineAsm(asm,
                               outputs.move_map(|x| folder.fold_expr(x)),
@@ -92,6 +92,16 @@ pub trait Visitor<'v> : Sized {
         walk_item(self, i)
     }
 
+    /// Invoked right before the body `id` of a fn, method, const,
+    /// static or associated const is walked; see `BodyId`. By default,
+    /// does nothing. Visitors that look at bodies from within a
+    /// dependency-tracked task (see `dep_graph::visit_all_items_in_krate`)
+    /// should override it to call `Map::read_body`, so that the task
+    /// depends on the body of an item only if it actually visits it.
+    #[allow(unused_variables)]
+    fn visit_body_id(&mut self, id: BodyId) {
+    }
+
     ///////////////////////////////////////////////////////////////////////////
 
     fn visit_name(&mut self, _span: Span, _name: Name) {
@@ -316,9 +326,11 @@ pub fn walk_item<'v, V: Visitor<'v>>(visitor: &mut V, item: &'v Item) {
         ItemStatic(ref typ, _, ref expr) |
         ItemConst(ref typ, ref expr) => {
             visitor.visit_ty(typ);
+            visitor.visit_body_id(BodyId { node_id: expr.id });
             visitor.visit_expr(expr);
         }
         ItemFn(ref declaration, unsafety, constness, abi, ref generics, ref body) => {
+            visitor.visit_body_id(BodyId { node_id: body.id });
             visitor.visit_fn(FnKind::ItemFn(item.name,
                                             generics,
                                             unsafety,
@@ -363,6 +375,10 @@ pub fn walk_item<'v, V: Visitor<'v>>(visitor: &mut V, item: &'v Item) {
             walk_list!(visitor, visit_ty_param_bound, bounds);
             walk_list!(visitor, visit_trait_item, methods);
         }
+        ItemTraitAlias(ref generics, ref bounds) => {
+            visitor.visit_generics(generics);
+            walk_list!(visitor, visit_ty_param_bound, bounds);
+        }
     }
     walk_list!(visitor, visit_attribute, &item.attrs);
 }
@@ -637,7 +653,10 @@ pub fn walk_trait_item<'v, V: Visitor<'v>>(visitor: &mut V, trait_item: &'v Trai
     match trait_item.node {
         ConstTraitItem(ref ty, ref default) => {
             visitor.visit_ty(ty);
-            walk_list!(visitor, visit_expr, default);
+            if let Some(ref expr) = *default {
+                visitor.visit_body_id(BodyId { node_id: expr.id });
+                visitor.visit_expr(expr);
+            }
         }
         MethodTraitItem(ref sig, None) => {
             visitor.visit_explicit_self(&sig.explicit_self);
@@ -645,6 +664,7 @@ pub fn walk_trait_item<'v, V: Visitor<'v>>(visitor: &mut V, trait_item: &'v Trai
             walk_fn_decl(visitor, &sig.decl);
         }
         MethodTraitItem(ref sig, Some(ref body)) => {
+            visitor.visit_body_id(BodyId { node_id: body.id });
             visitor.visit_fn(FnKind::Method(trait_item.name,
                                             sig,
                                             None,
@@ -667,9 +687,11 @@ pub fn walk_impl_item<'v, V: Visitor<'v>>(visitor: &mut V, impl_item: &'v ImplIt
     match impl_item.node {
         ImplItemKind::Const(ref ty, ref expr) => {
             visitor.visit_ty(ty);
+            visitor.visit_body_id(BodyId { node_id: expr.id });
             visitor.visit_expr(expr);
         }
         ImplItemKind::Method(ref sig, ref body) => {
+            visitor.visit_body_id(BodyId { node_id: body.id });
             visitor.visit_fn(FnKind::Method(impl_item.name,
                                             sig,
                                             Some(&impl_item.vis),
@@ -819,6 +841,9 @@ pub fn walk_expr<'v, V: Visitor<'v>>(visitor: &mut V, expression: &'v Expr) {
         ExprRet(ref optional_expression) => {
             walk_list!(visitor, visit_expr, optional_expression);
         }
+        ExprBecome(ref subexpression) => {
+            visitor.visit_expr(subexpression)
+        }
         ExprInlineAsm(_, ref outputs, ref inputs) => {
             for output in outputs {
                 visitor.visit_expr(output)
@@ -67,7 +67,7 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::iter;
 use syntax::ast::*;
-use syntax::attr::{ThinAttributes, ThinAttributesExt};
+use syntax::attr::{self, ThinAttributes, ThinAttributesExt};
 use syntax::errors::Handler;
 use syntax::ext::mtwt;
 use syntax::ptr::P;
@@ -485,6 +485,7 @@ pub fn lower_ty_param(lctx: &LoweringContext, tp: &TyParam) -> hir::TyParam {
         bounds: lower_bounds(lctx, &tp.bounds),
         default: tp.default.as_ref().map(|x| lower_ty(lctx, x)),
         span: tp.span,
+        pure_wrt_drop: attr::contains_name(tp.attrs.as_attr_slice(), "may_dangle"),
     }
 }
 
@@ -506,6 +507,7 @@ pub fn lower_lifetime_def(lctx: &LoweringContext, l: &LifetimeDef) -> hir::Lifet
     hir::LifetimeDef {
         lifetime: lower_lifetime(lctx, &l.lifetime),
         bounds: lower_lifetimes(lctx, &l.bounds),
+        pure_wrt_drop: attr::contains_name(l.attrs.as_attr_slice(), "may_dangle"),
     }
 }
 
@@ -728,6 +730,9 @@ pub fn lower_item_kind(lctx: &LoweringContext, i: &ItemKind) -> hir::Item_ {
                            bounds,
                            items)
         }
+        ItemKind::TraitAlias(ref generics, ref bounds) => {
+            hir::ItemTraitAlias(lower_generics(lctx, generics), lower_bounds(lctx, bounds))
+        }
         ItemKind::Mac(_) => panic!("Shouldn't still be around"),
     }
 }
@@ -1315,6 +1320,7 @@ pub fn lower_expr(lctx: &LoweringContext, e: &Expr) -> P<hir::Expr> {
                 respan(sp_ident.span, lower_ident(lctx, sp_ident.node))
             })),
             ExprKind::Ret(ref e) => hir::ExprRet(e.as_ref().map(|x| lower_expr(lctx, x))),
+            ExprKind::Become(ref e) => hir::ExprBecome(lower_expr(lctx, e)),
             ExprKind::InlineAsm(InlineAsm {
                     ref inputs,
                     ref outputs,
@@ -1754,6 +1760,7 @@ pub fn lower_trait_bound_modifier(_lctx: &LoweringContext,
     match f {
         TraitBoundModifier::None => hir::TraitBoundModifier::None,
         TraitBoundModifier::Maybe => hir::TraitBoundModifier::Maybe,
+        TraitBoundModifier::Negative => hir::TraitBoundModifier::Negative,
     }
 }
 
@@ -15,6 +15,7 @@ use hir::*;
 use hir::intravisit::Visitor;
 use hir::def_id::{CRATE_DEF_INDEX, DefId, DefIndex};
 use middle::cstore::InlinedItem;
+use util::nodemap::FnvHashMap;
 use std::iter::repeat;
 use syntax::ast::{NodeId, CRATE_NODE_ID, DUMMY_NODE_ID};
 use syntax::codemap::Span;
@@ -24,6 +25,7 @@ use syntax::codemap::Span;
 pub struct NodeCollector<'ast> {
     pub krate: &'ast Crate,
     pub map: Vec<MapEntry<'ast>>,
+    pub bodies: FnvHashMap<BodyId, BodyEntry<'ast>>,
     pub definitions: Definitions,
     pub parent_node: NodeId,
 }
@@ -33,6 +35,7 @@ impl<'ast> NodeCollector<'ast> {
         let mut collector = NodeCollector {
             krate: krate,
             map: vec![],
+            bodies: FnvHashMap(),
             definitions: Definitions::new(),
             parent_node: CRATE_NODE_ID,
         };
@@ -52,11 +55,13 @@ impl<'ast> NodeCollector<'ast> {
                   parent_def_path: DefPath,
                   parent_def_id: DefId,
                   map: Vec<MapEntry<'ast>>,
+                  bodies: FnvHashMap<BodyId, BodyEntry<'ast>>,
                   definitions: Definitions)
                   -> NodeCollector<'ast> {
         let mut collector = NodeCollector {
             krate: krate,
             map: map,
+            bodies: bodies,
             parent_node: parent_node,
             definitions: definitions,
         };
@@ -116,6 +121,13 @@ impl<'ast> NodeCollector<'ast> {
         let entry = MapEntry::from_node(self.parent_node, node);
         self.insert_entry(id, entry);
     }
+
+    /// Records the body of `owner`, if it has one, in the bodies table.
+    fn insert_body(&mut self, owner: NodeId) {
+        if let Some(body) = self.map[owner as usize].body() {
+            self.bodies.insert(body.id(), BodyEntry { owner: owner, body: body });
+        }
+    }
 }
 
 impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
@@ -135,7 +147,7 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
         let def_data = match i.node {
             ItemDefaultImpl(..) | ItemImpl(..) =>
                 DefPathData::Impl,
-            ItemEnum(..) | ItemStruct(..) | ItemTrait(..) |
+            ItemEnum(..) | ItemStruct(..) | ItemTrait(..) | ItemTraitAlias(..) |
             ItemExternCrate(..) | ItemForeignMod(..) | ItemTy(..) =>
                 DefPathData::TypeNs(i.name),
             ItemMod(..) =>
@@ -147,6 +159,7 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
         };
 
         self.insert_def(i.id, NodeItem(i), def_data);
+        self.insert_body(i.id);
 
         let parent_node = self.parent_node;
         self.parent_node = i.id;
@@ -182,10 +195,14 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
                     self.create_def(field.id, DefPathData::Field(field.name));
                 }
             }
-            ItemTrait(_, _, ref bounds, _) => {
+            ItemTrait(_, _, ref bounds, _) | ItemTraitAlias(_, ref bounds) => {
                 for b in bounds.iter() {
-                    if let TraitTyParamBound(ref t, TraitBoundModifier::None) = *b {
-                        self.insert(t.trait_ref.ref_id, NodeItem(i));
+                    match *b {
+                        TraitTyParamBound(ref t, TraitBoundModifier::None) |
+                        TraitTyParamBound(ref t, TraitBoundModifier::Negative) => {
+                            self.insert(t.trait_ref.ref_id, NodeItem(i));
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -233,6 +250,7 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
         };
 
         self.insert(ti.id, NodeTraitItem(ti));
+        self.insert_body(ti.id);
         self.create_def(ti.id, def_data);
 
         let parent_node = self.parent_node;
@@ -257,6 +275,7 @@ impl<'ast> Visitor<'ast> for NodeCollector<'ast> {
         };
 
         self.insert_def(ii.id, NodeImplItem(ii), def_data);
+        self.insert_body(ii.id);
 
         let parent_node = self.parent_node;
         self.parent_node = ii.id;
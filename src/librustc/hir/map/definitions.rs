@@ -11,6 +11,7 @@
 use middle::cstore::LOCAL_CRATE;
 use hir::def_id::{DefId, DefIndex};
 use rustc_data_structures::fnv::FnvHashMap;
+use std::hash::{Hash, Hasher, SipHasher};
 use syntax::ast;
 use syntax::parse::token::InternedString;
 use util::nodemap::NodeMap;
@@ -20,6 +21,7 @@ pub struct Definitions {
     data: Vec<DefData>,
     key_map: FnvHashMap<DefKey, DefIndex>,
     node_map: NodeMap<DefIndex>,
+    table: DefPathTable,
 }
 
 /// A unique identifier that we can use to lookup a definition
@@ -105,6 +107,65 @@ impl DefPath {
         data.reverse();
         DefPath { data: data, krate: krate }
     }
+
+    /// A hash of this path that is the same in every compilation
+    /// session, unlike the `ast::Name`s in it. The crate is not part
+    /// of the hash; see `DefPathTable`.
+    pub fn deterministic_hash(&self) -> u64 {
+        self.data.iter().fold(ROOT_PATH_HASH, |hash, data| data.deterministic_hash(hash))
+    }
+}
+
+/// The `DefPath::deterministic_hash` of the crate root.
+const ROOT_PATH_HASH: u64 = 0;
+
+/// Hands out the `DefIndex` of each definition of a crate, and maps the
+/// `DefPath::deterministic_hash` of each definition back to its index.
+/// Indices are handed out in the order in which definitions are
+/// created, so adding an item renumbers all the items after it; the
+/// hash of a path only changes when the item itself moves. The hashes
+/// are written into the crate metadata, and are how a `DefPath` of
+/// another crate (perhaps recorded in an earlier compilation session)
+/// is resolved to a `DefId`. The table read back from the metadata
+/// only knows the items that were encoded.
+#[derive(Clone, Debug)]
+pub struct DefPathTable {
+    hashes: Vec<u64>,
+    indices: FnvHashMap<u64, DefIndex>,
+}
+
+impl DefPathTable {
+    pub fn new() -> DefPathTable {
+        DefPathTable { hashes: vec![], indices: FnvHashMap() }
+    }
+
+    /// Allocates the index of the next definition, whose path has the
+    /// given hash. Should two paths share a hash, the first definition
+    /// keeps it; inlined items come after all the crate's own items.
+    pub fn allocate(&mut self, hash: u64) -> DefIndex {
+        let index = DefIndex::new(self.hashes.len());
+        self.hashes.push(hash);
+        self.indices.entry(hash).or_insert(index);
+        index
+    }
+
+    /// Records the index of an item of another crate, as read from its
+    /// metadata.
+    pub fn insert(&mut self, hash: u64, index: DefIndex) {
+        self.indices.insert(hash, index);
+    }
+
+    /// The hash of the path of a definition allocated by this table.
+    pub fn def_path_hash(&self, index: DefIndex) -> u64 {
+        self.hashes[index.as_usize()]
+    }
+
+    /// The index of the item that `path` may name. As the table is
+    /// keyed by hash, the caller has to check that the path of the
+    /// returned index really is `path`.
+    pub fn lookup(&self, path: &DefPath) -> Option<DefIndex> {
+        self.indices.get(&path.deterministic_hash()).cloned()
+    }
 }
 
 /// Root of an inlined item. We track the `DefPath` of the item within
@@ -167,6 +228,7 @@ impl Definitions {
             data: vec![],
             key_map: FnvHashMap(),
             node_map: NodeMap(),
+            table: DefPathTable::new(),
         }
     }
 
@@ -178,6 +240,10 @@ impl Definitions {
         self.data[index.as_usize()].key.clone()
     }
 
+    pub fn def_path_table(&self) -> &DefPathTable {
+        &self.table
+    }
+
     /// Returns the path from the crate root to `index`. The root
     /// nodes are not included in the path (i.e., this will be an
     /// empty vector for the crate root). For an inlined item, this
@@ -211,28 +277,15 @@ impl Definitions {
         // the crate root
         assert!(path.is_local());
 
-        let root_key = DefKey {
-            parent: None,
-            disambiguated_data: DisambiguatedDefPathData {
-                data: DefPathData::CrateRoot,
-                disambiguator: 0,
-            },
-        };
-        let root_id = self.key_map[&root_key];
-
-        debug!("retrace_path: root_id={:?}", root_id);
-
-        let mut id = root_id;
-        for data in &path.data {
-            let key = DefKey { parent: Some(id), disambiguated_data: data.clone() };
-            debug!("key = {:?}", key);
-            id = match self.key_map.get(&key) {
-                Some(&id) => id,
-                None => return None
-            };
-        }
-
-        Some(id)
+        // The table is keyed by hash, so make sure that we found the
+        // item we were looking for.
+        self.table.lookup(path).and_then(|index| {
+            if self.def_path(index) == *path {
+                Some(index)
+            } else {
+                None
+            }
+        })
     }
 
     pub fn create_def_with_parent(&mut self,
@@ -271,7 +324,22 @@ impl Definitions {
         debug!("create_def_with_parent: after disambiguation, key = {:?}", key);
 
         // Create the definition.
-        let index = DefIndex::new(self.data.len());
+        let hash = match key.parent {
+            Some(parent) => {
+                key.disambiguated_data.deterministic_hash(self.table.def_path_hash(parent))
+            }
+            None => match key.disambiguated_data.data {
+                // An inlined item keeps the path it has in its crate.
+                DefPathData::InlinedRoot(ref p) => {
+                    p.data.iter().fold(ROOT_PATH_HASH, |hash, data| {
+                        data.deterministic_hash(hash)
+                    })
+                }
+                _ => ROOT_PATH_HASH,
+            },
+        };
+        let index = self.table.allocate(hash);
+        assert_eq!(index.as_usize(), self.data.len());
         self.data.push(DefData { key: key.clone(), node_id: node_id });
         debug!("create_def_with_parent: node_map[{:?}] = {:?}", node_id, index);
         self.node_map.insert(node_id, index);
@@ -283,6 +351,18 @@ impl Definitions {
     }
 }
 
+impl DisambiguatedDefPathData {
+    /// The `DefPath::deterministic_hash` of the path made of the path
+    /// with hash `parent` and this data.
+    fn deterministic_hash(&self, parent: u64) -> u64 {
+        let mut state = SipHasher::new();
+        parent.hash(&mut state);
+        self.data.deterministic_hash_to(&mut state);
+        self.disambiguator.hash(&mut state);
+        state.finish()
+    }
+}
+
 impl DefPathData {
     pub fn as_interned_str(&self) -> InternedString {
         use self::DefPathData::*;
@@ -334,5 +414,72 @@ impl DefPathData {
     pub fn to_string(&self) -> String {
         self.as_interned_str().to_string()
     }
+
+    /// Hashes the kind of this data and the string of its name, if
+    /// any; the `ast::Name` itself is only meaningful in the current
+    /// session.
+    fn deterministic_hash_to<H: Hasher>(&self, state: &mut H) {
+        use self::DefPathData::*;
+        let kind: u8 = match *self {
+            CrateRoot => 0,
+            InlinedRoot(_) => 1,
+            Misc => 2,
+            Impl => 3,
+            TypeNs(_) => 4,
+            ValueNs(_) => 5,
+            Module(_) => 6,
+            MacroDef(_) => 7,
+            ClosureExpr => 8,
+            TypeParam(_) => 9,
+            LifetimeDef(_) => 10,
+            EnumVariant(_) => 11,
+            Field(_) => 12,
+            StructCtor => 13,
+            Initializer => 14,
+            Binding(_) => 15,
+        };
+        kind.hash(state);
+        self.as_interned_str().hash(state);
+    }
 }
 
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hir::def_id::DefIndex;
+    use syntax::parse::token;
+
+    fn define(defs: &mut Definitions, parent: DefIndex, node_id: u32, name: &str) -> DefIndex {
+        let data = DefPathData::TypeNs(token::intern(name));
+        defs.create_def_with_parent(Some(parent), node_id, data)
+    }
+
+    #[test]
+    fn def_index_allocated_from_table() {
+        let mut old = Definitions::new();
+        let root = old.create_def_with_parent(None, 0, DefPathData::CrateRoot);
+        let old_a = define(&mut old, root, 1, "a");
+        let old_b = define(&mut old, root, 2, "b");
+
+        // Adding an item renumbers the items after it...
+        let mut new = Definitions::new();
+        let root = new.create_def_with_parent(None, 0, DefPathData::CrateRoot);
+        define(&mut new, root, 1, "z");
+        let new_a = define(&mut new, root, 2, "a");
+        let new_b = define(&mut new, root, 3, "b");
+        assert!(old_b != new_b);
+
+        // ...but not the hashes of their paths, through which the paths
+        // of the old session find the items of the new one.
+        for &(old_index, new_index) in &[(old_a, new_a), (old_b, new_b)] {
+            let old_path = old.def_path(old_index);
+            assert_eq!(old_path.deterministic_hash(),
+                       old.def_path_table().def_path_hash(old_index));
+            assert_eq!(old.def_path_table().def_path_hash(old_index),
+                       new.def_path_table().def_path_hash(new_index));
+            assert_eq!(new.def_path_table().lookup(&old_path), Some(new_index));
+            assert_eq!(new.retrace_path(&old_path), Some(new_index));
+        }
+    }
+}
@@ -11,7 +11,7 @@
 pub use self::Node::*;
 use self::MapEntry::*;
 use self::collector::NodeCollector;
-pub use self::definitions::{Definitions, DefKey, DefPath, DefPathData,
+pub use self::definitions::{Definitions, DefKey, DefPath, DefPathData, DefPathTable,
                             DisambiguatedDefPathData, InlinedRootPath};
 
 use dep_graph::{DepGraph, DepNode};
@@ -30,6 +30,7 @@ use hir::fold::Folder;
 use hir::print as pprust;
 
 use arena::TypedArena;
+use util::nodemap::FnvHashMap;
 use std::cell::RefCell;
 use std::io;
 use std::mem;
@@ -58,6 +59,31 @@ pub enum Node<'ast> {
     NodeTyParam(&'ast TyParam)
 }
 
+/// The body of a fn or method, or the initializer of a const, static
+/// or associated const; see `hir::BodyId`.
+#[derive(Copy, Clone, Debug)]
+pub enum Body<'ast> {
+    Fn(&'ast Block),
+    Const(&'ast Expr),
+}
+
+impl<'ast> Body<'ast> {
+    pub fn id(self) -> BodyId {
+        match self {
+            Body::Fn(block) => BodyId { node_id: block.id },
+            Body::Const(expr) => BodyId { node_id: expr.id },
+        }
+    }
+}
+
+/// An entry in the bodies table of the map: a body, together with the
+/// item, trait item or impl item that owns it.
+#[derive(Copy, Clone, Debug)]
+pub struct BodyEntry<'ast> {
+    owner: NodeId,
+    body: Body<'ast>,
+}
+
 /// Represents an entry and its parent NodeID.
 /// The odd layout is to bring down the total size.
 #[derive(Copy, Debug)]
@@ -147,6 +173,36 @@ impl<'ast> MapEntry<'ast> {
             _ => return None
         })
     }
+
+    /// The body owned by this entry, if it is a fn, method, const,
+    /// static or associated const that has one.
+    fn body(self) -> Option<Body<'ast>> {
+        match self {
+            EntryItem(_, item) => {
+                match item.node {
+                    ItemFn(_, _, _, _, _, ref body) => Some(Body::Fn(body)),
+                    ItemConst(_, ref expr) |
+                    ItemStatic(_, _, ref expr) => Some(Body::Const(expr)),
+                    _ => None
+                }
+            }
+            EntryTraitItem(_, item) => {
+                match item.node {
+                    MethodTraitItem(_, Some(ref body)) => Some(Body::Fn(body)),
+                    ConstTraitItem(_, Some(ref expr)) => Some(Body::Const(expr)),
+                    _ => None
+                }
+            }
+            EntryImplItem(_, item) => {
+                match item.node {
+                    ImplItemKind::Method(_, ref body) => Some(Body::Fn(body)),
+                    ImplItemKind::Const(_, ref expr) => Some(Body::Const(expr)),
+                    ImplItemKind::Type(_) => None
+                }
+            }
+            _ => None
+        }
+    }
 }
 
 /// Stores a crate and any number of inlined items from other crates.
@@ -193,6 +249,12 @@ pub struct Map<'ast> {
     /// plain old integers.
     map: RefCell<Vec<MapEntry<'ast>>>,
 
+    /// The bodies of the fns, methods, consts and statics in the map,
+    /// together with their owners. Bodies are tracked as the `HirBody`
+    /// of their owner, separately from the rest of the owner, so they
+    /// are only to be reached through `body`.
+    bodies: RefCell<FnvHashMap<BodyId, BodyEntry<'ast>>>,
+
     definitions: RefCell<Definitions>,
 }
 
@@ -204,15 +266,29 @@ impl<'ast> Map<'ast> {
     /// otherwise have had access to those contents, and hence needs a
     /// read recorded). If the function just returns a DefId or
     /// NodeId, no actual content was returned, so no read is needed.
+    ///
+    /// Revealing an item, trait item or impl item only records a read
+    /// of its `Hir`, even though its body is physically reachable from
+    /// it; code that looks at the body must get it from `body` (or call
+    /// `read_body`), which records a read of its `HirBody`.
     fn read(&self, id: NodeId) {
         self.dep_graph.read(self.dep_node(id));
     }
 
+    /// Nodes within a body are tracked as `HirBody` of the enclosing
+    /// item, and all other nodes as `Hir` of the enclosing item.
     fn dep_node(&self, id0: NodeId) -> DepNode<DefId> {
         let map = self.map.borrow();
         let mut id = id0;
+        let mut child = None;
+        let mut in_body = false;
         loop {
-            match map[id as usize] {
+            let entry = map[id as usize];
+            if child.is_some() && entry.body().map(|body| body.id().node_id) == child {
+                in_body = true;
+            }
+
+            match entry {
                 EntryItem(_, item) => {
                     let def_id = self.local_def_id(item.id);
                     // NB                          ^~~~~~~
@@ -224,7 +300,11 @@ impl<'ast> Map<'ast> {
                     // enclosing view path item. This seems mega super
                     // ultra wrong, but then who am I to judge?
                     // -nmatsakis
-                    return DepNode::Hir(def_id);
+                    return if in_body {
+                        DepNode::HirBody(def_id)
+                    } else {
+                        DepNode::Hir(def_id)
+                    };
                 }
 
                 EntryForeignItem(p, _) |
@@ -238,8 +318,10 @@ impl<'ast> Map<'ast> {
                 EntryBlock(p, _) |
                 EntryStructCtor(p, _) |
                 EntryLifetime(p, _) |
-                EntryTyParam(p, _) =>
-                    id = p,
+                EntryTyParam(p, _) => {
+                    child = Some(id);
+                    id = p;
+                }
 
                 RootCrate |
                 RootInlinedParent(_) =>
@@ -282,6 +364,12 @@ impl<'ast> Map<'ast> {
         self.definitions.borrow().def_path(def_id.index)
     }
 
+    /// The `DefPath::deterministic_hash` of the path of `def_id`.
+    pub fn def_path_hash(&self, def_id: DefId) -> u64 {
+        assert!(def_id.is_local());
+        self.definitions.borrow().def_path_table().def_path_hash(def_id.index)
+    }
+
     pub fn retrace_path(&self, path: &DefPath) -> Option<DefId> {
         self.definitions.borrow().retrace_path(path)
                                  .map(DefId::local)
@@ -358,6 +446,46 @@ impl<'ast> Map<'ast> {
         self.find_entry(id).and_then(|x| x.parent_node()).unwrap_or(id)
     }
 
+    /// Returns the body of `owner` if it is a fn, method, const,
+    /// static or associated const that has one. Closures are not body
+    /// owners; their bodies are part of the enclosing body.
+    pub fn maybe_body_owned_by(&self, owner: NodeId) -> Option<BodyId> {
+        self.find_entry(owner)
+            .and_then(|entry| entry.body())
+            .map(|body| body.id())
+    }
+
+    fn body_entry(&self, id: BodyId) -> BodyEntry<'ast> {
+        match self.bodies.borrow().get(&id) {
+            Some(&entry) => entry,
+            None => bug!("body_entry: {} is not a body", self.node_to_string(id.node_id)),
+        }
+    }
+
+    /// Returns the body `id`, recording a read of the `HirBody` of its
+    /// owner.
+    pub fn body(&self, id: BodyId) -> Body<'ast> {
+        self.read_body(id);
+        self.body_entry(id).body
+    }
+
+    /// Records a read of the `HirBody` of the owner of `id`, for code
+    /// that walks a body it reached through its owner; see
+    /// `intravisit::Visitor::visit_body_id`.
+    pub fn read_body(&self, id: BodyId) {
+        self.read(id.node_id);
+    }
+
+    /// Returns the fn, method, const, static or associated const that
+    /// owns the body `id`.
+    pub fn body_owner(&self, id: BodyId) -> NodeId {
+        self.body_entry(id).owner
+    }
+
+    pub fn body_owner_def_id(&self, id: BodyId) -> DefId {
+        self.local_def_id(self.body_owner(id))
+    }
+
     /// Check if the node is an argument. An argument is a local variable whose
     /// immediate parent is an item or a closure.
     pub fn is_argument(&self, id: NodeId) -> bool {
@@ -620,6 +748,7 @@ impl<'ast> Map<'ast> {
             Some(NodeBlock(block)) => block.span,
             Some(NodeStructCtor(_)) => self.expect_item(self.get_parent(id)).span,
             Some(NodeTyParam(ty_param)) => ty_param.span,
+            Some(NodeLifetime(lifetime)) => lifetime.span,
             _ => return None,
         };
         Some(sp)
@@ -781,10 +910,10 @@ impl<F: FoldOps> Folder for IdAndSpanUpdater<F> {
 }
 
 pub fn map_crate<'ast>(forest: &'ast mut Forest) -> Map<'ast> {
-    let (map, definitions) = {
+    let (map, bodies, definitions) = {
         let mut collector = NodeCollector::root(&forest.krate);
         intravisit::walk_crate(&mut collector, &forest.krate);
-        (collector.map, collector.definitions)
+        (collector.map, collector.bodies, collector.definitions)
     };
 
     if log_enabled!(::log::DEBUG) {
@@ -807,6 +936,7 @@ pub fn map_crate<'ast>(forest: &'ast mut Forest) -> Map<'ast> {
         forest: forest,
         dep_graph: forest.dep_graph.clone(),
         map: RefCell::new(map),
+        bodies: RefCell::new(bodies),
         definitions: RefCell::new(definitions),
     }
 }
@@ -844,10 +974,12 @@ pub fn map_decoded_item<'ast, F: FoldOps>(map: &Map<'ast>,
             parent_def_path,
             parent_def_id,
             mem::replace(&mut *map.map.borrow_mut(), vec![]),
+            mem::replace(&mut *map.bodies.borrow_mut(), FnvHashMap()),
             mem::replace(&mut *map.definitions.borrow_mut(), Definitions::new()));
     ii.visit(&mut collector);
 
     *map.map.borrow_mut() = collector.map;
+    *map.bodies.borrow_mut() = collector.bodies;
     *map.definitions.borrow_mut() = collector.definitions;
 
     ii
@@ -914,6 +1046,7 @@ fn node_id_to_string(map: &Map, id: NodeId, include_id: bool) -> String {
                 ItemEnum(..) => "enum",
                 ItemStruct(..) => "struct",
                 ItemTrait(..) => "trait",
+                ItemTraitAlias(..) => "trait alias",
                 ItemImpl(..) => "impl",
                 ItemDefaultImpl(..) => "default impl",
             };
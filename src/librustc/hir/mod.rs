@@ -155,6 +155,9 @@ impl fmt::Debug for Lifetime {
 pub struct LifetimeDef {
     pub lifetime: Lifetime,
     pub bounds: HirVec<Lifetime>,
+    /// Whether the parameter was marked `#[may_dangle]`, i.e. the destructor
+    /// of the enclosing `Drop` impl promises not to access data it outlives.
+    pub pure_wrt_drop: bool,
 }
 
 /// A "Path" is essentially Rust's notion of a name; for instance:
@@ -341,12 +344,13 @@ pub enum TyParamBound {
     RegionTyParamBound(Lifetime),
 }
 
-/// A modifier on a bound, currently this is only used for `?Sized`, where the
-/// modifier is `Maybe`. Negative bounds should also be handled here.
+/// A modifier on a bound: `Maybe` for `?Sized`, and `Negative` for
+/// `!Trait`, which requires that the trait is not implemented.
 #[derive(Copy, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub enum TraitBoundModifier {
     None,
     Maybe,
+    Negative,
 }
 
 pub type TyParamBounds = HirVec<TyParamBound>;
@@ -358,6 +362,8 @@ pub struct TyParam {
     pub bounds: TyParamBounds,
     pub default: Option<P<Ty>>,
     pub span: Span,
+    /// Whether the parameter was marked `#[may_dangle]`; see `LifetimeDef`.
+    pub pure_wrt_drop: bool,
 }
 
 /// Represents lifetimes and type parameters attached to a declaration
@@ -389,6 +395,13 @@ impl Generics {
         !self.ty_params.is_empty()
     }
 
+    /// Whether any parameter carries `#[may_dangle]`, which makes the
+    /// enclosing impl require an `unsafe impl` declaration.
+    pub fn carries_may_dangle(&self) -> bool {
+        self.lifetimes.iter().any(|l| l.pure_wrt_drop) ||
+            self.ty_params.iter().any(|t| t.pure_wrt_drop)
+    }
+
     pub fn is_parameterized(&self) -> bool {
         self.is_lt_parameterized() || self.is_type_parameterized()
     }
@@ -984,6 +997,8 @@ pub enum Expr_ {
     ExprAgain(Option<Spanned<Ident>>),
     /// A `return`, with an optional value to be returned
     ExprRet(Option<P<Expr>>),
+    /// A `become`, i.e. a guaranteed tail call
+    ExprBecome(P<Expr>),
 
     /// Inline assembly (from `asm!`), with its outputs and inputs.
     ExprInlineAsm(InlineAsm, Vec<P<Expr>>, Vec<P<Expr>>),
@@ -1516,6 +1531,19 @@ pub struct ItemId {
     pub id: NodeId,
 }
 
+// Identifies the body of a fn, method, const, static or associated
+// const: the root block or expression that gets type-checked and
+// translated on its own, as opposed to the signature of its owner.
+// The HIR map keeps the bodies in a table of their own, indexed by
+// `BodyId`: `Map::body` returns a body and records a read of the
+// owner's `HirBody`, `Map::maybe_body_owned_by` finds the body of a
+// given owner and `Map::body_owner` the owner of a body. Closure
+// bodies are part of the body that contains the closure.
+#[derive(Copy, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
+pub struct BodyId {
+    pub node_id: NodeId,
+}
+
 //  FIXME (#3300): Should allow items to be anonymous. Right now
 //  we just use dummy names for anon items.
 /// An item
@@ -1558,6 +1586,8 @@ pub enum Item_ {
     ItemStruct(VariantData, Generics),
     /// Represents a Trait Declaration
     ItemTrait(Unsafety, Generics, TyParamBounds, HirVec<TraitItem>),
+    /// A trait alias, e.g. `trait Foo = Bar + Quux;`
+    ItemTraitAlias(Generics, TyParamBounds),
 
     // Default trait implementations
     ///
@@ -1586,6 +1616,7 @@ impl Item_ {
             ItemEnum(..) => "enum",
             ItemStruct(..) => "struct",
             ItemTrait(..) => "trait",
+            ItemTraitAlias(..) => "trait alias",
             ItemImpl(..) |
             ItemDefaultImpl(..) => "item",
         }
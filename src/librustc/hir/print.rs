@@ -833,6 +833,19 @@ impl<'a> State<'a> {
                 }
                 self.bclose(item.span)?;
             }
+            hir::ItemTraitAlias(ref generics, ref bounds) => {
+                self.ibox(indent_unit)?;
+                self.ibox(0)?;
+                self.word_nbsp(&visibility_qualified(&item.vis, "trait"))?;
+                self.print_name(item.name)?;
+                self.print_generics(generics)?;
+                self.end()?; // end the inner ibox
+
+                self.print_bounds(" =", &bounds[..])?;
+                self.print_where_clause(&generics.where_clause)?;
+                word(&mut self.s, ";")?;
+                self.end()?; // end the outer ibox
+            }
         }
         self.ann.post(self, NodeItem(item))
     }
@@ -1486,6 +1499,11 @@ impl<'a> State<'a> {
                     _ => (),
                 }
             }
+            hir::ExprBecome(ref expr) => {
+                word(&mut self.s, "become")?;
+                word(&mut self.s, " ")?;
+                self.print_expr(&expr)?;
+            }
             hir::ExprInlineAsm(ref a, ref outputs, ref inputs) => {
                 word(&mut self.s, "asm!")?;
                 self.popen()?;
@@ -2030,6 +2048,10 @@ impl<'a> State<'a> {
                         word(&mut self.s, "?")?;
                         self.print_poly_trait_ref(tref)
                     }
+                    TraitTyParamBound(ref tref, TraitBoundModifier::Negative) => {
+                        word(&mut self.s, "!")?;
+                        self.print_poly_trait_ref(tref)
+                    }
                     RegionTyParamBound(ref lt) => {
                         self.print_lifetime(lt)
                     }
@@ -2046,6 +2068,9 @@ impl<'a> State<'a> {
     }
 
     pub fn print_lifetime_def(&mut self, lifetime: &hir::LifetimeDef) -> io::Result<()> {
+        if lifetime.pure_wrt_drop {
+            self.word_nbsp("#[may_dangle]")?;
+        }
         self.print_lifetime(&lifetime.lifetime)?;
         let mut sep = ":";
         for v in &lifetime.bounds {
@@ -2085,6 +2110,9 @@ impl<'a> State<'a> {
     }
 
     pub fn print_ty_param(&mut self, param: &hir::TyParam) -> io::Result<()> {
+        if param.pure_wrt_drop {
+            self.word_nbsp("#[may_dangle]")?;
+        }
         self.print_name(param.name)?;
         self.print_bounds(":", &param.bounds)?;
         match param.default {
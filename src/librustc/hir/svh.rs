@@ -35,9 +35,11 @@
 //! hash of all contents of a crate which can somehow be exposed to downstream
 //! crates.
 //!
-//! This hash is currently calculated by just hashing the AST, but this is
-//! obviously wrong (doc changes should not result in an incompatible ABI).
-//! Implementation-wise, this is required at this moment in time.
+//! This hash is currently calculated by hashing the HIR of every item,
+//! leaving out the bodies that downstream crates cannot see: a change to
+//! the body of a non-generic, non-`#[inline]` function does not change the
+//! SVH. This is still too strict (doc changes should not result in an
+//! incompatible ABI), but it is what is implemented at this moment in time.
 //!
 //! By encoding this strict version hash into all crate's metadata, stale crates
 //! can be detected immediately and error'd about by rustc itself.
@@ -0,0 +1,430 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Canonical queries: answering a trait-system question in an
+//! inference context of its own, so that the answer can be cached and
+//! shared between every inference context that asks it.
+//!
+//! A goal such as `<Vec<?T> as IntoIterator>::Item` mentions
+//! inference variables of the context that asks it, so it cannot be
+//! used as a cache key directly. *Canonicalizing* the goal replaces
+//! each unresolved inference variable (and region variable) with a
+//! canonical variable, numbered in order of first appearance, giving
+//! `<Vec<^0> as IntoIterator>::Item`. Two goals that differ only in
+//! which variables they mention have the same canonical form.
+//!
+//! Canonical variables are represented as if the canonical value were
+//! wrapped in one extra `Binder`: types as `TyBound` and regions as
+//! `ReLateBound` with an anonymous bound region, both bound at that
+//! outermost level. `instantiate_canonical` replaces them with fresh
+//! inference variables, so the query can be answered in a fresh
+//! inference context. The answer -- the values the query variables
+//! were found to have, the region constraints that were recorded, and
+//! the result proper -- is canonicalized again, cached, and finally
+//! applied to the caller's context by `instantiate_query_response`.
+//!
+//! Goals mentioning skolemized types or regions cannot be
+//! canonicalized, since they only make sense inside the snapshot that
+//! created them; `canonicalize` returns `None` for those and the
+//! caller answers the goal directly.
+
+use infer::{InferCtxt, InferOk, MiscVariable, RelateRegionParamBound, TypeOrigin};
+use infer::type_variable::TypeVariableOrigin;
+use infer::unify_key::ToType;
+use traits::ProjectionMode;
+use ty::{self, Ty, TyCtxt};
+use ty::fold::{TypeFoldable, TypeFolder, TypeVisitor};
+use util::nodemap::FnvHashMap;
+
+use std::cell::{Cell, RefCell};
+use std::hash::Hash;
+use syntax::codemap::Span;
+use syntax::parse::token::special_idents;
+
+/// A value whose inference variables have been replaced by canonical
+/// variables; see the module comment.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Canonical<V> {
+    /// The kind of each canonical variable, by index.
+    pub variables: Vec<CanonicalVarKind>,
+    pub value: V,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CanonicalVarKind {
+    Ty,
+    Int,
+    Float,
+    Region,
+}
+
+/// What a canonical variable stands for in some inference context.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CanonicalVarValue<'tcx> {
+    Ty(Ty<'tcx>),
+    Region(ty::Region),
+}
+
+/// The answer to a canonical query, as computed in the inference
+/// context that answered it.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct QueryResponse<'tcx, R> {
+    /// The value each variable of the query was found to be equal to.
+    pub var_values: Vec<CanonicalVarValue<'tcx>>,
+
+    /// `(sub, sup)` pairs of regions that must be related for the
+    /// answer to hold.
+    pub region_constraints: Vec<(ty::Region, ty::Region)>,
+
+    pub value: R,
+}
+
+/// The key of a canonical query: the canonical goal, together with
+/// the parts of the inference context that the answer depends on.
+pub type QueryKey<'tcx, G> = (ProjectionMode, Vec<ty::Predicate<'tcx>>, Canonical<G>);
+
+/// Answers to canonical queries, shared by all inference contexts.
+pub struct QueryCache<'tcx, G, A> {
+    hashmap: RefCell<FnvHashMap<QueryKey<'tcx, G>, A>>,
+
+    /// How many lookups found an answer; see `TyCtxt::print_debug_stats`.
+    hits: Cell<usize>,
+}
+
+impl<'tcx, G: Eq + Hash, A: Clone> QueryCache<'tcx, G, A> {
+    pub fn new() -> QueryCache<'tcx, G, A> {
+        QueryCache {
+            hashmap: RefCell::new(FnvHashMap()),
+            hits: Cell::new(0),
+        }
+    }
+
+    pub fn get(&self, key: &QueryKey<'tcx, G>) -> Option<A> {
+        let answer = self.hashmap.borrow().get(key).cloned();
+        if answer.is_some() {
+            self.hits.set(self.hits.get() + 1);
+        }
+        answer
+    }
+
+    pub fn insert(&self, key: QueryKey<'tcx, G>, answer: A) {
+        self.hashmap.borrow_mut().insert(key, answer);
+    }
+
+    /// The number of answers in the cache.
+    pub fn len(&self) -> usize {
+        self.hashmap.borrow().len()
+    }
+
+    /// The number of lookups that were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+}
+
+impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
+    /// Canonicalizes `value`, returning the canonical value together
+    /// with what each of its canonical variables stands for in this
+    /// context. Returns `None` if `value` cannot be canonicalized.
+    pub fn canonicalize<V>(&self, value: &V)
+                           -> Option<(Canonical<V>, Vec<CanonicalVarValue<'tcx>>)>
+        where V: TypeFoldable<'tcx>
+    {
+        if value.has_escaping_regions() {
+            return None;
+        }
+
+        let mut canonicalizer = Canonicalizer {
+            infcx: self,
+            variables: vec![],
+            var_values: vec![],
+            indices: FnvHashMap(),
+            binder_depth: 1,
+            skolemized: false,
+        };
+        let value = value.fold_with(&mut canonicalizer);
+        debug!("canonicalize: {:?} with {:?}", value, canonicalizer.var_values);
+        if canonicalizer.skolemized {
+            return None;
+        }
+
+        let canonical = Canonical {
+            variables: canonicalizer.variables,
+            value: value,
+        };
+        Some((canonical, canonicalizer.var_values))
+    }
+
+    /// Replaces each canonical variable in `canonical` with a fresh
+    /// inference variable of this context, returning the value and
+    /// the fresh variables.
+    pub fn instantiate_canonical<V>(&self, span: Span, canonical: &Canonical<V>)
+                                    -> (V, Vec<CanonicalVarValue<'tcx>>)
+        where V: TypeFoldable<'tcx>
+    {
+        let var_values: Vec<_> = canonical.variables.iter().map(|kind| {
+            match *kind {
+                CanonicalVarKind::Ty => {
+                    CanonicalVarValue::Ty(self.next_ty_var(TypeVariableOrigin::MiscVariable(span)))
+                }
+                CanonicalVarKind::Int => {
+                    CanonicalVarValue::Ty(self.tcx.mk_int_var(self.next_int_var_id()))
+                }
+                CanonicalVarKind::Float => {
+                    CanonicalVarValue::Ty(self.tcx.mk_float_var(self.next_float_var_id()))
+                }
+                CanonicalVarKind::Region => {
+                    CanonicalVarValue::Region(self.next_region_var(MiscVariable(span)))
+                }
+            }
+        }).collect();
+
+        let (value, _, _) = self.tcx.replace_late_bound_regions_and_types(
+            &ty::Binder(canonical.value.clone()),
+            |br| {
+                let value = match br {
+                    ty::BrAnon(index) => var_values.get(index as usize),
+                    _ => None
+                };
+                match value {
+                    Some(&CanonicalVarValue::Region(r)) => r,
+                    _ => bug!("instantiate_canonical: bad canonical region {:?}", br)
+                }
+            },
+            |bt| {
+                match var_values.get(bt.index as usize) {
+                    Some(&CanonicalVarValue::Ty(t)) => t,
+                    _ => bug!("instantiate_canonical: bad canonical type {:?}", bt)
+                }
+            });
+        (value, var_values)
+    }
+
+    /// Builds the canonical response to a query that was answered in
+    /// this context, where `query_values` are the variables that
+    /// `instantiate_canonical` created for the query. Everything the
+    /// answer depends on must have been recorded in this context by
+    /// now: no further region constraints are collected.
+    pub fn make_query_response<R>(&self, query_values: &[CanonicalVarValue<'tcx>], value: &R)
+                                  -> Option<Canonical<QueryResponse<'tcx, R>>>
+        where R: TypeFoldable<'tcx>
+    {
+        let response = QueryResponse {
+            var_values: query_values.to_vec(),
+            region_constraints: self.region_vars.region_constraints(),
+            value: value.clone(),
+        };
+        self.canonicalize(&response).map(|(canonical, _)| canonical)
+    }
+
+    /// Applies `response`, the answer to a query whose variables stood
+    /// for `caller_values` in this context: each query variable is
+    /// equated with the value the query found for it, and the region
+    /// constraints of the answer are added. Returns the result proper,
+    /// with any obligations that arose from equating the types.
+    pub fn instantiate_query_response<R>(&self,
+                                         span: Span,
+                                         caller_values: &[CanonicalVarValue<'tcx>],
+                                         response: &Canonical<QueryResponse<'tcx, R>>)
+                                         -> InferOk<'tcx, R>
+        where R: TypeFoldable<'tcx>
+    {
+        let (response, _) = self.instantiate_canonical(span, response);
+        assert_eq!(caller_values.len(), response.var_values.len());
+
+        let mut obligations = vec![];
+        for (&caller_value, &query_value) in caller_values.iter().zip(&response.var_values) {
+            match (caller_value, query_value) {
+                (CanonicalVarValue::Ty(a), CanonicalVarValue::Ty(b)) => {
+                    match self.eq_types(false, TypeOrigin::Misc(span), a, b) {
+                        Ok(InferOk { obligations: eq_obligations, .. }) => {
+                            obligations.extend(eq_obligations);
+                        }
+                        Err(err) => {
+                            span_bug!(span,
+                                      "instantiate_query_response: cannot equate {:?} and {:?}: {}",
+                                      a, b, err)
+                        }
+                    }
+                }
+                (CanonicalVarValue::Region(a), CanonicalVarValue::Region(b)) => {
+                    self.region_vars.make_eqregion(RelateRegionParamBound(span), a, b);
+                }
+                _ => {
+                    span_bug!(span,
+                              "instantiate_query_response: {:?} answered with {:?}",
+                              caller_value, query_value)
+                }
+            }
+        }
+
+        for &(sub, sup) in &response.region_constraints {
+            self.region_vars.make_subregion(RelateRegionParamBound(span), sub, sup);
+        }
+
+        InferOk { value: response.value, obligations: obligations }
+    }
+}
+
+struct Canonicalizer<'cx, 'tcx: 'cx> {
+    infcx: &'cx InferCtxt<'cx, 'tcx>,
+    variables: Vec<CanonicalVarKind>,
+    var_values: Vec<CanonicalVarValue<'tcx>>,
+
+    /// The canonical variable each value in `var_values` was given.
+    indices: FnvHashMap<CanonicalVarValue<'tcx>, u32>,
+
+    /// The depth of the binder that canonical variables are bound by,
+    /// relative to the point we are folding.
+    binder_depth: u32,
+
+    /// Whether a skolemized type or region was encountered.
+    skolemized: bool,
+}
+
+impl<'cx, 'tcx> Canonicalizer<'cx, 'tcx> {
+    fn canonical_var(&mut self, kind: CanonicalVarKind, value: CanonicalVarValue<'tcx>) -> u32 {
+        let variables = &mut self.variables;
+        let var_values = &mut self.var_values;
+        *self.indices.entry(value).or_insert_with(|| {
+            variables.push(kind);
+            var_values.push(value);
+            (variables.len() - 1) as u32
+        })
+    }
+
+    fn canonical_ty(&mut self, kind: CanonicalVarKind, ty: Ty<'tcx>) -> Ty<'tcx> {
+        let index = self.canonical_var(kind, CanonicalVarValue::Ty(ty));
+        let bound_ty = ty::BoundTy { index: index, name: special_idents::invalid.name };
+        self.infcx.tcx.mk_ty(ty::TyBound(ty::DebruijnIndex::new(self.binder_depth), bound_ty))
+    }
+}
+
+impl<'cx, 'tcx> TypeFolder<'tcx> for Canonicalizer<'cx, 'tcx> {
+    fn tcx<'b>(&'b self) -> &'b TyCtxt<'tcx> {
+        self.infcx.tcx
+    }
+
+    fn fold_binder<T>(&mut self, t: &ty::Binder<T>) -> ty::Binder<T>
+        where T: TypeFoldable<'tcx>
+    {
+        self.binder_depth += 1;
+        let t = t.super_fold_with(self);
+        self.binder_depth -= 1;
+        t
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        match r {
+            ty::ReVar(vid) => {
+                let r = self.infcx.region_vars.opportunistic_resolve_var(vid);
+                let index = self.canonical_var(CanonicalVarKind::Region,
+                                               CanonicalVarValue::Region(r));
+                ty::ReLateBound(ty::DebruijnIndex::new(self.binder_depth), ty::BrAnon(index))
+            }
+
+            ty::ReSkolemized(..) => {
+                self.skolemized = true;
+                r
+            }
+
+            ty::ReStatic |
+            ty::ReEarlyBound(..) |
+            ty::ReLateBound(..) |
+            ty::ReFree(_) |
+            ty::ReScope(_) |
+            ty::ReEmpty => r,
+        }
+    }
+
+    fn fold_ty(&mut self, t: Ty<'tcx>) -> Ty<'tcx> {
+        if !t.needs_infer() {
+            return t;
+        }
+
+        let tcx = self.infcx.tcx;
+        match t.sty {
+            ty::TyInfer(ty::TyVar(v)) => {
+                let opt_ty = self.infcx.type_variables.borrow_mut().probe(v);
+                match opt_ty {
+                    Some(ty) => ty.fold_with(self),
+                    None => {
+                        let root = self.infcx.type_variables.borrow_mut().root_var(v);
+                        self.canonical_ty(CanonicalVarKind::Ty, tcx.mk_var(root))
+                    }
+                }
+            }
+
+            ty::TyInfer(ty::IntVar(v)) => {
+                let opt_value = self.infcx.int_unification_table.borrow_mut().probe(v);
+                match opt_value {
+                    Some(value) => value.to_type(tcx),
+                    None => {
+                        let root = self.infcx.int_unification_table.borrow_mut().find(v);
+                        self.canonical_ty(CanonicalVarKind::Int, tcx.mk_int_var(root))
+                    }
+                }
+            }
+
+            ty::TyInfer(ty::FloatVar(v)) => {
+                let opt_value = self.infcx.float_unification_table.borrow_mut().probe(v);
+                match opt_value {
+                    Some(value) => value.to_type(tcx),
+                    None => {
+                        let root = self.infcx.float_unification_table.borrow_mut().find(v);
+                        self.canonical_ty(CanonicalVarKind::Float, tcx.mk_float_var(root))
+                    }
+                }
+            }
+
+            ty::TyInfer(ty::SkolemizedTy(_)) |
+            ty::TyInfer(ty::FreshTy(_)) |
+            ty::TyInfer(ty::FreshIntTy(_)) |
+            ty::TyInfer(ty::FreshFloatTy(_)) => {
+                self.skolemized = true;
+                t
+            }
+
+            _ => t.super_fold_with(self),
+        }
+    }
+}
+
+impl<'tcx> TypeFoldable<'tcx> for CanonicalVarValue<'tcx> {
+    fn super_fold_with<F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Self {
+        match *self {
+            CanonicalVarValue::Ty(ty) => CanonicalVarValue::Ty(ty.fold_with(folder)),
+            CanonicalVarValue::Region(r) => CanonicalVarValue::Region(r.fold_with(folder)),
+        }
+    }
+
+    fn super_visit_with<V: TypeVisitor<'tcx>>(&self, visitor: &mut V) -> bool {
+        match *self {
+            CanonicalVarValue::Ty(ty) => ty.visit_with(visitor),
+            CanonicalVarValue::Region(r) => r.visit_with(visitor),
+        }
+    }
+}
+
+impl<'tcx, R: TypeFoldable<'tcx>> TypeFoldable<'tcx> for QueryResponse<'tcx, R> {
+    fn super_fold_with<F: TypeFolder<'tcx>>(&self, folder: &mut F) -> Self {
+        QueryResponse {
+            var_values: self.var_values.fold_with(folder),
+            region_constraints: self.region_constraints.fold_with(folder),
+            value: self.value.fold_with(folder),
+        }
+    }
+
+    fn super_visit_with<V: TypeVisitor<'tcx>>(&self, visitor: &mut V) -> bool {
+        self.var_values.visit_with(visitor) ||
+            self.region_constraints.visit_with(visitor) ||
+            self.value.visit_with(visitor)
+    }
+}
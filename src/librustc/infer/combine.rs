@@ -37,17 +37,21 @@ use super::equate::Equate;
 use super::glb::Glb;
 use super::lub::Lub;
 use super::sub::Sub;
-use super::InferCtxt;
+use super::{CombinedSnapshot, InferCtxt};
 use super::{MiscVariable, TypeTrace};
 use super::type_variable::{RelationDir, BiTo, EqTo, SubtypeOf, SupertypeOf};
 
+use middle::const_val::ConstVal;
 use ty::{IntType, UintType};
 use ty::{self, Ty, TyCtxt};
 use ty::error::TypeError;
-use ty::fold::{TypeFolder, TypeFoldable};
+use ty::fold::TypeFoldable;
 use ty::relate::{Relate, RelateResult, TypeRelation};
-use traits::PredicateObligations;
+use traits::{self, PredicateObligations};
 
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
 use syntax::ast;
 use syntax::codemap::Span;
 
@@ -57,7 +61,11 @@ pub struct CombineFields<'a, 'tcx: 'a> {
     pub a_is_expected: bool,
     pub trace: TypeTrace<'tcx>,
     pub cause: Option<ty::relate::Cause>,
-    pub obligations: PredicateObligations<'tcx>,
+    /// Obligations that the relation recorded instead of deciding them
+    /// on the spot; see `defer_projection_eq`. The buffer is shared by
+    /// every relation spawned from these fields, since each of those
+    /// works on a clone of them.
+    pub obligations: Rc<RefCell<PredicateObligations<'tcx>>>,
 }
 
 pub fn super_combine_tys<'a,'tcx:'a,R>(infcx: &InferCtxt<'a, 'tcx>,
@@ -72,8 +80,9 @@ pub fn super_combine_tys<'a,'tcx:'a,R>(infcx: &InferCtxt<'a, 'tcx>,
     match (&a.sty, &b.sty) {
         // Relate integral variables to other types
         (&ty::TyInfer(ty::IntVar(a_id)), &ty::TyInfer(ty::IntVar(b_id))) => {
-            infcx.int_unification_table
+            infcx.inner
                  .borrow_mut()
+                 .int_unification_table()
                  .unify_var_var(a_id, b_id)
                  .map_err(|e| int_unification_error(a_is_expected, e))?;
             Ok(a)
@@ -93,8 +102,9 @@ pub fn super_combine_tys<'a,'tcx:'a,R>(infcx: &InferCtxt<'a, 'tcx>,
 
         // Relate floating-point variables to other types
         (&ty::TyInfer(ty::FloatVar(a_id)), &ty::TyInfer(ty::FloatVar(b_id))) => {
-            infcx.float_unification_table
+            infcx.inner
                  .borrow_mut()
+                 .float_unification_table()
                  .unify_var_var(a_id, b_id)
                  .map_err(|e| float_unification_error(relation.a_is_expected(), e))?;
             Ok(a)
@@ -119,14 +129,48 @@ pub fn super_combine_tys<'a,'tcx:'a,R>(infcx: &InferCtxt<'a, 'tcx>,
     }
 }
 
+/// Relates two constants appearing in types. Constants have no
+/// subtyping, so every relation requires them to be equal, binding any
+/// constant variables along the way.
+pub fn super_combine_consts<'a,'tcx:'a,R>(infcx: &InferCtxt<'a, 'tcx>,
+                                          relation: &mut R,
+                                          a: &ty::Const,
+                                          b: &ty::Const)
+                                          -> RelateResult<'tcx, ty::Const>
+    where R: TypeRelation<'a,'tcx>
+{
+    let a_is_expected = relation.a_is_expected();
+
+    match (a, b) {
+        (&ty::Const::Infer(a_id), &ty::Const::Infer(b_id)) => {
+            infcx.inner
+                 .borrow_mut()
+                 .const_variables()
+                 .unify_var_var(a_id, b_id)
+                 .map_err(|e| const_unification_error(a_is_expected, e))?;
+            Ok(a.clone())
+        }
+        (&ty::Const::Infer(vid), &ty::Const::Value(ref value)) => {
+            unify_const_variable(infcx, a_is_expected, vid, value.clone())
+        }
+        (&ty::Const::Value(ref value), &ty::Const::Infer(vid)) => {
+            unify_const_variable(infcx, !a_is_expected, vid, value.clone())
+        }
+        (&ty::Const::Value(_), &ty::Const::Value(_)) => {
+            ty::relate::super_relate_consts(relation, a, b)
+        }
+    }
+}
+
 fn unify_integral_variable<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
                                     vid_is_expected: bool,
                                     vid: ty::IntVid,
                                     val: ty::IntVarValue)
                                     -> RelateResult<'tcx, Ty<'tcx>>
 {
-    infcx.int_unification_table
+    infcx.inner
          .borrow_mut()
+         .int_unification_table()
          .unify_var_value(vid, val)
          .map_err(|e| int_unification_error(vid_is_expected, e))?;
     match val {
@@ -135,14 +179,29 @@ fn unify_integral_variable<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
     }
 }
 
+fn unify_const_variable<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
+                                 vid_is_expected: bool,
+                                 vid: ty::ConstVid,
+                                 val: ConstVal)
+                                 -> RelateResult<'tcx, ty::Const>
+{
+    infcx.inner
+         .borrow_mut()
+         .const_variables()
+         .unify_var_value(vid, val.clone())
+         .map_err(|e| const_unification_error(vid_is_expected, e))?;
+    Ok(ty::Const::Value(val))
+}
+
 fn unify_float_variable<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
                                  vid_is_expected: bool,
                                  vid: ty::FloatVid,
                                  val: ast::FloatTy)
                                  -> RelateResult<'tcx, Ty<'tcx>>
 {
-    infcx.float_unification_table
+    infcx.inner
          .borrow_mut()
+         .float_unification_table()
          .unify_var_value(vid, val)
          .map_err(|e| float_unification_error(vid_is_expected, e))?;
     Ok(infcx.tcx.mk_mach_float(val))
@@ -153,6 +212,74 @@ impl<'a, 'tcx> CombineFields<'a, 'tcx> {
         self.infcx.tcx
     }
 
+    /// Takes the obligations recorded by this relation and the ones it
+    /// spawned.
+    pub fn into_obligations(self) -> PredicateObligations<'tcx> {
+        mem::replace(&mut *self.obligations.borrow_mut(), vec![])
+    }
+
+    /// Like `InferCtxt::commit_if_ok`, but also forgets the obligations
+    /// recorded by `f` if it fails, as they were recorded against
+    /// bindings that have been rolled back.
+    pub fn commit_if_ok<T, F>(&self, f: F) -> RelateResult<'tcx, T>
+        where F: FnOnce(&CombinedSnapshot) -> RelateResult<'tcx, T>
+    {
+        let obligations_before = self.obligations.borrow().len();
+        let result = self.infcx.commit_if_ok(f);
+        if result.is_err() {
+            self.obligations.borrow_mut().truncate(obligations_before);
+        }
+        result
+    }
+
+    /// Relates `a` and `b`, one of which is a projection that has not
+    /// been normalized, by recording the obligation that the projection
+    /// equals the other type, to be processed later by fulfillment.
+    /// Normalizing here instead could recurse back into selection. The
+    /// obligation asks for equality even when relating by subtyping,
+    /// which is stricter but never unsound.
+    ///
+    /// Returns false, recording nothing, unless the caller asked for
+    /// this with `InferCtxt::deferring_projection_equality`, or if the
+    /// types are not of a shape that can be deferred. Variables are
+    /// left to be instantiated as usual, and types with escaping
+    /// late-bound regions cannot be turned into obligations.
+    pub fn defer_projection_eq(&self, a: Ty<'tcx>, b: Ty<'tcx>) -> bool {
+        if !self.infcx.defer_projection_equality.get() {
+            return false;
+        }
+
+        let (projection_ty, other) = match (&a.sty, &b.sty) {
+            // Projections of the same associated item are related
+            // structurally.
+            (&ty::TyProjection(ref a_data), &ty::TyProjection(ref b_data))
+                if a_data.item_name == b_data.item_name &&
+                   a_data.trait_ref.def_id == b_data.trait_ref.def_id => return false,
+            (&ty::TyProjection(ref data), _) => (data, b),
+            (_, &ty::TyProjection(ref data)) => (data, a),
+            _ => return false,
+        };
+
+        match other.sty {
+            ty::TyInfer(_) | ty::TyError => return false,
+            _ => {}
+        }
+        if a.has_escaping_regions() || b.has_escaping_regions() {
+            return false;
+        }
+
+        debug!("defer_projection_eq({:?} == {:?})", projection_ty, other);
+        let predicate = ty::Binder(ty::ProjectionPredicate {
+            projection_ty: projection_ty.clone(),
+            ty: other,
+        });
+        let cause = traits::ObligationCause::misc(self.trace.origin.span(), ast::DUMMY_NODE_ID);
+        self.obligations.borrow_mut().push(traits::Obligation::new(
+            cause,
+            ty::Predicate::Projection(predicate)));
+        true
+    }
+
     pub fn switch_expected(&self) -> CombineFields<'a, 'tcx> {
         CombineFields {
             a_is_expected: !self.a_is_expected,
@@ -212,7 +339,8 @@ impl<'a, 'tcx> CombineFields<'a, 'tcx> {
             };
             // Get the actual variable that b_vid has been inferred to
             let (b_vid, b_ty) = {
-                let mut variables = self.infcx.type_variables.borrow_mut();
+                let mut inner = self.infcx.inner.borrow_mut();
+                let mut variables = inner.type_variables();
                 let b_vid = variables.root_var(b_vid);
                 (b_vid, variables.probe_root(b_vid))
             };
@@ -229,16 +357,14 @@ impl<'a, 'tcx> CombineFields<'a, 'tcx> {
                 Some(t) => t, // ...already instantiated.
                 None => {     // ...not yet instantiated:
                     // Generalize type if necessary.
-                    let generalized_ty = match dir {
-                        EqTo => self.generalize(a_ty, b_vid, false),
-                        BiTo | SupertypeOf | SubtypeOf => self.generalize(a_ty, b_vid, true),
-                    }?;
+                    let generalized_ty = self.generalize(a_ty, b_vid, dir)?;
                     debug!("instantiate(a_ty={:?}, dir={:?}, \
                                         b_vid={:?}, generalized_ty={:?})",
                            a_ty, dir, b_vid,
                            generalized_ty);
-                    self.infcx.type_variables
+                    self.infcx.inner
                         .borrow_mut()
+                        .type_variables()
                         .instantiate_and_push(
                             b_vid, generalized_ty, &mut stack);
                     generalized_ty
@@ -263,46 +389,77 @@ impl<'a, 'tcx> CombineFields<'a, 'tcx> {
         Ok(())
     }
 
-    /// Attempts to generalize `ty` for the type variable `for_vid`.  This checks for cycle -- that
-    /// is, whether the type `ty` references `for_vid`. If `make_region_vars` is true, it will also
-    /// replace all regions with fresh variables. Returns `TyError` in the case of a cycle, `Ok`
-    /// otherwise.
+    /// Attempts to generalize `ty` for the type variable `for_vid`, which is about to be related
+    /// to `ty` according to `dir`. This checks for cycles -- that is, whether the type `ty`
+    /// references `for_vid` -- and returns `CyclicTy` if there is one.
+    ///
+    /// Regions are replaced with fresh region variables wherever subtyping could apply to them,
+    /// taking the variance of each position into account: `&'a T` becomes `&'?1 T` when
+    /// relating by subtyping, leaving `'?1` free to differ from `'a`, but regions in invariant
+    /// positions (including everything when `dir` is `EqTo`) are kept as they are, since they
+    /// would just be equated with the variable again.
     fn generalize(&self,
                   ty: Ty<'tcx>,
                   for_vid: ty::TyVid,
-                  make_region_vars: bool)
+                  dir: RelationDir)
                   -> RelateResult<'tcx, Ty<'tcx>>
     {
+        let ambient_variance = match dir {
+            EqTo => ty::Invariant,
+            SubtypeOf => ty::Covariant,
+            SupertypeOf => ty::Contravariant,
+            BiTo => ty::Bivariant,
+        };
+
         let mut generalize = Generalizer {
             infcx: self.infcx,
             span: self.trace.origin.span(),
             for_vid: for_vid,
-            make_region_vars: make_region_vars,
-            cycle_detected: false
+            ambient_variance: ambient_variance,
         };
-        let u = ty.fold_with(&mut generalize);
-        if generalize.cycle_detected {
-            Err(TypeError::CyclicTy)
-        } else {
-            Ok(u)
-        }
+        generalize.relate(&ty, &ty)
     }
 }
 
+/// Generalization is written as a relation of a type with itself, so
+/// that relating substitutions and the like tracks the variance of
+/// each position for us.
 struct Generalizer<'cx, 'tcx:'cx> {
     infcx: &'cx InferCtxt<'cx, 'tcx>,
     span: Span,
     for_vid: ty::TyVid,
-    make_region_vars: bool,
-    cycle_detected: bool,
+    ambient_variance: ty::Variance,
 }
 
-impl<'cx, 'tcx> ty::fold::TypeFolder<'tcx> for Generalizer<'cx, 'tcx> {
-    fn tcx(&self) -> &TyCtxt<'tcx> {
+impl<'cx, 'tcx> TypeRelation<'cx, 'tcx> for Generalizer<'cx, 'tcx> {
+    fn tcx(&self) -> &'cx TyCtxt<'tcx> {
         self.infcx.tcx
     }
 
-    fn fold_ty(&mut self, t: Ty<'tcx>) -> Ty<'tcx> {
+    fn tag(&self) -> &'static str {
+        "Generalizer"
+    }
+
+    fn a_is_expected(&self) -> bool {
+        true
+    }
+
+    fn relate_with_variance<T: Relate<'cx, 'tcx>>(&mut self,
+                                                  variance: ty::Variance,
+                                                  a: &T,
+                                                  b: &T)
+                                                  -> RelateResult<'tcx, T>
+    {
+        let old_ambient_variance = self.ambient_variance;
+        self.ambient_variance = self.ambient_variance.xform(variance);
+        let result = self.relate(a, b);
+        self.ambient_variance = old_ambient_variance;
+        result
+    }
+
+    fn tys(&mut self, t: Ty<'tcx>, t2: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        assert_eq!(t, t2); // we are abusing TypeRelation here; both LHS and RHS ought to be ==
+
         // Check to see whether the type we are genealizing references
         // `vid`. At the same time, also update any type variables to
         // the values that they are bound to. This is needed to truly
@@ -312,31 +469,38 @@ impl<'cx, 'tcx> ty::fold::TypeFolder<'tcx> for Generalizer<'cx, 'tcx> {
         //  where `$1` has already been instantiated with `Box<$0>`)
         match t.sty {
             ty::TyInfer(ty::TyVar(vid)) => {
-                let mut variables = self.infcx.type_variables.borrow_mut();
+                let mut inner = self.infcx.inner.borrow_mut();
+                let mut variables = inner.type_variables();
                 let vid = variables.root_var(vid);
                 if vid == self.for_vid {
-                    self.cycle_detected = true;
-                    self.tcx().types.err
+                    Err(TypeError::CyclicTy)
                 } else {
                     match variables.probe_root(vid) {
                         Some(u) => {
                             drop(variables);
-                            self.fold_ty(u)
+                            drop(inner);
+                            self.relate(&u, &u)
                         }
-                        None => t,
+                        None => Ok(t),
                     }
                 }
             }
+            ty::TyInfer(_) => {
+                // Integral and float variables cannot contain `for_vid`.
+                Ok(t)
+            }
             _ => {
-                t.super_fold_with(self)
+                ty::relate::super_relate_tys(self, t, t)
             }
         }
     }
 
-    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+    fn regions(&mut self, r: ty::Region, r2: ty::Region) -> RelateResult<'tcx, ty::Region> {
+        assert_eq!(r, r2); // we are abusing TypeRelation here; both LHS and RHS ought to be ==
+
         match r {
             // Never make variables for regions bound within the type itself.
-            ty::ReLateBound(..) => { return r; }
+            ty::ReLateBound(..) => { return Ok(r); }
 
             // Early-bound regions should really have been substituted away before
             // we get to this point.
@@ -351,22 +515,38 @@ impl<'cx, 'tcx> ty::fold::TypeFolder<'tcx> for Generalizer<'cx, 'tcx> {
             // the higher-ranked decision procedures rely on this.
             ty::ReSkolemized(..) => { }
 
-            // For anything else, we make a region variable, unless we
-            // are *equating*, in which case it's just wasteful.
+            // For anything else, we make a region variable, unless the
+            // region appears invariantly, in which case it would just
+            // be equated with the variable again.
             ty::ReEmpty |
             ty::ReStatic |
             ty::ReScope(..) |
             ty::ReVar(..) |
             ty::ReFree(..) => {
-                if !self.make_region_vars {
-                    return r;
+                if self.ambient_variance == ty::Invariant {
+                    return Ok(r);
                 }
             }
         }
 
         // FIXME: This is non-ideal because we don't give a
         // very descriptive origin for this region variable.
-        self.infcx.next_region_var(MiscVariable(self.span))
+        Ok(self.infcx.next_region_var(MiscVariable(self.span)))
+    }
+
+    fn consts(&mut self, c: &ty::Const, c2: &ty::Const) -> RelateResult<'tcx, ty::Const> {
+        assert_eq!(c, c2); // as in `regions`, both sides are the same
+
+        // Constants are always related invariantly, so there is
+        // nothing to gain from a fresh variable here.
+        Ok(c.clone())
+    }
+
+    fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
+                  -> RelateResult<'tcx, ty::Binder<T>>
+        where T: Relate<'cx, 'tcx>
+    {
+        Ok(ty::Binder(self.relate(a.skip_binder(), b.skip_binder())?))
     }
 }
 
@@ -403,3 +583,10 @@ fn float_unification_error<'tcx>(a_is_expected: bool,
     let (a, b) = v;
     TypeError::FloatMismatch(ty::relate::expected_found_bool(a_is_expected, &a, &b))
 }
+
+fn const_unification_error<'tcx>(a_is_expected: bool, v: (ConstVal, ConstVal))
+                                 -> TypeError<'tcx>
+{
+    let (a, b) = v;
+    TypeError::ConstMismatch(ty::relate::expected_found_bool(a_is_expected, &a, &b))
+}
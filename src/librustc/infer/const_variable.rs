@@ -0,0 +1,99 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Inference variables standing for constants that appear in types,
+//! such as the length of an array. Constants have no subtyping, so
+//! unlike type variables a constant variable is either unknown or
+//! equal to exactly one value, and two variables that are related
+//! must end up with the same value.
+
+use middle::const_val::ConstVal;
+use ty;
+
+use rustc_data_structures::snapshot_vec::{self as sv, Rollback};
+use rustc_data_structures::unify as ut;
+
+use super::undo_log::InferCtxtUndoLogs;
+
+/// The constant variables of an inference context. Like the type
+/// variables, their changes are recorded into the context's shared
+/// undo log, so they are accessed through a `ConstVariableTable`
+/// borrowing both; see `with_log`.
+pub struct ConstVariableStorage {
+    values: ut::UnificationStorage<ty::ConstVid>,
+}
+
+pub type UndoLog = sv::UndoLog<ut::Delegate<ty::ConstVid>>;
+
+pub struct ConstVariableTable<'a, 'tcx: 'a> {
+    storage: &'a mut ConstVariableStorage,
+    undo_log: &'a mut InferCtxtUndoLogs<'tcx>,
+}
+
+impl ConstVariableStorage {
+    pub fn new() -> ConstVariableStorage {
+        ConstVariableStorage {
+            values: ut::UnificationTable::new(),
+        }
+    }
+
+    /// Borrows the constant variables together with `undo_log`, into
+    /// which the changes made through the result are recorded.
+    pub fn with_log<'a, 'tcx>(&'a mut self, undo_log: &'a mut InferCtxtUndoLogs<'tcx>)
+                              -> ConstVariableTable<'a, 'tcx> {
+        ConstVariableTable { storage: self, undo_log: undo_log }
+    }
+}
+
+impl Rollback<UndoLog> for ConstVariableStorage {
+    fn reverse(&mut self, undo: UndoLog) {
+        self.values.reverse(undo);
+    }
+}
+
+impl<'a, 'tcx> ConstVariableTable<'a, 'tcx> {
+    fn values<'b>(&'b mut self)
+                  -> ut::UnificationTable<ty::ConstVid,
+                                          &'b mut Vec<ut::VarValue<ty::ConstVid>>,
+                                          &'b mut InferCtxtUndoLogs<'tcx>> {
+        self.storage.values.with_log(&mut *self.undo_log)
+    }
+
+    pub fn new_var(&mut self) -> ty::ConstVid {
+        let vid = self.values().new_key(None);
+        debug!("new_var() -> {:?}", vid);
+        vid
+    }
+
+    /// Returns the value `vid` has been unified with, if any.
+    pub fn probe(&mut self, vid: ty::ConstVid) -> Option<ConstVal> {
+        self.values().probe(vid)
+    }
+
+    /// Records that `a` and `b` must be the same constant. Fails with
+    /// both values if they are already known to differ.
+    pub fn unify_var_var(&mut self, a: ty::ConstVid, b: ty::ConstVid)
+                         -> Result<(), (ConstVal, ConstVal)> {
+        debug!("unify_var_var(a={:?}, b={:?})", a, b);
+        self.values().unify_var_var(a, b).map(|_| ())
+    }
+
+    /// Records that `vid` is the constant `value`. Fails with both
+    /// values if `vid` is already known to be something else.
+    pub fn unify_var_value(&mut self, vid: ty::ConstVid, value: ConstVal)
+                           -> Result<(), (ConstVal, ConstVal)> {
+        debug!("unify_var_value(vid={:?}, value={:?})", vid, value);
+        self.values().unify_var_value(vid, value)
+    }
+
+    pub fn unsolved_variables(&mut self) -> Vec<ty::ConstVid> {
+        self.values().unsolved_variables()
+    }
+}
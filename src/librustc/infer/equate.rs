@@ -29,7 +29,7 @@ impl<'a, 'tcx> Equate<'a, 'tcx> {
     }
 
     pub fn obligations(self) -> PredicateObligations<'tcx> {
-        self.fields.obligations
+        self.fields.into_obligations()
     }
 }
 
@@ -55,11 +55,11 @@ impl<'a, 'tcx> TypeRelation<'a,'tcx> for Equate<'a, 'tcx> {
         if a == b { return Ok(a); }
 
         let infcx = self.fields.infcx;
-        let a = infcx.type_variables.borrow_mut().replace_if_possible(a);
-        let b = infcx.type_variables.borrow_mut().replace_if_possible(b);
+        let a = infcx.inner.borrow_mut().type_variables().replace_if_possible(a);
+        let b = infcx.inner.borrow_mut().type_variables().replace_if_possible(b);
         match (&a.sty, &b.sty) {
             (&ty::TyInfer(TyVar(a_id)), &ty::TyInfer(TyVar(b_id))) => {
-                infcx.type_variables.borrow_mut().relate_vars(a_id, EqTo, b_id);
+                infcx.inner.borrow_mut().type_variables().relate_vars(a_id, EqTo, b_id);
                 Ok(a)
             }
 
@@ -74,7 +74,9 @@ impl<'a, 'tcx> TypeRelation<'a,'tcx> for Equate<'a, 'tcx> {
             }
 
             _ => {
-                combine::super_combine_tys(self.fields.infcx, self, a, b)?;
+                if !self.fields.defer_projection_eq(a, b) {
+                    combine::super_combine_tys(self.fields.infcx, self, a, b)?;
+                }
                 Ok(a)
             }
         }
@@ -86,15 +88,19 @@ impl<'a, 'tcx> TypeRelation<'a,'tcx> for Equate<'a, 'tcx> {
                a,
                b);
         let origin = Subtype(self.fields.trace.clone());
-        self.fields.infcx.region_vars.make_eqregion(origin, a, b);
+        let infcx = self.fields.infcx;
+        infcx.region_vars.make_eqregion(&mut infcx.inner.borrow_mut().undo_log, origin, a, b);
         Ok(a)
     }
 
+    fn consts(&mut self, a: &ty::Const, b: &ty::Const) -> RelateResult<'tcx, ty::Const> {
+        combine::super_combine_consts(self.fields.infcx, self, a, b)
+    }
+
     fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
                   -> RelateResult<'tcx, ty::Binder<T>>
         where T: Relate<'a, 'tcx>
     {
-        self.fields.higher_ranked_sub(a, b)?;
-        self.fields.higher_ranked_sub(b, a)
+        self.fields.higher_ranked_equate(a, b)
     }
 }
@@ -74,7 +74,9 @@ use super::region_inference::SameRegions;
 use std::collections::HashSet;
 
 use hir::map as ast_map;
+use hir::map::blocks::FnLikeNode;
 use hir;
+use hir::intravisit;
 use hir::print as pprust;
 
 use middle::cstore::CrateStore;
@@ -120,6 +122,10 @@ impl<'tcx> TyCtxt<'tcx> {
              Some(span))
         }
 
+        // Where the region's bound region came from, for regions that
+        // need a note pointing there.
+        let mut origin_note = None;
+
         let (description, span) = match region {
             ty::ReScope(scope) => {
                 let new_string;
@@ -175,8 +181,9 @@ impl<'tcx> TyCtxt<'tcx> {
 
             ty::ReFree(ref fr) => {
                 let prefix = match fr.bound_region {
-                    ty::BrAnon(idx) => {
-                        format!("the anonymous lifetime #{} defined on", idx + 1)
+                    ty::BrAnon(_) => {
+                        format!("the anonymous lifetime `{}` defined on",
+                                bound_region_name(fr.bound_region))
                     }
                     ty::BrFresh(_) => "an anonymous lifetime defined on".to_owned(),
                     _ => {
@@ -185,6 +192,11 @@ impl<'tcx> TyCtxt<'tcx> {
                     }
                 };
 
+                if let ty::BrAnon(_) = fr.bound_region {
+                    let body_id = fr.scope.node_id(&self.region_maps);
+                    origin_note = Some((fr.bound_region, Some(self.map.get_parent_node(body_id))));
+                }
+
                 match self.map.find(fr.scope.node_id(&self.region_maps)) {
                     Some(ast_map::NodeBlock(ref blk)) => {
                         let (msg, opt_span) = explain_span(self, "block", blk.span);
@@ -210,12 +222,16 @@ impl<'tcx> TyCtxt<'tcx> {
 
             ty::ReEarlyBound(ref data) => (data.name.to_string(), None),
 
-            // FIXME(#13998) ReSkolemized should probably print like
-            // ReFree rather than dumping Debug output on the user.
-            //
-            // We shouldn't really be having unification failures with ReVar
-            // and ReLateBound though.
-            ty::ReSkolemized(..) | ty::ReVar(_) | ty::ReLateBound(..) => {
+            // Skolemized regions stand in for the bound regions of a
+            // higher-ranked type while it is being compared, so describe
+            // them by the bound region they replace.
+            ty::ReSkolemized(_, br) | ty::ReLateBound(_, br) => {
+                origin_note = Some((br, None));
+                (format!("the lifetime `{}`", bound_region_name(br)), None)
+            }
+
+            // We shouldn't really be having unification failures with ReVar.
+            ty::ReVar(_) => {
                 (format!("lifetime {:?}", region), None)
             }
         };
@@ -225,7 +241,82 @@ impl<'tcx> TyCtxt<'tcx> {
         } else {
             err.note(&message);
         }
+        if let Some((br, fn_id)) = origin_note {
+            self.note_bound_region_origin(err, br, fn_id);
+        }
+    }
+
+    /// Points at where the bound region `br` was introduced: the
+    /// declaration of a named lifetime or, for an anonymous lifetime of
+    /// the function `fn_id`, the parameter in whose type it was elided.
+    pub fn note_bound_region_origin(&self,
+                                    err: &mut DiagnosticBuilder,
+                                    br: ty::BoundRegion,
+                                    fn_id: Option<ast::NodeId>) {
+        match br {
+            ty::BrNamed(def_id, name) => {
+                if let Some(span) = self.map.span_if_local(def_id) {
+                    err.span_note(span, &format!("lifetime `{}` declared here", name));
+                }
+            }
+            ty::BrAnon(idx) => {
+                let decl = fn_id.and_then(|id| self.map.find(id))
+                                .and_then(FnLikeNode::from_node)
+                                .map(|fn_like| fn_like.decl());
+                if let Some(span) = decl.and_then(|decl| elided_lifetime_arg(decl, idx)) {
+                    err.span_note(span, &format!("lifetime `{}` is the anonymous lifetime \
+                                                  of this parameter",
+                                                 bound_region_name(br)));
+                }
+            }
+            ty::BrFresh(_) | ty::BrEnv => {}
+        }
+    }
+}
+
+/// The name diagnostics use for the bound region `br`. Lifetimes the
+/// user left anonymous get synthesized names, `'1`, `'2` and so on,
+/// numbered by their position among the elided lifetimes.
+pub fn bound_region_name(br: ty::BoundRegion) -> String {
+    match br {
+        ty::BrNamed(_, name) => name.to_string(),
+        ty::BrAnon(idx) => format!("'{}", idx + 1),
+        ty::BrFresh(_) | ty::BrEnv => "'_".to_string(),
+    }
+}
+
+/// The type of the parameter of `decl` containing the `idx`th elided
+/// reference lifetime, counting from the first parameter.
+fn elided_lifetime_arg(decl: &hir::FnDecl, idx: u32) -> Option<Span> {
+    struct ElidedCounter {
+        remaining: u32,
+        found: bool,
+    }
+
+    impl<'v> intravisit::Visitor<'v> for ElidedCounter {
+        fn visit_ty(&mut self, ty: &'v hir::Ty) {
+            if self.found {
+                return;
+            }
+            if let hir::TyRptr(None, _) = ty.node {
+                if self.remaining == 0 {
+                    self.found = true;
+                    return;
+                }
+                self.remaining -= 1;
+            }
+            intravisit::walk_ty(self, ty);
+        }
+    }
+
+    let mut counter = ElidedCounter { remaining: idx, found: false };
+    for arg in &decl.inputs {
+        intravisit::Visitor::visit_ty(&mut counter, &arg.ty);
+        if counter.found {
+            return Some(arg.ty.span);
+        }
     }
+    None
 }
 
 pub trait ErrorReporting<'tcx> {
@@ -1281,6 +1372,7 @@ impl<'a, 'tcx> Rebuilder<'a, 'tcx> {
                 bounds: bounds,
                 default: ty_param.default.clone(),
                 span: ty_param.span,
+                pure_wrt_drop: ty_param.pure_wrt_drop,
             }
         }).collect()
     }
@@ -1368,7 +1460,8 @@ impl<'a, 'tcx> Rebuilder<'a, 'tcx> {
         let mut lifetimes = Vec::new();
         for lt in add {
             lifetimes.push(hir::LifetimeDef { lifetime: *lt,
-                                              bounds: hir::HirVec::new() });
+                                              bounds: hir::HirVec::new(),
+                                              pure_wrt_drop: false });
         }
         for lt in &generics.lifetimes {
             if keep.contains(&lt.lifetime.name) ||
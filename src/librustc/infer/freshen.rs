@@ -111,7 +111,7 @@ impl<'a, 'tcx> TypeFolder<'tcx> for TypeFreshener<'a, 'tcx> {
 
         match t.sty {
             ty::TyInfer(ty::TyVar(v)) => {
-                let opt_ty = self.infcx.type_variables.borrow_mut().probe(v);
+                let opt_ty = self.infcx.inner.borrow_mut().type_variables().probe(v);
                 self.freshen(
                     opt_ty,
                     ty::TyVar(v),
@@ -120,18 +120,20 @@ impl<'a, 'tcx> TypeFolder<'tcx> for TypeFreshener<'a, 'tcx> {
 
             ty::TyInfer(ty::IntVar(v)) => {
                 self.freshen(
-                    self.infcx.int_unification_table.borrow_mut()
-                                                    .probe(v)
-                                                    .map(|v| v.to_type(tcx)),
+                    self.infcx.inner.borrow_mut()
+                                    .int_unification_table()
+                                    .probe(v)
+                                    .map(|v| v.to_type(tcx)),
                     ty::IntVar(v),
                     ty::FreshIntTy)
             }
 
             ty::TyInfer(ty::FloatVar(v)) => {
                 self.freshen(
-                    self.infcx.float_unification_table.borrow_mut()
-                                                      .probe(v)
-                                                      .map(|v| v.to_type(tcx)),
+                    self.infcx.inner.borrow_mut()
+                                    .float_unification_table()
+                                    .probe(v)
+                                    .map(|v| v.to_type(tcx)),
                     ty::FloatVar(v),
                     ty::FreshFloatTy)
             }
@@ -148,6 +150,12 @@ impl<'a, 'tcx> TypeFolder<'tcx> for TypeFreshener<'a, 'tcx> {
                 t
             }
 
+            ty::TyInfer(ty::SkolemizedTy(_)) => {
+                // Skolemized types behave like type parameters: they
+                // stand for one particular (unknown) type.
+                t
+            }
+
             ty::TyBool |
             ty::TyChar |
             ty::TyInt(..) |
@@ -168,7 +176,8 @@ impl<'a, 'tcx> TypeFolder<'tcx> for TypeFreshener<'a, 'tcx> {
             ty::TyClosure(..) |
             ty::TyTuple(..) |
             ty::TyProjection(..) |
-            ty::TyParam(..) => {
+            ty::TyParam(..) |
+            ty::TyBound(..) => {
                 t.super_fold_with(self)
             }
         }
@@ -0,0 +1,166 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Speculative inference whose result outlives its snapshot.
+//!
+//! Sometimes we want to run some inference just to learn the *shape*
+//! of a type -- e.g. the expected signature of a closure -- without
+//! keeping any of the constraints that were recorded along the way.
+//! Rolling back the snapshot is not enough on its own, because the
+//! result may mention variables that were created inside the
+//! snapshot and no longer exist afterwards. `fudge_inference_if_ok`
+//! replaces each such variable with a fresh one of the same kind.
+
+use ty::{self, Ty, TyCtxt, TyVid, IntVid, FloatVid, RegionVid};
+use ty::fold::{TypeFoldable, TypeFolder};
+use util::nodemap::FnvHashMap;
+
+use super::{InferCtxt, RegionVariableOrigin};
+use super::type_variable::TypeVariableOrigin;
+
+impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
+    /// Executes `f` in a snapshot and then rolls the snapshot back,
+    /// whether or not `f` succeeded. On success, the value returned
+    /// by `f` is resolved as far as possible, and any type, integral,
+    /// float or region variables in it that were created by `f` are
+    /// replaced with fresh variables. Variables that already existed
+    /// before the snapshot are left alone.
+    ///
+    /// Note that any relationships between the variables created by
+    /// `f` are lost: two variables unified inside the snapshot come
+    /// out as two unrelated fresh variables.
+    pub fn fudge_inference_if_ok<T, E, F>(&self, f: F) -> Result<T, E>
+        where F: FnOnce() -> Result<T, E>,
+              T: TypeFoldable<'tcx>,
+    {
+        debug!("fudge_inference_if_ok()");
+
+        let (int_vars_before, float_vars_before) = {
+            let mut inner = self.inner.borrow_mut();
+            (inner.int_unification_table().len() as u32,
+             inner.float_unification_table().len() as u32)
+        };
+
+        let (value, type_vars, region_vars) = self.probe(|snapshot| {
+            let value = f()?;
+            let value = self.resolve_type_vars_if_possible(&value);
+
+            // Everything we need to re-create the variables has to be
+            // read out before the snapshot is rolled back.
+            let mut inner = self.inner.borrow_mut();
+            let vids = inner.type_variables
+                            .types_created_since_snapshot(&snapshot.type_snapshot);
+            let type_vars: FnvHashMap<_, _> = {
+                let type_variables = inner.type_variables();
+                vids.into_iter()
+                    .map(|vid| (vid, (type_variables.var_diverges(vid),
+                                      type_variables.var_origin(vid))))
+                    .collect()
+            };
+            let region_vars: FnvHashMap<_, _> =
+                self.region_vars.vars_created_since(
+                        inner.undo_log.actions_since_snapshot(&snapshot.undo_snapshot))
+                    .map(|vid| (vid, self.region_vars.var_origin(vid)))
+                    .collect();
+
+            Ok((value, type_vars, region_vars))
+        })?;
+
+        let mut fudger = InferenceFudger {
+            infcx: self,
+            type_vars: type_vars,
+            int_vars_before: int_vars_before,
+            float_vars_before: float_vars_before,
+            region_vars: region_vars,
+            fresh_map: FnvHashMap(),
+            fresh_regions: FnvHashMap(),
+        };
+        Ok(value.fold_with(&mut fudger))
+    }
+}
+
+struct InferenceFudger<'a, 'tcx: 'a> {
+    infcx: &'a InferCtxt<'a, 'tcx>,
+
+    /// Type variables created inside the snapshot, with whether they
+    /// were diverging and why they were created.
+    type_vars: FnvHashMap<TyVid, (bool, TypeVariableOrigin)>,
+
+    /// Integral and float variables with an index at least this large
+    /// were created inside the snapshot.
+    int_vars_before: u32,
+    float_vars_before: u32,
+
+    /// Region variables created inside the snapshot.
+    region_vars: FnvHashMap<RegionVid, RegionVariableOrigin>,
+
+    /// The fresh variable handed out for each variable replaced so
+    /// far, so that repeated occurrences stay the same variable.
+    fresh_map: FnvHashMap<ty::InferTy, Ty<'tcx>>,
+    fresh_regions: FnvHashMap<RegionVid, ty::Region>,
+}
+
+impl<'a, 'tcx> TypeFolder<'tcx> for InferenceFudger<'a, 'tcx> {
+    fn tcx<'b>(&'b self) -> &'b TyCtxt<'tcx> {
+        self.infcx.tcx
+    }
+
+    fn fold_ty(&mut self, t: Ty<'tcx>) -> Ty<'tcx> {
+        if !t.needs_infer() {
+            return t;
+        }
+
+        let infcx = self.infcx;
+        match t.sty {
+            ty::TyInfer(ty::TyVar(vid)) => {
+                match self.type_vars.get(&vid) {
+                    Some(&(diverging, origin)) => {
+                        *self.fresh_map.entry(ty::TyVar(vid)).or_insert_with(|| {
+                            infcx.tcx.mk_var(infcx.next_ty_var_id(diverging, origin))
+                        })
+                    }
+                    None => t,
+                }
+            }
+
+            ty::TyInfer(ty::IntVar(IntVid { index })) if index >= self.int_vars_before => {
+                *self.fresh_map.entry(ty::IntVar(IntVid { index: index })).or_insert_with(|| {
+                    infcx.tcx.mk_int_var(infcx.next_int_var_id())
+                })
+            }
+
+            ty::TyInfer(ty::FloatVar(FloatVid { index })) if index >= self.float_vars_before => {
+                *self.fresh_map.entry(ty::FloatVar(FloatVid { index: index }))
+                               .or_insert_with(|| {
+                    infcx.tcx.mk_float_var(infcx.next_float_var_id())
+                })
+            }
+
+            _ => t.super_fold_with(self),
+        }
+    }
+
+    fn fold_region(&mut self, r: ty::Region) -> ty::Region {
+        let infcx = self.infcx;
+        match r {
+            ty::ReVar(vid) => {
+                match self.region_vars.get(&vid) {
+                    Some(origin) => {
+                        *self.fresh_regions.entry(vid).or_insert_with(|| {
+                            infcx.next_region_var(origin.clone())
+                        })
+                    }
+                    None => r,
+                }
+            }
+            _ => r,
+        }
+    }
+}
@@ -13,17 +13,37 @@
 
 use super::{CombinedSnapshot, InferCtxt, HigherRankedType, SkolemizationMap};
 use super::combine::CombineFields;
+use super::region_inference::TaintDirections;
 
-use ty::{self, TyCtxt, Binder, TypeFoldable};
+use ty::{self, Ty, TyCtxt, Binder, TypeFoldable};
 use ty::error::TypeError;
+use ty::fold::{TypeFolder, TypeVisitor};
 use ty::relate::{Relate, RelateResult, TypeRelation};
 use syntax::codemap::Span;
 use util::nodemap::{FnvHashMap, FnvHashSet};
 
+/// A skolemized region or type that escaped a higher-ranked
+/// comparison; see `leak_check`.
+#[derive(Debug)]
+pub enum Leak<'tcx> {
+    /// The skolemized region that replaced the bound region was
+    /// related to the given region.
+    Region(ty::BoundRegion, ty::Region),
+
+    /// The skolemized type that replaced the bound type flowed into a
+    /// type variable from outside the snapshot, as part of the given
+    /// type.
+    Type(ty::BoundTy, Ty<'tcx>),
+}
+
 pub trait HigherRankedRelations<'a,'tcx> {
     fn higher_ranked_sub<T>(&self, a: &Binder<T>, b: &Binder<T>) -> RelateResult<'tcx, Binder<T>>
         where T: Relate<'a,'tcx>;
 
+    fn higher_ranked_equate<T>(&self, a: &Binder<T>, b: &Binder<T>)
+                               -> RelateResult<'tcx, Binder<T>>
+        where T: Relate<'a,'tcx>;
+
     fn higher_ranked_lub<T>(&self, a: &Binder<T>, b: &Binder<T>) -> RelateResult<'tcx, Binder<T>>
         where T: Relate<'a,'tcx>;
 
@@ -32,11 +52,28 @@ pub trait HigherRankedRelations<'a,'tcx> {
 }
 
 trait InferCtxtExt {
-    fn tainted_regions(&self, snapshot: &CombinedSnapshot, r: ty::Region) -> Vec<ty::Region>;
+    fn tainted_regions(&self,
+                       snapshot: &CombinedSnapshot,
+                       r: ty::Region,
+                       directions: TaintDirections)
+                       -> Vec<ty::Region>;
 
     fn region_vars_confined_to_snapshot(&self,
                                         snapshot: &CombinedSnapshot)
                                         -> Vec<ty::RegionVid>;
+
+    fn region_vars_confined_to_snapshot_in(&self,
+                                           snapshot: &CombinedSnapshot,
+                                           scratch: &mut ConfinedRegionsScratch);
+}
+
+/// The buffers filled in by `region_vars_confined_to_snapshot_in`.
+/// One is kept on `InferCtxt`, so that the higher-ranked LUB and GLB do
+/// not allocate afresh for every binder they compare.
+#[derive(Default)]
+pub struct ConfinedRegionsScratch {
+    region_vars: Vec<ty::RegionVid>,
+    escaping_region_vars: FnvHashSet<ty::Region>,
 }
 
 impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
@@ -57,7 +94,7 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
 
         // Start a snapshot so we can examine "all bindings that were
         // created as part of this type comparison".
-        return self.infcx.commit_if_ok(|snapshot| {
+        return self.commit_if_ok(|snapshot| {
             // First, we instantiate each bound region in the subtype with a fresh
             // region variable.
             let (a_prime, _) =
@@ -75,13 +112,19 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
             debug!("b_prime={:?}", b_prime);
 
             // Compare types now that bound regions have been replaced.
+            let obligations_before = self.obligations.borrow().len();
             let result = self.sub().relate(&a_prime, &b_prime)?;
 
             // Presuming type comparison succeeds, we need to check
             // that the skolemized regions do not "leak".
             match leak_check(self.infcx, &skol_map, snapshot) {
                 Ok(()) => { }
-                Err((skol_br, tainted_region)) => {
+                Err(Leak::Type(skol_bt, tainted_ty)) => {
+                    debug!("Not as polymorphic (type)!");
+                    return Err(TypeError::TypesInsufficientlyPolymorphic(skol_bt,
+                                                                         tainted_ty));
+                }
+                Err(Leak::Region(skol_br, tainted_region)) => {
                     if self.a_is_expected {
                         debug!("Not as polymorphic!");
                         return Err(TypeError::RegionsInsufficientlyPolymorphic(skol_br,
@@ -94,6 +137,20 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
                 }
             }
 
+            // Obligations recorded by the comparison may refer to the
+            // skolemized regions; rebind those in the obligations' own
+            // binders, as trait matching does for its results.
+            let new_obligations = self.obligations.borrow_mut().split_off(obligations_before);
+            if !new_obligations.is_empty() {
+                let new_obligations =
+                    plug_leaks(self.infcx, skol_map.clone(), snapshot, &new_obligations);
+                debug!("higher_ranked_sub: obligations={:?}", new_obligations);
+                self.obligations.borrow_mut().extend(new_obligations);
+            }
+
+            // We are done with the skolemized regions now.
+            self.infcx.pop_skolemized(skol_map, snapshot);
+
             debug!("higher_ranked_sub: OK result={:?}",
                    result);
 
@@ -101,12 +158,86 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
         });
     }
 
+    fn higher_ranked_equate<T>(&self, a: &Binder<T>, b: &Binder<T>)
+                               -> RelateResult<'tcx, Binder<T>>
+        where T: Relate<'a,'tcx>
+    {
+        debug!("higher_ranked_equate(a={:?}, b={:?})",
+               a, b);
+
+        // `a == b` holds if `a <: b` and `b <: a`, but checking both
+        // directions skolemizes and leak-checks everything twice.
+        // Instead, we replace the bound regions of *both* sides with the
+        // same skolemized regions, matched up by position, and equate the
+        // results. The binders are equal exactly when no skolemized
+        // region ends up related to anything but itself, which a single
+        // leak check tells us.
+        return self.commit_if_ok(|snapshot| {
+            // Anonymize first, so that `for<'x> fn(&'x u8)` and
+            // `for<'y> fn(&'y u8)` share their skolemized region.
+            let a = self.tcx().anonymize_late_bound_regions(a);
+            let b = self.tcx().anonymize_late_bound_regions(b);
+            let (a_prime, b_prime, skol_map) =
+                skolemize_late_bound_regions_pair(self.infcx, &a, &b, snapshot);
+
+            debug!("a_prime={:?}", a_prime);
+            debug!("b_prime={:?}", b_prime);
+
+            let obligations_before = self.obligations.borrow().len();
+            let result = self.equate().relate(&a_prime, &b_prime)?;
+
+            match leak_check(self.infcx, &skol_map, snapshot) {
+                Ok(()) => { }
+                Err(Leak::Type(skol_bt, tainted_ty)) => {
+                    debug!("Not as polymorphic (type)!");
+                    return Err(TypeError::TypesInsufficientlyPolymorphic(skol_bt,
+                                                                         tainted_ty));
+                }
+                Err(Leak::Region(skol_br, tainted_region)) => {
+                    if self.a_is_expected {
+                        debug!("Not as polymorphic!");
+                        return Err(TypeError::RegionsInsufficientlyPolymorphic(skol_br,
+                                                                               tainted_region));
+                    } else {
+                        debug!("Overly polymorphic!");
+                        return Err(TypeError::RegionsOverlyPolymorphic(skol_br,
+                                                                       tainted_region));
+                    }
+                }
+            }
+
+            // As in `higher_ranked_sub`.
+            let new_obligations = self.obligations.borrow_mut().split_off(obligations_before);
+            if !new_obligations.is_empty() {
+                let new_obligations =
+                    plug_leaks(self.infcx, skol_map.clone(), snapshot, &new_obligations);
+                debug!("higher_ranked_equate: obligations={:?}", new_obligations);
+                self.obligations.borrow_mut().extend(new_obligations);
+            }
+
+            self.infcx.pop_skolemized(skol_map, snapshot);
+
+            debug!("higher_ranked_equate: OK result={:?}",
+                   result);
+
+            Ok(ty::Binder(result))
+        });
+    }
+
     fn higher_ranked_lub<T>(&self, a: &Binder<T>, b: &Binder<T>) -> RelateResult<'tcx, Binder<T>>
         where T: Relate<'a,'tcx>
     {
+        // The LUB is found by generalizing the regions in the result
+        // below, which cannot see the regions a type variable will
+        // turn out to contain.
+        if has_unresolved_ty_vars(self.infcx, a) || has_unresolved_ty_vars(self.infcx, b) {
+            debug!("higher_ranked_lub: ambiguous");
+            return Err(TypeError::Ambiguous);
+        }
+
         // Start a snapshot so we can examine "all bindings that were
         // created as part of this type comparison".
-        return self.infcx.commit_if_ok(|snapshot| {
+        return self.commit_if_ok(|snapshot| {
             // Instantiate each bound region with a fresh region variable.
             let span = self.trace.origin.span();
             let (a_with_fresh, a_map) =
@@ -124,14 +255,16 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
             debug!("lub result0 = {:?}", result0);
 
             // Generalize the regions appearing in result0 if possible
-            let new_vars = self.infcx.region_vars_confined_to_snapshot(snapshot);
+            let mut scratch = self.infcx.region_scratch.borrow_mut();
+            self.infcx.region_vars_confined_to_snapshot_in(snapshot, &mut scratch);
+            let new_vars = &scratch.region_vars;
             let span = self.trace.origin.span();
             let result1 =
                 fold_regions_in(
                     self.tcx(),
                     &result0,
                     |r, debruijn| generalize_region(self.infcx, span, snapshot, debruijn,
-                                                    &new_vars, &a_map, r));
+                                                    new_vars, &a_map, r));
 
             debug!("lub({:?},{:?}) = {:?}",
                    a,
@@ -156,7 +289,7 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
                 return r0;
             }
 
-            let tainted = infcx.tainted_regions(snapshot, r0);
+            let tainted = infcx.tainted_regions(snapshot, r0, TaintDirections::both());
 
             // Variables created during LUB computation which are
             // *related* to regions that pre-date the LUB computation
@@ -196,9 +329,16 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
         debug!("higher_ranked_glb({:?}, {:?})",
                a, b);
 
+        // As for the LUB, regions hidden in type variables cannot be
+        // generalized.
+        if has_unresolved_ty_vars(self.infcx, a) || has_unresolved_ty_vars(self.infcx, b) {
+            debug!("higher_ranked_glb: ambiguous");
+            return Err(TypeError::Ambiguous);
+        }
+
         // Make a snapshot so we can examine "all bindings that were
         // created as part of this type comparison".
-        return self.infcx.commit_if_ok(|snapshot| {
+        return self.commit_if_ok(|snapshot| {
             // Instantiate each bound region with a fresh region variable.
             let (a_with_fresh, a_map) =
                 self.infcx.replace_late_bound_regions_with_fresh_var(
@@ -217,14 +357,16 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
             debug!("glb result0 = {:?}", result0);
 
             // Generalize the regions appearing in result0 if possible
-            let new_vars = self.infcx.region_vars_confined_to_snapshot(snapshot);
+            let mut scratch = self.infcx.region_scratch.borrow_mut();
+            self.infcx.region_vars_confined_to_snapshot_in(snapshot, &mut scratch);
+            let new_vars = &scratch.region_vars;
             let span = self.trace.origin.span();
             let result1 =
                 fold_regions_in(
                     self.tcx(),
                     &result0,
                     |r, debruijn| generalize_region(self.infcx, span, snapshot, debruijn,
-                                                    &new_vars,
+                                                    new_vars,
                                                     &a_map, &a_vars, &b_vars,
                                                     r));
 
@@ -250,7 +392,7 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
                 return r0;
             }
 
-            let tainted = infcx.tainted_regions(snapshot, r0);
+            let tainted = infcx.tainted_regions(snapshot, r0, TaintDirections::both());
 
             let mut a_r = None;
             let mut b_r = None;
@@ -328,6 +470,27 @@ impl<'a,'tcx> HigherRankedRelations<'a,'tcx> for CombineFields<'a,'tcx> {
     }
 }
 
+/// True if `value` still mentions a type variable after resolving as
+/// many as possible. Integral and float variables do not count, as they
+/// never stand for types containing regions.
+fn has_unresolved_ty_vars<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>, value: &T) -> bool
+    where T: TypeFoldable<'tcx>
+{
+    struct TyVarFinder;
+
+    impl<'tcx> TypeVisitor<'tcx> for TyVarFinder {
+        fn visit_ty(&mut self, t: Ty<'tcx>) -> bool {
+            match t.sty {
+                ty::TyInfer(ty::TyVar(_)) => true,
+                _ => t.has_infer_types() && t.super_visit_with(self),
+            }
+        }
+    }
+
+    value.has_infer_types() &&
+        infcx.resolve_type_vars_if_possible(value).visit_with(&mut TyVarFinder)
+}
+
 fn var_ids<'a, 'tcx>(fields: &CombineFields<'a, 'tcx>,
                       map: &FnvHashMap<ty::BoundRegion, ty::Region>)
                      -> Vec<ty::RegionVid> {
@@ -372,13 +535,29 @@ fn fold_regions_in<'tcx, T, F>(tcx: &TyCtxt<'tcx>,
 }
 
 impl<'a,'tcx> InferCtxtExt for InferCtxt<'a,'tcx> {
-    fn tainted_regions(&self, snapshot: &CombinedSnapshot, r: ty::Region) -> Vec<ty::Region> {
-        self.region_vars.tainted(&snapshot.region_vars_snapshot, r)
+    fn tainted_regions(&self,
+                       snapshot: &CombinedSnapshot,
+                       r: ty::Region,
+                       directions: TaintDirections)
+                       -> Vec<ty::Region> {
+        let inner = self.inner.borrow();
+        self.region_vars.tainted(inner.undo_log.actions_since_snapshot(&snapshot.undo_snapshot),
+                                 r,
+                                 directions)
     }
 
     fn region_vars_confined_to_snapshot(&self,
                                         snapshot: &CombinedSnapshot)
                                         -> Vec<ty::RegionVid>
+    {
+        let mut scratch = ConfinedRegionsScratch::default();
+        self.region_vars_confined_to_snapshot_in(snapshot, &mut scratch);
+        scratch.region_vars
+    }
+
+    fn region_vars_confined_to_snapshot_in(&self,
+                                           snapshot: &CombinedSnapshot,
+                                           scratch: &mut ConfinedRegionsScratch)
     {
         /*!
          * Returns the set of region variables that do not affect any
@@ -428,17 +607,24 @@ impl<'a,'tcx> InferCtxtExt for InferCtxt<'a,'tcx> {
          * variable created during the snapshot but which finds its
          * way into a type variable is considered to "escape" the
          * snapshot.
+         *
+         * The result is left in `scratch.region_vars`; the previous
+         * contents of `scratch` are discarded.
          */
 
-        let mut region_vars =
-            self.region_vars.vars_created_since_snapshot(&snapshot.region_vars_snapshot);
+        let ConfinedRegionsScratch { ref mut region_vars, ref mut escaping_region_vars } =
+            *scratch;
 
-        let escaping_types =
-            self.type_variables.borrow_mut().types_escaping_snapshot(&snapshot.type_snapshot);
+        let inner = self.inner.borrow();
+        let actions_since_snapshot =
+            inner.undo_log.actions_since_snapshot(&snapshot.undo_snapshot);
 
-        let mut escaping_region_vars = FnvHashSet();
-        for ty in &escaping_types {
-            self.tcx.collect_regions(ty, &mut escaping_region_vars);
+        region_vars.clear();
+        region_vars.extend(self.region_vars.vars_created_since(actions_since_snapshot));
+
+        escaping_region_vars.clear();
+        for ty in inner.type_variables.types_escaping_snapshot(&snapshot.type_snapshot) {
+            self.tcx.collect_regions(ty, escaping_region_vars);
         }
 
         region_vars.retain(|&region_vid| {
@@ -446,18 +632,16 @@ impl<'a,'tcx> InferCtxtExt for InferCtxt<'a,'tcx> {
             !escaping_region_vars.contains(&r)
         });
 
-        debug!("region_vars_confined_to_snapshot: region_vars={:?} escaping_types={:?}",
+        debug!("region_vars_confined_to_snapshot: region_vars={:?} escaping_region_vars={:?}",
                region_vars,
-               escaping_types);
-
-        region_vars
+               escaping_region_vars);
     }
 }
 
 pub fn skolemize_late_bound_regions<'a,'tcx,T>(infcx: &InferCtxt<'a,'tcx>,
                                                binder: &ty::Binder<T>,
                                                snapshot: &CombinedSnapshot)
-                                               -> (T, SkolemizationMap)
+                                               -> (T, SkolemizationMap<'tcx>)
     where T : TypeFoldable<'tcx>
 {
     /*!
@@ -466,11 +650,17 @@ pub fn skolemize_late_bound_regions<'a,'tcx,T>(infcx: &InferCtxt<'a,'tcx>,
      * skolemized region. This is the first step of checking subtyping
      * when higher-ranked things are involved. See `README.md` for more
      * details.
+     *
+     * Types bound by `binder` (`TyBound`) are likewise replaced with
+     * fresh skolemized types.
      */
 
-    let (result, map) = infcx.tcx.replace_late_bound_regions(binder, |br| {
-        infcx.region_vars.new_skolemized(br, &snapshot.region_vars_snapshot)
-    });
+    let (result, regions, types) = infcx.tcx.replace_late_bound_regions_and_types(
+        binder,
+        |br| infcx.region_vars.new_skolemized(br, &snapshot.region_vars_snapshot),
+        |_| infcx.next_skolemized_ty());
+
+    let map = SkolemizationMap { regions: regions, types: types };
 
     debug!("skolemize_bound_regions(binder={:?}, result={:?}, map={:?})",
            binder,
@@ -480,10 +670,54 @@ pub fn skolemize_late_bound_regions<'a,'tcx,T>(infcx: &InferCtxt<'a,'tcx>,
     (result, map)
 }
 
+/// Like `skolemize_late_bound_regions`, but skolemizes two binders at
+/// once: a bound region (or type) that appears in both `a` and `b` is
+/// replaced with the same skolemized region (or type) on both sides.
+/// Bound types are matched up by their index alone.
+fn skolemize_late_bound_regions_pair<'a,'tcx,T>(infcx: &InferCtxt<'a,'tcx>,
+                                                a: &ty::Binder<T>,
+                                                b: &ty::Binder<T>,
+                                                snapshot: &CombinedSnapshot)
+                                                -> (T, T, SkolemizationMap<'tcx>)
+    where T : TypeFoldable<'tcx>
+{
+    let mut regions = FnvHashMap();
+    let mut types = FnvHashMap();
+    let (a_prime, b_prime) = {
+        let mut fld_r = |br: ty::BoundRegion| {
+            *regions.entry(br).or_insert_with(|| {
+                infcx.region_vars.new_skolemized(br, &snapshot.region_vars_snapshot)
+            })
+        };
+        let mut type_indices = FnvHashMap();
+        let mut fld_t = |bt: ty::BoundTy| {
+            let skol = *type_indices.entry(bt.index).or_insert_with(|| {
+                infcx.next_skolemized_ty()
+            });
+            types.insert(bt, skol);
+            skol
+        };
+        let a_prime = infcx.tcx.replace_late_bound_regions_and_types(a, &mut fld_r,
+                                                                      &mut fld_t).0;
+        let b_prime = infcx.tcx.replace_late_bound_regions_and_types(b, &mut fld_r,
+                                                                      &mut fld_t).0;
+        (a_prime, b_prime)
+    };
+
+    let map = SkolemizationMap { regions: regions, types: types };
+
+    debug!("skolemize_late_bound_regions_pair(a={:?}, b={:?}, map={:?})",
+           a,
+           b,
+           map);
+
+    (a_prime, b_prime, map)
+}
+
 pub fn leak_check<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
-                           skol_map: &SkolemizationMap,
+                           skol_map: &SkolemizationMap<'tcx>,
                            snapshot: &CombinedSnapshot)
-                           -> Result<(),(ty::BoundRegion,ty::Region)>
+                           -> Result<(), Leak<'tcx>>
 {
     /*!
      * Searches the region constriants created since `snapshot` was started
@@ -491,15 +725,29 @@ pub fn leak_check<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
      * in `skol_map` would "escape" -- meaning that they are related to
      * other regions in some way. If so, the higher-ranked subtyping doesn't
      * hold. See `README.md` for more details.
+     *
+     * Skolemized types escape if a type variable created before
+     * `snapshot` was unified with a type that mentions them.
      */
 
     debug!("leak_check: skol_map={:?}",
            skol_map);
 
+    leak_check_types(infcx, skol_map, snapshot)?;
+
     let new_vars = infcx.region_vars_confined_to_snapshot(snapshot);
-    for (&skol_br, &skol) in skol_map {
-        let tainted = infcx.tainted_regions(snapshot, skol);
-        for &tainted_region in &tainted {
+    for (&skol_br, &skol) in &skol_map.regions {
+        // Regions that the skolemized region must outlive
+        // (`r <= skol`) and regions that must outlive it (`skol <=
+        // r`) are examined separately: `'empty <= skol` and `skol <=
+        // 'static` hold whatever `skol` stands for, so they are not
+        // leaks.
+        let incoming = infcx.tainted_regions(snapshot, skol, TaintDirections::incoming());
+        let outgoing = infcx.tainted_regions(snapshot, skol, TaintDirections::outgoing());
+        let candidates =
+            incoming.iter().map(|&r| (r, ty::ReEmpty))
+                    .chain(outgoing.iter().map(|&r| (r, ty::ReStatic)));
+        for (tainted_region, trivial_region) in candidates {
             // Each skolemized should only be relatable to itself
             // or new variables:
             match tainted_region {
@@ -508,6 +756,7 @@ pub fn leak_check<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
                 }
                 _ => {
                     if tainted_region == skol { continue; }
+                    if tainted_region == trivial_region { continue; }
                 }
             };
 
@@ -517,7 +766,32 @@ pub fn leak_check<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
                    tainted_region);
 
             // A is not as polymorphic as B:
-            return Err((skol_br, tainted_region));
+            return Err(Leak::Region(skol_br, tainted_region));
+        }
+    }
+    Ok(())
+}
+
+fn leak_check_types<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
+                             skol_map: &SkolemizationMap<'tcx>,
+                             snapshot: &CombinedSnapshot)
+                             -> Result<(), Leak<'tcx>>
+{
+    if skol_map.types.is_empty() {
+        return Ok(());
+    }
+
+    let escaping_types = infcx.types_escaping_snapshot(snapshot);
+    for &escaping_ty in &escaping_types {
+        let escaping_ty = infcx.resolve_type_vars_if_possible(&escaping_ty);
+        for (&skol_bt, &skol) in &skol_map.types {
+            if escaping_ty.walk().any(|t| t == skol) {
+                debug!("{:?} (which replaced {:?}) escapes via {:?}",
+                       skol,
+                       skol_bt,
+                       escaping_ty);
+                return Err(Leak::Type(skol_bt, escaping_ty));
+            }
         }
     }
     Ok(())
@@ -552,7 +826,7 @@ pub fn leak_check<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
 /// to the depth of the predicate, in this case 1, so that the final
 /// predicate is `for<'a> &'a int : Clone`.
 pub fn plug_leaks<'a,'tcx,T>(infcx: &InferCtxt<'a,'tcx>,
-                             skol_map: SkolemizationMap,
+                             skol_map: SkolemizationMap<'tcx>,
                              snapshot: &CombinedSnapshot,
                              value: &T)
                              -> T
@@ -569,10 +843,10 @@ pub fn plug_leaks<'a,'tcx,T>(infcx: &InferCtxt<'a,'tcx>,
     // represented. Because `leak_check` passed, we know that
     // these taint sets are mutually disjoint.
     let inv_skol_map: FnvHashMap<ty::Region, ty::BoundRegion> =
-        skol_map
+        skol_map.regions
         .into_iter()
         .flat_map(|(skol_br, skol)| {
-            infcx.tainted_regions(snapshot, skol)
+            infcx.tainted_regions(snapshot, skol, TaintDirections::both())
                 .into_iter()
                 .map(move |tainted_region| (tainted_region, skol_br))
         })
@@ -607,8 +881,77 @@ pub fn plug_leaks<'a,'tcx,T>(infcx: &InferCtxt<'a,'tcx>,
         }
     });
 
+    // Skolemized types are mapped back to bound types in the same way.
+    // Because `leak_check` passed, no type variable from outside the
+    // snapshot refers to them, so only `value` itself needs rewriting.
+    let result = if skol_map.types.is_empty() {
+        result
+    } else {
+        let mut plugger = SkolemizedTypePlugger {
+            tcx: infcx.tcx,
+            current_depth: 1,
+            inv_skol_map: skol_map.types.into_iter().map(|(bt, skol)| (skol, bt)).collect(),
+        };
+        result.fold_with(&mut plugger)
+    };
+
     debug!("plug_leaks: result={:?}",
            result);
 
     result
 }
+
+struct SkolemizedTypePlugger<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+    current_depth: u32,
+    inv_skol_map: FnvHashMap<Ty<'tcx>, ty::BoundTy>,
+}
+
+impl<'a, 'tcx> TypeFolder<'tcx> for SkolemizedTypePlugger<'a, 'tcx> {
+    fn tcx(&self) -> &TyCtxt<'tcx> { self.tcx }
+
+    fn fold_binder<T: TypeFoldable<'tcx>>(&mut self, t: &ty::Binder<T>) -> ty::Binder<T> {
+        self.current_depth += 1;
+        let t = t.super_fold_with(self);
+        self.current_depth -= 1;
+        t
+    }
+
+    fn fold_ty(&mut self, t: Ty<'tcx>) -> Ty<'tcx> {
+        if !t.has_infer_types() {
+            return t;
+        }
+
+        match self.inv_skol_map.get(&t) {
+            None => t.super_fold_with(self),
+            Some(&bt) => {
+                // As for regions above, the caller ensures that there
+                // is a binder to put the bound type in.
+                assert!(self.current_depth > 1);
+                let debruijn = ty::DebruijnIndex::new(self.current_depth - 1);
+                self.tcx.mk_ty(ty::TyBound(debruijn, bt))
+            }
+        }
+    }
+}
+
+/// Discards the skolemized regions in `skol_map`, along with any
+/// constraints that mention them directly, without rolling back the
+/// rest of `snapshot`. This is meant to be called once a higher-ranked
+/// computation has succeeded (i.e., after `leak_check` passed and, if
+/// applicable, `plug_leaks` has been used), at which point the
+/// skolemized regions are dead weight in the constraint set. The
+/// regions in `skol_map` must be the most recently created skolemized
+/// regions in `snapshot`.
+pub fn pop_skolemized<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
+                               skol_map: SkolemizationMap<'tcx>,
+                               snapshot: &CombinedSnapshot)
+{
+    debug!("pop_skolemized({:?})", skol_map);
+    let skol_regions: FnvHashSet<_> = skol_map.regions.values().cloned().collect();
+    let mut inner = infcx.inner.borrow_mut();
+    let actions_since_snapshot = inner.undo_log.actions_since_snapshot_mut(&snapshot.undo_snapshot);
+    infcx.region_vars.pop_skolemized(actions_since_snapshot,
+                                     &skol_regions,
+                                     &snapshot.region_vars_snapshot);
+}
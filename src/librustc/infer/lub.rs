@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::combine::CombineFields;
+use super::combine::{self, CombineFields};
 use super::higher_ranked::HigherRankedRelations;
 use super::InferCtxt;
 use super::lattice::{self, LatticeDir};
@@ -17,6 +17,7 @@ use super::Subtype;
 use ty::{self, Ty, TyCtxt};
 use ty::relate::{Relate, RelateResult, TypeRelation};
 use traits::PredicateObligations;
+use syntax::codemap::Span;
 
 /// "Least upper bound" (common supertype)
 pub struct Lub<'a, 'tcx: 'a> {
@@ -29,7 +30,7 @@ impl<'a, 'tcx> Lub<'a, 'tcx> {
     }
 
     pub fn obligations(self) -> PredicateObligations<'tcx> {
-        self.fields.obligations
+        self.fields.into_obligations()
     }
 }
 
@@ -65,7 +66,12 @@ impl<'a, 'tcx> TypeRelation<'a, 'tcx> for Lub<'a, 'tcx> {
                b);
 
         let origin = Subtype(self.fields.trace.clone());
-        Ok(self.fields.infcx.region_vars.lub_regions(origin, a, b))
+        let infcx = self.fields.infcx;
+        Ok(infcx.region_vars.lub_regions(&mut infcx.inner.borrow_mut().undo_log, origin, a, b))
+    }
+
+    fn consts(&mut self, a: &ty::Const, b: &ty::Const) -> RelateResult<'tcx, ty::Const> {
+        combine::super_combine_consts(self.fields.infcx, self, a, b)
     }
 
     fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
@@ -81,6 +87,10 @@ impl<'a, 'tcx> LatticeDir<'a,'tcx> for Lub<'a, 'tcx> {
         self.fields.infcx
     }
 
+    fn span(&self) -> Span {
+        self.fields.trace.origin.span()
+    }
+
     fn relate_bound(&self, v: Ty<'tcx>, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, ()> {
         let mut sub = self.fields.sub();
         sub.relate(&a, &v)?;
@@ -16,7 +16,7 @@ pub use self::SubregionOrigin::*;
 pub use self::ValuePairs::*;
 pub use ty::IntVarValue;
 pub use self::freshen::TypeFreshener;
-pub use self::region_inference::{GenericKind, VerifyBound};
+pub use self::region_inference::{GenericKind, RegionConstraintData, VerifyBound};
 
 use hir::def_id::DefId;
 use hir;
@@ -34,24 +34,32 @@ use ty::error::{ExpectedFound, TypeError, UnconstrainedNumeric};
 use ty::fold::{TypeFolder, TypeFoldable};
 use ty::relate::{Relate, RelateResult, TypeRelation};
 use traits::{self, PredicateObligations, ProjectionMode};
-use rustc_data_structures::unify::{self, UnificationTable};
-use std::cell::{RefCell, Ref};
+use rustc_data_structures::snapshot_vec::{Rollback, UndoLogs};
+use rustc_data_structures::unify as ut;
+use std::cell::{Cell, RefCell, Ref};
 use std::fmt;
+use std::rc::Rc;
 use syntax::ast;
 use syntax::codemap;
-use syntax::codemap::{Span, DUMMY_SP};
+use syntax::codemap::Span;
 use syntax::errors::DiagnosticBuilder;
 use util::nodemap::{FnvHashMap, FnvHashSet, NodeMap};
 
 use self::combine::CombineFields;
+use self::higher_ranked::ConfinedRegionsScratch;
 use self::region_inference::{RegionVarBindings, RegionSnapshot};
 use self::error_reporting::ErrorReporting;
+use self::type_variable::TypeVariableOrigin;
+use self::undo_log::{InferCtxtUndoLogs, TouchedVars, UndoLog};
 use self::unify_key::ToType;
 
 pub mod bivariate;
+pub mod canonical;
 pub mod combine;
+pub mod const_variable;
 pub mod equate;
 pub mod error_reporting;
+mod fudge;
 pub mod glb;
 mod higher_ranked;
 pub mod lattice;
@@ -61,6 +69,7 @@ pub mod resolve;
 mod freshen;
 pub mod sub;
 pub mod type_variable;
+mod undo_log;
 pub mod unify_key;
 
 pub struct InferOk<'tcx, T> {
@@ -78,22 +87,26 @@ pub struct InferCtxt<'a, 'tcx: 'a> {
 
     pub tables: &'a RefCell<ty::Tables<'tcx>>,
 
-    // We instantiate UnificationTable with bounds<Ty> because the
-    // types that might instantiate a general type variable have an
-    // order, represented by its upper and lower bounds.
-    type_variables: RefCell<type_variable::TypeVariableTable<'tcx>>,
-
-    // Map from integral variable to the kind of integer it represents
-    int_unification_table: RefCell<UnificationTable<ty::IntVid>>,
+    // The type, integral and floating variables, together with the
+    // undo log shared by all the tables of this context.
+    inner: RefCell<InferCtxtInner<'tcx>>,
 
-    // Map from floating variable to the kind of float it represents
-    float_unification_table: RefCell<UnificationTable<ty::FloatVid>>,
-
-    // For region variables.
+    // For region variables. These record their changes into the undo
+    // log in `inner` as well.
     region_vars: RegionVarBindings<'a, 'tcx>,
 
+    // Cache for projections. This cache is snapshotted along with the
+    // inference tables above.
+    pub projection_cache: RefCell<traits::ProjectionCache<'tcx>>,
+
     pub parameter_environment: ty::ParameterEnvironment<'a, 'tcx>,
 
+    // Cache for `ty::wf::obligations`, holding the obligations of types
+    // without inference variables. Those only depend on the type and on
+    // `parameter_environment`, so they are valid for the whole lifetime
+    // of the context, snapshots or not.
+    pub wf_cache: RefCell<FnvHashMap<Ty<'tcx>, Vec<traits::PredicateObligation<'tcx>>>>,
+
     // the set of predicates on which errors have been reported, to
     // avoid reporting the same error twice.
     pub reported_trait_errors: RefCell<FnvHashSet<traits::TraitErrorKey<'tcx>>>,
@@ -111,11 +124,134 @@ pub struct InferCtxt<'a, 'tcx: 'a> {
     projection_mode: ProjectionMode,
 
     err_count_on_creation: usize,
+
+    // Number of skolemized types (`SkolemizedTy`) created so far; used
+    // to give each one a distinct index.
+    skolemized_ty_count: Cell<u32>,
+
+    // A set of `T: 'a` constraints that have been registered during
+    // inference but not yet decomposed into region constraints, each
+    // tagged with the id of the innermost enclosing fn body / static
+    // initializer. We can't decompose these right away because the
+    // types involved may still contain inference variables, so they
+    // are held here until regionck calls
+    // `take_registered_region_obligations`. See also the comment on
+    // `ObligationCause::body_id`.
+    region_obligations: RefCell<Vec<(ast::NodeId, traits::RegionObligation<'tcx>)>>,
+
+    // Buffers reused by every higher-ranked LUB and GLB computed in this
+    // context; see `ConfinedRegionsScratch`.
+    region_scratch: RefCell<ConfinedRegionsScratch>,
+
+    // What unconstrained numeric and diverging variables default to.
+    // See `fallback_if_possible`.
+    fallback_policy: Cell<&'a (FallbackPolicy<'tcx> + 'a)>,
+
+    // Whether type relations may relate a projection that has not been
+    // normalized to another type by returning a projection obligation
+    // instead of an error. Only callers that register the obligations
+    // returned in `InferOk` set this; see
+    // `deferring_projection_equality`.
+    defer_projection_equality: Cell<bool>,
+}
+
+/// The tables of an inference context that have to be borrowed
+/// together with the undo log they record their changes into.
+struct InferCtxtInner<'tcx> {
+    // Every change made to the tables of the inference context while
+    // a snapshot is open, so that it can be undone.
+    undo_log: InferCtxtUndoLogs<'tcx>,
+
+    // We instantiate UnificationTable with bounds<Ty> because the
+    // types that might instantiate a general type variable have an
+    // order, represented by its upper and lower bounds.
+    type_variables: type_variable::TypeVariableStorage<'tcx>,
+
+    // Map from integral variable to the kind of integer it represents
+    int_unification_table: ut::UnificationStorage<ty::IntVid>,
+
+    // Map from floating variable to the kind of float it represents
+    float_unification_table: ut::UnificationStorage<ty::FloatVid>,
+
+    // Map from constant variable to the constant it was unified with
+    const_variables: const_variable::ConstVariableStorage,
+}
+
+type UnificationTable<'a, 'tcx, K> =
+    ut::UnificationTable<K, &'a mut Vec<ut::VarValue<K>>, &'a mut InferCtxtUndoLogs<'tcx>>;
+
+impl<'tcx> InferCtxtInner<'tcx> {
+    fn new() -> InferCtxtInner<'tcx> {
+        InferCtxtInner {
+            undo_log: InferCtxtUndoLogs::new(),
+            type_variables: type_variable::TypeVariableStorage::new(),
+            int_unification_table: ut::UnificationTable::new(),
+            float_unification_table: ut::UnificationTable::new(),
+            const_variables: const_variable::ConstVariableStorage::new(),
+        }
+    }
+
+    fn type_variables<'a>(&'a mut self) -> type_variable::TypeVariableTable<'a, 'tcx> {
+        self.type_variables.with_log(&mut self.undo_log)
+    }
+
+    fn int_unification_table<'a>(&'a mut self) -> UnificationTable<'a, 'tcx, ty::IntVid> {
+        self.int_unification_table.with_log(&mut self.undo_log)
+    }
+
+    fn float_unification_table<'a>(&'a mut self) -> UnificationTable<'a, 'tcx, ty::FloatVid> {
+        self.float_unification_table.with_log(&mut self.undo_log)
+    }
+
+    fn const_variables<'a>(&'a mut self) -> const_variable::ConstVariableTable<'a, 'tcx> {
+        self.const_variables.with_log(&mut self.undo_log)
+    }
+}
+
+/// Decides what inference variables that nothing else constrained fall
+/// back to once type checking of a body is otherwise done. Only integer,
+/// floating-point and diverging variables have a fallback; ordinary type
+/// variables are left alone (or given their declared default, see
+/// `InferCtxt::default`).
+pub trait FallbackPolicy<'tcx> {
+    /// The fallback of an integer variable, such as the type of `1`.
+    fn int_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx>;
+
+    /// The fallback of a floating-point variable, such as the type of `1.0`.
+    fn float_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx>;
+
+    /// The fallback of a diverging type variable, such as the type of
+    /// `return` or `panic!()` in expression position.
+    fn diverging_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx>;
 }
 
+/// The language's fallback: `i32`, `f64` and `()`.
+pub struct DefaultFallback;
+
+impl<'tcx> FallbackPolicy<'tcx> for DefaultFallback {
+    fn int_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.types.i32
+    }
+
+    fn float_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.types.f64
+    }
+
+    fn diverging_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.mk_nil()
+    }
+}
+
+static DEFAULT_FALLBACK: DefaultFallback = DefaultFallback;
+
 /// A map returned by `skolemize_late_bound_regions()` indicating the skolemized
-/// region that each late-bound region was replaced with.
-pub type SkolemizationMap = FnvHashMap<ty::BoundRegion,ty::Region>;
+/// region that each late-bound region, and the skolemized type that each
+/// bound type, was replaced with.
+#[derive(Clone, Debug)]
+pub struct SkolemizationMap<'tcx> {
+    pub regions: FnvHashMap<ty::BoundRegion, ty::Region>,
+    pub types: FnvHashMap<ty::BoundTy, Ty<'tcx>>,
+}
 
 /// Why did we require that the two types be related?
 ///
@@ -371,15 +507,20 @@ pub fn new_infer_ctxt<'a, 'tcx>(tcx: &'a TyCtxt<'tcx>,
     InferCtxt {
         tcx: tcx,
         tables: tables,
-        type_variables: RefCell::new(type_variable::TypeVariableTable::new()),
-        int_unification_table: RefCell::new(UnificationTable::new()),
-        float_unification_table: RefCell::new(UnificationTable::new()),
+        inner: RefCell::new(InferCtxtInner::new()),
         region_vars: RegionVarBindings::new(tcx),
+        projection_cache: RefCell::new(traits::ProjectionCache::new()),
         parameter_environment: param_env.unwrap_or(tcx.empty_parameter_environment()),
+        wf_cache: RefCell::new(FnvHashMap()),
         reported_trait_errors: RefCell::new(FnvHashSet()),
         normalize: false,
         projection_mode: projection_mode,
-        err_count_on_creation: tcx.sess.err_count()
+        err_count_on_creation: tcx.sess.err_count(),
+        skolemized_ty_count: Cell::new(0),
+        region_obligations: RefCell::new(vec![]),
+        region_scratch: RefCell::new(ConfinedRegionsScratch::default()),
+        fallback_policy: Cell::new(&DEFAULT_FALLBACK),
+        defer_projection_equality: Cell::new(false),
     }
 }
 
@@ -403,33 +544,12 @@ pub fn mk_subty<'a, 'tcx>(cx: &InferCtxt<'a, 'tcx>,
     cx.sub_types(a_is_expected, origin, a, b)
 }
 
-pub fn can_mk_subty<'a, 'tcx>(cx: &InferCtxt<'a, 'tcx>, a: Ty<'tcx>, b: Ty<'tcx>)
-    -> UnitResult<'tcx>
-{
-    debug!("can_mk_subty({:?} <: {:?})", a, b);
-    cx.probe(|_| {
-        let trace = TypeTrace {
-            origin: TypeOrigin::Misc(codemap::DUMMY_SP),
-            values: Types(expected_found(true, a, b))
-        };
-        cx.sub(true, trace, &a, &b).map(|_| ())
-    })
-}
-
-pub fn can_mk_eqty<'a, 'tcx>(cx: &InferCtxt<'a, 'tcx>, a: Ty<'tcx>, b: Ty<'tcx>)
-    -> UnitResult<'tcx>
-{
-    cx.can_equate(&a, &b)
-}
-
 pub fn mk_subr<'a, 'tcx>(cx: &InferCtxt<'a, 'tcx>,
                          origin: SubregionOrigin<'tcx>,
                          a: ty::Region,
                          b: ty::Region) {
     debug!("mk_subr({:?} <: {:?})", a, b);
-    let snapshot = cx.region_vars.start_snapshot();
-    cx.region_vars.make_subregion(origin, a, b);
-    cx.region_vars.commit(snapshot);
+    cx.region_vars.make_subregion(&mut cx.inner.borrow_mut().undo_log, origin, a, b);
 }
 
 pub fn mk_eqty<'a, 'tcx>(cx: &InferCtxt<'a, 'tcx>,
@@ -494,9 +614,9 @@ fn expected_found<T>(a_is_expected: bool,
 
 #[must_use = "once you start a snapshot, you should always consume it"]
 pub struct CombinedSnapshot {
+    projection_cache_snapshot: traits::ProjectionCacheSnapshot,
+    undo_snapshot: undo_log::Snapshot,
     type_snapshot: type_variable::Snapshot,
-    int_snapshot: unify::Snapshot<ty::IntVid>,
-    float_snapshot: unify::Snapshot<ty::FloatVid>,
     region_vars_snapshot: RegionSnapshot,
 }
 
@@ -512,23 +632,39 @@ pub fn normalize_associated_type<'tcx,T>(tcx: &TyCtxt<'tcx>, value: &T) -> T
         return value;
     }
 
-    let infcx = new_infer_ctxt(tcx, &tcx.tables, None, ProjectionMode::Any);
-    let mut selcx = traits::SelectionContext::new(&infcx);
-    let cause = traits::ObligationCause::dummy();
-    let traits::Normalized { value: result, obligations } =
-        traits::normalize(&mut selcx, cause, &value);
+    // Everything is monomorphic by now, so each projection can be
+    // normalized on its own, and the answer cached for the next time.
+    let param_env = tcx.empty_parameter_environment();
+    let result = value.fold_with(&mut ProjectionNormalizer { param_env: &param_env });
 
-    debug!("normalize_associated_type: result={:?} obligations={:?}",
-           result,
-           obligations);
+    debug!("normalize_associated_type: result={:?}", result);
+    result
+}
 
-    let mut fulfill_cx = traits::FulfillmentContext::new();
+struct ProjectionNormalizer<'a, 'b: 'a, 'tcx: 'b> {
+    param_env: &'a ty::ParameterEnvironment<'b, 'tcx>,
+}
 
-    for obligation in obligations {
-        fulfill_cx.register_predicate_obligation(&infcx, obligation);
+impl<'a, 'b, 'tcx> TypeFolder<'tcx> for ProjectionNormalizer<'a, 'b, 'tcx> {
+    fn tcx(&self) -> &TyCtxt<'tcx> {
+        self.param_env.tcx
     }
 
-    drain_fulfillment_cx_or_panic(DUMMY_SP, &infcx, &mut fulfill_cx, &result)
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        if !ty.has_projection_types() {
+            return ty;
+        }
+        let ty = ty.super_fold_with(self);
+        match ty.sty {
+            // Projections under a binder wait until the binder is
+            // instantiated, as in `traits::normalize`.
+            ty::TyProjection(projection_ty) if !ty.has_escaping_regions() => {
+                traits::normalize_projection_ty(self.param_env, projection_ty,
+                                                ProjectionMode::Any)
+            }
+            _ => ty,
+        }
+    }
 }
 
 pub fn drain_fulfillment_cx_or_panic<'a,'tcx,T>(span: Span,
@@ -596,7 +732,21 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
 
     pub fn type_var_diverges(&'a self, ty: Ty) -> bool {
         match ty.sty {
-            ty::TyInfer(ty::TyVar(vid)) => self.type_variables.borrow().var_diverges(vid),
+            ty::TyInfer(ty::TyVar(vid)) => {
+                self.inner.borrow_mut().type_variables().var_diverges(vid)
+            }
+            _ => false
+        }
+    }
+
+    /// True if `a` and `b` are unresolved type variables that have been
+    /// related to each other by subtyping or equality, possibly through
+    /// other variables.
+    pub fn type_vars_sub_unified(&self, a: Ty<'tcx>, b: Ty<'tcx>) -> bool {
+        match (&a.sty, &b.sty) {
+            (&ty::TyInfer(ty::TyVar(a_vid)), &ty::TyInfer(ty::TyVar(b_vid))) => {
+                self.inner.borrow_mut().type_variables().sub_unified(a_vid, b_vid)
+            }
             _ => false
         }
     }
@@ -610,14 +760,14 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         use ty::error::UnconstrainedNumeric::{UnconstrainedInt, UnconstrainedFloat};
         match ty.sty {
             ty::TyInfer(ty::IntVar(vid)) => {
-                if self.int_unification_table.borrow_mut().has_value(vid) {
+                if self.inner.borrow_mut().int_unification_table().has_value(vid) {
                     Neither
                 } else {
                     UnconstrainedInt
                 }
             },
             ty::TyInfer(ty::FloatVar(vid)) => {
-                if self.float_unification_table.borrow_mut().has_value(vid) {
+                if self.inner.borrow_mut().float_unification_table().has_value(vid) {
                     Neither
                 } else {
                     UnconstrainedFloat
@@ -627,6 +777,47 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         }
     }
 
+    /// Replaces the policy deciding what unconstrained numeric and
+    /// diverging variables fall back to; `DefaultFallback` is used
+    /// until this is called.
+    pub fn set_fallback_policy(&self, policy: &'a (FallbackPolicy<'tcx> + 'a)) {
+        self.fallback_policy.set(policy);
+    }
+
+    /// The type the unsolved inference variable `ty` falls back to under
+    /// the current policy, or `None` if it is not a variable that has a
+    /// fallback.
+    pub fn fallback_type(&self, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+        use ty::error::UnconstrainedNumeric::Neither;
+        use ty::error::UnconstrainedNumeric::{UnconstrainedInt, UnconstrainedFloat};
+
+        let policy = self.fallback_policy.get();
+        if self.type_var_diverges(ty) {
+            return Some(policy.diverging_fallback(self.tcx));
+        }
+        match self.type_is_unconstrained_numeric(ty) {
+            UnconstrainedInt => Some(policy.int_fallback(self.tcx)),
+            UnconstrainedFloat => Some(policy.float_fallback(self.tcx)),
+            Neither => None,
+        }
+    }
+
+    /// Unifies `ty`, if it is an unsolved variable with a fallback, with
+    /// that fallback. Returns true if a fallback was applied.
+    pub fn fallback_if_possible(&self, origin: TypeOrigin, ty: Ty<'tcx>) -> bool {
+        let resolved = self.resolve_type_vars_if_possible(&ty);
+        let fallback = match self.fallback_type(resolved) {
+            Some(fallback) => fallback,
+            None => return false,
+        };
+        match mk_eqty(self, false, origin, ty, fallback) {
+            // FIXME(#32730) propagate obligations
+            Ok(InferOk { obligations, .. }) => assert!(obligations.is_empty()),
+            Err(e) => self.report_mismatched_types(origin, fallback, ty, e),
+        }
+        true
+    }
+
     /// Returns a type variable's default fallback if any exists. A default
     /// must be attached to the variable when created, if it is created
     /// without a default, this will return None.
@@ -638,7 +829,9 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
     /// See `type_variable::Default` for details about what a default entails.
     pub fn default(&self, ty: Ty<'tcx>) -> Option<type_variable::Default<'tcx>> {
         match ty.sty {
-            ty::TyInfer(ty::TyVar(vid)) => self.type_variables.borrow().default(vid),
+            ty::TyInfer(ty::TyVar(vid)) => {
+                self.inner.borrow_mut().type_variables().default(vid)
+            }
             _ => None
         }
     }
@@ -646,20 +839,23 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
     pub fn unsolved_variables(&self) -> Vec<ty::Ty<'tcx>> {
         let mut variables = Vec::new();
 
-        let unbound_ty_vars = self.type_variables
+        let unbound_ty_vars = self.inner
                                   .borrow_mut()
+                                  .type_variables()
                                   .unsolved_variables()
                                   .into_iter()
                                   .map(|t| self.tcx.mk_var(t));
 
-        let unbound_int_vars = self.int_unification_table
+        let unbound_int_vars = self.inner
                                    .borrow_mut()
+                                   .int_unification_table()
                                    .unsolved_variables()
                                    .into_iter()
                                    .map(|v| self.tcx.mk_int_var(v));
 
-        let unbound_float_vars = self.float_unification_table
+        let unbound_float_vars = self.inner
                                      .borrow_mut()
+                                     .float_unification_table()
                                      .unsolved_variables()
                                      .into_iter()
                                      .map(|v| self.tcx.mk_float_var(v));
@@ -679,7 +875,7 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
             a_is_expected: a_is_expected,
             trace: trace,
             cause: None,
-            obligations: PredicateObligations::new(),
+            obligations: Rc::new(RefCell::new(PredicateObligations::new())),
         }
     }
 
@@ -720,52 +916,116 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
     }
 
     fn start_snapshot(&self) -> CombinedSnapshot {
+        let mut inner = self.inner.borrow_mut();
         CombinedSnapshot {
-            type_snapshot: self.type_variables.borrow_mut().snapshot(),
-            int_snapshot: self.int_unification_table.borrow_mut().snapshot(),
-            float_snapshot: self.float_unification_table.borrow_mut().snapshot(),
+            projection_cache_snapshot: self.projection_cache.borrow_mut().snapshot(),
+            undo_snapshot: inner.undo_log.start_snapshot(),
+            type_snapshot: inner.type_variables.start_snapshot(),
             region_vars_snapshot: self.region_vars.start_snapshot(),
         }
     }
 
     fn rollback_to(&self, cause: &str, snapshot: CombinedSnapshot) {
         debug!("rollback_to(cause={})", cause);
-        let CombinedSnapshot { type_snapshot,
-                               int_snapshot,
-                               float_snapshot,
+        let CombinedSnapshot { projection_cache_snapshot,
+                               undo_snapshot,
+                               type_snapshot,
                                region_vars_snapshot } = snapshot;
 
-        self.type_variables
-            .borrow_mut()
-            .rollback_to(type_snapshot);
-        self.int_unification_table
-            .borrow_mut()
-            .rollback_to(int_snapshot);
-        self.float_unification_table
+        self.projection_cache
             .borrow_mut()
-            .rollback_to(float_snapshot);
-        self.region_vars
-            .rollback_to(region_vars_snapshot);
+            .rollback_to(projection_cache_snapshot);
+
+        let mut inner = self.inner.borrow_mut();
+        while let Some(undo) = inner.undo_log.pop_since(&undo_snapshot) {
+            self.rollback_undo_entry(&mut inner, undo);
+        }
+        inner.undo_log.rollback_to(undo_snapshot);
+        inner.type_variables.end_snapshot(type_snapshot);
+        self.region_vars.end_snapshot(region_vars_snapshot);
     }
 
     fn commit_from(&self, snapshot: CombinedSnapshot) {
         debug!("commit_from!");
-        let CombinedSnapshot { type_snapshot,
-                               int_snapshot,
-                               float_snapshot,
+        let CombinedSnapshot { projection_cache_snapshot,
+                               undo_snapshot,
+                               type_snapshot,
                                region_vars_snapshot } = snapshot;
 
-        self.type_variables
-            .borrow_mut()
-            .commit(type_snapshot);
-        self.int_unification_table
+        self.projection_cache
             .borrow_mut()
-            .commit(int_snapshot);
-        self.float_unification_table
-            .borrow_mut()
-            .commit(float_snapshot);
-        self.region_vars
-            .commit(region_vars_snapshot);
+            .commit(projection_cache_snapshot);
+
+        let mut inner = self.inner.borrow_mut();
+        let touched = if self.tcx.sess.opts.debugging_opts.opportunistic_resolve {
+            Some(inner.undo_log.touched_since(&undo_snapshot))
+        } else {
+            None
+        };
+        inner.undo_log.commit(undo_snapshot);
+        inner.type_variables.end_snapshot(type_snapshot);
+        self.region_vars.end_snapshot(region_vars_snapshot);
+
+        if let Some(touched) = touched {
+            self.shorten_variable_chains(&mut inner, touched);
+        }
+    }
+
+    /// Shortens the chains `shallow_resolve` would otherwise have to
+    /// follow for the variables touched in a snapshot that was just
+    /// committed: their paths in the unification tables are compressed,
+    /// and a type variable instantiated with an integral or float
+    /// variable that has since been resolved is instantiated with the
+    /// result instead. If there is an enclosing snapshot, this is
+    /// recorded in it like any other change.
+    fn shorten_variable_chains(&self, inner: &mut InferCtxtInner<'tcx>, touched: TouchedVars) {
+        debug!("shorten_variable_chains: {} type, {} int, {} float variables",
+               touched.ty_vars.len(), touched.int_vars.len(), touched.float_vars.len());
+
+        for vid in touched.int_vars {
+            inner.int_unification_table().find(vid);
+        }
+        for vid in touched.float_vars {
+            inner.float_unification_table().find(vid);
+        }
+        for vid in touched.ty_vars {
+            let root = inner.type_variables().root_var(vid);
+            let ty = match inner.type_variables().probe(root) {
+                Some(ty) => ty,
+                None => continue,
+            };
+            let resolved = match ty.sty {
+                ty::TyInfer(ty::IntVar(v)) => {
+                    inner.int_unification_table()
+                         .probe(v)
+                         .map_or(ty, |v| v.to_type(self.tcx))
+                }
+                ty::TyInfer(ty::FloatVar(v)) => {
+                    inner.float_unification_table()
+                         .probe(v)
+                         .map_or(ty, |v| v.to_type(self.tcx))
+                }
+                _ => ty,
+            };
+            if resolved != ty {
+                inner.type_variables().replace_known(root, resolved);
+            }
+        }
+    }
+
+    /// Reverses `undo`, just popped off the undo log.
+    fn rollback_undo_entry(&self, inner: &mut InferCtxtInner<'tcx>, undo: UndoLog<'tcx>) {
+        match undo {
+            UndoLog::TypeVariables(undo) => inner.type_variables.reverse(undo),
+            UndoLog::IntUnificationTable(undo) => inner.int_unification_table.reverse(undo),
+            UndoLog::FloatUnificationTable(undo) => inner.float_unification_table.reverse(undo),
+            UndoLog::ConstVariables(undo) => inner.const_variables.reverse(undo),
+            UndoLog::RegionUnificationTable(undo) => self.region_vars.rollback_unification(undo),
+            UndoLog::RegionVars(undo) => self.region_vars.rollback_undo_entry(undo),
+            UndoLog::PushRegionObligation => {
+                self.region_obligations.borrow_mut().pop();
+            }
+        }
     }
 
     /// Execute `f` and commit the bindings
@@ -801,9 +1061,9 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         F: FnOnce() -> Result<T, E>
     {
         debug!("commit_regions_if_ok()");
-        let CombinedSnapshot { type_snapshot,
-                               int_snapshot,
-                               float_snapshot,
+        let CombinedSnapshot { projection_cache_snapshot,
+                               undo_snapshot,
+                               type_snapshot,
                                region_vars_snapshot } = self.start_snapshot();
 
         let r = self.commit_if_ok(|_| f());
@@ -812,23 +1072,46 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
 
         // Roll back any non-region bindings - they should be resolved
         // inside `f`, with, e.g. `resolve_type_vars_if_possible`.
-        self.type_variables
-            .borrow_mut()
-            .rollback_to(type_snapshot);
-        self.int_unification_table
+        self.projection_cache
             .borrow_mut()
-            .rollback_to(int_snapshot);
-        self.float_unification_table
-            .borrow_mut()
-            .rollback_to(float_snapshot);
+            .rollback_to(projection_cache_snapshot);
+        let mut inner = self.inner.borrow_mut();
+        let mut region_undos = vec![];
+        while let Some(undo) = inner.undo_log.pop_since(&undo_snapshot) {
+            if undo.is_region() {
+                region_undos.push(undo);
+            } else {
+                self.rollback_undo_entry(&mut inner, undo);
+            }
+        }
 
-        // Commit region vars that may escape through resolved types.
-        self.region_vars
-            .commit(region_vars_snapshot);
+        // Commit region vars that may escape through resolved types,
+        // putting their entries back so that an enclosing snapshot
+        // can still undo them.
+        for undo in region_undos.into_iter().rev() {
+            inner.undo_log.push(undo);
+        }
+        inner.undo_log.commit(undo_snapshot);
+        inner.type_variables.end_snapshot(type_snapshot);
+        self.region_vars.end_snapshot(region_vars_snapshot);
 
         r
     }
 
+    /// Runs `f`, letting the type relations it performs defer the
+    /// equality of a projection and another type as an obligation (see
+    /// `CombineFields::defer_projection_eq`). The caller must register
+    /// the obligations returned by those relations.
+    pub fn deferring_projection_equality<R, F>(&self, f: F) -> R where
+        F: FnOnce() -> R,
+    {
+        let old = self.defer_projection_equality.get();
+        self.defer_projection_equality.set(true);
+        let result = f();
+        self.defer_projection_equality.set(old);
+        result
+    }
+
     /// Execute `f` then unroll any bindings it creates
     pub fn probe<R, F>(&self, f: F) -> R where
         F: FnOnce(&CombinedSnapshot) -> R,
@@ -840,11 +1123,17 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         r
     }
 
+    /// The types with which the type variables that existed before
+    /// `snapshot` have been instantiated since it started.
+    pub fn types_escaping_snapshot(&self, snapshot: &CombinedSnapshot) -> Vec<Ty<'tcx>> {
+        self.inner.borrow().type_variables.types_escaping_snapshot(&snapshot.type_snapshot).to_vec()
+    }
+
     pub fn add_given(&self,
                      sub: ty::FreeRegion,
                      sup: ty::RegionVid)
     {
-        self.region_vars.add_given(sub, sup);
+        self.region_vars.add_given(&mut self.inner.borrow_mut().undo_log, sub, sup);
     }
 
     pub fn sub_types(&self,
@@ -915,7 +1204,7 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
     pub fn skolemize_late_bound_regions<T>(&self,
                                            value: &ty::Binder<T>,
                                            snapshot: &CombinedSnapshot)
-                                           -> (T, SkolemizationMap)
+                                           -> (T, SkolemizationMap<'tcx>)
         where T : TypeFoldable<'tcx>
     {
         /*! See `higher_ranked::skolemize_late_bound_regions` */
@@ -924,7 +1213,7 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
     }
 
     pub fn leak_check(&self,
-                      skol_map: &SkolemizationMap,
+                      skol_map: &SkolemizationMap<'tcx>,
                       snapshot: &CombinedSnapshot)
                       -> UnitResult<'tcx>
     {
@@ -932,12 +1221,17 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
 
         match higher_ranked::leak_check(self, skol_map, snapshot) {
             Ok(()) => Ok(()),
-            Err((br, r)) => Err(TypeError::RegionsInsufficientlyPolymorphic(br, r))
+            Err(higher_ranked::Leak::Region(br, r)) => {
+                Err(TypeError::RegionsInsufficientlyPolymorphic(br, r))
+            }
+            Err(higher_ranked::Leak::Type(bt, ty)) => {
+                Err(TypeError::TypesInsufficientlyPolymorphic(bt, ty))
+            }
         }
     }
 
     pub fn plug_leaks<T>(&self,
-                         skol_map: SkolemizationMap,
+                         skol_map: SkolemizationMap<'tcx>,
                          snapshot: &CombinedSnapshot,
                          value: &T)
                          -> T
@@ -948,6 +1242,15 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         higher_ranked::plug_leaks(self, skol_map, snapshot, value)
     }
 
+    pub fn pop_skolemized(&self,
+                          skol_map: SkolemizationMap<'tcx>,
+                          snapshot: &CombinedSnapshot)
+    {
+        /*! See `higher_ranked::pop_skolemized` */
+
+        higher_ranked::pop_skolemized(self, skol_map, snapshot)
+    }
+
     pub fn equality_predicate(&self,
                               span: Span,
                               predicate: &ty::PolyEquatePredicate<'tcx>)
@@ -976,47 +1279,82 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         })
     }
 
-    pub fn next_ty_var_id(&self, diverging: bool) -> TyVid {
-        self.type_variables
+    pub fn next_ty_var_id(&self, diverging: bool, origin: TypeVariableOrigin) -> TyVid {
+        self.inner
             .borrow_mut()
-            .new_var(diverging, None)
+            .type_variables()
+            .new_var(diverging, origin, None)
     }
 
-    pub fn next_ty_var(&self) -> Ty<'tcx> {
-        self.tcx.mk_var(self.next_ty_var_id(false))
+    pub fn next_ty_var(&self, origin: TypeVariableOrigin) -> Ty<'tcx> {
+        self.tcx.mk_var(self.next_ty_var_id(false, origin))
     }
 
     pub fn next_ty_var_with_default(&self,
-                                    default: Option<type_variable::Default<'tcx>>) -> Ty<'tcx> {
-        let ty_var_id = self.type_variables
+                                    default: Option<type_variable::Default<'tcx>>,
+                                    origin: TypeVariableOrigin) -> Ty<'tcx> {
+        let ty_var_id = self.inner
                             .borrow_mut()
-                            .new_var(false, default);
+                            .type_variables()
+                            .new_var(false, origin, default);
 
         self.tcx.mk_var(ty_var_id)
     }
 
-    pub fn next_diverging_ty_var(&self) -> Ty<'tcx> {
-        self.tcx.mk_var(self.next_ty_var_id(true))
+    pub fn next_diverging_ty_var(&self, origin: TypeVariableOrigin) -> Ty<'tcx> {
+        self.tcx.mk_var(self.next_ty_var_id(true, origin))
+    }
+
+    pub fn next_ty_vars(&self, n: usize, origin: TypeVariableOrigin) -> Vec<Ty<'tcx>> {
+        (0..n).map(|_i| self.next_ty_var(origin)).collect()
     }
 
-    pub fn next_ty_vars(&self, n: usize) -> Vec<Ty<'tcx>> {
-        (0..n).map(|_i| self.next_ty_var()).collect()
+    /// Returns why the type variable `vid` was created.
+    pub fn type_var_origin(&self, vid: TyVid) -> TypeVariableOrigin {
+        self.inner.borrow_mut().type_variables().var_origin(vid)
     }
 
     pub fn next_int_var_id(&self) -> IntVid {
-        self.int_unification_table
+        self.inner
             .borrow_mut()
+            .int_unification_table()
             .new_key(None)
     }
 
     pub fn next_float_var_id(&self) -> FloatVid {
-        self.float_unification_table
+        self.inner
             .borrow_mut()
+            .float_unification_table()
             .new_key(None)
     }
 
+    pub fn next_const_var_id(&self) -> ty::ConstVid {
+        self.inner
+            .borrow_mut()
+            .const_variables()
+            .new_var()
+    }
+
+    pub fn next_const_var(&self) -> ty::Const {
+        ty::Const::Infer(self.next_const_var_id())
+    }
+
+    /// Replaces `c` with the value its variable has been unified with,
+    /// if it is a variable and that value is known.
+    pub fn resolve_const(&self, c: &ty::Const) -> ty::Const {
+        match *c {
+            ty::Const::Infer(vid) => {
+                match self.inner.borrow_mut().const_variables().probe(vid) {
+                    Some(value) => ty::Const::Value(value),
+                    None => c.clone(),
+                }
+            }
+            ty::Const::Value(_) => c.clone(),
+        }
+    }
+
     pub fn next_region_var(&self, origin: RegionVariableOrigin) -> ty::Region {
-        ty::ReVar(self.region_vars.new_region_var(origin))
+        ty::ReVar(self.region_vars.new_region_var(&mut self.inner.borrow_mut().undo_log, origin))
     }
 
     pub fn region_vars_for_defs(&self,
@@ -1045,7 +1383,8 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
                 }
             });
 
-            let ty_var = self.next_ty_var_with_default(default);
+            let origin = TypeVariableOrigin::TypeParameterDefinition(span, def.name);
+            let ty_var = self.next_ty_var_with_default(default, origin);
             substs.types.push(space, ty_var);
         }
     }
@@ -1193,23 +1532,24 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
                 // structurally), and we prevent cycles in any case,
                 // so this recursion should always be of very limited
                 // depth.
-                self.type_variables.borrow_mut()
-                    .probe(v)
-                    .map(|t| self.shallow_resolve(t))
-                    .unwrap_or(typ)
+                let known = self.inner.borrow_mut().type_variables().probe(v);
+                known.map(|t| self.shallow_resolve(t))
+                     .unwrap_or(typ)
             }
 
             ty::TyInfer(ty::IntVar(v)) => {
-                self.int_unification_table
+                self.inner
                     .borrow_mut()
+                    .int_unification_table()
                     .probe(v)
                     .map(|v| v.to_type(self.tcx))
                     .unwrap_or(typ)
             }
 
             ty::TyInfer(ty::FloatVar(v)) => {
-                self.float_unification_table
+                self.inner
                     .borrow_mut()
+                    .float_unification_table()
                     .probe(v)
                     .map(|v| v.to_type(self.tcx))
                     .unwrap_or(typ)
@@ -1221,6 +1561,13 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         }
     }
 
+    /// Returns the representative of the set of type variables that
+    /// `vid` has been equated with. Two unbound type variables with the
+    /// same root are known to be the same type.
+    pub fn root_ty_var(&self, vid: TyVid) -> TyVid {
+        self.type_variables.borrow_mut().root_var(vid)
+    }
+
     pub fn resolve_type_vars_if_possible<T>(&self, value: &T) -> T
         where T: TypeFoldable<'tcx>
     {
@@ -1425,9 +1772,67 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
         -> (T, FnvHashMap<ty::BoundRegion,ty::Region>)
         where T : TypeFoldable<'tcx>
     {
-        self.tcx.replace_late_bound_regions(
+        // Types bound by `for<T>` binders, if any, are replaced with
+        // fresh type variables.
+        let (value, map, _) = self.tcx.replace_late_bound_regions_and_types(
             value,
-            |br| self.next_region_var(LateBoundRegion(span, br, lbrct)))
+            |br| self.next_region_var(LateBoundRegion(span, br, lbrct)),
+            |_| self.next_ty_var(TypeVariableOrigin::LateBoundInFn(span)));
+        (value, map)
+    }
+
+    /// Creates a new skolemized type; see `higher_ranked::skolemize_late_bound_regions`.
+    pub fn next_skolemized_ty(&self) -> Ty<'tcx> {
+        let index = self.skolemized_ty_count.get();
+        self.skolemized_ty_count.set(index + 1);
+        self.tcx.mk_infer(ty::SkolemizedTy(index))
+    }
+
+    /// Registers an obligation that the type `sup_type` must outlive
+    /// `sub_region`. Unlike `verify_generic_bound`, this does not
+    /// produce any region constraints right away: the obligation is
+    /// queued until the types it mentions have been resolved, at
+    /// which point `take_registered_region_obligations` hands it back
+    /// to be decomposed.
+    pub fn register_region_obligation(&self,
+                                      sup_type: Ty<'tcx>,
+                                      sub_region: ty::Region,
+                                      cause: traits::ObligationCause<'tcx>) {
+        let region_obligation = traits::RegionObligation { sup_type: sup_type,
+                                                           sub_region: sub_region,
+                                                           cause: cause };
+
+        debug!("register_region_obligation({:?}, cause={:?})",
+               region_obligation, region_obligation.cause);
+
+        self.region_obligations
+            .borrow_mut()
+            .push((region_obligation.cause.body_id, region_obligation));
+        self.inner.borrow_mut().undo_log.push(UndoLog::PushRegionObligation);
+    }
+
+    /// Removes and returns the region obligations registered so far
+    /// for the fn body / static initializer `body_id`, in the order
+    /// in which they were registered.
+    pub fn take_registered_region_obligations(&self,
+                                              body_id: ast::NodeId)
+                                              -> Vec<traits::RegionObligation<'tcx>> {
+        assert!(!self.inner.borrow().undo_log.in_snapshot(),
+                "cannot take registered region obligations in a snapshot");
+        let mut region_obligations = self.region_obligations.borrow_mut();
+        let (taken, kept): (Vec<_>, Vec<_>) =
+            region_obligations.drain(..).partition(|&(id, _)| id == body_id);
+        *region_obligations = kept;
+        taken.into_iter().map(|(_, r_o)| r_o).collect()
+    }
+
+    /// Removes and returns the region constraints accumulated so far,
+    /// together with their origins, without solving them. The
+    /// inference context can then carry on as though they had never
+    /// been added. See `take_and_reset_region_constraints` in
+    /// `region_inference`.
+    pub fn take_and_reset_region_constraints(&self) -> RegionConstraintData<'tcx> {
+        self.region_vars.take_and_reset_region_constraints(&mut self.inner.borrow_mut().undo_log)
     }
 
     /// See `verify_generic_bound` method in `region_inference`
@@ -1441,25 +1846,86 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
                a,
                bound);
 
-        self.region_vars.verify_generic_bound(origin, kind, a, bound);
+        self.region_vars.verify_generic_bound(&mut self.inner.borrow_mut().undo_log,
+                                              origin,
+                                              kind,
+                                              a,
+                                              bound);
+    }
+
+    /// Checks whether `a` could be made a subtype of `b` in `param_env`,
+    /// without keeping any of the constraints that would take. The
+    /// obligations relating them would still need are returned, less
+    /// those that are among the where-clauses of `param_env`.
+    pub fn can_sub<'b,T>(&'b self,
+                         param_env: &ty::ParameterEnvironment<'b, 'tcx>,
+                         a: &T,
+                         b: &T)
+                         -> InferResult<'tcx, ()>
+        where T: Relate<'b,'tcx> + fmt::Debug
+    {
+        debug!("can_sub({:?}, {:?})", a, b);
+        self.probe(|_| {
+            self.sub(true, self.probe_trace(), a, b)
+                .map(|ok| self.unproven_obligations(param_env, ok.obligations))
+        })
     }
 
-    pub fn can_equate<'b,T>(&'b self, a: &T, b: &T) -> UnitResult<'tcx>
+    /// Checks whether `a` and `b` could be made equal in `param_env`; see
+    /// `can_sub`.
+    pub fn can_eq<'b,T>(&'b self,
+                        param_env: &ty::ParameterEnvironment<'b, 'tcx>,
+                        a: &T,
+                        b: &T)
+                        -> InferResult<'tcx, ()>
         where T: Relate<'b,'tcx> + fmt::Debug
     {
-        debug!("can_equate({:?}, {:?})", a, b);
+        debug!("can_eq({:?}, {:?})", a, b);
         self.probe(|_| {
-            // Gin up a dummy trace, since this won't be committed
-            // anyhow. We should make this typetrace stuff more
-            // generic so we don't have to do anything quite this
-            // terrible.
-            let e = self.tcx.types.err;
-            let trace = TypeTrace {
-                origin: TypeOrigin::Misc(codemap::DUMMY_SP),
-                values: Types(expected_found(true, e, e))
-            };
-            self.equate(true, trace, a, b)
-        }).map(|_| ())
+            self.equate(true, self.probe_trace(), a, b)
+                .map(|ok| self.unproven_obligations(param_env, ok.obligations))
+        })
+    }
+
+    fn unproven_obligations<'c>(&self,
+                                param_env: &ty::ParameterEnvironment<'c, 'tcx>,
+                                obligations: PredicateObligations<'tcx>)
+                                -> InferOk<'tcx, ()>
+    {
+        let obligations = obligations.into_iter().filter(|obligation| {
+            let predicate = self.resolve_type_vars_if_possible(&obligation.predicate);
+            !param_env.caller_bounds.contains(&predicate)
+        }).collect();
+        InferOk { value: (), obligations: obligations }
+    }
+
+    fn probe_trace(&self) -> TypeTrace<'tcx> {
+        // Gin up a dummy trace, since this won't be committed
+        // anyhow. We should make this typetrace stuff more
+        // generic so we don't have to do anything quite this
+        // terrible.
+        let e = self.tcx.types.err;
+        TypeTrace {
+            origin: TypeOrigin::Misc(codemap::DUMMY_SP),
+            values: Types(expected_found(true, e, e))
+        }
+    }
+
+    /// Matches `skeleton`, which may mention type parameters, against
+    /// `value`, returning the type in `value` that each parameter stands
+    /// for. Inference variables in `value` are resolved as far as they
+    /// are known but never bound, and no state of `self` changes; see
+    /// `ty::_match::MatchAgainst`.
+    pub fn match_poly_trait_ref(&self,
+                                skeleton: ty::PolyTraitRef<'tcx>,
+                                value: ty::PolyTraitRef<'tcx>)
+                                -> RelateResult<'tcx, FnvHashMap<ty::ParamTy, Ty<'tcx>>>
+    {
+        let value = self.resolve_type_vars_if_possible(&value);
+        debug!("match_poly_trait_ref({:?}, {:?})", skeleton, value);
+        let mut matcher = ty::_match::MatchAgainst::new(self.tcx);
+        matcher.relate(&skeleton, &value)?;
+        Ok(matcher.into_map())
     }
 
     pub fn node_ty(&self, id: ast::NodeId) -> McResult<Ty<'tcx>> {
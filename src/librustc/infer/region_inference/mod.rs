@@ -18,10 +18,12 @@ pub use self::RegionResolutionError::*;
 pub use self::VarValue::*;
 
 use super::{RegionVariableOrigin, SubregionOrigin, MiscVariable};
+use super::undo_log::{InferCtxtUndoLogs, UndoLog};
 use super::unify_key;
 
 use rustc_data_structures::graph::{self, Direction, NodeIndex};
-use rustc_data_structures::unify::{self, UnificationTable};
+use rustc_data_structures::snapshot_vec::{self as sv, Rollback, UndoLogs};
+use rustc_data_structures::unify as ut;
 use middle::free_region::FreeRegionMap;
 use ty::{self, Ty, TyCtxt};
 use ty::{BoundRegion, Region, RegionVid};
@@ -33,6 +35,8 @@ use util::nodemap::{FnvHashMap, FnvHashSet};
 use std::cell::{Cell, RefCell};
 use std::cmp::Ordering::{self, Less, Greater, Equal};
 use std::fmt;
+use std::mem;
+use std::slice;
 use std::u32;
 use syntax::ast;
 
@@ -98,6 +102,14 @@ pub enum VerifyBound {
 
     // B = forall {B} --> 'min must meet all bounds b in {B}
     AllBounds(Vec<VerifyBound>),
+
+    // B = if {R == R'} then B' --> each pair of regions must be equal
+    // once resolved, and 'min must then meet B'
+    //
+    // This arises from a declared bound on a projection whose trait
+    // reference only matches the subject's if their regions (some of
+    // which may be inference variables) turn out to be the same.
+    IfEq(Vec<(Region, Region)>, Box<VerifyBound>),
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
@@ -108,13 +120,15 @@ pub struct TwoRegions {
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum UndoLogEntry {
-    OpenSnapshot,
-    CommitedSnapshot,
     AddVar(RegionVid),
     AddConstraint(Constraint),
     AddVerify(usize),
     AddGiven(ty::FreeRegion, ty::RegionVid),
     AddCombination(CombineMapType, TwoRegions),
+
+    // An entry whose effect was already undone by `pop_skolemized`;
+    // rolling it back is a no-op.
+    Purged,
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -190,6 +204,31 @@ impl SameRegions {
 
 pub type CombineMap = FnvHashMap<TwoRegions, RegionVid>;
 
+/// When computing the taint set of a region (see `tainted`), which
+/// edges of the constraint graph should be followed. An edge `a <= b`
+/// is *outgoing* from `a` and *incoming* to `b`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TaintDirections {
+    incoming: bool,
+    outgoing: bool,
+}
+
+impl TaintDirections {
+    /// Follow only edges `r1 <= r`, i.e. regions that `r` must outlive.
+    pub fn incoming() -> Self {
+        TaintDirections { incoming: true, outgoing: false }
+    }
+
+    /// Follow only edges `r <= r1`, i.e. regions that must outlive `r`.
+    pub fn outgoing() -> Self {
+        TaintDirections { incoming: false, outgoing: true }
+    }
+
+    pub fn both() -> Self {
+        TaintDirections { incoming: true, outgoing: true }
+    }
+}
+
 pub struct RegionVarBindings<'a, 'tcx: 'a> {
     tcx: &'a TyCtxt<'tcx>,
     var_origins: RefCell<Vec<RegionVariableOrigin>>,
@@ -230,17 +269,12 @@ pub struct RegionVarBindings<'a, 'tcx: 'a> {
     skolemization_count: Cell<u32>,
     bound_count: Cell<u32>,
 
-    // The undo log records actions that might later be undone.
-    //
-    // Note: when the undo_log is empty, we are not actively
-    // snapshotting. When the `start_snapshot()` method is called, we
-    // push an OpenSnapshot entry onto the list to indicate that we
-    // are now actively snapshotting. The reason for this is that
-    // otherwise we end up adding entries for things like the lower
-    // bound on a variable and so forth, which can never be rolled
-    // back.
-    undo_log: RefCell<Vec<UndoLogEntry>>,
-    unification_table: RefCell<UnificationTable<ty::RegionVid>>,
+    // Changes that might later be undone are recorded into the undo
+    // log of the inference context, which is shared with the other
+    // tables and passed in to each method that records something.
+    // Nothing is recorded outside of a snapshot, as such changes (for
+    // example the lower bound on a variable) can never be rolled back.
+    unification_table: RefCell<ut::UnificationStorage<ty::RegionVid>>,
 
     // This contains the results of inference.  It begins as an empty
     // option and only acquires a value after inference is complete.
@@ -248,11 +282,50 @@ pub struct RegionVarBindings<'a, 'tcx: 'a> {
 }
 
 pub struct RegionSnapshot {
-    length: usize,
-    region_snapshot: unify::Snapshot<ty::RegionVid>,
     skolemization_count: u32,
 }
 
+/// The region constraints accumulated by a `RegionVarBindings`, as
+/// handed out by `take_and_reset_region_constraints`. The region
+/// variables they mention remain valid in the table they were taken
+/// from.
+pub struct RegionConstraintData<'tcx> {
+    /// Constraints of the form `A <= B`, each with the origin that
+    /// required it.
+    pub constraints: FnvHashMap<Constraint, SubregionOrigin<'tcx>>,
+
+    /// Bounds that must be verified once inference is complete.
+    pub verifys: Vec<Verify<'tcx>>,
+
+    /// Relationships known to hold from closure signatures; see the
+    /// `givens` field of `RegionVarBindings`.
+    pub givens: FnvHashSet<(ty::FreeRegion, ty::RegionVid)>,
+}
+
+impl<'tcx> RegionConstraintData<'tcx> {
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty() && self.verifys.is_empty() && self.givens.is_empty()
+    }
+}
+
+/// The iterator returned by `RegionVarBindings::vars_created_since`.
+pub struct VarsCreatedSince<'b, 'tcx: 'b> {
+    actions: slice::Iter<'b, UndoLog<'tcx>>,
+}
+
+impl<'b, 'tcx> Iterator for VarsCreatedSince<'b, 'tcx> {
+    type Item = RegionVid;
+
+    fn next(&mut self) -> Option<RegionVid> {
+        for action in &mut self.actions {
+            if let UndoLog::RegionVars(AddVar(vid)) = *action {
+                return Some(vid);
+            }
+        }
+        None
+    }
+}
+
 impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
     pub fn new(tcx: &'a TyCtxt<'tcx>) -> RegionVarBindings<'a, 'tcx> {
         RegionVarBindings {
@@ -266,80 +339,92 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
             glbs: RefCell::new(FnvHashMap()),
             skolemization_count: Cell::new(0),
             bound_count: Cell::new(0),
-            undo_log: RefCell::new(Vec::new()),
-            unification_table: RefCell::new(UnificationTable::new()),
+            unification_table: RefCell::new(ut::UnificationTable::new()),
         }
     }
 
-    fn in_snapshot(&self) -> bool {
-        !self.undo_log.borrow().is_empty()
+    /// Called when the inference context starts a snapshot; the
+    /// changes made in it are recorded in the shared undo log.
+    pub fn start_snapshot(&self) -> RegionSnapshot {
+        debug!("RegionVarBindings: start_snapshot()");
+        RegionSnapshot { skolemization_count: self.skolemization_count.get() }
     }
 
-    pub fn start_snapshot(&self) -> RegionSnapshot {
-        let length = self.undo_log.borrow().len();
-        debug!("RegionVarBindings: start_snapshot({})", length);
-        self.undo_log.borrow_mut().push(OpenSnapshot);
-        RegionSnapshot {
-            length: length,
-            region_snapshot: self.unification_table.borrow_mut().snapshot(),
-            skolemization_count: self.skolemization_count.get(),
-        }
+    /// Called when the inference context commits or rolls back
+    /// `snapshot`, after reversing its entries in the undo log (see
+    /// `rollback_undo_entry`) if rolling back.
+    pub fn end_snapshot(&self, snapshot: RegionSnapshot) {
+        debug!("RegionVarBindings: end_snapshot({:?})", snapshot);
+        self.skolemization_count.set(snapshot.skolemization_count);
     }
 
-    pub fn commit(&self, snapshot: RegionSnapshot) {
-        debug!("RegionVarBindings: commit({})", snapshot.length);
-        assert!(self.undo_log.borrow().len() > snapshot.length);
-        assert!((*self.undo_log.borrow())[snapshot.length] == OpenSnapshot);
+    pub fn rollback_unification(&self, undo: sv::UndoLog<ut::Delegate<ty::RegionVid>>) {
+        self.unification_table.borrow_mut().reverse(undo);
+    }
 
-        let mut undo_log = self.undo_log.borrow_mut();
-        if snapshot.length == 0 {
-            undo_log.truncate(0);
-        } else {
-            (*undo_log)[snapshot.length] = CommitedSnapshot;
-        }
-        self.skolemization_count.set(snapshot.skolemization_count);
-        self.unification_table.borrow_mut().commit(snapshot.region_snapshot);
-    }
-
-    pub fn rollback_to(&self, snapshot: RegionSnapshot) {
-        debug!("RegionVarBindings: rollback_to({:?})", snapshot);
-        let mut undo_log = self.undo_log.borrow_mut();
-        assert!(undo_log.len() > snapshot.length);
-        assert!((*undo_log)[snapshot.length] == OpenSnapshot);
-        while undo_log.len() > snapshot.length + 1 {
-            match undo_log.pop().unwrap() {
-                OpenSnapshot => {
-                    bug!("Failure to observe stack discipline");
-                }
-                CommitedSnapshot => {}
-                AddVar(vid) => {
-                    let mut var_origins = self.var_origins.borrow_mut();
-                    var_origins.pop().unwrap();
-                    assert_eq!(var_origins.len(), vid.index as usize);
-                }
-                AddConstraint(ref constraint) => {
-                    self.constraints.borrow_mut().remove(constraint);
-                }
-                AddVerify(index) => {
-                    self.verifys.borrow_mut().pop();
-                    assert_eq!(self.verifys.borrow().len(), index);
-                }
-                AddGiven(sub, sup) => {
-                    self.givens.borrow_mut().remove(&(sub, sup));
-                }
-                AddCombination(Glb, ref regions) => {
-                    self.glbs.borrow_mut().remove(regions);
-                }
-                AddCombination(Lub, ref regions) => {
-                    self.lubs.borrow_mut().remove(regions);
-                }
+    pub fn rollback_undo_entry(&self, undo_entry: UndoLogEntry) {
+        match undo_entry {
+            Purged => {}
+            AddVar(vid) => {
+                let mut var_origins = self.var_origins.borrow_mut();
+                var_origins.pop().unwrap();
+                assert_eq!(var_origins.len(), vid.index as usize);
+            }
+            AddConstraint(ref constraint) => {
+                self.constraints.borrow_mut().remove(constraint);
+            }
+            AddVerify(index) => {
+                self.verifys.borrow_mut().pop();
+                assert_eq!(self.verifys.borrow().len(), index);
+            }
+            AddGiven(sub, sup) => {
+                self.givens.borrow_mut().remove(&(sub, sup));
+            }
+            AddCombination(Glb, ref regions) => {
+                self.glbs.borrow_mut().remove(regions);
+            }
+            AddCombination(Lub, ref regions) => {
+                self.lubs.borrow_mut().remove(regions);
             }
         }
-        let c = undo_log.pop().unwrap();
-        assert!(c == OpenSnapshot);
-        self.skolemization_count.set(snapshot.skolemization_count);
-        self.unification_table.borrow_mut()
-            .rollback_to(snapshot.region_snapshot);
+    }
+
+    /// Removes the constraints, verifys and givens accumulated so far
+    /// and returns them to the caller, leaving the table as though no
+    /// constraint had ever been added. The region variables created so
+    /// far are kept, but no longer unified with one another, and no
+    /// cached LUB or GLB is reused afterwards. Must not be called while
+    /// a snapshot is open, nor once the regions have been resolved.
+    pub fn take_and_reset_region_constraints(&self,
+                                             undo_log: &mut InferCtxtUndoLogs<'tcx>)
+                                             -> RegionConstraintData<'tcx> {
+        debug!("RegionVarBindings: take_and_reset_region_constraints()");
+        assert!(!undo_log.in_snapshot());
+        assert!(self.values_are_none());
+        assert_eq!(self.skolemization_count.get(), 0);
+
+        // Forget the LUBs and GLBs, so that fresh variables (and
+        // constraints) are created if the same combination is asked
+        // for again.
+        self.lubs.borrow_mut().clear();
+        self.glbs.borrow_mut().clear();
+
+        // Recreate every variable in a "not unified" state, since the
+        // unifications were justified by the constraints we hand out.
+        let mut unification_table = self.unification_table.borrow_mut();
+        *unification_table = ut::UnificationTable::new();
+        for index in 0..self.num_vars() {
+            let vid = RegionVid { index: index };
+            let u_vid = unification_table.with_log(&mut *undo_log).new_key(
+                unify_key::RegionVidKey { min_vid: vid });
+            assert_eq!(vid, u_vid);
+        }
+
+        RegionConstraintData {
+            constraints: mem::replace(&mut *self.constraints.borrow_mut(), FnvHashMap()),
+            verifys: mem::replace(&mut *self.verifys.borrow_mut(), Vec::new()),
+            givens: mem::replace(&mut *self.givens.borrow_mut(), FnvHashSet()),
+        }
     }
 
     pub fn num_vars(&self) -> u32 {
@@ -349,17 +434,22 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
         len as u32
     }
 
-    pub fn new_region_var(&self, origin: RegionVariableOrigin) -> RegionVid {
+    pub fn var_origin(&self, vid: RegionVid) -> RegionVariableOrigin {
+        self.var_origins.borrow()[vid.index as usize].clone()
+    }
+
+    pub fn new_region_var(&self,
+                          undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                          origin: RegionVariableOrigin)
+                          -> RegionVid {
         let vid = RegionVid { index: self.num_vars() };
         self.var_origins.borrow_mut().push(origin.clone());
 
-        let u_vid = self.unification_table.borrow_mut().new_key(
+        let u_vid = self.unification_table.borrow_mut().with_log(&mut *undo_log).new_key(
             unify_key::RegionVidKey { min_vid: vid }
             );
         assert_eq!(vid, u_vid);
-        if self.in_snapshot() {
-            self.undo_log.borrow_mut().push(AddVar(vid));
-        }
+        undo_log.push(AddVar(vid));
         debug!("created new region variable {:?} with origin {:?}",
                vid,
                origin);
@@ -382,14 +472,81 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
     /// it's just there to make it explicit which snapshot bounds the
     /// skolemized region that results.
     pub fn new_skolemized(&self, br: ty::BoundRegion, snapshot: &RegionSnapshot) -> Region {
-        assert!(self.in_snapshot());
-        assert!(self.undo_log.borrow()[snapshot.length] == OpenSnapshot);
+        assert!(self.skolemization_count.get() >= snapshot.skolemization_count);
 
         let sc = self.skolemization_count.get();
         self.skolemization_count.set(sc + 1);
         ReSkolemized(ty::SkolemizedRegionVid { index: sc }, br)
     }
 
+    /// Removes the skolemized regions `skols`, which must be the ones
+    /// most recently created in `snapshot`, together with any
+    /// constraints or combinations that mention them directly. This is
+    /// used once a higher-ranked computation has succeeded and its
+    /// skolemized regions are no longer needed, so that they do not
+    /// stay in the constraint set for the rest of the snapshot.
+    ///
+    /// `actions_since_snapshot` are the entries of the undo log
+    /// recorded since `snapshot` was started.
+    pub fn pop_skolemized(&self,
+                          actions_since_snapshot: &mut [UndoLog<'tcx>],
+                          skols: &FnvHashSet<Region>,
+                          snapshot: &RegionSnapshot) {
+        debug!("pop_skolemized(skols={:?})", skols);
+
+        let last_to_pop = self.skolemization_count.get();
+        assert!(last_to_pop as usize >= skols.len(),
+                "popping more skolemized regions than exist: count = {}, skols.len = {}",
+                last_to_pop,
+                skols.len());
+        let first_to_pop = last_to_pop - skols.len() as u32;
+        assert!(first_to_pop >= snapshot.skolemization_count,
+                "popping more skolemized regions than the snapshot created: \
+                 count now = {}, count then = {}, skols.len = {}",
+                last_to_pop,
+                snapshot.skolemization_count,
+                skols.len());
+        debug_assert!(skols.iter().all(|&r| match r {
+                          ReSkolemized(vid, _) => first_to_pop <= vid.index &&
+                                                  vid.index < last_to_pop,
+                          _ => false,
+                      }),
+                      "invalid skolemized regions or out of range ({}..{}): {:?}",
+                      first_to_pop,
+                      last_to_pop,
+                      skols);
+
+        for undo in actions_since_snapshot.iter_mut().rev() {
+            if let UndoLog::RegionVars(ref mut undo_entry) = *undo {
+                if kill_entry(skols, undo_entry) {
+                    let undo_entry = mem::replace(undo_entry, Purged);
+                    self.rollback_undo_entry(undo_entry);
+                }
+            }
+        }
+
+        self.skolemization_count.set(first_to_pop);
+        return;
+
+        fn kill_entry(skols: &FnvHashSet<Region>, undo_entry: &UndoLogEntry) -> bool {
+            match undo_entry {
+                &AddConstraint(ConstrainVarSubVar(..)) => false,
+                &AddConstraint(ConstrainRegSubVar(a, _)) => skols.contains(&a),
+                &AddConstraint(ConstrainVarSubReg(_, b)) => skols.contains(&b),
+                &AddCombination(_, ref regions) => {
+                    skols.contains(&regions.a) || skols.contains(&regions.b)
+                }
+                // Verifys are identified by their index, so they
+                // cannot be removed out of order; givens never
+                // involve skolemized regions.
+                &AddVerify(_) |
+                &AddGiven(..) |
+                &AddVar(..) |
+                &Purged => false,
+            }
+        }
+    }
+
     pub fn new_bound(&self, debruijn: ty::DebruijnIndex) -> Region {
         // Creates a fresh bound variable for use in GLB computations.
         // See discussion of GLB computation in the large comment at
@@ -423,20 +580,21 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
         self.values.borrow().is_none()
     }
 
-    fn add_constraint(&self, constraint: Constraint, origin: SubregionOrigin<'tcx>) {
+    fn add_constraint(&self,
+                      undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                      constraint: Constraint,
+                      origin: SubregionOrigin<'tcx>) {
         // cannot add constraints once regions are resolved
         assert!(self.values_are_none());
 
         debug!("RegionVarBindings: add_constraint({:?})", constraint);
 
         if self.constraints.borrow_mut().insert(constraint, origin).is_none() {
-            if self.in_snapshot() {
-                self.undo_log.borrow_mut().push(AddConstraint(constraint));
-            }
+            undo_log.push(AddConstraint(constraint));
         }
     }
 
-    fn add_verify(&self, verify: Verify<'tcx>) {
+    fn add_verify(&self, undo_log: &mut InferCtxtUndoLogs<'tcx>, verify: Verify<'tcx>) {
         // cannot add verifys once regions are resolved
         assert!(self.values_are_none());
 
@@ -453,12 +611,13 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
         let mut verifys = self.verifys.borrow_mut();
         let index = verifys.len();
         verifys.push(verify);
-        if self.in_snapshot() {
-            self.undo_log.borrow_mut().push(AddVerify(index));
-        }
+        undo_log.push(AddVerify(index));
     }
 
-    pub fn add_given(&self, sub: ty::FreeRegion, sup: ty::RegionVid) {
+    pub fn add_given(&self,
+                     undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                     sub: ty::FreeRegion,
+                     sup: ty::RegionVid) {
         // cannot add givens once regions are resolved
         assert!(self.values_are_none());
 
@@ -466,24 +625,32 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
         if givens.insert((sub, sup)) {
             debug!("add_given({:?} <= {:?})", sub, sup);
 
-            self.undo_log.borrow_mut().push(AddGiven(sub, sup));
+            undo_log.push(AddGiven(sub, sup));
         }
     }
 
-    pub fn make_eqregion(&self, origin: SubregionOrigin<'tcx>, sub: Region, sup: Region) {
+    pub fn make_eqregion(&self,
+                         undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                         origin: SubregionOrigin<'tcx>,
+                         sub: Region,
+                         sup: Region) {
         if sub != sup {
             // Eventually, it would be nice to add direct support for
             // equating regions.
-            self.make_subregion(origin.clone(), sub, sup);
-            self.make_subregion(origin, sup, sub);
+            self.make_subregion(undo_log, origin.clone(), sub, sup);
+            self.make_subregion(undo_log, origin, sup, sub);
 
             if let (ty::ReVar(sub), ty::ReVar(sup)) = (sub, sup) {
-                self.unification_table.borrow_mut().union(sub, sup);
+                self.unification_table.borrow_mut().with_log(undo_log).union(sub, sup);
             }
         }
     }
 
-    pub fn make_subregion(&self, origin: SubregionOrigin<'tcx>, sub: Region, sup: Region) {
+    pub fn make_subregion(&self,
+                          undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                          origin: SubregionOrigin<'tcx>,
+                          sub: Region,
+                          sup: Region) {
         // cannot add constraints once regions are resolved
         assert!(self.values_are_none());
 
@@ -506,30 +673,57 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
                 // all regions are subregions of static, so we can ignore this
             }
             (ReVar(sub_id), ReVar(sup_id)) => {
-                self.add_constraint(ConstrainVarSubVar(sub_id, sup_id), origin);
+                self.add_constraint(undo_log, ConstrainVarSubVar(sub_id, sup_id), origin);
             }
             (r, ReVar(sup_id)) => {
-                self.add_constraint(ConstrainRegSubVar(r, sup_id), origin);
+                self.add_constraint(undo_log, ConstrainRegSubVar(r, sup_id), origin);
             }
             (ReVar(sub_id), r) => {
-                self.add_constraint(ConstrainVarSubReg(sub_id, r), origin);
+                self.add_constraint(undo_log, ConstrainVarSubReg(sub_id, r), origin);
             }
             _ => {
-                self.add_verify(VerifyRegSubReg(origin, sub, sup));
+                self.add_verify(undo_log, VerifyRegSubReg(origin, sub, sup));
             }
         }
     }
 
+    /// Returns the `sub <= sup` relationships between regions recorded
+    /// so far, as `(sub, sup)` pairs. Canonical queries use this to
+    /// hand the region constraints of their answer back to the caller;
+    /// see `infer::canonical`.
+    pub fn region_constraints(&self) -> Vec<(Region, Region)> {
+        let mut result: Vec<_> = self.constraints.borrow().keys().map(|constraint| {
+            match *constraint {
+                ConstrainVarSubVar(sub, sup) => (ReVar(sub), ReVar(sup)),
+                ConstrainRegSubVar(sub, sup) => (sub, ReVar(sup)),
+                ConstrainVarSubReg(sub, sup) => (ReVar(sub), sup),
+            }
+        }).collect();
+        result.extend(self.verifys.borrow().iter().filter_map(|verify| {
+            match *verify {
+                VerifyRegSubReg(_, sub, sup) => Some((sub, sup)),
+                VerifyGenericBound(..) => None,
+            }
+        }));
+        result
+    }
+
     /// See `Verify::VerifyGenericBound`
     pub fn verify_generic_bound(&self,
+                                undo_log: &mut InferCtxtUndoLogs<'tcx>,
                                 origin: SubregionOrigin<'tcx>,
                                 kind: GenericKind<'tcx>,
                                 sub: Region,
                                 bound: VerifyBound) {
-        self.add_verify(VerifyGenericBound(kind, origin, sub, bound));
+        self.add_verify(undo_log, VerifyGenericBound(kind, origin, sub, bound));
     }
 
-    pub fn lub_regions(&self, origin: SubregionOrigin<'tcx>, a: Region, b: Region) -> Region {
+    pub fn lub_regions(&self,
+                       undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                       origin: SubregionOrigin<'tcx>,
+                       a: Region,
+                       b: Region)
+                       -> Region {
         // cannot add constraints once regions are resolved
         assert!(self.values_are_none());
 
@@ -539,13 +733,18 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
         } else if a == b {
             a // LUB(a,a) = a
         } else {
-            self.combine_vars(Lub, a, b, origin.clone(), |this, old_r, new_r| {
-                this.make_subregion(origin.clone(), old_r, new_r)
+            self.combine_vars(undo_log, Lub, a, b, origin.clone(), |this, log, old_r, new_r| {
+                this.make_subregion(log, origin.clone(), old_r, new_r)
             })
         }
     }
 
-    pub fn glb_regions(&self, origin: SubregionOrigin<'tcx>, a: Region, b: Region) -> Region {
+    pub fn glb_regions(&self,
+                       undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                       origin: SubregionOrigin<'tcx>,
+                       a: Region,
+                       b: Region)
+                       -> Region {
         // cannot add constraints once regions are resolved
         assert!(self.values_are_none());
 
@@ -560,8 +759,8 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
             }
 
             _ => {
-                self.combine_vars(Glb, a, b, origin.clone(), |this, old_r, new_r| {
-                    this.make_subregion(origin.clone(), new_r, old_r)
+                self.combine_vars(undo_log, Glb, a, b, origin.clone(), |this, log, old_r, new_r| {
+                    this.make_subregion(log, origin.clone(), new_r, old_r)
                 })
             }
         }
@@ -582,8 +781,11 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
         }
     }
 
-    pub fn opportunistic_resolve_var(&self, rid: RegionVid) -> ty::Region {
-        ty::ReVar(self.unification_table.borrow_mut().find_value(rid).min_vid)
+    pub fn opportunistic_resolve_var(&self,
+                                     undo_log: &mut InferCtxtUndoLogs<'tcx>,
+                                     rid: RegionVid)
+                                     -> ty::Region {
+        ty::ReVar(self.unification_table.borrow_mut().with_log(undo_log).find_value(rid).min_vid)
     }
 
     fn combine_map(&self, t: CombineMapType) -> &RefCell<CombineMap> {
@@ -594,13 +796,14 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
     }
 
     pub fn combine_vars<F>(&self,
+                           undo_log: &mut InferCtxtUndoLogs<'tcx>,
                            t: CombineMapType,
                            a: Region,
                            b: Region,
                            origin: SubregionOrigin<'tcx>,
                            mut relate: F)
                            -> Region
-        where F: FnMut(&RegionVarBindings<'a, 'tcx>, Region, Region)
+        where F: FnMut(&RegionVarBindings<'a, 'tcx>, &mut InferCtxtUndoLogs<'tcx>, Region, Region)
     {
         let vars = TwoRegions { a: a, b: b };
         match self.combine_map(t).borrow().get(&vars) {
@@ -609,34 +812,42 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
             }
             None => {}
         }
-        let c = self.new_region_var(MiscVariable(origin.span()));
+        let c = self.new_region_var(undo_log, MiscVariable(origin.span()));
         self.combine_map(t).borrow_mut().insert(vars, c);
-        if self.in_snapshot() {
-            self.undo_log.borrow_mut().push(AddCombination(t, vars));
-        }
-        relate(self, a, ReVar(c));
-        relate(self, b, ReVar(c));
+        undo_log.push(AddCombination(t, vars));
+        relate(self, &mut *undo_log, a, ReVar(c));
+        relate(self, &mut *undo_log, b, ReVar(c));
         debug!("combine_vars() c={:?}", c);
         ReVar(c)
     }
 
-    pub fn vars_created_since_snapshot(&self, mark: &RegionSnapshot) -> Vec<RegionVid> {
-        self.undo_log.borrow()[mark.length..]
-            .iter()
-            .filter_map(|&elt| {
-                match elt {
-                    AddVar(vid) => Some(vid),
-                    _ => None,
-                }
-            })
-            .collect()
+    /// The region variables created in a snapshot, given the entries
+    /// `actions_since_snapshot` of the undo log recorded since it was
+    /// started.
+    pub fn vars_created_since_snapshot(&self, actions_since_snapshot: &[UndoLog<'tcx>])
+                                       -> Vec<RegionVid> {
+        self.vars_created_since(actions_since_snapshot).collect()
+    }
+
+    /// Like `vars_created_since_snapshot`, but without collecting the
+    /// variables into a vector.
+    pub fn vars_created_since<'b>(&self, actions_since_snapshot: &'b [UndoLog<'tcx>])
+                                  -> VarsCreatedSince<'b, 'tcx> {
+        VarsCreatedSince { actions: actions_since_snapshot.iter() }
     }
 
-    /// Computes all regions that have been related to `r0` in any way since the mark `mark` was
-    /// made---`r0` itself will be the first entry. This is used when checking whether skolemized
-    /// regions are being improperly related to other regions.
-    pub fn tainted(&self, mark: &RegionSnapshot, r0: Region) -> Vec<Region> {
-        debug!("tainted(mark={:?}, r0={:?})", mark, r0);
+    /// Computes all regions that have been related to `r0` by the
+    /// entries `actions_since_snapshot` of the undo log---`r0` itself
+    /// will be the first entry. Only constraint edges in the
+    /// directions given by `directions` are followed. This is used
+    /// when checking whether skolemized regions are being improperly
+    /// related to other regions.
+    pub fn tainted(&self,
+                   actions_since_snapshot: &[UndoLog<'tcx>],
+                   r0: Region,
+                   directions: TaintDirections)
+                   -> Vec<Region> {
+        debug!("tainted(r0={:?}, directions={:?})", r0, directions);
         let _indenter = indenter();
 
         // `result_set` acts as a worklist: we explore all outgoing
@@ -649,39 +860,43 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
             let r = result_set[result_index];
             debug!("result_index={}, r={:?}", result_index, r);
 
-            for undo_entry in self.undo_log.borrow()[mark.length..].iter() {
+            for undo in actions_since_snapshot {
+                let undo_entry = match *undo {
+                    UndoLog::RegionVars(ref undo_entry) => undo_entry,
+                    _ => continue,
+                };
                 match undo_entry {
                     &AddConstraint(ConstrainVarSubVar(a, b)) => {
-                        consider_adding_bidirectional_edges(&mut result_set, r, ReVar(a), ReVar(b));
+                        consider_adding_edges(&mut result_set, directions, r, ReVar(a), ReVar(b));
                     }
                     &AddConstraint(ConstrainRegSubVar(a, b)) => {
-                        consider_adding_bidirectional_edges(&mut result_set, r, a, ReVar(b));
+                        consider_adding_edges(&mut result_set, directions, r, a, ReVar(b));
                     }
                     &AddConstraint(ConstrainVarSubReg(a, b)) => {
-                        consider_adding_bidirectional_edges(&mut result_set, r, ReVar(a), b);
+                        consider_adding_edges(&mut result_set, directions, r, ReVar(a), b);
                     }
                     &AddGiven(a, b) => {
-                        consider_adding_bidirectional_edges(&mut result_set,
-                                                            r,
-                                                            ReFree(a),
-                                                            ReVar(b));
+                        consider_adding_edges(&mut result_set,
+                                              directions,
+                                              r,
+                                              ReFree(a),
+                                              ReVar(b));
                     }
                     &AddVerify(i) => {
                         match (*self.verifys.borrow())[i] {
                             VerifyRegSubReg(_, a, b) => {
-                                consider_adding_bidirectional_edges(&mut result_set, r, a, b);
+                                consider_adding_edges(&mut result_set, directions, r, a, b);
                             }
                             VerifyGenericBound(_, _, a, ref bound) => {
                                 bound.for_each_region(&mut |b| {
-                                    consider_adding_bidirectional_edges(&mut result_set, r, a, b)
+                                    consider_adding_edges(&mut result_set, directions, r, a, b)
                                 });
                             }
                         }
                     }
                     &AddCombination(..) |
                     &AddVar(..) |
-                    &OpenSnapshot |
-                    &CommitedSnapshot => {}
+                    &Purged => {}
                 }
             }
 
@@ -690,12 +905,19 @@ impl<'a, 'tcx> RegionVarBindings<'a, 'tcx> {
 
         return result_set;
 
-        fn consider_adding_bidirectional_edges(result_set: &mut Vec<Region>,
-                                               r: Region,
-                                               r1: Region,
-                                               r2: Region) {
-            consider_adding_directed_edge(result_set, r, r1, r2);
-            consider_adding_directed_edge(result_set, r, r2, r1);
+        // Consider the edge `sub <= sup`: it is outgoing from `sub`
+        // and incoming to `sup`.
+        fn consider_adding_edges(result_set: &mut Vec<Region>,
+                                 directions: TaintDirections,
+                                 r: Region,
+                                 sub: Region,
+                                 sup: Region) {
+            if directions.outgoing {
+                consider_adding_directed_edge(result_set, r, sub, sup);
+            }
+            if directions.incoming {
+                consider_adding_directed_edge(result_set, r, sup, sub);
+            }
         }
 
         fn consider_adding_directed_edge(result_set: &mut Vec<Region>,
@@ -1339,8 +1561,7 @@ impl<'tcx> fmt::Debug for RegionAndOrigin<'tcx> {
 
 impl fmt::Debug for RegionSnapshot {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "RegionSnapshot(length={},skolemization={})",
-               self.length, self.skolemization_count)
+        write!(f, "RegionSnapshot(skolemization={})", self.skolemization_count)
     }
 }
 
@@ -1383,6 +1604,14 @@ impl VerifyBound {
             &VerifyBound::AllBounds(ref bs) => for b in bs {
                 b.for_each_region(f);
             },
+
+            &VerifyBound::IfEq(ref pairs, ref b) => {
+                for &(r1, r2) in pairs {
+                    f(r1);
+                    f(r2);
+                }
+                b.for_each_region(f);
+            }
         }
     }
 
@@ -1392,6 +1621,9 @@ impl VerifyBound {
             &VerifyBound::AllRegions(ref bs) => bs.is_empty(),
             &VerifyBound::AnyBound(ref bs) => bs.iter().any(|b| b.must_hold()),
             &VerifyBound::AllBounds(ref bs) => bs.iter().all(|b| b.must_hold()),
+            &VerifyBound::IfEq(ref pairs, ref b) => {
+                pairs.iter().all(|&(r1, r2)| r1 == r2) && b.must_hold()
+            }
         }
     }
 
@@ -1401,6 +1633,12 @@ impl VerifyBound {
             &VerifyBound::AllRegions(ref bs) => bs.contains(&ty::ReEmpty),
             &VerifyBound::AnyBound(ref bs) => bs.iter().all(|b| b.cannot_hold()),
             &VerifyBound::AllBounds(ref bs) => bs.iter().any(|b| b.cannot_hold()),
+            &VerifyBound::IfEq(ref pairs, ref b) => {
+                // Distinct regions can only become equal through inference.
+                b.cannot_hold() || pairs.iter().any(|&(r1, r2)| {
+                    r1 != r2 && !r1.needs_infer() && !r2.needs_infer()
+                })
+            }
         }
     }
 
@@ -1448,6 +1686,11 @@ impl VerifyBound {
             &VerifyBound::AllBounds(ref bs) =>
                 bs.iter()
                   .all(|b| b.is_met(tcx, free_regions, var_values, min)),
+
+            &VerifyBound::IfEq(ref pairs, ref b) =>
+                pairs.iter()
+                     .all(|&(r1, r2)| normalize(var_values, r1) == normalize(var_values, r2)) &&
+                    b.is_met(tcx, free_regions, var_values, min),
         }
     }
 }
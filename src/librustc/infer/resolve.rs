@@ -73,7 +73,10 @@ impl<'a, 'tcx> ty::fold::TypeFolder<'tcx> for OpportunisticTypeAndRegionResolver
 
     fn fold_region(&mut self, r: ty::Region) -> ty::Region {
         match r {
-          ty::ReVar(rid) => self.infcx.region_vars.opportunistic_resolve_var(rid),
+          ty::ReVar(rid) => {
+              let undo_log = &mut self.infcx.inner.borrow_mut().undo_log;
+              self.infcx.region_vars.opportunistic_resolve_var(undo_log, rid)
+          }
           _ => r,
         }
     }
@@ -30,7 +30,7 @@ impl<'a, 'tcx> Sub<'a, 'tcx> {
     }
 
     pub fn obligations(self) -> PredicateObligations<'tcx> {
-        self.fields.obligations
+        self.fields.into_obligations()
     }
 }
 
@@ -70,12 +70,13 @@ impl<'a, 'tcx> TypeRelation<'a, 'tcx> for Sub<'a, 'tcx> {
         if a == b { return Ok(a); }
 
         let infcx = self.fields.infcx;
-        let a = infcx.type_variables.borrow_mut().replace_if_possible(a);
-        let b = infcx.type_variables.borrow_mut().replace_if_possible(b);
+        let a = infcx.inner.borrow_mut().type_variables().replace_if_possible(a);
+        let b = infcx.inner.borrow_mut().type_variables().replace_if_possible(b);
         match (&a.sty, &b.sty) {
             (&ty::TyInfer(TyVar(a_id)), &ty::TyInfer(TyVar(b_id))) => {
-                infcx.type_variables
+                infcx.inner
                     .borrow_mut()
+                    .type_variables()
                     .relate_vars(a_id, SubtypeOf, b_id);
                 Ok(a)
             }
@@ -95,7 +96,9 @@ impl<'a, 'tcx> TypeRelation<'a, 'tcx> for Sub<'a, 'tcx> {
             }
 
             _ => {
-                combine::super_combine_tys(self.fields.infcx, self, a, b)?;
+                if !self.fields.defer_projection_eq(a, b) {
+                    combine::super_combine_tys(self.fields.infcx, self, a, b)?;
+                }
                 Ok(a)
             }
         }
@@ -108,10 +111,15 @@ impl<'a, 'tcx> TypeRelation<'a, 'tcx> for Sub<'a, 'tcx> {
         // from the "cause" field, we could perhaps give more tailored
         // error messages.
         let origin = SubregionOrigin::Subtype(self.fields.trace.clone());
-        self.fields.infcx.region_vars.make_subregion(origin, a, b);
+        let infcx = self.fields.infcx;
+        infcx.region_vars.make_subregion(&mut infcx.inner.borrow_mut().undo_log, origin, a, b);
         Ok(a)
     }
 
+    fn consts(&mut self, a: &ty::Const, b: &ty::Const) -> RelateResult<'tcx, ty::Const> {
+        combine::super_combine_consts(self.fields.infcx, self, a, b)
+    }
+
     fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
                   -> RelateResult<'tcx, ty::Binder<T>>
         where T: Relate<'a,'tcx>
@@ -13,25 +13,116 @@ use self::TypeVariableValue::*;
 use self::UndoEntry::*;
 use hir::def_id::{DefId};
 use ty::{self, Ty};
+use syntax::ast;
 use syntax::codemap::Span;
 
-use std::cmp::min;
 use std::marker::PhantomData;
 use std::mem;
-use std::u32;
-use rustc_data_structures::snapshot_vec as sv;
-use rustc_data_structures::unify as ut;
+use rustc_data_structures::snapshot_vec::{self as sv, Rollback, UndoLogs};
+use rustc_data_structures::unify::{self as ut, UnifyKey};
+
+use super::undo_log::InferCtxtUndoLogs;
+
+/// The type variables of an inference context. Their changes are
+/// recorded into the context's shared undo log, so they are accessed
+/// through a `TypeVariableTable` borrowing both; see `with_log`.
+pub struct TypeVariableStorage<'tcx> {
+    values: sv::SnapshotVecStorage<Delegate<'tcx>>,
+    eq_relations: ut::UnificationStorage<ty::TyVid>,
+
+    /// Two variables are unified here if they are related by
+    /// subtyping, directly or through other variables, e.g. after
+    /// `?A <: ?B` and `?C :> ?B`, all three share a root. Unlike
+    /// `eq_relations`, this only records *that* they are related, and
+    /// not how; see `sub_unified`.
+    sub_relations: ut::UnificationStorage<TyVidSubKey>,
+
+    /// One entry per open snapshot, innermost last, holding the types
+    /// escaping that snapshot so far. See `types_escaping_snapshot`.
+    escapes: Vec<EscapeSet<'tcx>>,
+}
+
+/// The types that variables which existed before some snapshot have
+/// been instantiated with since it started.
+struct EscapeSet<'tcx> {
+    /// Number of type variables when the snapshot started; variables
+    /// with a lower index predate it.
+    num_vars: u32,
+    escaping: Vec<Ty<'tcx>>,
+}
 
-pub struct TypeVariableTable<'tcx> {
-    values: sv::SnapshotVec<Delegate<'tcx>>,
-    eq_relations: ut::UnificationTable<ty::TyVid>,
+/// Identifies the escape set of an open snapshot; see
+/// `TypeVariableStorage::start_snapshot`.
+pub struct Snapshot {
+    escape_depth: usize,
 }
 
-struct TypeVariableData<'tcx> {
+pub struct TypeVariableTable<'a, 'tcx: 'a> {
+    storage: &'a mut TypeVariableStorage<'tcx>,
+    undo_log: &'a mut InferCtxtUndoLogs<'tcx>,
+}
+
+pub struct TypeVariableData<'tcx> {
     value: TypeVariableValue<'tcx>,
+    origin: TypeVariableOrigin,
     diverging: bool
 }
 
+/// Why a type variable was created, so that an error about a variable
+/// whose type cannot be inferred can point at where it came from.
+#[derive(Copy, Clone, Debug)]
+pub enum TypeVariableOrigin {
+    MiscVariable(Span),
+    NormalizeProjectionType(Span),
+
+    /// A `_` written in a type, or the type of a local variable
+    /// declared without one.
+    TypeInference(Span),
+
+    /// The value of a type parameter at a use of the item declaring it.
+    TypeParameterDefinition(Span, ast::Name),
+
+    /// The type of an argument of a closure, or the closure's return
+    /// type, when its signature cannot be deduced from the context.
+    ClosureArgument(Span),
+    ClosureReturnType(Span),
+
+    /// The type of a variable captured by a closure.
+    ClosureUpvar(Span),
+
+    /// A type bound by a `for<T>` binder in a fn signature, instantiated
+    /// where the signature is used.
+    LateBoundInFn(Span),
+
+    /// The type of an expression that never produces a value, such
+    /// as `return` or a block ending in one.
+    Diverging(Span),
+
+    /// The type an expression is coerced to before it is used.
+    Adjustment(Span),
+
+    /// The least upper or greatest lower bound of two types.
+    LatticeVariable(Span),
+}
+
+impl TypeVariableOrigin {
+    pub fn span(&self) -> Span {
+        match *self {
+            TypeVariableOrigin::MiscVariable(span) |
+            TypeVariableOrigin::NormalizeProjectionType(span) |
+            TypeVariableOrigin::TypeInference(span) |
+            TypeVariableOrigin::TypeParameterDefinition(span, _) |
+            TypeVariableOrigin::ClosureArgument(span) |
+            TypeVariableOrigin::ClosureReturnType(span) |
+            TypeVariableOrigin::ClosureUpvar(span) |
+            TypeVariableOrigin::LateBoundInFn(span) |
+            TypeVariableOrigin::Diverging(span) |
+            TypeVariableOrigin::Adjustment(span) |
+            TypeVariableOrigin::LatticeVariable(span) => span,
+        }
+    }
+}
+
 enum TypeVariableValue<'tcx> {
     Known(Ty<'tcx>),
     Bounded {
@@ -51,21 +142,39 @@ pub struct Default<'tcx> {
     pub def_id: DefId
 }
 
-pub struct Snapshot {
-    snapshot: sv::Snapshot,
-    eq_snapshot: ut::Snapshot<ty::TyVid>,
+/// The key of the `sub_relations` table. It is distinct from `TyVid`
+/// so that the undo log entries of the two tables can be told apart.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TyVidSubKey {
+    vid: ty::TyVid,
+}
+
+impl UnifyKey for TyVidSubKey {
+    type Value = ();
+    fn index(&self) -> u32 { self.vid.index }
+    fn from_index(i: u32) -> TyVidSubKey { TyVidSubKey { vid: ty::TyVid { index: i } } }
+    fn tag(_: Option<TyVidSubKey>) -> &'static str { "TyVidSubKey" }
+}
+
+/// A change made to the type variables, as recorded in the undo log.
+pub enum UndoLog<'tcx> {
+    EqRelation(sv::UndoLog<ut::Delegate<ty::TyVid>>),
+    SubRelation(sv::UndoLog<ut::Delegate<TyVidSubKey>>),
+    Values(sv::UndoLog<Delegate<'tcx>>),
+    // A type was added to the escape sets from the given depth on.
+    Escaped(usize),
 }
 
-enum UndoEntry<'tcx> {
+pub enum UndoEntry<'tcx> {
     // The type of the var was specified.
     SpecifyVar(ty::TyVid, Vec<Relation>, Option<Default<'tcx>>),
     Relate(ty::TyVid, ty::TyVid),
     RelateRange(ty::TyVid, usize),
 }
 
-struct Delegate<'tcx>(PhantomData<&'tcx ()>);
+pub struct Delegate<'tcx>(PhantomData<&'tcx ()>);
 
-type Relation = (RelationDir, ty::TyVid);
+pub type Relation = (RelationDir, ty::TyVid);
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum RelationDir {
@@ -83,27 +192,127 @@ impl RelationDir {
     }
 }
 
-impl<'tcx> TypeVariableTable<'tcx> {
-    pub fn new() -> TypeVariableTable<'tcx> {
-        TypeVariableTable {
+impl<'tcx> TypeVariableStorage<'tcx> {
+    pub fn new() -> TypeVariableStorage<'tcx> {
+        TypeVariableStorage {
             values: sv::SnapshotVec::new(),
             eq_relations: ut::UnificationTable::new(),
+            sub_relations: ut::UnificationTable::new(),
+            escapes: vec![],
+        }
+    }
+
+    /// Borrows the type variables together with `undo_log`, into
+    /// which the changes made through the result are recorded.
+    pub fn with_log<'a>(&'a mut self, undo_log: &'a mut InferCtxtUndoLogs<'tcx>)
+                        -> TypeVariableTable<'a, 'tcx> {
+        TypeVariableTable { storage: self, undo_log: undo_log }
+    }
+
+    /// Called when the inference context starts a snapshot, to begin
+    /// collecting the types escaping it.
+    pub fn start_snapshot(&mut self) -> Snapshot {
+        self.escapes.push(EscapeSet {
+            num_vars: self.values.len() as u32,
+            escaping: vec![],
+        });
+        Snapshot { escape_depth: self.escapes.len() - 1 }
+    }
+
+    /// Called when the inference context commits or rolls back
+    /// `snapshot`, after reversing its entries in the undo log if
+    /// rolling back. Whatever escaped `snapshot` was recorded in the
+    /// enclosing snapshots as well, so there is nothing to merge.
+    pub fn end_snapshot(&mut self, snapshot: Snapshot) {
+        assert_eq!(self.escapes.len(), snapshot.escape_depth + 1);
+        self.escapes.pop();
+    }
+
+    pub fn types_escaping_snapshot(&self, s: &Snapshot) -> &[Ty<'tcx>] {
+        /*!
+         * Find the set of type variables that existed *before* `s`
+         * but which have only been unified since `s` started, and
+         * return the types with which they were unified. So if we had
+         * a type variable `V0`, then we started the snapshot, then we
+         * created a type variable `V1`, unifed `V0` with `T0`, and
+         * unified `V1` with `T1`, this function would return `{T0}`.
+         *
+         * The set is maintained by `instantiate_and_push` as variables
+         * get instantiated, so this does not walk the undo log.
+         */
+
+        let escaping_types = &self.escapes[s.escape_depth].escaping;
+        debug!("types_escaping_snapshot: {} types", escaping_types.len());
+        escaping_types
+    }
+
+    /// Returns the type variables created since `s` was started, in
+    /// the order they were created.
+    pub fn types_created_since_snapshot(&self, s: &Snapshot) -> Vec<ty::TyVid> {
+        let num_vars = self.escapes[s.escape_depth].num_vars;
+        (num_vars..self.values.len() as u32)
+            .map(|index| ty::TyVid { index: index })
+            .collect()
+    }
+}
+
+impl<'tcx> Rollback<UndoLog<'tcx>> for TypeVariableStorage<'tcx> {
+    fn reverse(&mut self, undo: UndoLog<'tcx>) {
+        match undo {
+            UndoLog::EqRelation(undo) => self.eq_relations.reverse(undo),
+            UndoLog::SubRelation(undo) => self.sub_relations.reverse(undo),
+            UndoLog::Values(undo) => self.values.reverse(undo),
+            UndoLog::Escaped(escape_depth) => {
+                // The escape sets of snapshots committed since then
+                // are gone, and the entries are reversed in the
+                // opposite order to the one they were pushed in.
+                for escape_set in self.escapes.iter_mut().skip(escape_depth) {
+                    escape_set.escaping.pop();
+                }
+            }
         }
     }
+}
 
-    fn relations<'a>(&'a mut self, a: ty::TyVid) -> &'a mut Vec<Relation> {
-        relations(self.values.get_mut(a.index as usize))
+impl<'a, 'tcx> TypeVariableTable<'a, 'tcx> {
+    fn values<'b>(&'b mut self)
+                  -> sv::SnapshotVec<Delegate<'tcx>,
+                                     &'b mut Vec<TypeVariableData<'tcx>>,
+                                     &'b mut InferCtxtUndoLogs<'tcx>> {
+        self.storage.values.with_log(&mut *self.undo_log)
+    }
+
+    fn eq_relations<'b>(&'b mut self)
+                        -> ut::UnificationTable<ty::TyVid,
+                                                &'b mut Vec<ut::VarValue<ty::TyVid>>,
+                                                &'b mut InferCtxtUndoLogs<'tcx>> {
+        self.storage.eq_relations.with_log(&mut *self.undo_log)
+    }
+
+    fn sub_relations<'b>(&'b mut self)
+                         -> ut::UnificationTable<TyVidSubKey,
+                                                 &'b mut Vec<ut::VarValue<TyVidSubKey>>,
+                                                 &'b mut InferCtxtUndoLogs<'tcx>> {
+        self.storage.sub_relations.with_log(&mut *self.undo_log)
+    }
+
+    fn relations<'b>(&'b mut self, a: ty::TyVid) -> &'b mut Vec<Relation> {
+        relations(self.storage.values.get_mut(a.index as usize))
     }
 
     pub fn default(&self, vid: ty::TyVid) -> Option<Default<'tcx>> {
-        match &self.values.get(vid.index as usize).value {
+        match &self.storage.values.get(vid.index as usize).value {
             &Known(_) => None,
             &Bounded { ref default, .. } => default.clone()
         }
     }
 
-    pub fn var_diverges<'a>(&'a self, vid: ty::TyVid) -> bool {
-        self.values.get(vid.index as usize).diverging
+    pub fn var_diverges(&self, vid: ty::TyVid) -> bool {
+        self.storage.values.get(vid.index as usize).diverging
+    }
+
+    pub fn var_origin(&self, vid: ty::TyVid) -> TypeVariableOrigin {
+        self.storage.values.get(vid.index as usize).origin
     }
 
     /// Records that `a <: b`, `a :> b`, or `a == b`, depending on `dir`.
@@ -113,29 +322,32 @@ impl<'tcx> TypeVariableTable<'tcx> {
         let a = self.root_var(a);
         let b = self.root_var(b);
         if a != b {
+            if dir != BiTo {
+                self.sub_relations().union(TyVidSubKey { vid: a }, TyVidSubKey { vid: b });
+            }
             if dir == EqTo {
                 // a and b must be equal which we mark in the unification table
-                let root = self.eq_relations.union(a, b);
+                let root = self.eq_relations().union(a, b);
                 // In addition to being equal, all relations from the variable which is no longer
                 // the root must be added to the root so they are not forgotten as the other
                 // variable should no longer be referenced (other than to get the root)
                 let other = if a == root { b } else { a };
                 let count = {
                     let (relations, root_relations) = if other.index < root.index {
-                        let (pre, post) = self.values.split_at_mut(root.index as usize);
+                        let (pre, post) = self.storage.values.split_at_mut(root.index as usize);
                         (relations(&mut pre[other.index as usize]), relations(&mut post[0]))
                     } else {
-                        let (pre, post) = self.values.split_at_mut(other.index as usize);
+                        let (pre, post) = self.storage.values.split_at_mut(other.index as usize);
                         (relations(&mut post[0]), relations(&mut pre[root.index as usize]))
                     };
                     root_relations.extend_from_slice(relations);
                     relations.len()
                 };
-                self.values.record(RelateRange(root, count));
+                self.values().record(RelateRange(root, count));
             } else {
                 self.relations(a).push((dir, b));
                 self.relations(b).push((dir.opposite(), a));
-                self.values.record(Relate(a, b));
+                self.values().record(Relate(a, b));
             }
         }
     }
@@ -153,7 +365,7 @@ impl<'tcx> TypeVariableTable<'tcx> {
     {
         debug_assert!(self.root_var(vid) == vid);
         let old_value = {
-            let value_ptr = &mut self.values.get_mut(vid.index as usize).value;
+            let value_ptr = &mut self.storage.values.get_mut(vid.index as usize).value;
             mem::replace(value_ptr, Known(ty))
         };
 
@@ -167,22 +379,70 @@ impl<'tcx> TypeVariableTable<'tcx> {
             stack.push((ty, dir, vid));
         }
 
-        self.values.record(SpecifyVar(vid, relations, default));
+        // `vid` escapes every open snapshot it predates. Inner snapshots
+        // started later and so have more variables predating them; once
+        // `vid` predates one, it predates all the inner ones as well.
+        let escape_depth = self.storage.escapes.iter().position(|e| vid.index < e.num_vars);
+        if let Some(escape_depth) = escape_depth {
+            for escape_set in &mut self.storage.escapes[escape_depth..] {
+                escape_set.escaping.push(ty);
+            }
+            self.undo_log.push(UndoLog::Escaped(escape_depth));
+        }
+
+        self.values().record(SpecifyVar(vid, relations, default));
     }
 
     pub fn new_var(&mut self,
                    diverging: bool,
+                   origin: TypeVariableOrigin,
                    default: Option<Default<'tcx>>) -> ty::TyVid {
-        self.eq_relations.new_key(());
-        let index = self.values.push(TypeVariableData {
+        self.eq_relations().new_key(());
+        self.sub_relations().new_key(());
+        let index = self.values().push(TypeVariableData {
             value: Bounded { relations: vec![], default: default },
+            origin: origin,
             diverging: diverging
         });
-        ty::TyVid { index: index as u32 }
+        let vid = ty::TyVid { index: index as u32 };
+        debug!("new_var(diverging={}, origin={:?}) -> {:?}", diverging, origin, vid);
+        vid
+    }
+
+    /// Replaces the type the (root) variable `vid` was instantiated
+    /// with by `ty`, which must be equivalent to it, e.g. because an
+    /// integral variable in it has since been resolved.
+    pub fn replace_known(&mut self, vid: ty::TyVid, ty: Ty<'tcx>) {
+        let index = vid.index as usize;
+        let (origin, diverging) = match *self.storage.values.get(index) {
+            TypeVariableData { value: Known(_), origin, diverging } => (origin, diverging),
+            _ => bug!("replacing the value of {:?}, which is not instantiated", vid),
+        };
+        debug!("replace_known: {:?} => {:?}", vid, ty);
+        self.values().set(index, TypeVariableData {
+            value: Known(ty),
+            origin: origin,
+            diverging: diverging,
+        });
     }
 
     pub fn root_var(&mut self, vid: ty::TyVid) -> ty::TyVid {
-        self.eq_relations.find(vid)
+        self.eq_relations().find(vid)
+    }
+
+    /// Returns the root of the set of variables `vid` is related to by
+    /// subtyping or equality, ignoring the direction of the relations.
+    /// Two unresolved variables with the same root will be resolved to
+    /// related types, so the root can stand for the whole set, e.g.
+    /// when deciding which variables a default or an error applies to.
+    pub fn sub_root_var(&mut self, vid: ty::TyVid) -> ty::TyVid {
+        self.sub_relations().find(TyVidSubKey { vid: vid }).vid
+    }
+
+    /// True if `a` and `b` are related by subtyping or equality,
+    /// directly or through other variables.
+    pub fn sub_unified(&mut self, a: ty::TyVid, b: ty::TyVid) -> bool {
+        self.sub_root_var(a) == self.sub_root_var(b)
     }
 
     pub fn probe(&mut self, vid: ty::TyVid) -> Option<Ty<'tcx>> {
@@ -193,7 +453,7 @@ impl<'tcx> TypeVariableTable<'tcx> {
     /// Retrieves the type of `vid` given that it is currently a root in the unification table
     pub fn probe_root(&mut self, vid: ty::TyVid) -> Option<Ty<'tcx>> {
         debug_assert!(self.root_var(vid) == vid);
-        match self.values.get(vid.index as usize).value {
+        match self.storage.values.get(vid.index as usize).value {
             Bounded { .. } => None,
             Known(t) => Some(t)
         }
@@ -211,71 +471,8 @@ impl<'tcx> TypeVariableTable<'tcx> {
         }
     }
 
-    pub fn snapshot(&mut self) -> Snapshot {
-        Snapshot {
-            snapshot: self.values.start_snapshot(),
-            eq_snapshot: self.eq_relations.snapshot(),
-        }
-    }
-
-    pub fn rollback_to(&mut self, s: Snapshot) {
-        self.values.rollback_to(s.snapshot);
-        self.eq_relations.rollback_to(s.eq_snapshot);
-    }
-
-    pub fn commit(&mut self, s: Snapshot) {
-        self.values.commit(s.snapshot);
-        self.eq_relations.commit(s.eq_snapshot);
-    }
-
-    pub fn types_escaping_snapshot(&mut self, s: &Snapshot) -> Vec<Ty<'tcx>> {
-        /*!
-         * Find the set of type variables that existed *before* `s`
-         * but which have only been unified since `s` started, and
-         * return the types with which they were unified. So if we had
-         * a type variable `V0`, then we started the snapshot, then we
-         * created a type variable `V1`, unifed `V0` with `T0`, and
-         * unified `V1` with `T1`, this function would return `{T0}`.
-         */
-
-        let mut new_elem_threshold = u32::MAX;
-        let mut escaping_types = Vec::new();
-        let actions_since_snapshot = self.values.actions_since_snapshot(&s.snapshot);
-        debug!("actions_since_snapshot.len() = {}", actions_since_snapshot.len());
-        for action in actions_since_snapshot {
-            match *action {
-                sv::UndoLog::NewElem(index) => {
-                    // if any new variables were created during the
-                    // snapshot, remember the lower index (which will
-                    // always be the first one we see). Note that this
-                    // action must precede those variables being
-                    // specified.
-                    new_elem_threshold = min(new_elem_threshold, index as u32);
-                    debug!("NewElem({}) new_elem_threshold={}", index, new_elem_threshold);
-                }
-
-                sv::UndoLog::Other(SpecifyVar(vid, _, _)) => {
-                    if vid.index < new_elem_threshold {
-                        // quick check to see if this variable was
-                        // created since the snapshot started or not.
-                        let escaping_type = match self.values.get(vid.index as usize).value {
-                            Bounded { .. } => bug!(),
-                            Known(ty) => ty,
-                        };
-                        escaping_types.push(escaping_type);
-                    }
-                    debug!("SpecifyVar({:?}) new_elem_threshold={}", vid, new_elem_threshold);
-                }
-
-                _ => { }
-            }
-        }
-
-        escaping_types
-    }
-
     pub fn unsolved_variables(&mut self) -> Vec<ty::TyVid> {
-        (0..self.values.len())
+        (0..self.storage.values.len())
             .filter_map(|i| {
                 let vid = ty::TyVid { index: i as u32 };
                 if self.probe(vid).is_some() {
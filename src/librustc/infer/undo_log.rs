@@ -0,0 +1,245 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The undo log shared by all the tables of an inference context.
+//!
+//! Each table (type, int/float and constant variables, region variables
+//! and their constraints) records the changes it makes into this one
+//! log while a snapshot is open. Starting or committing a snapshot
+//! therefore only has to note a position in the log, and rolling back
+//! pops the entries recorded since that position, handing each back
+//! to the table it came from (see `InferCtxt::rollback_to`).
+
+use super::const_variable;
+use super::region_inference;
+use super::type_variable;
+use ty;
+
+use rustc_data_structures::snapshot_vec::{self as sv, UndoLogs};
+use rustc_data_structures::unify as ut;
+
+/// A change made to one of the tables of an inference context.
+pub enum UndoLog<'tcx> {
+    TypeVariables(type_variable::UndoLog<'tcx>),
+    IntUnificationTable(sv::UndoLog<ut::Delegate<ty::IntVid>>),
+    FloatUnificationTable(sv::UndoLog<ut::Delegate<ty::FloatVid>>),
+    ConstVariables(const_variable::UndoLog),
+    RegionUnificationTable(sv::UndoLog<ut::Delegate<ty::RegionVid>>),
+    RegionVars(region_inference::UndoLogEntry),
+    PushRegionObligation,
+}
+
+impl<'tcx> UndoLog<'tcx> {
+    /// Whether this entry concerns the region variables or the
+    /// constraints between them, rather than type variables.
+    pub fn is_region(&self) -> bool {
+        match *self {
+            UndoLog::RegionUnificationTable(..) |
+            UndoLog::RegionVars(..) |
+            UndoLog::PushRegionObligation => true,
+            UndoLog::TypeVariables(..) |
+            UndoLog::IntUnificationTable(..) |
+            UndoLog::FloatUnificationTable(..) |
+            UndoLog::ConstVariables(..) => false,
+        }
+    }
+}
+
+impl<'tcx> From<type_variable::UndoLog<'tcx>> for UndoLog<'tcx> {
+    fn from(l: type_variable::UndoLog<'tcx>) -> Self {
+        UndoLog::TypeVariables(l)
+    }
+}
+
+impl<'tcx> From<sv::UndoLog<type_variable::Delegate<'tcx>>> for UndoLog<'tcx> {
+    fn from(l: sv::UndoLog<type_variable::Delegate<'tcx>>) -> Self {
+        UndoLog::TypeVariables(type_variable::UndoLog::Values(l))
+    }
+}
+
+impl<'tcx> From<sv::UndoLog<ut::Delegate<ty::TyVid>>> for UndoLog<'tcx> {
+    fn from(l: sv::UndoLog<ut::Delegate<ty::TyVid>>) -> Self {
+        UndoLog::TypeVariables(type_variable::UndoLog::EqRelation(l))
+    }
+}
+
+impl<'tcx> From<sv::UndoLog<ut::Delegate<type_variable::TyVidSubKey>>> for UndoLog<'tcx> {
+    fn from(l: sv::UndoLog<ut::Delegate<type_variable::TyVidSubKey>>) -> Self {
+        UndoLog::TypeVariables(type_variable::UndoLog::SubRelation(l))
+    }
+}
+
+impl<'tcx> From<sv::UndoLog<ut::Delegate<ty::IntVid>>> for UndoLog<'tcx> {
+    fn from(l: sv::UndoLog<ut::Delegate<ty::IntVid>>) -> Self {
+        UndoLog::IntUnificationTable(l)
+    }
+}
+
+impl<'tcx> From<sv::UndoLog<ut::Delegate<ty::FloatVid>>> for UndoLog<'tcx> {
+    fn from(l: sv::UndoLog<ut::Delegate<ty::FloatVid>>) -> Self {
+        UndoLog::FloatUnificationTable(l)
+    }
+}
+
+impl<'tcx> From<const_variable::UndoLog> for UndoLog<'tcx> {
+    fn from(l: const_variable::UndoLog) -> Self {
+        UndoLog::ConstVariables(l)
+    }
+}
+
+impl<'tcx> From<sv::UndoLog<ut::Delegate<ty::RegionVid>>> for UndoLog<'tcx> {
+    fn from(l: sv::UndoLog<ut::Delegate<ty::RegionVid>>) -> Self {
+        UndoLog::RegionUnificationTable(l)
+    }
+}
+
+impl<'tcx> From<region_inference::UndoLogEntry> for UndoLog<'tcx> {
+    fn from(l: region_inference::UndoLogEntry) -> Self {
+        UndoLog::RegionVars(l)
+    }
+}
+
+/// The variables created or changed by the entries recorded since some
+/// snapshot; see `InferCtxtUndoLogs::touched_since`.
+pub struct TouchedVars {
+    pub ty_vars: Vec<ty::TyVid>,
+    pub int_vars: Vec<ty::IntVid>,
+    pub float_vars: Vec<ty::FloatVid>,
+}
+
+/// A position in the undo log. Snapshots are tokens that should be
+/// created and consumed linearly, in a stack discipline.
+pub struct Snapshot {
+    undo_len: usize,
+}
+
+pub struct InferCtxtUndoLogs<'tcx> {
+    logs: Vec<UndoLog<'tcx>>,
+
+    // Nothing is recorded while this is zero: changes made outside of
+    // any snapshot can never be rolled back.
+    num_open_snapshots: usize,
+}
+
+impl<'tcx, T> UndoLogs<T> for InferCtxtUndoLogs<'tcx> where UndoLog<'tcx>: From<T> {
+    fn in_snapshot(&self) -> bool {
+        self.num_open_snapshots > 0
+    }
+
+    fn push(&mut self, undo: T) {
+        if self.num_open_snapshots > 0 {
+            self.logs.push(undo.into());
+        }
+    }
+}
+
+impl<'tcx> InferCtxtUndoLogs<'tcx> {
+    pub fn new() -> InferCtxtUndoLogs<'tcx> {
+        InferCtxtUndoLogs {
+            logs: Vec::new(),
+            num_open_snapshots: 0,
+        }
+    }
+
+    pub fn in_snapshot(&self) -> bool {
+        self.num_open_snapshots > 0
+    }
+
+    pub fn start_snapshot(&mut self) -> Snapshot {
+        self.num_open_snapshots += 1;
+        Snapshot { undo_len: self.logs.len() }
+    }
+
+    pub fn actions_since_snapshot(&self, snapshot: &Snapshot) -> &[UndoLog<'tcx>] {
+        &self.logs[snapshot.undo_len..]
+    }
+
+    pub fn actions_since_snapshot_mut(&mut self, snapshot: &Snapshot) -> &mut [UndoLog<'tcx>] {
+        &mut self.logs[snapshot.undo_len..]
+    }
+
+    /// Collects the type, integral and float variables that were
+    /// created, unified or instantiated since `snapshot` was started.
+    pub fn touched_since(&self, snapshot: &Snapshot) -> TouchedVars {
+        let mut touched = TouchedVars { ty_vars: vec![], int_vars: vec![], float_vars: vec![] };
+        for undo in self.actions_since_snapshot(snapshot) {
+            match *undo {
+                UndoLog::TypeVariables(type_variable::UndoLog::EqRelation(ref undo)) => {
+                    match *undo {
+                        sv::UndoLog::NewElem(i) | sv::UndoLog::SetElem(i, _) => {
+                            touched.ty_vars.push(ty::TyVid { index: i as u32 });
+                        }
+                        sv::UndoLog::Other(_) => {}
+                    }
+                }
+                UndoLog::TypeVariables(type_variable::UndoLog::Values(
+                        sv::UndoLog::Other(type_variable::UndoEntry::SpecifyVar(vid, ..)))) => {
+                    touched.ty_vars.push(vid);
+                }
+                UndoLog::IntUnificationTable(sv::UndoLog::NewElem(i)) |
+                UndoLog::IntUnificationTable(sv::UndoLog::SetElem(i, _)) => {
+                    touched.int_vars.push(ty::IntVid { index: i as u32 });
+                }
+                UndoLog::FloatUnificationTable(sv::UndoLog::NewElem(i)) |
+                UndoLog::FloatUnificationTable(sv::UndoLog::SetElem(i, _)) => {
+                    touched.float_vars.push(ty::FloatVid { index: i as u32 });
+                }
+                _ => {}
+            }
+        }
+        touched.ty_vars.sort_by_key(|vid| vid.index);
+        touched.ty_vars.dedup();
+        touched.int_vars.sort_by_key(|vid| vid.index);
+        touched.int_vars.dedup();
+        touched.float_vars.sort_by_key(|vid| vid.index);
+        touched.float_vars.dedup();
+        touched
+    }
+
+    /// Pops the most recent entry recorded since `snapshot` was
+    /// started, for the caller to reverse.
+    pub fn pop_since(&mut self, snapshot: &Snapshot) -> Option<UndoLog<'tcx>> {
+        self.assert_open_snapshot(snapshot);
+        if self.logs.len() > snapshot.undo_len {
+            self.logs.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Closes `snapshot` once all of its entries have been popped
+    /// and reversed.
+    pub fn rollback_to(&mut self, snapshot: Snapshot) {
+        debug!("rollback_to({})", snapshot.undo_len);
+        self.assert_open_snapshot(&snapshot);
+        assert!(self.logs.len() == snapshot.undo_len);
+        self.num_open_snapshots -= 1;
+    }
+
+    /// Closes `snapshot`, keeping its entries. Of course, they can
+    /// still be undone if there is a snapshot further out.
+    pub fn commit(&mut self, snapshot: Snapshot) {
+        debug!("commit({})", snapshot.undo_len);
+        self.assert_open_snapshot(&snapshot);
+
+        if self.num_open_snapshots == 1 {
+            // The root snapshot: nothing can be rolled back anymore.
+            assert!(snapshot.undo_len == 0);
+            self.logs.clear();
+        }
+        self.num_open_snapshots -= 1;
+    }
+
+    fn assert_open_snapshot(&self, snapshot: &Snapshot) {
+        // Or else there was a failure to follow a stack discipline:
+        assert!(self.num_open_snapshots > 0);
+        assert!(self.logs.len() >= snapshot.undo_len);
+    }
+}
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use middle::const_val::ConstVal;
 use syntax::ast;
 use ty::{self, IntVarValue, Ty, TyCtxt};
 use rustc_data_structures::unify::{Combine, UnifyKey};
@@ -74,6 +75,15 @@ impl<'tcx> ToType<'tcx> for ast::FloatTy {
     }
 }
 
+// Constant keys
+
+impl UnifyKey for ty::ConstVid {
+    type Value = Option<ConstVal>;
+    fn index(&self) -> u32 { self.index }
+    fn from_index(i: u32) -> ty::ConstVid { ty::ConstVid { index: i } }
+    fn tag(_: Option<ty::ConstVid>) -> &'static str { "ConstVid" }
+}
+
 impl UnifyKey for ty::TyVid {
     type Value = ();
     fn index(&self) -> u32 { self.index }
@@ -84,6 +84,7 @@ pub mod middle {
     pub mod cstore;
     pub mod dataflow;
     pub mod dead;
+    pub mod diagnostic_items;
     pub mod dependency_format;
     pub mod effect;
     pub mod entry;
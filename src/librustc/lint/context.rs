@@ -33,12 +33,14 @@ use lint::{Level, LevelSource, Lint, LintId, LintArray, LintPass};
 use lint::{EarlyLintPass, EarlyLintPassObject, LateLintPass, LateLintPassObject};
 use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid};
 use lint::builtin;
+use util::common::duration_to_secs_str;
 use util::nodemap::FnvHashMap;
 
 use std::cell::RefCell;
 use std::cmp;
 use std::default::Default as StdDefault;
 use std::mem;
+use std::time::{Duration, Instant};
 use syntax::attr::{self, AttrMetaMethods};
 use syntax::codemap::Span;
 use syntax::errors::DiagnosticBuilder;
@@ -59,11 +61,12 @@ pub struct LintStore {
     /// added by a plugin.
     lints: Vec<(&'static Lint, bool)>,
 
-    /// Trait objects for each lint pass.
+    /// Trait objects for each lint pass, along with the time spent in
+    /// each when `-Z time-lints` is given.
     /// This is only `None` while iterating over the objects. See the definition
     /// of run_lints.
-    early_passes: Option<Vec<EarlyLintPassObject>>,
-    late_passes: Option<Vec<LateLintPassObject>>,
+    early_passes: Option<Vec<(EarlyLintPassObject, PassTiming)>>,
+    late_passes: Option<Vec<(LateLintPassObject, PassTiming)>>,
 
     /// Lints indexed by name.
     by_name: FnvHashMap<String, TargetLint>,
@@ -91,6 +94,34 @@ pub struct FutureIncompatibleInfo {
     pub reference: &'static str // e.g., a URL for an issue/PR/RFC or error code
 }
 
+/// Time spent running a single lint pass, reported by `-Z time-lints`.
+struct PassTiming {
+    /// The lints declared by the pass, which is how the user can tell
+    /// which pass (and so which plugin) the time is attributed to.
+    name: String,
+    from_plugin: bool,
+    time: Duration,
+}
+
+impl PassTiming {
+    fn new<P: LintPass + ?Sized>(pass: &P, index: usize, from_plugin: bool) -> PassTiming {
+        let lints = pass.get_lints();
+        let name = if lints.is_empty() {
+            format!("<pass #{}>", index)
+        } else {
+            lints.iter()
+                 .map(|lint| lint.name_lower())
+                 .collect::<Vec<_>>()
+                 .join(", ")
+        };
+        PassTiming {
+            name: name,
+            from_plugin: from_plugin,
+            time: Duration::new(0, 0),
+        }
+    }
+}
+
 /// The targed of the `by_name` map, which accounts for renaming/deprecation.
 enum TargetLint {
     /// A direct lint target
@@ -156,7 +187,9 @@ impl LintStore {
                                from_plugin: bool,
                                pass: EarlyLintPassObject) {
         self.push_pass(sess, from_plugin, &pass);
-        self.early_passes.as_mut().unwrap().push(pass);
+        let passes = self.early_passes.as_mut().unwrap();
+        let timing = PassTiming::new(&*pass, passes.len(), from_plugin);
+        passes.push((pass, timing));
     }
 
     pub fn register_late_pass(&mut self,
@@ -164,7 +197,9 @@ impl LintStore {
                               from_plugin: bool,
                               pass: LateLintPassObject) {
         self.push_pass(sess, from_plugin, &pass);
-        self.late_passes.as_mut().unwrap().push(pass);
+        let passes = self.late_passes.as_mut().unwrap();
+        let timing = PassTiming::new(&*pass, passes.len(), from_plugin);
+        passes.push((pass, timing));
     }
 
     // Helper method for register_early/late_pass
@@ -341,12 +376,39 @@ macro_rules! run_lints { ($cx:expr, $f:ident, $ps:ident, $($args:expr),*) => ({
     // Move the vector of passes out of `$cx` so that we can
     // iterate over it mutably while passing `$cx` to the methods.
     let mut passes = $cx.mut_lints().$ps.take().unwrap();
-    for obj in &mut passes {
-        obj.$f($cx, $($args),*);
+    if $cx.sess().opts.debugging_opts.time_lints {
+        for &mut (ref mut obj, ref mut timing) in &mut passes {
+            let start = Instant::now();
+            obj.$f($cx, $($args),*);
+            timing.time = timing.time + start.elapsed();
+        }
+    } else {
+        for &mut (ref mut obj, _) in &mut passes {
+            obj.$f($cx, $($args),*);
+        }
     }
     $cx.mut_lints().$ps = Some(passes);
 }) }
 
+/// Print the time spent in each lint pass, slowest first, for `-Z time-lints`.
+fn print_pass_timings<'a, I>(kind: &str, timings: I)
+    where I: Iterator<Item=&'a PassTiming>
+{
+    let mut timings = timings.collect::<Vec<_>>();
+    timings.sort_by(|a, b| b.time.cmp(&a.time));
+
+    let mut total = Duration::new(0, 0);
+    for timing in timings {
+        total = total + timing.time;
+        println!("time: {}\t{} lint pass ({}): {}",
+                 duration_to_secs_str(timing.time),
+                 kind,
+                 if timing.from_plugin { "plugin" } else { "builtin" },
+                 timing.name);
+    }
+    println!("time: {}\ttotal {} lint passes", duration_to_secs_str(total), kind);
+}
+
 /// Parse the lint attributes into a vector, with `Err`s for malformed lint
 /// attributes. Writing this as an iterator is an enormous mess.
 // See also the hir version just below.
@@ -501,13 +563,25 @@ pub trait LintContext: Sized {
         })
     }
 
-    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str) {
-        let (level, src) = match self.level_src(lint) {
-            None => return,
-            Some(pair) => pair,
-        };
+    /// Like `level_src`, but for a lint reported at `span`. A lint
+    /// inside the expansion of a macro from another crate is allowed
+    /// if that macro was marked `#[allow_internal_lints]`, since the
+    /// users of the macro cannot fix it. Forbidden lints stay forbidden.
+    fn level_src_at(&self, lint: &'static Lint, span: Option<Span>) -> Option<LevelSource> {
+        let lvlsrc = self.level_src(lint);
+        match (lvlsrc, span) {
+            (Some((level, _)), Some(span)) if level != Forbid => {
+                match self.sess().codemap().foreign_macro_expansion(span) {
+                    Some((_, ref callee)) if callee.allow_internal_lints => None,
+                    _ => lvlsrc,
+                }
+            }
+            _ => lvlsrc,
+        }
+    }
 
-        raw_emit_lint(&self.sess(), self.lints(), lint, (level, src), span, msg);
+    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str) {
+        self.lookup(lint, span, msg).emit();
     }
 
     fn lookup(&self,
@@ -515,12 +589,27 @@ pub trait LintContext: Sized {
               span: Option<Span>,
               msg: &str)
               -> DiagnosticBuilder {
-        let (level, src) = match self.level_src(lint) {
+        let (level, src) = match self.level_src_at(lint, span) {
             None => return self.sess().diagnostic().struct_dummy(),
             Some(pair) => pair,
         };
 
-        raw_struct_lint(&self.sess(), self.lints(), lint, (level, src), span, msg)
+        let mut err = raw_struct_lint(&self.sess(), self.lints(), lint, (level, src), span, msg);
+
+        // Lints in code that came from another crate's macro are still
+        // reported, but against the macro rather than just the user.
+        if let Some((call_site, callee)) =
+                span.and_then(|sp| self.sess().codemap().foreign_macro_expansion(sp)) {
+            if level != Allow {
+                err.span_note(call_site,
+                              &format!("this lint originates in the `{}!` macro, \
+                                        which is defined in crate `{}`",
+                                       callee.name(),
+                                       callee.def_crate.unwrap()));
+            }
+        }
+
+        err
     }
 
     /// Emit a lint at the appropriate level, for a particular span.
@@ -1257,6 +1346,11 @@ pub fn check_crate(tcx: &TyCtxt, access_levels: &AccessLevels) {
 
     *tcx.node_lint_levels.borrow_mut() = cx.node_levels.into_inner();
 
+    if tcx.sess.opts.debugging_opts.time_lints {
+        let passes = cx.lints.late_passes.as_ref().unwrap();
+        print_pass_timings("late", passes.iter().map(|&(_, ref timing)| timing));
+    }
+
     // Put the lint store back in the session.
     mem::replace(&mut *tcx.sess.lint_store.borrow_mut(), cx.lints);
 }
@@ -1282,6 +1376,11 @@ pub fn check_ast_crate(sess: &Session, krate: &ast::Crate) {
         run_lints!(cx, check_crate_post, early_passes, krate);
     });
 
+    if sess.opts.debugging_opts.time_lints {
+        let passes = cx.lints.early_passes.as_ref().unwrap();
+        print_pass_timings("early", passes.iter().map(|&(_, ref timing)| timing));
+    }
+
     // Put the lint store back in the session.
     mem::replace(&mut *sess.lint_store.borrow_mut(), cx.lints);
 
@@ -12,6 +12,8 @@ use syntax::parse::token::InternedString;
 use syntax::ast;
 use std::rc::Rc;
 use hir::def_id::DefId;
+use std::cmp::Ordering;
+use std::fmt;
 use std::hash;
 use std::mem::transmute;
 use rustc_const_math::*;
@@ -98,4 +100,29 @@ impl ConstVal {
             Dummy => "dummy value",
         }
     }
+
+    /// Orders two integral, character or boolean constants of the same
+    /// type, e.g. to sort the values of a `SwitchInt`. Returns `None` for
+    /// any other pair.
+    pub fn try_cmp(&self, other: &ConstVal) -> Option<Ordering> {
+        match (self, other) {
+            (&Integral(a), &Integral(b)) => a.try_cmp(b).ok(),
+            (&Char(a), &Char(b)) => Some(a.cmp(&b)),
+            (&Bool(a), &Bool(b)) => Some(a.cmp(&b)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConstVal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Float(x) => write!(f, "{}", x),
+            Integral(i) => write!(f, "{}", i),
+            Str(ref s) => write!(f, "{:?}", s),
+            Bool(b) => write!(f, "{}", b),
+            Char(c) => write!(f, "{:?}", c),
+            _ => write!(f, "{}", self.description()),
+        }
+    }
 }
@@ -218,6 +218,7 @@ pub trait CrateStore<'tcx> : Any {
                                     -> Vec<(ast::CrateNum, LinkagePreference)>;
     fn lang_items(&self, cnum: ast::CrateNum) -> Vec<(DefIndex, usize)>;
     fn missing_lang_items(&self, cnum: ast::CrateNum) -> Vec<lang_items::LangItem>;
+    fn diagnostic_items(&self, cnum: ast::CrateNum) -> Vec<(DefIndex, ast::Name)>;
     fn is_staged_api(&self, cnum: ast::CrateNum) -> bool;
     fn is_explicitly_linked(&self, cnum: ast::CrateNum) -> bool;
     fn is_allocator(&self, cnum: ast::CrateNum) -> bool;
@@ -235,10 +236,13 @@ pub trait CrateStore<'tcx> : Any {
     fn plugin_registrar_fn(&self, cnum: ast::CrateNum) -> Option<DefId>;
     fn native_libraries(&self, cnum: ast::CrateNum) -> Vec<(NativeLibraryKind, String)>;
     fn reachable_ids(&self, cnum: ast::CrateNum) -> Vec<DefId>;
+    /// Every impl of a trait in the crate, whatever the trait.
+    fn crate_trait_impls(&self, cnum: ast::CrateNum) -> Vec<DefId>;
 
     // resolve
     fn def_key(&self, def: DefId) -> hir_map::DefKey;
     fn relative_def_path(&self, def: DefId) -> hir_map::DefPath;
+    fn retrace_path(&self, cnum: ast::CrateNum, path: &hir_map::DefPath) -> Option<DefId>;
     fn variant_kind(&self, def_id: DefId) -> Option<VariantKind>;
     fn struct_ctor_def_id(&self, struct_def_id: DefId) -> Option<DefId>;
     fn tuple_struct_definition_if_ctor(&self, did: DefId) -> Option<DefId>;
@@ -404,6 +408,8 @@ impl<'tcx> CrateStore<'tcx> for DummyCrateStore {
         { bug!("lang_items") }
     fn missing_lang_items(&self, cnum: ast::CrateNum) -> Vec<lang_items::LangItem>
         { bug!("missing_lang_items") }
+    fn diagnostic_items(&self, cnum: ast::CrateNum) -> Vec<(DefIndex, ast::Name)>
+        { bug!("diagnostic_items") }
     fn is_staged_api(&self, cnum: ast::CrateNum) -> bool { bug!("is_staged_api") }
     fn is_explicitly_linked(&self, cnum: ast::CrateNum) -> bool { bug!("is_explicitly_linked") }
     fn is_allocator(&self, cnum: ast::CrateNum) -> bool { bug!("is_allocator") }
@@ -425,10 +431,13 @@ impl<'tcx> CrateStore<'tcx> for DummyCrateStore {
     fn native_libraries(&self, cnum: ast::CrateNum) -> Vec<(NativeLibraryKind, String)>
         { bug!("native_libraries") }
     fn reachable_ids(&self, cnum: ast::CrateNum) -> Vec<DefId> { bug!("reachable_ids") }
+    fn crate_trait_impls(&self, cnum: ast::CrateNum) -> Vec<DefId> { vec![] }
 
     // resolve
     fn def_key(&self, def: DefId) -> hir_map::DefKey { bug!("def_key") }
     fn relative_def_path(&self, def: DefId) -> hir_map::DefPath { bug!("relative_def_path") }
+    fn retrace_path(&self, cnum: ast::CrateNum, path: &hir_map::DefPath) -> Option<DefId>
+        { bug!("retrace_path") }
     fn variant_kind(&self, def_id: DefId) -> Option<VariantKind> { bug!("variant_kind") }
     fn struct_ctor_def_id(&self, struct_def_id: DefId) -> Option<DefId>
         { bug!("struct_ctor_def_id") }
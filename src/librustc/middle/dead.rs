@@ -14,6 +14,7 @@
 
 use dep_graph::DepNode;
 use hir::map as ast_map;
+use hir::map::DefPathData;
 use hir::{self, pat_util, PatKind};
 use hir::intravisit::{self, Visitor};
 
@@ -321,27 +322,44 @@ fn has_allow_dead_code_or_lang_attr(attrs: &[ast::Attribute]) -> bool {
 //   or
 //   2) We are not sure to be live or not
 //     * Implementation of a trait method
-struct LifeSeeder {
-    worklist: Vec<ast::NodeId>
+//     * Item whose name is mentioned by code that was configured away
+//       only because of whether we are building with `--test`
+//         - This is so that helpers used only by `#[cfg(test)]` code are
+//           not reported as dead in a normal build.
+struct LifeSeeder<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+    worklist: Vec<ast::NodeId>,
+}
+
+impl<'a, 'tcx> LifeSeeder<'a, 'tcx> {
+    fn used_by_other_test_config(&self, id: ast::NodeId, name: ast::Name) -> bool {
+        used_by_other_test_config(self.tcx, id, name)
+    }
 }
 
-impl<'v> Visitor<'v> for LifeSeeder {
+impl<'a, 'tcx, 'v> Visitor<'v> for LifeSeeder<'a, 'tcx> {
     fn visit_item(&mut self, item: &hir::Item) {
         let allow_dead_code = has_allow_dead_code_or_lang_attr(&item.attrs);
-        if allow_dead_code {
+        if allow_dead_code || self.used_by_other_test_config(item.id, item.name) {
             self.worklist.push(item.id);
         }
         match item.node {
-            hir::ItemEnum(ref enum_def, _) if allow_dead_code => {
-                self.worklist.extend(enum_def.variants.iter()
-                                                      .map(|variant| variant.node.data.id()));
+            hir::ItemEnum(ref enum_def, _) => {
+                for variant in &enum_def.variants {
+                    let id = variant.node.data.id();
+                    if allow_dead_code || self.used_by_other_test_config(id, variant.node.name) {
+                        self.worklist.push(id);
+                    }
+                }
             }
             hir::ItemTrait(_, _, _, ref trait_items) => {
                 for trait_item in trait_items {
                     match trait_item.node {
                         hir::ConstTraitItem(_, Some(_)) |
                         hir::MethodTraitItem(_, Some(_)) => {
-                            if has_allow_dead_code_or_lang_attr(&trait_item.attrs) {
+                            if has_allow_dead_code_or_lang_attr(&trait_item.attrs) ||
+                                    self.used_by_other_test_config(trait_item.id,
+                                                                   trait_item.name) {
                                 self.worklist.push(trait_item.id);
                             }
                         }
@@ -352,7 +370,8 @@ impl<'v> Visitor<'v> for LifeSeeder {
             hir::ItemImpl(_, _, _, ref opt_trait, _, ref impl_items) => {
                 for impl_item in impl_items {
                     if opt_trait.is_some() ||
-                            has_allow_dead_code_or_lang_attr(&impl_item.attrs) {
+                            has_allow_dead_code_or_lang_attr(&impl_item.attrs) ||
+                            self.used_by_other_test_config(impl_item.id, impl_item.name) {
                         self.worklist.push(impl_item.id);
                     }
                 }
@@ -362,6 +381,41 @@ impl<'v> Visitor<'v> for LifeSeeder {
     }
 }
 
+/// Whether code configured away only because of whether we are building
+/// with `--test` may use the item, variant or field `id`, named `name`.
+/// That code is never resolved, so this goes by where it mentions `name`;
+/// see `OtherTestConfigNames::may_use`.
+fn used_by_other_test_config(tcx: &TyCtxt, id: ast::NodeId, name: ast::Name) -> bool {
+    let path = tcx.map.def_path(tcx.map.local_def_id(id));
+    let parents = match path.data.split_last() {
+        Some((_, parents)) => parents,
+        None => return false,
+    };
+    let module: Vec<_> = parents.iter().filter_map(|parent| {
+        match parent.data {
+            DefPathData::Module(name) => Some(name),
+            _ => None,
+        }
+    }).collect();
+    let parent = match parents.last().map(|parent| &parent.data) {
+        Some(&DefPathData::TypeNs(name)) => Some(name),
+        // An inherent or trait impl item is mentioned through the type.
+        Some(&DefPathData::Impl) => {
+            match tcx.map.expect_item(tcx.map.get_parent(id)).node {
+                hir::ItemImpl(_, _, _, _, ref self_ty, _) => match self_ty.node {
+                    hir::TyPath(None, ref path) => {
+                        path.segments.last().map(|segment| segment.identifier.name)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    tcx.sess.other_test_config_names.borrow().may_use(name, &module, parent)
+}
+
 fn create_and_seed_worklist(tcx: &TyCtxt,
                             access_levels: &privacy::AccessLevels,
                             krate: &hir::Crate) -> Vec<ast::NodeId> {
@@ -378,7 +432,8 @@ fn create_and_seed_worklist(tcx: &TyCtxt,
 
     // Seed implemented trait items
     let mut life_seeder = LifeSeeder {
-        worklist: worklist
+        tcx: tcx,
+        worklist: worklist,
     };
     krate.visit_all_items(&mut life_seeder);
 
@@ -433,6 +488,7 @@ impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
             && !self.symbol_is_live(field.id, None)
             && !is_marker_field
             && !has_allow_dead_code_or_lang_attr(&field.attrs)
+            && !used_by_other_test_config(self.tcx, field.id, field.name)
     }
 
     fn should_warn_about_variant(&mut self, variant: &hir::Variant_) -> bool {
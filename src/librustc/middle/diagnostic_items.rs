@@ -0,0 +1,159 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Detecting diagnostic items.
+//
+// Diagnostic items are items that lints and error messages want to
+// refer to by name, such as `Option` or `transmute`. Unlike language
+// items they carry no meaning for the language itself; the compiler
+// merely needs a way to find them that does not depend on the path at
+// which a library happens to define or re-export them. Libraries mark
+// such items with `#[rustc_diagnostic_item = "Name"]`.
+
+use dep_graph::DepNode;
+use hir::map as hir_map;
+use session::Session;
+use middle::cstore::CrateStore;
+use hir::def_id::DefId;
+use util::nodemap::FnvHashMap;
+
+use syntax::ast;
+use syntax::attr::AttrMetaMethods;
+use syntax::parse::token::{self, InternedString};
+use hir::intravisit::Visitor;
+use hir;
+
+pub struct DiagnosticItems {
+    items: FnvHashMap<ast::Name, DefId>,
+}
+
+impl DiagnosticItems {
+    pub fn new() -> DiagnosticItems {
+        DiagnosticItems { items: FnvHashMap() }
+    }
+
+    /// The item registered under `name`, if any crate in the graph
+    /// defines one.
+    pub fn get(&self, name: &str) -> Option<DefId> {
+        self.items.get(&token::intern(name)).cloned()
+    }
+
+    /// True if `def_id` is the item registered under `name`.
+    pub fn is(&self, name: &str, def_id: DefId) -> bool {
+        self.get(name) == Some(def_id)
+    }
+
+    /// All diagnostic items, in no particular order.
+    pub fn iter<'a>(&'a self) -> Box<Iterator<Item=(ast::Name, DefId)> + 'a> {
+        box self.items.iter().map(|(&name, &def_id)| (name, def_id))
+    }
+}
+
+struct DiagnosticItemCollector<'a, 'tcx: 'a> {
+    items: DiagnosticItems,
+
+    ast_map: &'a hir_map::Map<'tcx>,
+
+    session: &'a Session,
+}
+
+impl<'a, 'v, 'tcx> Visitor<'v> for DiagnosticItemCollector<'a, 'tcx> {
+    fn visit_item(&mut self, item: &hir::Item) {
+        self.observe_item(item.id, &item.attrs);
+
+        // Intrinsics such as `transmute` live in foreign modules,
+        // which `visit_all_items` does not descend into.
+        if let hir::ItemForeignMod(ref foreign_mod) = item.node {
+            for foreign_item in &foreign_mod.items {
+                self.observe_item(foreign_item.id, &foreign_item.attrs);
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> DiagnosticItemCollector<'a, 'tcx> {
+    fn observe_item(&mut self, id: ast::NodeId, attrs: &[ast::Attribute]) {
+        if let Some(name) = extract(attrs) {
+            let def_id = self.ast_map.local_def_id(id);
+            self.collect_item(token::intern(&name), def_id);
+        }
+    }
+
+    fn collect_item(&mut self, name: ast::Name, item_def_id: DefId) {
+        // Check for duplicates.
+        if let Some(&original_def_id) = self.items.items.get(&name) {
+            if original_def_id != item_def_id {
+                let cstore = &self.session.cstore;
+                let mut err = match self.ast_map.span_if_local(item_def_id) {
+                    Some(span) => struct_span_err!(
+                        self.session,
+                        span,
+                        E0525,
+                        "duplicate diagnostic item found: `{}`.",
+                        name),
+                    None => self.session.struct_err(&format!(
+                            "duplicate diagnostic item in crate `{}`: `{}`.",
+                            cstore.crate_name(item_def_id.krate),
+                            name)),
+                };
+                if let Some(span) = self.ast_map.span_if_local(original_def_id) {
+                    span_note!(&mut err, span,
+                               "first defined here.");
+                } else {
+                    err.note(&format!("first defined in crate `{}`.",
+                                      cstore.crate_name(original_def_id.krate)));
+                }
+                err.emit();
+                return;
+            }
+        }
+
+        self.items.items.insert(name, item_def_id);
+    }
+
+    fn collect_external_diagnostic_items(&mut self) {
+        let cstore = &self.session.cstore;
+
+        for cnum in cstore.crates() {
+            for (index, name) in cstore.diagnostic_items(cnum) {
+                let def_id = DefId { krate: cnum, index: index };
+                self.collect_item(name, def_id);
+            }
+        }
+    }
+}
+
+pub fn extract(attrs: &[ast::Attribute]) -> Option<InternedString> {
+    for attribute in attrs {
+        match attribute.value_str() {
+            Some(ref value) if attribute.check_name("rustc_diagnostic_item") => {
+                return Some(value.clone());
+            }
+            _ => {}
+        }
+    }
+
+    return None;
+}
+
+pub fn collect_diagnostic_items(session: &Session,
+                                map: &hir_map::Map)
+                                -> DiagnosticItems {
+    let _task = map.dep_graph.in_task(DepNode::CollectDiagnosticItems);
+    let krate: &hir::Crate = map.krate();
+    let mut collector = DiagnosticItemCollector {
+        items: DiagnosticItems::new(),
+        ast_map: map,
+        session: session,
+    };
+    collector.collect_external_diagnostic_items();
+    krate.visit_all_items(&mut collector);
+    collector.items
+}
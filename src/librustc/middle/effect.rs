@@ -18,7 +18,7 @@ use ty::{self, Ty, TyCtxt};
 use ty::MethodCall;
 
 use syntax::ast;
-use syntax::codemap::Span;
+use syntax::codemap::{ExpnId, Span};
 use hir;
 use hir::intravisit;
 use hir::intravisit::{FnKind, Visitor};
@@ -27,11 +27,16 @@ use hir::intravisit::{FnKind, Visitor};
 struct UnsafeContext {
     push_unsafe_count: usize,
     root: RootUnsafeContext,
+    /// If `root` is an `unsafe` block written inside a macro marked
+    /// `#[allow_internal_unsafe]`, the expansion that block comes
+    /// from. Such a block only covers the code of that expansion, not
+    /// the code the macro's caller passed in.
+    macro_expansion: Option<ExpnId>,
 }
 
 impl UnsafeContext {
     fn new(root: RootUnsafeContext) -> UnsafeContext {
-        UnsafeContext { root: root, push_unsafe_count: 0 }
+        UnsafeContext { root: root, push_unsafe_count: 0, macro_expansion: None }
     }
 }
 
@@ -68,6 +73,18 @@ impl<'a, 'tcx> EffectCheckVisitor<'a, 'tcx> {
                           description);
             }
             UnsafeBlock(block_id) => {
+                if let Some(expn_id) = self.unsafe_context.macro_expansion {
+                    if !self.tcx.sess.codemap().span_is_within_expansion(span, expn_id) {
+                        struct_span_err!(self.tcx.sess, span, E0133,
+                                         "{} requires unsafe function or block",
+                                         description)
+                            .note("the enclosing `unsafe` block comes from a macro marked \
+                                   `#[allow_internal_unsafe]`, so it does not cover code \
+                                   passed to that macro")
+                            .emit();
+                        return;
+                    }
+                }
                 // OK, but record this.
                 debug!("effect: recording unsafe block as used: {}", block_id);
                 self.tcx.used_unsafe.borrow_mut().insert(block_id);
@@ -120,8 +137,23 @@ impl<'a, 'tcx, 'v> Visitor<'v> for EffectCheckVisitor<'a, 'tcx> {
                 // external blocks (e.g. `unsafe { println("") }`,
                 // expands to `unsafe { ... unsafe { ... } }` where
                 // the inner one is compiler generated).
-                if self.unsafe_context.root == SafeContext || source == hir::CompilerGenerated {
-                    self.unsafe_context.root = UnsafeBlock(block.id)
+                //
+                // An `unsafe` block inside a `#[allow_internal_unsafe]`
+                // macro does not cover the code the macro's caller
+                // passed in, so any `unsafe` block found inside of it
+                // also gets a new context.
+                if self.unsafe_context.root == SafeContext ||
+                   source == hir::CompilerGenerated ||
+                   self.unsafe_context.macro_expansion.is_some() {
+                    let codemap = self.tcx.sess.codemap();
+                    self.unsafe_context.root = UnsafeBlock(block.id);
+                    self.unsafe_context.macro_expansion =
+                        if source == hir::UserProvided &&
+                           codemap.span_allows_internal_unsafe(block.span) {
+                            Some(codemap.source_expn_id(block.span))
+                        } else {
+                            None
+                        };
                 }
             }
             hir::PushUnsafeBlock(..) => {
@@ -11,7 +11,7 @@
 use dep_graph::DepNode;
 use hir::def::Def;
 use hir::def_id::DefId;
-use ty::subst::{Subst, Substs, EnumeratedItems};
+use ty::subst::{Subst, Substs};
 use ty::{TransmuteRestriction, TyCtxt};
 use ty::{self, Ty, TypeFoldable};
 
@@ -27,8 +27,6 @@ pub fn check_crate(tcx: &TyCtxt) {
     let mut visitor = IntrinsicCheckingVisitor {
         tcx: tcx,
         param_envs: Vec::new(),
-        dummy_sized_ty: tcx.types.isize,
-        dummy_unsized_ty: tcx.mk_slice(tcx.types.isize),
     };
     tcx.visit_all_items_in_krate(DepNode::IntrinsicCheck, &mut visitor);
 }
@@ -41,13 +39,6 @@ struct IntrinsicCheckingVisitor<'a, 'tcx: 'a> {
     // call to `transmute`, we can check it in the context of the top
     // of the stack (which ought not to be empty).
     param_envs: Vec<ty::ParameterEnvironment<'a,'tcx>>,
-
-    // Dummy sized/unsized types that use to substitute for type
-    // parameters in order to estimate how big a type will be for any
-    // possible instantiation of the type parameters in scope.  See
-    // `check_transmute` for more details.
-    dummy_sized_ty: Ty<'tcx>,
-    dummy_unsized_ty: Ty<'tcx>,
 }
 
 impl<'a, 'tcx> IntrinsicCheckingVisitor<'a, 'tcx> {
@@ -60,18 +51,6 @@ impl<'a, 'tcx> IntrinsicCheckingVisitor<'a, 'tcx> {
     }
 
     fn check_transmute(&self, span: Span, from: Ty<'tcx>, to: Ty<'tcx>, id: ast::NodeId) {
-        // Find the parameter environment for the most recent function that
-        // we entered.
-
-        let param_env = match self.param_envs.last() {
-            Some(p) => p,
-            None => {
-                span_bug!(
-                    span,
-                    "transmute encountered outside of any fn");
-            }
-        };
-
         // Simple case: no type parameters involved.
         if
             !from.has_param_types() && !from.has_self_ty() &&
@@ -89,125 +68,61 @@ impl<'a, 'tcx> IntrinsicCheckingVisitor<'a, 'tcx> {
             return;
         }
 
-        // The rules around type parameters are a bit subtle. We are
-        // checking these rules before monomorphization, so there may
-        // be unsubstituted type parameters present in the
-        // types. Obviously we cannot create LLVM types for those.
-        // However, if a type parameter appears only indirectly (i.e.,
-        // through a pointer), it does not necessarily affect the
-        // size, so that should be allowed. The only catch is that we
-        // DO want to be careful around unsized type parameters, since
-        // fat pointers have a different size than a thin pointer, and
-        // hence `&T` and `&U` have different sizes if `T : Sized` but
-        // `U : Sized` does not hold.
-        //
-        // However, it's not as simple as checking whether `T :
-        // Sized`, because even if `T : Sized` does not hold, that
-        // just means that `T` *may* not be sized.  After all, even a
-        // type parameter `T: ?Sized` could be bound to a sized
-        // type. (Issue #20116)
-        //
-        // To handle this, we first check for "interior" type
-        // parameters, which are always illegal. If there are none of
-        // those, then we know that the only way that all type
-        // parameters `T` are referenced indirectly, e.g. via a
-        // pointer type like `&T`. In that case, we only care whether
-        // `T` is sized or not, because that influences whether `&T`
-        // is a thin or fat pointer.
-        //
-        // One could imagine establishing a sophisticated constraint
-        // system to ensure that the transmute is legal, but instead
-        // we do something brutally dumb. We just substitute dummy
-        // sized or unsized types for every type parameter in scope,
-        // exhaustively checking all possible combinations. Here are some examples:
-        //
-        // ```
-        // fn foo<T, U>() {
-        //     // T=int, U=int
-        // }
-        //
-        // fn bar<T: ?Sized, U>() {
-        //     // T=int, U=int
-        //     // T=[int], U=int
-        // }
-        //
-        // fn baz<T: ?Sized, U: ?Sized>() {
-        //     // T=int, U=int
-        //     // T=[int], U=int
-        //     // T=int, U=[int]
-        //     // T=[int], U=[int]
-        // }
-        // ```
-        //
-        // In all cases, we keep the original unsubstituted types
-        // around for error reporting.
+        // Find the parameter environment for the most recent function that
+        // we entered.
+        let param_env = match self.param_envs.last() {
+            Some(p) => p,
+            None => {
+                span_bug!(
+                    span,
+                    "transmute encountered outside of any fn");
+            }
+        };
 
+        // Transmutes involving type parameters are generally checked for
+        // each instantiation during trans, once their sizes are known. (Issue
+        // #20116: a check made here has to hold for every possible
+        // instantiation, and so would reject many transmutes that are fine.)
+        //
+        // There is one case we can still check conservatively up front: if
+        // every type parameter is only reached through a pointer and is
+        // known to be `Sized`, then every such pointer is thin, and the
+        // sizes of `from` and `to` do not depend on the instantiation at
+        // all. Substituting any sized type for the parameters then gives the
+        // sizes for every instantiation, e.g. for `&T` to `i8`.
         let from_tc = from.type_contents(self.tcx);
         let to_tc = to.type_contents(self.tcx);
         if from_tc.interior_param() || to_tc.interior_param() {
-            span_err!(self.tcx.sess, span, E0139,
-                      "cannot transmute to or from a type that contains \
-                       unsubstituted type parameters");
             return;
         }
 
-        let mut substs = param_env.free_substs.clone();
-        self.with_each_combination(
-            span,
-            param_env,
-            param_env.free_substs.types.iter_enumerated(),
-            &mut substs,
-            &mut |substs| {
-                let restriction = TransmuteRestriction {
-                    span: span,
-                    original_from: from,
-                    original_to: to,
-                    substituted_from: from.subst(self.tcx, substs),
-                    substituted_to: to.subst(self.tcx, substs),
-                    id: id,
-                };
-                self.push_transmute_restriction(restriction);
-            });
-    }
-
-    fn with_each_combination(&self,
-                             span: Span,
-                             param_env: &ty::ParameterEnvironment<'a,'tcx>,
-                             mut types_in_scope: EnumeratedItems<Ty<'tcx>>,
-                             substs: &mut Substs<'tcx>,
-                             callback: &mut FnMut(&Substs<'tcx>))
-    {
-        // This parameter invokes `callback` many times with different
-        // substitutions that replace all the parameters in scope with
-        // either `int` or `[int]`, depending on whether the type
-        // parameter is known to be sized. See big comment above for
-        // an explanation of why this is a reasonable thing to do.
-
-        match types_in_scope.next() {
-            None => {
-                debug!("with_each_combination(substs={:?})",
-                       substs);
-
-                callback(substs);
-            }
-
-            Some((space, index, &param_ty)) => {
-                debug!("with_each_combination: space={:?}, index={}, param_ty={:?}",
-                       space, index, param_ty);
-
-                if !param_ty.is_sized(param_env, span) {
-                    debug!("with_each_combination: param_ty is not known to be sized");
-
-                    substs.types.get_mut_slice(space)[index] = self.dummy_unsized_ty;
-                    self.with_each_combination(span, param_env, types_in_scope.clone(),
-                                               substs, callback);
-                }
-
-                substs.types.get_mut_slice(space)[index] = self.dummy_sized_ty;
-                self.with_each_combination(span, param_env, types_in_scope,
-                                           substs, callback);
+        // Projections are left to trans as well: their size is not known
+        // even behind a pointer, and they cannot be normalized once their
+        // parameters have been substituted away.
+        let size_is_known = from.walk().chain(to.walk()).all(|ty| {
+            match ty.sty {
+                ty::TyParam(_) => ty.is_sized(param_env, span),
+                ty::TyProjection(..) => false,
+                _ => true,
             }
+        });
+        if !size_is_known {
+            return;
         }
+
+        // We keep the original unsubstituted types around for error
+        // reporting.
+        let substs = Substs::new(param_env.free_substs.types.map(|_| self.tcx.types.isize),
+                                 param_env.free_substs.regions.clone());
+        let restriction = TransmuteRestriction {
+            span: span,
+            original_from: from,
+            original_to: to,
+            substituted_from: from.subst(self.tcx, &substs),
+            substituted_to: to.subst(self.tcx, &substs),
+            id: id,
+        };
+        self.push_transmute_restriction(restriction);
     }
 
     fn push_transmute_restriction(&self, restriction: TransmuteRestriction<'tcx>) {
@@ -232,6 +147,10 @@ impl<'a, 'tcx, 'v> Visitor<'v> for IntrinsicCheckingVisitor<'a, 'tcx> {
         }
     }
 
+    fn visit_body_id(&mut self, id: hir::BodyId) {
+        self.tcx.map.read_body(id);
+    }
+
     fn visit_expr(&mut self, expr: &hir::Expr) {
         if let hir::ExprPath(..) = expr.node {
             match self.tcx.resolve_expr(expr) {
@@ -327,6 +327,17 @@ pub enum TerminatorKind<'tcx> {
         /// Cleanups to be done if the call unwinds.
         cleanup: Option<BasicBlock>
     },
+
+    /// Block ends with a tail call (`become`): the callee takes over the
+    /// current function's stack frame, and its return value becomes this
+    /// function's return value. Nothing may remain to be dropped at this
+    /// point, so there is neither a destination nor a cleanup block.
+    TailCall {
+        /// The function that’s being called
+        func: Operand<'tcx>,
+        /// Arguments the function is called with
+        args: Vec<Operand<'tcx>>,
+    },
 }
 
 impl<'tcx> Terminator<'tcx> {
@@ -349,6 +360,7 @@ impl<'tcx> TerminatorKind<'tcx> {
             SwitchInt { targets: ref b, .. } => b[..].into_cow(),
             Resume => (&[]).into_cow(),
             Return => (&[]).into_cow(),
+            TailCall { .. } => (&[]).into_cow(),
             Call { destination: Some((_, t)), cleanup: Some(c), .. } => vec![t, c].into_cow(),
             Call { destination: Some((_, ref t)), cleanup: None, .. } =>
                 slice::ref_slice(t).into_cow(),
@@ -370,6 +382,7 @@ impl<'tcx> TerminatorKind<'tcx> {
             SwitchInt { targets: ref mut b, .. } => b.iter_mut().collect(),
             Resume => Vec::new(),
             Return => Vec::new(),
+            TailCall { .. } => Vec::new(),
             Call { destination: Some((_, ref mut t)), cleanup: Some(ref mut c), .. } => vec![t, c],
             Call { destination: Some((_, ref mut t)), cleanup: None, .. } => vec![t],
             Call { destination: None, cleanup: Some(ref mut c), .. } => vec![c],
@@ -456,6 +469,16 @@ impl<'tcx> TerminatorKind<'tcx> {
                 }
                 write!(fmt, ")")
             }
+            TailCall { ref func, ref args } => {
+                write!(fmt, "become {:?}(", func)?;
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        write!(fmt, ", ")?;
+                    }
+                    write!(fmt, "{:?}", arg)?;
+                }
+                write!(fmt, ")")
+            }
         }
     }
 
@@ -463,7 +486,7 @@ impl<'tcx> TerminatorKind<'tcx> {
     pub fn fmt_successor_labels(&self) -> Vec<Cow<'static, str>> {
         use self::TerminatorKind::*;
         match *self {
-            Return | Resume => vec![],
+            Return | Resume | TailCall { .. } => vec![],
             Goto { .. } => vec!["".into()],
             If { .. } => vec!["true".into(), "false".into()],
             Switch { ref adt_def, .. } => {
@@ -745,9 +768,20 @@ pub enum Rvalue<'tcx> {
 
     BinaryOp(BinOp, Operand<'tcx>, Operand<'tcx>),
 
+    /// Like `BinaryOp`, but yields a `(T, bool)` pair of the result and
+    /// whether the operation overflowed. Only used for `Add`, `Sub` and
+    /// `Mul` on integers, when overflow is checked.
+    CheckedBinaryOp(BinOp, Operand<'tcx>, Operand<'tcx>),
+
     UnaryOp(UnOp, Operand<'tcx>),
 
     /// Creates an *uninitialized* Box
+    ///
+    /// `box EXPR` is built as an explicit sequence: this rvalue
+    /// allocates the memory, `EXPR` is then evaluated directly into
+    /// the box's contents in a block of its own (with a free of the
+    /// allocation scheduled on the unwind path only), and the box is
+    /// used only after that block completes.
     Box(Ty<'tcx>),
 
     /// Create an aggregate value, like a tuple or struct.  This is
@@ -785,6 +819,9 @@ pub enum CastKind {
     /// Convert safe fn() to unsafe fn()
     UnsafeFnPointer,
 
+    /// Convert a non-capturing closure to fn()
+    ClosureFnPointer,
+
     /// "Unsize" -- convert a thin-or-fat pointer to a fat pointer.
     /// trans must figure out the details once full monomorphization
     /// is known. For example, this could be used to cast from a
@@ -855,6 +892,9 @@ impl<'tcx> Debug for Rvalue<'tcx> {
             Len(ref a) => write!(fmt, "Len({:?})", a),
             Cast(ref kind, ref lv, ref ty) => write!(fmt, "{:?} as {:?} ({:?})", lv, ty, kind),
             BinaryOp(ref op, ref a, ref b) => write!(fmt, "{:?}({:?}, {:?})", op, a, b),
+            CheckedBinaryOp(ref op, ref a, ref b) => {
+                write!(fmt, "Checked{:?}({:?}, {:?})", op, a, b)
+            }
             UnaryOp(ref op, ref a) => write!(fmt, "{:?}({:?})", op, a),
             Box(ref t) => write!(fmt, "Box({:?})", t),
             InlineAsm { ref asm, ref outputs, ref inputs } => {
@@ -414,6 +414,14 @@ macro_rules! make_mir_visitor {
                         }
                         cleanup.map(|t| self.visit_branch(block, t));
                     }
+
+                    TerminatorKind::TailCall { ref $($mutability)* func,
+                                               ref $($mutability)* args } => {
+                        self.visit_operand(func);
+                        for arg in args {
+                            self.visit_operand(arg);
+                        }
+                    }
                 }
             }
 
@@ -450,7 +458,10 @@ macro_rules! make_mir_visitor {
 
                     Rvalue::BinaryOp(_bin_op,
                                      ref $($mutability)* lhs,
-                                     ref $($mutability)* rhs) => {
+                                     ref $($mutability)* rhs) |
+                    Rvalue::CheckedBinaryOp(_bin_op,
+                                            ref $($mutability)* lhs,
+                                            ref $($mutability)* rhs) => {
                         self.visit_operand(lhs);
                         self.visit_operand(rhs);
                     }
@@ -31,8 +31,10 @@ use syntax::parse;
 use syntax::parse::lexer::Reader;
 use syntax::parse::token::InternedString;
 use syntax::feature_gate::UnstableFeatures;
+use syntax::util::lev_distance::lev_distance;
 
 use getopts;
+use std::cmp;
 use std::collections::HashMap;
 use std::env;
 use std::fmt;
@@ -49,7 +51,9 @@ pub enum OptLevel {
     No, // -O0
     Less, // -O1
     Default, // -O2
-    Aggressive // -O3
+    Aggressive, // -O3
+    Size, // -Os
+    SizeMin, // -Oz
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -164,6 +168,7 @@ pub enum PrintRequest {
     CrateName,
     Cfg,
     TargetList,
+    TargetFeatures,
 }
 
 pub enum Input {
@@ -316,6 +321,38 @@ impl Passes {
     }
 }
 
+/// Describes a single `-C` or `-Z` option. The `CG_OPTIONS` and
+/// `DB_OPTIONS` tables generated by `options!` list every option the
+/// compiler understands, and are what `-C help`/`-Z help` print.
+pub struct OptionDesc<S: 'static> {
+    /// The option's name, with `_` where the command line uses `-`.
+    pub name: &'static str,
+    pub setter: S,
+    /// What kind of value the option takes, or `None` for a flag.
+    pub type_desc: Option<&'static str>,
+    pub desc: &'static str,
+    /// Whether the option may be used on the stable compiler.
+    pub stability: OptionStability,
+}
+
+/// Finds the option in `options` whose name is closest to `name`, for
+/// suggesting a correction when an unknown option is given.
+fn closest_option_name<S>(options: &[OptionDesc<S>], name: &str) -> Option<&'static str> {
+    let max_dist = cmp::max(name.len(), 3) / 3;
+    options.iter()
+           .map(|desc| (lev_distance(name, desc.name), desc.name))
+           .filter(|&(dist, _)| dist <= max_dist)
+           .min_by_key(|&(dist, _)| dist)
+           .map(|(_, name)| name)
+}
+
+/// The stability of an `options!` entry: the table's default, unless the
+/// entry names its own.
+macro_rules! option_stability {
+    ($default:expr) => ($default);
+    ($default:expr, $stability:ident) => (OptionStability::$stability);
+}
+
 /// Declare a macro that will define all CodegenOptions/DebuggingOptions fields and parsers all
 /// at once. The goal of this macro is to define an interface that can be
 /// programmatically used by the option parser in order to initialize the struct
@@ -328,9 +365,10 @@ impl Passes {
 /// parsing specific types of values in this module.
 macro_rules! options {
     ($struct_name:ident, $setter_name:ident, $defaultfn:ident,
-     $buildfn:ident, $prefix:expr, $outputname:expr,
+     $buildfn:ident, $prefix:expr, $outputname:expr, $default_stability:expr,
      $stat:ident, $mod_desc:ident, $mod_set:ident,
-     $($opt:ident : $t:ty = ($init:expr, $parse:ident, $desc:expr)),* ,) =>
+     $($opt:ident : $t:ty = ($init:expr, $parse:ident, $desc:expr $(, $opt_stability:ident)*)),*
+     ,) =>
 (
     #[derive(Clone)]
     pub struct $struct_name { $(pub $opt: $t),* }
@@ -348,10 +386,10 @@ macro_rules! options {
             let value = iter.next();
             let option_to_lookup = key.replace("-", "_");
             let mut found = false;
-            for &(candidate, setter, opt_type_desc, _) in $stat {
-                if option_to_lookup != candidate { continue }
-                if !setter(&mut op, value) {
-                    match (value, opt_type_desc) {
+            for desc in $stat {
+                if option_to_lookup != desc.name { continue }
+                if !(desc.setter)(&mut op, value) {
+                    match (value, desc.type_desc) {
                         (Some(..), None) => {
                             early_error(error_format, &format!("{} option `{}` takes no \
                                                               value", $outputname, key))
@@ -371,21 +409,36 @@ macro_rules! options {
                         (None, None) => bug!()
                     }
                 }
+                if desc.stability == OptionStability::Unstable &&
+                   !nightly_options::is_nightly_build() {
+                    early_error(error_format, &format!("the {} option `{}` is only accepted \
+                                                      on the nightly compiler",
+                                                     $outputname, key));
+                }
                 found = true;
                 break;
             }
             if !found {
-                early_error(error_format, &format!("unknown {} option: `{}`",
-                                                 $outputname, key));
+                let mut msg = format!("unknown {} option: `{}`", $outputname, key);
+                if let Some(name) = closest_option_name($stat, &option_to_lookup) {
+                    msg.push_str(&format!("; did you mean `-{} {}`?",
+                                          $prefix, name.replace("_", "-")));
+                }
+                early_error(error_format, &msg);
             }
         }
         return op;
     }
 
     pub type $setter_name = fn(&mut $struct_name, v: Option<&str>) -> bool;
-    pub const $stat: &'static [(&'static str, $setter_name,
-                                     Option<&'static str>, &'static str)] =
-        &[ $( (stringify!($opt), $mod_set::$opt, $mod_desc::$parse, $desc) ),* ];
+    pub const $stat: &'static [OptionDesc<$setter_name>] =
+        &[ $( OptionDesc {
+                  name: stringify!($opt),
+                  setter: $mod_set::$opt,
+                  type_desc: $mod_desc::$parse,
+                  desc: $desc,
+                  stability: option_stability!($default_stability $(, $opt_stability)*),
+              } ),* ];
 
     #[allow(non_upper_case_globals, dead_code)]
     mod $mod_desc {
@@ -513,7 +566,7 @@ macro_rules! options {
 ) }
 
 options! {CodegenOptions, CodegenSetter, basic_codegen_options,
-         build_codegen_options, "C", "codegen",
+         build_codegen_options, "C", "codegen", OptionStability::Stable,
          CG_OPTIONS, cg_type_desc, cgsetters,
     ar: Option<String> = (None, parse_opt_string,
         "tool to assemble archives with"),
@@ -568,17 +621,20 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
     debuginfo: Option<usize> = (None, parse_opt_uint,
         "debug info emission level, 0 = no debug info, 1 = line tables only, \
          2 = full debug info with variable and type information"),
-    opt_level: Option<usize> = (None, parse_opt_uint,
-        "optimize with possible levels 0-3"),
+    opt_level: Option<String> = (None, parse_opt_string,
+        "optimize with possible levels 0-3, s, or z"),
     debug_assertions: Option<bool> = (None, parse_opt_bool,
         "explicitly enable the cfg(debug_assertions) directive"),
     inline_threshold: Option<usize> = (None, parse_opt_uint,
         "set the inlining threshold for"),
+    gdb_scripts_section: Option<bool> = (None, parse_opt_bool,
+        "embed a .debug_gdb_scripts section that loads the Rust pretty-printers \
+         (defaults to on for ELF targets with debug info)", Unstable),
 }
 
 
 options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
-         build_debugging_options, "Z", "debugging",
+         build_debugging_options, "Z", "debugging", OptionStability::UnstableButNotReally,
          DB_OPTIONS, db_type_desc, dbsetters,
     verbose: bool = (false, parse_bool,
         "in general, enable more debug printouts"),
@@ -588,6 +644,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "count where LLVM instrs originate"),
     time_llvm_passes: bool = (false, parse_bool,
         "measure time of each LLVM pass"),
+    time_lints: bool = (false, parse_bool,
+        "measure time of each lint pass, including those loaded from plugins"),
     input_stats: bool = (false, parse_bool,
         "gather statistics about the input"),
     trans_stats: bool = (false, parse_bool,
@@ -618,6 +676,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print the pre-expansion AST as JSON and halt"),
     ls: bool = (false, parse_bool,
         "list the symbols defined by a library crate"),
+    diff_metadata: Option<String> = (None, parse_opt_string,
+        "compare the public interface of the input library to that of an older build"),
     save_analysis: bool = (false, parse_bool,
         "write syntax and type analysis information in addition to normal output"),
     print_move_fragments: bool = (false, parse_bool,
@@ -645,6 +705,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "enable incremental compilation (experimental)"),
     dump_dep_graph: bool = (false, parse_bool,
           "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv)"),
+    dump_crate_graph: bool = (false, parse_bool,
+          "print the resolved crate graph, and why each crate was loaded, as JSON"),
     query_dep_graph: bool = (false, parse_bool,
           "enable queries of the dependency graph for regression testing"),
     no_analysis: bool = (false, parse_bool,
@@ -669,14 +731,41 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "keep the AST after lowering it to HIR"),
     show_span: Option<String> = (None, parse_opt_string,
           "show spans for compiler debugging (expr|pat|ty)"),
+    size_attribution: bool = (false, parse_bool,
+          "print the size of the machine code of every function, grouped by the item \
+           it was instantiated from"),
     print_trans_items: Option<String> = (None, parse_opt_string,
           "print the result of the translation item collection pass"),
     mir_opt_level: Option<usize> = (None, parse_opt_uint,
           "set the MIR optimization level (0-3)"),
     dump_mir: Option<String> = (None, parse_opt_string,
           "dump MIR state at various points in translation"),
+    nll: bool = (false, parse_bool,
+          "use liveness-based (non-lexical) regions when checking loans of fns with MIR"),
+    dump_mir_html: bool = (false, parse_bool,
+          "with -Z dump-mir, also write HTML with dataflow state at each program point"),
     orbit: bool = (false, parse_bool,
           "get MIR where it belongs - everywhere; most importantly, in orbit"),
+    opportunistic_resolve: bool = (false, parse_bool,
+          "shorten type inference variable chains when a snapshot is committed"),
+    emit_stack_sizes: bool = (false, parse_bool,
+          "emit a section containing the stack size of each function"),
+    unsaturated_float_casts: bool = (false, parse_bool,
+          "do not saturate float to integer casts; out of range values give undefined results"),
+    fast_math: bool = (false, parse_bool,
+          "let the `*_fast` float intrinsics use LLVM's fast-math flags"),
+    no_panic_messages: bool = (false, parse_bool,
+          "leave the message and source location out of panics inserted by the compiler"),
+    size_report: bool = (false, parse_bool,
+          "print the size of each output and what size-related options saved"),
+    virtual_function_elimination: bool = (false, parse_bool,
+          "let LTO remove trait methods of crate-private traits that are never \
+           called through a trait object (requires -C lto)"),
+    batch: Option<String> = (None, parse_opt_string,
+          "compile each crate listed in the given file, one after another in this process"),
+    metadata_threads: usize = (0, parse_uint,
+          "decode the def-path tables of loaded crates on this many worker threads \
+           (0 decodes each one on first use)"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -905,7 +994,7 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
                  "[asm|llvm-bc|llvm-ir|obj|link|dep-info]"),
         opt::multi_s("", "print", "Comma separated list of compiler information to \
                                print on stdout",
-                 "[crate-name|file-names|sysroot|cfg|target-list]"),
+                 "[crate-name|file-names|sysroot|cfg|target-list|target-features]"),
         opt::flagmulti_s("g",  "",  "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
         opt::opt_s("o", "", "Write output to <filename>", "FILENAME"),
@@ -1111,6 +1200,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         early_error(error_format, "Value for codegen units must be a positive nonzero integer");
     }
 
+    if debugging_opts.virtual_function_elimination && !cg.lto {
+        early_error(error_format, "-Z virtual-function-elimination requires -C lto");
+    }
+
     let cg = cg;
 
     let sysroot_opt = matches.opt_str("sysroot").map(|m| PathBuf::from(&m));
@@ -1123,15 +1216,17 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             }
             OptLevel::Default
         } else {
-            match cg.opt_level {
+            match cg.opt_level.as_ref().map(|s| &s[..]) {
                 None => OptLevel::No,
-                Some(0) => OptLevel::No,
-                Some(1) => OptLevel::Less,
-                Some(2) => OptLevel::Default,
-                Some(3) => OptLevel::Aggressive,
+                Some("0") => OptLevel::No,
+                Some("1") => OptLevel::Less,
+                Some("2") => OptLevel::Default,
+                Some("3") => OptLevel::Aggressive,
+                Some("s") => OptLevel::Size,
+                Some("z") => OptLevel::SizeMin,
                 Some(arg) => {
                     early_error(error_format, &format!("optimization level needs to be \
-                                                      between 0-3 (instead was `{}`)",
+                                                      between 0-3, s or z (instead was `{}`)",
                                                      arg));
                 }
             }
@@ -1189,6 +1284,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             "sysroot" => PrintRequest::Sysroot,
             "cfg" => PrintRequest::Cfg,
             "target-list" => PrintRequest::TargetList,
+            "target-features" => PrintRequest::TargetFeatures,
             req => {
                 early_error(error_format, &format!("unknown print request `{}`", req))
             }
@@ -1302,7 +1398,7 @@ pub mod nightly_options {
         is_nightly_build() && matches.opt_strs("Z").iter().any(|x| *x == "unstable-options")
     }
 
-    fn is_nightly_build() -> bool {
+    pub fn is_nightly_build() -> bool {
         match get_unstable_features_setting() {
             UnstableFeatures::Allow | UnstableFeatures::Cheat => true,
             _ => false,
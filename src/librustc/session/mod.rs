@@ -18,6 +18,7 @@ use mir::transform as mir_pass;
 
 use syntax::ast::{NodeId, NodeIdAssigner, Name};
 use syntax::codemap::{Span, MultiSpan};
+use syntax::config::OtherTestConfigNames;
 use syntax::errors::{self, DiagnosticBuilder};
 use syntax::errors::emitter::{Emitter, BasicEmitter, EmitterWriter};
 use syntax::errors::json::JsonEmitter;
@@ -26,10 +27,12 @@ use syntax::feature_gate;
 use syntax::parse;
 use syntax::parse::ParseSess;
 use syntax::parse::token;
+use syntax::util::lev_distance::find_best_match_for_name;
 use syntax::{ast, codemap};
 use syntax::feature_gate::AttributeType;
 
 use rustc_back::target::Target;
+use rustc_const_math::overflow::OverflowMode;
 
 use std::path::{Path, PathBuf};
 use std::cell::{Cell, RefCell};
@@ -92,6 +95,18 @@ pub struct Session {
     /// macro name and defintion span in the source crate.
     pub imported_macro_spans: RefCell<HashMap<Span, (String, Span)>>,
 
+    /// Names mentioned by code that was configured away only because of
+    /// whether `--test` was given, e.g. a `#[cfg(test)]` module in a
+    /// normal build. The dead code lint treats items that this code may
+    /// be using as live.
+    pub other_test_config_names: RefCell<OtherTestConfigNames>,
+
+    /// The compiler-inserted panics that `-Z no-panic-messages` stripped,
+    /// with the bytes of file name and of message each would have carried.
+    /// Keyed by span, since both MIR building and trans ask about the same
+    /// panic. Reported by `-Z size-report`.
+    stripped_panics: RefCell<HashMap<Span, (usize, usize)>>,
+
     next_node_id: Cell<ast::NodeId>,
 }
 
@@ -291,6 +306,44 @@ impl Session {
     pub fn lto(&self) -> bool {
         self.opts.cg.lto
     }
+    /// Whether runtime integer arithmetic panics or wraps on overflow.
+    pub fn overflow_mode(&self) -> OverflowMode {
+        OverflowMode::new(self.opts.debugging_opts.force_overflow_checks,
+                          self.opts.debug_assertions)
+    }
+    pub fn no_panic_messages(&self) -> bool {
+        self.opts.debugging_opts.no_panic_messages
+    }
+    /// The file name and line to embed in a compiler-inserted panic at
+    /// `span`, or an empty name and line 0 under `-Z no-panic-messages`.
+    pub fn panic_location(&self, span: Span) -> (token::InternedString, u32) {
+        let loc = self.codemap().lookup_char_pos(span.lo);
+        if self.no_panic_messages() {
+            self.stripped_panics.borrow_mut().entry(span).or_insert((0, 0)).0 =
+                loc.file.name.len();
+            (token::intern_and_get_ident(""), 0)
+        } else {
+            (token::intern_and_get_ident(&loc.file.name), loc.line as u32)
+        }
+    }
+    /// The message to embed in a compiler-inserted panic at `span`, or an
+    /// empty one under `-Z no-panic-messages`.
+    pub fn panic_message(&self, span: Span, msg: token::InternedString)
+                         -> token::InternedString {
+        if self.no_panic_messages() {
+            self.stripped_panics.borrow_mut().entry(span).or_insert((0, 0)).1 = msg.len();
+            token::intern_and_get_ident("")
+        } else {
+            msg
+        }
+    }
+    /// How many panics `-Z no-panic-messages` stripped, and how many bytes
+    /// of file name and message that left out.
+    pub fn stripped_panics(&self) -> (usize, usize) {
+        let stripped = self.stripped_panics.borrow();
+        let bytes = stripped.values().fold(0, |sum, &(file, msg)| sum + file + msg);
+        (stripped.len(), bytes)
+    }
     pub fn no_landing_pads(&self) -> bool {
         self.opts.debugging_opts.no_landing_pads
     }
@@ -323,6 +376,86 @@ impl Session {
             &self.opts.search_paths,
             kind)
     }
+
+    /// The CPU to generate code for: `-C target-cpu` if given, the
+    /// target's default otherwise.
+    pub fn target_cpu(&self) -> &str {
+        match self.opts.cg.target_cpu {
+            Some(ref s) => s,
+            None => &self.target.target.options.cpu,
+        }
+    }
+
+    /// The target features asked for: the target's default features with
+    /// the `+feature`/`-feature` flags of `-C target-feature` applied on
+    /// top, in order. Sorted by name. Features implied by the CPU or by
+    /// other features are only known to the backend.
+    pub fn target_features(&self) -> Vec<String> {
+        let mut enabled = Vec::new();
+        let flags = target_feature_flags(&self.target.target.options.features)
+            .chain(target_feature_flags(&self.opts.cg.target_feature));
+        for (enable, name) in flags {
+            enabled.retain(|f: &String| &f[..] != name);
+            if enable {
+                enabled.push(name.to_string());
+            }
+        }
+        enabled.sort();
+        enabled
+    }
+
+    /// Reports `-C target-cpu` and `-C target-feature` values that the
+    /// backend does not know for this target, as told by `has_cpu` and
+    /// `has_feature`, suggesting the closest of `known_cpus` and
+    /// `known_features`. Those lists are empty if the backend cannot
+    /// provide them.
+    pub fn check_target_options<C, F>(&self,
+                                      has_cpu: C,
+                                      known_cpus: &[String],
+                                      has_feature: F,
+                                      known_features: &[String])
+        where C: Fn(&str) -> bool, F: Fn(&str) -> bool
+    {
+        let report = |kind: &str, name: &str, known: &[String], list_help: &str| {
+            let mut err = self.struct_err(&format!("unknown {} `{}` for target `{}`",
+                                                   kind, name, self.opts.target_triple));
+            let known_names: Vec<_> = known.iter().map(|k| token::intern(k)).collect();
+            match find_best_match_for_name(known_names.iter(), name, None) {
+                Some(suggestion) => err.help(&format!("did you mean `{}`?", suggestion)),
+                None => err.help(list_help),
+            };
+            err.emit();
+        };
+
+        if let Some(ref cpu) = self.opts.cg.target_cpu {
+            // `native` is resolved by the backend itself.
+            if cpu != "native" && !has_cpu(cpu) {
+                report("target CPU", cpu, known_cpus,
+                       "`llc -mcpu=help` lists the CPUs this target supports");
+            }
+        }
+        for (_, name) in target_feature_flags(&self.opts.cg.target_feature) {
+            if !has_feature(name) {
+                report("target feature", name, known_features,
+                       "`llc -mattr=help` lists the features this target supports");
+            }
+        }
+    }
+}
+
+/// Splits a comma-separated list of target feature flags, such as
+/// `+sse2,-avx`, into whether each feature is enabled and its name. A
+/// flag without a leading `+` or `-` enables the feature.
+fn target_feature_flags<'a>(flags: &'a str) -> Box<Iterator<Item=(bool, &'a str)> + 'a> {
+    box flags.split(',').map(|f| f.trim()).filter(|f| !f.is_empty()).map(|f| {
+        if f.starts_with('-') {
+            (false, &f[1..])
+        } else if f.starts_with('+') {
+            (true, &f[1..])
+        } else {
+            (true, f)
+        }
+    })
 }
 
 impl NodeIdAssigner for Session {
@@ -485,10 +618,12 @@ pub fn build_session_(sopts: config::Options,
         crate_disambiguator: Cell::new(token::intern("")),
         features: RefCell::new(feature_gate::Features::new()),
         recursion_limit: Cell::new(64),
+        stripped_panics: RefCell::new(HashMap::new()),
         next_node_id: Cell::new(1),
         injected_allocator: Cell::new(None),
         available_macros: RefCell::new(HashSet::new()),
         imported_macro_spans: RefCell::new(HashMap::new()),
+        other_test_config_names: RefCell::new(OtherTestConfigNames::default()),
     };
 
     sess
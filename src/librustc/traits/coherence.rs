@@ -10,24 +10,46 @@
 
 //! See `README.md` for high-level documentation
 
-use super::{SelectionContext, Obligation, ObligationCause};
+use super::{SelectionContext, Obligation, ObligationCause, PredicateObligation};
+use super::util;
+use super::select::IntercrateAmbiguityCause;
 
 use middle::cstore::LOCAL_CRATE;
 use hir::def_id::DefId;
 use ty::subst::TypeSpace;
 use ty::{self, Ty, TyCtxt};
+use ty::fold::TypeFoldable;
 use infer::{self, InferCtxt, TypeOrigin};
 use syntax::codemap::DUMMY_SP;
 
 #[derive(Copy, Clone)]
 struct InferIsLocal(bool);
 
+/// The area in which two impls overlap.
+pub struct OverlapResult<'tcx> {
+    /// The header of the first impl, with the types satisfying both impls
+    /// substituted in. Any type variables left over were unconstrained.
+    pub impl_header: ty::ImplHeader<'tcx>,
+
+    /// Why the `where` clauses of the impls could not be ruled out: trait
+    /// references that another crate could come to implement.
+    pub intercrate_ambiguity_causes: Vec<IntercrateAmbiguityCause>,
+}
+
+/// Which kind of crate could implement a trait reference that the
+/// current crate cannot decide; see `trait_ref_is_knowable`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    Upstream,
+    Downstream,
+}
+
 /// If there are types that satisfy both impls, returns a suitably-freshened
 /// `ImplHeader` with those types substituted
 pub fn overlapping_impls<'cx, 'tcx>(infcx: &InferCtxt<'cx, 'tcx>,
                                     impl1_def_id: DefId,
                                     impl2_def_id: DefId)
-                                    -> Option<ty::ImplHeader<'tcx>>
+                                    -> Option<OverlapResult<'tcx>>
 {
     debug!("impl_can_satisfy(\
            impl1_def_id={:?}, \
@@ -36,6 +58,7 @@ pub fn overlapping_impls<'cx, 'tcx>(infcx: &InferCtxt<'cx, 'tcx>,
            impl2_def_id);
 
     let selcx = &mut SelectionContext::intercrate(infcx);
+    selcx.enable_tracking_intercrate_ambiguity_causes();
     overlap(selcx, impl1_def_id, impl2_def_id)
 }
 
@@ -44,7 +67,7 @@ pub fn overlapping_impls<'cx, 'tcx>(infcx: &InferCtxt<'cx, 'tcx>,
 fn overlap<'cx, 'tcx>(selcx: &mut SelectionContext<'cx, 'tcx>,
                       a_def_id: DefId,
                       b_def_id: DefId)
-                      -> Option<ty::ImplHeader<'tcx>>
+                      -> Option<OverlapResult<'tcx>>
 {
     debug!("overlap(a_def_id={:?}, b_def_id={:?})",
            a_def_id,
@@ -69,7 +92,7 @@ fn overlap<'cx, 'tcx>(selcx: &mut SelectionContext<'cx, 'tcx>,
 
     // Are any of the obligations unsatisfiable? If so, no overlap.
     let infcx = selcx.infcx();
-    let opt_failing_obligation =
+    let obligations: Vec<_> =
         a_impl_header.predicates
                      .iter()
                      .chain(&b_impl_header.predicates)
@@ -77,17 +100,73 @@ fn overlap<'cx, 'tcx>(selcx: &mut SelectionContext<'cx, 'tcx>,
                      .map(|p| Obligation { cause: ObligationCause::dummy(),
                                            recursion_depth: 0,
                                            predicate: p })
-                     .find(|o| !selcx.evaluate_obligation(o));
+                     .collect();
 
-    if let Some(failing_obligation) = opt_failing_obligation {
+    if let Some(failing_obligation) = obligations.iter().find(|o| !selcx.evaluate_obligation(o)) {
         debug!("overlap: obligation unsatisfiable {:?}", failing_obligation);
         return None
     }
 
-    Some(selcx.infcx().resolve_type_vars_if_possible(&a_impl_header))
+    // Does one impl require `T: !Trait` where the other requires `T: Trait`
+    // (or a subtrait of it)? If so, no overlap.
+    if let Some(negative) = contradictory_negative_bound(infcx, &obligations) {
+        debug!("overlap: negative bound contradicted {:?}", negative);
+        return None
+    }
+
+    let intercrate_ambiguity_causes = selcx.take_intercrate_ambiguity_causes();
+    debug!("overlap: intercrate_ambiguity_causes={:#?}", intercrate_ambiguity_causes);
+
+    Some(OverlapResult {
+        impl_header: infcx.resolve_type_vars_if_possible(&a_impl_header),
+        intercrate_ambiguity_causes: intercrate_ambiguity_causes,
+    })
+}
+
+/// Finds a `T: !Trait` predicate among `obligations` whose positive
+/// counterpart is implied by one of the other predicates. Unbound type
+/// variables are compared by their unification root, so `?A: !Copy` and
+/// `?B: Copy` conflict once the impl headers have equated `?A` and `?B`.
+fn contradictory_negative_bound<'cx, 'tcx>(infcx: &InferCtxt<'cx, 'tcx>,
+                                           obligations: &[PredicateObligation<'tcx>])
+                                           -> Option<ty::PolyTraitPredicate<'tcx>>
+{
+    let tcx = infcx.tcx;
+    let mut root_vars = ty::fold::BottomUpFolder {
+        tcx: tcx,
+        fldop: |ty| match ty.sty {
+            ty::TyInfer(ty::TyVar(vid)) => tcx.mk_var(infcx.root_ty_var(vid)),
+            _ => ty,
+        }
+    };
+
+    let negatives: Vec<_> = obligations.iter().filter_map(|o| match o.predicate {
+        ty::Predicate::NegativeTrait(ref data) => Some(data.clone()),
+        _ => None,
+    }).collect();
+    if negatives.is_empty() {
+        return None;
+    }
+
+    let positives: Vec<_> =
+        util::elaborate_predicates(tcx, obligations.iter().map(|o| o.predicate.clone()).collect())
+            .filter_map(|p| match p {
+                ty::Predicate::Trait(data) => Some(data.fold_with(&mut root_vars)),
+                _ => None,
+            })
+            .collect();
+
+    negatives.into_iter().find(|negative| {
+        let negative = negative.fold_with(&mut root_vars);
+        positives.iter().any(|positive| *positive == negative)
+    })
 }
 
-pub fn trait_ref_is_knowable<'tcx>(tcx: &TyCtxt<'tcx>, trait_ref: &ty::TraitRef<'tcx>) -> bool
+/// Returns `None` if this crate can decide whether `trait_ref` holds,
+/// or else which kind of crate could implement it without us knowing.
+pub fn trait_ref_is_knowable<'tcx>(tcx: &TyCtxt<'tcx>,
+                                   trait_ref: &ty::TraitRef<'tcx>)
+                                   -> Option<Conflict>
 {
     debug!("trait_ref_is_knowable(trait_ref={:?})", trait_ref);
 
@@ -95,7 +174,7 @@ pub fn trait_ref_is_knowable<'tcx>(tcx: &TyCtxt<'tcx>, trait_ref: &ty::TraitRef<
     // impl this, so it's up to us.
     if orphan_check_trait_ref(tcx, trait_ref, InferIsLocal(false)).is_ok() {
         debug!("trait_ref_is_knowable: orphan check passed");
-        return true;
+        return None;
     }
 
     // if the trait is not marked fundamental, then it's always possible that
@@ -106,7 +185,7 @@ pub fn trait_ref_is_knowable<'tcx>(tcx: &TyCtxt<'tcx>, trait_ref: &ty::TraitRef<
         !tcx.has_attr(trait_ref.def_id, "fundamental")
     {
         debug!("trait_ref_is_knowable: trait is neither local nor fundamental");
-        return false;
+        return Some(Conflict::Upstream);
     }
 
     // find out when some downstream (or cousin) crate could impl this
@@ -115,7 +194,12 @@ pub fn trait_ref_is_knowable<'tcx>(tcx: &TyCtxt<'tcx>, trait_ref: &ty::TraitRef<
     // implemented by an upstream crate, which means that the impl
     // must be visible to us, and -- since the trait is fundamental
     // -- we can test.
-    orphan_check_trait_ref(tcx, trait_ref, InferIsLocal(true)).is_err()
+    if orphan_check_trait_ref(tcx, trait_ref, InferIsLocal(true)).is_ok() {
+        debug!("trait_ref_is_knowable: downstream crate might implement");
+        Some(Conflict::Downstream)
+    } else {
+        None
+    }
 }
 
 pub enum OrphanCheckErr<'tcx> {
@@ -264,6 +348,7 @@ fn ty_is_local_constructor<'tcx>(tcx: &TyCtxt<'tcx>,
         ty::TyRef(..) |
         ty::TyTuple(..) |
         ty::TyParam(..) |
+        ty::TyBound(..) |
         ty::TyProjection(..) => {
             false
         }
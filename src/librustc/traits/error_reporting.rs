@@ -28,6 +28,7 @@ use super::{
 use fmt_macros::{Parser, Piece, Position};
 use hir::def_id::DefId;
 use infer::InferCtxt;
+use infer::type_variable::TypeVariableOrigin;
 use ty::{self, ToPredicate, ToPolyTraitRef, Ty, TyCtxt};
 use ty::fast_reject;
 use ty::fold::{TypeFoldable, TypeFolder};
@@ -36,7 +37,7 @@ use util::nodemap::{FnvHashMap, FnvHashSet};
 use std::cmp;
 use std::fmt;
 use syntax::attr::{AttributeMethods, AttrMetaMethods};
-use syntax::codemap::Span;
+use syntax::codemap::{Span, DUMMY_SP};
 use syntax::errors::DiagnosticBuilder;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -182,28 +183,15 @@ fn find_similar_impl_candidates<'a, 'tcx>(
     let simp = fast_reject::simplify_type(infcx.tcx,
                                           trait_ref.skip_binder().self_ty(),
                                           true);
-    let mut impl_candidates = Vec::new();
-    let trait_def = infcx.tcx.lookup_trait_def(trait_ref.def_id());
-
-    match simp {
-        Some(simp) => trait_def.for_each_impl(infcx.tcx, |def_id| {
-            let imp = infcx.tcx.impl_trait_ref(def_id).unwrap();
-            let imp_simp = fast_reject::simplify_type(infcx.tcx,
-                                                      imp.self_ty(),
-                                                      true);
-            if let Some(imp_simp) = imp_simp {
-                if simp != imp_simp {
-                    return;
-                }
-            }
-            impl_candidates.push(imp);
-        }),
-        None => trait_def.for_each_impl(infcx.tcx, |def_id| {
-            impl_candidates.push(
-                infcx.tcx.impl_trait_ref(def_id).unwrap());
-        })
-    };
-    impl_candidates
+    infcx.tcx.trait_impls_of(trait_ref.def_id()).into_iter().map(|def_id| {
+        infcx.tcx.impl_trait_ref(def_id).unwrap()
+    }).filter(|imp| {
+        // Skip impls whose self type certainly differs from ours.
+        match (simp, fast_reject::simplify_type(infcx.tcx, imp.self_ty(), true)) {
+            (Some(simp), Some(imp_simp)) => simp == imp_simp,
+            _ => true,
+        }
+    }).collect()
 }
 
 fn report_similar_impl_candidates(span: Span,
@@ -473,7 +461,9 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                         err.emit();
                     }
 
-                    ty::Predicate::Projection(..) | ty::Predicate::TypeOutlives(..) => {
+                    ty::Predicate::NegativeTrait(..) |
+                    ty::Predicate::Projection(..) |
+                    ty::Predicate::TypeOutlives(..) => {
                         let predicate =
                             infcx.resolve_type_vars_if_possible(&obligation.predicate);
                         let mut err = struct_span_err!(
@@ -549,6 +539,12 @@ pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
             note_obligation_cause(infcx, &mut err, obligation);
             err.emit();
         }
+
+        // Overflow is only recovered from while probing; once the
+        // obligation is committed to, it is reported like any other.
+        SelectionError::Overflow => {
+            report_overflow_error(infcx, obligation, true);
+        }
     }
 }
 
@@ -582,29 +578,46 @@ pub fn report_object_safety_error<'tcx>(tcx: &TyCtxt<'tcx>,
                      in the supertrait listing");
             }
 
-            ObjectSafetyViolation::Method(method,
-                                          MethodViolationCode::StaticMethod) => {
-                err.fileline_note(
-                    span,
-                    &format!("method `{}` has no receiver",
-                             method.name));
-            }
-
-            ObjectSafetyViolation::Method(method,
-                                          MethodViolationCode::ReferencesSelf) => {
-                err.fileline_note(
-                    span,
-                    &format!("method `{}` references the `Self` type \
-                              in its arguments or return type",
-                             method.name));
-            }
-
-            ObjectSafetyViolation::Method(method,
-                                          MethodViolationCode::Generic) => {
-                err.fileline_note(
-                    span,
-                    &format!("method `{}` has generic type parameters",
-                             method.name));
+            ObjectSafetyViolation::Method(method, code, method_span) => {
+                let msg = match code {
+                    MethodViolationCode::StaticMethod => {
+                        format!("method `{}` has no receiver", method.name)
+                    }
+                    MethodViolationCode::ReferencesSelf => {
+                        format!("method `{}` references the `Self` type \
+                                 in its arguments or return type",
+                                method.name)
+                    }
+                    MethodViolationCode::Generic => {
+                        format!("method `{}` has generic type parameters",
+                                method.name)
+                    }
+                };
+                let method_span = match method_span {
+                    Some(method_span) => method_span,
+                    None => {
+                        // The trait is defined in another crate, so there
+                        // is nothing local to point at or to change.
+                        err.fileline_note(span, &msg);
+                        continue;
+                    }
+                };
+                err.span_note(method_span, &msg);
+                let help = match code {
+                    MethodViolationCode::StaticMethod => {
+                        format!("consider giving `{}` a `&self` receiver, or \
+                                 adding `where Self: Sized` to it so that it \
+                                 is not required on trait objects",
+                                method.name)
+                    }
+                    MethodViolationCode::ReferencesSelf |
+                    MethodViolationCode::Generic => {
+                        format!("consider adding `where Self: Sized` to `{}` \
+                                 so that it is not callable on trait objects",
+                                method.name)
+                    }
+                };
+                err.fileline_help(method_span, &help);
             }
         }
     }
@@ -711,7 +724,9 @@ fn predicate_can_apply<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
         fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
             if let ty::TyParam(..) = ty.sty {
                 let infcx = self.infcx;
-                self.var_map.entry(ty).or_insert_with(|| infcx.next_ty_var())
+                self.var_map.entry(ty).or_insert_with(|| {
+                    infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP))
+                })
             } else {
                 ty.super_fold_with(self)
             }
@@ -746,10 +761,64 @@ fn need_type_info<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                             span: Span,
                             ty: Ty<'tcx>)
 {
-    span_err!(infcx.tcx.sess, span, E0282,
-              "unable to infer enough type information about `{}`; \
-               type annotations or generic parameter binding required",
-              ty);
+    let mut err = struct_span_err!(infcx.tcx.sess, span, E0282,
+                                   "unable to infer enough type information about `{}`; \
+                                    type annotations or generic parameter binding required",
+                                   ty);
+    note_type_var_origin(infcx, &mut err, ty);
+    err.emit();
+}
+
+/// Points at where the first type variable in `ty` was created, if
+/// that tells the user which type is missing.
+fn note_type_var_origin<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                  err: &mut DiagnosticBuilder,
+                                  ty: Ty<'tcx>)
+{
+    let vid = ty.walk().filter_map(|t| match t.sty {
+        ty::TyInfer(ty::TyVar(vid)) => Some(vid),
+        _ => None,
+    }).next();
+    let origin = match vid {
+        Some(vid) => infcx.type_var_origin(vid),
+        None => return,
+    };
+    if origin.span() == DUMMY_SP {
+        return;
+    }
+
+    let msg = match origin {
+        TypeVariableOrigin::TypeInference(_) => {
+            "cannot infer the type introduced here".to_string()
+        }
+        TypeVariableOrigin::TypeParameterDefinition(_, name) => {
+            format!("cannot infer the type of the type parameter `{}` used here", name)
+        }
+        TypeVariableOrigin::ClosureArgument(_) => {
+            "cannot infer the type of the closure argument introduced here".to_string()
+        }
+        TypeVariableOrigin::ClosureReturnType(_) => {
+            "cannot infer the return type of the closure introduced here".to_string()
+        }
+        TypeVariableOrigin::ClosureUpvar(_) => {
+            "cannot infer the type of the variable captured here".to_string()
+        }
+        TypeVariableOrigin::NormalizeProjectionType(_) => {
+            "cannot infer the associated type projected here".to_string()
+        }
+        TypeVariableOrigin::LateBoundInFn(_) => {
+            "cannot infer the type bound by the signature used here".to_string()
+        }
+        TypeVariableOrigin::Diverging(_) => {
+            "cannot infer the type of this diverging expression".to_string()
+        }
+        TypeVariableOrigin::Adjustment(_) => {
+            "cannot infer the type this expression is coerced to".to_string()
+        }
+        TypeVariableOrigin::MiscVariable(_) |
+        TypeVariableOrigin::LatticeVariable(_) => return,
+    };
+    err.span_note(origin.span(), &msg);
 }
 
 fn note_obligation_cause<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
@@ -868,11 +937,17 @@ fn note_obligation_cause_code<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
         }
         ObligationCauseCode::ImplDerivedObligation(ref data) => {
             let parent_trait_ref = infcx.resolve_type_vars_if_possible(&data.parent_trait_ref);
-            err.fileline_note(
-                cause_span,
-                &format!("required because of the requirements on the impl of `{}` for `{}`",
-                         parent_trait_ref,
-                         parent_trait_ref.0.self_ty()));
+            let msg = format!("required because of the requirements on the impl of `{}` for `{}`",
+                              parent_trait_ref,
+                              parent_trait_ref.0.self_ty());
+            // Point at the impl if it is in this crate.
+            let impl_span = data.parent_impl_def_id.and_then(|did| {
+                infcx.tcx.map.span_if_local(did)
+            });
+            match impl_span {
+                Some(span) => { err.span_note(span, &msg); }
+                None => { err.fileline_note(cause_span, &msg); }
+            }
             let parent_predicate = parent_trait_ref.to_predicate();
             note_obligation_cause_code(infcx,
                                        err,
@@ -13,9 +13,8 @@ use infer::{InferCtxt, InferOk};
 use ty::{self, Ty, TyCtxt, TypeFoldable, ToPolyTraitRef};
 use rustc_data_structures::obligation_forest::{Backtrace, ObligationForest, Error};
 use std::iter;
-use syntax::ast;
 use util::common::ErrorReported;
-use util::nodemap::{FnvHashMap, FnvHashSet, NodeMap};
+use util::nodemap::{FnvHashMap, FnvHashSet};
 
 use super::CodeAmbiguity;
 use super::CodeProjectionError;
@@ -69,34 +68,30 @@ pub struct FulfillmentContext<'tcx> {
     // fulfillment context.
     predicates: ObligationForest<PendingPredicateObligation<'tcx>,
                                  LocalFulfilledPredicates<'tcx>>,
-
-    // A set of constraints that regionck must validate. Each
-    // constraint has the form `T:'a`, meaning "some type `T` must
-    // outlive the lifetime 'a". These constraints derive from
-    // instantiated type parameters. So if you had a struct defined
-    // like
-    //
-    //     struct Foo<T:'static> { ... }
-    //
-    // then in some expression `let x = Foo { ... }` it will
-    // instantiate the type parameter `T` with a fresh type `$0`. At
-    // the same time, it will record a region obligation of
-    // `$0:'static`. This will get checked later by regionck. (We
-    // can't generally check these things right away because we have
-    // to wait until types are resolved.)
-    //
-    // These are stored in a map keyed to the id of the innermost
-    // enclosing fn body / static initializer expression. This is
-    // because the location where the obligation was incurred can be
-    // relevant with respect to which sublifetime assumptions are in
-    // place. The reason that we store under the fn-id, and not
-    // something more fine-grained, is so that it is easier for
-    // regionck to be sure that it has found *all* the region
-    // obligations (otherwise, it's easy to fail to walk to a
-    // particular node-id).
-    region_obligations: NodeMap<Vec<RegionObligation<'tcx>>>,
 }
 
+// A constraint that regionck must validate, of the form `T:'a`,
+// meaning "some type `T` must outlive the lifetime 'a". These
+// constraints derive from instantiated type parameters. So if you had
+// a struct defined like
+//
+//     struct Foo<T:'static> { ... }
+//
+// then in some expression `let x = Foo { ... }` it will instantiate
+// the type parameter `T` with a fresh type `$0`. At the same time, it
+// will record a region obligation of `$0:'static`. This will get
+// checked later by regionck. (We can't generally check these things
+// right away because we have to wait until types are resolved.)
+//
+// Region obligations are queued in the inference context (see
+// `InferCtxt::register_region_obligation`) under the id of the
+// innermost enclosing fn body / static initializer expression. This
+// is because the location where the obligation was incurred can be
+// relevant with respect to which sublifetime assumptions are in
+// place. The reason that we store under the fn-id, and not something
+// more fine-grained, is so that it is easier for regionck to be sure
+// that it has found *all* the region obligations (otherwise, it's
+// easy to fail to walk to a particular node-id).
 #[derive(Clone)]
 pub struct RegionObligation<'tcx> {
     pub sub_region: ty::Region,
@@ -116,7 +111,6 @@ impl<'tcx> FulfillmentContext<'tcx> {
         FulfillmentContext {
             duplicate_set: LocalFulfilledPredicates::new(),
             predicates: ObligationForest::new(),
-            region_obligations: NodeMap(),
         }
     }
 
@@ -166,14 +160,6 @@ impl<'tcx> FulfillmentContext<'tcx> {
         }
     }
 
-    pub fn register_region_obligation<'a>(&mut self,
-                                          t_a: Ty<'tcx>,
-                                          r_b: ty::Region,
-                                          cause: ObligationCause<'tcx>)
-    {
-        register_region_obligation(t_a, r_b, cause, &mut self.region_obligations);
-    }
-
     pub fn register_predicate_obligation<'a>(&mut self,
                                              infcx: &InferCtxt<'a,'tcx>,
                                              obligation: PredicateObligation<'tcx>)
@@ -197,16 +183,6 @@ impl<'tcx> FulfillmentContext<'tcx> {
         self.predicates.push_tree(obligation, LocalFulfilledPredicates::new());
     }
 
-    pub fn region_obligations(&self,
-                              body_id: ast::NodeId)
-                              -> &[RegionObligation<'tcx>]
-    {
-        match self.region_obligations.get(&body_id) {
-            None => Default::default(),
-            Some(vec) => vec,
-        }
-    }
-
     pub fn select_all_or_error<'a>(&mut self,
                                    infcx: &InferCtxt<'a,'tcx>)
                                    -> Result<(),Vec<FulfillmentError<'tcx>>>
@@ -277,15 +253,11 @@ impl<'tcx> FulfillmentContext<'tcx> {
             debug!("select: starting another iteration");
 
             // Process pending obligations.
-            let outcome = {
-                let region_obligations = &mut self.region_obligations;
-                self.predicates.process_obligations(
-                    |obligation, tree, backtrace| process_predicate(selcx,
-                                                                     tree,
-                                                                     obligation,
-                                                                     backtrace,
-                                                                     region_obligations))
-            };
+            let outcome = self.predicates.process_obligations(
+                |obligation, tree, backtrace| process_predicate(selcx,
+                                                                 tree,
+                                                                 obligation,
+                                                                 backtrace));
 
             debug!("select: outcome={:?}", outcome);
 
@@ -320,12 +292,11 @@ impl<'tcx> FulfillmentContext<'tcx> {
 fn process_predicate<'a,'tcx>(selcx: &mut SelectionContext<'a,'tcx>,
                               tree_cache: &mut LocalFulfilledPredicates<'tcx>,
                               pending_obligation: &mut PendingPredicateObligation<'tcx>,
-                              backtrace: Backtrace<PendingPredicateObligation<'tcx>>,
-                              region_obligations: &mut NodeMap<Vec<RegionObligation<'tcx>>>)
+                              backtrace: Backtrace<PendingPredicateObligation<'tcx>>)
                               -> Result<Option<Vec<PendingPredicateObligation<'tcx>>>,
                                         FulfillmentErrorCode<'tcx>>
 {
-    match process_predicate1(selcx, pending_obligation, region_obligations) {
+    match process_predicate1(selcx, pending_obligation) {
         Ok(Some(v)) => process_child_obligations(selcx,
                                                  tree_cache,
                                                  &pending_obligation.obligation,
@@ -506,8 +477,7 @@ fn trait_ref_type_vars<'a, 'tcx>(selcx: &mut SelectionContext<'a, 'tcx>,
 /// - `Ok(None)` if we don't have enough info to be sure
 /// - `Err` if the predicate does not hold
 fn process_predicate1<'a,'tcx>(selcx: &mut SelectionContext<'a,'tcx>,
-                               pending_obligation: &mut PendingPredicateObligation<'tcx>,
-                               region_obligations: &mut NodeMap<Vec<RegionObligation<'tcx>>>)
+                               pending_obligation: &mut PendingPredicateObligation<'tcx>)
                                -> Result<Option<Vec<PredicateObligation<'tcx>>>,
                                          FulfillmentErrorCode<'tcx>>
 {
@@ -578,6 +548,19 @@ fn process_predicate1<'a,'tcx>(selcx: &mut SelectionContext<'a,'tcx>,
             }
         }
 
+        ty::Predicate::NegativeTrait(ref data) => {
+            let trait_obligation = obligation.with(data.clone());
+            match selcx.select_negative(&trait_obligation) {
+                Ok(Some(())) => Ok(Some(vec![])),
+                Ok(None) => {
+                    pending_obligation.stalled_on =
+                        trait_ref_type_vars(selcx, data.to_poly_trait_ref());
+                    Ok(None)
+                }
+                Err(selection_err) => Err(CodeSelectionError(selection_err))
+            }
+        }
+
         ty::Predicate::Equate(ref binder) => {
             match selcx.infcx().equality_predicate(obligation.cause.span, binder) {
                 Ok(InferOk { obligations, .. }) => {
@@ -614,18 +597,16 @@ fn process_predicate1<'a,'tcx>(selcx: &mut SelectionContext<'a,'tcx>,
                         // Otherwise, we have something of the form
                         // `for<'a> T: 'a where 'a not in T`, which we can treat as `T: 'static`.
                         Some(t_a) => {
-                            register_region_obligation(t_a, ty::ReStatic,
-                                                       obligation.cause.clone(),
-                                                       region_obligations);
+                            selcx.infcx().register_region_obligation(t_a, ty::ReStatic,
+                                                                     obligation.cause.clone());
                             Ok(Some(vec![]))
                         }
                     }
                 }
                 // If there aren't, register the obligation.
                 Some(ty::OutlivesPredicate(t_a, r_b)) => {
-                    register_region_obligation(t_a, r_b,
-                                               obligation.cause.clone(),
-                                               region_obligations);
+                    selcx.infcx().register_region_obligation(t_a, r_b,
+                                                             obligation.cause.clone());
                     Ok(Some(vec![]))
                 }
             }
@@ -718,24 +699,6 @@ fn coinductive_obligation<'a, 'tcx>(selcx: &SelectionContext<'a, 'tcx>,
     }
 }
 
-fn register_region_obligation<'tcx>(t_a: Ty<'tcx>,
-                                    r_b: ty::Region,
-                                    cause: ObligationCause<'tcx>,
-                                    region_obligations: &mut NodeMap<Vec<RegionObligation<'tcx>>>)
-{
-    let region_obligation = RegionObligation { sup_type: t_a,
-                                               sub_region: r_b,
-                                               cause: cause };
-
-    debug!("register_region_obligation({:?}, cause={:?})",
-           region_obligation, region_obligation.cause);
-
-    region_obligations.entry(region_obligation.cause.body_id)
-                      .or_insert(vec![])
-                      .push(region_obligation);
-
-}
-
 impl<'tcx> LocalFulfilledPredicates<'tcx> {
     pub fn new() -> LocalFulfilledPredicates<'tcx> {
         LocalFulfilledPredicates {
@@ -33,21 +33,26 @@ pub use self::error_reporting::report_overflow_error_cycle;
 pub use self::error_reporting::report_selection_error;
 pub use self::error_reporting::report_object_safety_error;
 pub use self::coherence::orphan_check;
-pub use self::coherence::overlapping_impls;
+pub use self::coherence::{overlapping_impls, OverlapResult};
 pub use self::coherence::OrphanCheckErr;
 pub use self::fulfill::{FulfillmentContext, GlobalFulfilledPredicates, RegionObligation};
 pub use self::project::{MismatchedProjectionTypes, ProjectionMode};
-pub use self::project::{normalize, Normalized};
+pub use self::project::{normalize, normalize_projection_ty, Normalized};
+pub use self::project::{ProjectionCache, ProjectionCacheSnapshot};
+pub use self::project::{NormalizationAnswer, NormalizationQueryCache};
 pub use self::object_safety::is_object_safe;
 pub use self::object_safety::astconv_object_safety_violations;
 pub use self::object_safety::object_safety_violations;
 pub use self::object_safety::ObjectSafetyViolation;
 pub use self::object_safety::MethodViolationCode;
 pub use self::object_safety::is_vtable_safe_method;
-pub use self::select::{EvaluationCache, SelectionContext, SelectionCache};
+pub use self::select::{EvaluationCache, GlobalEvaluationCache, SelectionContext, SelectionCache};
+pub use self::select::EvaluationQueryCache;
+pub use self::select::{IntercrateAmbiguityCause, OverflowError};
 pub use self::select::{MethodMatchResult, MethodMatched, MethodAmbiguous, MethodDidNotMatch};
 pub use self::select::{MethodMatchedData}; // intentionally don't export variants
-pub use self::specialize::{Overlap, specialization_graph, specializes, translate_substs};
+pub use self::specialize::{OverlapError, specialization_graph, specializes, translate_substs};
+pub use self::specialize::SpecializesCache;
 pub use self::util::elaborate_predicates;
 pub use self::util::get_vtable_index_of_object_method;
 pub use self::util::trait_ref_for_builtin_bound;
@@ -94,8 +99,7 @@ pub struct ObligationCause<'tcx> {
     // used for region obligations to determine the precise
     // environment in which the region obligation should be evaluated
     // (in particular, closures can add new assumptions). See the
-    // field `region_obligations` of the `FulfillmentContext` for more
-    // information.
+    // comment on `RegionObligation` for more information.
     pub body_id: ast::NodeId,
 
     pub code: ObligationCauseCode<'tcx>
@@ -154,6 +158,10 @@ pub struct DerivedObligationCause<'tcx> {
     /// directly.
     parent_trait_ref: ty::PolyTraitRef<'tcx>,
 
+    /// The impl that was selected for the parent obligation, if the
+    /// current obligation is one of its where-clauses.
+    parent_impl_def_id: Option<DefId>,
+
     /// The parent trait had this cause
     parent_code: Rc<ObligationCauseCode<'tcx>>
 }
@@ -171,6 +179,15 @@ pub enum SelectionError<'tcx> {
                                 ty::PolyTraitRef<'tcx>,
                                 ty::error::TypeError<'tcx>),
     TraitNotObjectSafe(DefId),
+    /// Selection hit the recursion limit in a `SelectionContext` that
+    /// recovers from overflow; see `SelectionContext::probing`.
+    Overflow,
+}
+
+impl<'tcx> From<OverflowError> for SelectionError<'tcx> {
+    fn from(_: OverflowError) -> SelectionError<'tcx> {
+        SelectionError::Overflow
+    }
 }
 
 pub struct FulfillmentError<'tcx> {
@@ -26,6 +26,7 @@ use traits;
 use ty::{self, ToPolyTraitRef, Ty, TyCtxt, TypeFoldable};
 use std::rc::Rc;
 use syntax::ast;
+use syntax::codemap::Span;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ObjectSafetyViolation<'tcx> {
@@ -36,8 +37,9 @@ pub enum ObjectSafetyViolation<'tcx> {
     /// (e.g. `trait Foo : Bar<Self>`)
     SupertraitSelf,
 
-    /// Method has something illegal
-    Method(Rc<ty::Method<'tcx>>, MethodViolationCode),
+    /// Method has something illegal. The span is that of the method's
+    /// declaration, if the trait is defined in the current crate.
+    Method(Rc<ty::Method<'tcx>>, MethodViolationCode, Option<Span>),
 }
 
 /// Reasons a method might not be object-safe.
@@ -116,8 +118,10 @@ fn object_safety_violations_for_trait<'tcx>(tcx: &TyCtxt<'tcx>,
         .filter_map(|item| {
             match *item {
                 ty::MethodTraitItem(ref m) => {
-                    object_safety_violation_for_method(tcx, trait_def_id, &m)
-                        .map(|code| ObjectSafetyViolation::Method(m.clone(), code))
+                    object_safety_violation_for_method(tcx, trait_def_id, &m).map(|code| {
+                        let span = tcx.map.span_if_local(m.def_id);
+                        ObjectSafetyViolation::Method(m.clone(), code, span)
+                    })
                 }
                 _ => None,
             }
@@ -153,7 +157,8 @@ pub fn supertraits_reference_self<'tcx>(tcx: &TyCtxt<'tcx>,
         .map(|predicate| predicate.subst_supertrait(tcx, &trait_ref))
         .any(|predicate| {
             match predicate {
-                ty::Predicate::Trait(ref data) => {
+                ty::Predicate::Trait(ref data) |
+                ty::Predicate::NegativeTrait(ref data) => {
                     // In the case of a trait predicate, we can skip the "self" type.
                     data.0.trait_ref.substs.types.get_slice(TypeSpace)
                                                  .iter()
@@ -204,6 +209,7 @@ fn generics_require_sized_self<'tcx>(tcx: &TyCtxt<'tcx>,
                 }
                 ty::Predicate::Projection(..) |
                 ty::Predicate::Trait(..) |
+                ty::Predicate::NegativeTrait(..) |
                 ty::Predicate::Equate(..) |
                 ty::Predicate::RegionOutlives(..) |
                 ty::Predicate::WellFormed(..) |
@@ -11,6 +11,7 @@
 //! Code for projecting associated types out of trait references.
 
 use super::elaborate_predicates;
+use super::FulfillmentContext;
 use super::report_overflow_error;
 use super::specialization_graph;
 use super::translate_substs;
@@ -25,6 +26,9 @@ use super::util;
 
 use hir::def_id::DefId;
 use infer::{self, InferOk, TypeOrigin};
+use infer::canonical::{Canonical, QueryCache, QueryResponse};
+use rustc_data_structures::snapshot_map::{Snapshot, SnapshotMap};
+use infer::type_variable::TypeVariableOrigin;
 use ty::subst::Subst;
 use ty::{self, ToPredicate, ToPolyTraitRef, Ty, TyCtxt};
 use ty::fold::{TypeFoldable, TypeFolder};
@@ -36,7 +40,7 @@ use std::rc::Rc;
 
 /// Depending on the stage of compilation, we want projection to be
 /// more or less conservative.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ProjectionMode {
     /// FIXME (#32205)
     /// At coherence-checking time, we're still constructing the
@@ -431,13 +435,27 @@ pub fn normalize_projection_type<'a,'b,'tcx>(
     depth: usize)
     -> NormalizedTy<'tcx>
 {
-    opt_normalize_projection_type(selcx, projection_ty.clone(), cause.clone(), depth)
+    // Only projections that the caller asks for directly go through
+    // the canonical query; the nested ones that come up while
+    // answering it are normalized right there.
+    let query_result = if depth == 0 {
+        normalize_projection_type_query(selcx, &projection_ty, &cause)
+    } else {
+        None
+    };
+    let opt_normalized = match query_result {
+        Some(opt_normalized) => opt_normalized,
+        None => opt_normalize_projection_type(selcx, projection_ty.clone(), cause.clone(), depth),
+    };
+
+    opt_normalized
         .unwrap_or_else(move || {
             // if we bottom out in ambiguity, create a type variable
             // and a deferred predicate to resolve this when more type
             // information is available.
 
-            let ty_var = selcx.infcx().next_ty_var();
+            let ty_var = selcx.infcx().next_ty_var(
+                TypeVariableOrigin::NormalizeProjectionType(cause.span));
             let projection = ty::Binder(ty::ProjectionPredicate {
                 projection_ty: projection_ty,
                 ty: ty_var
@@ -451,10 +469,153 @@ pub fn normalize_projection_type<'a,'b,'tcx>(
         })
 }
 
+/// The answer of normalizing a projection as a canonical query: the
+/// normalized type and the predicates it depends on.
+pub type NormalizationAnswer<'tcx> = (Ty<'tcx>, Vec<ty::Predicate<'tcx>>);
+
+/// The answers of normalizing projections, asked as canonical queries
+/// (`None` meaning the projection is ambiguous); see `infer::canonical`.
+pub type NormalizationQueryCache<'tcx> =
+    QueryCache<'tcx,
+               ty::ProjectionTy<'tcx>,
+               Canonical<QueryResponse<'tcx, Option<NormalizationAnswer<'tcx>>>>>;
+
+/// Normalizes `projection_ty` as a canonical query: it is
+/// canonicalized, normalized in an inference context of its own, and
+/// the answer cached in the tcx under the canonical form, then applied
+/// to the caller's inference context. Returns `None` if the projection
+/// cannot be asked as a query, in which case the caller normalizes it
+/// directly.
+fn normalize_projection_type_query<'a,'b,'tcx>(selcx: &'a mut SelectionContext<'b,'tcx>,
+                                               projection_ty: &ty::ProjectionTy<'tcx>,
+                                               cause: &ObligationCause<'tcx>)
+                                               -> Option<Option<NormalizedTy<'tcx>>>
+{
+    // Closure signatures and kinds are only known to the inference
+    // context of the fn that contains the closure.
+    let infcx = selcx.infcx();
+    let param_env = infcx.param_env();
+    if selcx.is_intercrate() ||
+       projection_ty.has_closure_types() ||
+       param_env.caller_bounds.needs_infer()
+    {
+        return None;
+    }
+
+    let (canonical, caller_values) = match infcx.canonicalize(projection_ty) {
+        Some(result) => result,
+        None => return None,
+    };
+    let key = (infcx.projection_mode(), param_env.caller_bounds.clone(), canonical);
+
+    let tcx = infcx.tcx;
+    let response = match tcx.normalization_query_cache.get(&key) {
+        Some(response) => response,
+        None => {
+            let query_infcx = infer::new_infer_ctxt(tcx,
+                                                    &tcx.tables,
+                                                    Some(param_env.clone()),
+                                                    key.0);
+            let (query_projection_ty, query_values) =
+                query_infcx.instantiate_canonical(cause.span, &key.2);
+            let mut query_selcx = SelectionContext::new(&query_infcx);
+            let answer = opt_normalize_projection_type(&mut query_selcx,
+                                                       query_projection_ty,
+                                                       cause.clone(),
+                                                       0);
+            let answer = answer.map(|Normalized { value, obligations }| {
+                (value, obligations.into_iter().map(|o| o.predicate).collect::<Vec<_>>())
+            });
+            let response = match query_infcx.make_query_response(&query_values, &answer) {
+                Some(response) => response,
+                None => return None,
+            };
+            debug!("normalize_projection_type_query: {:?} = {:?}", key.2, response);
+
+            tcx.normalization_query_cache.insert(key, response.clone());
+            response
+        }
+    };
+
+    let InferOk { value: answer, obligations: mut eq_obligations } =
+        infcx.instantiate_query_response(cause.span, &caller_values, &response);
+    Some(answer.map(|(value, predicates)| {
+        eq_obligations.extend(predicates.into_iter().map(|predicate| {
+            Obligation::with_depth(cause.clone(), 1, predicate)
+        }));
+        Normalized {
+            value: value,
+            obligations: eq_obligations,
+        }
+    }))
+}
+
+/// Normalizes `projection_ty` under the where-clauses of `param_env`,
+/// without the caller needing an inference context of its own. Code
+/// that keeps projections in the types it builds, as `AstConv` does,
+/// uses this at the points where it needs the concrete type.
+///
+/// If the projection cannot be normalized -- it is ambiguous, or an
+/// obligation it depends on does not hold -- it is returned unchanged
+/// for the caller to deal with. Answers that cannot depend on the
+/// environment are cached in `tcx.normalized_projection_cache`; they
+/// have their regions erased, as trans expects.
+pub fn normalize_projection_ty<'a,'tcx>(param_env: &ty::ParameterEnvironment<'a,'tcx>,
+                                        projection_ty: ty::ProjectionTy<'tcx>,
+                                        projection_mode: ProjectionMode)
+                                        -> Ty<'tcx>
+{
+    let tcx = param_env.tcx;
+    let unnormalized = tcx.mk_projection(projection_ty.trait_ref, projection_ty.item_name);
+    let cacheable = param_env.caller_bounds.is_empty() &&
+                    projection_mode == ProjectionMode::Any &&
+                    !unnormalized.needs_subst() &&
+                    !unnormalized.needs_infer() &&
+                    !unnormalized.has_erasable_regions();
+    if cacheable {
+        if let Some(&ty) = tcx.normalized_projection_cache.borrow().get(&projection_ty) {
+            return ty;
+        }
+    }
+
+    let infcx = infer::new_infer_ctxt(tcx, &tcx.tables, Some(param_env.clone()),
+                                      projection_mode);
+    let mut selcx = SelectionContext::new(&infcx);
+    let cause = ObligationCause::dummy();
+    let result = match opt_normalize_projection_type(&mut selcx, projection_ty, cause, 0) {
+        Some(Normalized { value, obligations }) => {
+            let mut fulfill_cx = FulfillmentContext::new();
+            for obligation in obligations {
+                fulfill_cx.register_predicate_obligation(&infcx, obligation);
+            }
+            match fulfill_cx.select_all_or_error(&infcx) {
+                Ok(()) => {
+                    let ty = infcx.resolve_type_vars_if_possible(&value);
+                    let ty = if cacheable { tcx.erase_regions(&ty) } else { ty };
+                    if ty.needs_infer() { unnormalized } else { ty }
+                }
+                Err(_) => unnormalized,
+            }
+        }
+        None => unnormalized,
+    };
+    debug!("normalize_projection_ty({:?}) = {:?}", projection_ty, result);
+
+    if cacheable {
+        tcx.normalized_projection_cache.borrow_mut().insert(projection_ty, result);
+    }
+    result
+}
+
 /// The guts of `normalize`: normalize a specific projection like `<T
 /// as Trait>::Item`. The result is always a type (and possibly
 /// additional obligations). Returns `None` in the case of ambiguity,
 /// which indicates that there are unbound type variables.
+///
+/// Results are remembered in the inference context's projection
+/// cache, so normalizing the same projection again is cheap. The cache
+/// also notices when normalizing a projection requires normalizing
+/// that same projection, and reports the cycle as an overflow.
 fn opt_normalize_projection_type<'a,'b,'tcx>(
     selcx: &'a mut SelectionContext<'b,'tcx>,
     projection_ty: ty::ProjectionTy<'tcx>,
@@ -462,6 +623,9 @@ fn opt_normalize_projection_type<'a,'b,'tcx>(
     depth: usize)
     -> Option<NormalizedTy<'tcx>>
 {
+    let infcx = selcx.infcx();
+    let projection_ty = infcx.resolve_type_vars_if_possible(&projection_ty);
+
     debug!("normalize_projection_type(\
            projection_ty={:?}, \
            depth={})",
@@ -469,6 +633,33 @@ fn opt_normalize_projection_type<'a,'b,'tcx>(
            depth);
 
     let obligation = Obligation::with_depth(cause.clone(), depth, projection_ty.clone());
+
+    let cache_result = infcx.projection_cache.borrow_mut().try_start(projection_ty);
+    match cache_result {
+        Ok(()) => { }
+        Err(ProjectionCacheEntry::InProgress) => {
+            // Normalizing this projection requires normalizing it
+            // again, so it can never finish.
+            debug!("normalize_projection_type: found cache entry: in-progress");
+            report_overflow_error(infcx, &obligation, false);
+        }
+        Err(ProjectionCacheEntry::Ambiguous) => {
+            debug!("normalize_projection_type: found cache entry: ambiguous");
+            return None;
+        }
+        Err(ProjectionCacheEntry::Error) => {
+            debug!("normalize_projection_type: found cache entry: error");
+            return Some(normalize_to_error(selcx, projection_ty, cause, depth));
+        }
+        Err(ProjectionCacheEntry::NormalizedTy(ty)) => {
+            // The obligations were already handed out along with the
+            // first answer, but may not have been registered by whoever
+            // got them, so hand them out again.
+            debug!("normalize_projection_type: found cache entry: {:?}", ty.value);
+            return Some(ty);
+        }
+    }
+
     match project_type(selcx, &obligation) {
         Ok(ProjectedTy::Progress(projected_ty, mut obligations)) => {
             // if projection succeeded, then what we get out of this
@@ -490,27 +681,34 @@ fn opt_normalize_projection_type<'a,'b,'tcx>(
                        depth);
 
                 obligations.extend(normalizer.obligations);
-                Some(Normalized {
+                let result = Normalized {
                     value: normalized_ty,
                     obligations: obligations,
-                })
+                };
+                infcx.projection_cache.borrow_mut().complete(projection_ty, &result);
+                Some(result)
             } else {
-                Some(Normalized {
+                let result = Normalized {
                     value: projected_ty,
                     obligations: obligations,
-                })
+                };
+                infcx.projection_cache.borrow_mut().complete(projection_ty, &result);
+                Some(result)
             }
         }
         Ok(ProjectedTy::NoProgress(projected_ty)) => {
             debug!("normalize_projection_type: projected_ty={:?} no progress",
                    projected_ty);
-            Some(Normalized {
+            let result = Normalized {
                 value: projected_ty,
                 obligations: vec!()
-            })
+            };
+            infcx.projection_cache.borrow_mut().complete(projection_ty, &result);
+            Some(result)
         }
         Err(ProjectionTyError::TooManyCandidates) => {
             debug!("normalize_projection_type: too many candidates");
+            infcx.projection_cache.borrow_mut().ambiguous(projection_ty);
             None
         }
         Err(ProjectionTyError::TraitSelectionError(_)) => {
@@ -520,6 +718,7 @@ fn opt_normalize_projection_type<'a,'b,'tcx>(
             // Trait`, which when processed will cause the error to be
             // reported later
 
+            infcx.projection_cache.borrow_mut().error(projection_ty);
             Some(normalize_to_error(selcx, projection_ty, cause, depth))
         }
     }
@@ -551,10 +750,11 @@ fn normalize_to_error<'a,'tcx>(selcx: &mut SelectionContext<'a,'tcx>,
                                -> NormalizedTy<'tcx>
 {
     let trait_ref = projection_ty.trait_ref.to_poly_trait_ref();
+    let origin = TypeVariableOrigin::NormalizeProjectionType(cause.span);
     let trait_obligation = Obligation { cause: cause,
                                         recursion_depth: depth,
                                         predicate: trait_ref.to_predicate() };
-    let new_value = selcx.infcx().next_ty_var();
+    let new_value = selcx.infcx().next_ty_var(origin);
     Normalized {
         value: new_value,
         obligations: vec!(trait_obligation)
@@ -827,20 +1027,13 @@ fn assemble_candidates_from_predicates<'cx,'tcx,I>(
             ty::Predicate::Projection(ref data) => {
                 let same_name = data.item_name() == obligation.predicate.item_name;
 
-                let is_match = same_name && infcx.probe(|_| {
-                    let origin = TypeOrigin::Misc(obligation.cause.span);
-                    let data_poly_trait_ref =
-                        data.to_poly_trait_ref();
-                    let obligation_poly_trait_ref =
-                        obligation_trait_ref.to_poly_trait_ref();
-                    infcx.sub_poly_trait_refs(false,
-                                              origin,
-                                              data_poly_trait_ref,
-                                              obligation_poly_trait_ref)
+                let is_match = same_name &&
+                    infcx.can_sub(selcx.param_env(),
+                                  &data.to_poly_trait_ref(),
+                                  &obligation_trait_ref.to_poly_trait_ref())
                         // FIXME(#32730) propagate obligations
                         .map(|InferOk { obligations, .. }| assert!(obligations.is_empty()))
-                        .is_ok()
-                });
+                        .is_ok();
 
                 debug!("assemble_candidates_from_predicates: candidate={:?} \
                                                              is_match={} same_name={}",
@@ -1176,3 +1369,92 @@ fn assoc_ty_def<'cx, 'tcx>(selcx: &SelectionContext<'cx, 'tcx>,
             .next()
     }
 }
+
+/// A cache of the results of normalizing projections within one
+/// inference context. Projections like `<T as Iterator>::Item` tend to
+/// be normalized over and over while checking a function, and each
+/// normalization involves a full trait selection.
+///
+/// An entry records the normalized type together with the obligations
+/// that normalizing produced, since those must still be proven by
+/// whoever uses the answer. Projections that are being normalized are
+/// marked as in progress, so that a projection whose normalization
+/// requires itself is reported as an overflow right away rather than
+/// only after hitting the recursion limit.
+///
+/// The cache takes part in the snapshots of the inference context:
+/// entries made inside a snapshot that is rolled back are forgotten, as
+/// they may mention type variables that no longer exist.
+pub struct ProjectionCache<'tcx> {
+    map: SnapshotMap<ty::ProjectionTy<'tcx>, ProjectionCacheEntry<'tcx>>,
+}
+
+#[derive(Clone, Debug)]
+enum ProjectionCacheEntry<'tcx> {
+    InProgress,
+    Ambiguous,
+    Error,
+    NormalizedTy(NormalizedTy<'tcx>),
+}
+
+// NB: intentionally not Clone
+pub struct ProjectionCacheSnapshot {
+    snapshot: Snapshot,
+}
+
+impl<'tcx> ProjectionCache<'tcx> {
+    pub fn new() -> Self {
+        ProjectionCache {
+            map: SnapshotMap::new()
+        }
+    }
+
+    pub fn snapshot(&mut self) -> ProjectionCacheSnapshot {
+        ProjectionCacheSnapshot { snapshot: self.map.snapshot() }
+    }
+
+    pub fn rollback_to(&mut self, snapshot: ProjectionCacheSnapshot) {
+        self.map.rollback_to(snapshot.snapshot);
+    }
+
+    pub fn commit(&mut self, snapshot: ProjectionCacheSnapshot) {
+        self.map.commit(snapshot.snapshot);
+    }
+
+    /// Try to start normalizing `key`; returns an error if
+    /// normalization already occurred (this error corresponds to a
+    /// cache hit, so it's actually a good thing).
+    fn try_start(&mut self, key: ty::ProjectionTy<'tcx>)
+                 -> Result<(), ProjectionCacheEntry<'tcx>> {
+        if let Some(entry) = self.map.get(&key) {
+            return Err(entry.clone());
+        }
+
+        self.map.insert(key, ProjectionCacheEntry::InProgress);
+        Ok(())
+    }
+
+    /// Indicates that `key` was normalized to `value`.
+    fn complete(&mut self, key: ty::ProjectionTy<'tcx>, value: &NormalizedTy<'tcx>) {
+        debug!("ProjectionCache::complete: adding cache entry: key={:?}, value={:?}",
+               key, value.value);
+        let fresh_key = self.map.insert(key, ProjectionCacheEntry::NormalizedTy(value.clone()));
+        assert!(!fresh_key, "never started projecting `{:?}`", key);
+    }
+
+    /// Indicates that trying to normalize `key` resulted in
+    /// ambiguity. No point in trying it again then until we gain more
+    /// type information (in which case, the "fully resolved" key will
+    /// be different).
+    fn ambiguous(&mut self, key: ty::ProjectionTy<'tcx>) {
+        let fresh = self.map.insert(key, ProjectionCacheEntry::Ambiguous);
+        assert!(!fresh, "never started projecting `{:?}`", key);
+    }
+
+    /// Indicates that trying to normalize `key` resulted in
+    /// error.
+    fn error(&mut self, key: ty::ProjectionTy<'tcx>) {
+        let fresh = self.map.insert(key, ProjectionCacheEntry::Error);
+        assert!(!fresh, "never started projecting `{:?}`", key);
+    }
+}
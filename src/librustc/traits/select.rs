@@ -16,14 +16,14 @@ use self::SelectionCandidate::*;
 use self::BuiltinBoundConditions::*;
 use self::EvaluationResult::*;
 
-use super::coherence;
+use super::coherence::{self, Conflict};
 use super::DerivedObligationCause;
 use super::project;
 use super::project::{normalize_with_depth, Normalized};
 use super::{PredicateObligation, TraitObligation, ObligationCause};
 use super::report_overflow_error;
 use super::{ObligationCauseCode, BuiltinDerivedObligation, ImplDerivedObligation};
-use super::{SelectionError, Unimplemented, OutputTypeParameterMismatch};
+use super::{SelectionError, Unimplemented, OutputTypeParameterMismatch, Overflow};
 use super::{ObjectCastObligation, Obligation};
 use super::ProjectionMode;
 use super::TraitNotObjectSafe;
@@ -39,16 +39,20 @@ use super::util;
 use hir::def_id::DefId;
 use infer;
 use infer::{InferCtxt, InferOk, TypeFreshener, TypeOrigin};
+use infer::canonical::QueryCache;
 use ty::subst::{Subst, Substs, TypeSpace};
 use ty::{self, ToPredicate, ToPolyTraitRef, Ty, TyCtxt, TypeFoldable};
 use traits;
 use ty::fast_reject;
+use ty::fold::TypeVisitor;
 use ty::relate::TypeRelation;
 
 use std::cell::RefCell;
 use std::fmt;
 use std::rc::Rc;
 use syntax::abi::Abi;
+use syntax::errors::DiagnosticBuilder;
+use syntax::util::stack;
 use hir;
 use util::common::ErrorReported;
 use util::nodemap::FnvHashMap;
@@ -63,21 +67,81 @@ pub struct SelectionContext<'cx, 'tcx:'cx> {
     /// recursively require themselves.
     freshener: TypeFreshener<'cx, 'tcx>,
 
-    /// If true, indicates that the evaluation should be conservative
+    /// If set, indicates that the evaluation should be conservative
     /// and consider the possibility of types outside this crate.
     /// This comes up primarily when resolving ambiguity. Imagine
     /// there is some trait reference `$0 : Bar` where `$0` is an
-    /// inference variable. If `intercrate` is true, then we can never
+    /// inference variable. If `intercrate` is set, then we can never
     /// say for sure that this reference is not implemented, even if
     /// there are *no impls at all for `Bar`*, because `$0` could be
     /// bound to some type that in a downstream crate that implements
     /// `Bar`. This is the suitable mode for coherence. Elsewhere,
-    /// though, we set this to false, because we are only interested
+    /// though, we leave this unset, because we are only interested
     /// in types that the user could actually have written --- in
     /// other words, we consider `$0 : Bar` to be unimplemented if
     /// there is no type that the user could *actually name* that
     /// would satisfy it. This avoids crippling inference, basically.
     intercrate: bool,
+
+    /// If set, the reasons why a trait reference was considered
+    /// ambiguous in intercrate mode are recorded here, so that
+    /// coherence can explain why two impls may overlap.
+    intercrate_ambiguity_causes: Option<Vec<IntercrateAmbiguityCause>>,
+
+    /// If set, hitting the recursion limit is not a fatal error, but
+    /// makes evaluation fail with `OverflowError` and selection with
+    /// `Overflow`. This is the suitable mode for speculative probes,
+    /// such as method lookup and coercion, which can simply treat a
+    /// candidate that overflows as one that does not apply; should the
+    /// candidate be committed to, it is selected again by a context
+    /// that reports the overflow.
+    recover_from_overflow: bool,
+}
+
+/// Trait evaluation hit the recursion limit in a `SelectionContext`
+/// that recovers from overflow.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OverflowError;
+
+/// Why a trait reference was ambiguous in intercrate mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntercrateAmbiguityCause {
+    DownstreamCrate {
+        trait_desc: String,
+        self_desc: Option<String>,
+    },
+    UpstreamCrateUpdate {
+        trait_desc: String,
+        self_desc: Option<String>,
+    },
+}
+
+impl IntercrateAmbiguityCause {
+    /// Adds a note to `err` explaining this ambiguity.
+    pub fn add_intercrate_ambiguity_hint(&self, err: &mut DiagnosticBuilder) {
+        err.note(&self.intercrate_ambiguity_hint());
+    }
+
+    pub fn intercrate_ambiguity_hint(&self) -> String {
+        match *self {
+            IntercrateAmbiguityCause::DownstreamCrate { ref trait_desc, ref self_desc } => {
+                let self_desc = match *self_desc {
+                    Some(ref ty) => format!(" for type `{}`", ty),
+                    None => String::new(),
+                };
+                format!("downstream crates may implement trait `{}`{}", trait_desc, self_desc)
+            }
+            IntercrateAmbiguityCause::UpstreamCrateUpdate { ref trait_desc, ref self_desc } => {
+                let self_desc = match *self_desc {
+                    Some(ref ty) => format!(" for type `{}`", ty),
+                    None => String::new(),
+                };
+                format!("upstream crates may add a new impl of trait `{}`{} \
+                         in future versions",
+                        trait_desc, self_desc)
+            }
+        }
+    }
 }
 
 // A stack that walks back up the stack frame.
@@ -212,6 +276,10 @@ enum SelectionCandidate<'tcx> {
     BuiltinObjectCandidate,
 
     BuiltinUnsizeCandidate,
+
+    /// A trait alias holds for any type for which the bounds it
+    /// stands for hold.
+    TraitAliasCandidate(DefId),
 }
 
 struct SelectionCandidateSet<'tcx> {
@@ -258,12 +326,43 @@ pub struct EvaluationCache<'tcx> {
     hashmap: RefCell<FnvHashMap<ty::PolyTraitRef<'tcx>, EvaluationResult>>
 }
 
+/// Evaluation results shared by all inference contexts, keyed by the
+/// where-clauses in scope and the trait-ref being evaluated, both with
+/// their free regions erased. This lets every fn with the same
+/// where-clauses reuse results such as `Vec<T>: Sized`, which the
+/// `EvaluationCache` of each fn's `ParameterEnvironment` would prove
+/// all over again.
+///
+/// Erasing regions is only sound as long as the result cannot depend
+/// on them; see `is_region_independent`.
+pub struct GlobalEvaluationCache<'tcx> {
+    hashmap: RefCell<FnvHashMap<GlobalEvaluationKey<'tcx>, EvaluationResult>>
+}
+
+type GlobalEvaluationKey<'tcx> = (Vec<ty::Predicate<'tcx>>, ty::PolyTraitRef<'tcx>);
+
+/// The answers of `evaluate_obligation`, asked as a canonical query;
+/// see `infer::canonical`.
+pub type EvaluationQueryCache<'tcx> = QueryCache<'tcx, ty::Predicate<'tcx>, bool>;
+
 impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     pub fn new(infcx: &'cx InferCtxt<'cx, 'tcx>) -> SelectionContext<'cx, 'tcx> {
         SelectionContext {
             infcx: infcx,
             freshener: infcx.freshener(),
             intercrate: false,
+            intercrate_ambiguity_causes: None,
+            recover_from_overflow: false,
+        }
+    }
+
+    /// A selection context for speculative probes, in which overflow
+    /// makes a candidate fail to apply instead of aborting
+    /// compilation; see `recover_from_overflow`.
+    pub fn probing(infcx: &'cx InferCtxt<'cx, 'tcx>) -> SelectionContext<'cx, 'tcx> {
+        SelectionContext {
+            recover_from_overflow: true,
+            ..SelectionContext::new(infcx)
         }
     }
 
@@ -272,6 +371,33 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             infcx: infcx,
             freshener: infcx.freshener(),
             intercrate: true,
+            intercrate_ambiguity_causes: None,
+            recover_from_overflow: false,
+        }
+    }
+
+    /// Start recording the causes of intercrate ambiguity; see
+    /// `take_intercrate_ambiguity_causes`.
+    pub fn enable_tracking_intercrate_ambiguity_causes(&mut self) {
+        assert!(self.intercrate);
+        assert!(self.intercrate_ambiguity_causes.is_none());
+        self.intercrate_ambiguity_causes = Some(vec![]);
+        debug!("selcx: enable_tracking_intercrate_ambiguity_causes");
+    }
+
+    /// Returns the causes of intercrate ambiguity recorded since
+    /// tracking was enabled, and stops recording them.
+    pub fn take_intercrate_ambiguity_causes(&mut self) -> Vec<IntercrateAmbiguityCause> {
+        assert!(self.intercrate);
+        self.intercrate_ambiguity_causes.take().unwrap_or(vec![])
+    }
+
+    fn add_intercrate_ambiguity_cause(&mut self, cause: IntercrateAmbiguityCause) {
+        if let Some(ref mut causes) = self.intercrate_ambiguity_causes {
+            debug!("add_intercrate_ambiguity_cause: {:?}", cause);
+            if !causes.contains(&cause) {
+                causes.push(cause);
+            }
         }
     }
 
@@ -279,6 +405,10 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         self.infcx
     }
 
+    pub fn is_intercrate(&self) -> bool {
+        self.intercrate
+    }
+
     pub fn tcx(&self) -> &'cx TyCtxt<'tcx> {
         self.infcx.tcx
     }
@@ -327,6 +457,25 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         }
     }
 
+    /// Attempts to prove the negative obligation `T: !Trait`, where
+    /// `obligation` is `T: Trait`. Returns `Ok(None)` if that cannot
+    /// be decided yet. Never affects the inference environment.
+    pub fn select_negative(&mut self, obligation: &TraitObligation<'tcx>)
+                           -> SelectionResult<'tcx, ()> {
+        debug!("select_negative({:?})", obligation);
+        assert!(!obligation.predicate.has_escaping_regions());
+
+        let result = self.infcx.probe(|_| {
+            self.evaluate_negative_obligation(TraitObligationStackList::empty(), obligation)
+        });
+        match result {
+            Ok(EvaluatedToOk) => Ok(Some(())),
+            Ok(EvaluatedToUnknown) | Ok(EvaluatedToAmbig) => Ok(None),
+            Ok(EvaluatedToErr) => Err(Unimplemented),
+            Err(OverflowError) => Err(Overflow),
+        }
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     // EVALUATION
     //
@@ -338,6 +487,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     // we can be sure it does not.
 
     /// Evaluates whether the obligation `obligation` can be satisfied (by any means).
+    /// When recovering from overflow, an obligation whose evaluation
+    /// overflows is considered not to hold.
     pub fn evaluate_obligation(&mut self,
                                obligation: &PredicateObligation<'tcx>)
                                -> bool
@@ -345,12 +496,73 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         debug!("evaluate_obligation({:?})",
                obligation);
 
+        if let Some(result) = self.evaluate_obligation_query(obligation) {
+            return result;
+        }
+
         self.infcx.probe(|_| {
             self.evaluate_predicate_recursively(TraitObligationStackList::empty(), obligation)
-                .may_apply()
+                .map(|result| result.may_apply())
+                .unwrap_or(false)
         })
     }
 
+    /// Answers `evaluate_obligation` as a canonical query: the
+    /// predicate is canonicalized, evaluated in an inference context
+    /// of its own, and the answer cached in the tcx under the
+    /// canonical form. Returns `None` if the predicate cannot be asked
+    /// as a query, in which case it is evaluated here directly.
+    fn evaluate_obligation_query(&mut self,
+                                 obligation: &PredicateObligation<'tcx>)
+                                 -> Option<bool>
+    {
+        // Closure signatures and kinds are only known to the inference
+        // context of the fn that contains the closure.
+        let param_env = self.param_env();
+        if self.intercrate ||
+           obligation.predicate.has_closure_types() ||
+           param_env.caller_bounds.needs_infer()
+        {
+            return None;
+        }
+
+        let canonical = match self.infcx.canonicalize(&obligation.predicate) {
+            Some((canonical, _)) => canonical,
+            None => return None,
+        };
+        let key = (self.infcx.projection_mode(), param_env.caller_bounds.clone(), canonical);
+
+        let tcx = self.tcx();
+        if let Some(result) = tcx.evaluation_query_cache.get(&key) {
+            debug!("evaluate_obligation_query: cached {:?}", result);
+            return Some(result);
+        }
+
+        let query_infcx = infer::new_infer_ctxt(tcx,
+                                                &tcx.tables,
+                                                Some(param_env.clone()),
+                                                key.0);
+        let (predicate, _) = query_infcx.instantiate_canonical(obligation.cause.span, &key.2);
+        let query_obligation = Obligation::new(obligation.cause.clone(), predicate);
+        let result = query_infcx.probe(|_| {
+            let mut selcx = SelectionContext::new(&query_infcx);
+            selcx.recover_from_overflow = self.recover_from_overflow;
+            selcx.evaluate_predicate_recursively(TraitObligationStackList::empty(),
+                                                 &query_obligation)
+        });
+        debug!("evaluate_obligation_query: {:?} = {:?}", key.2, result);
+
+        // Whether the evaluation overflows depends on how deep in the
+        // stack the query was asked, so overflow is not cached.
+        let result = match result {
+            Ok(result) => result.may_apply(),
+            Err(OverflowError) => return Some(false),
+        };
+
+        tcx.evaluation_query_cache.insert(key, result);
+        Some(result)
+    }
+
     /// Evaluates whether the obligation `obligation` can be satisfied,
     /// and returns `false` if not certain. However, this is not entirely
     /// accurate if inference variables are involved.
@@ -363,7 +575,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
 
         self.infcx.probe(|_| {
             self.evaluate_predicate_recursively(TraitObligationStackList::empty(), obligation)
-                == EvaluatedToOk
+                == Ok(EvaluatedToOk)
         })
     }
 
@@ -373,16 +585,16 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     fn evaluate_predicates_recursively<'a,'o,I>(&mut self,
                                                 stack: TraitObligationStackList<'o, 'tcx>,
                                                 predicates: I)
-                                                -> EvaluationResult
+                                                -> Result<EvaluationResult, OverflowError>
         where I : Iterator<Item=&'a PredicateObligation<'tcx>>, 'tcx:'a
     {
         let mut result = EvaluatedToOk;
         for obligation in predicates {
-            let eval = self.evaluate_predicate_recursively(stack, obligation);
+            let eval = self.evaluate_predicate_recursively(stack, obligation)?;
             debug!("evaluate_predicate_recursively({:?}) = {:?}",
                    obligation, eval);
             match eval {
-                EvaluatedToErr => { return EvaluatedToErr; }
+                EvaluatedToErr => { return Ok(EvaluatedToErr); }
                 EvaluatedToAmbig => { result = EvaluatedToAmbig; }
                 EvaluatedToUnknown => {
                     if result < EvaluatedToUnknown {
@@ -392,13 +604,13 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 EvaluatedToOk => { }
             }
         }
-        result
+        Ok(result)
     }
 
     fn evaluate_predicate_recursively<'o>(&mut self,
                                           previous_stack: TraitObligationStackList<'o, 'tcx>,
                                           obligation: &PredicateObligation<'tcx>)
-                                           -> EvaluationResult
+                                           -> Result<EvaluationResult, OverflowError>
     {
         debug!("evaluate_predicate_recursively({:?})",
                obligation);
@@ -408,7 +620,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         // predicates that are global in scope and hence unaffected by
         // the current environment.
         if self.tcx().fulfilled_predicates.borrow().check_duplicate(&obligation.predicate) {
-            return EvaluatedToOk;
+            return Ok(EvaluatedToOk);
         }
 
         match obligation.predicate {
@@ -418,15 +630,21 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 self.evaluate_obligation_recursively(previous_stack, &obligation)
             }
 
+            ty::Predicate::NegativeTrait(ref t) => {
+                assert!(!t.has_escaping_regions());
+                let obligation = obligation.with(t.clone());
+                self.evaluate_negative_obligation(previous_stack, &obligation)
+            }
+
             ty::Predicate::Equate(ref p) => {
                 // does this code ever run?
                 match self.infcx.equality_predicate(obligation.cause.span, p) {
                     Ok(InferOk { obligations, .. }) => {
                         // FIXME(#32730) propagate obligations
                         assert!(obligations.is_empty());
-                        EvaluatedToOk
+                        Ok(EvaluatedToOk)
                     },
-                    Err(_) => EvaluatedToErr
+                    Err(_) => Ok(EvaluatedToErr)
                 }
             }
 
@@ -436,21 +654,21 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                     Some(obligations) =>
                         self.evaluate_predicates_recursively(previous_stack, obligations.iter()),
                     None =>
-                        EvaluatedToAmbig,
+                        Ok(EvaluatedToAmbig),
                 }
             }
 
             ty::Predicate::TypeOutlives(..) | ty::Predicate::RegionOutlives(..) => {
                 // we do not consider region relationships when
                 // evaluating trait matches
-                EvaluatedToOk
+                Ok(EvaluatedToOk)
             }
 
             ty::Predicate::ObjectSafe(trait_def_id) => {
                 if object_safety::is_object_safe(self.tcx(), trait_def_id) {
-                    EvaluatedToOk
+                    Ok(EvaluatedToOk)
                 } else {
-                    EvaluatedToErr
+                    Ok(EvaluatedToErr)
                 }
             }
 
@@ -462,10 +680,10 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                                                              subobligations.iter())
                     }
                     Ok(None) => {
-                        EvaluatedToAmbig
+                        Ok(EvaluatedToAmbig)
                     }
                     Err(_) => {
-                        EvaluatedToErr
+                        Ok(EvaluatedToErr)
                     }
                 }
             }
@@ -474,13 +692,13 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 match self.infcx.closure_kind(closure_def_id) {
                     Some(closure_kind) => {
                         if closure_kind.extends(kind) {
-                            EvaluatedToOk
+                            Ok(EvaluatedToOk)
                         } else {
-                            EvaluatedToErr
+                            Ok(EvaluatedToErr)
                         }
                     }
                     None => {
-                        EvaluatedToAmbig
+                        Ok(EvaluatedToAmbig)
                     }
                 }
             }
@@ -490,7 +708,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     fn evaluate_obligation_recursively<'o>(&mut self,
                                            previous_stack: TraitObligationStackList<'o, 'tcx>,
                                            obligation: &TraitObligation<'tcx>)
-                                           -> EvaluationResult
+                                           -> Result<EvaluationResult, OverflowError>
     {
         debug!("evaluate_obligation_recursively({:?})",
                obligation);
@@ -501,22 +719,100 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             debug!("CACHE HIT: EVAL({:?})={:?}",
                    fresh_trait_ref,
                    result);
-            return result;
+            return Ok(result);
         }
 
-        let result = self.evaluate_stack(&stack);
+        let result = self.evaluate_stack(&stack)?;
 
         debug!("CACHE MISS: EVAL({:?})={:?}",
                fresh_trait_ref,
                result);
         self.insert_evaluation_cache(fresh_trait_ref, result);
 
-        result
+        Ok(result)
+    }
+
+    /// Evaluates `T: !Trait`, given `obligation` as `T: Trait`. This
+    /// holds if a where-clause says so or a negative impl applies, and
+    /// is refuted if `T: Trait` holds. Short of both, the only evidence
+    /// left is that no impl applies. That is enough for a type without
+    /// type parameters and a local trait -- but not in coherence, as
+    /// impls in other crates could still make the trait apply. The crate
+    /// defining a foreign trait can always add impls of it.
+    fn evaluate_negative_obligation<'o>(&mut self,
+                                        previous_stack: TraitObligationStackList<'o, 'tcx>,
+                                        obligation: &TraitObligation<'tcx>)
+                                        -> Result<EvaluationResult, OverflowError>
+    {
+        debug!("evaluate_negative_obligation({:?})", obligation);
+
+        let positive = self.evaluate_obligation_recursively(previous_stack, obligation)?;
+        if positive == EvaluatedToOk {
+            return Ok(EvaluatedToErr);
+        }
+
+        if obligation.predicate.has_infer_types() {
+            return Ok(EvaluatedToAmbig);
+        }
+
+        let in_caller_bounds = self.param_env().caller_bounds.iter().any(|bound| {
+            match *bound {
+                ty::Predicate::NegativeTrait(ref data) => {
+                    let bound = data.to_poly_trait_ref();
+                    self.infcx.probe(|_| self.match_poly_trait_ref(obligation, bound).is_ok())
+                }
+                _ => false
+            }
+        });
+        if in_caller_bounds || self.has_matching_negative_impl(obligation) {
+            return Ok(EvaluatedToOk);
+        }
+
+        match positive {
+            EvaluatedToErr => {}
+            _ => return Ok(EvaluatedToAmbig)
+        }
+        if self.intercrate {
+            debug!("evaluate_negative_obligation({:?}) --> no impl, intercrate --> ambiguous",
+                   obligation);
+            return Ok(EvaluatedToAmbig);
+        }
+        let trait_ref = obligation.predicate.skip_binder().trait_ref;
+        if trait_ref.has_param_types() || trait_ref.has_self_ty() || !trait_ref.def_id.is_local() {
+            Ok(EvaluatedToErr)
+        } else {
+            Ok(EvaluatedToOk)
+        }
+    }
+
+    /// True if a negative impl `impl !Trait for ..` matches `obligation`.
+    fn has_matching_negative_impl(&mut self, obligation: &TraitObligation<'tcx>) -> bool {
+        let def = self.tcx().lookup_trait_def(obligation.predicate.def_id());
+        if !def.has_negative_impls(self.tcx()) {
+            return false;
+        }
+
+        let mut found = false;
+        def.for_each_relevant_impl(
+            self.tcx(),
+            obligation.predicate.0.trait_ref.self_ty(),
+            |impl_def_id| {
+                if found ||
+                   self.tcx().trait_impl_polarity(impl_def_id) != Some(hir::ImplPolarity::Negative)
+                {
+                    return;
+                }
+                self.infcx.probe(|snapshot| {
+                    found = self.match_impl(impl_def_id, obligation, snapshot).is_ok();
+                });
+            }
+        );
+        found
     }
 
     fn evaluate_stack<'o>(&mut self,
                           stack: &TraitObligationStack<'o, 'tcx>)
-                          -> EvaluationResult
+                          -> Result<EvaluationResult, OverflowError>
     {
         // In intercrate mode, whenever any of the types are unbound,
         // there can always be an impl. Even if there are no impls in
@@ -547,7 +843,11 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         if unbound_input_types && self.intercrate {
             debug!("evaluate_stack({:?}) --> unbound argument, intercrate -->  ambiguous",
                    stack.fresh_trait_ref);
-            return EvaluatedToAmbig;
+            if self.intercrate_ambiguity_causes.is_some() {
+                let cause = self.ambiguity_cause(stack, Conflict::Downstream);
+                self.add_intercrate_ambiguity_cause(cause);
+            }
+            return Ok(EvaluatedToAmbig);
         }
         if unbound_input_types &&
               stack.iter().skip(1).any(
@@ -556,7 +856,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         {
             debug!("evaluate_stack({:?}) --> unbound argument, recursive --> giving up",
                    stack.fresh_trait_ref);
-            return EvaluatedToUnknown;
+            return Ok(EvaluatedToUnknown);
         }
 
         // If there is any previous entry on the stack that precisely
@@ -585,13 +885,14 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         {
             debug!("evaluate_stack({:?}) --> recursive",
                    stack.fresh_trait_ref);
-            return EvaluatedToOk;
+            return Ok(EvaluatedToOk);
         }
 
         match self.candidate_from_obligation(stack) {
             Ok(Some(c)) => self.evaluate_candidate(stack, &c),
-            Ok(None) => EvaluatedToAmbig,
-            Err(..) => EvaluatedToErr
+            Ok(None) => Ok(EvaluatedToAmbig),
+            Err(Overflow) => Err(OverflowError),
+            Err(..) => Ok(EvaluatedToErr)
         }
     }
 
@@ -601,7 +902,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     fn evaluate_candidate<'o>(&mut self,
                               stack: &TraitObligationStack<'o, 'tcx>,
                               candidate: &SelectionCandidate<'tcx>)
-                              -> EvaluationResult
+                              -> Result<EvaluationResult, OverflowError>
     {
         debug!("evaluate_candidate: depth={} candidate={:?}",
                stack.obligation.recursion_depth, candidate);
@@ -613,12 +914,12 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                         stack.list(),
                         selection.nested_obligations().iter())
                 }
-                Err(..) => EvaluatedToErr
+                Err(..) => Ok(EvaluatedToErr)
             }
-        });
+        })?;
         debug!("evaluate_candidate: depth={} result={:?}",
                stack.obligation.recursion_depth, result);
-        result
+        Ok(result)
     }
 
     fn pick_evaluation_cache(&self) -> &EvaluationCache<'tcx> {
@@ -633,9 +934,34 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         }
     }
 
+    /// The key for `trait_ref` in the `GlobalEvaluationCache`, if its
+    /// evaluation in the current environment can be shared.
+    fn global_evaluation_key(&self, trait_ref: ty::PolyTraitRef<'tcx>)
+                             -> Option<GlobalEvaluationKey<'tcx>>
+    {
+        // see comment in `pick_candidate_cache`
+        if self.intercrate {
+            return None;
+        }
+
+        let caller_bounds = &self.param_env().caller_bounds;
+        if !is_region_independent(&trait_ref) ||
+           !caller_bounds.iter().all(|p| is_region_independent(p)) {
+            return None;
+        }
+
+        let tcx = self.tcx();
+        Some((tcx.erase_regions(caller_bounds), tcx.erase_regions(&trait_ref)))
+    }
+
     fn check_evaluation_cache(&self, trait_ref: ty::PolyTraitRef<'tcx>)
                               -> Option<EvaluationResult>
     {
+        if let Some(key) = self.global_evaluation_key(trait_ref) {
+            let cache = &self.tcx().global_evaluation_cache;
+            return cache.hashmap.borrow().get(&key).cloned();
+        }
+
         let cache = self.pick_evaluation_cache();
         cache.hashmap.borrow().get(&trait_ref).cloned()
     }
@@ -655,6 +981,12 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             return;
         }
 
+        if let Some(key) = self.global_evaluation_key(trait_ref) {
+            let cache = &self.tcx().global_evaluation_cache;
+            cache.hashmap.borrow_mut().insert(key, result);
+            return;
+        }
+
         let cache = self.pick_evaluation_cache();
         cache.hashmap.borrow_mut().insert(trait_ref, result);
     }
@@ -675,9 +1007,26 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         // not update) the cache.
         let recursion_limit = self.infcx.tcx.sess.recursion_limit.get();
         if stack.obligation.recursion_depth >= recursion_limit {
+            if self.recover_from_overflow {
+                debug!("candidate_from_obligation: overflow on {:?}", stack.obligation);
+                return Err(Overflow);
+            }
             report_overflow_error(self.infcx(), &stack.obligation, true);
         }
 
+        // Independently of the recursion limit, bail out before we run
+        // out of native stack on very deeply nested types.
+        if stack::in_red_zone() {
+            if self.recover_from_overflow {
+                return Err(Overflow);
+            }
+            // Printing the predicate would recurse just as deeply.
+            self.tcx().sess.span_fatal_with_code(stack.obligation.cause.span,
+                                                 "reached the recursion limit while \
+                                                  evaluating a trait requirement",
+                                                 "E0275");
+        }
+
         // Check the cache. Note that we skolemize the trait-ref
         // separately rather than using `stack.fresh_trait_ref` -- this
         // is because we want the unbound variables to be replaced
@@ -722,6 +1071,24 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         Ok(Some(candidate))
     }
 
+    // True if one of `candidates` is a negative impl that definitely applies
+    fn has_applicable_negative_impl(&self,
+                                    trait_def_id: DefId,
+                                    candidates: &[EvaluatedCandidate<'tcx>])
+                                    -> bool {
+        if !self.tcx().lookup_trait_def(trait_def_id).has_negative_impls(self.tcx()) {
+            return false;
+        }
+
+        candidates.iter().any(|c| match c.candidate {
+            ImplCandidate(def_id) => {
+                c.evaluation == EvaluatedToOk &&
+                    self.tcx().trait_impl_polarity(def_id) == Some(hir::ImplPolarity::Negative)
+            }
+            _ => false,
+        })
+    }
+
     fn candidate_from_obligation_no_cache<'o>(&mut self,
                                               stack: &TraitObligationStack<'o, 'tcx>)
                                               -> SelectionResult<'tcx, SelectionCandidate<'tcx>>
@@ -737,8 +1104,12 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             return Ok(None);
         }
 
-        if !self.is_knowable(stack) {
+        if let Some(conflict) = self.is_knowable(stack) {
             debug!("coherence stage: not knowable");
+            if self.intercrate_ambiguity_causes.is_some() {
+                let cause = self.ambiguity_cause(stack, conflict);
+                self.add_intercrate_ambiguity_cause(cause);
+            }
             return Ok(None);
         }
 
@@ -785,17 +1156,27 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
 
         // Winnow, but record the exact outcome of evaluation, which
         // is needed for specialization.
-        let mut candidates: Vec<_> = candidates.into_iter().filter_map(|c| {
-            let eval = self.evaluate_candidate(stack, &c);
+        let mut evaluated_candidates = Vec::with_capacity(candidates.len());
+        for c in candidates {
+            let eval = self.evaluate_candidate(stack, &c)?;
             if eval.may_apply() {
-                Some(EvaluatedCandidate {
+                evaluated_candidates.push(EvaluatedCandidate {
                     candidate: c,
                     evaluation: eval,
-                })
-            } else {
-                None
+                });
             }
-        }).collect();
+        }
+        let mut candidates = evaluated_candidates;
+
+        // A negative impl that is known to apply disqualifies the
+        // obligation outright, whatever else might have matched: a
+        // where-clause or blanket impl cannot make `Foo: Send` hold
+        // in the face of `impl !Send for Foo`.
+        let trait_def_id = stack.obligation.predicate.def_id();
+        if self.has_applicable_negative_impl(trait_def_id, &candidates) {
+            debug!("negative impl applies, unimplemented");
+            return Err(Unimplemented);
+        }
 
         // If there are STILL multiple candidate, we can further
         // reduce the list by dropping duplicates -- including
@@ -844,14 +1225,16 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         self.filter_negative_impls(candidates.pop().unwrap().candidate)
     }
 
+    /// Returns which kind of crate could implement the trait reference
+    /// of `stack` without this crate being able to see it, if any.
     fn is_knowable<'o>(&mut self,
                        stack: &TraitObligationStack<'o, 'tcx>)
-                       -> bool
+                       -> Option<Conflict>
     {
         debug!("is_knowable(intercrate={})", self.intercrate);
 
         if !self.intercrate {
-            return true;
+            return None;
         }
 
         let obligation = &stack.obligation;
@@ -865,6 +1248,34 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         coherence::trait_ref_is_knowable(self.tcx(), trait_ref)
     }
 
+    /// Describes why the trait reference of `stack` is ambiguous, given
+    /// which kind of crate could implement it.
+    fn ambiguity_cause<'o>(&self,
+                           stack: &TraitObligationStack<'o, 'tcx>,
+                           conflict: Conflict)
+                           -> IntercrateAmbiguityCause
+    {
+        let predicate = self.infcx().resolve_type_vars_if_possible(&stack.obligation.predicate);
+        let trait_ref = predicate.skip_binder().trait_ref;
+        let self_ty = trait_ref.self_ty();
+        let trait_desc = trait_ref.to_string();
+        let self_desc = if self_ty.has_concrete_skeleton() {
+            Some(self_ty.to_string())
+        } else {
+            None
+        };
+        match conflict {
+            Conflict::Upstream => IntercrateAmbiguityCause::UpstreamCrateUpdate {
+                trait_desc: trait_desc,
+                self_desc: self_desc,
+            },
+            Conflict::Downstream => IntercrateAmbiguityCause::DownstreamCrate {
+                trait_desc: trait_desc,
+                self_desc: self_desc,
+            },
+        }
+    }
+
     fn pick_candidate_cache(&self) -> &SelectionCache<'tcx> {
         // If there are any where-clauses in scope, then we always use
         // a cache local to this particular scope. Otherwise, we
@@ -942,8 +1353,13 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         // the self-type contains no inference byproducts (that really
         // shouldn't happen in other circumstances anyway, given
         // coherence).
+        //
+        // Overflow depends on how deep in the stack the obligation
+        // was encountered, not just on the obligation, so it is not
+        // cached either.
 
         match *candidate {
+            Err(Overflow) => false,
             Ok(Some(_)) | Err(_) => true,
             Ok(None) => {
                 cache_fresh_trait_pred.0.trait_ref.substs.types.has_infer_types()
@@ -1017,6 +1433,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 self.assemble_fn_pointer_candidates(obligation, &mut candidates)?;
                 self.assemble_candidates_from_impls(obligation, &mut candidates)?;
                 self.assemble_candidates_from_object_ty(obligation, &mut candidates);
+                self.assemble_candidates_for_trait_alias(obligation, &mut candidates);
             }
         }
 
@@ -1131,7 +1548,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                         obligation: &TraitObligation<'tcx>,
                         trait_bound: ty::PolyTraitRef<'tcx>,
                         skol_trait_ref: ty::TraitRef<'tcx>,
-                        skol_map: &infer::SkolemizationMap,
+                        skol_map: &infer::SkolemizationMap<'tcx>,
                         snapshot: &infer::CombinedSnapshot)
                         -> bool
     {
@@ -1168,14 +1585,11 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                             .iter()
                             .filter_map(|o| o.to_opt_poly_trait_ref());
 
-        let matching_bounds =
-            all_bounds.filter(
-                |bound| self.evaluate_where_clause(stack, bound.clone()).may_apply());
-
-        let param_candidates =
-            matching_bounds.map(|bound| ParamCandidate(bound));
-
-        candidates.vec.extend(param_candidates);
+        for bound in all_bounds {
+            if self.evaluate_where_clause(stack, bound.clone())?.may_apply() {
+                candidates.vec.push(ParamCandidate(bound));
+            }
+        }
 
         Ok(())
     }
@@ -1183,14 +1597,14 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     fn evaluate_where_clause<'o>(&mut self,
                                  stack: &TraitObligationStack<'o, 'tcx>,
                                  where_clause_trait_ref: ty::PolyTraitRef<'tcx>)
-                                 -> EvaluationResult
+                                 -> Result<EvaluationResult, OverflowError>
     {
         self.infcx().probe(move |_| {
             match self.match_where_clause_trait_ref(stack.obligation, where_clause_trait_ref) {
                 Ok(obligations) => {
                     self.evaluate_predicates_recursively(stack.list(), obligations.iter())
                 }
-                Err(()) => EvaluatedToErr
+                Err(()) => Ok(EvaluatedToErr)
             }
         })
     }
@@ -1318,6 +1732,17 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         Ok(())
     }
 
+    fn assemble_candidates_for_trait_alias(&mut self,
+                                           obligation: &TraitObligation<'tcx>,
+                                           candidates: &mut SelectionCandidateSet<'tcx>)
+    {
+        let def_id = obligation.predicate.def_id();
+        if self.tcx().lookup_trait_def(def_id).is_alias() {
+            debug!("assemble_candidates_for_trait_alias(obligation={:?})", obligation);
+            candidates.vec.push(TraitAliasCandidate(def_id));
+        }
+    }
+
     fn assemble_candidates_from_default_impls(&mut self,
                                               obligation: &TraitObligation<'tcx>,
                                               candidates: &mut SelectionCandidateSet<'tcx>)
@@ -1346,6 +1771,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                     }
                 }
                 ty::TyParam(..) |
+                ty::TyInfer(ty::SkolemizedTy(_)) |
                 ty::TyProjection(..) => {
                     // In these cases, we don't know what the actual
                     // type is.  Therefore, we cannot break it down
@@ -1570,6 +1996,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 BuiltinObjectCandidate |
                 BuiltinUnsizeCandidate |
                 DefaultImplObjectCandidate(..) |
+                TraitAliasCandidate(..) |
                 BuiltinCandidate(..) => {
                     // We have a where-clause so don't go around looking
                     // for impls.
@@ -1785,7 +2212,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 nominal(bound, types)
             }
 
-            ty::TyProjection(_) | ty::TyParam(_) => {
+            ty::TyProjection(_) | ty::TyParam(_) |
+            ty::TyInfer(ty::SkolemizedTy(_)) => {
                 // Note: A type parameter is only considered to meet a
                 // particular bound if there is a where clause telling
                 // us that it does, and that case is handled by
@@ -1803,7 +2231,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
 
             ty::TyError => ok_if(Vec::new()),
 
-            ty::TyInfer(ty::FreshTy(_))
+            ty::TyBound(..)
+            | ty::TyInfer(ty::FreshTy(_))
             | ty::TyInfer(ty::FreshIntTy(_))
             | ty::TyInfer(ty::FreshFloatTy(_)) => {
                 bug!("asked to assemble builtin bounds of unexpected type: {:?}",
@@ -1863,8 +2292,10 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
 
             ty::TyTrait(..) |
             ty::TyParam(..) |
+            ty::TyBound(..) |
             ty::TyProjection(..) |
             ty::TyInfer(ty::TyVar(_)) |
+            ty::TyInfer(ty::SkolemizedTy(_)) |
             ty::TyInfer(ty::FreshTy(_)) |
             ty::TyInfer(ty::FreshIntTy(_)) |
             ty::TyInfer(ty::FreshFloatTy(_)) => {
@@ -1923,10 +2354,10 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     {
         let derived_cause = match self.tcx().lang_items.to_builtin_kind(trait_def_id) {
             Some(_) => {
-                self.derived_cause(obligation, BuiltinDerivedObligation)
+                self.derived_cause(obligation, None, BuiltinDerivedObligation)
             },
             None => {
-                self.derived_cause(obligation, ImplDerivedObligation)
+                self.derived_cause(obligation, None, ImplDerivedObligation)
             }
         };
 
@@ -2058,6 +2489,11 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 let data = self.confirm_builtin_unsize_candidate(obligation)?;
                 Ok(VtableBuiltin(data))
             }
+
+            TraitAliasCandidate(alias_def_id) => {
+                let data = self.confirm_trait_alias_candidate(obligation, alias_def_id);
+                Ok(VtableBuiltin(data))
+            }
         }
     }
 
@@ -2199,6 +2635,50 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     }
 
     /// See `confirm_default_impl_candidate`
+    fn confirm_trait_alias_candidate(&mut self,
+                                     obligation: &TraitObligation<'tcx>,
+                                     alias_def_id: DefId)
+                                     -> VtableBuiltinData<PredicateObligation<'tcx>>
+    {
+        debug!("confirm_trait_alias_candidate({:?}, {:?})",
+               obligation,
+               alias_def_id);
+
+        let nested: Result<Vec<_>, ()> = self.infcx.commit_if_ok(|snapshot| {
+            let poly_trait_ref = obligation.predicate.to_poly_trait_ref();
+            let (trait_ref, skol_map) =
+                self.infcx().skolemize_late_bound_regions(&poly_trait_ref, snapshot);
+            let tcx = self.tcx();
+            let cause = obligation.cause.clone();
+            let recursion_depth = obligation.recursion_depth + 1;
+
+            // The bounds the alias stands for and its where-clauses are
+            // all in the `TypeSpace`; the `SelfSpace` only holds
+            // `Self: Alias`, which is what we are proving.
+            let predicates: Vec<_> = tcx
+                .lookup_predicates(alias_def_id)
+                .predicates.get_slice(TypeSpace).iter()
+                .flat_map(|predicate| {
+                    let predicate =
+                        normalize_with_depth(self, cause.clone(), recursion_depth,
+                                             &predicate.subst(tcx, trait_ref.substs));
+                    predicate.obligations.into_iter().chain(
+                        Some(Obligation {
+                            cause: cause.clone(),
+                            recursion_depth: recursion_depth,
+                            predicate: predicate.value
+                        }))
+                }).collect();
+            Ok(self.infcx().plug_leaks(skol_map, snapshot, &predicates))
+        });
+
+        // no Errors in that code above
+        let nested = nested.unwrap();
+        debug!("confirm_trait_alias_candidate: nested={:?}", nested);
+
+        VtableBuiltinData { nested: nested }
+    }
+
     fn vtable_default_impl(&mut self,
                            obligation: &TraitObligation<'tcx>,
                            trait_def_id: DefId,
@@ -2251,7 +2731,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 self.rematch_impl(impl_def_id, obligation,
                                   snapshot);
             debug!("confirm_impl_candidate substs={:?}", substs);
-            Ok(self.vtable_impl(impl_def_id, substs, obligation.cause.clone(),
+            let cause = self.derived_cause(obligation, Some(impl_def_id), ImplDerivedObligation);
+            Ok(self.vtable_impl(impl_def_id, substs, cause,
                                 obligation.recursion_depth + 1, skol_map, snapshot))
         })
     }
@@ -2261,7 +2742,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                    mut substs: Normalized<'tcx, Substs<'tcx>>,
                    cause: ObligationCause<'tcx>,
                    recursion_depth: usize,
-                   skol_map: infer::SkolemizationMap,
+                   skol_map: infer::SkolemizationMap<'tcx>,
                    snapshot: &infer::CombinedSnapshot)
                    -> VtableImplData<'tcx, PredicateObligation<'tcx>>
     {
@@ -2650,7 +3131,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                     impl_def_id: DefId,
                     obligation: &TraitObligation<'tcx>,
                     snapshot: &infer::CombinedSnapshot)
-                    -> (Normalized<'tcx, Substs<'tcx>>, infer::SkolemizationMap)
+                    -> (Normalized<'tcx, Substs<'tcx>>, infer::SkolemizationMap<'tcx>)
     {
         match self.match_impl(impl_def_id, obligation, snapshot) {
             Ok((substs, skol_map)) => (substs, skol_map),
@@ -2667,7 +3148,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                   obligation: &TraitObligation<'tcx>,
                   snapshot: &infer::CombinedSnapshot)
                   -> Result<(Normalized<'tcx, Substs<'tcx>>,
-                             infer::SkolemizationMap), ()>
+                             infer::SkolemizationMap<'tcx>), ()>
     {
         let impl_trait_ref = self.tcx().impl_trait_ref(impl_def_id).unwrap();
 
@@ -2861,7 +3342,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                                  recursion_depth: usize,
                                  def_id: DefId, // of impl or trait
                                  substs: &Substs<'tcx>, // for impl or trait
-                                 skol_map: infer::SkolemizationMap,
+                                 skol_map: infer::SkolemizationMap<'tcx>,
                                  snapshot: &infer::CombinedSnapshot)
                                  -> Vec<PredicateObligation<'tcx>>
     {
@@ -2902,13 +3383,15 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     #[allow(unused_comparisons)]
     fn derived_cause(&self,
                      obligation: &TraitObligation<'tcx>,
+                     parent_impl_def_id: Option<DefId>,
                      variant: fn(DerivedObligationCause<'tcx>) -> ObligationCauseCode<'tcx>)
                      -> ObligationCause<'tcx>
     {
         /*!
          * Creates a cause for obligations that are derived from
          * `obligation` by a recursive search (e.g., for a builtin
-         * bound, or eventually a `impl Foo for ..`). If `obligation`
+         * bound, a `impl Foo for ..`, or the where-clauses of the
+         * impl `parent_impl_def_id`). If `obligation`
          * is itself a derived obligation, this is just a clone, but
          * otherwise we create a "derived obligation" cause so as to
          * keep track of the original root obligation for error
@@ -2921,6 +3404,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         if obligation.recursion_depth >= 0 {
             let derived_cause = DerivedObligationCause {
                 parent_trait_ref: obligation.predicate.to_poly_trait_ref(),
+                parent_impl_def_id: parent_impl_def_id,
                 parent_code: Rc::new(obligation.cause.code.clone())
             };
             let derived_code = variant(derived_cause);
@@ -2947,6 +3431,41 @@ impl<'tcx> EvaluationCache<'tcx> {
     }
 }
 
+impl<'tcx> GlobalEvaluationCache<'tcx> {
+    pub fn new() -> GlobalEvaluationCache<'tcx> {
+        GlobalEvaluationCache {
+            hashmap: RefCell::new(FnvHashMap())
+        }
+    }
+}
+
+/// Whether evaluating with `value` (a trait-ref or a where-clause) gives
+/// the same result whatever its free regions are. Selection does not
+/// check region constraints, so regions only matter when something is
+/// higher-ranked: the leak check then tells `'static` apart from other
+/// regions, which erasing them would hide.
+fn is_region_independent<'tcx, T: TypeFoldable<'tcx>>(value: &T) -> bool {
+    struct HigherRankedVisitor;
+
+    impl<'tcx> TypeVisitor<'tcx> for HigherRankedVisitor {
+        fn visit_ty(&mut self, t: Ty<'tcx>) -> bool {
+            match t.sty {
+                ty::TyBound(..) => true,
+                _ => t.super_visit_with(self)
+            }
+        }
+
+        fn visit_region(&mut self, r: ty::Region) -> bool {
+            match r {
+                ty::ReLateBound(..) | ty::ReSkolemized(..) => true,
+                _ => false
+            }
+        }
+    }
+
+    !value.visit_with(&mut HigherRankedVisitor)
+}
+
 impl<'o,'tcx> TraitObligationStack<'o,'tcx> {
     fn list(&'o self) -> TraitObligationStackList<'o,'tcx> {
         TraitObligationStackList::with(self)
@@ -26,16 +26,45 @@ use infer::{self, InferCtxt, TypeOrigin};
 use middle::region;
 use ty::subst::{Subst, Substs};
 use traits::{self, ProjectionMode, ObligationCause, Normalized};
+use traits::IntercrateAmbiguityCause;
 use ty::{self, TyCtxt};
+use util::nodemap::FnvHashMap;
 use syntax::codemap::DUMMY_SP;
 
+use std::cell::RefCell;
+
 pub mod specialization_graph;
 
+/// Memoizes `specializes`, which selection asks about the same pair of
+/// impls over and over, and which needs a fresh inference context and a
+/// round of fulfillment to answer.
+pub struct SpecializesCache {
+    map: RefCell<FnvHashMap<(DefId, DefId), bool>>,
+}
+
+impl SpecializesCache {
+    pub fn new() -> SpecializesCache {
+        SpecializesCache {
+            map: RefCell::new(FnvHashMap())
+        }
+    }
+}
+
 /// Information pertinent to an overlapping impl error.
-pub struct Overlap<'a, 'tcx: 'a> {
-    pub in_context: InferCtxt<'a, 'tcx>,
-    pub with_impl: DefId,
-    pub on_trait_ref: ty::TraitRef<'tcx>,
+pub struct OverlapError {
+    /// The impl that was being inserted into the specialization graph.
+    pub impl1: DefId,
+    /// The impl it overlaps with.
+    pub impl2: DefId,
+    /// The trait reference both impls apply to, e.g. `Foo<_>`.
+    pub trait_desc: String,
+    /// The self type both impls apply to, if it has at least some outer
+    /// concrete shell (e.g. `Box<_>`); a bare `_` adds no information.
+    pub self_desc: Option<String>,
+    /// See `traits::OverlapResult::intercrate_ambiguity_causes`. Empty
+    /// unless the overlap is only possible through impls that other
+    /// crates may add.
+    pub intercrate_ambiguity_causes: Vec<IntercrateAmbiguityCause>,
 }
 
 /// Given a subst for the requested impl, translate it to a subst
@@ -117,6 +146,16 @@ pub fn specializes(tcx: &TyCtxt, impl1_def_id: DefId, impl2_def_id: DefId) -> bo
         return false;
     }
 
+    let key = (impl1_def_id, impl2_def_id);
+    if let Some(&result) = tcx.specializes_cache.map.borrow().get(&key) {
+        return result;
+    }
+    let result = specializes_uncached(tcx, impl1_def_id, impl2_def_id);
+    tcx.specializes_cache.map.borrow_mut().insert(key, result);
+    result
+}
+
+fn specializes_uncached(tcx: &TyCtxt, impl1_def_id: DefId, impl2_def_id: DefId) -> bool {
     // We determine whether there's a subset relationship by:
     //
     // - skolemizing impl1,
@@ -11,7 +11,7 @@
 use std::cell;
 use std::rc::Rc;
 
-use super::{Overlap, specializes};
+use super::{OverlapError, specializes};
 
 use middle::cstore::CrateStore;
 use hir::def_id::DefId;
@@ -67,7 +67,7 @@ struct Children {
 }
 
 /// The result of attempting to insert an impl into a group of children.
-enum InsertResult<'a, 'tcx: 'a> {
+enum InsertResult {
     /// The impl was inserted as a new child in this group of children.
     BecameNewSibling,
 
@@ -79,7 +79,7 @@ enum InsertResult<'a, 'tcx: 'a> {
 
     /// The impl has an unresolvable overlap with an existing child (neither
     /// specializes the other).
-    Overlapped(Overlap<'a, 'tcx>),
+    Overlapped(OverlapError),
 }
 
 impl Children {
@@ -102,11 +102,11 @@ impl Children {
 
     /// Attempt to insert an impl into this set of children, while comparing for
     /// specialiation relationships.
-    fn insert<'a, 'tcx>(&mut self,
-                        tcx: &'a TyCtxt<'tcx>,
-                        impl_def_id: DefId,
-                        simplified_self: Option<SimplifiedType>)
-                        -> InsertResult<'a, 'tcx>
+    fn insert<'tcx>(&mut self,
+                    tcx: &TyCtxt<'tcx>,
+                    impl_def_id: DefId,
+                    simplified_self: Option<SimplifiedType>)
+                    -> InsertResult
     {
         for slot in match simplified_self {
             Some(sty) => self.filtered_mut(sty),
@@ -117,7 +117,7 @@ impl Children {
             let infcx = infer::new_infer_ctxt(tcx, &tcx.tables, None, ProjectionMode::Topmost);
             let overlap = traits::overlapping_impls(&infcx, possible_sibling, impl_def_id);
 
-            if let Some(impl_header) = overlap {
+            if let Some(overlap) = overlap {
                 let le = specializes(tcx, impl_def_id, possible_sibling);
                 let ge = specializes(tcx, possible_sibling, impl_def_id);
 
@@ -136,11 +136,8 @@ impl Children {
                     return InsertResult::Replaced(possible_sibling);
                 } else {
                     // overlap, but no specialization; error out
-                    return InsertResult::Overlapped(Overlap {
-                        with_impl: possible_sibling,
-                        on_trait_ref: impl_header.trait_ref.unwrap(),
-                        in_context: infcx,
-                    });
+                    return InsertResult::Overlapped(
+                        overlap_error(impl_def_id, possible_sibling, overlap));
                 }
             }
         }
@@ -163,6 +160,24 @@ impl Children {
     }
 }
 
+/// Describes the overlap of the impl being inserted, `impl1`, with an
+/// existing impl, `impl2`.
+fn overlap_error(impl1: DefId, impl2: DefId, overlap: traits::OverlapResult) -> OverlapError {
+    let trait_ref = overlap.impl_header.trait_ref.unwrap();
+    let self_ty = trait_ref.self_ty();
+    OverlapError {
+        impl1: impl1,
+        impl2: impl2,
+        trait_desc: trait_ref.to_string(),
+        self_desc: if self_ty.has_concrete_skeleton() {
+            Some(self_ty.to_string())
+        } else {
+            None
+        },
+        intercrate_ambiguity_causes: overlap.intercrate_ambiguity_causes,
+    }
+}
+
 impl Graph {
     pub fn new() -> Graph {
         Graph {
@@ -174,10 +189,10 @@ impl Graph {
     /// Insert a local impl into the specialization graph. If an existing impl
     /// conflicts with it (has overlap, but neither specializes the other),
     /// information about the area of overlap is returned in the `Err`.
-    pub fn insert<'a, 'tcx>(&mut self,
-                            tcx: &'a TyCtxt<'tcx>,
-                            impl_def_id: DefId)
-                            -> Result<(), Overlap<'a, 'tcx>> {
+    pub fn insert<'tcx>(&mut self,
+                        tcx: &TyCtxt<'tcx>,
+                        impl_def_id: DefId)
+                        -> Result<(), OverlapError> {
         assert!(impl_def_id.is_local());
 
         let trait_ref = tcx.impl_trait_ref(impl_def_id).unwrap();
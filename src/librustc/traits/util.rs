@@ -43,6 +43,9 @@ impl<'a,'tcx> PredicateSet<'a,'tcx> {
             ty::Predicate::Trait(ref data) =>
                 ty::Predicate::Trait(self.tcx.anonymize_late_bound_regions(data)),
 
+            ty::Predicate::NegativeTrait(ref data) =>
+                ty::Predicate::NegativeTrait(self.tcx.anonymize_late_bound_regions(data)),
+
             ty::Predicate::Equate(ref data) =>
                 ty::Predicate::Equate(self.tcx.anonymize_late_bound_regions(data)),
 
@@ -143,6 +146,10 @@ impl<'cx, 'tcx> Elaborator<'cx, 'tcx> {
 
                 self.stack.extend(predicates);
             }
+            ty::Predicate::NegativeTrait(..) => {
+                // `T: !Sub` does not tell us anything about the
+                // supertraits of `Sub`.
+            }
             ty::Predicate::WellFormed(..) => {
                 // Currently, we do not elaborate WF predicates,
                 // although we easily could.
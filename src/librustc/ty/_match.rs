@@ -11,6 +11,9 @@
 use ty::{self, Ty, TyCtxt};
 use ty::error::TypeError;
 use ty::relate::{self, Relate, TypeRelation, RelateResult};
+use util::nodemap::FnvHashMap;
+
+use std::collections::hash_map::Entry;
 
 /// A type "A" *matches* "B" if the fresh types in B could be
 /// substituted with values so as to make it equal to A. Matching is
@@ -87,6 +90,128 @@ impl<'a, 'tcx> TypeRelation<'a, 'tcx> for Match<'a, 'tcx> {
         }
     }
 
+    fn consts(&mut self, a: &ty::Const, b: &ty::Const) -> RelateResult<'tcx, ty::Const> {
+        debug!("{}.consts({:?}, {:?})",
+               self.tag(),
+               a,
+               b);
+        match (a, b) {
+            (&ty::Const::Value(_), &ty::Const::Value(_)) => {
+                relate::super_relate_consts(self, a, b)
+            }
+
+            // A constant variable could still turn out to be anything.
+            _ => Ok(a.clone()),
+        }
+    }
+
+    fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
+                  -> RelateResult<'tcx, ty::Binder<T>>
+        where T: Relate<'a,'tcx>
+    {
+        Ok(ty::Binder(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
+
+/// Matches a "skeleton" A, which may mention type parameters, against a
+/// type B, recording the type in B that each parameter of A lines up
+/// with. Matching succeeds if substituting those types into A would
+/// make it equal to B, regions aside.
+///
+/// Unlike unification this only goes one way: type parameters in B
+/// are treated like any other type, and inference variables in B are
+/// never bound. An inference variable in B fails the match, as it
+/// cannot be known what it stands for, so callers should resolve B
+/// as far as they can first.
+pub struct MatchAgainst<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+    map: FnvHashMap<ty::ParamTy, Ty<'tcx>>,
+}
+
+impl<'a, 'tcx> MatchAgainst<'a, 'tcx> {
+    pub fn new(tcx: &'a TyCtxt<'tcx>) -> MatchAgainst<'a, 'tcx> {
+        MatchAgainst { tcx: tcx, map: FnvHashMap() }
+    }
+
+    /// The type each parameter of the skeleton was matched with.
+    pub fn into_map(self) -> FnvHashMap<ty::ParamTy, Ty<'tcx>> {
+        self.map
+    }
+}
+
+impl<'a, 'tcx> TypeRelation<'a, 'tcx> for MatchAgainst<'a, 'tcx> {
+    fn tag(&self) -> &'static str { "MatchAgainst" }
+    fn tcx(&self) -> &'a TyCtxt<'tcx> { self.tcx }
+    fn a_is_expected(&self) -> bool { true } // irrelevant
+
+    fn relate_with_variance<T:Relate<'a,'tcx>>(&mut self,
+                                               _: ty::Variance,
+                                               a: &T,
+                                               b: &T)
+                                               -> RelateResult<'tcx, T>
+    {
+        self.relate(a, b)
+    }
+
+    fn regions(&mut self, a: ty::Region, b: ty::Region) -> RelateResult<'tcx, ty::Region> {
+        debug!("{}.regions({:?}, {:?})",
+               self.tag(),
+               a,
+               b);
+        Ok(b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        debug!("{}.tys({:?}, {:?})", self.tag(),
+               a, b);
+
+        match (&a.sty, &b.sty) {
+            (&ty::TyParam(p), _) => {
+                match self.map.entry(p) {
+                    Entry::Occupied(entry) => {
+                        // A parameter used more than once has to match
+                        // the same type each time.
+                        let bound = *entry.get();
+                        Match::new(self.tcx).relate(&bound, &b)
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(b);
+                        Ok(b)
+                    }
+                }
+            }
+
+            (&ty::TyInfer(_), _) |
+            (_, &ty::TyInfer(_)) => {
+                Err(TypeError::Sorts(relate::expected_found(self, &a, &b)))
+            }
+
+            (&ty::TyError, _) | (_, &ty::TyError) => {
+                Ok(self.tcx().types.err)
+            }
+
+            _ => {
+                relate::super_relate_tys(self, a, b)
+            }
+        }
+    }
+
+    fn consts(&mut self, a: &ty::Const, b: &ty::Const) -> RelateResult<'tcx, ty::Const> {
+        debug!("{}.consts({:?}, {:?})",
+               self.tag(),
+               a,
+               b);
+        match (a, b) {
+            (&ty::Const::Value(_), &ty::Const::Value(_)) => {
+                relate::super_relate_consts(self, a, b)
+            }
+
+            // Array lengths, the only constants in types so far, are
+            // always known by the time they are matched.
+            _ => Ok(b.clone()),
+        }
+    }
+
     fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
                   -> RelateResult<'tcx, ty::Binder<T>>
         where T: Relate<'a,'tcx>
@@ -23,6 +23,7 @@ use hir;
 pub enum AutoAdjustment<'tcx> {
     AdjustReifyFnPointer,    // go from a fn-item type to a fn-pointer type
     AdjustUnsafeFnPointer,   // go from a safe fn pointer to an unsafe fn pointer
+    AdjustClosureFnPointer,  // go from a non-capturing closure to a fn pointer
     AdjustMutToConstPointer, // go from a mut raw pointer to a const raw pointer
     AdjustDerefRef(AutoDerefRef<'tcx>),
 }
@@ -108,6 +109,7 @@ impl<'tcx> AutoAdjustment<'tcx> {
         match *self {
             AdjustReifyFnPointer |
             AdjustUnsafeFnPointer |
+            AdjustClosureFnPointer |
             AdjustMutToConstPointer => false,
             AdjustDerefRef(ref r) => r.is_identity(),
         }
@@ -175,6 +177,19 @@ impl<'tcx> ty::TyS<'tcx> {
                         }
                     }
 
+                    AdjustClosureFnPointer => {
+                        match self.sty {
+                            ty::TyClosure(def_id, ref substs) => {
+                                let closure_ty = cx.closure_type(def_id, substs);
+                                cx.coerce_closure_fn_ty(&closure_ty.sig)
+                            }
+                            ref b => {
+                                bug!("AdjustClosureFnPointer adjustment on non-closure: {:?}",
+                                     b);
+                            }
+                        }
+                    }
+
                     AdjustMutToConstPointer => {
                         match self.sty {
                             ty::TyRawPtr(mt) => cx.mk_ptr(ty::TypeAndMut {
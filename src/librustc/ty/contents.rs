@@ -241,7 +241,8 @@ impl<'tcx> ty::TyS<'tcx> {
                 }
 
                 ty::TyProjection(..) |
-                ty::TyParam(_) => {
+                ty::TyParam(_) |
+                ty::TyBound(..) => {
                     TC::All
                 }
 
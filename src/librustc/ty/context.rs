@@ -41,6 +41,7 @@ use std::borrow::Borrow;
 use std::cell::{Cell, RefCell, Ref};
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use syntax::abi::Abi;
 use syntax::ast::{self, Name, NodeId};
 use syntax::attr;
 use syntax::parse::token::{self, special_idents};
@@ -305,15 +306,27 @@ pub struct TyCtxt<'tcx> {
     // FIXME dep tracking -- should be harmless enough
     pub normalized_cache: RefCell<FnvHashMap<Ty<'tcx>, Ty<'tcx>>>,
 
+    // FIXME dep tracking -- see `traits::normalize_projection_ty`
+    pub normalized_projection_cache: RefCell<FnvHashMap<ty::ProjectionTy<'tcx>, Ty<'tcx>>>,
+
     pub lang_items: middle::lang_items::LanguageItems,
 
+    /// Items marked `#[rustc_diagnostic_item]`, in this crate or any
+    /// of its dependencies; see `get_diagnostic_item`.
+    pub diagnostic_items: middle::diagnostic_items::DiagnosticItems,
+
     /// Maps from def-id of a type or region parameter to its
     /// (inferred) variance.
     pub item_variance_map: RefCell<DepTrackingMap<maps::ItemVariances<'tcx>>>,
 
-    /// True if the variance has been computed yet; false otherwise.
+    /// True if variances can be requested yet; false otherwise.
     pub variance_computed: Cell<bool>,
 
+    /// Infers the variances of a local item and records them in
+    /// `item_variance_map`. Installed by typeck once item types have
+    /// been collected; see `item_variances`.
+    pub variance_provider: Cell<Option<fn(&TyCtxt<'tcx>, DefId)>>,
+
     /// Maps a DefId of a type to a list of its inherent impls.
     /// Contains implementations of methods that are inherent to a type.
     /// Methods in these implementations don't need to be exported.
@@ -370,6 +383,18 @@ pub struct TyCtxt<'tcx> {
     /// Merge this with `selection_cache`?
     pub evaluation_cache: traits::EvaluationCache<'tcx>,
 
+    /// Caches the results of trait evaluation across all functions
+    /// with the same where-clauses; see `traits::GlobalEvaluationCache`.
+    pub global_evaluation_cache: traits::GlobalEvaluationCache<'tcx>,
+
+    /// Caches the answers of trait evaluation and of normalization
+    /// asked as canonical queries; see `infer::canonical`.
+    pub evaluation_query_cache: traits::EvaluationQueryCache<'tcx>,
+    pub normalization_query_cache: traits::NormalizationQueryCache<'tcx>,
+
+    /// Caches the answers of `traits::specializes`.
+    pub specializes_cache: traits::SpecializesCache,
+
     /// A set of predicates that have been fulfilled *somewhere*.
     /// This is used to avoid duplicate work. Predicates are only
     /// added to this set when they mention only "global" names
@@ -379,6 +404,9 @@ pub struct TyCtxt<'tcx> {
     /// Caches the representation hints for struct definitions.
     repr_hint_cache: RefCell<DepTrackingMap<maps::ReprHints<'tcx>>>,
 
+    /// Caches the attributes that affect the translation of functions.
+    codegen_fn_attrs_cache: RefCell<DepTrackingMap<maps::CodegenFnAttrs<'tcx>>>,
+
     /// Maps Expr NodeId's to their constant qualification.
     pub const_qualif_map: RefCell<NodeMap<middle::const_qualif::ConstQualif>>,
 
@@ -526,6 +554,7 @@ impl<'tcx> TyCtxt<'tcx> {
                                  freevars: FreevarMap,
                                  region_maps: RegionMaps,
                                  lang_items: middle::lang_items::LanguageItems,
+                                 diagnostic_items: middle::diagnostic_items::DiagnosticItems,
                                  stability: stability::Index<'tcx>,
                                  crate_name: &str,
                                  f: F) -> R
@@ -549,6 +578,7 @@ impl<'tcx> TyCtxt<'tcx> {
             free_region_maps: RefCell::new(FnvHashMap()),
             item_variance_map: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             variance_computed: Cell::new(false),
+            variance_provider: Cell::new(None),
             sess: s,
             def_map: def_map,
             tables: RefCell::new(Tables::empty()),
@@ -569,7 +599,9 @@ impl<'tcx> TyCtxt<'tcx> {
             trait_items_cache: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             ty_param_defs: RefCell::new(NodeMap()),
             normalized_cache: RefCell::new(FnvHashMap()),
+            normalized_projection_cache: RefCell::new(FnvHashMap()),
             lang_items: lang_items,
+            diagnostic_items: diagnostic_items,
             inherent_impls: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             impl_items: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             used_unsafe: RefCell::new(NodeSet()),
@@ -583,7 +615,12 @@ impl<'tcx> TyCtxt<'tcx> {
             stability: RefCell::new(stability),
             selection_cache: traits::SelectionCache::new(),
             evaluation_cache: traits::EvaluationCache::new(),
+            global_evaluation_cache: traits::GlobalEvaluationCache::new(),
+            evaluation_query_cache: traits::EvaluationQueryCache::new(),
+            normalization_query_cache: traits::NormalizationQueryCache::new(),
+            specializes_cache: traits::SpecializesCache::new(),
             repr_hint_cache: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
+            codegen_fn_attrs_cache: RefCell::new(DepTrackingMap::new(dep_graph.clone())),
             const_qualif_map: RefCell::new(NodeMap()),
             custom_coerce_unsized_kinds: RefCell::new(DefIdMap()),
             cast_kinds: RefCell::new(NodeMap()),
@@ -756,12 +793,16 @@ impl<'tcx> TyCtxt<'tcx> {
         sty_debug_print!(
             self,
             TyEnum, TyBox, TyArray, TySlice, TyRawPtr, TyRef, TyFnDef, TyFnPtr,
-            TyTrait, TyStruct, TyClosure, TyTuple, TyParam, TyInfer, TyProjection);
+            TyTrait, TyStruct, TyClosure, TyTuple, TyParam, TyBound, TyInfer, TyProjection);
 
         println!("Substs interner: #{}", self.substs_interner.borrow().len());
         println!("BareFnTy interner: #{}", self.bare_fn_interner.borrow().len());
         println!("Region interner: #{}", self.region_interner.borrow().len());
         println!("Stability interner: #{}", self.stability_interner.borrow().len());
+        println!("Evaluation query cache: #{}, {} hits",
+                 self.evaluation_query_cache.len(), self.evaluation_query_cache.hits());
+        println!("Normalization query cache: #{}, {} hits",
+                 self.normalization_query_cache.len(), self.normalization_query_cache.hits());
     }
 }
 
@@ -820,6 +861,28 @@ impl<'tcx> TyCtxt<'tcx> {
         })
     }
 
+    /// The fn pointer type a non-capturing closure with signature
+    /// `sig` coerces to. Closure signatures take their arguments as a
+    /// single tuple; the fn pointer takes them individually.
+    pub fn coerce_closure_fn_ty(&self, sig: &ty::PolyFnSig<'tcx>) -> Ty<'tcx> {
+        let sig = sig.map_bound_ref(|sig| {
+            let inputs = match sig.inputs[0].sty {
+                ty::TyTuple(ref inputs) => inputs.clone(),
+                _ => bug!("closure signature without tupled arguments: {:?}", sig)
+            };
+            ty::FnSig {
+                inputs: inputs,
+                output: sig.output,
+                variadic: false
+            }
+        });
+        self.mk_fn_ptr(ty::BareFnTy {
+            unsafety: hir::Unsafety::Normal,
+            abi: Abi::Rust,
+            sig: sig
+        })
+    }
+
     pub fn mk_bare_fn(&self, bare_fn: BareFnTy<'tcx>) -> &'tcx BareFnTy<'tcx> {
         if let Some(bare_fn) = self.bare_fn_interner.borrow().get(&bare_fn) {
             return *bare_fn;
@@ -1074,4 +1137,23 @@ impl<'tcx> TyCtxt<'tcx> {
             })
         })
     }
+
+    /// Obtain the `#[inline]`, `#[cold]` and `#[optimize]` attributes
+    /// of a function. Malformed attributes are reported for local
+    /// functions only, since other crates reported them already.
+    pub fn codegen_fn_attrs(&self, did: DefId) -> ty::CodegenFnAttrs {
+        self.codegen_fn_attrs_cache.memoize(did, || {
+            let attrs = self.get_attrs(did);
+            let diagnostic = if did.is_local() {
+                Some(self.sess.diagnostic())
+            } else {
+                None
+            };
+            ty::CodegenFnAttrs {
+                inline: attr::find_inline_attr(diagnostic, &attrs),
+                cold: attr::contains_name(&attrs, "cold"),
+                optimize: attr::find_optimize_attr(diagnostic, &attrs),
+            }
+        })
+    }
 }
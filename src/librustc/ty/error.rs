@@ -9,7 +9,9 @@
 // except according to those terms.
 
 use hir::def_id::DefId;
+use middle::const_val::ConstVal;
 use ty::subst;
+use infer::error_reporting::bound_region_name;
 use infer::type_variable;
 use ty::{self, BoundRegion, Region, Ty, TyCtxt};
 
@@ -47,10 +49,12 @@ pub enum TypeError<'tcx> {
     RegionsNoOverlap(Region, Region),
     RegionsInsufficientlyPolymorphic(BoundRegion, Region),
     RegionsOverlyPolymorphic(BoundRegion, Region),
+    TypesInsufficientlyPolymorphic(ty::BoundTy, Ty<'tcx>),
     Sorts(ExpectedFound<Ty<'tcx>>),
     IntegerAsChar,
     IntMismatch(ExpectedFound<ty::IntVarValue>),
     FloatMismatch(ExpectedFound<ast::FloatTy>),
+    ConstMismatch(ExpectedFound<ConstVal>),
     Traits(ExpectedFound<DefId>),
     BuiltinBoundsMismatch(ExpectedFound<ty::BuiltinBounds>),
     VariadicMismatch(ExpectedFound<bool>),
@@ -58,7 +62,13 @@ pub enum TypeError<'tcx> {
     ConvergenceMismatch(ExpectedFound<bool>),
     ProjectionNameMismatched(ExpectedFound<Name>),
     ProjectionBoundsLength(ExpectedFound<usize>),
-    TyParamDefaultMismatch(ExpectedFound<type_variable::Default<'tcx>>)
+    TyParamDefaultMismatch(ExpectedFound<type_variable::Default<'tcx>>),
+
+    /// A LUB or GLB could not be computed because the types involved
+    /// still contain unresolved type variables. Unlike the other errors
+    /// this does not mean the types are unrelated, and callers may
+    /// fall back to subtyping or try again later.
+    Ambiguous,
 }
 
 #[derive(Clone, RustcEncodable, RustcDecodable, PartialEq, Eq, Hash, Debug, Copy)]
@@ -137,12 +147,16 @@ impl<'tcx> fmt::Display for TypeError<'tcx> {
                 write!(f, "lifetimes do not intersect")
             }
             RegionsInsufficientlyPolymorphic(br, _) => {
-                write!(f, "expected bound lifetime parameter {}, \
-                           found concrete lifetime", br)
+                write!(f, "expected bound lifetime parameter `{}`, \
+                           found concrete lifetime", bound_region_name(br))
             }
             RegionsOverlyPolymorphic(br, _) => {
                 write!(f, "expected concrete lifetime, \
-                           found bound lifetime parameter {}", br)
+                           found bound lifetime parameter `{}`", bound_region_name(br))
+            }
+            TypesInsufficientlyPolymorphic(bt, ty) => {
+                write!(f, "expected bound type parameter {}, \
+                           found concrete type `{}`", bt.name, ty)
             }
             Sorts(values) => ty::tls::with(|tcx| {
                 report_maybe_different(f, values.expected.sort_string(tcx),
@@ -181,11 +195,19 @@ impl<'tcx> fmt::Display for TypeError<'tcx> {
                        values.expected,
                        values.found)
             }
+            ConstMismatch(ref values) => {
+                write!(f, "expected `{}`, found `{}`",
+                       values.expected,
+                       values.found)
+            }
             VariadicMismatch(ref values) => {
                 write!(f, "expected {} fn, found {} function",
                        if values.expected { "variadic" } else { "non-variadic" },
                        if values.found { "variadic" } else { "non-variadic" })
             }
+            Ambiguous => {
+                write!(f, "the types involved are not yet known")
+            }
             ConvergenceMismatch(ref values) => {
                 write!(f, "expected {} fn, found {} function",
                        if values.expected { "converging" } else { "diverging" },
@@ -239,6 +261,7 @@ impl<'tcx> ty::TyS<'tcx> {
             ty::TyInfer(ty::FreshTy(_)) => "skolemized type".to_string(),
             ty::TyInfer(ty::FreshIntTy(_)) => "skolemized integral type".to_string(),
             ty::TyInfer(ty::FreshFloatTy(_)) => "skolemized floating-point type".to_string(),
+            ty::TyInfer(ty::SkolemizedTy(_)) => "skolemized type".to_string(),
             ty::TyProjection(_) => "associated type".to_string(),
             ty::TyParam(ref p) => {
                 if p.space == subst::SelfSpace {
@@ -247,6 +270,7 @@ impl<'tcx> ty::TyS<'tcx> {
                     "type parameter".to_string()
                 }
             }
+            ty::TyBound(..) => "bound type".to_string(),
             ty::TyError => "type error".to_string(),
         }
     }
@@ -275,15 +299,18 @@ impl<'tcx> TyCtxt<'tcx> {
                 self.note_and_explain_region(db, "...does not overlap ",
                                            region2, "");
             }
-            RegionsInsufficientlyPolymorphic(_, conc_region) => {
+            RegionsInsufficientlyPolymorphic(br, conc_region) => {
+                self.note_bound_region_origin(db, br, None);
                 self.note_and_explain_region(db, "concrete lifetime that was found is ",
                                            conc_region, "");
             }
-            RegionsOverlyPolymorphic(_, ty::ReVar(_)) => {
+            RegionsOverlyPolymorphic(br, ty::ReVar(_)) => {
                 // don't bother to print out the message below for
                 // inference variables, it's not very illuminating.
+                self.note_bound_region_origin(db, br, None);
             }
-            RegionsOverlyPolymorphic(_, conc_region) => {
+            RegionsOverlyPolymorphic(br, conc_region) => {
+                self.note_bound_region_origin(db, br, None);
                 self.note_and_explain_region(db, "expected concrete lifetime is ",
                                            conc_region, "");
             }
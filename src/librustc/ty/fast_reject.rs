@@ -86,7 +86,7 @@ pub fn simplify_type(tcx: &TyCtxt,
         ty::TyFnDef(_, _, ref f) | ty::TyFnPtr(ref f) => {
             Some(FunctionSimplifiedType(f.sig.0.inputs.len()))
         }
-        ty::TyProjection(_) | ty::TyParam(_) => {
+        ty::TyProjection(_) | ty::TyParam(_) | ty::TyBound(..) => {
             if can_simplify_params {
                 // In normalized types, projections don't unify with
                 // anything. when lazy normalization happens, this
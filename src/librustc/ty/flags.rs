@@ -14,7 +14,8 @@ use ty::{self, Ty, TypeFlags, TypeFoldable};
 pub struct FlagComputation {
     pub flags: TypeFlags,
 
-    // maximum depth of any bound region that we have seen thus far
+    // maximum depth of any bound region or bound type that we have
+    // seen thus far
     pub depth: u32,
 }
 
@@ -83,6 +84,11 @@ impl FlagComputation {
                 }
             }
 
+            &ty::TyBound(debruijn, _) => {
+                self.add_flags(TypeFlags::HAS_TY_BOUND);
+                self.add_depth(debruijn.depth);
+            }
+
             &ty::TyClosure(_, ref substs) => {
                 self.add_flags(TypeFlags::HAS_TY_CLOSURE);
                 self.add_flags(TypeFlags::HAS_LOCAL_NAMES);
@@ -312,13 +312,16 @@ impl<'a, 'tcx> TypeFolder<'tcx> for RegionFolder<'a, 'tcx>
 ///////////////////////////////////////////////////////////////////////////
 // Late-bound region replacer
 
-// Replaces the escaping regions in a type.
+// Replaces the escaping regions in a type, and, if a type callback is
+// given, the escaping types bound by `for<T>` binders as well.
 
 struct RegionReplacer<'a, 'tcx: 'a> {
     tcx: &'a TyCtxt<'tcx>,
     current_depth: u32,
     fld_r: &'a mut (FnMut(ty::BoundRegion) -> ty::Region + 'a),
-    map: FnvHashMap<ty::BoundRegion, ty::Region>
+    fld_t: Option<&'a mut (FnMut(ty::BoundTy) -> Ty<'tcx> + 'a)>,
+    map: FnvHashMap<ty::BoundRegion, ty::Region>,
+    type_map: FnvHashMap<ty::BoundTy, Ty<'tcx>>,
 }
 
 impl<'tcx> TyCtxt<'tcx> {
@@ -335,6 +338,25 @@ impl<'tcx> TyCtxt<'tcx> {
         (result, replacer.map)
     }
 
+    /// Like `replace_late_bound_regions`, but also replaces the types
+    /// bound by `value` (see `TyBound`) using `fld_t`.
+    pub fn replace_late_bound_regions_and_types<T,F,G>(&self,
+        value: &Binder<T>,
+        mut fld_r: F,
+        mut fld_t: G)
+        -> (T, FnvHashMap<ty::BoundRegion, ty::Region>, FnvHashMap<ty::BoundTy, Ty<'tcx>>)
+        where F : FnMut(ty::BoundRegion) -> ty::Region,
+              G : FnMut(ty::BoundTy) -> Ty<'tcx>,
+              T : TypeFoldable<'tcx>,
+    {
+        debug!("replace_late_bound_regions_and_types({:?})", value);
+        let fld_t: &mut FnMut(ty::BoundTy) -> Ty<'tcx> = &mut fld_t;
+        let mut replacer = RegionReplacer::new(self, &mut fld_r);
+        replacer.fld_t = Some(fld_t);
+        let result = value.skip_binder().fold_with(&mut replacer);
+        (result, replacer.map, replacer.type_map)
+    }
+
 
     /// Replace any late-bound regions bound in `value` with free variants attached to scope-id
     /// `scope_id`.
@@ -417,7 +439,9 @@ impl<'a, 'tcx> RegionReplacer<'a, 'tcx> {
             tcx: tcx,
             current_depth: 1,
             fld_r: fld_r,
-            map: FnvHashMap()
+            fld_t: None,
+            map: FnvHashMap(),
+            type_map: FnvHashMap(),
         }
     }
 }
@@ -434,11 +458,27 @@ impl<'a, 'tcx> TypeFolder<'tcx> for RegionReplacer<'a, 'tcx>
     }
 
     fn fold_ty(&mut self, t: Ty<'tcx>) -> Ty<'tcx> {
+        // Bound types count towards the region depth as well, so this
+        // also skips types with no escaping bound types.
         if !t.has_regions_escaping_depth(self.current_depth-1) {
             return t;
         }
 
-        t.super_fold_with(self)
+        match t.sty {
+            ty::TyBound(debruijn, bt) if debruijn.depth == self.current_depth => {
+                if let Some(ref mut fld_t) = self.fld_t {
+                    debug!("RegionReplacer.fold_ty({:?}) folding type (current_depth={})",
+                           t, self.current_depth);
+                    let ty = *self.type_map.entry(bt).or_insert_with(|| fld_t(bt));
+                    // Unlike regions, replacement types are never
+                    // re-bound at this depth.
+                    assert!(!ty.has_escaping_regions());
+                    return ty;
+                }
+                t
+            }
+            _ => t.super_fold_with(self)
+        }
     }
 
     fn fold_region(&mut self, r: ty::Region) -> ty::Region {
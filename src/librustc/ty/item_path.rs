@@ -313,6 +313,7 @@ pub fn characteristic_def_id_of_type<'tcx>(ty: Ty<'tcx>) -> Option<DefId> {
         ty::TyFnPtr(_) |
         ty::TyProjection(_) |
         ty::TyParam(_) |
+        ty::TyBound(..) |
         ty::TyInfer(_) |
         ty::TyError |
         ty::TyFloat(_) => None,
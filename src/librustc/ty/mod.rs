@@ -60,8 +60,9 @@ pub use self::sty::{BareFnTy, FnSig, PolyFnSig, FnOutput, PolyFnOutput};
 pub use self::sty::{ClosureTy, InferTy, ParamTy, ProjectionTy, TraitTy};
 pub use self::sty::{ClosureSubsts, TypeAndMut};
 pub use self::sty::{TraitRef, TypeVariants, PolyTraitRef};
-pub use self::sty::{BoundRegion, EarlyBoundRegion, FreeRegion, Region};
-pub use self::sty::{TyVid, IntVid, FloatVid, RegionVid, SkolemizedRegionVid};
+pub use self::sty::{BoundRegion, BoundTy, EarlyBoundRegion, FreeRegion, Region};
+pub use self::sty::{TyVid, IntVid, FloatVid, ConstVid, RegionVid, SkolemizedRegionVid};
+pub use self::sty::Const;
 pub use self::sty::BoundRegion::*;
 pub use self::sty::FnOutput::*;
 pub use self::sty::InferTy::*;
@@ -417,6 +418,35 @@ pub enum Variance {
     Bivariant,      // T<A> <: T<B>            -- e.g., unused type parameter
 }
 
+impl Variance {
+    /// `a.xform(b)` is the variance with which something appears when
+    /// it occurs with variance `b` inside a context of variance `a`.
+    /// For example, a type parameter that is contravariant in a
+    /// function argument is covariant when the function type itself
+    /// is used contravariantly:
+    ///
+    /// ```text
+    /// Contravariant.xform(Contravariant) == Covariant
+    /// ```
+    pub fn xform(self, v: Variance) -> Variance {
+        match (self, v) {
+            (Covariant, Covariant) => Covariant,
+            (Covariant, Contravariant) => Contravariant,
+            (Covariant, Invariant) => Invariant,
+            (Covariant, Bivariant) => Bivariant,
+
+            (Contravariant, Covariant) => Contravariant,
+            (Contravariant, Contravariant) => Covariant,
+            (Contravariant, Invariant) => Invariant,
+            (Contravariant, Bivariant) => Bivariant,
+
+            (Invariant, _) => Invariant,
+
+            (Bivariant, _) => Bivariant,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct MethodCallee<'tcx> {
     /// Impl method ID, for inherent methods, or trait method ID, otherwise.
@@ -471,12 +501,40 @@ pub struct CReaderCacheKey {
     pub pos: usize,
 }
 
+/// The attributes of a function that affect how it is translated, as
+/// returned by `TyCtxt::codegen_fn_attrs`. Everything that decides
+/// whether a function gets inlined should consult these rather than
+/// looking for the attributes itself.
+#[derive(Copy, Clone, PartialEq)]
+pub struct CodegenFnAttrs {
+    /// The `#[inline]` attribute, if any.
+    pub inline: attr::InlineAttr,
+    /// Whether the function is marked `#[cold]`.
+    pub cold: bool,
+    /// The `#[optimize]` attribute, if any.
+    pub optimize: attr::OptimizeAttr,
+}
+
+impl CodegenFnAttrs {
+    /// True if the function asks to be inlined, in which case it is
+    /// translated into every compilation unit that uses it.
+    pub fn requests_inline(&self) -> bool {
+        match self.inline {
+            attr::InlineAttr::Hint | attr::InlineAttr::Always => true,
+            attr::InlineAttr::None | attr::InlineAttr::Never => false,
+        }
+    }
+}
+
 /// A restriction that certain types must be the same size. The use of
 /// `transmute` gives rise to these restrictions. These generally
 /// cannot be checked until trans; therefore, each call to `transmute`
-/// will push one or more such restriction into the
+/// whose sizes do not depend on how its type parameters are
+/// instantiated pushes such a restriction into the
 /// `transmute_restrictions` vector during `intrinsicck`. They are
-/// then checked during `trans` by the fn `check_intrinsics`.
+/// then checked during `trans` by the fn `check_intrinsics`. All
+/// other calls are checked for each instantiation instead, as it is
+/// translated.
 #[derive(Copy, Clone)]
 pub struct TransmuteRestriction<'tcx> {
     /// The span whence the restriction comes.
@@ -489,13 +547,13 @@ pub struct TransmuteRestriction<'tcx> {
     pub original_to: Ty<'tcx>,
 
     /// The type being transmuted from, with all type parameters
-    /// substituted for an arbitrary representative. Not to be shown
-    /// to the end user.
+    /// substituted for an arbitrary sized representative. Not to be
+    /// shown to the end user.
     pub substituted_from: Ty<'tcx>,
 
     /// The type being transmuted to, with all type parameters
-    /// substituted for an arbitrary representative. Not to be shown
-    /// to the end user.
+    /// substituted for an arbitrary sized representative. Not to be
+    /// shown to the end user.
     pub substituted_to: Ty<'tcx>,
 
     /// NodeId of the transmute intrinsic.
@@ -533,6 +591,10 @@ bitflags! {
         // that are local to a particular fn
         const HAS_LOCAL_NAMES   = 1 << 9,
 
+        // true if there are types bound by a `for<T>` binder (see
+        // `TyBound`); whether they escape is tracked by the depth
+        const HAS_TY_BOUND      = 1 << 10,
+
         const NEEDS_SUBST        = TypeFlags::HAS_PARAMS.bits |
                                    TypeFlags::HAS_SELF.bits |
                                    TypeFlags::HAS_RE_EARLY_BOUND.bits,
@@ -549,7 +611,8 @@ bitflags! {
                                   TypeFlags::HAS_TY_ERR.bits |
                                   TypeFlags::HAS_PROJECTION.bits |
                                   TypeFlags::HAS_TY_CLOSURE.bits |
-                                  TypeFlags::HAS_LOCAL_NAMES.bits,
+                                  TypeFlags::HAS_LOCAL_NAMES.bits |
+                                  TypeFlags::HAS_TY_BOUND.bits,
 
         // Caches for type_is_sized, type_moves_by_default
         const SIZEDNESS_CACHED  = 1 << 16,
@@ -723,6 +786,11 @@ pub struct TypeParameterDef<'tcx> {
     pub default_def_id: DefId, // for use in error reporing about defaults
     pub default: Option<Ty<'tcx>>,
     pub object_lifetime_default: ObjectLifetimeDefault,
+
+    /// `pure_wrt_drop`, set by the (unsafe) `#[may_dangle]` attribute
+    /// on generic parameter `T`, asserts data behind the parameter
+    /// `T` won't be accessed during the parent type's `Drop` impl.
+    pub pure_wrt_drop: bool,
 }
 
 #[derive(Clone)]
@@ -732,6 +800,11 @@ pub struct RegionParameterDef {
     pub space: subst::ParamSpace,
     pub index: u32,
     pub bounds: Vec<ty::Region>,
+
+    /// `pure_wrt_drop`, set by the (unsafe) `#[may_dangle]` attribute
+    /// on generic parameter `'a`, asserts data of lifetime `'a`
+    /// won't be accessed during the parent type's `Drop` impl.
+    pub pure_wrt_drop: bool,
 }
 
 impl RegionParameterDef {
@@ -814,6 +887,10 @@ pub enum Predicate<'tcx> {
     /// would be the parameters in the `TypeSpace`.
     Trait(PolyTraitPredicate<'tcx>),
 
+    /// Corresponds to `where Foo : !Bar<A,B,C>`: `Foo` must not
+    /// implement the trait.
+    NegativeTrait(PolyTraitPredicate<'tcx>),
+
     /// where `T1 == T2`.
     Equate(PolyEquatePredicate<'tcx>),
 
@@ -914,6 +991,8 @@ impl<'tcx> Predicate<'tcx> {
         match *self {
             Predicate::Trait(ty::Binder(ref data)) =>
                 Predicate::Trait(ty::Binder(data.subst(tcx, substs))),
+            Predicate::NegativeTrait(ty::Binder(ref data)) =>
+                Predicate::NegativeTrait(ty::Binder(data.subst(tcx, substs))),
             Predicate::Equate(ty::Binder(ref data)) =>
                 Predicate::Equate(ty::Binder(data.subst(tcx, substs))),
             Predicate::RegionOutlives(ty::Binder(ref data)) =>
@@ -1090,7 +1169,8 @@ impl<'tcx> Predicate<'tcx> {
     /// with depth 0 are bound by the predicate.
     pub fn walk_tys(&self) -> IntoIter<Ty<'tcx>> {
         let vec: Vec<_> = match *self {
-            ty::Predicate::Trait(ref data) => {
+            ty::Predicate::Trait(ref data) |
+            ty::Predicate::NegativeTrait(ref data) => {
                 data.0.trait_ref.substs.types.as_slice().to_vec()
             }
             ty::Predicate::Equate(ty::Binder(ref data)) => {
@@ -1133,6 +1213,7 @@ impl<'tcx> Predicate<'tcx> {
             Predicate::Trait(ref t) => {
                 Some(t.to_poly_trait_ref())
             }
+            Predicate::NegativeTrait(..) |
             Predicate::Projection(..) |
             Predicate::Equate(..) |
             Predicate::RegionOutlives(..) |
@@ -1388,7 +1469,7 @@ impl<'a, 'tcx> ParameterEnvironment<'a, 'tcx> {
                                                            &predicates,
                                                            cx.region_maps.item_extent(id))
                     }
-                    hir::ItemTrait(..) => {
+                    hir::ItemTrait(..) | hir::ItemTraitAlias(..) => {
                         let def_id = cx.map.local_def_id(id);
                         let trait_def = cx.lookup_trait_def(def_id);
                         let predicates = cx.lookup_predicates(def_id);
@@ -1409,6 +1490,12 @@ impl<'a, 'tcx> ParameterEnvironment<'a, 'tcx> {
                 // This is a convenience to allow closures to work.
                 ParameterEnvironment::for_item(cx, cx.map.get_parent(id))
             }
+            Some(ast_map::NodeStructCtor(..)) |
+            Some(ast_map::NodeVariant(..)) => {
+                // Constructors have the generics of the struct or enum
+                // they construct.
+                ParameterEnvironment::for_item(cx, cx.map.get_parent(id))
+            }
             _ => {
                 bug!("ParameterEnvironment::from_item(): \
                       `{}` is not an item",
@@ -2113,6 +2200,7 @@ impl<'tcx> TyCtxt<'tcx> {
             hir::ExprBreak(..) |
             hir::ExprAgain(..) |
             hir::ExprRet(..) |
+            hir::ExprBecome(..) |
             hir::ExprWhile(..) |
             hir::ExprLoop(..) |
             hir::ExprAssign(..) |
@@ -2291,6 +2379,16 @@ impl<'tcx> TyCtxt<'tcx> {
         }
     }
 
+    /// The inverse of `def_path`: finds the item that `path` names
+    /// in `path.krate`, if there still is one.
+    pub fn retrace_path(&self, path: &ast_map::DefPath) -> Option<DefId> {
+        if path.is_local() {
+            self.map.retrace_path(path)
+        } else {
+            self.sess.cstore.retrace_path(path.krate, path)
+        }
+    }
+
     pub fn item_name(&self, id: DefId) -> ast::Name {
         if let Some(id) = self.map.as_local_node_id(id) {
             self.map.name(id)
@@ -2337,6 +2435,18 @@ impl<'tcx> TyCtxt<'tcx> {
         self.lookup_adt_def_master(did)
     }
 
+    /// Returns the item marked `#[rustc_diagnostic_item = "name"]`, if
+    /// any. Lints and error reporting should use this, rather than
+    /// matching on item paths, to recognize well-known library items.
+    pub fn get_diagnostic_item(&self, name: &str) -> Option<DefId> {
+        self.diagnostic_items.get(name)
+    }
+
+    /// True if `did` is the item marked `#[rustc_diagnostic_item = "name"]`.
+    pub fn is_diagnostic_item(&self, name: &str, did: DefId) -> bool {
+        self.diagnostic_items.is(name, did)
+    }
+
     /// Given the did of an item, returns its full set of predicates.
     pub fn lookup_predicates(&self, did: DefId) -> GenericPredicates<'tcx> {
         lookup_locally_or_in_crate_store(
@@ -2410,6 +2520,13 @@ impl<'tcx> TyCtxt<'tcx> {
     }
 
     pub fn item_variances(&self, item_id: DefId) -> Rc<ItemVariances> {
+        // Variances of local items are inferred on demand.
+        if let Some(provider) = self.variance_provider.get() {
+            if item_id.is_local() && !self.item_variance_map.borrow().contains_key(&item_id) {
+                provider(self, item_id);
+            }
+        }
+
         lookup_locally_or_in_crate_store(
             "item_variance_map", item_id, &self.item_variance_map,
             || Rc::new(self.sess.cstore.item_variances(item_id)))
@@ -2483,6 +2600,24 @@ impl<'tcx> TyCtxt<'tcx> {
         self.populated_external_types.borrow_mut().insert(type_id);
     }
 
+    /// Returns the inherent impls of the type `type_id` (a struct, enum or
+    /// trait), whether the type is defined in this crate or another one.
+    /// Local impls are only known once coherence has run.
+    pub fn inherent_impls_of(&self, type_id: DefId) -> Rc<Vec<DefId>> {
+        self.populate_inherent_implementations_for_type_if_necessary(type_id);
+        self.inherent_impls.borrow().get(&type_id).cloned().unwrap_or_else(|| Rc::new(vec![]))
+    }
+
+    /// Returns every impl of the trait `trait_id`, from this crate and
+    /// from all crates it depends on, blanket impls first.
+    pub fn trait_impls_of(&self, trait_id: DefId) -> Vec<DefId> {
+        let mut impls = vec![];
+        self.lookup_trait_def(trait_id).for_each_impl(self, |impl_def_id| {
+            impls.push(impl_def_id);
+        });
+        impls
+    }
+
     /// Populates the type context with all the implementations for the given
     /// trait if necessary.
     pub fn populate_implementations_for_trait_if_necessary(&self, trait_id: DefId) {
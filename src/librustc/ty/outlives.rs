@@ -185,6 +185,7 @@ fn compute_components<'a,'tcx>(infcx: &InferCtxt<'a,'tcx>,
         ty::TyFnDef(..) |       // OutlivesFunction (*)
         ty::TyFnPtr(_) |        // OutlivesFunction (*)
         ty::TyTrait(..) |       // OutlivesObject, OutlivesFragment (*)
+        ty::TyBound(..) |       // bound by a binder, like a late-bound region
         ty::TyError => {
             // (*) Bare functions and traits are both binders. In the
             // RFC, this means we would add the bound regions to the
@@ -14,6 +14,8 @@
 //! type equality, etc.
 
 use hir::def_id::DefId;
+use middle::const_val::ConstVal;
+use rustc_const_math::ConstInt;
 use ty::subst::{ParamSpace, Substs};
 use ty::{self, Ty, TyCtxt, TypeFoldable};
 use ty::error::{ExpectedFound, TypeError};
@@ -74,6 +76,9 @@ pub trait TypeRelation<'a,'tcx> : Sized {
     fn regions(&mut self, a: ty::Region, b: ty::Region)
                -> RelateResult<'tcx, ty::Region>;
 
+    fn consts(&mut self, a: &ty::Const, b: &ty::Const)
+              -> RelateResult<'tcx, ty::Const>;
+
     fn binders<T>(&mut self, a: &ty::Binder<T>, b: &ty::Binder<T>)
                   -> RelateResult<'tcx, ty::Binder<T>>
         where T: Relate<'a,'tcx>;
@@ -478,6 +483,12 @@ pub fn super_relate_tys<'a,'tcx:'a,R>(relation: &mut R,
             Ok(a)
         }
 
+        (&ty::TyBound(a_debruijn, ref a_b), &ty::TyBound(b_debruijn, ref b_b))
+            if a_debruijn == b_debruijn && a_b.index == b_b.index =>
+        {
+            Ok(a)
+        }
+
         (&ty::TyEnum(a_def, a_substs), &ty::TyEnum(b_def, b_substs))
             if a_def == b_def =>
         {
@@ -532,10 +543,14 @@ pub fn super_relate_tys<'a,'tcx:'a,R>(relation: &mut R,
         (&ty::TyArray(a_t, sz_a), &ty::TyArray(b_t, sz_b)) =>
         {
             let t = relation.relate(&a_t, &b_t)?;
-            if sz_a == sz_b {
-                Ok(tcx.mk_array(t, sz_a))
-            } else {
-                Err(TypeError::FixedArraySize(expected_found(relation, &sz_a, &sz_b)))
+            let len = |sz: usize| ty::Const::Value(ConstVal::Integral(ConstInt::Infer(sz as u64)));
+            match relation.relate(&len(sz_a), &len(sz_b)) {
+                Ok(_) => Ok(tcx.mk_array(t, sz_a)),
+                // Keep the more specific error for array lengths.
+                Err(TypeError::ConstMismatch(_)) => {
+                    Err(TypeError::FixedArraySize(expected_found(relation, &sz_a, &sz_b)))
+                }
+                Err(e) => Err(e),
             }
         }
 
@@ -624,6 +639,45 @@ impl<'a,'tcx:'a> Relate<'a,'tcx> for ty::Region {
     }
 }
 
+impl<'a,'tcx:'a> Relate<'a,'tcx> for ty::Const {
+    fn relate<R>(relation: &mut R,
+                 a: &ty::Const,
+                 b: &ty::Const)
+                 -> RelateResult<'tcx, ty::Const>
+        where R: TypeRelation<'a,'tcx>
+    {
+        relation.consts(a, b)
+    }
+}
+
+/// Relates two constant values. Constants have no subtyping, so this
+/// is the same for every relation: the values must be equal. Like
+/// `super_relate_tys`, this does not handle constant variables.
+pub fn super_relate_consts<'a,'tcx:'a,R>(relation: &mut R,
+                                         a: &ty::Const,
+                                         b: &ty::Const)
+                                         -> RelateResult<'tcx, ty::Const>
+    where R: TypeRelation<'a,'tcx>
+{
+    match (a, b) {
+        (&ty::Const::Infer(_), _) |
+        (_, &ty::Const::Infer(_)) =>
+        {
+            // The caller should handle these cases!
+            bug!("var consts encountered in super_relate_consts")
+        }
+
+        (&ty::Const::Value(ref a_value), &ty::Const::Value(ref b_value)) =>
+        {
+            if a_value == b_value {
+                Ok(a.clone())
+            } else {
+                Err(TypeError::ConstMismatch(expected_found(relation, a_value, b_value)))
+            }
+        }
+    }
+}
+
 impl<'a,'tcx:'a,T> Relate<'a,'tcx> for ty::Binder<T>
     where T: Relate<'a,'tcx>
 {
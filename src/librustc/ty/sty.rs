@@ -10,6 +10,7 @@
 
 //! This module contains TypeVariants and its major components
 
+use middle::const_val::ConstVal;
 use middle::cstore;
 use hir::def_id::DefId;
 use middle::region;
@@ -153,6 +154,15 @@ pub enum TypeVariants<'tcx> {
     /// A type parameter; for example, `T` in `fn f<T>(x: T) {}
     TyParam(ParamTy),
 
+    /// A type bound by an enclosing `Binder`, as `T` in `for<T> Foo<T>`.
+    /// Like `ReLateBound`, the De Bruijn index identifies the binder.
+    /// There is no surface syntax for such binders: they are only
+    /// introduced by the compiler itself, e.g. for the variables of a
+    /// canonical query (see `infer::canonical`). Bound types are always
+    /// replaced (by fresh type variables, skolemized types, ...) before
+    /// the binder's contents are examined.
+    TyBound(DebruijnIndex, BoundTy),
+
     /// A type variable used during type-checking.
     TyInfer(InferTy),
 
@@ -714,6 +724,11 @@ pub struct FloatVid {
     pub index: u32
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstVid {
+    pub index: u32
+}
+
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Copy)]
 pub struct RegionVid {
     pub index: u32
@@ -724,6 +739,23 @@ pub struct SkolemizedRegionVid {
     pub index: u32
 }
 
+/// A type bound by a `for<T>` binder. `index` is the position of the
+/// type among the binder's type variables.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable, Debug)]
+pub struct BoundTy {
+    pub index: u32,
+    pub name: Name,
+}
+
+/// A constant that takes part in relating types, such as the length of
+/// an array. While type-checking is in progress it may still be an
+/// inference variable; see `infer::const_variable`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Const {
+    Value(ConstVal),
+    Infer(ConstVid),
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum InferTy {
     TyVar(TyVid),
@@ -735,7 +767,12 @@ pub enum InferTy {
     /// `infer::freshen` for more details.
     FreshTy(u32),
     FreshIntTy(u32),
-    FreshFloatTy(u32)
+    FreshFloatTy(u32),
+
+    /// A `SkolemizedTy` stands for an arbitrary type while a
+    /// higher-ranked `for<T>` binder is being examined; it is the type
+    /// counterpart of `ReSkolemized`. See `infer::higher_ranked`.
+    SkolemizedTy(u32),
 }
 
 /// Bounds suitable for an existentially quantified type parameter
@@ -1230,6 +1267,7 @@ impl<'tcx> TyS<'tcx> {
             TyRawPtr(_) |
             TyTuple(_) |
             TyParam(_) |
+            TyBound(..) |
             TyInfer(_) |
             TyError => {
                 vec![]
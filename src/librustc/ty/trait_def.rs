@@ -98,6 +98,18 @@ impl<'tcx> TraitDef<'tcx> {
         self.trait_ref.def_id
     }
 
+    /// Whether this is a trait alias, e.g. `trait Foo = Bar + Send;`.
+    /// An alias has no items and cannot be implemented; its bounds
+    /// are stored as its supertraits, so that elaboration expands a
+    /// bound on the alias into the bounds it stands for.
+    pub fn is_alias(&self) -> bool {
+        self.flags.get().intersects(TraitFlags::IS_TRAIT_ALIAS)
+    }
+
+    pub fn set_is_alias(&self) {
+        self.flags.set(self.flags.get() | TraitFlags::IS_TRAIT_ALIAS);
+    }
+
     // returns None if not yet calculated
     pub fn object_safety(&self) -> Option<bool> {
         if self.flags.get().intersects(TraitFlags::OBJECT_SAFETY_VALID) {
@@ -161,6 +173,10 @@ impl<'tcx> TraitDef<'tcx> {
             self.blanket_impls.borrow_mut().push(impl_def_id)
         }
 
+        if tcx.trait_impl_polarity(impl_def_id) == Some(hir::ImplPolarity::Negative) {
+            self.flags.set(self.flags.get() | TraitFlags::HAS_NEGATIVE_IMPLS);
+        }
+
         true
     }
 
@@ -198,16 +214,24 @@ impl<'tcx> TraitDef<'tcx> {
     /// Adds a local impl into the specialization graph, returning an error with
     /// overlap information if the impl overlaps but does not specialize an
     /// existing impl.
-    pub fn add_impl_for_specialization<'a>(&self,
-                                           tcx: &'a TyCtxt<'tcx>,
-                                           impl_def_id: DefId)
-                                           -> Result<(), traits::Overlap<'a, 'tcx>> {
+    pub fn add_impl_for_specialization(&self,
+                                       tcx: &TyCtxt<'tcx>,
+                                       impl_def_id: DefId)
+                                       -> Result<(), traits::OverlapError> {
         assert!(impl_def_id.is_local());
 
         self.specialization_graph.borrow_mut()
             .insert(tcx, impl_def_id)
     }
 
+    /// Returns true if any impl of this trait, local or external, is a
+    /// negative impl such as `impl !Send for Foo`.
+    pub fn has_negative_impls(&self, tcx: &TyCtxt<'tcx>) -> bool {
+        self.read_trait_impls(tcx);
+        tcx.populate_implementations_for_trait_if_necessary(self.trait_ref.def_id);
+        self.flags.get().intersects(TraitFlags::HAS_NEGATIVE_IMPLS)
+    }
+
     pub fn ancestors<'a>(&'a self, of_impl: DefId) -> specialization_graph::Ancestors<'a, 'tcx> {
         specialization_graph::ancestors(self, of_impl)
     }
@@ -277,5 +301,7 @@ bitflags! {
         const IS_OBJECT_SAFE        = 1 << 1,
         const OBJECT_SAFETY_VALID   = 1 << 2,
         const IMPLS_VALID           = 1 << 3,
+        const HAS_NEGATIVE_IMPLS    = 1 << 4,
+        const IS_TRAIT_ALIAS        = 1 << 5,
     }
 }
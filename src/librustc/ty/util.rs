@@ -298,6 +298,7 @@ impl<'tcx> TyCtxt<'tcx> {
                 match predicate {
                     ty::Predicate::Projection(..) |
                     ty::Predicate::Trait(..) |
+                    ty::Predicate::NegativeTrait(..) |
                     ty::Predicate::Equate(..) |
                     ty::Predicate::WellFormed(..) |
                     ty::Predicate::ObjectSafe(..) |
@@ -465,6 +466,11 @@ impl<'tcx> TyCtxt<'tcx> {
                         did(state, data.trait_ref.def_id);
                         hash!(data.item_name.as_str());
                     }
+                    TyBound(debruijn, bound_ty) => {
+                        byte!(24);
+                        hash!(debruijn.depth);
+                        hash!(bound_ty.index);
+                    }
                 }
                 true
             });
@@ -545,7 +551,7 @@ impl<'tcx> ty::TyS<'tcx> {
 
             TyArray(..) | TySlice(_) | TyTrait(..) | TyTuple(..) |
             TyClosure(..) | TyEnum(..) | TyStruct(..) |
-            TyProjection(..) | TyParam(..) | TyInfer(..) | TyError => None
+            TyProjection(..) | TyParam(..) | TyBound(..) | TyInfer(..) | TyError => None
         }.unwrap_or_else(|| !self.impls_bound(param_env, ty::BoundCopy, span));
 
         if !self.has_param_types() && !self.has_self_ty() {
@@ -583,7 +589,7 @@ impl<'tcx> ty::TyS<'tcx> {
             TyStr | TyTrait(..) | TySlice(_) => Some(false),
 
             TyEnum(..) | TyStruct(..) | TyProjection(..) | TyParam(..) |
-            TyInfer(..) | TyError => None
+            TyBound(..) | TyInfer(..) | TyError => None
         }.unwrap_or_else(|| self.impls_bound(param_env, ty::BoundSized, span));
 
         if !self.has_param_types() && !self.has_self_ty() {
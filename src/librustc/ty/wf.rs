@@ -25,12 +25,29 @@ use util::common::ErrorReported;
 /// inference variable, returns `None`, because we are not able to
 /// make any progress at all. This is to prevent "livelock" where we
 /// say "$0 is WF if $0 is WF".
+///
+/// The obligations of a type without inference variables are cached in
+/// `infcx.wf_cache` and handed out again with the cause moved to `span`
+/// and `body_id`.
 pub fn obligations<'a,'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                             body_id: ast::NodeId,
                             ty: Ty<'tcx>,
                             span: Span)
                             -> Option<Vec<traits::PredicateObligation<'tcx>>>
 {
+    let cacheable = !ty.needs_infer();
+    if cacheable {
+        if let Some(cached) = infcx.wf_cache.borrow().get(&ty) {
+            debug!("wf::obligations({:?}, body_id={:?}) cached", ty, body_id);
+            return Some(cached.iter().map(|obligation| {
+                let mut obligation = obligation.clone();
+                obligation.cause.span = span;
+                obligation.cause.body_id = body_id;
+                obligation
+            }).collect());
+        }
+    }
+
     let mut wf = WfPredicates { infcx: infcx,
                                 body_id: body_id,
                                 span: span,
@@ -39,6 +56,12 @@ pub fn obligations<'a,'tcx>(infcx: &InferCtxt<'a, 'tcx>,
         debug!("wf::obligations({:?}, body_id={:?}) = {:?}", ty, body_id, wf.out);
         let result = wf.normalize();
         debug!("wf::obligations({:?}, body_id={:?}) ~~> {:?}", ty, body_id, result);
+        // Normalization may have left inference variables behind for
+        // projections it could not resolve yet; those results are not
+        // final, so they are not cached.
+        if cacheable && !result.iter().any(|obligation| obligation.predicate.needs_infer()) {
+            infcx.wf_cache.borrow_mut().insert(ty, result.clone());
+        }
         Some(result)
     } else {
         None // no progress made, return None
@@ -73,6 +96,13 @@ pub fn predicate_obligations<'a,'tcx>(infcx: &InferCtxt<'a, 'tcx>,
         ty::Predicate::Trait(ref t) => {
             wf.compute_trait_ref(&t.skip_binder().trait_ref); // (*)
         }
+        ty::Predicate::NegativeTrait(ref t) => {
+            // Only the types have to be WF: the where-clauses of the
+            // trait need not hold, as the trait is not implemented.
+            for &ty in t.skip_binder().trait_ref.substs.types.as_slice() { // (*)
+                wf.compute(ty);
+            }
+        }
         ty::Predicate::Equate(ref t) => {
             wf.compute(t.skip_binder().0);
             wf.compute(t.skip_binder().1);
@@ -155,6 +185,7 @@ pub fn implied_bounds<'a,'tcx>(
                 assert!(!obligation.has_escaping_regions());
                 match obligation.predicate {
                     ty::Predicate::Trait(..) |
+                    ty::Predicate::NegativeTrait(..) |
                     ty::Predicate::Equate(..) |
                     ty::Predicate::Projection(..) |
                     ty::Predicate::ClosureKind(..) |
@@ -295,7 +326,8 @@ impl<'a,'tcx> WfPredicates<'a,'tcx> {
                 ty::TyFloat(..) |
                 ty::TyError |
                 ty::TyStr |
-                ty::TyParam(_) => {
+                ty::TyParam(_) |
+                ty::TyBound(..) => {
                     // WfScalar, WfParameter, etc
                 }
 
@@ -14,7 +14,7 @@ use ty::subst::{self, Subst};
 use ty::{BrAnon, BrEnv, BrFresh, BrNamed};
 use ty::{TyBool, TyChar, TyStruct, TyEnum};
 use ty::{TyError, TyStr, TyArray, TySlice, TyFloat, TyFnDef, TyFnPtr};
-use ty::{TyParam, TyRawPtr, TyRef, TyTuple};
+use ty::{TyParam, TyBound, TyRawPtr, TyRef, TyTuple};
 use ty::TyClosure;
 use ty::{TyBox, TyTrait, TyInt, TyUint, TyInfer};
 use ty::{self, Ty, TyCtxt, TypeFoldable};
@@ -430,6 +430,9 @@ impl<'tcx> fmt::Debug for ty::adjustment::AutoAdjustment<'tcx> {
             ty::adjustment::AdjustUnsafeFnPointer => {
                 write!(f, "AdjustUnsafeFnPointer")
             }
+            ty::adjustment::AdjustClosureFnPointer => {
+                write!(f, "AdjustClosureFnPointer")
+            }
             ty::adjustment::AdjustMutToConstPointer => {
                 write!(f, "AdjustMutToConstPointer")
             }
@@ -459,6 +462,7 @@ impl<'tcx> fmt::Debug for ty::Predicate<'tcx> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ty::Predicate::Trait(ref a) => write!(f, "{:?}", a),
+            ty::Predicate::NegativeTrait(ref a) => write!(f, "Not({:?})", a),
             ty::Predicate::Equate(ref pair) => write!(f, "{:?}", pair),
             ty::Predicate::RegionOutlives(ref pair) => write!(f, "{:?}", pair),
             ty::Predicate::TypeOutlives(ref pair) => write!(f, "{:?}", pair),
@@ -723,6 +727,12 @@ impl fmt::Debug for ty::FloatVid {
     }
 }
 
+impl fmt::Debug for ty::ConstVid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "_#{}c", self.index)
+    }
+}
+
 impl fmt::Debug for ty::RegionVid {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "'_#{}r", self.index)
@@ -743,7 +753,8 @@ impl fmt::Debug for ty::InferTy {
             ty::FloatVar(ref v) => v.fmt(f),
             ty::FreshTy(v) => write!(f, "FreshTy({:?})", v),
             ty::FreshIntTy(v) => write!(f, "FreshIntTy({:?})", v),
-            ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({:?})", v)
+            ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({:?})", v),
+            ty::SkolemizedTy(v) => write!(f, "SkolemizedTy({:?})", v)
         }
     }
 }
@@ -877,6 +888,13 @@ impl<'tcx> fmt::Display for ty::TypeVariants<'tcx> {
             TyInfer(infer_ty) => write!(f, "{}", infer_ty),
             TyError => write!(f, "[type error]"),
             TyParam(ref param_ty) => write!(f, "{}", param_ty),
+            TyBound(debruijn, ref bound_ty) => {
+                if verbose() {
+                    write!(f, "{}/#{}", bound_ty.name, debruijn.depth)
+                } else {
+                    write!(f, "{}", bound_ty.name)
+                }
+            }
             TyEnum(def, substs) | TyStruct(def, substs) => {
                 ty::tls::with(|tcx| {
                     if def.did.is_local() &&
@@ -960,7 +978,8 @@ impl fmt::Display for ty::InferTy {
             ty::TyVar(_) | ty::IntVar(_) | ty::FloatVar(_) => write!(f, "_"),
             ty::FreshTy(v) => write!(f, "FreshTy({})", v),
             ty::FreshIntTy(v) => write!(f, "FreshIntTy({})", v),
-            ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({})", v)
+            ty::FreshFloatTy(v) => write!(f, "FreshFloatTy({})", v),
+            ty::SkolemizedTy(v) => write!(f, "SkolemizedTy({})", v)
         }
     }
 }
@@ -1056,6 +1075,10 @@ impl<'tcx> fmt::Display for ty::Predicate<'tcx> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ty::Predicate::Trait(ref data) => write!(f, "{}", data),
+            ty::Predicate::NegativeTrait(ref data) => {
+                let trait_ref = &data.skip_binder().trait_ref;
+                write!(f, "{}: !{}", trait_ref.self_ty(), trait_ref)
+            }
             ty::Predicate::Equate(ref predicate) => write!(f, "{}", predicate),
             ty::Predicate::RegionOutlives(ref predicate) => write!(f, "{}", predicate),
             ty::Predicate::TypeOutlives(ref predicate) => write!(f, "{}", predicate),
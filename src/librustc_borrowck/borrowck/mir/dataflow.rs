@@ -12,13 +12,15 @@ use syntax::attr::AttrMetaMethods;
 
 use rustc::ty::TyCtxt;
 use rustc::mir::repr::{self, Mir};
+use rustc::util::nodemap::FnvHashMap;
 
 use std::io;
 use std::mem;
 use std::usize;
 
 use super::MirBorrowckCtxt;
-use super::gather_moves::{Location, MoveData, MovePathData, MovePathIndex, MoveOutIndex, PathMap};
+use super::gather_moves::{Location, MoveData, MoveOut, MovePathContent, MovePathData};
+use super::gather_moves::{MovePathIndex, MoveOutIndex, PathMap};
 use super::graphviz;
 use bitslice::BitSlice; // adds set_bit/get_bit to &[usize] bitvector rep.
 
@@ -130,6 +132,56 @@ impl<'b, 'a: 'b, 'tcx: 'a> MirBorrowckCtxt<'b, 'a, 'tcx> {
     }
 }
 
+/// A borrow shown by `-Z dump-mir-html`: `dest = &kind borrowed`.
+struct DumpedBorrow<'a, 'tcx: 'a> {
+    location: Location,
+    dest: &'a repr::Lvalue<'tcx>,
+    kind: repr::BorrowKind,
+    borrowed: &'a repr::Lvalue<'tcx>,
+}
+
+impl<'a, 'tcx> DumpedBorrow<'a, 'tcx> {
+    fn to_string(&self) -> String {
+        let kind = match self.kind {
+            repr::BorrowKind::Shared => "",
+            repr::BorrowKind::Unique => "uniq ",
+            repr::BorrowKind::Mut => "mut ",
+        };
+        format!("&{}{:?} (at {:?})", kind, self.borrowed, self.location)
+    }
+}
+
+/// The lvalue a call terminator writes its return value to when it
+/// returns into `succ`, if any.
+fn call_destination_for<'a, 'tcx>(term: &'a repr::Terminator<'tcx>,
+                                  succ: repr::BasicBlock)
+                                  -> Option<&'a repr::Lvalue<'tcx>> {
+    match term.kind {
+        repr::TerminatorKind::Call { destination: Some((ref dest, target)), .. }
+            if target == succ => Some(dest),
+        _ => None,
+    }
+}
+
+/// Sets (or clears) the bit for `move_path_index` and for every
+/// child path beneath it.
+fn set_path_bits(set: &mut [usize],
+                 move_paths: &MovePathData,
+                 move_path_index: MovePathIndex,
+                 value: bool) {
+    if value {
+        set.set_bit(move_path_index.idx());
+    } else {
+        set.clear_bit(move_path_index.idx());
+    }
+
+    let mut next_child_index = move_paths[move_path_index].first_child;
+    while let Some(child_index) = next_child_index {
+        set_path_bits(set, move_paths, child_index, value);
+        next_child_index = move_paths[child_index].next_sibling;
+    }
+}
+
 fn on_all_children_bits<Each>(set: &mut [usize],
                               path_map: &PathMap,
                               move_paths: &MovePathData,
@@ -197,7 +249,237 @@ impl<'b, 'a: 'b, 'tcx: 'a> MirBorrowckCtxt<'b, 'a, 'tcx> {
             "borrowck_graphviz_postflow",
             |this, path: &str| {
                 graphviz::print_borrowck_graph_to(this, "postflow", path)
-            })
+            })?;
+        self.dump_dataflow_html();
+        Ok(())
+    }
+
+    /// Under `-Z dump-mir-html`, renders the MIR with the paths that
+    /// may have been moved out of, the paths that may be initialized
+    /// and the borrows that may be in scope on entry to each
+    /// statement, along with the moves the statement itself performs.
+    ///
+    /// The block-level gen and kill sets lose the order of effects
+    /// within a block, so the per-statement state is recomputed here
+    /// from the on-entry set of each block.
+    fn dump_dataflow_html(&self) {
+        let tcx = self.bcx.tcx;
+        if !tcx.sess.opts.debugging_opts.dump_mir_html {
+            return;
+        }
+
+        let move_data = &self.flow_state.operator;
+        let move_paths = &move_data.move_paths;
+        let borrows = self.dump_borrows();
+        let borrow_indices: FnvHashMap<_, _> = borrows.iter().enumerate().map(|(i, borrow)| {
+            ((borrow.location.block.index(), borrow.location.index), i)
+        }).collect();
+
+        // A path becomes initialized when it is assigned to (or is the
+        // destination of a call that returns), and stops being so when
+        // it is moved out of. Arguments are initialized on entry.
+        let init_start = |in_out: &mut [usize]| {
+            for i in 0..move_paths.len() {
+                let mpi = MovePathIndex::new(i);
+                if let MovePathContent::Lvalue(repr::Lvalue::Arg(_)) = move_paths[mpi].content {
+                    set_path_bits(in_out, move_paths, mpi, true);
+                }
+            }
+        };
+        let init_effect = |in_out: &mut [usize], loc: Location| {
+            for move_index in &move_data.loc_map[loc] {
+                let path = move_data.moves[move_index.idx()].path;
+                set_path_bits(in_out, move_paths, path, false);
+            }
+            if let Some(stmt) = self.mir.basic_block_data(loc.block).statements.get(loc.index) {
+                match stmt.kind {
+                    repr::StatementKind::Assign(ref lvalue, _) => {
+                        let path = move_data.rev_lookup.find(lvalue);
+                        set_path_bits(in_out, move_paths, path, true);
+                    }
+                }
+            }
+        };
+        let init_edge = |in_out: &mut [usize], term: &repr::Terminator, succ| {
+            if let Some(dest) = call_destination_for(term, succ) {
+                let path = move_data.rev_lookup.find(dest);
+                set_path_bits(in_out, move_paths, path, true);
+            }
+        };
+
+        // MIR has no marker for the end of a region, so a borrow is
+        // taken to stay in scope until the reference it was stored in
+        // is overwritten.
+        let borrow_effect = |in_out: &mut [usize], loc: Location| {
+            let stmt = match self.mir.basic_block_data(loc.block).statements.get(loc.index) {
+                Some(stmt) => stmt,
+                None => return,
+            };
+            match stmt.kind {
+                repr::StatementKind::Assign(ref lvalue, ref rvalue) => {
+                    for (i, borrow) in borrows.iter().enumerate() {
+                        if borrow.dest == lvalue {
+                            in_out.clear_bit(i);
+                        }
+                    }
+                    if let repr::Rvalue::Ref(..) = *rvalue {
+                        in_out.set_bit(borrow_indices[&(loc.block.index(), loc.index)]);
+                    }
+                }
+            }
+        };
+        let borrow_edge = |in_out: &mut [usize], term: &repr::Terminator, succ| {
+            if let Some(dest) = call_destination_for(term, succ) {
+                for (i, borrow) in borrows.iter().enumerate() {
+                    if borrow.dest == dest {
+                        in_out.clear_bit(i);
+                    }
+                }
+            }
+        };
+
+        let inits_on_entry =
+            self.dump_entry_sets(move_paths.len(), &init_start, &init_effect, &init_edge);
+        let borrows_on_entry =
+            self.dump_entry_sets(borrows.len(), &|_| {}, &borrow_effect, &borrow_edge);
+
+        let mut facts = Vec::with_capacity(self.mir.basic_blocks.len());
+        let mut in_out = vec![0; self.flow_state.sets.words_per_block];
+        for bb in self.mir.all_basic_blocks() {
+            let statements = &self.mir.basic_block_data(bb).statements;
+            in_out.clone_from_slice(self.flow_state.sets.on_entry_set_for(bb.index()));
+            let mut inits = inits_on_entry[bb.index()].clone();
+            let mut in_scope = borrows_on_entry[bb.index()].clone();
+
+            let mut block_facts = Vec::with_capacity(statements.len() + 1);
+            for index in 0..(statements.len() + 1) {
+                let loc = Location { block: bb, index: index };
+                let moved = self.flow_state.interpret_set(&in_out);
+                let moves: Vec<_> = move_data.loc_map[loc].iter()
+                                             .map(|i| &move_data.moves[i.idx()])
+                                             .collect();
+                let initialized: Vec<_> = (0..move_paths.len())
+                    .filter(|&i| inits.get_bit(i))
+                    .filter_map(|i| match move_paths[MovePathIndex::new(i)].content {
+                        MovePathContent::Lvalue(ref lvalue) => Some(format!("{:?}", lvalue)),
+                        MovePathContent::Static => None,
+                    })
+                    .collect();
+                let live_borrows: Vec<_> = borrows.iter().enumerate()
+                    .filter(|&(i, _)| in_scope.get_bit(i))
+                    .map(|(_, borrow)| borrow.to_string())
+                    .collect();
+                let mut lines = vec![format!("maybe moved: {}",
+                                             self.move_outs_to_string(&moved)),
+                                     format!("maybe initialized: [{}]", initialized.join(", ")),
+                                     format!("borrows in scope: [{}]", live_borrows.join(", "))];
+                if !moves.is_empty() {
+                    lines.push(format!("moves out: {}", self.move_outs_to_string(&moves)));
+                }
+                block_facts.push(lines);
+
+                init_effect(&mut inits, loc);
+                borrow_effect(&mut in_scope, loc);
+                for move_index in &move_data.loc_map[loc] {
+                    in_out.set_bit(move_index.idx());
+                }
+                if let Some(stmt) = statements.get(index) {
+                    match stmt.kind {
+                        repr::StatementKind::Assign(ref lvalue, _) => {
+                            let move_path_index = move_data.rev_lookup.find(lvalue);
+                            on_all_children_bits(&mut in_out,
+                                                 &move_data.path_map,
+                                                 &move_data.move_paths,
+                                                 move_path_index,
+                                                 &|in_out, mpi| {
+                                                     in_out.clear_bit(mpi.idx());
+                                                 });
+                        }
+                    }
+                }
+            }
+            facts.push(block_facts);
+        }
+
+        ::rustc_mir::pretty::dump_mir_html(tcx, "borrowck", &"moves", self.node_id, self.mir,
+                                           |bb, index| facts[bb.index()][index].clone());
+    }
+
+    /// Collects every `dest = &borrowed` statement, in block order.
+    fn dump_borrows(&self) -> Vec<DumpedBorrow<'b, 'tcx>> {
+        let mir: &'b Mir<'tcx> = self.mir;
+        let mut borrows = vec![];
+        for bb in mir.all_basic_blocks() {
+            let statements = &mir.basic_block_data(bb).statements;
+            for (index, stmt) in statements.iter().enumerate() {
+                match stmt.kind {
+                    repr::StatementKind::Assign(ref dest,
+                                                repr::Rvalue::Ref(_, kind, ref borrowed)) => {
+                        borrows.push(DumpedBorrow {
+                            location: Location { block: bb, index: index },
+                            dest: dest,
+                            kind: kind,
+                            borrowed: borrowed,
+                        });
+                    }
+                    repr::StatementKind::Assign(..) => {}
+                }
+            }
+        }
+        borrows
+    }
+
+    /// Solves a forward "may" analysis of `bits` bits for the HTML
+    /// dump, returning the set on entry to each block. `start` fills
+    /// in the set on entry to the function, `effect` applies the
+    /// statement or terminator at a location, and `on_edge` adjusts
+    /// the set flowing out of a terminator into one successor.
+    fn dump_entry_sets(&self,
+                       bits: usize,
+                       start: &Fn(&mut [usize]),
+                       effect: &Fn(&mut [usize], Location),
+                       on_edge: &Fn(&mut [usize], &repr::Terminator<'tcx>, repr::BasicBlock))
+                       -> Vec<Vec<usize>> {
+        let usize_bits = mem::size_of::<usize>() * 8;
+        let words = (bits + usize_bits - 1) / usize_bits;
+        let mut on_entry = vec![vec![0; words]; self.mir.basic_blocks.len()];
+        start(&mut on_entry[repr::START_BLOCK.index()]);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for bb in self.mir.all_basic_blocks() {
+                let data = self.mir.basic_block_data(bb);
+                let mut in_out = on_entry[bb.index()].clone();
+                for index in 0..(data.statements.len() + 1) {
+                    effect(&mut in_out, Location { block: bb, index: index });
+                }
+                for &succ in data.terminator().successors().iter() {
+                    let mut out = in_out.clone();
+                    on_edge(&mut out, data.terminator(), succ);
+                    for (entry, &bit) in on_entry[succ.index()].iter_mut().zip(&out) {
+                        if (*entry | bit) != *entry {
+                            *entry |= bit;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+        on_entry
+    }
+
+    fn move_outs_to_string(&self, move_outs: &[&MoveOut]) -> String {
+        let move_paths = &self.flow_state.operator.move_paths;
+        let strings: Vec<_> = move_outs.iter().map(|move_out| {
+            match move_paths[move_out.path].content {
+                MovePathContent::Lvalue(ref lvalue) =>
+                    format!("{:?} (at {:?})", lvalue, move_out.source),
+                MovePathContent::Static =>
+                    format!("<static> (at {:?})", move_out.source),
+            }
+        }).collect();
+        format!("[{}]", strings.join(", "))
     }
 
     fn if_attr_meta_name_found<F>(&self,
@@ -412,7 +694,8 @@ impl<D: BitDenotation> DataflowState<D> {
     {
         match bb.terminator().kind {
             repr::TerminatorKind::Return |
-            repr::TerminatorKind::Resume => {}
+            repr::TerminatorKind::Resume |
+            repr::TerminatorKind::TailCall { .. } => {}
             repr::TerminatorKind::Goto { ref target } |
             repr::TerminatorKind::Drop { ref target, value: _, unwind: None } => {
                 self.propagate_bits_into_entry_set_for(in_out, changed, target);
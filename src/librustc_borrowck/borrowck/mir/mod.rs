@@ -22,6 +22,7 @@ mod abs_domain;
 mod dataflow;
 mod gather_moves;
 mod graphviz;
+pub mod nll;
 
 use self::dataflow::{Dataflow, DataflowState};
 use self::gather_moves::{MoveData};
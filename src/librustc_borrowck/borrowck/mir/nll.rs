@@ -0,0 +1,692 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Liveness-based ("non-lexical") regions for checking loans on MIR.
+//!
+//! With `-Z nll`, the loans of fns that have MIR are checked here
+//! instead of in `check_loans`. Rather than keeping a loan in effect
+//! until the end of the lexical scope that region inference picked
+//! for it, the region of a borrow is the set of points in the MIR
+//! control-flow graph, reachable from the borrow, at which some local
+//! that may hold the resulting reference is live -- that is, may
+//! still be used later on. Every access made within that region is
+//! then checked against the borrowed lvalue.
+//!
+//! Which locals may hold a borrow is approximated flow-insensitively:
+//! starting from the destination of the borrow, the reference flows
+//! into every local written by a statement or call reading a holder,
+//! provided the type of that local mentions a region which the region
+//! of the borrow outlives. Writing a holder through a reference makes
+//! the referent a holder as well.
+//!
+//! Only conflicts between loans and other accesses are checked here.
+//! A borrow outliving the borrowed lvalue, including one still held
+//! when the lvalue is dropped, implies that the lexical region of the
+//! borrow does as well, so those errors are left to `gather_loans`.
+
+use borrowck::BorrowckCtxt;
+
+use syntax::ast;
+use syntax::codemap::Span;
+
+use rustc::mir::repr::*;
+use rustc::mir::tcx::LvalueTy;
+use rustc::mir::visit::{LvalueContext, Visitor};
+use rustc::ty::{self, Ty};
+use rustc::util::nodemap::FnvHashSet;
+
+use bitslice::BitSlice;
+use std::mem;
+
+use super::gather_moves::Location;
+
+pub fn check_loans<'a, 'tcx>(bccx: &BorrowckCtxt<'a, 'tcx>,
+                             mir: &Mir<'tcx>,
+                             id: ast::NodeId) {
+    debug!("nll::check_loans(id={})", id);
+
+    let mut cx = NllCtxt::new(bccx, mir, id);
+    cx.compute_liveness();
+    cx.compute_refs_to();
+
+    for borrow in cx.borrows() {
+        cx.check_borrow(&borrow);
+    }
+}
+
+/// A `&` or `&mut` rvalue, along with where its result is stored.
+struct Borrow<'tcx> {
+    location: Location,
+    span: Span,
+    region: ty::Region,
+    kind: BorrowKind,
+    lvalue: Lvalue<'tcx>,
+    dest: Lvalue<'tcx>,
+}
+
+/// A way an access may be incompatible with a borrow in effect.
+enum Conflict {
+    Assign,
+    Move,
+    Use,
+    Borrow(BorrowKind),
+}
+
+/// How an accessed lvalue relates to a borrowed one.
+#[derive(PartialEq)]
+enum Overlap {
+    Disjoint,
+    /// The borrowed lvalue is reached from the accessed one through a
+    /// dereference of a reference or raw pointer, so overwriting the
+    /// accessed lvalue leaves the borrowed data alone.
+    Indirect,
+    Overlapping,
+}
+
+/// Dense numbering of the locals of a fn -- args, then vars, then
+/// temps, then the return pointer -- used as the domain of liveness.
+struct Locals {
+    args: usize,
+    vars: usize,
+    temps: usize,
+}
+
+impl Locals {
+    fn new(mir: &Mir) -> Locals {
+        Locals {
+            args: mir.arg_decls.len(),
+            vars: mir.var_decls.len(),
+            temps: mir.temp_decls.len(),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.args + self.vars + self.temps + 1
+    }
+
+    /// The local named by `lvalue`, unless it is a static or a projection.
+    fn index(&self, lvalue: &Lvalue) -> Option<usize> {
+        match *lvalue {
+            Lvalue::Arg(index) => Some(index as usize),
+            Lvalue::Var(index) => Some(self.args + index as usize),
+            Lvalue::Temp(index) => Some(self.args + self.vars + index as usize),
+            Lvalue::ReturnPointer => Some(self.args + self.vars + self.temps),
+            Lvalue::Static(_) | Lvalue::Projection(_) => None,
+        }
+    }
+
+    /// The local `lvalue` is (a projection of), if any.
+    fn root(&self, lvalue: &Lvalue) -> Option<usize> {
+        match *lvalue {
+            Lvalue::Projection(ref proj) => self.root(&proj.base),
+            _ => self.index(lvalue),
+        }
+    }
+
+    fn ty<'tcx>(&self, mir: &Mir<'tcx>, local: usize) -> Option<Ty<'tcx>> {
+        if local < self.args {
+            Some(mir.arg_decls[local].ty)
+        } else if local < self.args + self.vars {
+            Some(mir.var_decls[local - self.args].ty)
+        } else if local < self.args + self.vars + self.temps {
+            Some(mir.temp_decls[local - self.args - self.vars].ty)
+        } else {
+            match mir.return_ty {
+                ty::FnConverging(ty) => Some(ty),
+                ty::FnDiverging => None,
+            }
+        }
+    }
+}
+
+/// Collects the lvalues accessed by a statement or terminator, along
+/// with the context they are accessed in. The bases of projections are
+/// not recorded on their own.
+struct AccessCollector<'tcx> {
+    accesses: Vec<(Lvalue<'tcx>, LvalueContext)>,
+}
+
+impl<'tcx> Visitor<'tcx> for AccessCollector<'tcx> {
+    fn visit_terminator_kind(&mut self, block: BasicBlock, kind: &TerminatorKind<'tcx>) {
+        if let TerminatorKind::Return = *kind {
+            self.accesses.push((Lvalue::ReturnPointer, LvalueContext::Consume));
+        }
+        self.super_terminator_kind(block, kind);
+    }
+
+    fn visit_lvalue(&mut self, lvalue: &Lvalue<'tcx>, context: LvalueContext) {
+        match context {
+            LvalueContext::Projection => {}
+            _ => self.accesses.push((lvalue.clone(), context)),
+        }
+        self.super_lvalue(lvalue, context);
+    }
+}
+
+fn is_write(context: LvalueContext) -> bool {
+    match context {
+        LvalueContext::Store | LvalueContext::Call => true,
+        _ => false,
+    }
+}
+
+fn is_mutable(kind: BorrowKind) -> bool {
+    match kind {
+        BorrowKind::Shared => false,
+        BorrowKind::Unique | BorrowKind::Mut => true,
+    }
+}
+
+fn kind_to_user_str(kind: BorrowKind) -> &'static str {
+    if is_mutable(kind) { "mutable" } else { "immutable" }
+}
+
+fn words_for(bits: usize) -> usize {
+    let usize_bits = mem::size_of::<usize>() * 8;
+    (bits + usize_bits - 1) / usize_bits
+}
+
+fn union(into: &mut [usize], from: &[usize]) {
+    for (into, from) in into.iter_mut().zip(from) {
+        *into |= *from;
+    }
+}
+
+/// Splits `lvalue` into the lvalue at its root and the projections
+/// applied to it, innermost first.
+fn split_projections<'l, 'tcx>(mut lvalue: &'l Lvalue<'tcx>)
+                               -> (&'l Lvalue<'tcx>, Vec<&'l LvalueProjection<'tcx>>) {
+    let mut projections = vec![];
+    while let Lvalue::Projection(ref proj) = *lvalue {
+        projections.push(&**proj);
+        lvalue = &proj.base;
+    }
+    projections.reverse();
+    (lvalue, projections)
+}
+
+/// Whether two projections of the same lvalue certainly do not overlap.
+fn disjoint_elems(a: &LvalueElem, b: &LvalueElem) -> bool {
+    match (a, b) {
+        (&ProjectionElem::Field(a, _), &ProjectionElem::Field(b, _)) => a != b,
+        (&ProjectionElem::Downcast(_, a), &ProjectionElem::Downcast(_, b)) => a != b,
+        (&ProjectionElem::ConstantIndex { offset: a, from_end: a_from_end, .. },
+         &ProjectionElem::ConstantIndex { offset: b, from_end: b_from_end, .. }) => {
+            a_from_end == b_from_end && a != b
+        }
+        _ => false,
+    }
+}
+
+struct NllCtxt<'b, 'a: 'b, 'tcx: 'a> {
+    bccx: &'b BorrowckCtxt<'a, 'tcx>,
+    mir: &'b Mir<'tcx>,
+    param_env: ty::ParameterEnvironment<'a, 'tcx>,
+    locals: Locals,
+    /// Index of the first point of each basic block; the points of a
+    /// block are its statements followed by its terminator.
+    block_starts: Vec<usize>,
+    /// Lvalues accessed at each point.
+    accesses: Vec<Vec<(Lvalue<'tcx>, LvalueContext)>>,
+    /// Bitvector of the locals live on entry to each point.
+    live: Vec<Vec<usize>>,
+    /// For each local, the locals it may hold a reference into.
+    refs_to: Vec<Vec<usize>>,
+    /// Points at which an error was reported already.
+    reported: Vec<bool>,
+}
+
+impl<'b, 'a: 'b, 'tcx: 'a> NllCtxt<'b, 'a, 'tcx> {
+    fn new(bccx: &'b BorrowckCtxt<'a, 'tcx>,
+           mir: &'b Mir<'tcx>,
+           id: ast::NodeId)
+           -> NllCtxt<'b, 'a, 'tcx> {
+        let mut block_starts = vec![];
+        let mut accesses = vec![];
+        for block in mir.all_basic_blocks() {
+            block_starts.push(accesses.len());
+            let data = &mir[block];
+            for statement in &data.statements {
+                let mut collector = AccessCollector { accesses: vec![] };
+                collector.visit_statement(block, statement);
+                accesses.push(collector.accesses);
+            }
+            let mut collector = AccessCollector { accesses: vec![] };
+            collector.visit_terminator(block, data.terminator());
+            accesses.push(collector.accesses);
+        }
+
+        let locals = Locals::new(mir);
+        let points = accesses.len();
+        NllCtxt {
+            bccx: bccx,
+            mir: mir,
+            param_env: ty::ParameterEnvironment::for_item(bccx.tcx, id),
+            refs_to: vec![vec![]; locals.count()],
+            locals: locals,
+            block_starts: block_starts,
+            accesses: accesses,
+            live: vec![],
+            reported: vec![false; points],
+        }
+    }
+
+    fn point_index(&self, point: Location) -> usize {
+        self.block_starts[point.block.index()] + point.index
+    }
+
+    fn successors(&self, point: Location) -> Vec<Location> {
+        let data = &self.mir[point.block];
+        if point.index < data.statements.len() {
+            vec![Location { block: point.block, index: point.index + 1 }]
+        } else {
+            data.terminator().successors().iter().map(|&block| {
+                Location { block: block, index: 0 }
+            }).collect()
+        }
+    }
+
+    fn span(&self, point: Location) -> Span {
+        let data = &self.mir[point.block];
+        if point.index < data.statements.len() {
+            data.statements[point.index].span
+        } else {
+            data.terminator().span
+        }
+    }
+
+    fn lvalue_ty(&self, lvalue: &Lvalue<'tcx>) -> Ty<'tcx> {
+        self.mir.lvalue_ty(self.bccx.tcx, lvalue).to_ty(self.bccx.tcx)
+    }
+
+    fn is_box(&self, lvalue: &Lvalue<'tcx>) -> bool {
+        match self.lvalue_ty(lvalue).sty {
+            ty::TyBox(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Computes the locals live on entry to each point: a local is live
+    /// if it may be used before being overwritten.
+    fn compute_liveness(&mut self) {
+        let mir = self.mir;
+        let words = words_for(self.locals.count());
+        self.live = vec![vec![0; words]; self.accesses.len()];
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in mir.all_basic_blocks().into_iter().rev() {
+                let data = &mir[block];
+                let mut live = vec![0; words];
+                for &succ in data.terminator().successors().iter() {
+                    union(&mut live, &self.live[self.block_starts[succ.index()]]);
+                }
+                for index in (0..data.statements.len() + 1).rev() {
+                    let point = self.block_starts[block.index()] + index;
+                    self.transfer(point, &mut live);
+                    if self.live[point] != live {
+                        self.live[point] = live.clone();
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Turns the locals live on exit from `point` into those live on
+    /// entry to it. Writing a whole local kills it; anything else
+    /// touching a local, including writing one of its fields, uses it.
+    fn transfer(&self, point: usize, live: &mut [usize]) {
+        for &(ref lvalue, context) in &self.accesses[point] {
+            if is_write(context) {
+                if let Some(local) = self.locals.index(lvalue) {
+                    live.clear_bit(local);
+                }
+            }
+        }
+        for &(ref lvalue, context) in &self.accesses[point] {
+            if is_write(context) && self.locals.index(lvalue).is_some() {
+                continue;
+            }
+            if let Some(local) = self.locals.root(lvalue) {
+                live.set_bit(local);
+            }
+        }
+    }
+
+    /// Computes which locals each local may hold a reference into, by
+    /// following borrows and the locals read while writing another.
+    fn compute_refs_to(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for point in 0..self.accesses.len() {
+                let mut refs = vec![];
+                for &(ref lvalue, context) in &self.accesses[point] {
+                    let root = match self.locals.root(lvalue) {
+                        Some(root) => root,
+                        None => continue,
+                    };
+                    match context {
+                        LvalueContext::Borrow { .. } | LvalueContext::Slice { .. } => {
+                            refs.push(root);
+                        }
+                        LvalueContext::Consume => {
+                            refs.extend(self.refs_to[root].iter().cloned());
+                        }
+                        _ => {}
+                    }
+                }
+                if refs.is_empty() {
+                    continue;
+                }
+                for &(ref lvalue, context) in &self.accesses[point] {
+                    if !is_write(context) {
+                        continue;
+                    }
+                    if let Some(root) = self.locals.root(lvalue) {
+                        for &local in &refs {
+                            if !self.refs_to[root].contains(&local) {
+                                self.refs_to[root].push(local);
+                                changed = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn borrows(&self) -> Vec<Borrow<'tcx>> {
+        let mut borrows = vec![];
+        for block in self.mir.all_basic_blocks() {
+            let data = &self.mir[block];
+            if data.is_cleanup {
+                continue;
+            }
+            for (index, statement) in data.statements.iter().enumerate() {
+                let StatementKind::Assign(ref dest, ref rvalue) = statement.kind;
+                if let Rvalue::Ref(region, kind, ref lvalue) = *rvalue {
+                    borrows.push(Borrow {
+                        location: Location { block: block, index: index },
+                        span: statement.span,
+                        region: region,
+                        kind: kind,
+                        lvalue: lvalue.clone(),
+                        dest: dest.clone(),
+                    });
+                }
+            }
+        }
+        borrows
+    }
+
+    /// Whether the type of `local` allows it to hold a reference with
+    /// the given region, i.e. mentions a region that it outlives.
+    fn may_hold(&self, local: usize, region: ty::Region) -> bool {
+        let ty = match self.locals.ty(self.mir, local) {
+            Some(ty) => ty,
+            None => return false,
+        };
+        let mut regions = FnvHashSet();
+        self.bccx.tcx.collect_regions(&ty, &mut regions);
+        regions.iter().any(|&r| self.bccx.is_subregion_of(r, region))
+    }
+
+    /// Computes the bitvector of locals that may hold the reference
+    /// created by `borrow`.
+    fn holders(&self, borrow: &Borrow<'tcx>) -> Vec<usize> {
+        let count = self.locals.count();
+        let may_hold: Vec<bool> = (0..count).map(|local| {
+            self.may_hold(local, borrow.region)
+        }).collect();
+
+        let mut holders = vec![0; words_for(count)];
+        if let Some(root) = self.locals.root(&borrow.dest) {
+            holders.set_bit(root);
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let mut new_holders = vec![];
+            for accesses in &self.accesses {
+                let reads_holder = accesses.iter().any(|&(ref lvalue, context)| {
+                    !is_write(context) && self.locals.root(lvalue).map_or(false, |root| {
+                        holders.get_bit(root)
+                    })
+                });
+                if !reads_holder {
+                    continue;
+                }
+                // A call may store the reference into anything else it
+                // was passed, so its arguments are written to as well.
+                let is_call = accesses.iter().any(|&(_, context)| match context {
+                    LvalueContext::Call => true,
+                    _ => false,
+                });
+                for &(ref lvalue, context) in accesses {
+                    if is_call || is_write(context) {
+                        new_holders.extend(self.locals.root(lvalue));
+                    }
+                }
+            }
+            for local in 0..count {
+                if holders.get_bit(local) {
+                    new_holders.extend(self.refs_to[local].iter().cloned());
+                }
+            }
+            for local in new_holders {
+                if may_hold[local] && holders.set_bit(local) {
+                    changed = true;
+                }
+            }
+        }
+        holders
+    }
+
+    /// Computes the region of a borrow: the points reachable from it at
+    /// which one of its holders is live.
+    fn region(&self, start: Location, holders: &[usize]) -> Vec<Location> {
+        let mut visited = vec![false; self.accesses.len()];
+        let mut stack = self.successors(start);
+        let mut region = vec![];
+        while let Some(point) = stack.pop() {
+            let index = self.point_index(point);
+            if visited[index] {
+                continue;
+            }
+            visited[index] = true;
+            let live = &self.live[index];
+            if !live.iter().zip(holders).any(|(&live, &holder)| live & holder != 0) {
+                continue;
+            }
+            region.push(point);
+            stack.extend(self.successors(point));
+        }
+        region
+    }
+
+    fn check_borrow(&mut self, borrow: &Borrow<'tcx>) {
+        let holders = self.holders(borrow);
+        let region = self.region(borrow.location, &holders);
+        debug!("check_borrow({:?} at {:?}): region={:?}",
+               borrow.lvalue, borrow.location, region);
+
+        for point in region {
+            if self.mir[point.block].is_cleanup {
+                continue;
+            }
+            let index = self.point_index(point);
+            let conflict = self.accesses[index].iter().filter_map(|&(ref lvalue, context)| {
+                self.conflict(borrow, lvalue, context).map(|conflict| (lvalue.clone(), conflict))
+            }).next();
+            if let Some((lvalue, conflict)) = conflict {
+                let span = self.span(point);
+                self.report_conflict(borrow, index, span, &lvalue, conflict);
+            }
+        }
+    }
+
+    fn overlap(&self, borrowed: &Lvalue<'tcx>, accessed: &Lvalue<'tcx>) -> Overlap {
+        let (borrowed_root, borrowed_projs) = split_projections(borrowed);
+        let (accessed_root, accessed_projs) = split_projections(accessed);
+        if borrowed_root != accessed_root {
+            return Overlap::Disjoint;
+        }
+        for (b, a) in borrowed_projs.iter().zip(&accessed_projs) {
+            if disjoint_elems(&b.elem, &a.elem) {
+                return Overlap::Disjoint;
+            }
+        }
+        if accessed_projs.len() < borrowed_projs.len() {
+            let indirect = borrowed_projs[accessed_projs.len()..].iter().any(|proj| {
+                match proj.elem {
+                    ProjectionElem::Deref => !self.is_box(&proj.base),
+                    _ => false,
+                }
+            });
+            if indirect {
+                return Overlap::Indirect;
+            }
+        }
+        Overlap::Overlapping
+    }
+
+    fn conflict(&self,
+                borrow: &Borrow<'tcx>,
+                lvalue: &Lvalue<'tcx>,
+                context: LvalueContext)
+                -> Option<Conflict> {
+        let overlap = self.overlap(&borrow.lvalue, lvalue);
+        if overlap == Overlap::Disjoint {
+            return None;
+        }
+        let direct = overlap == Overlap::Overlapping;
+        match context {
+            LvalueContext::Store | LvalueContext::Call if direct => Some(Conflict::Assign),
+            LvalueContext::Consume => {
+                if self.lvalue_ty(lvalue).moves_by_default(&self.param_env, borrow.span) {
+                    Some(Conflict::Move)
+                } else if direct && is_mutable(borrow.kind) {
+                    Some(Conflict::Use)
+                } else {
+                    None
+                }
+            }
+            LvalueContext::Inspect if direct && is_mutable(borrow.kind) => Some(Conflict::Use),
+            LvalueContext::Borrow { kind, .. } if is_mutable(kind) || is_mutable(borrow.kind) => {
+                Some(Conflict::Borrow(kind))
+            }
+            LvalueContext::Slice { .. } if is_mutable(borrow.kind) => {
+                Some(Conflict::Borrow(BorrowKind::Shared))
+            }
+            _ => None,
+        }
+    }
+
+    fn describe_lvalue(&self, lvalue: &Lvalue<'tcx>) -> String {
+        match *lvalue {
+            Lvalue::Var(index) => self.mir.var_decls[index as usize].name.to_string(),
+            Lvalue::Arg(index) => format!("arg{}", index),
+            Lvalue::Temp(_) => "<rvalue>".to_string(),
+            Lvalue::Static(def_id) => self.bccx.tcx.item_path_str(def_id),
+            Lvalue::ReturnPointer => "<return value>".to_string(),
+            Lvalue::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Deref => format!("*{}", self.describe_lvalue(&proj.base)),
+                ProjectionElem::Field(field, _) => {
+                    // Fields of dereferenced pointers are shown as if
+                    // autoderef had been applied, like in expressions.
+                    let base = match proj.base {
+                        Lvalue::Projection(ref base) if base.elem == ProjectionElem::Deref => {
+                            &base.base
+                        }
+                        ref base => base,
+                    };
+                    format!("{}.{}", self.describe_lvalue(base), self.field_name(&proj.base, field))
+                }
+                ProjectionElem::Index(_) | ProjectionElem::ConstantIndex { .. } => {
+                    format!("{}[..]", self.describe_lvalue(&proj.base))
+                }
+                ProjectionElem::Downcast(adt_def, variant_index) => {
+                    format!("({} as {})",
+                            self.describe_lvalue(&proj.base),
+                            adt_def.variants[variant_index].name)
+                }
+            },
+        }
+    }
+
+    fn field_name(&self, base: &Lvalue<'tcx>, field: Field) -> String {
+        match self.mir.lvalue_ty(self.bccx.tcx, base) {
+            LvalueTy::Ty { ty } => match ty.sty {
+                ty::TyStruct(adt_def, _) => {
+                    adt_def.struct_variant().fields[field.index()].name.to_string()
+                }
+                _ => field.index().to_string(),
+            },
+            LvalueTy::Downcast { adt_def, variant_index, .. } => {
+                adt_def.variants[variant_index].fields[field.index()].name.to_string()
+            }
+        }
+    }
+
+    fn report_conflict(&mut self,
+                       borrow: &Borrow<'tcx>,
+                       point: usize,
+                       span: Span,
+                       lvalue: &Lvalue<'tcx>,
+                       conflict: Conflict) {
+        if mem::replace(&mut self.reported[point], true) {
+            return;
+        }
+
+        let accessed = self.describe_lvalue(lvalue);
+        let borrowed = self.describe_lvalue(&borrow.lvalue);
+        let mut err = match conflict {
+            Conflict::Assign => {
+                struct_span_err!(self.bccx, span, E0506,
+                                 "cannot assign to `{}` because it is borrowed",
+                                 accessed)
+            }
+            Conflict::Move => {
+                struct_span_err!(self.bccx, span, E0505,
+                                 "cannot move out of `{}` because it is borrowed",
+                                 accessed)
+            }
+            Conflict::Use => {
+                struct_span_err!(self.bccx, span, E0503,
+                                 "cannot use `{}` because it was mutably borrowed",
+                                 accessed)
+            }
+            Conflict::Borrow(kind) if is_mutable(kind) && is_mutable(borrow.kind) => {
+                struct_span_err!(self.bccx, span, E0499,
+                                 "cannot borrow `{}` as mutable more than once at a time",
+                                 accessed)
+            }
+            Conflict::Borrow(kind) => {
+                let pronoun = if accessed == borrowed {
+                    "it".to_string()
+                } else {
+                    format!("`{}`", borrowed)
+                };
+                struct_span_err!(self.bccx, span, E0502,
+                                 "cannot borrow `{}` as {} because {} is also borrowed as {}",
+                                 accessed,
+                                 kind_to_user_str(kind),
+                                 pronoun,
+                                 kind_to_user_str(borrow.kind))
+            }
+        };
+        err.span_note(borrow.span, &format!("borrow of `{}` occurs here", borrowed));
+        err.emit();
+    }
+}
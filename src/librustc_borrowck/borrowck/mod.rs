@@ -50,6 +50,7 @@ use rustc::hir::intravisit;
 use rustc::hir::intravisit::{Visitor, FnKind};
 
 use rustc::mir::mir_map::MirMap;
+use rustc::mir::repr::Mir;
 
 pub mod check_loans;
 
@@ -65,6 +66,10 @@ pub struct LoanDataFlowOperator;
 pub type LoanDataFlow<'a, 'tcx> = DataFlowContext<'a, 'tcx, LoanDataFlowOperator>;
 
 impl<'a, 'tcx, 'v> Visitor<'v> for BorrowckCtxt<'a, 'tcx> {
+    fn visit_body_id(&mut self, id: hir::BodyId) {
+        self.tcx.map.read_body(id);
+    }
+
     fn visit_fn(&mut self, fk: FnKind<'v>, fd: &'v FnDecl,
                 b: &'v Block, s: Span, id: ast::NodeId) {
         match fk {
@@ -173,6 +178,10 @@ fn borrowck_fn(this: &mut BorrowckCtxt,
         });
     }
 
+    if let Some(mir) = this.nll_mir(id) {
+        mir::nll::check_loans(this, mir, id);
+    }
+
     let cfg = cfg::CFG::new(this.tcx, body);
     let AnalysisData { all_loans,
                        loans: loan_dfcx,
@@ -221,8 +230,14 @@ fn build_borrowck_dataflow_data<'a, 'tcx>(this: &mut BorrowckCtxt<'a, 'tcx>,
                              LoanDataFlowOperator,
                              id_range,
                              all_loans.len());
+    // With `-Z nll` conflicting loans are checked on the MIR instead, so
+    // the loans gathered here (for their lifetime and mutability errors)
+    // are never brought into effect.
+    let lexical_loans = this.nll_mir(id).is_none();
     for (loan_idx, loan) in all_loans.iter().enumerate() {
-        loan_dfcx.add_gen(loan.gen_scope.node_id(&tcx.region_maps), loan_idx);
+        if lexical_loans {
+            loan_dfcx.add_gen(loan.gen_scope.node_id(&tcx.region_maps), loan_idx);
+        }
         loan_dfcx.add_kill(KillFrom::ScopeEnd,
                            loan.kill_scope.node_id(&tcx.region_maps), loan_idx);
     }
@@ -601,6 +616,15 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
         self.free_region_map = old_free_region_map;
     }
 
+    /// The MIR of the fn `id`, if its loans are to be checked with
+    /// liveness-based regions (`-Z nll`) rather than lexical ones.
+    fn nll_mir(&self, id: ast::NodeId) -> Option<&'a Mir<'tcx>> {
+        if !self.tcx.sess.opts.debugging_opts.nll {
+            return None;
+        }
+        self.mir_map.and_then(|mir_map| mir_map.map.get(&id))
+    }
+
     pub fn is_subregion_of(&self, r_sub: ty::Region, r_sup: ty::Region)
                            -> bool
     {
@@ -141,6 +141,9 @@ enum WitnessPreference {
 }
 
 impl<'a, 'tcx, 'v> Visitor<'v> for MatchCheckCtxt<'a, 'tcx> {
+    fn visit_body_id(&mut self, id: hir::BodyId) {
+        self.tcx.map.read_body(id);
+    }
     fn visit_expr(&mut self, ex: &hir::Expr) {
         check_expr(self, ex);
     }
@@ -1081,11 +1084,28 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
         })
     }
 
+    // With `bindings_after_at`, `x @ Foo(y)` is legal as long as `y` is a
+    // copy: it is read before `x` takes the value. By-ref sub-bindings
+    // are reported as mixing by-move and by-ref bindings below.
+    let bindings_after_at = tcx.sess.features.borrow().bindings_after_at;
+    let sub_binding_conflicts = |sub: &Pat| {
+        if !bindings_after_at {
+            return pat_contains_bindings(&def_map.borrow(), sub);
+        }
+        let mut conflicts = false;
+        pat_bindings(def_map, sub, |bm, id, span, _path| {
+            if let hir::BindByValue(_) = bm {
+                conflicts |= binding_moves_by_default(cx, id, span);
+            }
+        });
+        conflicts
+    };
+
     let check_move = |p: &Pat, sub: Option<&Pat>| {
         // check legality of moving out of the enum
 
         // x @ Foo(..) is legal, but x @ Foo(y) isn't.
-        if sub.map_or(false, |p| pat_contains_bindings(&def_map.borrow(), &p)) {
+        if sub.map_or(false, |p| sub_binding_conflicts(p)) {
             span_err!(cx.tcx.sess, p.span, E0007, "cannot bind by-move with sub-bindings");
         } else if has_guard {
             span_err!(cx.tcx.sess, p.span, E0008, "cannot bind by-move into a pattern guard");
@@ -1102,13 +1122,7 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
             if pat_is_binding(&def_map.borrow(), &p) {
                 match p.node {
                     PatKind::Ident(hir::BindByValue(_), _, ref sub) => {
-                        let pat_ty = tcx.node_id_to_type(p.id);
-                        //FIXME: (@jroesch) this code should be floated up as well
-                        let infcx = infer::new_infer_ctxt(cx.tcx,
-                                                          &cx.tcx.tables,
-                                                          Some(cx.param_env.clone()),
-                                                          ProjectionMode::AnyFinal);
-                        if infcx.type_moves_by_default(pat_ty, pat.span) {
+                        if binding_moves_by_default(cx, p.id, pat.span) {
                             check_move(p, sub.as_ref().map(|p| &**p));
                         }
                     }
@@ -1128,6 +1142,17 @@ fn check_legality_of_move_bindings(cx: &MatchCheckCtxt,
     }
 }
 
+/// Whether binding `id` by value moves the value rather than copying it.
+fn binding_moves_by_default(cx: &MatchCheckCtxt, id: NodeId, span: Span) -> bool {
+    let pat_ty = cx.tcx.node_id_to_type(id);
+    //FIXME: (@jroesch) this code should be floated up as well
+    let infcx = infer::new_infer_ctxt(cx.tcx,
+                                      &cx.tcx.tables,
+                                      Some(cx.param_env.clone()),
+                                      ProjectionMode::AnyFinal);
+    infcx.type_moves_by_default(pat_ty, span)
+}
+
 /// Ensures that a pattern guard doesn't borrow by mutable reference or
 /// assign.
 fn check_for_mutation_in_guard<'a, 'tcx>(cx: &'a MatchCheckCtxt<'a, 'tcx>,
@@ -1181,8 +1206,13 @@ impl<'a, 'tcx> Delegate<'tcx> for MutationChecker<'a, 'tcx> {
 
 /// Forbids bindings in `@` patterns. This is necessary for memory safety,
 /// because of the way rvalues are handled in the borrow check. (See issue
-/// #14587.)
+/// #14587.) With `bindings_after_at` they are allowed, and conflicts
+/// between them are reported by `check_legality_of_move_bindings` and the
+/// borrow checker instead.
 fn check_legality_of_bindings_in_at_patterns(cx: &MatchCheckCtxt, pat: &Pat) {
+    if cx.tcx.sess.features.borrow().bindings_after_at {
+        return;
+    }
     AtBindingPatternVisitor { cx: cx, bindings_allowed: true }.visit_pat(pat);
 }
 
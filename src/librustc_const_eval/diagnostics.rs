@@ -505,6 +505,10 @@ match Some("hi".to_string()) {
 
 The `op_string_ref` binding has type `&Option<&String>` in both cases.
 
+The unstable `bindings_after_at` feature lifts this restriction for
+sub-bindings that don't conflict with the binding before the `@`, such as
+copies of its fields.
+
 See also https://github.com/rust-lang/rust/issues/14587
 "##,
 
@@ -38,6 +38,7 @@ use syntax::codemap::Span;
 use syntax::ptr::P;
 use syntax::codemap;
 use syntax::attr::IntType;
+use syntax::abi::Abi;
 
 use std::borrow::Cow;
 use std::cmp::Ordering;
@@ -95,6 +96,10 @@ pub fn lookup_const_by_id<'a, 'tcx: 'a>(tcx: &'a TyCtxt<'tcx>,
                                         substs: Option<subst::Substs<'tcx>>)
                                         -> Option<(&'tcx Expr, Option<ty::Ty<'tcx>>)> {
     if let Some(node_id) = tcx.map.as_local_node_id(def_id) {
+        // The initializer is evaluated by the caller.
+        if let Some(body_id) = tcx.map.maybe_body_owned_by(node_id) {
+            tcx.map.read_body(body_id);
+        }
         match tcx.map.find(node_id) {
             None => None,
             Some(ast_map::NodeItem(it)) => match it.node {
@@ -205,10 +210,64 @@ fn inline_const_fn_from_external_crate(tcx: &TyCtxt, def_id: DefId)
     fn_id
 }
 
+/// True if `def_id` is the `variant_count` intrinsic, the one intrinsic
+/// that may be called in constant expressions.
+pub fn is_variant_count_intrinsic(tcx: &TyCtxt, def_id: DefId) -> bool {
+    match tcx.lookup_item_type(def_id).ty.sty {
+        ty::TyFnDef(_, _, f) if f.abi == Abi::RustIntrinsic => {
+            tcx.item_name(def_id).as_str() == "variant_count"
+        }
+        _ => false
+    }
+}
+
+/// The number of variants of the type `variant_count` is called with,
+/// or `None` if that type is still generic or not known yet.
+///
+/// Array lengths in signatures are evaluated before typeck, so when
+/// there are no substitutions recorded for the callee yet this falls
+/// back to resolving the explicit type argument on its path. Only the
+/// definition of the type matters, not its own type arguments; types
+/// that are not named by a path to their definition are not known yet.
+fn variant_count_of_callee(tcx: &TyCtxt, callee: &hir::Expr) -> Option<usize> {
+    let substs = tcx.tables.borrow().item_substs.get(&callee.id).map(|s| s.substs.clone());
+    if let Some(substs) = substs {
+        let ty = *substs.types.get(subst::FnSpace, 0);
+        return match ty.sty {
+            ty::TyEnum(adt, _) => Some(adt.variants.len()),
+            ty::TyParam(..) | ty::TyProjection(..) | ty::TyInfer(..) => None,
+            _ => Some(0),
+        };
+    }
+
+    let path = match callee.node {
+        hir::ExprPath(None, ref path) => path,
+        _ => return None,
+    };
+    let ty = match path.segments.last().map(|segment| &segment.parameters) {
+        Some(&hir::AngleBracketedParameters(ref data)) if data.types.len() == 1 => &data.types[0],
+        _ => return None,
+    };
+    match ty.node {
+        hir::TyPath(..) => {
+            match tcx.def_map.borrow().get(&ty.id).map(|d| d.full_def()) {
+                Some(Def::Enum(did)) => Some(tcx.lookup_adt_def(did).variants.len()),
+                Some(Def::Struct(..)) | Some(Def::PrimTy(..)) => Some(0),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 pub fn lookup_const_fn_by_id<'tcx>(tcx: &TyCtxt<'tcx>, def_id: DefId)
                                    -> Option<FnLikeNode<'tcx>>
 {
     let fn_id = if let Some(node_id) = tcx.map.as_local_node_id(def_id) {
+        // The body is evaluated by the caller.
+        if let Some(body_id) = tcx.map.maybe_body_owned_by(node_id) {
+            tcx.map.read_body(body_id);
+        }
         node_id
     } else {
         if let Some(fn_id) = inline_const_fn_from_external_crate(tcx, def_id) {
@@ -411,6 +470,7 @@ pub enum ErrKind {
     Math(ConstMathErr),
 
     IntermediateUnsignedNegative,
+    FloatToIntOutOfRange(f64),
     /// Expected, Got
     TypeMismatch(String, ConstInt),
     BadType(ConstVal),
@@ -482,6 +542,11 @@ impl ConstEvalErr {
             },
             BadType(ref i) => format!("value of wrong type: {:?}", i).into_cow(),
             ErroneousReferencedConstant(_) => "could not evaluate referenced constant".into_cow(),
+            FloatToIntOutOfRange(f) => {
+                format!("casting `{}` to an integer type that cannot represent it \
+                         without saturation; the result would depend on the target",
+                        f).into_cow()
+            }
         }
     }
 }
@@ -627,12 +692,16 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
         match (eval_const_expr_partial(tcx, &a, ty_hint, fn_args)?,
                eval_const_expr_partial(tcx, &b, b_ty, fn_args)?) {
           (Float(a), Float(b)) => {
+            let fty = match ety.map(|t| &t.sty) {
+                Some(&ty::TyFloat(fty)) => fty,
+                _ => ast::FloatTy::F64,
+            };
             match op.node {
-              hir::BiAdd => Float(a + b),
-              hir::BiSub => Float(a - b),
-              hir::BiMul => Float(a * b),
-              hir::BiDiv => Float(a / b),
-              hir::BiRem => Float(a % b),
+              hir::BiAdd => Float(float::add(a, b, fty)),
+              hir::BiSub => Float(float::sub(a, b, fty)),
+              hir::BiMul => Float(float::mul(a, b, fty)),
+              hir::BiDiv => Float(float::div(a, b, fty)),
+              hir::BiRem => Float(float::rem(a, b, fty)),
               hir::BiEq => Bool(a == b),
               hir::BiLt => Bool(a < b),
               hir::BiLe => Bool(a <= b),
@@ -793,6 +862,14 @@ pub fn eval_const_expr_partial<'tcx>(tcx: &TyCtxt<'tcx>,
               Struct(_) => signal!(e, UnimplementedConstVal("tuple struct constructors")),
               callee => signal!(e, CallOn(callee)),
           };
+          if is_variant_count_intrinsic(tcx, did) {
+              let count = match variant_count_of_callee(tcx, callee) {
+                  Some(count) => count,
+                  None => signal!(e, UnimplementedConstVal("`variant_count` of a generic or unknown type")),
+              };
+              let count = ConstUsize::new(count as u64, tcx.sess.target.uint_type).unwrap();
+              return Ok(Integral(Usize(count)));
+          }
           let (decl, result) = if let Some(fn_like) = lookup_const_fn_by_id(tcx, did) {
               (fn_like.decl(), &fn_like.body().expr)
           } else {
@@ -1104,12 +1181,39 @@ fn cast_const_int<'tcx>(tcx: &TyCtxt<'tcx>, val: ConstInt, ty: ty::Ty) -> CastRe
 }
 
 fn cast_const_float<'tcx>(tcx: &TyCtxt<'tcx>, f: f64, ty: ty::Ty) -> CastResult {
+    // Casts saturate at the bounds of the integer type, and NaN becomes
+    // zero, as in trans. Under `-Z unsaturated-float-casts` the runtime
+    // result is platform-dependent, so there is no value to fold to.
+    let in_range = |signed: bool, bits: u64| {
+        let bits = bits as i32;
+        let t = f.trunc();
+        if signed {
+            t >= -(2f64.powi(bits - 1)) && t < 2f64.powi(bits - 1)
+        } else {
+            t > -1.0 && t < 2f64.powi(bits)
+        }
+    };
+    let saturate = |val: ConstInt| {
+        if tcx.sess.opts.debugging_opts.unsaturated_float_casts {
+            return Err(FloatToIntOutOfRange(f));
+        }
+        cast_const_int(tcx, if f.is_nan() { Infer(0) } else { val }, ty)
+    };
+    let target = &tcx.sess.target;
     match ty.sty {
+        ty::TyInt(ity) if !in_range(true, overflow::int_ty_bits(ity, target.int_type)) => {
+            let (min, max) = overflow::int_ty_range(ity, target.int_type);
+            saturate(if f < 0.0 { InferSigned(min) } else { Infer(max as u64) })
+        }
+        ty::TyUint(uty) if !in_range(false, overflow::uint_ty_bits(uty, target.uint_type)) => {
+            let (min, max) = overflow::uint_ty_range(uty, target.uint_type);
+            saturate(Infer(if f < 0.0 { min } else { max }))
+        }
         ty::TyInt(_) if f >= 0.0 => cast_const_int(tcx, Infer(f as u64), ty),
         ty::TyInt(_) => cast_const_int(tcx, InferSigned(f as i64), ty),
-        ty::TyUint(_) if f >= 0.0 => cast_const_int(tcx, Infer(f as u64), ty),
+        ty::TyUint(_) => cast_const_int(tcx, Infer(f as u64), ty),
         ty::TyFloat(ast::FloatTy::F64) => Ok(Float(f)),
-        ty::TyFloat(ast::FloatTy::F32) => Ok(Float(f as f32 as f64)),
+        ty::TyFloat(ast::FloatTy::F32) => Ok(Float(float::convert(f, ast::FloatTy::F32))),
         _ => Err(CannotCast),
     }
 }
@@ -1163,7 +1267,22 @@ fn lit_to_const<'tcx>(lit: &ast::LitKind,
 
         LitKind::Float(ref n, _) |
         LitKind::FloatUnsuffixed(ref n) => {
-            if let Ok(x) = n.parse::<f64>() {
+            // An `f32` literal is parsed as one directly; going through
+            // `f64` would round twice.
+            let is_f32 = match *lit {
+                LitKind::Float(_, FloatTy::F32) => true,
+                LitKind::FloatUnsuffixed(_) => match ty_hint.map(|t| &t.sty) {
+                    Some(&ty::TyFloat(FloatTy::F32)) => true,
+                    _ => false,
+                },
+                _ => false,
+            };
+            let parsed = if is_f32 {
+                n.parse::<f32>().map(|x| x as f64)
+            } else {
+                n.parse::<f64>()
+            };
+            if let Ok(x) = parsed {
                 Ok(Float(x))
             } else {
                 // FIXME(#31407) this is only necessary because float parsing is buggy
@@ -1178,16 +1297,8 @@ fn lit_to_const<'tcx>(lit: &ast::LitKind,
 pub fn compare_const_vals(a: &ConstVal, b: &ConstVal) -> Option<Ordering> {
     match (a, b) {
         (&Integral(a), &Integral(b)) => a.try_cmp(b).ok(),
-        (&Float(a), &Float(b)) => {
-            // This is pretty bad but it is the existing behavior.
-            Some(if a == b {
-                Ordering::Equal
-            } else if a < b {
-                Ordering::Less
-            } else {
-                Ordering::Greater
-            })
-        }
+        // NaN is not ordered with anything, itself included.
+        (&Float(a), &Float(b)) => a.partial_cmp(&b),
         (&Str(ref a), &Str(ref b)) => Some(a.cmp(b)),
         (&Bool(a), &Bool(b)) => Some(a.cmp(&b)),
         (&ByteStr(ref a), &ByteStr(ref b)) => Some(a.cmp(b)),
@@ -0,0 +1,246 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Target-independent floating point arithmetic for constant evaluation.
+//!
+//! Constants are folded in software rather than with the host's float
+//! instructions, so that a constant has the same value whichever machine
+//! compiled it; an x87 host, for instance, would otherwise compute in
+//! extended precision and round twice. Values of both float types are
+//! carried around as `f64`, since every `f32` is exactly representable as
+//! one. Each operation is rounded once, to the nearest value of the
+//! result type with ties to even, which is what IEEE 754 and the target
+//! do at runtime.
+//!
+//! Operations whose result is exact -- anything involving a zero, an
+//! infinity or a NaN -- are left to the host. NaN results are replaced
+//! by a single canonical NaN, as their payload is not portable.
+
+use std::cmp;
+use std::f64;
+use std::mem;
+
+use syntax::ast::FloatTy;
+
+#[derive(Copy, Clone)]
+struct Format {
+    /// Significand bits, including the implicit leading one.
+    precision: i32,
+    /// Exponents of the smallest and largest normal numbers.
+    min_exp: i32,
+    max_exp: i32,
+}
+
+fn format(ty: FloatTy) -> Format {
+    match ty {
+        FloatTy::F32 => Format { precision: 24, min_exp: -126, max_exp: 127 },
+        FloatTy::F64 => Format { precision: 53, min_exp: -1022, max_exp: 1023 },
+    }
+}
+
+pub fn add(a: f64, b: f64, ty: FloatTy) -> f64 {
+    if is_special(a) || is_special(b) {
+        return canonical(a + b);
+    }
+    let (neg_a, m_a, e_a) = unpack(a);
+    let (neg_b, m_b, e_b) = unpack(b);
+    let ((neg_a, m_a, e_a), (neg_b, m_b, e_b)) = if e_a >= e_b {
+        ((neg_a, m_a, e_a), (neg_b, m_b, e_b))
+    } else {
+        ((neg_b, m_b, e_b), (neg_a, m_a, e_a))
+    };
+
+    // Give both significands some guard bits and align the smaller
+    // operand, keeping track of whether any of its bits were lost.
+    let (m_a, m_b, e) = (m_a << 10, m_b << 10, e_a - 10);
+    let shift = (e_a - e_b) as u32;
+    let (m_b, sticky) = if shift >= 64 {
+        (0, true)
+    } else {
+        (m_b >> shift, m_b & ((1 << shift) - 1) != 0)
+    };
+
+    if neg_a == neg_b {
+        round(neg_a, m_a + m_b, e, sticky, format(ty))
+    } else if m_a == m_b && !sticky {
+        // An exact cancellation is +0 when rounding to nearest, whatever
+        // the signs of the operands were.
+        0.0
+    } else if m_a > m_b {
+        // The lost bits of `b` are subtracted too, so borrow one from
+        // the kept ones.
+        round(neg_a, m_a - m_b - sticky as u64, e, sticky, format(ty))
+    } else {
+        // Both operands had the same exponent, so nothing was lost.
+        round(neg_b, m_b - m_a, e, false, format(ty))
+    }
+}
+
+pub fn sub(a: f64, b: f64, ty: FloatTy) -> f64 {
+    add(a, -b, ty)
+}
+
+pub fn mul(a: f64, b: f64, ty: FloatTy) -> f64 {
+    if is_special(a) || is_special(b) {
+        return canonical(a * b);
+    }
+    let (neg_a, m_a, e_a) = unpack(a);
+    let (neg_b, m_b, e_b) = unpack(b);
+
+    // The product has at most 106 bits; keep the top 64.
+    let (hi, lo) = mul_wide(m_a, m_b);
+    let m = hi << 22 | lo >> 42;
+    let sticky = lo & ((1 << 42) - 1) != 0;
+    round(neg_a != neg_b, m, e_a + e_b + 42, sticky, format(ty))
+}
+
+pub fn div(a: f64, b: f64, ty: FloatTy) -> f64 {
+    if is_special(a) || is_special(b) {
+        return canonical(a / b);
+    }
+    let (neg_a, m_a, e_a) = unpack(a);
+    let (neg_b, m_b, e_b) = unpack(b);
+
+    // Long division, one quotient bit at a time. The first bit has
+    // weight 1, as both significands are normalized.
+    let mut rem = m_a;
+    let mut quot = 0u64;
+    for _ in 0..64 {
+        quot <<= 1;
+        if rem >= m_b {
+            rem -= m_b;
+            quot |= 1;
+        }
+        rem <<= 1;
+    }
+    round(neg_a != neg_b, quot, e_a - e_b - 63, rem != 0, format(ty))
+}
+
+/// The remainder of truncating division, with the sign of `a`. Like
+/// `fmod` in C, this is always exact.
+pub fn rem(a: f64, b: f64, ty: FloatTy) -> f64 {
+    if is_special(a) || is_special(b) {
+        return canonical(a % b);
+    }
+    let (neg_a, m_a, e_a) = unpack(a);
+    let (_, m_b, e_b) = unpack(b);
+    if e_a < e_b || (e_a == e_b && m_a < m_b) {
+        return a;
+    }
+
+    let mut rem = m_a % m_b;
+    for _ in 0..(e_a - e_b) {
+        rem = (rem << 1) % m_b;
+    }
+    round(neg_a, rem, e_b, false, format(ty))
+}
+
+/// Rounds `a` to the nearest value of type `ty`, as a cast does.
+pub fn convert(a: f64, ty: FloatTy) -> f64 {
+    if is_special(a) {
+        return canonical(a);
+    }
+    let (neg, m, e) = unpack(a);
+    round(neg, m, e, false, format(ty))
+}
+
+fn is_special(a: f64) -> bool {
+    !a.is_finite() || a == 0.0
+}
+
+fn canonical(a: f64) -> f64 {
+    if a.is_nan() { f64::NAN } else { a }
+}
+
+/// Splits a finite, non-zero `a` into its sign and a significand and
+/// exponent such that `|a| = m * 2^e` with `2^52 <= m < 2^53`.
+fn unpack(a: f64) -> (bool, u64, i32) {
+    let bits: u64 = unsafe { mem::transmute(a) };
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (m, e) = if biased == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | 1 << 52, biased - 1075)
+    };
+    let shift = m.leading_zeros() - 11;
+    (bits >> 63 != 0, m << shift, e - shift as i32)
+}
+
+/// Builds the `f64` equal to `m * 2^e`, which must be representable.
+fn pack(neg: bool, mut m: u64, mut e: i32) -> f64 {
+    let sign = (neg as u64) << 63;
+    if m == 0 {
+        return unsafe { mem::transmute(sign) };
+    }
+    while m >= 1 << 53 {
+        m >>= 1;
+        e += 1;
+    }
+    while m < 1 << 52 && e > -1074 {
+        m <<= 1;
+        e -= 1;
+    }
+    let bits = if m >= 1 << 52 {
+        sign | ((e + 1075) as u64) << 52 | (m & ((1 << 52) - 1))
+    } else {
+        // Subnormal.
+        sign | m
+    };
+    unsafe { mem::transmute(bits) }
+}
+
+/// Rounds `m * 2^e` to the nearest value of `format`, ties to even.
+/// `sticky` says whether the exact value was a little larger than that,
+/// in which case `m * 2^e` must have more bits than `format` can hold.
+fn round(neg: bool, m: u64, e: i32, sticky: bool, format: Format) -> f64 {
+    if m == 0 {
+        return pack(neg, 0, 0);
+    }
+    let top = e + 63 - m.leading_zeros() as i32;
+    let lsb = cmp::max(top, format.min_exp) - (format.precision - 1);
+    let (m, e) = if lsb > e {
+        let shift = (lsb - e) as u32;
+        let (kept, half, rest) = if shift > 64 {
+            (0, false, true)
+        } else if shift == 64 {
+            (0, m >> 63 != 0, m << 1 != 0)
+        } else {
+            (m >> shift, (m >> (shift - 1)) & 1 != 0, m & ((1 << (shift - 1)) - 1) != 0)
+        };
+        let rest = rest || sticky;
+        if half && (rest || kept & 1 != 0) {
+            (kept + 1, lsb)
+        } else {
+            (kept, lsb)
+        }
+    } else {
+        (m, e)
+    };
+
+    if m != 0 && e + 63 - m.leading_zeros() as i32 > format.max_exp {
+        return if neg { f64::NEG_INFINITY } else { f64::INFINITY };
+    }
+    pack(neg, m, e)
+}
+
+/// The full 128-bit product of `a` and `b`, as high and low halves.
+fn mul_wide(a: u64, b: u64) -> (u64, u64) {
+    let (a_hi, a_lo) = (a >> 32, a & 0xffff_ffff);
+    let (b_hi, b_lo) = (b >> 32, b & 0xffff_ffff);
+    let lo_lo = a_lo * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_lo = a_hi * b_lo;
+    let hi_hi = a_hi * b_hi;
+    let mid = (lo_lo >> 32) + (lo_hi & 0xffff_ffff) + (hi_lo & 0xffff_ffff);
+    let lo = mid << 32 | (lo_lo & 0xffff_ffff);
+    let hi = hi_hi + (lo_hi >> 32) + (hi_lo >> 32) + (mid >> 32);
+    (hi, lo)
+}
@@ -311,38 +311,63 @@ impl ::std::fmt::Display for ConstInt {
     }
 }
 
-macro_rules! overflowing {
-    ($e:expr, $err:expr) => {{
-        if $e.1 {
-            return Err(Overflow($err));
-        } else {
-            $e.0
+/// `lhs op rhs` for `+`, `-`, `*`, `/` and `%`, under the rules of
+/// `overflow`.
+fn arith(op: Op, lhs: ConstInt, rhs: ConstInt) -> Result<ConstInt, ConstMathErr> {
+    use overflow::{signed_binop, unsigned_binop};
+    match (lhs, rhs) {
+        (I8(a), I8(b)) => signed_binop(op, a as i64, b as i64, 8).map(|r| I8(r as i8)),
+        (I16(a), I16(b)) => signed_binop(op, a as i64, b as i64, 16).map(|r| I16(r as i16)),
+        (I32(a), I32(b)) => signed_binop(op, a as i64, b as i64, 32).map(|r| I32(r as i32)),
+        (I64(a), I64(b)) => signed_binop(op, a, b, 64).map(I64),
+        (Isize(Is32(a)), Isize(Is32(b))) => {
+            signed_binop(op, a as i64, b as i64, 32).map(|r| Isize(Is32(r as i32)))
+        }
+        (Isize(Is64(a)), Isize(Is64(b))) => signed_binop(op, a, b, 64).map(|r| Isize(Is64(r))),
+        (InferSigned(a), InferSigned(b)) => signed_binop(op, a, b, 64).map(InferSigned),
+        (U8(a), U8(b)) => unsigned_binop(op, a as u64, b as u64, 8).map(|r| U8(r as u8)),
+        (U16(a), U16(b)) => unsigned_binop(op, a as u64, b as u64, 16).map(|r| U16(r as u16)),
+        (U32(a), U32(b)) => unsigned_binop(op, a as u64, b as u64, 32).map(|r| U32(r as u32)),
+        (U64(a), U64(b)) => unsigned_binop(op, a, b, 64).map(U64),
+        (Usize(Us32(a)), Usize(Us32(b))) => {
+            unsigned_binop(op, a as u64, b as u64, 32).map(|r| Usize(Us32(r as u32)))
         }
-    }}
+        (Usize(Us64(a)), Usize(Us64(b))) => {
+            unsigned_binop(op, a, b, 64).map(|r| Usize(Us64(r)))
+        }
+        (Infer(a), Infer(b)) => unsigned_binop(op, a, b, 64).map(Infer),
+        _ => return Err(UnequalTypes(op)),
+    }.ok_or(Overflow(op))
+}
+
+/// `lhs << b` or `lhs >> b`, under the rules of `overflow`.
+fn shift(op: Op, lhs: ConstInt, b: u32) -> Result<ConstInt, ConstMathErr> {
+    use overflow::{signed_shift, unsigned_shift};
+    match lhs {
+        I8(a) => signed_shift(op, a as i64, b, 8).map(|r| I8(r as i8)),
+        I16(a) => signed_shift(op, a as i64, b, 16).map(|r| I16(r as i16)),
+        I32(a) => signed_shift(op, a as i64, b, 32).map(|r| I32(r as i32)),
+        I64(a) => signed_shift(op, a, b, 64).map(I64),
+        Isize(Is32(a)) => signed_shift(op, a as i64, b, 32).map(|r| Isize(Is32(r as i32))),
+        Isize(Is64(a)) => signed_shift(op, a, b, 64).map(|r| Isize(Is64(r))),
+        InferSigned(a) => signed_shift(op, a, b, 64).map(InferSigned),
+        U8(a) => unsigned_shift(op, a as u64, b, 8).map(|r| U8(r as u8)),
+        U16(a) => unsigned_shift(op, a as u64, b, 16).map(|r| U16(r as u16)),
+        U32(a) => unsigned_shift(op, a as u64, b, 32).map(|r| U32(r as u32)),
+        U64(a) => unsigned_shift(op, a, b, 64).map(U64),
+        Usize(Us32(a)) => unsigned_shift(op, a as u64, b, 32).map(|r| Usize(Us32(r as u32))),
+        Usize(Us64(a)) => unsigned_shift(op, a, b, 64).map(|r| Usize(Us64(r))),
+        Infer(a) => unsigned_shift(op, a, b, 64).map(Infer),
+    }.ok_or(Overflow(op))
 }
 
 macro_rules! impl_binop {
-    ($op:ident, $func:ident, $checked_func:ident) => {
+    ($op:ident, $func:ident) => {
         impl ::std::ops::$op for ConstInt {
             type Output = Result<Self, ConstMathErr>;
             fn $func(self, rhs: Self) -> Result<Self, ConstMathErr> {
-                match self.infer(rhs)? {
-                    (I8(a), I8(b)) => a.$checked_func(b).map(I8),
-                    (I16(a), I16(b)) => a.$checked_func(b).map(I16),
-                    (I32(a), I32(b)) => a.$checked_func(b).map(I32),
-                    (I64(a), I64(b)) => a.$checked_func(b).map(I64),
-                    (Isize(Is32(a)), Isize(Is32(b))) => a.$checked_func(b).map(Is32).map(Isize),
-                    (Isize(Is64(a)), Isize(Is64(b))) => a.$checked_func(b).map(Is64).map(Isize),
-                    (U8(a), U8(b)) => a.$checked_func(b).map(U8),
-                    (U16(a), U16(b)) => a.$checked_func(b).map(U16),
-                    (U32(a), U32(b)) => a.$checked_func(b).map(U32),
-                    (U64(a), U64(b)) => a.$checked_func(b).map(U64),
-                    (Usize(Us32(a)), Usize(Us32(b))) => a.$checked_func(b).map(Us32).map(Usize),
-                    (Usize(Us64(a)), Usize(Us64(b))) => a.$checked_func(b).map(Us64).map(Usize),
-                    (Infer(a), Infer(b)) => a.$checked_func(b).map(Infer),
-                    (InferSigned(a), InferSigned(b)) => a.$checked_func(b).map(InferSigned),
-                    _ => return Err(UnequalTypes(Op::$op)),
-                }.ok_or(Overflow(Op::$op))
+                let (lhs, rhs) = self.infer(rhs)?;
+                arith(Op::$op, lhs, rhs)
             }
         }
     }
@@ -375,44 +400,20 @@ macro_rules! derive_binop {
     }
 }
 
-impl_binop!(Add, add, checked_add);
-impl_binop!(Sub, sub, checked_sub);
-impl_binop!(Mul, mul, checked_mul);
+impl_binop!(Add, add);
+impl_binop!(Sub, sub);
+impl_binop!(Mul, mul);
 derive_binop!(BitAnd, bitand);
 derive_binop!(BitOr, bitor);
 derive_binop!(BitXor, bitxor);
 
-fn check_division(
-    lhs: ConstInt,
-    rhs: ConstInt,
-    op: Op,
-    zerr: ConstMathErr,
-) -> Result<(), ConstMathErr> {
-    match (lhs, rhs) {
-        (I8(_), I8(0)) => Err(zerr),
-        (I16(_), I16(0)) => Err(zerr),
-        (I32(_), I32(0)) => Err(zerr),
-        (I64(_), I64(0)) => Err(zerr),
-        (Isize(_), Isize(Is32(0))) => Err(zerr),
-        (Isize(_), Isize(Is64(0))) => Err(zerr),
-        (InferSigned(_), InferSigned(0)) => Err(zerr),
-
-        (U8(_), U8(0)) => Err(zerr),
-        (U16(_), U16(0)) => Err(zerr),
-        (U32(_), U32(0)) => Err(zerr),
-        (U64(_), U64(0)) => Err(zerr),
-        (Usize(_), Usize(Us32(0))) => Err(zerr),
-        (Usize(_), Usize(Us64(0))) => Err(zerr),
-        (Infer(_), Infer(0)) => Err(zerr),
-
-        (I8(::std::i8::MIN), I8(-1)) => Err(Overflow(op)),
-        (I16(::std::i16::MIN), I16(-1)) => Err(Overflow(op)),
-        (I32(::std::i32::MIN), I32(-1)) => Err(Overflow(op)),
-        (I64(::std::i64::MIN), I64(-1)) => Err(Overflow(op)),
-        (Isize(Is32(::std::i32::MIN)), Isize(Is32(-1))) => Err(Overflow(op)),
-        (Isize(Is64(::std::i64::MIN)), Isize(Is64(-1))) => Err(Overflow(op)),
-        (InferSigned(::std::i64::MIN), InferSigned(-1)) => Err(Overflow(op)),
-
+/// A zero divisor gets its own error rather than the generic overflow
+/// `arith` would report.
+fn check_division(rhs: ConstInt, zerr: ConstMathErr) -> Result<(), ConstMathErr> {
+    match rhs {
+        I8(0) | I16(0) | I32(0) | I64(0) | Isize(Is32(0)) | Isize(Is64(0)) |
+        InferSigned(0) | U8(0) | U16(0) | U32(0) | U64(0) | Usize(Us32(0)) |
+        Usize(Us64(0)) | Infer(0) => Err(zerr),
         _ => Ok(()),
     }
 }
@@ -421,26 +422,8 @@ impl ::std::ops::Div for ConstInt {
     type Output = Result<Self, ConstMathErr>;
     fn div(self, rhs: Self) -> Result<Self, ConstMathErr> {
         let (lhs, rhs) = self.infer(rhs)?;
-        check_division(lhs, rhs, Op::Div, DivisionByZero)?;
-        match (lhs, rhs) {
-            (I8(a), I8(b)) => Ok(I8(a/b)),
-            (I16(a), I16(b)) => Ok(I16(a/b)),
-            (I32(a), I32(b)) => Ok(I32(a/b)),
-            (I64(a), I64(b)) => Ok(I64(a/b)),
-            (Isize(Is32(a)), Isize(Is32(b))) => Ok(Isize(Is32(a/b))),
-            (Isize(Is64(a)), Isize(Is64(b))) => Ok(Isize(Is64(a/b))),
-            (InferSigned(a), InferSigned(b)) => Ok(InferSigned(a/b)),
-
-            (U8(a), U8(b)) => Ok(U8(a/b)),
-            (U16(a), U16(b)) => Ok(U16(a/b)),
-            (U32(a), U32(b)) => Ok(U32(a/b)),
-            (U64(a), U64(b)) => Ok(U64(a/b)),
-            (Usize(Us32(a)), Usize(Us32(b))) => Ok(Usize(Us32(a/b))),
-            (Usize(Us64(a)), Usize(Us64(b))) => Ok(Usize(Us64(a/b))),
-            (Infer(a), Infer(b)) => Ok(Infer(a/b)),
-
-            _ => Err(UnequalTypes(Op::Div)),
-        }
+        check_division(rhs, DivisionByZero)?;
+        arith(Op::Div, lhs, rhs)
     }
 }
 
@@ -448,27 +431,8 @@ impl ::std::ops::Rem for ConstInt {
     type Output = Result<Self, ConstMathErr>;
     fn rem(self, rhs: Self) -> Result<Self, ConstMathErr> {
         let (lhs, rhs) = self.infer(rhs)?;
-        // should INT_MIN%-1 be zero or an error?
-        check_division(lhs, rhs, Op::Rem, RemainderByZero)?;
-        match (lhs, rhs) {
-            (I8(a), I8(b)) => Ok(I8(a%b)),
-            (I16(a), I16(b)) => Ok(I16(a%b)),
-            (I32(a), I32(b)) => Ok(I32(a%b)),
-            (I64(a), I64(b)) => Ok(I64(a%b)),
-            (Isize(Is32(a)), Isize(Is32(b))) => Ok(Isize(Is32(a%b))),
-            (Isize(Is64(a)), Isize(Is64(b))) => Ok(Isize(Is64(a%b))),
-            (InferSigned(a), InferSigned(b)) => Ok(InferSigned(a%b)),
-
-            (U8(a), U8(b)) => Ok(U8(a%b)),
-            (U16(a), U16(b)) => Ok(U16(a%b)),
-            (U32(a), U32(b)) => Ok(U32(a%b)),
-            (U64(a), U64(b)) => Ok(U64(a%b)),
-            (Usize(Us32(a)), Usize(Us32(b))) => Ok(Usize(Us32(a%b))),
-            (Usize(Us64(a)), Usize(Us64(b))) => Ok(Usize(Us64(a%b))),
-            (Infer(a), Infer(b)) => Ok(Infer(a%b)),
-
-            _ => Err(UnequalTypes(Op::Rem)),
-        }
+        check_division(rhs, RemainderByZero)?;
+        arith(Op::Rem, lhs, rhs)
     }
 }
 
@@ -476,22 +440,7 @@ impl ::std::ops::Shl<ConstInt> for ConstInt {
     type Output = Result<Self, ConstMathErr>;
     fn shl(self, rhs: Self) -> Result<Self, ConstMathErr> {
         let b = rhs.to_u32().ok_or(ShiftNegative)?;
-        match self {
-            I8(a) => Ok(I8(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            I16(a) => Ok(I16(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            I32(a) => Ok(I32(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            I64(a) => Ok(I64(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            Isize(Is32(a)) => Ok(Isize(Is32(overflowing!(a.overflowing_shl(b), Op::Shl)))),
-            Isize(Is64(a)) => Ok(Isize(Is64(overflowing!(a.overflowing_shl(b), Op::Shl)))),
-            U8(a) => Ok(U8(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            U16(a) => Ok(U16(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            U32(a) => Ok(U32(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            U64(a) => Ok(U64(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            Usize(Us32(a)) => Ok(Usize(Us32(overflowing!(a.overflowing_shl(b), Op::Shl)))),
-            Usize(Us64(a)) => Ok(Usize(Us64(overflowing!(a.overflowing_shl(b), Op::Shl)))),
-            Infer(a) => Ok(Infer(overflowing!(a.overflowing_shl(b), Op::Shl))),
-            InferSigned(a) => Ok(InferSigned(overflowing!(a.overflowing_shl(b), Op::Shl))),
-        }
+        shift(Op::Shl, self, b)
     }
 }
 
@@ -499,50 +448,36 @@ impl ::std::ops::Shr<ConstInt> for ConstInt {
     type Output = Result<Self, ConstMathErr>;
     fn shr(self, rhs: Self) -> Result<Self, ConstMathErr> {
         let b = rhs.to_u32().ok_or(ShiftNegative)?;
-        match self {
-            I8(a) => Ok(I8(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            I16(a) => Ok(I16(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            I32(a) => Ok(I32(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            I64(a) => Ok(I64(overflowing!(a.overflowing_shr(b), Op::Shl))),
-            Isize(Is32(a)) => Ok(Isize(Is32(overflowing!(a.overflowing_shr(b), Op::Shr)))),
-            Isize(Is64(a)) => Ok(Isize(Is64(overflowing!(a.overflowing_shr(b), Op::Shr)))),
-            U8(a) => Ok(U8(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            U16(a) => Ok(U16(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            U32(a) => Ok(U32(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            U64(a) => Ok(U64(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            Usize(Us32(a)) => Ok(Usize(Us32(overflowing!(a.overflowing_shr(b), Op::Shr)))),
-            Usize(Us64(a)) => Ok(Usize(Us64(overflowing!(a.overflowing_shr(b), Op::Shr)))),
-            Infer(a) => Ok(Infer(overflowing!(a.overflowing_shr(b), Op::Shr))),
-            InferSigned(a) => Ok(InferSigned(overflowing!(a.overflowing_shr(b), Op::Shr))),
-        }
+        shift(Op::Shr, self, b)
     }
 }
 
 impl ::std::ops::Neg for ConstInt {
     type Output = Result<Self, ConstMathErr>;
     fn neg(self) -> Result<Self, ConstMathErr> {
+        use overflow::signed_neg;
         match self {
-            I8(a) => Ok(I8(overflowing!(a.overflowing_neg(), Op::Neg))),
-            I16(a) => Ok(I16(overflowing!(a.overflowing_neg(), Op::Neg))),
-            I32(a) => Ok(I32(overflowing!(a.overflowing_neg(), Op::Neg))),
-            I64(a) => Ok(I64(overflowing!(a.overflowing_neg(), Op::Neg))),
-            Isize(Is32(a)) => Ok(Isize(Is32(overflowing!(a.overflowing_neg(), Op::Neg)))),
-            Isize(Is64(a)) => Ok(Isize(Is64(overflowing!(a.overflowing_neg(), Op::Neg)))),
-            U8(0) => Ok(U8(0)),
-            U16(0) => Ok(U16(0)),
-            U32(0) => Ok(U32(0)),
-            U64(0) => Ok(U64(0)),
-            Usize(Us32(0)) => Ok(Usize(Us32(0))),
-            Usize(Us64(0)) => Ok(Usize(Us64(0))),
-            U8(_) => Err(UnsignedNegation),
-            U16(_) => Err(UnsignedNegation),
-            U32(_) => Err(UnsignedNegation),
-            U64(_) => Err(UnsignedNegation),
-            Usize(_) => Err(UnsignedNegation),
-            Infer(a @ 0...as_u64::I64MAX) => Ok(InferSigned(-(a as i64))),
-            Infer(_) => Err(Overflow(Op::Neg)),
-            InferSigned(a) => Ok(InferSigned(overflowing!(a.overflowing_neg(), Op::Neg))),
-        }
+            I8(a) => signed_neg(a as i64, 8).map(|r| I8(r as i8)),
+            I16(a) => signed_neg(a as i64, 16).map(|r| I16(r as i16)),
+            I32(a) => signed_neg(a as i64, 32).map(|r| I32(r as i32)),
+            I64(a) => signed_neg(a, 64).map(I64),
+            Isize(Is32(a)) => signed_neg(a as i64, 32).map(|r| Isize(Is32(r as i32))),
+            Isize(Is64(a)) => signed_neg(a, 64).map(|r| Isize(Is64(r))),
+            InferSigned(a) => signed_neg(a, 64).map(InferSigned),
+            U8(0) => Some(U8(0)),
+            U16(0) => Some(U16(0)),
+            U32(0) => Some(U32(0)),
+            U64(0) => Some(U64(0)),
+            Usize(Us32(0)) => Some(Usize(Us32(0))),
+            Usize(Us64(0)) => Some(Usize(Us64(0))),
+            U8(_) => return Err(UnsignedNegation),
+            U16(_) => return Err(UnsignedNegation),
+            U32(_) => return Err(UnsignedNegation),
+            U64(_) => return Err(UnsignedNegation),
+            Usize(_) => return Err(UnsignedNegation),
+            Infer(a @ 0...as_u64::I64MAX) => Some(InferSigned(-(a as i64))),
+            Infer(_) => None,
+        }.ok_or(Overflow(Op::Neg))
     }
 }
 
@@ -36,6 +36,8 @@ mod int;
 mod us;
 mod is;
 mod err;
+pub mod float;
+pub mod overflow;
 
 pub use int::*;
 pub use us::*;
@@ -0,0 +1,179 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Integer overflow semantics, shared by everything that does integer
+//! arithmetic on behalf of the program.
+//!
+//! Constant evaluation, the overflowing-literals lint, MIR building
+//! and trans all need to agree on the bounds of each integer type and
+//! on which operations are checked, or a value that is accepted in one
+//! place is rejected (or silently wraps) in another. They take those
+//! answers from here rather than each keeping its own table.
+//!
+//! The rules are:
+//!
+//! - Division and remainder by zero, and `MIN / -1` for signed types,
+//!   are always an error: at compile time in a constant, otherwise a
+//!   panic at runtime.
+//! - Everything else that overflows -- `+`, `-`, `*`, negation, and
+//!   shifts by at least the bit width -- is an error in a constant, and
+//!   at runtime panics when the mode is `Checked` and wraps when it is
+//!   `Wrapping`.
+//! - An integer literal must fit its type. `isize` and `usize` take the
+//!   bounds of the target, never of the host.
+
+use syntax::ast;
+
+use err::Op;
+
+/// What runtime arithmetic does on overflow.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// Overflow panics.
+    Checked,
+    /// Overflow wraps around in two's complement.
+    Wrapping,
+}
+
+impl OverflowMode {
+    /// The mode for a compilation with the given `-Z force-overflow-checks`
+    /// and `debug_assertions` settings; checks follow debug assertions
+    /// unless explicitly forced on or off.
+    pub fn new(force_overflow_checks: Option<bool>, debug_assertions: bool) -> OverflowMode {
+        if force_overflow_checks.unwrap_or(debug_assertions) {
+            OverflowMode::Checked
+        } else {
+            OverflowMode::Wrapping
+        }
+    }
+
+    /// Whether `+`, `-`, `*`, negation and shifts are checked at runtime.
+    pub fn checks_arith(self) -> bool {
+        self == OverflowMode::Checked
+    }
+
+    /// Whether division and remainder are checked at runtime, for a zero
+    /// divisor and for `MIN / -1`. They always are, whatever the mode.
+    pub fn checks_div(self) -> bool {
+        true
+    }
+}
+
+/// The width in bits of `int_ty`, resolving `isize` to `target_int_ty`,
+/// which must be a fixed-size type.
+pub fn int_ty_bits(int_ty: ast::IntTy, target_int_ty: ast::IntTy) -> u64 {
+    match int_ty {
+        ast::IntTy::Is => match target_int_ty {
+            ast::IntTy::Is => panic!("int_ty_bits: the target's `isize` cannot be `isize`"),
+            t => int_ty_bits(t, t),
+        },
+        ast::IntTy::I8 => 8,
+        ast::IntTy::I16 => 16,
+        ast::IntTy::I32 => 32,
+        ast::IntTy::I64 => 64,
+    }
+}
+
+/// The width in bits of `uint_ty`, resolving `usize` to `target_uint_ty`,
+/// which must be a fixed-size type.
+pub fn uint_ty_bits(uint_ty: ast::UintTy, target_uint_ty: ast::UintTy) -> u64 {
+    match uint_ty {
+        ast::UintTy::Us => match target_uint_ty {
+            ast::UintTy::Us => panic!("uint_ty_bits: the target's `usize` cannot be `usize`"),
+            t => uint_ty_bits(t, t),
+        },
+        ast::UintTy::U8 => 8,
+        ast::UintTy::U16 => 16,
+        ast::UintTy::U32 => 32,
+        ast::UintTy::U64 => 64,
+    }
+}
+
+/// The inclusive bounds of `int_ty`, resolving `isize` to `target_int_ty`.
+pub fn int_ty_range(int_ty: ast::IntTy, target_int_ty: ast::IntTy) -> (i64, i64) {
+    signed_range(int_ty_bits(int_ty, target_int_ty))
+}
+
+/// The inclusive bounds of `uint_ty`, resolving `usize` to `target_uint_ty`.
+pub fn uint_ty_range(uint_ty: ast::UintTy, target_uint_ty: ast::UintTy) -> (u64, u64) {
+    unsigned_range(uint_ty_bits(uint_ty, target_uint_ty))
+}
+
+fn signed_range(bits: u64) -> (i64, i64) {
+    (-1 << (bits - 1), (!0u64 >> (65 - bits)) as i64)
+}
+
+fn unsigned_range(bits: u64) -> (u64, u64) {
+    (0, !0 >> (64 - bits))
+}
+
+/// `a op b` in a signed type `bits` wide, with both operands already in
+/// range, or `None` when the rules above make it an error. Division and
+/// remainder by zero are reported as overflow here; callers that want a
+/// dedicated error check the divisor first.
+pub fn signed_binop(op: Op, a: i64, b: i64, bits: u64) -> Option<i64> {
+    let (min, max) = signed_range(bits);
+    let result = match op {
+        Op::Add => a.checked_add(b),
+        Op::Sub => a.checked_sub(b),
+        Op::Mul => a.checked_mul(b),
+        Op::Div | Op::Rem if b == 0 || (a == min && b == -1) => None,
+        Op::Div => Some(a / b),
+        Op::Rem => Some(a % b),
+        _ => panic!("signed_binop: {:?} is not an arithmetic operator", op),
+    };
+    result.and_then(|r| if min <= r && r <= max { Some(r) } else { None })
+}
+
+/// `a op b` in an unsigned type `bits` wide; see `signed_binop`.
+pub fn unsigned_binop(op: Op, a: u64, b: u64, bits: u64) -> Option<u64> {
+    let (_, max) = unsigned_range(bits);
+    let result = match op {
+        Op::Add => a.checked_add(b),
+        Op::Sub => a.checked_sub(b),
+        Op::Mul => a.checked_mul(b),
+        Op::Div => a.checked_div(b),
+        Op::Rem => a.checked_rem(b),
+        _ => panic!("unsigned_binop: {:?} is not an arithmetic operator", op),
+    };
+    result.and_then(|r| if r <= max { Some(r) } else { None })
+}
+
+/// `a << shift` or `a >> shift` in a signed type `bits` wide. Bits shifted
+/// out are lost, and only a shift by at least the bit width is an error.
+pub fn signed_shift(op: Op, a: i64, shift: u32, bits: u64) -> Option<i64> {
+    if shift as u64 >= bits {
+        return None;
+    }
+    match op {
+        // Shift in 64 bits, then sign-extend from the type's top bit.
+        Op::Shl => Some((a << shift) << (64 - bits) >> (64 - bits)),
+        Op::Shr => Some(a >> shift),
+        _ => panic!("signed_shift: {:?} is not a shift", op),
+    }
+}
+
+/// `a << shift` or `a >> shift` in an unsigned type `bits` wide; see
+/// `signed_shift`.
+pub fn unsigned_shift(op: Op, a: u64, shift: u32, bits: u64) -> Option<u64> {
+    if shift as u64 >= bits {
+        return None;
+    }
+    match op {
+        Op::Shl => Some((a << shift) & unsigned_range(bits).1),
+        Op::Shr => Some(a >> shift),
+        _ => panic!("unsigned_shift: {:?} is not a shift", op),
+    }
+}
+
+/// `-a` in a signed type `bits` wide, or `None` for `MIN`.
+pub fn signed_neg(a: i64, bits: u64) -> Option<i64> {
+    if a == signed_range(bits).0 { None } else { Some(-a) }
+}
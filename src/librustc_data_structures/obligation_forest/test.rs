@@ -234,3 +234,36 @@ fn backtrace() {
     assert_eq!(ok.len(), 0);
     assert!(err.is_empty());
 }
+
+#[test]
+fn stalled() {
+    // check that the forest only reports progress when some
+    // obligation succeeded, as `FulfillmentContext::select` stops
+    // iterating once nothing changes
+    let mut forest = ObligationForest::new();
+    forest.push_tree("A", "A");
+    forest.push_tree("B", "B");
+    let Outcome { completed: ok, errors: err, stalled } =
+        forest.process_obligations::<(), _>(|obligation, _, _| {
+            match *obligation {
+                "A" => Ok(Some(vec!["A.1"])),
+                "B" => Ok(None),
+                _ => unreachable!(),
+            }
+        });
+    assert!(ok.is_empty());
+    assert!(err.is_empty());
+    assert!(!stalled);
+
+    let Outcome { completed: ok, errors: err, stalled } =
+        forest.process_obligations::<(), _>(|obligation, _, _| {
+            match *obligation {
+                "A.1" | "B" => Ok(None),
+                _ => unreachable!(),
+            }
+        });
+    assert!(ok.is_empty());
+    assert!(err.is_empty());
+    assert!(stalled);
+    assert_eq!(forest.pending_obligations(), vec!["B", "A.1"]);
+}
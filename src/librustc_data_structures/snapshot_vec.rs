@@ -18,18 +18,22 @@
 //! ensure that any changes you make this with this pointer are rolled back, you must invoke
 //! `record` to record any changes you make and also supplying a delegate capable of reversing
 //! those changes.
+//!
+//! Several snapshottable structures can also share a single undo log, so that taking a snapshot
+//! of all of them is one operation. Such a structure keeps only its values (see
+//! `SnapshotVecStorage`), is given the log for the duration of each change via `with_log`, and
+//! implements `Rollback` so that whoever owns the log can reverse its entries.
+//!
+//! Committing a snapshot is O(1): the log only counts the open snapshots rather than marking where
+//! each one starts, and it is truncated once the outermost snapshot is committed, so snapshots
+//! committed within another one cost nothing.
 use self::UndoLog::*;
 
+use std::marker::PhantomData;
 use std::mem;
 use std::ops;
 
 pub enum UndoLog<D: SnapshotVecDelegate> {
-    /// Indicates where a snapshot started.
-    OpenSnapshot,
-
-    /// Indicates a snapshot that has been committed.
-    CommittedSnapshot,
-
     /// New variable with given index was created.
     NewElem(usize),
 
@@ -40,11 +44,17 @@ pub enum UndoLog<D: SnapshotVecDelegate> {
     Other(D::Undo),
 }
 
-pub struct SnapshotVec<D: SnapshotVecDelegate> {
-    values: Vec<D::Value>,
-    undo_log: Vec<UndoLog<D>>,
+pub struct SnapshotVec<D: SnapshotVecDelegate,
+                       V = Vec<<D as SnapshotVecDelegate>::Value>,
+                       L = VecLog<UndoLog<D>>> {
+    values: V,
+    undo_log: L,
+    marker: PhantomData<D>,
 }
 
+/// The values of a `SnapshotVec` whose changes are recorded into a log owned elsewhere.
+pub type SnapshotVecStorage<D> = SnapshotVec<D, Vec<<D as SnapshotVecDelegate>::Value>, ()>;
+
 // Snapshots are tokens that should be created/consumed linearly.
 pub struct Snapshot {
     // Length of the undo log at the time the snapshot was taken.
@@ -58,157 +68,252 @@ pub trait SnapshotVecDelegate {
     fn reverse(values: &mut Vec<Self::Value>, action: Self::Undo);
 }
 
-impl<D: SnapshotVecDelegate> SnapshotVec<D> {
-    pub fn new() -> SnapshotVec<D> {
-        SnapshotVec {
-            values: Vec::new(),
-            undo_log: Vec::new(),
+/// A log that undo entries of type `T` are recorded into.
+pub trait UndoLogs<T> {
+    /// Whether a snapshot is open, i.e. whether changes need to be recorded at all.
+    fn in_snapshot(&self) -> bool;
+
+    /// Records `undo`. Does nothing if no snapshot is open.
+    fn push(&mut self, undo: T);
+}
+
+/// An undo log of its own, as used by a standalone `SnapshotVec`.
+pub struct VecLog<T> {
+    log: Vec<T>,
+
+    // Nothing is recorded while this is zero: changes made outside of any snapshot can never be
+    // rolled back.
+    num_open_snapshots: usize,
+}
+
+impl<T> Default for VecLog<T> {
+    fn default() -> VecLog<T> {
+        VecLog {
+            log: Vec::new(),
+            num_open_snapshots: 0,
         }
     }
+}
 
+impl<T> UndoLogs<T> for VecLog<T> {
     fn in_snapshot(&self) -> bool {
-        !self.undo_log.is_empty()
+        self.num_open_snapshots > 0
     }
 
-    pub fn record(&mut self, action: D::Undo) {
-        if self.in_snapshot() {
-            self.undo_log.push(Other(action));
+    fn push(&mut self, undo: T) {
+        if self.num_open_snapshots > 0 {
+            self.log.push(undo);
         }
     }
+}
 
-    pub fn len(&self) -> usize {
-        self.values.len()
+impl<T> VecLog<T> {
+    pub fn start_snapshot(&mut self) -> Snapshot {
+        self.num_open_snapshots += 1;
+        Snapshot { length: self.log.len() }
     }
 
-    pub fn push(&mut self, elem: D::Value) -> usize {
-        let len = self.values.len();
-        self.values.push(elem);
+    pub fn actions_since_snapshot(&self, snapshot: &Snapshot) -> &[T] {
+        &self.log[snapshot.length..]
+    }
+
+    fn assert_open_snapshot(&self, snapshot: &Snapshot) {
+        // Or else there was a failure to follow a stack discipline:
+        assert!(self.num_open_snapshots > 0);
+        assert!(self.log.len() >= snapshot.length);
+    }
 
-        if self.in_snapshot() {
-            self.undo_log.push(NewElem(len));
+    /// Pops the entries recorded since `snapshot` was taken, handing each to `values` to reverse.
+    pub fn rollback_to<R: Rollback<T>>(&mut self, values: &mut R, snapshot: Snapshot) {
+        debug!("rollback_to({})", snapshot.length);
+
+        self.assert_open_snapshot(&snapshot);
+
+        while self.log.len() > snapshot.length {
+            values.reverse(self.log.pop().unwrap());
         }
 
-        len
+        self.num_open_snapshots -= 1;
+    }
+
+    /// Commits all changes since the last snapshot. Of course, they can still be undone if there
+    /// is a snapshot further out, so the log is only cleared once the outermost one is committed.
+    pub fn commit(&mut self, snapshot: Snapshot) {
+        debug!("commit({})", snapshot.length);
+
+        self.assert_open_snapshot(&snapshot);
+
+        if self.num_open_snapshots == 1 {
+            // The root snapshot.
+            assert!(snapshot.length == 0);
+            self.log.clear();
+        }
+
+        self.num_open_snapshots -= 1;
+    }
+}
+
+impl<'a, T, L: UndoLogs<T>> UndoLogs<T> for &'a mut L {
+    fn in_snapshot(&self) -> bool {
+        (**self).in_snapshot()
+    }
+
+    fn push(&mut self, undo: T) {
+        (**self).push(undo)
+    }
+}
+
+/// Implemented by structures whose changes are recorded into a shared log, to reverse an entry
+/// once the owner of the log pops it while rolling back a snapshot.
+pub trait Rollback<U> {
+    fn reverse(&mut self, undo: U);
+}
+
+impl<D: SnapshotVecDelegate, L: Default> SnapshotVec<D, Vec<D::Value>, L> {
+    pub fn new() -> SnapshotVec<D, Vec<D::Value>, L> {
+        SnapshotVec {
+            values: Vec::new(),
+            undo_log: L::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D: SnapshotVecDelegate> SnapshotVecStorage<D> {
+    /// Borrows the values together with `undo_log`, into which the changes made through the
+    /// result are recorded.
+    pub fn with_log<'a, L>(&'a mut self, undo_log: L) -> SnapshotVec<D, &'a mut Vec<D::Value>, L>
+        where L: UndoLogs<UndoLog<D>>
+    {
+        SnapshotVec {
+            values: &mut self.values,
+            undo_log: undo_log,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D: SnapshotVecDelegate, V: AsRef<Vec<D::Value>>, L> SnapshotVec<D, V, L> {
+    pub fn len(&self) -> usize {
+        self.values.as_ref().len()
     }
 
     pub fn get(&self, index: usize) -> &D::Value {
-        &self.values[index]
+        &self.values.as_ref()[index]
     }
+}
 
+impl<D: SnapshotVecDelegate, V: AsMut<Vec<D::Value>>, L> SnapshotVec<D, V, L> {
     /// Returns a mutable pointer into the vec; whatever changes you make here cannot be undone
     /// automatically, so you should be sure call `record()` with some sort of suitable undo
     /// action.
     pub fn get_mut(&mut self, index: usize) -> &mut D::Value {
-        &mut self.values[index]
+        &mut self.values.as_mut()[index]
+    }
+}
+
+impl<D, V, L> SnapshotVec<D, V, L>
+    where D: SnapshotVecDelegate,
+          V: AsMut<Vec<D::Value>>,
+          L: UndoLogs<UndoLog<D>>
+{
+    pub fn record(&mut self, action: D::Undo) {
+        self.undo_log.push(Other(action));
+    }
+
+    pub fn push(&mut self, elem: D::Value) -> usize {
+        let values = self.values.as_mut();
+        let len = values.len();
+        values.push(elem);
+        self.undo_log.push(NewElem(len));
+        len
     }
 
     /// Updates the element at the given index. The old value will saved (and perhaps restored) if
     /// a snapshot is active.
     pub fn set(&mut self, index: usize, new_elem: D::Value) {
-        let old_elem = mem::replace(&mut self.values[index], new_elem);
-        if self.in_snapshot() {
+        let old_elem = mem::replace(&mut self.values.as_mut()[index], new_elem);
+        if self.undo_log.in_snapshot() {
             self.undo_log.push(SetElem(index, old_elem));
         }
     }
+}
 
-    pub fn start_snapshot(&mut self) -> Snapshot {
-        let length = self.undo_log.len();
-        self.undo_log.push(OpenSnapshot);
-        Snapshot { length: length }
-    }
+impl<D: SnapshotVecDelegate, V: AsMut<Vec<D::Value>>, L> Rollback<UndoLog<D>>
+    for SnapshotVec<D, V, L>
+{
+    fn reverse(&mut self, undo: UndoLog<D>) {
+        let values = self.values.as_mut();
+        match undo {
+            NewElem(i) => {
+                values.pop();
+                assert!(values.len() == i);
+            }
 
-    pub fn actions_since_snapshot(&self, snapshot: &Snapshot) -> &[UndoLog<D>] {
-        &self.undo_log[snapshot.length..]
+            SetElem(i, v) => {
+                values[i] = v;
+            }
+
+            Other(u) => {
+                D::reverse(values, u);
+            }
+        }
     }
+}
 
-    fn assert_open_snapshot(&self, snapshot: &Snapshot) {
-        // Or else there was a failure to follow a stack discipline:
-        assert!(self.undo_log.len() > snapshot.length);
+impl<D: SnapshotVecDelegate> SnapshotVec<D> {
+    pub fn start_snapshot(&mut self) -> Snapshot {
+        self.undo_log.start_snapshot()
+    }
 
-        // Invariant established by start_snapshot():
-        assert!(match self.undo_log[snapshot.length] {
-            OpenSnapshot => true,
-            _ => false,
-        });
+    pub fn actions_since_snapshot(&self, snapshot: &Snapshot) -> &[UndoLog<D>] {
+        self.undo_log.actions_since_snapshot(snapshot)
     }
 
     pub fn rollback_to(&mut self, snapshot: Snapshot) {
-        debug!("rollback_to({})", snapshot.length);
-
-        self.assert_open_snapshot(&snapshot);
-
-        while self.undo_log.len() > snapshot.length + 1 {
-            match self.undo_log.pop().unwrap() {
-                OpenSnapshot => {
-                    // This indicates a failure to obey the stack discipline.
-                    panic!("Cannot rollback an uncommitted snapshot");
-                }
-
-                CommittedSnapshot => {
-                    // This occurs when there are nested snapshots and
-                    // the inner is committed but outer is rolled back.
-                }
-
-                NewElem(i) => {
-                    self.values.pop();
-                    assert!(self.values.len() == i);
-                }
-
-                SetElem(i, v) => {
-                    self.values[i] = v;
-                }
-
-                Other(u) => {
-                    D::reverse(&mut self.values, u);
-                }
-            }
-        }
-
-        let v = self.undo_log.pop().unwrap();
-        assert!(match v {
-            OpenSnapshot => true,
-            _ => false,
-        });
-        assert!(self.undo_log.len() == snapshot.length);
+        // The log is moved out for the duration, as `self` reverses its entries. Nothing is
+        // recorded meanwhile.
+        let mut undo_log = mem::replace(&mut self.undo_log, VecLog::default());
+        undo_log.rollback_to(self, snapshot);
+        self.undo_log = undo_log;
     }
 
     /// Commits all changes since the last snapshot. Of course, they
     /// can still be undone if there is a snapshot further out.
     pub fn commit(&mut self, snapshot: Snapshot) {
-        debug!("commit({})", snapshot.length);
-
-        self.assert_open_snapshot(&snapshot);
-
-        if snapshot.length == 0 {
-            // The root snapshot.
-            self.undo_log.truncate(0);
-        } else {
-            self.undo_log[snapshot.length] = CommittedSnapshot;
-        }
+        self.undo_log.commit(snapshot);
     }
 }
 
-impl<D: SnapshotVecDelegate> ops::Deref for SnapshotVec<D> {
+impl<D: SnapshotVecDelegate, V: AsRef<Vec<D::Value>>, L> ops::Deref for SnapshotVec<D, V, L> {
     type Target = [D::Value];
     fn deref(&self) -> &[D::Value] {
-        &*self.values
+        &*self.values.as_ref()
     }
 }
 
-impl<D: SnapshotVecDelegate> ops::DerefMut for SnapshotVec<D> {
+impl<D, V, L> ops::DerefMut for SnapshotVec<D, V, L>
+    where D: SnapshotVecDelegate,
+          V: AsRef<Vec<D::Value>> + AsMut<Vec<D::Value>>
+{
     fn deref_mut(&mut self) -> &mut [D::Value] {
-        &mut *self.values
+        &mut *self.values.as_mut()
     }
 }
 
-impl<D: SnapshotVecDelegate> ops::Index<usize> for SnapshotVec<D> {
+impl<D: SnapshotVecDelegate, V: AsRef<Vec<D::Value>>, L> ops::Index<usize>
+    for SnapshotVec<D, V, L>
+{
     type Output = D::Value;
     fn index(&self, index: usize) -> &D::Value {
         self.get(index)
     }
 }
 
-impl<D: SnapshotVecDelegate> ops::IndexMut<usize> for SnapshotVec<D> {
+impl<D, V, L> ops::IndexMut<usize> for SnapshotVec<D, V, L>
+    where D: SnapshotVecDelegate,
+          V: AsRef<Vec<D::Value>> + AsMut<Vec<D::Value>>
+{
     fn index_mut(&mut self, index: usize) -> &mut D::Value {
         self.get_mut(index)
     }
@@ -11,7 +11,7 @@
 use std::marker;
 use std::fmt::Debug;
 use std::marker::PhantomData;
-use snapshot_vec as sv;
+use snapshot_vec::{self as sv, Rollback, UndoLogs};
 
 #[cfg(test)]
 mod tests;
@@ -63,11 +63,22 @@ pub struct VarValue<K: UnifyKey> {
 }
 
 /// Table of unification keys and their values.
-pub struct UnificationTable<K: UnifyKey> {
+///
+/// By default the table keeps its own undo log and can be snapshotted
+/// directly. A `UnificationStorage` instead records its changes into a
+/// log that is shared with other tables and owned elsewhere; see
+/// `with_log`.
+pub struct UnificationTable<K: UnifyKey,
+                            S = Vec<VarValue<K>>,
+                            L = sv::VecLog<sv::UndoLog<Delegate<K>>>> {
     /// Indicates the current value of each key.
-    values: sv::SnapshotVec<Delegate<K>>,
+    values: sv::SnapshotVec<Delegate<K>, S, L>,
 }
 
+/// The keys of a unification table whose changes are recorded into a
+/// log owned elsewhere.
+pub type UnificationStorage<K> = UnificationTable<K, Vec<VarValue<K>>, ()>;
+
 /// At any time, users may snapshot a unification table.  The changes
 /// made during the snapshot may either be *committed* or *rolled back*.
 pub struct Snapshot<K: UnifyKey> {
@@ -77,7 +88,7 @@ pub struct Snapshot<K: UnifyKey> {
 }
 
 #[derive(Copy, Clone)]
-struct Delegate<K>(PhantomData<K>);
+pub struct Delegate<K>(PhantomData<K>);
 
 impl<K: UnifyKey> VarValue<K> {
     fn new_var(key: K, value: K::Value) -> VarValue<K> {
@@ -128,11 +139,32 @@ impl<K: UnifyKey> VarValue<K> {
 // other type parameter U, and we have no way to say
 // Option<U>:LatticeValue.
 
-impl<K: UnifyKey> UnificationTable<K> {
-    pub fn new() -> UnificationTable<K> {
+impl<K: UnifyKey, L: Default> UnificationTable<K, Vec<VarValue<K>>, L> {
+    pub fn new() -> UnificationTable<K, Vec<VarValue<K>>, L> {
         UnificationTable { values: sv::SnapshotVec::new() }
     }
+}
+
+impl<K: UnifyKey> UnificationStorage<K> {
+    /// Borrows the table together with `undo_log`, into which the
+    /// changes made through the result are recorded.
+    pub fn with_log<'a, L>(&'a mut self, undo_log: L)
+                           -> UnificationTable<K, &'a mut Vec<VarValue<K>>, L>
+        where L: UndoLogs<sv::UndoLog<Delegate<K>>>
+    {
+        UnificationTable { values: self.values.with_log(undo_log) }
+    }
+}
+
+impl<K: UnifyKey, S: AsMut<Vec<VarValue<K>>>, L> sv::Rollback<sv::UndoLog<Delegate<K>>>
+    for UnificationTable<K, S, L>
+{
+    fn reverse(&mut self, undo: sv::UndoLog<Delegate<K>>) {
+        self.values.reverse(undo);
+    }
+}
 
+impl<K: UnifyKey> UnificationTable<K> {
     /// Starts a new snapshot. Each snapshot must be either
     /// rolled back or committed in a "LIFO" (stack) order.
     pub fn snapshot(&mut self) -> Snapshot<K> {
@@ -155,6 +187,17 @@ impl<K: UnifyKey> UnificationTable<K> {
         debug!("{}: commit()", UnifyKey::tag(None::<K>));
         self.values.commit(snapshot.snapshot);
     }
+}
+
+impl<K, S, L> UnificationTable<K, S, L>
+    where K: UnifyKey,
+          S: AsRef<Vec<VarValue<K>>> + AsMut<Vec<VarValue<K>>>,
+          L: UndoLogs<sv::UndoLog<Delegate<K>>>
+{
+    /// Number of keys created so far.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
 
     pub fn new_key(&mut self, value: K::Value) -> K {
         let len = self.values.len();
@@ -254,8 +297,11 @@ impl<K: UnifyKey> sv::SnapshotVecDelegate for Delegate<K> {
 
 // # Base union-find algorithm, where we are just making sets
 
-impl<'tcx, K: UnifyKey> UnificationTable<K>
-    where K::Value: Combine
+impl<'tcx, K, S, L> UnificationTable<K, S, L>
+    where K: UnifyKey,
+          K::Value: Combine,
+          S: AsRef<Vec<VarValue<K>>> + AsMut<Vec<VarValue<K>>>,
+          L: UndoLogs<sv::UndoLog<Delegate<K>>>
 {
     pub fn union(&mut self, a_id: K, b_id: K) -> K {
         let node_a = self.get(a_id);
@@ -289,9 +335,11 @@ impl<'tcx, K: UnifyKey> UnificationTable<K>
 // floats---anything that doesn't have a subtyping relationship we
 // need to worry about.
 
-impl<'tcx, K, V> UnificationTable<K>
+impl<'tcx, K, V, S, L> UnificationTable<K, S, L>
     where K: UnifyKey<Value = Option<V>>,
-          V: Clone + PartialEq + Debug
+          V: Clone + PartialEq + Debug,
+          S: AsRef<Vec<VarValue<K>>> + AsMut<Vec<VarValue<K>>>,
+          L: UndoLogs<sv::UndoLog<Delegate<K>>>
 {
     pub fn unify_var_var(&mut self, a_id: K, b_id: K) -> Result<K, (V, V)> {
         let node_a = self.get(a_id);
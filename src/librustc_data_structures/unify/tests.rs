@@ -12,7 +12,8 @@
 
 extern crate test;
 use self::test::Bencher;
-use unify::{UnifyKey, UnificationTable};
+use snapshot_vec as sv;
+use unify::{Delegate, UnifyKey, UnificationStorage, UnificationTable};
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 struct UnitKey(u32);
@@ -109,6 +110,47 @@ fn even_odd() {
     }
 }
 
+#[test]
+fn commit_nested_snapshot() {
+    let mut ut: UnificationTable<UnitKey> = UnificationTable::new();
+    let k1 = ut.new_key(());
+    let outer = ut.snapshot();
+    let k2 = ut.new_key(());
+    let inner = ut.snapshot();
+    ut.union(k1, k2);
+    ut.commit(inner);
+    assert!(ut.unioned(k1, k2));
+
+    // The changes committed by the inner snapshot are still undone by the outer one.
+    ut.rollback_to(outer);
+    let k3 = ut.new_key(());
+    assert_eq!(k3, k2);
+    assert!(!ut.unioned(k1, k3));
+
+    let outer = ut.snapshot();
+    ut.union(k1, k3);
+    ut.commit(outer);
+    assert!(ut.unioned(k1, k3));
+}
+
+#[test]
+fn shared_log() {
+    let mut ut: UnificationStorage<UnitKey> = UnificationStorage::new();
+    let mut log: sv::VecLog<sv::UndoLog<Delegate<UnitKey>>> = Default::default();
+    let k1 = ut.with_log(&mut log).new_key(());
+
+    // Open a snapshot in the log; the table records into it from now on.
+    let snapshot = log.start_snapshot();
+    let k2 = ut.with_log(&mut log).new_key(());
+    ut.with_log(&mut log).union(k1, k2);
+    assert!(ut.with_log(&mut log).unioned(k1, k2));
+
+    log.rollback_to(&mut ut, snapshot);
+    let k3 = ut.with_log(&mut log).new_key(());
+    assert_eq!(k3, k2);
+    assert!(!ut.with_log(&mut log).unioned(k1, k3));
+}
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 struct IntKey(u32);
 
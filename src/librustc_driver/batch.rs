@@ -0,0 +1,119 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! Compiling several crates one after another in a single process, for
+//! `-Z batch`.
+//!
+//! Build systems that run rustc thousands of times, such as test suites,
+//! pay for starting the process and initializing LLVM on every run. With
+//! a batch file, the crates listed in it are compiled in turn on the same
+//! compiler thread instead, so LLVM is initialized once and the names
+//! interned for one crate are already there for the next.
+//!
+//! A batch file holds the command line of each crate, without the program
+//! name, one argument per line. Crates are separated by an empty line.
+//! Other options given alongside `-Z batch` are not passed on to them.
+
+use {abort_msg, run_compiler, CompilerCalls};
+
+use rustc::session::config::ErrorOutputType;
+use rustc::session::early_error;
+
+use syntax::errors;
+use syntax::errors::emitter::Emitter;
+
+use std::cell::Cell;
+use std::fs::File;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+
+thread_local!(static IN_BATCH: Cell<bool> = Cell::new(false));
+
+/// Compiles each crate listed in the batch file at `path`, reporting
+/// the errors of each one as it goes. Aborts afterwards if any of them
+/// failed to compile.
+pub fn run_batch<'a>(path: &str, program: &str, callbacks: &mut CompilerCalls<'a>) {
+    if IN_BATCH.with(|in_batch| in_batch.get()) {
+        early_error(ErrorOutputType::default(),
+                    "`-Z batch` cannot be used by a crate in a batch");
+    }
+    IN_BATCH.with(|in_batch| in_batch.set(true));
+
+    let mut contents = String::new();
+    if let Err(e) = File::open(path).and_then(|mut f| f.read_to_string(&mut contents)) {
+        early_error(ErrorOutputType::default(),
+                    &format!("couldn't read batch file `{}`: {}", path, e));
+    }
+
+    let crates = parse_batch(&contents);
+    let mut failed = 0;
+    for args in &crates {
+        let mut crate_args = vec![program.to_string()];
+        crate_args.extend(args.iter().cloned());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            run_compiler(&crate_args, callbacks)
+        }));
+        match result {
+            Ok((Ok(()), _)) => {}
+            Ok((Err(err_count), sess)) => {
+                if err_count > 0 {
+                    failed += 1;
+                    let msg = abort_msg(err_count);
+                    match sess {
+                        Some(sess) => sess.struct_fatal(&msg).emit(),
+                        None => emit_fatal(&msg),
+                    }
+                }
+            }
+            // A fatal error has already been reported; move on to the
+            // next crate. Anything else is a bug, left to `monitor`.
+            Err(value) => {
+                if !value.is::<errors::FatalError>() {
+                    panic::resume_unwind(value);
+                }
+                failed += 1;
+            }
+        }
+    }
+
+    IN_BATCH.with(|in_batch| in_batch.set(false));
+
+    if failed > 0 {
+        emit_fatal(&format!("{} of {} crates in the batch failed to compile",
+                            failed, crates.len()));
+        panic!(errors::FatalError);
+    }
+}
+
+fn parse_batch(contents: &str) -> Vec<Vec<String>> {
+    let mut crates = vec![];
+    let mut args = vec![];
+    for line in contents.lines() {
+        if line.is_empty() {
+            if !args.is_empty() {
+                crates.push(args);
+                args = vec![];
+            }
+        } else {
+            args.push(line.to_string());
+        }
+    }
+    if !args.is_empty() {
+        crates.push(args);
+    }
+    crates
+}
+
+fn emit_fatal(msg: &str) {
+    let mut emitter = errors::emitter::BasicEmitter::stderr(errors::ColorConfig::Auto);
+    emitter.emit(None, msg, None, errors::Level::Fatal);
+}
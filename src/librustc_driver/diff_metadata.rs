@@ -0,0 +1,356 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Comparing the public interface of two builds of a library crate,
+//! for `-Z diff-metadata`.
+//!
+//! Both libraries are loaded side by side into the crate store of an
+//! otherwise empty compilation, so that their items can be decoded and
+//! printed by the same code that reports types in error messages. The
+//! interface of a library is every public item reachable through its
+//! public modules, along with the methods of its inherent impls and
+//! its impls of public traits for public types, each one rendered as a
+//! string; items are then matched up by path, and impls by the trait
+//! and type they are for.
+
+use rustc_trans::back::link;
+
+use {driver, abort_on_err};
+
+use rustc::dep_graph::DepGraph;
+use rustc::hir;
+use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
+use rustc::hir::lowering::{lower_crate, LoweringContext};
+use rustc::hir::map as hir_map;
+use rustc::middle::cstore::{ChildItem, DlDef};
+use rustc::session::Session;
+use rustc::session::config::Input;
+use rustc::ty::{self, TyCtxt};
+use rustc::util::nodemap::DefIdSet;
+use rustc_metadata::creader::CrateReader;
+use rustc_metadata::cstore::CStore;
+use rustc_resolve as resolve;
+
+use syntax::ast;
+use syntax::codemap;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// The public items of a crate: the signature of each item, by path
+/// and kind of item.
+type Interface = BTreeMap<(String, &'static str), String>;
+
+pub fn diff_metadata_input(sess: &Session,
+                           cstore: &CStore,
+                           cfg: ast::CrateConfig,
+                           old: &Path,
+                           new: &Path) {
+    // The libraries are inspected from within an empty library crate.
+    let input = Input::Str {
+        name: "<diff-metadata>".to_string(),
+        input: "#![crate_type = \"lib\"]".to_string(),
+    };
+    let krate = panictry!(driver::phase_1_parse_input(sess, cfg, &input));
+    let id = link::find_crate_name(Some(sess), &krate.attrs, &input);
+    let krate = match driver::phase_2_configure_and_expand(sess, cstore, krate, &id[..], None) {
+        Err(_) => return,
+        Ok(k) => driver::assign_node_ids(sess, k),
+    };
+
+    let mut hir_forest;
+    let lcx = LoweringContext::new(sess, Some(&krate));
+    let arenas = ty::CtxtArenas::new();
+    let dep_graph = DepGraph::new(false);
+    let _ignore = dep_graph.in_ignore();
+    hir_forest = hir_map::Forest::new(lower_crate(&lcx, &krate), dep_graph.clone());
+    let ast_map = driver::make_map(sess, &mut hir_forest);
+
+    abort_on_err(driver::phase_3_run_analysis_passes(sess,
+                                                     cstore,
+                                                     ast_map,
+                                                     &arenas,
+                                                     &id,
+                                                     resolve::MakeGlobMap::No,
+                                                     |tcx, _, _, result| {
+        if result.is_err() {
+            return;
+        }
+
+        let mut reader = CrateReader::new(sess, cstore, &id);
+        let old_cnum = reader.load_crate_for_inspection(old, codemap::DUMMY_SP);
+        let new_cnum = reader.load_crate_for_inspection(new, codemap::DUMMY_SP);
+
+        let old_interface = InterfaceCollector::collect(tcx, old_cnum);
+        let new_interface = InterfaceCollector::collect(tcx, new_cnum);
+        print_diff(&old_interface, &new_interface);
+    }), sess);
+}
+
+fn print_diff(old: &Interface, new: &Interface) {
+    let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let &(ref path, kind) = key;
+        match (old.get(key), new.get(key)) {
+            (Some(_), None) => println!("removed: {} {}", kind, path),
+            (None, Some(_)) => println!("added: {} {}", kind, path),
+            (Some(old_sig), Some(new_sig)) => {
+                if old_sig != new_sig {
+                    println!("changed: {} {}", kind, path);
+                    println!("    was: {}", old_sig);
+                    println!("    now: {}", new_sig);
+                }
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+struct InterfaceCollector<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+    krate: ast::CrateNum,
+    visited_modules: DefIdSet,
+
+    /// The public traits, structs and enums of the crate.
+    public_items: DefIdSet,
+    interface: Interface,
+}
+
+impl<'a, 'tcx> InterfaceCollector<'a, 'tcx> {
+    fn collect(tcx: &'a TyCtxt<'tcx>, krate: ast::CrateNum) -> Interface {
+        let _ignore = tcx.dep_graph.in_ignore();
+        let mut collector = InterfaceCollector {
+            tcx: tcx,
+            krate: krate,
+            visited_modules: DefIdSet(),
+            public_items: DefIdSet(),
+            interface: Interface::new(),
+        };
+        let name = tcx.crate_name(krate).to_string();
+        let children = tcx.sess.cstore.crate_top_level_items(krate);
+        collector.collect_module(&name, children);
+        collector.collect_trait_impls();
+        collector.interface
+    }
+
+    fn record(&mut self, path: String, kind: &'static str, signature: String) {
+        self.interface.insert((path, kind), signature);
+    }
+
+    fn collect_module(&mut self, prefix: &str, children: Vec<ChildItem>) {
+        let tcx = self.tcx;
+        for child in children {
+            if child.vis != ty::Visibility::Public {
+                continue;
+            }
+            let def = match child.def {
+                DlDef(def) => def,
+                _ => continue,
+            };
+            let path = format!("{}::{}", prefix, child.name);
+            match def {
+                Def::Mod(did) => {
+                    self.record(path.clone(), "mod", String::new());
+                    // Modules re-exported from other crates are not part
+                    // of this crate's interface beyond their name.
+                    if did.krate == self.krate && self.visited_modules.insert(did) {
+                        let children = tcx.sess.cstore.item_children(did);
+                        self.collect_module(&path, children);
+                    }
+                }
+                Def::Fn(did) => {
+                    let signature = self.item_signature(did);
+                    self.record(path, "fn", signature);
+                }
+                Def::Const(did) => {
+                    let signature = self.item_signature(did);
+                    self.record(path, "const", signature);
+                }
+                Def::Static(did, mutbl) => {
+                    let kind = if mutbl { "static mut" } else { "static" };
+                    let signature = self.item_signature(did);
+                    self.record(path, kind, signature);
+                }
+                Def::TyAlias(did) => {
+                    let signature = self.item_signature(did);
+                    self.record(path, "type", signature);
+                }
+                Def::Struct(did) => {
+                    // Tuple and unit structs are listed once more as
+                    // their constructor.
+                    let did = tcx.sess.cstore.tuple_struct_definition_if_ctor(did)
+                                             .unwrap_or(did);
+                    self.public_items.insert(did);
+                    let signature = self.adt_signature(did);
+                    self.record(path.clone(), "struct", signature);
+                    self.collect_inherent_impls(&path, did);
+                }
+                Def::Enum(did) => {
+                    self.public_items.insert(did);
+                    let signature = self.adt_signature(did);
+                    self.record(path.clone(), "enum", signature);
+                    self.collect_inherent_impls(&path, did);
+                }
+                Def::Trait(did) => {
+                    self.public_items.insert(did);
+                    let signature = where_clause(&tcx.lookup_predicates(did));
+                    self.record(path.clone(), "trait", signature);
+                    for item in tcx.trait_items(did).iter() {
+                        self.collect_associated_item(&path, item);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_inherent_impls(&mut self, prefix: &str, did: DefId) {
+        if did.krate != self.krate {
+            return;
+        }
+        let tcx = self.tcx;
+        for &impl_did in tcx.inherent_impls_of(did).iter() {
+            for item_id in tcx.sess.cstore.impl_items(impl_did) {
+                let item = tcx.impl_or_trait_item(item_id.def_id());
+                if item.vis() == ty::Visibility::Public {
+                    self.collect_associated_item(prefix, &item);
+                }
+            }
+        }
+    }
+
+    /// Records each impl of a trait that other crates can see: the
+    /// trait and the self type must not be private to this crate. The
+    /// signature of an impl is its where clause and the values of its
+    /// associated types, since the rest follows from the trait.
+    fn collect_trait_impls(&mut self) {
+        let tcx = self.tcx;
+        for impl_did in tcx.sess.cstore.crate_trait_impls(self.krate) {
+            let trait_ref = match tcx.impl_trait_ref(impl_did) {
+                Some(trait_ref) => trait_ref,
+                None => continue,
+            };
+            let self_ty = trait_ref.self_ty();
+            let self_did = match self_ty.sty {
+                ty::TyStruct(def, _) | ty::TyEnum(def, _) => Some(def.did),
+                ty::TyTrait(ref data) => Some(data.principal_def_id()),
+                _ => None,
+            };
+            let is_visible = {
+                let is_private = |did: DefId| {
+                    did.krate == self.krate && !self.public_items.contains(&did)
+                };
+                !is_private(trait_ref.def_id) && !self_did.map_or(false, &is_private)
+            };
+            if !is_visible {
+                continue;
+            }
+
+            let mut signature = where_clause(&tcx.lookup_predicates(impl_did));
+            for item_id in tcx.sess.cstore.impl_items(impl_did) {
+                let item = tcx.impl_or_trait_item(item_id.def_id());
+                if let ty::TypeTraitItem(ref assoc_ty) = item {
+                    if let Some(ty) = assoc_ty.ty {
+                        signature.push_str(&format!("; type {} = {}", assoc_ty.name, ty));
+                    }
+                }
+            }
+            let path = format!("{} for {}", trait_ref, self_ty);
+            self.record(path, "impl", signature);
+        }
+    }
+
+    fn collect_associated_item(&mut self, prefix: &str, item: &ty::ImplOrTraitItem<'tcx>) {
+        let path = format!("{}::{}", prefix, item.name());
+        match *item {
+            ty::MethodTraitItem(ref method) => {
+                let unsafety = match method.fty.unsafety {
+                    hir::Unsafety::Unsafe => "unsafe ",
+                    hir::Unsafety::Normal => "",
+                };
+                let signature = format!("{}{}{}",
+                                        unsafety,
+                                        method.fty.sig.0,
+                                        where_clause(&method.predicates));
+                self.record(path, "method", signature);
+            }
+            ty::ConstTraitItem(ref constant) => {
+                self.record(path, "associated const", constant.ty.to_string());
+            }
+            ty::TypeTraitItem(ref assoc_ty) => {
+                let default = assoc_ty.ty.map(|ty| ty.to_string()).unwrap_or(String::new());
+                self.record(path, "associated type", default);
+            }
+        }
+    }
+
+    fn item_signature(&self, did: DefId) -> String {
+        format!("{}{}",
+                self.tcx.lookup_item_type(did).ty,
+                where_clause(&self.tcx.lookup_predicates(did)))
+    }
+
+    /// The type and fields of a struct or enum. Private fields are
+    /// left out, but their presence is not, since it stops other
+    /// crates from constructing the type.
+    fn adt_signature(&self, did: DefId) -> String {
+        let adt = self.tcx.lookup_adt_def(did);
+        let body = match adt.adt_kind() {
+            ty::AdtKind::Struct => variant_signature(adt.struct_variant()),
+            ty::AdtKind::Enum => {
+                let variants: Vec<_> = adt.variants.iter().map(|variant| {
+                    format!("{}{}", variant.name, variant_signature(variant))
+                }).collect();
+                format!(" {{ {} }}", variants.join(", "))
+            }
+        };
+        format!("{}{}{}",
+                self.tcx.lookup_item_type(did).ty,
+                body,
+                where_clause(&self.tcx.lookup_predicates(did)))
+    }
+}
+
+fn variant_signature(variant: &ty::VariantDefData) -> String {
+    let mut fields = vec![];
+    let mut has_private_fields = false;
+    for field in &variant.fields {
+        if field.vis != ty::Visibility::Public {
+            has_private_fields = true;
+            if variant.kind() == ty::VariantKind::Tuple {
+                fields.push("_".to_string());
+            }
+        } else if variant.kind() == ty::VariantKind::Tuple {
+            fields.push(field.unsubst_ty().to_string());
+        } else {
+            fields.push(format!("{}: {}", field.name, field.unsubst_ty()));
+        }
+    }
+    match variant.kind() {
+        ty::VariantKind::Unit => String::new(),
+        ty::VariantKind::Tuple => format!("({})", fields.join(", ")),
+        ty::VariantKind::Struct => {
+            if has_private_fields {
+                fields.push("..".to_string());
+            }
+            format!(" {{ {} }}", fields.join(", "))
+        }
+    }
+}
+
+fn where_clause(predicates: &ty::GenericPredicates) -> String {
+    let predicates: Vec<_> = predicates.predicates.iter().map(|p| p.to_string()).collect();
+    if predicates.is_empty() {
+        String::new()
+    } else {
+        format!(" where {}", predicates.join(", "))
+    }
+}
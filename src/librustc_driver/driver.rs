@@ -496,6 +496,11 @@ pub fn phase_2_configure_and_expand(sess: &Session,
     //
     // baz! should not use this definition unless foo is enabled.
 
+    *sess.other_test_config_names.borrow_mut() =
+        time(time_passes, "collecting names used by the other test configuration", || {
+            syntax::config::other_test_config_names(&krate)
+        });
+
     let mut feature_gated_cfgs = vec![];
     krate = time(time_passes, "configuration 1", || {
         sess.track_errors(|| {
@@ -608,6 +613,7 @@ pub fn phase_2_configure_and_expand(sess: &Session,
             features: Some(&features),
             recursion_limit: sess.recursion_limit.get(),
             trace_mac: sess.opts.debugging_opts.trace_macros,
+            record_expanded_tokens: sess.opts.debugging_opts.save_analysis,
         };
         let mut ecx = syntax::ext::base::ExtCtxt::new(&sess.parse_sess,
                                                       krate.config.clone(),
@@ -772,6 +778,12 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
         })
     })?;
 
+    let diagnostic_items = time(time_passes, "diagnostic item collection", || {
+        sess.track_errors(|| {
+            middle::diagnostic_items::collect_diagnostic_items(&sess, &hir_map)
+        })
+    })?;
+
     let resolve::CrateMap {
         def_map,
         freevars,
@@ -826,6 +838,7 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
                                freevars,
                                region_map,
                                lang_items,
+                               diagnostic_items,
                                index,
                                name,
                                |tcx| {
@@ -953,6 +966,9 @@ pub fn phase_4_translate_to_llvm<'tcx>(tcx: &TyCtxt<'tcx>,
         passes.push_pass(box mir::transform::no_landing_pads::NoLandingPads);
         passes.push_pass(box mir::transform::remove_dead_blocks::RemoveDeadBlocks);
         passes.push_pass(box mir::transform::erase_regions::EraseRegions);
+        passes.push_pass(box mir::transform::peephole::Peephole::with_default_rules());
+        passes.push_pass(box mir::transform::known_discriminants::KnownDiscriminants);
+        passes.push_pass(box mir::transform::remove_dead_blocks::RemoveDeadBlocks);
         passes.push_pass(box mir::transform::break_critical_edges::BreakCriticalEdges);
         passes.run_passes(tcx, &mut mir_map);
     });
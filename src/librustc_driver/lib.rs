@@ -66,6 +66,7 @@ use pretty::{PpMode, UserIdentifiedItem};
 use rustc_resolve as resolve;
 use rustc_save_analysis as save;
 use rustc_trans::back::link;
+use rustc_trans::back::write;
 use rustc::session::{config, Session, build_session, CompileResult};
 use rustc::session::config::{Input, PrintRequest, OutputType, ErrorOutputType};
 use rustc::session::config::{get_unstable_features_setting, nightly_options};
@@ -82,7 +83,7 @@ use std::default::Default;
 use std::env;
 use std::io::{self, Read, Write};
 use std::iter::repeat;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::rc::Rc;
 use std::str;
@@ -98,12 +99,16 @@ use syntax::errors::emitter::Emitter;
 use syntax::diagnostics;
 use syntax::parse::token;
 use syntax::feature_gate::{GatedCfg, UnstableFeatures};
+use syntax::util::stack;
 
 #[cfg(test)]
 pub mod test;
 
+pub mod batch;
+pub mod diff_metadata;
 pub mod driver;
 pub mod pretty;
+pub mod server;
 pub mod target_features;
 
 
@@ -148,6 +153,21 @@ pub fn run(args: Vec<String>) -> isize {
     0
 }
 
+// Check `-C target-cpu` and `-C target-feature` against what LLVM knows about
+// the target, before we spend any time compiling. LLVM is only consulted if
+// either option was given.
+fn check_target_options(sess: &Session) {
+    if sess.opts.cg.target_cpu.is_none() && sess.opts.cg.target_feature.is_empty() {
+        return;
+    }
+    write::init_llvm(sess);
+    if let Some(info) = write::TargetInfo::new(sess) {
+        sess.check_target_options(|cpu| info.has_cpu(cpu), &info.cpus(),
+                                  |feature| info.has_feature(feature), &info.features());
+        sess.abort_if_errors();
+    }
+}
+
 // Parse args and run the compiler. This is the primary entry point for rustc.
 // See comments on CompilerCalls below for details about the callbacks argument.
 pub fn run_compiler<'a>(args: &[String],
@@ -167,6 +187,11 @@ pub fn run_compiler<'a>(args: &[String],
 
     let sopts = config::build_session_options(&matches);
 
+    if let Some(ref batch) = sopts.debugging_opts.batch {
+        batch::run_batch(batch, &args[0], callbacks);
+        return (Ok(()), None);
+    }
+
     if sopts.debugging_opts.debug_llvm {
         unsafe { llvm::LLVMSetDebug(1); }
     }
@@ -191,11 +216,26 @@ pub fn run_compiler<'a>(args: &[String],
     let cstore = Rc::new(CStore::new(token::get_ident_interner()));
     let sess = build_session(sopts, input_file_path, descriptions, cstore.clone());
     rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+    check_target_options(&sess);
     let mut cfg = config::build_configuration(&sess);
     target_features::add_configuration(&mut cfg, &sess);
 
     do_or_return!(callbacks.late_callback(&matches, &sess, &input, &odir, &ofile), Some(sess));
 
+    // Comparing metadata needs a type context of its own, much like typed
+    // pretty printing, so it is hardwired in as well.
+    if let Some(ref old) = sess.opts.debugging_opts.diff_metadata {
+        match input {
+            Input::File(ref new) => {
+                diff_metadata::diff_metadata_input(&sess, &cstore, cfg, Path::new(old), new);
+            }
+            Input::Str { .. } => {
+                early_error(ErrorOutputType::default(), "cannot diff metadata for stdin");
+            }
+        }
+        return (Ok(()), Some(sess));
+    }
+
     // It is somewhat unfortunate that this is hardwired in - this is forced by
     // the fact that pretty_print_input requires the session by value.
     let pretty = callbacks.parse_pretty(&sess, &matches);
@@ -422,6 +462,7 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                 let sess = build_session(sopts.clone(), None, descriptions.clone(),
                                          cstore.clone());
                 rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+                check_target_options(&sess);
                 let should_stop = RustcDefaultCalls::print_crate_info(&sess, None, odir, ofile);
                 if should_stop == Compilation::Stop {
                     return None;
@@ -560,6 +601,20 @@ impl RustcDefaultCalls {
                     println!("{}", targets.join("\n"));
                 },
                 PrintRequest::Sysroot => println!("{}", sess.sysroot().display()),
+                PrintRequest::TargetFeatures => {
+                    // Ask LLVM, so that the features implied by the CPU and
+                    // by other features are listed too. If it cannot list
+                    // the target's features, print those that were asked for.
+                    write::init_llvm(sess);
+                    let mut features = write::TargetInfo::for_codegen(sess)
+                        .map_or(Vec::new(), |info| info.enabled_features());
+                    if features.is_empty() {
+                        features = sess.target_features();
+                    }
+                    for feature in features {
+                        println!("{}", feature);
+                    }
+                }
                 PrintRequest::FileNames |
                 PrintRequest::CrateName => {
                     let input = match input {
@@ -822,31 +877,44 @@ fn describe_codegen_flags() {
     print_flag_list("-C", config::CG_OPTIONS);
 }
 
-fn print_flag_list<T>(cmdline_opt: &str,
-                      flag_list: &[(&'static str, T, Option<&'static str>, &'static str)]) {
+fn print_flag_list<T>(cmdline_opt: &str, flag_list: &[config::OptionDesc<T>]) {
     let max_len = flag_list.iter()
-                           .map(|&(name, _, opt_type_desc, _)| {
-                               let extra_len = match opt_type_desc {
+                           .map(|opt| {
+                               let extra_len = match opt.type_desc {
                                    Some(..) => 4,
                                    None => 0,
                                };
-                               name.chars().count() + extra_len
+                               opt.name.chars().count() + extra_len
                            })
                            .max()
                            .unwrap_or(0);
 
-    for &(name, _, opt_type_desc, desc) in flag_list {
-        let (width, extra) = match opt_type_desc {
+    // When none of the options are stable a single note at the end says
+    // so; otherwise each unstable option is marked on its own line.
+    let all_unstable = flag_list.iter().all(|opt| opt.stability != config::OptionStability::Stable);
+    for opt in flag_list {
+        let (width, extra) = match opt.type_desc {
             Some(..) => (max_len - 4, "=val"),
             None => (max_len, ""),
         };
-        println!("    {} {:>width$}{} -- {}",
+        let note = if !all_unstable && opt.stability != config::OptionStability::Stable {
+            " (unstable)"
+        } else {
+            ""
+        };
+        println!("    {} {:>width$}{} -- {}{}",
                  cmdline_opt,
-                 name.replace("_", "-"),
+                 opt.name.replace("_", "-"),
                  extra,
-                 desc,
+                 opt.desc,
+                 note,
                  width = width);
     }
+
+    if all_unstable && !flag_list.is_empty() {
+        println!("\nOptions listed under {} are unstable and may change or be removed \
+                  at any time.", cmdline_opt);
+    }
 }
 
 /// Process command line options. Emits messages as appropriate. If compilation
@@ -993,8 +1061,16 @@ pub fn monitor<F: FnOnce() + Send + 'static>(f: F) {
         cfg = cfg.stack_size(STACK_SIZE);
     }
 
+    // Record the stack size the compiler thread ends up with so the
+    // parser, folders and trait selection can detect when they are
+    // about to overflow it (see `syntax::util::stack`).
+    let stack_size = env::var("RUST_MIN_STACK").ok()
+                                              .and_then(|s| s.parse().ok())
+                                              .unwrap_or(STACK_SIZE);
+
     let thread = cfg.spawn(move || {
          io::set_panic(box err);
+         stack::record_stack_size(stack_size);
          f()
      });
 
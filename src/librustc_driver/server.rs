@@ -0,0 +1,232 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A compiler that stays resident between compilations of a crate, for
+//! tools such as IDEs that want the crate's diagnostics again each time
+//! one of its files changes.
+//!
+//! The server is created once from the crate's command line. It is then
+//! told which files changed, along with their contents if those have not
+//! been saved to disk, and asked to compile the crate again. If no file
+//! changed since the last compilation, its result is returned again.
+//! Diagnostics are sent to the caller one by one as they are reported,
+//! so that they can be shown before the compilation is over.
+//!
+//! The server always compiles incrementally, in the directory given with
+//! `-Z incremental` or else in `<crate>.incremental` in the output
+//! directory, or next to the crate root if there is none. Each compilation loads the dependency graph saved by the
+//! previous one and only discards the parts of it that depend on items
+//! that changed.
+
+use {driver, handle_options, make_input, make_output, target_features};
+
+use rustc::session::{self, config, CompileResult};
+use rustc::session::config::Input;
+use rustc_lint;
+use rustc_metadata::cstore::CStore;
+
+use syntax::codemap::{CodeMap, FileLoader, MultiSpan, RealFileLoader};
+use syntax::errors::{self, Level, RenderSpan};
+use syntax::errors::emitter::Emitter;
+use syntax::parse::token;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc::Sender;
+
+/// A diagnostic reported while compiling. The notes and help messages
+/// attached to an error follow it as diagnostics of their own.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    pub code: Option<String>,
+    /// Where the diagnostic points, as `file:line:col: line:col`.
+    pub span: Option<String>,
+}
+
+pub struct CompilerServer {
+    sopts: config::Options,
+    input_path: PathBuf,
+    odir: Option<PathBuf>,
+    ofile: Option<PathBuf>,
+    /// The contents of changed files that have not been saved to disk.
+    unsaved_files: Rc<RefCell<HashMap<PathBuf, String>>>,
+    changed: bool,
+    /// The result of the last compilation, returned again as long as no
+    /// file changes.
+    last_result: CompileResult,
+    diagnostics: Sender<Diagnostic>,
+}
+
+impl CompilerServer {
+    /// Creates a server for the crate compiled by the command line
+    /// `args`, sending the diagnostics of each compilation to
+    /// `diagnostics`. Returns `None` if `args` does not name a source
+    /// file to compile.
+    ///
+    /// Unless `args` give one with `-Z incremental`, the incremental
+    /// compilation directory is `<crate>.incremental`, where `<crate>`
+    /// is the file stem of the crate root. It is put in the output
+    /// directory if there is one, and otherwise next to the crate root
+    /// rather than in the current directory of the process.
+    pub fn new(args: &[String], diagnostics: Sender<Diagnostic>) -> Option<CompilerServer> {
+        let matches = match handle_options(args) {
+            Some(matches) => matches,
+            None => return None,
+        };
+        let input_path = match make_input(&matches.free) {
+            Some((Input::File(path), _)) => path,
+            _ => return None,
+        };
+        let (odir, ofile) = make_output(&matches);
+        let mut sopts = config::build_session_options(&matches);
+        if sopts.incremental.is_none() {
+            let stem = input_path.file_stem().unwrap_or(input_path.as_os_str());
+            let dir = odir.clone().unwrap_or_else(|| {
+                input_path.parent().map(Path::to_path_buf).unwrap_or(PathBuf::new())
+            });
+            sopts.incremental = Some(dir.join(stem).with_extension("incremental"));
+        }
+        Some(CompilerServer {
+            sopts: sopts,
+            input_path: input_path,
+            odir: odir,
+            ofile: ofile,
+            unsaved_files: Rc::new(RefCell::new(HashMap::new())),
+            changed: true,
+            last_result: Ok(()),
+            diagnostics: diagnostics,
+        })
+    }
+
+    /// Notes that the file at `path` changed. If `contents` is given, it
+    /// is used instead of what is on disk until the file changes again.
+    /// `path` has to be spelled the way the compiler reaches the file:
+    /// as on the command line for the crate root, and relative to it for
+    /// the files of its modules.
+    pub fn file_changed(&mut self, path: &Path, contents: Option<String>) {
+        let mut unsaved_files = self.unsaved_files.borrow_mut();
+        match contents {
+            Some(contents) => {
+                unsaved_files.insert(path.to_path_buf(), contents);
+            }
+            None => {
+                unsaved_files.remove(path);
+            }
+        }
+        self.changed = true;
+    }
+
+    /// Compiles the crate again if any of its files changed since the
+    /// last compilation, and returns the result of the last compilation.
+    pub fn compile(&mut self) -> CompileResult {
+        if self.changed {
+            self.changed = false;
+            self.last_result = self.compile_crate();
+        }
+        self.last_result
+    }
+
+    fn compile_crate(&self) -> CompileResult {
+        let file_loader = box UnsavedFileLoader { unsaved_files: self.unsaved_files.clone() };
+        let codemap = Rc::new(CodeMap::with_file_loader(file_loader));
+        let emitter = box DiagnosticSender {
+            codemap: codemap.clone(),
+            diagnostics: self.diagnostics.clone(),
+        };
+        let handler = errors::Handler::with_emitter(true, self.sopts.treat_err_as_bug, emitter);
+        let cstore = Rc::new(CStore::new(token::get_ident_interner()));
+        let sess = session::build_session_(self.sopts.clone(),
+                                           Some(self.input_path.clone()),
+                                           handler,
+                                           codemap,
+                                           cstore.clone());
+        rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+        let mut cfg = config::build_configuration(&sess);
+        target_features::add_configuration(&mut cfg, &sess);
+
+        let input = Input::File(self.input_path.clone());
+        let plugins = sess.opts.debugging_opts.extra_plugins.clone();
+        let control = driver::CompileController::basic();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            driver::compile_input(&sess, &cstore, cfg, &input, &self.odir, &self.ofile,
+                                  Some(plugins), &control)
+        }));
+        match result {
+            Ok(result) => result,
+            // A fatal error has already been reported; the server lives
+            // on to compile the crate again once it is fixed.
+            Err(value) => {
+                if !value.is::<errors::FatalError>() {
+                    panic::resume_unwind(value);
+                }
+                Err(sess.err_count())
+            }
+        }
+    }
+}
+
+struct UnsavedFileLoader {
+    unsaved_files: Rc<RefCell<HashMap<PathBuf, String>>>,
+}
+
+impl FileLoader for UnsavedFileLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        self.unsaved_files.borrow().contains_key(path) || RealFileLoader.file_exists(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.unsaved_files.borrow().get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => RealFileLoader.read_file(path),
+        }
+    }
+}
+
+struct DiagnosticSender {
+    codemap: Rc<CodeMap>,
+    diagnostics: Sender<Diagnostic>,
+}
+
+impl DiagnosticSender {
+    fn send(&self, msp: Option<&MultiSpan>, msg: &str, code: Option<&str>, lvl: Level) {
+        let span = msp.and_then(|msp| msp.spans.first())
+                      .map(|&sp| self.codemap.span_to_string(sp));
+        // The receiver may have gone away; the compilation still has to
+        // run to completion.
+        let _ = self.diagnostics.send(Diagnostic {
+            level: lvl,
+            message: msg.to_string(),
+            code: code.map(|code| code.to_string()),
+            span: span,
+        });
+    }
+}
+
+impl Emitter for DiagnosticSender {
+    fn emit(&mut self, msp: Option<&MultiSpan>, msg: &str, code: Option<&str>, lvl: Level) {
+        self.send(msp, msg, code, lvl);
+    }
+
+    fn custom_emit(&mut self, sp: &RenderSpan, msg: &str, lvl: Level) {
+        let msp = match *sp {
+            RenderSpan::FullSpan(ref msp) |
+            RenderSpan::EndSpan(ref msp) |
+            RenderSpan::FileLine(ref msp) => Some(msp),
+            RenderSpan::Suggestion(_) => None,
+        };
+        self.send(msp, msg, None, lvl);
+    }
+}
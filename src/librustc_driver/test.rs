@@ -14,7 +14,9 @@ use driver;
 use rustc::dep_graph::DepGraph;
 use rustc_lint;
 use rustc_resolve as resolve;
+use rustc::middle::diagnostic_items;
 use rustc::middle::lang_items;
+use rustc::middle::const_val::ConstVal;
 use rustc::middle::free_region::FreeRegionMap;
 use rustc::middle::region::{self, CodeExtent};
 use rustc::middle::region::CodeExtentData;
@@ -22,10 +24,12 @@ use rustc::middle::resolve_lifetime;
 use rustc::middle::stability;
 use rustc::ty::subst;
 use rustc::ty::subst::Subst;
-use rustc::traits::ProjectionMode;
+use rustc::traits::{self, ObligationCause, ProjectionMode};
 use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::relate::TypeRelation;
-use rustc::infer::{self, InferOk, InferResult, TypeOrigin};
+use rustc::infer::{self, FallbackPolicy, InferOk, InferResult, TypeOrigin};
+use rustc::infer::region_inference::{ConstrainRegSubVar, ConstrainVarSubReg};
+use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc_metadata::cstore::CStore;
 use rustc::hir::map as hir_map;
 use rustc::session::{self, config};
@@ -129,6 +133,7 @@ fn test_env<F>(source_string: &str,
 
     // run just enough stuff to build a tcx:
     let lang_items = lang_items::collect_language_items(&sess, &ast_map);
+    let diagnostic_items = diagnostic_items::collect_diagnostic_items(&sess, &ast_map);
     let resolve::CrateMap { def_map, freevars, .. } =
         resolve::resolve_crate(&sess, &ast_map, resolve::MakeGlobMap::No);
     let named_region_map = resolve_lifetime::krate(&sess, &ast_map, &def_map.borrow());
@@ -142,6 +147,7 @@ fn test_env<F>(source_string: &str,
                                freevars,
                                region_map,
                                lang_items,
+                               diagnostic_items,
                                index,
                                "test_crate",
                                |tcx| {
@@ -227,6 +233,7 @@ impl<'a, 'tcx> Env<'a, 'tcx> {
                 hir::ItemEnum(..) |
                 hir::ItemStruct(..) |
                 hir::ItemTrait(..) |
+                hir::ItemTraitAlias(..) |
                 hir::ItemImpl(..) |
                 hir::ItemDefaultImpl(..) => {
                     None
@@ -247,7 +254,7 @@ impl<'a, 'tcx> Env<'a, 'tcx> {
     }
 
     pub fn is_subtype(&self, a: Ty<'tcx>, b: Ty<'tcx>) -> bool {
-        match infer::can_mk_subty(self.infcx, a, b) {
+        match self.infcx.can_sub(self.infcx.param_env(), &a, &b) {
             Ok(_) => true,
             Err(_) => false,
         }
@@ -307,6 +314,14 @@ impl<'a, 'tcx> Env<'a, 'tcx> {
         ty::ReLateBound(debruijn, ty::BrAnon(id))
     }
 
+    pub fn t_bound(&self, index: u32) -> Ty<'tcx> {
+        let name = format!("T{}", index);
+        self.infcx.tcx.mk_ty(ty::TyBound(ty::DebruijnIndex::new(1), ty::BoundTy {
+            index: index,
+            name: token::intern(&name[..]),
+        }))
+    }
+
     pub fn t_rptr(&self, r: ty::Region) -> Ty<'tcx> {
         self.infcx.tcx.mk_imm_ref(self.infcx.tcx.mk_region(r), self.tcx().types.isize)
     }
@@ -500,7 +515,7 @@ fn sub_free_bound_false_infer() {
     //! does NOT hold for any instantiation of `_#1`.
 
     test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
-        let t_infer1 = env.infcx.next_ty_var();
+        let t_infer1 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
         let t_rptr_bound1 = env.t_rptr_late_bound(1);
         env.check_not_sub(env.t_fn(&[t_infer1], env.tcx().types.isize),
                           env.t_fn(&[t_rptr_bound1], env.tcx().types.isize));
@@ -519,7 +534,7 @@ fn lub_free_bound_infer() {
 
     test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
         env.create_simple_region_hierarchy();
-        let t_infer1 = env.infcx.next_ty_var();
+        let t_infer1 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
         let t_rptr_bound1 = env.t_rptr_late_bound(1);
         let t_rptr_free1 = env.t_rptr_free(1, 1);
         env.check_lub(env.t_fn(&[t_infer1], env.tcx().types.isize),
@@ -639,7 +654,7 @@ fn glb_bound_free() {
 fn glb_bound_free_infer() {
     test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
         let t_rptr_bound1 = env.t_rptr_late_bound(1);
-        let t_infer1 = env.infcx.next_ty_var();
+        let t_infer1 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
 
         // compute GLB(fn(_) -> isize, for<'b> fn(&'b isize) -> isize),
         // which should yield for<'b> fn(&'b isize) -> isize
@@ -857,3 +872,479 @@ fn walk_ty_skip_subtree() {
         assert!(expected.is_empty());
     })
 }
+
+/// Test that the types escaping a snapshot are those instantiating
+/// variables that predate it, and that they are forgotten again when
+/// an inner snapshot is rolled back.
+#[test]
+fn types_escaping_snapshot() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        let int_ty = tcx.types.isize;
+        let uint_ty = tcx.types.usize;
+        let t_outer1 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let t_outer2 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+
+        env.infcx.probe(|snapshot| {
+            let t_inner = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+            env.assert_eq(t_inner, uint_ty);
+            assert_eq!(env.infcx.types_escaping_snapshot(snapshot), []);
+
+            env.assert_eq(t_outer1, int_ty);
+            assert_eq!(env.infcx.types_escaping_snapshot(snapshot), [int_ty]);
+
+            env.infcx.probe(|inner_snapshot| {
+                env.assert_eq(t_outer2, uint_ty);
+                assert_eq!(env.infcx.types_escaping_snapshot(inner_snapshot), [uint_ty]);
+                assert_eq!(env.infcx.types_escaping_snapshot(snapshot), [int_ty, uint_ty]);
+            });
+
+            assert_eq!(env.infcx.types_escaping_snapshot(snapshot), [int_ty]);
+            assert_eq!(env.infcx.shallow_resolve(t_outer2), t_outer2);
+        });
+
+        assert_eq!(env.infcx.shallow_resolve(t_outer1), t_outer1);
+    })
+}
+
+/// Test that instantiating a type variable by subtyping makes fresh
+/// region variables only where the regions are variant:
+///
+///     &'static mut &'static isize <: _#1
+///
+/// instantiates `_#1` with `&'_#2 mut &'static isize`. The region under
+/// the `&mut` is invariant, so it is kept rather than replaced.
+#[test]
+fn generalize_keeps_invariant_regions() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        let t_infer1 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let t_rptr_static = env.t_rptr_static();
+        let t_mut = tcx.mk_mut_ref(tcx.mk_region(ty::ReStatic), t_rptr_static);
+        env.make_subtype(t_mut, t_infer1);
+
+        let t_resolved = env.infcx.resolve_type_vars_if_possible(&t_infer1);
+        match t_resolved.sty {
+            ty::TyRef(&ty::ReVar(_), ty::TypeAndMut { ty, mutbl: hir::MutMutable }) => {
+                assert_eq!(ty, t_rptr_static);
+            }
+            _ => panic!("`_#1` instantiated with {:?}", t_resolved),
+        }
+    })
+}
+
+/// Test that the skolemized regions of a successful higher-ranked
+/// subtyping are popped afterwards, so that no constraint mentioning
+/// them outlives the computation:
+///
+///     for<'a> fn(&'a isize) <: for<'b> fn(&'b isize)
+///
+/// relates `&'!b isize` to `&'_#0 isize`, with `'!b` skolemized.
+#[test]
+fn higher_ranked_sub_pops_skolemized() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let t_rptr_bound1 = env.t_rptr_late_bound(1);
+        let t_rptr_bound2 = env.t_rptr_late_bound(2);
+        env.check_sub(env.t_fn(&[t_rptr_bound1], env.tcx().types.isize),
+                      env.t_fn(&[t_rptr_bound2], env.tcx().types.isize));
+
+        let data = env.infcx.take_and_reset_region_constraints();
+        for constraint in data.constraints.keys() {
+            match *constraint {
+                ConstrainRegSubVar(ty::ReSkolemized(..), _) |
+                ConstrainVarSubReg(_, ty::ReSkolemized(..)) => {
+                    panic!("constraint on a popped skolemized region: {:?}", constraint);
+                }
+                _ => {}
+            }
+        }
+    })
+}
+
+/// Test that a function generic over a bound type is a subtype of any
+/// of its instantiations:
+///
+///     for<T> fn(T) <: fn(isize)
+#[test]
+fn sub_bound_type_instantiated() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let t_bound = env.t_bound(0);
+        env.check_sub(env.t_fn(&[t_bound], env.t_nil()),
+                      env.t_fn(&[env.tcx().types.isize], env.t_nil()));
+    })
+}
+
+/// Test that the skolemized type replacing a bound type may not flow
+/// into a type variable from outside the comparison:
+///
+///     fn(_#0) <: for<T> fn(T)
+#[test]
+fn sub_bound_type_leak() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let t_infer = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let t_bound = env.t_bound(0);
+        match env.sub(&env.t_fn(&[t_infer], env.t_nil()), &env.t_fn(&[t_bound], env.t_nil())) {
+            Err(ty::error::TypeError::TypesInsufficientlyPolymorphic(bt, _)) => {
+                assert_eq!(bt.index, 0);
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    })
+}
+
+/// Test that registered region obligations are only queued: they add
+/// no region constraint, are handed back per body, and are forgotten
+/// when the snapshot that registered them is rolled back.
+#[test]
+fn region_obligations_are_queued() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let t_param = env.t_param(subst::TypeSpace, 0);
+        let re_free = env.re_free(1, 1);
+        env.infcx.register_region_obligation(t_param,
+                                             re_free,
+                                             ObligationCause::misc(DUMMY_SP, 1));
+        env.infcx.probe(|_| {
+            env.infcx.register_region_obligation(env.tcx().types.isize,
+                                                 re_free,
+                                                 ObligationCause::misc(DUMMY_SP, 1));
+        });
+        env.infcx.register_region_obligation(t_param,
+                                             ty::ReStatic,
+                                             ObligationCause::misc(DUMMY_SP, 2));
+
+        assert!(env.infcx.take_and_reset_region_constraints().is_empty());
+
+        let obligations = env.infcx.take_registered_region_obligations(1);
+        assert_eq!(obligations.len(), 1);
+        assert_eq!(obligations[0].sup_type, t_param);
+        assert_eq!(obligations[0].sub_region, re_free);
+        assert!(env.infcx.take_registered_region_obligations(1).is_empty());
+        assert_eq!(env.infcx.take_registered_region_obligations(2).len(), 1);
+    })
+}
+
+/// Test that `take_and_reset_region_constraints` hands out the
+/// constraints recorded so far and leaves the table empty:
+///
+///     &'static isize <: &'_#0 isize
+///
+/// records `'static <= '_#0`.
+#[test]
+fn take_and_reset_region_constraints() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let re_var = env.infcx.next_region_var(infer::MiscVariable(DUMMY_SP));
+        env.make_subtype(env.t_rptr_static(), env.t_rptr(re_var));
+
+        let data = env.infcx.take_and_reset_region_constraints();
+        let constraints: Vec<_> = data.constraints.keys().cloned().collect();
+        assert_eq!(constraints.len(), 1);
+        match (constraints[0], re_var) {
+            (ConstrainRegSubVar(ty::ReStatic, vid), ty::ReVar(re_vid)) => {
+                assert_eq!(vid, re_vid);
+            }
+            _ => panic!("unexpected constraints: {:?}", constraints),
+        }
+
+        assert!(env.infcx.take_and_reset_region_constraints().is_empty());
+    })
+}
+
+/// Test that relating type variables by subtyping records that they
+/// are related, transitively, and that a rollback forgets it:
+///
+///     _#0 <: _#1, _#1 <: _#2
+#[test]
+fn type_vars_sub_unified() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let t_infer1 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let t_infer2 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let t_infer3 = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        assert!(!env.infcx.type_vars_sub_unified(t_infer1, t_infer2));
+
+        env.make_subtype(t_infer1, t_infer2);
+        assert!(env.infcx.type_vars_sub_unified(t_infer1, t_infer2));
+        assert!(!env.infcx.type_vars_sub_unified(t_infer1, t_infer3));
+
+        env.infcx.probe(|_| {
+            env.make_subtype(t_infer2, t_infer3);
+            assert!(env.infcx.type_vars_sub_unified(t_infer1, t_infer3));
+        });
+        assert!(!env.infcx.type_vars_sub_unified(t_infer1, t_infer3));
+    })
+}
+
+/// A fallback policy choosing the narrowest types instead of the
+/// language's `i32`, `f64` and `()`.
+struct NarrowFallback;
+
+impl<'tcx> FallbackPolicy<'tcx> for NarrowFallback {
+    fn int_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.types.u8
+    }
+
+    fn float_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.types.f32
+    }
+
+    fn diverging_fallback(&self, tcx: &TyCtxt<'tcx>) -> Ty<'tcx> {
+        tcx.types.bool
+    }
+}
+
+static NARROW_FALLBACK: NarrowFallback = NarrowFallback;
+
+/// Test that unconstrained numeric and diverging variables fall back to
+/// `i32`, `f64` and `()` by default, that ordinary type variables have
+/// no fallback, and that the policy can be replaced.
+#[test]
+fn fallback_policy() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        let origin = TypeOrigin::Misc(DUMMY_SP);
+        let t_int = tcx.mk_int_var(env.infcx.next_int_var_id());
+        let t_float = tcx.mk_float_var(env.infcx.next_float_var_id());
+        let t_diverging =
+            env.infcx.next_diverging_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let t_infer = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+
+        assert_eq!(env.infcx.fallback_type(t_int), Some(tcx.types.i32));
+        assert_eq!(env.infcx.fallback_type(t_float), Some(tcx.types.f64));
+        assert_eq!(env.infcx.fallback_type(t_diverging), Some(tcx.mk_nil()));
+        assert_eq!(env.infcx.fallback_type(t_infer), None);
+
+        env.infcx.set_fallback_policy(&NARROW_FALLBACK);
+        assert!(env.infcx.fallback_if_possible(origin, t_int));
+        assert!(env.infcx.fallback_if_possible(origin, t_float));
+        assert!(env.infcx.fallback_if_possible(origin, t_diverging));
+        assert!(!env.infcx.fallback_if_possible(origin, t_infer));
+
+        assert_eq!(env.infcx.resolve_type_vars_if_possible(&t_int), tcx.types.u8);
+        assert_eq!(env.infcx.resolve_type_vars_if_possible(&t_float), tcx.types.f32);
+        assert_eq!(env.infcx.resolve_type_vars_if_possible(&t_diverging), tcx.types.bool);
+    })
+}
+
+/// Test that constant variables unify with values and with each other,
+/// and that a rollback undoes it.
+#[test]
+fn unify_const_variables() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let c_true = ty::Const::Value(ConstVal::Bool(true));
+        let c_false = ty::Const::Value(ConstVal::Bool(false));
+        let c_var1 = env.infcx.next_const_var();
+        let c_var2 = env.infcx.next_const_var();
+        let trace = || env.dummy_type_trace();
+
+        env.infcx.probe(|_| {
+            assert!(env.infcx.equate(true, trace(), &c_var1, &c_true).is_ok());
+            assert_eq!(env.infcx.resolve_const(&c_var1), c_true);
+        });
+        assert_eq!(env.infcx.resolve_const(&c_var1), c_var1);
+
+        assert!(env.infcx.equate(true, trace(), &c_var1, &c_var2).is_ok());
+        assert!(env.infcx.sub(true, trace(), &c_true, &c_var2).is_ok());
+        assert_eq!(env.infcx.resolve_const(&c_var1), c_true);
+
+        match env.infcx.equate(true, trace(), &c_var1, &c_false) {
+            Err(ty::error::TypeError::ConstMismatch(_)) => {}
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+    })
+}
+
+/// Test that array lengths are related through `TypeRelation::consts`
+/// by every relation, keeping the array-specific error on a mismatch.
+#[test]
+fn relate_array_lengths() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        let t_array3 = tcx.mk_array(tcx.types.isize, 3);
+        let t_array4 = tcx.mk_array(tcx.types.isize, 4);
+        env.check_sub(t_array3, t_array3);
+        env.check_lub(t_array3, t_array3, t_array3);
+        env.check_glb(t_array3, t_array3, t_array3);
+
+        match env.sub(&t_array3, &t_array4) {
+            Err(ty::error::TypeError::FixedArraySize(values)) => {
+                assert_eq!((values.expected, values.found), (3, 4));
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("unexpected success"),
+        }
+        assert!(env.lub(&t_array3, &t_array4).is_err());
+        assert!(env.glb(&t_array3, &t_array4).is_err());
+    })
+}
+
+/// Test that `fudge_inference_if_ok` keeps the resolved result but none
+/// of the side effects: the variable created inside comes out as a
+/// fresh unbound variable, and the outer variable is unbound again.
+#[test]
+fn fudge_inference_if_ok() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        let t_outer = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let result = env.infcx.fudge_inference_if_ok(|| {
+            let t_inner = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+            env.make_subtype(t_outer, tcx.types.usize);
+            Ok::<_, ()>(env.t_pair(t_inner, t_outer))
+        });
+
+        match result.unwrap().sty {
+            ty::TyTuple(ref tys) => {
+                match tys[0].sty {
+                    ty::TyInfer(ty::TyVar(_)) => {
+                        assert_eq!(env.infcx.shallow_resolve(tys[0]), tys[0]);
+                    }
+                    _ => panic!("inner variable fudged into {:?}", tys[0]),
+                }
+                assert_eq!(tys[1], tcx.types.usize);
+            }
+            ref sty => panic!("unexpected result: {:?}", sty),
+        }
+        assert_eq!(env.infcx.shallow_resolve(t_outer), t_outer);
+
+        let result = env.infcx.fudge_inference_if_ok(|| -> Result<Ty, ()> {
+            env.make_subtype(t_outer, tcx.types.usize);
+            Err(())
+        });
+        assert_eq!(result, Err(()));
+        assert_eq!(env.infcx.shallow_resolve(t_outer), t_outer);
+    })
+}
+
+/// Test that the leak check only looks at the direction in which a
+/// skolemized region is related: `'!a <= 'static` holds for any `'!a`,
+/// so
+///
+///     fn() -> &'static isize <: for<'a> fn() -> &'a isize
+///
+/// but `'static <= '!a` does not, so
+///
+///     fn(&'static isize) <: for<'a> fn(&'a isize)
+///
+/// is rejected.
+#[test]
+fn leak_check_taint_directions() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let t_rptr_bound1 = env.t_rptr_late_bound(1);
+        let t_rptr_static = env.t_rptr_static();
+        env.check_sub(env.t_fn(&[], t_rptr_static),
+                      env.t_fn(&[], t_rptr_bound1));
+        env.check_not_sub(env.t_fn(&[t_rptr_static], env.t_nil()),
+                          env.t_fn(&[t_rptr_bound1], env.t_nil()));
+    })
+}
+
+/// Test that relating an unnormalized projection, when asked to defer
+/// it, hands back a projection obligation, also from within the
+/// higher-ranked relation of fn signatures:
+///
+///     fn(<isize as Trait>::Assoc) <: for<'a> fn(isize)
+#[test]
+fn relate_returns_projection_obligations() {
+    test_env("#![feature(no_core)] #![no_core] trait Trait { type Assoc; }",
+             errors(&[]),
+             |env| {
+        let tcx = env.infcx.tcx;
+        let trait_def_id = tcx.map.local_def_id(env.lookup_item(&["Trait".to_string()]));
+        let trait_ref = ty::TraitRef {
+            def_id: trait_def_id,
+            substs: tcx.mk_substs(subst::Substs::new_trait(vec![], vec![], tcx.types.isize)),
+        };
+        let t_proj = tcx.mk_projection(trait_ref, token::intern("Assoc"));
+
+        let t_fn_proj = env.t_fn(&[t_proj], env.t_nil());
+        let t_fn_isize = env.t_fn(&[tcx.types.isize], env.t_nil());
+        let obligations = env.infcx.deferring_projection_equality(|| {
+            match env.sub(&t_fn_proj, &t_fn_isize) {
+                Ok(InferOk { obligations, .. }) => obligations,
+                Err(e) => panic!("unexpected error: {}", e),
+            }
+        });
+
+        assert_eq!(obligations.len(), 1);
+        match obligations[0].predicate {
+            ty::Predicate::Projection(ref data) => {
+                assert_eq!(data.0.projection_ty.trait_ref, trait_ref);
+                assert_eq!(data.0.ty, tcx.types.isize);
+            }
+            ref predicate => panic!("unexpected obligation: {:?}", predicate),
+        }
+    })
+}
+
+/// Test that `match_poly_trait_ref` finds what the parameters of a
+/// skeleton stand for without binding inference variables:
+///
+///     (T0, T0): Trait<T1>  against  (isize, isize): Trait<usize>
+#[test]
+fn match_poly_trait_ref() {
+    test_env("#![feature(no_core)] #![no_core] trait Trait<U> {}",
+             errors(&[]),
+             |env| {
+        let tcx = env.infcx.tcx;
+        let trait_def_id = tcx.map.local_def_id(env.lookup_item(&["Trait".to_string()]));
+        let trait_ref = |self_ty, param_ty| {
+            ty::Binder(ty::TraitRef {
+                def_id: trait_def_id,
+                substs: tcx.mk_substs(subst::Substs::new_trait(vec![param_ty], vec![], self_ty)),
+            })
+        };
+        let t_param0 = env.t_param(subst::TypeSpace, 0);
+        let t_param1 = env.t_param(subst::TypeSpace, 1);
+        let skeleton = trait_ref(env.t_pair(t_param0, t_param0), t_param1);
+        let (t_isize, t_usize) = (tcx.types.isize, tcx.types.usize);
+
+        let value = trait_ref(env.t_pair(t_isize, t_isize), t_usize);
+        let map = env.infcx.match_poly_trait_ref(skeleton, value).unwrap();
+        let mut params: Vec<_> = map.iter().map(|(p, &t)| (p.idx, t)).collect();
+        params.sort_by_key(|&(idx, _)| idx);
+        assert_eq!(params, [(0, t_isize), (1, t_usize)]);
+
+        let mismatched = trait_ref(env.t_pair(t_isize, t_usize), t_usize);
+        assert!(env.infcx.match_poly_trait_ref(skeleton, mismatched).is_err());
+
+        // An unresolved variable fails the match and stays unbound.
+        let t_infer = env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+        let with_var = trait_ref(env.t_pair(t_isize, t_isize), t_infer);
+        assert!(env.infcx.match_poly_trait_ref(skeleton, with_var).is_err());
+        assert_eq!(env.infcx.shallow_resolve(t_infer), t_infer);
+
+        env.make_subtype(t_infer, t_usize);
+        assert!(env.infcx.match_poly_trait_ref(skeleton, with_var).is_ok());
+    })
+}
+
+/// Test that a predicate evaluated as a canonical query is answered
+/// from the cache when it is asked again about different inference
+/// variables, and that a different predicate gets an answer of its
+/// own.
+#[test]
+fn evaluation_query_cache_reuse() {
+    test_env(EMPTY_SOURCE_STR, errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        let evaluate = |a, b| {
+            let predicate = ty::Predicate::Equate(ty::Binder(ty::EquatePredicate(a, b)));
+            let obligation = traits::Obligation::new(ObligationCause::dummy(), predicate);
+            traits::SelectionContext::new(env.infcx).evaluate_obligation(&obligation)
+        };
+        let new_var = || env.infcx.next_ty_var(TypeVariableOrigin::MiscVariable(DUMMY_SP));
+
+        let (t_a, t_b) = (new_var(), new_var());
+        assert!(evaluate(t_a, env.t_pair(t_b, tcx.types.isize)));
+        assert_eq!(tcx.evaluation_query_cache.len(), 1);
+        assert_eq!(tcx.evaluation_query_cache.hits(), 0);
+
+        let (t_c, t_d) = (new_var(), new_var());
+        assert!(evaluate(t_c, env.t_pair(t_d, tcx.types.isize)));
+        assert_eq!(tcx.evaluation_query_cache.len(), 1);
+        assert_eq!(tcx.evaluation_query_cache.hits(), 1);
+
+        assert!(!evaluate(tcx.types.isize, env.t_pair(t_d, tcx.types.isize)));
+        assert_eq!(tcx.evaluation_query_cache.len(), 2);
+        assert_eq!(tcx.evaluation_query_cache.hits(), 1);
+    })
+}
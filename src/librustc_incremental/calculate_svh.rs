@@ -14,6 +14,8 @@
 use std::hash::{Hash, SipHasher, Hasher};
 use rustc::hir::def_id::{CRATE_DEF_INDEX, DefId};
 use rustc::hir::svh::Svh;
+use rustc::hir;
+use rustc::hir::map as hir_map;
 use rustc::ty;
 use rustc::hir::intravisit::{self, Visitor};
 
@@ -23,8 +25,15 @@ pub trait SvhCalculate {
     /// Calculate the SVH for an entire krate.
     fn calculate_krate_hash(&self) -> Svh;
 
-    /// Calculate the SVH for a particular item.
+    /// Calculate the SVH for the signature of a particular item,
+    /// leaving out its bodies.
     fn calculate_item_hash(&self, def_id: DefId) -> u64;
+
+    /// Calculate the SVH for the bodies of a particular item: the
+    /// body of a fn, the initializer of a const or static, or the
+    /// bodies of the methods and associated consts of an impl or
+    /// trait.
+    fn calculate_body_hash(&self, def_id: DefId) -> u64;
 }
 
 impl<'tcx> SvhCalculate for ty::TyCtxt<'tcx> {
@@ -54,7 +63,10 @@ impl<'tcx> SvhCalculate for ty::TyCtxt<'tcx> {
         debug!("state: {:?}", state);
 
         {
-            let mut visit = StrictVersionHashVisitor::new(&mut state, self);
+            // Downstream crates only ever see the interface of this
+            // crate, so a body they cannot inline leaves the SVH
+            // untouched and does not force them to be rebuilt.
+            let mut visit = StrictVersionHashVisitor::new_interface(&mut state, self);
             krate.visit_all_items(&mut visit);
         }
 
@@ -81,7 +93,7 @@ impl<'tcx> SvhCalculate for ty::TyCtxt<'tcx> {
         let mut state = SipHasher::new();
 
         {
-            let mut visit = StrictVersionHashVisitor::new(&mut state, self);
+            let mut visit = StrictVersionHashVisitor::new_signature(&mut state, self);
             if def_id.index == CRATE_DEF_INDEX {
                 // the crate root itself is not registered in the map
                 // as an item, so we have to fetch it this way
@@ -95,6 +107,40 @@ impl<'tcx> SvhCalculate for ty::TyCtxt<'tcx> {
 
         state.finish()
     }
+
+    fn calculate_body_hash(&self, def_id: DefId) -> u64 {
+        assert!(def_id.is_local());
+
+        let mut state = SipHasher::new();
+
+        // the crate root has no bodies of its own
+        if def_id.index != CRATE_DEF_INDEX {
+            let mut visit = StrictVersionHashVisitor::new(&mut state, self);
+            let node_id = self.map.as_local_node_id(def_id).unwrap();
+            let item = self.map.expect_item(node_id);
+            let mut owners = vec![item.id];
+            match item.node {
+                hir::ItemImpl(_, _, _, _, _, ref impl_items) => {
+                    owners.extend(impl_items.iter().map(|ii| ii.id));
+                }
+                hir::ItemTrait(_, _, _, ref trait_items) => {
+                    owners.extend(trait_items.iter().map(|ti| ti.id));
+                }
+                _ => { }
+            }
+            for owner in owners {
+                if let Some(body_id) = self.map.maybe_body_owned_by(owner) {
+                    match self.map.get(body_id.node_id) {
+                        hir_map::NodeBlock(block) => visit.visit_block(block),
+                        hir_map::NodeExpr(expr) => visit.visit_expr(expr),
+                        node => bug!("unexpected body node {:?}", node)
+                    }
+                }
+            }
+        }
+
+        state.finish()
+    }
 }
 
 // FIXME (#14132): Even this SVH computation still has implementation
@@ -107,11 +153,13 @@ mod svh_visitor {
     pub use self::SawStmtComponent::*;
     use self::SawAbiComponent::*;
     use syntax::ast::{self, Name, NodeId};
+    use syntax::attr;
     use syntax::codemap::Span;
     use syntax::parse::token;
     use rustc::ty;
     use rustc::hir;
     use rustc::hir::*;
+    use rustc::hir::map;
     use rustc::hir::intravisit as visit;
     use rustc::hir::intravisit::{Visitor, FnKind};
 
@@ -120,13 +168,91 @@ mod svh_visitor {
     pub struct StrictVersionHashVisitor<'a, 'tcx: 'a> {
         pub tcx: &'a ty::TyCtxt<'tcx>,
         pub st: &'a mut SipHasher,
+        hash_bodies: HashBodies,
+    }
+
+    /// Which bodies of fns, consts and statics get walked.
+    #[derive(Copy, Clone, PartialEq)]
+    enum HashBodies {
+        All,
+        /// Only bodies that are encoded into the crate metadata, and
+        /// hence can be inlined or evaluated by downstream crates.
+        Exported,
+        Nothing,
     }
 
     impl<'a, 'tcx> StrictVersionHashVisitor<'a, 'tcx> {
         pub fn new(st: &'a mut SipHasher,
                    tcx: &'a ty::TyCtxt<'tcx>)
                    -> Self {
-            StrictVersionHashVisitor { st: st, tcx: tcx }
+            StrictVersionHashVisitor { st: st, tcx: tcx, hash_bodies: HashBodies::All }
+        }
+
+        /// Like `new`, but the bodies of fns, consts and statics are
+        /// hashed as a placeholder instead of being walked.
+        pub fn new_signature(st: &'a mut SipHasher,
+                             tcx: &'a ty::TyCtxt<'tcx>)
+                             -> Self {
+            StrictVersionHashVisitor { st: st, tcx: tcx, hash_bodies: HashBodies::Nothing }
+        }
+
+        /// Like `new_signature`, but bodies that downstream crates can
+        /// see (those of consts and of generic, `#[inline]` or `const`
+        /// fns) are still walked.
+        pub fn new_interface(st: &'a mut SipHasher,
+                             tcx: &'a ty::TyCtxt<'tcx>)
+                             -> Self {
+            StrictVersionHashVisitor { st: st, tcx: tcx, hash_bodies: HashBodies::Exported }
+        }
+
+        /// Returns true (after hashing a placeholder) if `id` is the
+        /// root of a body that should be left out of the hash.
+        fn skip_body(&mut self, id: NodeId) -> bool {
+            if self.hash_bodies == HashBodies::All {
+                return false;
+            }
+            let owner = self.tcx.map.get_parent_node(id);
+            if self.tcx.map.maybe_body_owned_by(owner) != Some(BodyId { node_id: id }) {
+                return false;
+            }
+            if self.hash_bodies == HashBodies::Exported && body_is_exported(self.tcx, owner) {
+                return false;
+            }
+            SawBody.hash(self.st);
+            true
+        }
+    }
+
+    /// Returns true if the body owned by `owner` is written into the
+    /// crate metadata. This mirrors the choices made when encoding
+    /// inlined items in `rustc_metadata::encoder`.
+    fn body_is_exported(tcx: &ty::TyCtxt, owner: NodeId) -> bool {
+        match tcx.map.get(owner) {
+            map::NodeItem(item) => match item.node {
+                ItemFn(_, _, constness, _, ref generics, _) => {
+                    !generics.ty_params.is_empty() ||
+                        constness == Constness::Const ||
+                        attr::requests_inline(&item.attrs)
+                }
+                ItemConst(..) => true,
+                _ => false,
+            },
+            map::NodeTraitItem(_) => true,
+            map::NodeImplItem(impl_item) => match impl_item.node {
+                ImplItemKind::Method(ref sig, _) => {
+                    let impl_has_types = match tcx.map.expect_item(tcx.map.get_parent(owner)).node {
+                        ItemImpl(_, _, ref generics, _, _, _) => !generics.ty_params.is_empty(),
+                        _ => false,
+                    };
+                    impl_has_types ||
+                        !sig.generics.ty_params.is_empty() ||
+                        sig.constness == Constness::Const ||
+                        attr::requests_inline(&impl_item.attrs)
+                }
+                ImplItemKind::Const(..) => true,
+                ImplItemKind::Type(..) => false,
+            },
+            _ => false,
         }
     }
 
@@ -175,6 +301,7 @@ mod svh_visitor {
         SawExplicitSelf,
         SawPath,
         SawBlock,
+        SawBody,
         SawPat,
         SawLocal,
         SawArm,
@@ -226,6 +353,7 @@ mod svh_visitor {
         SawExprPath(Option<usize>),
         SawExprAddrOf(hir::Mutability),
         SawExprRet,
+        SawExprBecome,
         SawExprInlineAsm(&'a hir::InlineAsm),
         SawExprStruct,
         SawExprRepeat,
@@ -259,6 +387,7 @@ mod svh_visitor {
             ExprBreak(id)            => SawExprBreak(id.map(|id| id.node.name.as_str())),
             ExprAgain(id)            => SawExprAgain(id.map(|id| id.node.name.as_str())),
             ExprRet(..)              => SawExprRet,
+            ExprBecome(..)           => SawExprBecome,
             ExprInlineAsm(ref a,_,_) => SawExprInlineAsm(a),
             ExprStruct(..)           => SawExprStruct,
             ExprRepeat(..)           => SawExprRepeat,
@@ -302,6 +431,26 @@ mod svh_visitor {
             visit::walk_variant(self, v, g, item_id)
         }
 
+        // We do recursively walk the bodies of functions/methods
+        // (rather than omitting their bodies from the hash) since
+        // monomorphization and cross-crate inlining generally implies
+        // that a change to a crate body will require downstream
+        // crates to be recompiled. Only the per-item signature hash
+        // used for incremental compilation leaves them out.
+        fn visit_expr(&mut self, ex: &'a Expr) {
+            if self.skip_body(ex.id) {
+                return;
+            }
+            SawExpr(saw_expr(&ex.node)).hash(self.st); visit::walk_expr(self, ex)
+        }
+
+        fn visit_block(&mut self, b: &'a Block) {
+            if self.skip_body(b.id) {
+                return;
+            }
+            SawBlock.hash(self.st); visit::walk_block(self, b)
+        }
+
         // All of the remaining methods just record (in the hash
         // SipHasher) that the visitor saw that particular variant
         // (with its payload), and continue walking as the default
@@ -328,15 +477,6 @@ mod svh_visitor {
             SawLifetimeDef(l.lifetime.name.as_str()).hash(self.st);
         }
 
-        // We do recursively walk the bodies of functions/methods
-        // (rather than omitting their bodies from the hash) since
-        // monomorphization and cross-crate inlining generally implies
-        // that a change to a crate body will require downstream
-        // crates to be recompiled.
-        fn visit_expr(&mut self, ex: &'a Expr) {
-            SawExpr(saw_expr(&ex.node)).hash(self.st); visit::walk_expr(self, ex)
-        }
-
         fn visit_stmt(&mut self, s: &'a Stmt) {
             SawStmt(saw_stmt(&s.node)).hash(self.st); visit::walk_stmt(self, s)
         }
@@ -403,10 +543,6 @@ mod svh_visitor {
             SawPath.hash(self.st); visit::walk_path_list_item(self, prefix, item)
         }
 
-        fn visit_block(&mut self, b: &'a Block) {
-            SawBlock.hash(self.st); visit::walk_block(self, b)
-        }
-
         fn visit_pat(&mut self, p: &'a Pat) {
             SawPat.hash(self.st); visit::walk_pat(self, p)
         }
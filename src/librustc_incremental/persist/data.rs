@@ -26,8 +26,10 @@ pub type SerializedEdge = (DepNode<DefPathIndex>, DepNode<DefPathIndex>);
 
 #[derive(Debug, RustcEncodable, RustcDecodable)]
 pub struct SerializedHash {
-    pub index: DefPathIndex,
+    /// the `Hir` or `HirBody` node that was hashed
+    pub node: DepNode<DefPathIndex>,
 
-    /// the hash itself, computed by `calculate_item_hash`
+    /// the hash itself, computed by `calculate_item_hash` (for `Hir`)
+    /// or `calculate_body_hash` (for `HirBody`)
     pub hash: u64,
 }
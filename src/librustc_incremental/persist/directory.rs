@@ -12,12 +12,18 @@
 //! back again (when deserializing). Note that the new DefId
 //! necessarily will not be the same as the old (and of course the
 //! item might even be removed in the meantime).
+//!
+//! Paths into other crates are resolved through the `DefPathTable` in
+//! the metadata of those crates. If a crate has changed since the
+//! paths were recorded, its paths are not resolved at all, so that
+//! everything that depended on it is considered dirty.
 
 use rustc::dep_graph::DepNode;
 use rustc::hir::map::DefPath;
 use rustc::hir::def_id::DefId;
 use rustc::ty;
 use rustc::util::nodemap::DefIdMap;
+use syntax::ast;
 use rustc_serialize::{Decoder as RustcDecoder, Encoder as RustcEncoder};
 use std::fmt::{self, Debug};
 
@@ -32,17 +38,80 @@ pub struct DefPathIndex {
 pub struct DefIdDirectory {
     // N.B. don't use Removable here because these def-ids are loaded
     // directly without remapping, so loading them should not fail.
-    paths: Vec<DefPath>
+    paths: Vec<DefPath>,
+
+    // The other crates that `paths` refer to, as they were when the
+    // paths were recorded.
+    krates: Vec<KrateInfo>,
+}
+
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct KrateInfo {
+    krate: ast::CrateNum,
+    name: String,
+    disambiguator: String,
+    svh: String,
 }
 
 impl DefIdDirectory {
-    pub fn new() -> DefIdDirectory {
-        DefIdDirectory { paths: vec![] }
+    pub fn new(krates: Vec<KrateInfo>) -> DefIdDirectory {
+        DefIdDirectory { paths: vec![], krates: krates }
+    }
+
+    pub fn krate_info(tcx: &ty::TyCtxt) -> Vec<KrateInfo> {
+        tcx.sess.cstore.crates()
+                       .into_iter()
+                       .map(|krate| KrateInfo {
+                           krate: krate,
+                           name: tcx.crate_name(krate).to_string(),
+                           disambiguator: tcx.crate_disambiguator(krate).to_string(),
+                           svh: tcx.sess.cstore.crate_hash(krate).as_str().to_string(),
+                       })
+                       .collect()
     }
 
     pub fn retrace(&self, tcx: &ty::TyCtxt) -> RetracedDefIdDirectory {
+        // The crate numbers may have been handed out differently in
+        // this session, so map the old ones to the new ones. A crate
+        // that has changed in the meantime maps to nothing.
+        let current_krates = Self::krate_info(tcx);
+        let krate_map: Vec<Option<ast::CrateNum>> = {
+            let mut map = vec![];
+            for old_info in &self.krates {
+                let new_krate = current_krates.iter().find(|new_info| {
+                    new_info.name == old_info.name &&
+                        new_info.disambiguator == old_info.disambiguator
+                });
+                if let Some(new_info) = new_krate {
+                    if new_info.svh != old_info.svh {
+                        debug!("retrace: crate {} has changed", old_info.name);
+                        continue;
+                    }
+                    let old_krate = old_info.krate as usize;
+                    if old_krate >= map.len() {
+                        map.resize(old_krate + 1, None);
+                    }
+                    map[old_krate] = Some(new_info.krate);
+                }
+            }
+            map
+        };
+
         let ids = self.paths.iter()
-                            .map(|path| tcx.map.retrace_path(path))
+                            .map(|path| {
+                                if path.is_local() {
+                                    return tcx.retrace_path(path);
+                                }
+                                let new_krate = krate_map.get(path.krate as usize)
+                                                         .and_then(|&krate| krate);
+                                new_krate.and_then(|krate| {
+                                    let path = DefPath {
+                                        data: path.data.clone(),
+                                        krate: krate,
+                                    };
+                                    tcx.retrace_path(&path)
+                                })
+                            })
                             .collect();
         RetracedDefIdDirectory { ids: ids }
     }
@@ -74,22 +143,19 @@ impl<'a,'tcx> DefIdDirectoryBuilder<'a,'tcx> {
         DefIdDirectoryBuilder {
             tcx: tcx,
             hash: DefIdMap(),
-            directory: DefIdDirectory::new()
+            directory: DefIdDirectory::new(DefIdDirectory::krate_info(tcx))
         }
     }
 
     pub fn add(&mut self, def_id: DefId) -> Option<DefPathIndex> {
-        if !def_id.is_local() {
-            // FIXME(#32015) clarify story about cross-crate dep tracking
-            return None;
-        }
-
         let tcx = self.tcx;
         let paths = &mut self.directory.paths;
         self.hash.entry(def_id)
                  .or_insert_with(|| {
                      let def_path = tcx.def_path(def_id);
-                     if !def_path.is_local() {
+                     if def_id.is_local() && !def_path.is_local() {
+                         // Inlined items are tracked through the
+                         // crate they were inlined from.
                          return None;
                      }
                      let index = paths.len() as u32;
@@ -136,14 +136,17 @@ fn initial_dirty_nodes<'tcx>(tcx: &ty::TyCtxt<'tcx>,
     let mut items_removed = false;
     let mut dirty_nodes = FnvHashSet();
     for hashed_item in hashed_items {
-        match retraced.def_id(hashed_item.index) {
-            Some(def_id) => {
-                // FIXME(#32753) -- should we use a distinct hash here
-                let current_hash = tcx.calculate_item_hash(def_id);
+        match retraced.map(hashed_item.node) {
+            Some(dep_node) => {
+                let current_hash = match dep_node {
+                    DepNode::Hir(def_id) => tcx.calculate_item_hash(def_id),
+                    DepNode::HirBody(def_id) => tcx.calculate_body_hash(def_id),
+                    _ => bug!("unexpected hashed node {:?}", dep_node)
+                };
                 debug!("initial_dirty_nodes: hash of {:?} is {:?}, was {:?}",
-                       def_id, current_hash, hashed_item.hash);
+                       dep_node, current_hash, hashed_item.hash);
                 if current_hash != hashed_item.hash {
-                    dirty_nodes.insert(DepNode::Hir(def_id));
+                    dirty_nodes.insert(dep_node);
                 }
             }
             None => {
@@ -90,24 +90,25 @@ pub fn encode_dep_graph<'tcx>(tcx: &ty::TyCtxt<'tcx>,
     let hashes =
         query.nodes()
              .into_iter()
-             .filter_map(|dep_node| match dep_node {
-                 DepNode::Hir(def_id) => {
-                     assert!(def_id.is_local());
-                     builder.add(def_id)
-                            .map(|index| {
-                                // FIXME(#32753) -- should we use a distinct hash here
-                                let hash = tcx.calculate_item_hash(def_id);
-                                SerializedHash { index: index, hash: hash }
-                            })
-                 }
-                 _ => None
+             .filter_map(|dep_node| {
+                 let hash = match dep_node {
+                     DepNode::Hir(def_id) => {
+                         assert!(def_id.is_local());
+                         tcx.calculate_item_hash(def_id)
+                     }
+                     DepNode::HirBody(def_id) => {
+                         assert!(def_id.is_local());
+                         tcx.calculate_body_hash(def_id)
+                     }
+                     _ => return None
+                 };
+                 builder.map(dep_node)
+                        .map(|node| SerializedHash { node: node, hash: hash })
              })
              .collect();
 
     // Create the serialized dep-graph, dropping nodes that are
-    // from other crates or from inlined items.
-    //
-    // FIXME(#32015) fix handling of other crates
+    // from inlined items.
     let graph = SerializedDepGraph {
         nodes: query.nodes().into_iter()
                             .flat_map(|node| builder.map(node))
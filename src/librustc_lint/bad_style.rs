@@ -117,6 +117,9 @@ impl LateLintPass for NonCamelCaseTypes {
             hir::ItemTrait(..) => {
                 self.check_case(cx, "trait", it.name, it.span)
             }
+            hir::ItemTraitAlias(..) => {
+                self.check_case(cx, "trait alias", it.name, it.span)
+            }
             hir::ItemEnum(ref enum_definition, _) => {
                 if has_extern_repr {
                     return;
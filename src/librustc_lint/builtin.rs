@@ -202,8 +202,10 @@ impl LintPass for UnsafeCode {
 impl LateLintPass for UnsafeCode {
     fn check_expr(&mut self, cx: &LateContext, e: &hir::Expr) {
         if let hir::ExprBlock(ref blk) = e.node {
-            // Don't warn about generated blocks, that'll just pollute the output.
-            if blk.rules == hir::UnsafeBlock(hir::UserProvided) {
+            // Don't warn about generated blocks, that'll just pollute the output,
+            // nor about blocks that a macro encapsulates on its users' behalf.
+            if blk.rules == hir::UnsafeBlock(hir::UserProvided) &&
+               !cx.sess().codemap().span_allows_internal_unsafe(blk.span) {
                 cx.span_lint(UNSAFE_CODE, blk.span, "usage of an `unsafe` block");
             }
         }
@@ -375,6 +377,7 @@ impl LateLintPass for MissingDoc {
                 "a trait"
             },
             hir::ItemTy(..) => "a type alias",
+            hir::ItemTraitAlias(..) => "a trait alias",
             hir::ItemImpl(_, _, _, Some(ref trait_ref), _, ref impl_items) => {
                 // If the trait is private, add the impl items to private_traits so they don't get
                 // reported for missing docs.
@@ -1083,11 +1086,7 @@ impl LateLintPass for MutableTransmutes {
         }
 
         fn def_id_is_transmute(cx: &LateContext, def_id: DefId) -> bool {
-            match cx.tcx.lookup_item_type(def_id).ty.sty {
-                ty::TyFnDef(_, _, ref bfty) if bfty.abi == RustIntrinsic => (),
-                _ => return false
-            }
-            cx.tcx.item_name(def_id).as_str() == "transmute"
+            cx.tcx.is_diagnostic_item("transmute", def_id)
         }
     }
 }
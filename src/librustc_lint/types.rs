@@ -17,12 +17,13 @@ use rustc::ty::{self, Ty, TyCtxt};
 use middle::const_val::ConstVal;
 use rustc_const_eval::eval_const_expr_partial;
 use rustc_const_eval::EvalHint::ExprTypeChecked;
+use rustc_const_math::overflow::{int_ty_bits, uint_ty_bits, int_ty_range, uint_ty_range};
 use util::nodemap::{FnvHashSet};
 use lint::{LateContext, LintContext, LintArray};
 use lint::{LintPass, LateLintPass};
 
 use std::cmp;
-use std::{i8, i16, i32, i64, u8, u16, u32, u64, f32, f64};
+use std::{f32, f64};
 
 use syntax::ast;
 use syntax::abi::Abi;
@@ -163,12 +164,7 @@ impl LateLintPass for TypeLimits {
                         match lit.node {
                             ast::LitKind::Int(v, ast::LitIntType::Signed(_)) |
                             ast::LitKind::Int(v, ast::LitIntType::Unsuffixed) => {
-                                let int_type = if let ast::IntTy::Is = t {
-                                    cx.sess().target.int_type
-                                } else {
-                                    t
-                                };
-                                let (_, max) = int_ty_range(int_type);
+                                let (_, max) = int_ty_range(t, cx.sess().target.int_type);
                                 let negative = self.negated_expr_id == e.id;
 
                                 // Detect literal value out of range [min, max] inclusive
@@ -184,12 +180,7 @@ impl LateLintPass for TypeLimits {
                         };
                     },
                     ty::TyUint(t) => {
-                        let uint_type = if let ast::UintTy::Us = t {
-                            cx.sess().target.uint_type
-                        } else {
-                            t
-                        };
-                        let (min, max) = uint_ty_range(uint_type);
+                        let (min, max) = uint_ty_range(t, cx.sess().target.uint_type);
                         let lit_val: u64 = match lit.node {
                             // _v is u8, within range by definition
                             ast::LitKind::Byte(_v) => return,
@@ -246,28 +237,6 @@ impl LateLintPass for TypeLimits {
             })
         }
 
-        // for isize & usize, be conservative with the warnings, so that the
-        // warnings are consistent between 32- and 64-bit platforms
-        fn int_ty_range(int_ty: ast::IntTy) -> (i64, i64) {
-            match int_ty {
-                ast::IntTy::Is => (i64::MIN,        i64::MAX),
-                ast::IntTy::I8 =>    (i8::MIN  as i64, i8::MAX  as i64),
-                ast::IntTy::I16 =>   (i16::MIN as i64, i16::MAX as i64),
-                ast::IntTy::I32 =>   (i32::MIN as i64, i32::MAX as i64),
-                ast::IntTy::I64 =>   (i64::MIN,        i64::MAX)
-            }
-        }
-
-        fn uint_ty_range(uint_ty: ast::UintTy) -> (u64, u64) {
-            match uint_ty {
-                ast::UintTy::Us => (u64::MIN,         u64::MAX),
-                ast::UintTy::U8 =>    (u8::MIN   as u64, u8::MAX   as u64),
-                ast::UintTy::U16 =>   (u16::MIN  as u64, u16::MAX  as u64),
-                ast::UintTy::U32 =>   (u32::MIN  as u64, u32::MAX  as u64),
-                ast::UintTy::U64 =>   (u64::MIN,         u64::MAX)
-            }
-        }
-
         fn float_ty_range(float_ty: ast::FloatTy) -> (f64, f64) {
             match float_ty {
                 ast::FloatTy::F32 => (f32::MIN as f64, f32::MAX as f64),
@@ -275,26 +244,6 @@ impl LateLintPass for TypeLimits {
             }
         }
 
-        fn int_ty_bits(int_ty: ast::IntTy, target_int_ty: ast::IntTy) -> u64 {
-            match int_ty {
-                ast::IntTy::Is => int_ty_bits(target_int_ty, target_int_ty),
-                ast::IntTy::I8 => 8,
-                ast::IntTy::I16 => 16 as u64,
-                ast::IntTy::I32 => 32,
-                ast::IntTy::I64 => 64,
-            }
-        }
-
-        fn uint_ty_bits(uint_ty: ast::UintTy, target_uint_ty: ast::UintTy) -> u64 {
-            match uint_ty {
-                ast::UintTy::Us => uint_ty_bits(target_uint_ty, target_uint_ty),
-                ast::UintTy::U8 => 8,
-                ast::UintTy::U16 => 16,
-                ast::UintTy::U32 => 32,
-                ast::UintTy::U64 => 64,
-            }
-        }
-
         fn check_limits(tcx: &TyCtxt, binop: hir::BinOp,
                         l: &hir::Expr, r: &hir::Expr) -> bool {
             let (lit, expr, swap) = match (&l.node, &r.node) {
@@ -310,8 +259,10 @@ impl LateLintPass for TypeLimits {
                 binop
             };
             match tcx.node_id_to_type(expr.id).sty {
+                // For isize and usize, be conservative with the warnings, so
+                // that they are consistent between 32- and 64-bit platforms.
                 ty::TyInt(int_ty) => {
-                    let (min, max) = int_ty_range(int_ty);
+                    let (min, max) = int_ty_range(int_ty, ast::IntTy::I64);
                     let lit_val: i64 = match lit.node {
                         hir::ExprLit(ref li) => match li.node {
                             ast::LitKind::Int(v, ast::LitIntType::Signed(_)) |
@@ -323,7 +274,7 @@ impl LateLintPass for TypeLimits {
                     is_valid(norm_binop, lit_val, min, max)
                 }
                 ty::TyUint(uint_ty) => {
-                    let (min, max): (u64, u64) = uint_ty_range(uint_ty);
+                    let (min, max): (u64, u64) = uint_ty_range(uint_ty, ast::UintTy::U64);
                     let lit_val: u64 = match lit.node {
                         hir::ExprLit(ref li) => match li.node {
                             ast::LitKind::Int(v, _) => v,
@@ -585,7 +536,7 @@ impl<'a, 'tcx> ImproperCTypesVisitor<'a, 'tcx> {
                 FfiSafe
             }
 
-            ty::TyParam(..) | ty::TyInfer(..) | ty::TyError |
+            ty::TyParam(..) | ty::TyBound(..) | ty::TyInfer(..) | ty::TyError |
             ty::TyClosure(..) | ty::TyProjection(..) |
             ty::TyFnDef(..) => {
                 bug!("Unexpected type in foreign function")
@@ -126,8 +126,9 @@ impl LateLintPass for UnusedResults {
             _ => return
         };
 
-        if let hir::ExprRet(..) = expr.node {
-            return;
+        match expr.node {
+            hir::ExprRet(..) | hir::ExprBecome(..) => return,
+            _ => {}
         }
 
         let t = cx.tcx.expr_ty(&expr);
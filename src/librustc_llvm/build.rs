@@ -100,6 +100,10 @@ fn main() {
         }
         cfg.flag(flag);
     }
+    // Our own LLVM exposes a few more APIs than upstream LLVM does.
+    if env::var_os("LLVM_RUSTLLVM").is_some() {
+        cfg.define("LLVM_RUSTLLVM", None);
+    }
     cfg.file("../rustllvm/ExecutionEngineWrapper.cpp")
        .file("../rustllvm/PassWrapper.cpp")
        .file("../rustllvm/RustWrapper.cpp")
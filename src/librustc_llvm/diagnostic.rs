@@ -93,9 +93,31 @@ impl InlineAsmDiagnostic {
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct StackSizeDiagnostic {
+    pub function: ValueRef,
+    pub stack_size: u64,
+}
+
+impl StackSizeDiagnostic {
+    unsafe fn unpack(di: DiagnosticInfoRef) -> StackSizeDiagnostic {
+        let mut opt = StackSizeDiagnostic {
+            function: ptr::null_mut(),
+            stack_size: 0,
+        };
+
+        super::LLVMRustUnpackStackSizeDiagnostic(di,
+            &mut opt.function,
+            &mut opt.stack_size);
+
+        opt
+    }
+}
+
 pub enum Diagnostic {
     Optimization(OptimizationDiagnostic),
     InlineAsm(InlineAsmDiagnostic),
+    StackSize(StackSizeDiagnostic),
 
     /// LLVM has other types that we do not wrap here.
     UnknownDiagnostic(DiagnosticInfoRef),
@@ -109,6 +131,9 @@ impl Diagnostic {
             super::DK_InlineAsm
                 => InlineAsm(InlineAsmDiagnostic::unpack(di)),
 
+            super::DK_StackSize
+                => StackSize(StackSizeDiagnostic::unpack(di)),
+
             super::DK_OptimizationRemark
                 => Optimization(OptimizationDiagnostic::unpack(OptimizationRemark, di)),
 
@@ -44,6 +44,7 @@ pub use self::FileType::*;
 pub use self::MetadataType::*;
 pub use self::AsmDialect::*;
 pub use self::CodeGenOptLevel::*;
+pub use self::CodeGenOptSize::*;
 pub use self::RelocMode::*;
 pub use self::CodeGenModel::*;
 pub use self::DiagnosticKind::*;
@@ -375,6 +376,14 @@ pub enum CodeGenOptLevel {
     CodeGenLevelAggressive = 3,
 }
 
+#[derive(Copy, Clone, PartialEq)]
+#[repr(C)]
+pub enum CodeGenOptSize {
+    CodeGenOptSizeNone = 0,
+    CodeGenOptSizeDefault = 1,
+    CodeGenOptSizeAggressive = 2,
+}
+
 #[derive(Copy, Clone, PartialEq)]
 #[repr(C)]
 pub enum RelocMode {
@@ -473,6 +482,9 @@ pub type ObjectFileRef = *mut ObjectFile_opaque;
 pub enum SectionIterator_opaque {}
 pub type SectionIteratorRef = *mut SectionIterator_opaque;
 #[allow(missing_copy_implementations)]
+pub enum SymbolIterator_opaque {}
+pub type SymbolIteratorRef = *mut SymbolIterator_opaque;
+#[allow(missing_copy_implementations)]
 pub enum Pass_opaque {}
 pub type PassRef = *mut Pass_opaque;
 #[allow(missing_copy_implementations)]
@@ -599,6 +611,8 @@ extern {
 
     /// See Module::setModuleInlineAsm.
     pub fn LLVMSetModuleInlineAsm(M: ModuleRef, Asm: *const c_char);
+    /// See Module::appendModuleInlineAsm.
+    pub fn LLVMRustAppendModuleInlineAsm(M: ModuleRef, Asm: *const c_char);
 
     /// See llvm::LLVMTypeKind::getTypeID.
     pub fn LLVMGetTypeKind(Ty: TypeRef) -> TypeKind;
@@ -1042,6 +1056,7 @@ extern {
     /* Operations on call instructions (only) */
     pub fn LLVMIsTailCall(CallInst: ValueRef) -> Bool;
     pub fn LLVMSetTailCall(CallInst: ValueRef, IsTailCall: Bool);
+    pub fn LLVMRustSetMustTailCall(CallInst: ValueRef);
 
     /* Operations on load/store instructions (only) */
     pub fn LLVMGetVolatile(MemoryAccessInst: ValueRef) -> Bool;
@@ -1724,6 +1739,23 @@ extern {
     /// Returns the current section contents as a string buffer.
     pub fn LLVMGetSectionContents(SI: SectionIteratorRef) -> *const c_char;
 
+    /// Enumerates the symbols in an object file.
+    pub fn LLVMGetSymbols(ObjFile: ObjectFileRef) -> SymbolIteratorRef;
+    /// Destroys a symbol iterator.
+    pub fn LLVMDisposeSymbolIterator(SI: SymbolIteratorRef);
+    /// Returns true if the symbol iterator is at the end of the symbol
+    /// list:
+    pub fn LLVMIsSymbolIteratorAtEnd(ObjFile: ObjectFileRef,
+                                     SI: SymbolIteratorRef)
+                                     -> Bool;
+    /// Moves the symbol iterator to point to the next symbol.
+    pub fn LLVMMoveToNextSymbol(SI: SymbolIteratorRef);
+    /// Returns the name of the current symbol.
+    pub fn LLVMGetSymbolName(SI: SymbolIteratorRef) -> *const c_char;
+    /// Returns the size of the current symbol, where the object file
+    /// format records one (Mach-O does not).
+    pub fn LLVMGetSymbolSize(SI: SymbolIteratorRef) -> c_ulonglong;
+
     /// Reads the given file and returns it as a memory buffer. Use
     /// LLVMDisposeMemoryBuffer() to get rid of it.
     pub fn LLVMRustCreateMemoryBufferWithContentsOfFile(Path: *const c_char)
@@ -2024,6 +2056,19 @@ extern {
                                        FunctionSections: bool,
                                        DataSections: bool) -> TargetMachineRef;
     pub fn LLVMRustDisposeTargetMachine(T: TargetMachineRef);
+    pub fn LLVMRustGetTargetCPUsCount(T: TargetMachineRef) -> size_t;
+    pub fn LLVMRustGetTargetCPU(T: TargetMachineRef,
+                                Index: size_t,
+                                Name: *mut *const c_char,
+                                Desc: *mut *const c_char);
+    pub fn LLVMRustGetTargetFeaturesCount(T: TargetMachineRef) -> size_t;
+    pub fn LLVMRustGetTargetFeature(T: TargetMachineRef,
+                                    Index: size_t,
+                                    Name: *mut *const c_char,
+                                    Desc: *mut *const c_char);
+    pub fn LLVMRustHasTargetCPU(T: TargetMachineRef, CPU: *const c_char) -> bool;
+    pub fn LLVMRustHasTargetFeature(T: TargetMachineRef, Feature: *const c_char) -> bool;
+    pub fn LLVMRustIsTargetFeatureEnabled(T: TargetMachineRef, Feature: *const c_char) -> bool;
     pub fn LLVMRustAddAnalysisPasses(T: TargetMachineRef,
                                      PM: PassManagerRef,
                                      M: ModuleRef);
@@ -2091,6 +2136,9 @@ extern {
                                             cookie_out: *mut c_uint,
                                             message_out: *mut TwineRef,
                                             instruction_out: *mut ValueRef);
+    pub fn LLVMRustUnpackStackSizeDiagnostic(DI: DiagnosticInfoRef,
+                                             function_out: *mut ValueRef,
+                                             stack_size_out: *mut u64);
 
     pub fn LLVMWriteDiagnosticInfoToString(DI: DiagnosticInfoRef, s: RustStringRef);
     pub fn LLVMGetDiagInfoSeverity(DI: DiagnosticInfoRef) -> DiagnosticSeverity;
@@ -2287,6 +2335,28 @@ pub fn mk_section_iter(llof: ObjectFileRef) -> SectionIter {
     }
 }
 
+/* Memory-managed interface to symbol iterators. */
+
+pub struct SymbolIter {
+    pub llsi: SymbolIteratorRef
+}
+
+impl Drop for SymbolIter {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMDisposeSymbolIterator(self.llsi);
+        }
+    }
+}
+
+pub fn mk_symbol_iter(llof: ObjectFileRef) -> SymbolIter {
+    unsafe {
+        SymbolIter {
+            llsi: LLVMGetSymbols(llof)
+        }
+    }
+}
+
 /// Safe wrapper around `LLVMGetParam`, because segfaults are no fun.
 pub fn get_param(llfn: ValueRef, index: c_uint) -> ValueRef {
     unsafe {
@@ -379,6 +379,7 @@ impl tr for Def {
           }
           Def::Variant(e_did, v_did) => Def::Variant(e_did.tr(dcx), v_did.tr(dcx)),
           Def::Trait(did) => Def::Trait(did.tr(dcx)),
+          Def::TraitAlias(did) => Def::TraitAlias(did.tr(dcx)),
           Def::Enum(did) => Def::Enum(did.tr(dcx)),
           Def::TyAlias(did) => Def::TyAlias(did.tr(dcx)),
           Def::AssociatedTy(trait_did, did) =>
@@ -615,6 +616,12 @@ impl<'a, 'tcx> rbml_writer_helpers<'tcx> for Encoder<'a> {
                             |this| Ok(this.emit_auto_deref_ref(ecx, auto_deref_ref)))
                     })
                 }
+
+                adjustment::AdjustClosureFnPointer => {
+                    this.emit_enum_variant("AdjustClosureFnPointer", 5, 0, |_| {
+                        Ok(())
+                    })
+                }
             }
         });
     }
@@ -992,7 +999,8 @@ impl<'a, 'tcx> rbml_decoder_decoder_helpers<'tcx> for reader::Decoder<'a> {
                                     -> adjustment::AutoAdjustment<'tcx> {
         self.read_enum("AutoAdjustment", |this| {
             let variants = ["AdjustReifyFnPointer", "AdjustUnsafeFnPointer",
-                            "AdjustMutToConstPointer", "AdjustDerefRef"];
+                            "AdjustMutToConstPointer", "AdjustDerefRef",
+                            "AdjustClosureFnPointer"];
             this.read_enum_variant(&variants, |this, i| {
                 Ok(match i {
                     1 => adjustment::AdjustReifyFnPointer,
@@ -1005,6 +1013,7 @@ impl<'a, 'tcx> rbml_decoder_decoder_helpers<'tcx> for reader::Decoder<'a> {
 
                         adjustment::AdjustDerefRef(auto_deref_ref)
                     }
+                    5 => adjustment::AdjustClosureFnPointer,
                     _ => bug!("bad enum variant for adjustment::AutoAdjustment")
                 })
             })
@@ -193,7 +193,7 @@ pub const tag_struct_field: usize = 0x8a;
 
 pub const tag_items_data_item_struct_ctor: usize = 0x8b;
 pub const tag_attribute_is_sugared_doc: usize = 0x8c;
-// GAP 0x8d
+pub const tag_region_param_def_pure_wrt_drop: usize = 0x8d;
 pub const tag_items_data_region: usize = 0x8e;
 
 pub const tag_region_param_def: usize = 0x8f;
@@ -244,6 +244,29 @@ pub const tag_items_data_item_defaultness: usize = 0xa8;
 pub const tag_items_data_parent_impl: usize = 0xa9;
 
 pub const tag_rustc_version: usize = 0x10f;
+
+// Diagnostic items are a top-level directory, like language items:
+//
+// tag_diagnostic_items
+// - tag_diagnostic_items_item
+//   - tag_diagnostic_items_item_name: string
+//   - tag_diagnostic_items_item_index: u32
+pub const tag_diagnostic_items: usize = 0x114; // top-level only
+pub const tag_diagnostic_items_item: usize = 0xaa;
+pub const tag_diagnostic_items_item_name: usize = 0xab;
+pub const tag_diagnostic_items_item_index: usize = 0xac;
+
+// The def-path table maps the `DefPath::deterministic_hash` of each
+// item in the index to its `DefIndex`:
+//
+// tag_def_path_table
+// - tag_def_path_table_entry
+//   - tag_def_path_table_entry_hash: u64
+//   - tag_def_path_table_entry_index: u32
+pub const tag_def_path_table: usize = 0x115; // top-level only
+pub const tag_def_path_table_entry: usize = 0xad;
+pub const tag_def_path_table_entry_hash: usize = 0xae;
+pub const tag_def_path_table_entry_index: usize = 0xaf;
 pub fn rustc_version() -> String {
     format!(
         "rustc {}",
@@ -13,7 +13,7 @@
 //! Validates all used crates and extern libraries and loads their metadata
 
 use common::rustc_version;
-use cstore::{self, CStore, CrateSource, MetadataBlob};
+use cstore::{self, CStore, CrateSource, LoadCause, MetadataBlob};
 use decoder;
 use loader::{self, CratePaths};
 
@@ -26,9 +26,10 @@ use rustc::util::nodemap::FnvHashMap;
 use rustc::hir::map as hir_map;
 
 use std::cell::{RefCell, Cell};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::fs;
+use rustc_serialize::json;
 
 use syntax::ast;
 use syntax::abi::Abi;
@@ -77,6 +78,76 @@ fn dump_crates(cstore: &CStore) {
     })
 }
 
+#[derive(RustcEncodable)]
+struct CrateGraphNode {
+    cnum: ast::CrateNum,
+    name: String,
+    hash: String,
+    disambiguator: String,
+    dylib: Option<String>,
+    rlib: Option<String>,
+    explicitly_linked: bool,
+    dependencies: Vec<ast::CrateNum>,
+    /// The first entry is what caused the crate to be loaded; the rest
+    /// are later requests that resolved to the already loaded crate.
+    requested_by: Vec<CrateRequest>,
+}
+
+#[derive(RustcEncodable)]
+struct CrateRequest {
+    kind: &'static str,
+    span: Option<String>,
+    parent: Option<ast::CrateNum>,
+    /// Paths given for the crate with `--extern`, which override the
+    /// library search for `extern crate` items.
+    extern_flag: Option<Vec<String>>,
+}
+
+/// Prints the crate graph as JSON, for `-Z dump-crate-graph`.
+fn dump_crate_graph(sess: &Session, cstore: &CStore) {
+    let mut nodes = vec![];
+    cstore.iter_crate_data_origins(|cnum, data, opt_source| {
+        let extern_flag = sess.opts.externs.get(data.name()).cloned();
+        let requested_by = data.requested_by.borrow().iter().map(|&cause| {
+            let (kind, span, parent) = match cause {
+                LoadCause::ExternCrate(span) => ("extern crate", Some(span), None),
+                LoadCause::Extension(span) => ("extern crate (expansion)", Some(span), None),
+                LoadCause::Dependency(parent) => ("dependency", None, Some(parent)),
+                LoadCause::InjectedAllocator => ("injected allocator", None, None),
+                LoadCause::Inspection => ("inspection", None, None),
+            };
+            CrateRequest {
+                kind: kind,
+                span: span.map(|span| sess.codemap().span_to_string(span)),
+                parent: parent,
+                extern_flag: if span.is_some() { extern_flag.clone() } else { None },
+            }
+        }).collect();
+        let (dylib, rlib) = match opt_source {
+            Some(CrateSource { dylib, rlib, .. }) => {
+                (dylib.map(|dl| dl.0.display().to_string()),
+                 rlib.map(|rl| rl.0.display().to_string()))
+            }
+            None => (None, None),
+        };
+        let mut dependencies: Vec<_> = data.cnum_map.borrow().values().cloned().collect();
+        dependencies.sort();
+        nodes.push(CrateGraphNode {
+            cnum: cnum,
+            name: data.name().to_string(),
+            hash: data.hash().to_string(),
+            disambiguator: decoder::get_crate_disambiguator(data.data()).to_string(),
+            dylib: dylib,
+            rlib: rlib,
+            explicitly_linked: data.explicitly_linked.get(),
+            dependencies: dependencies,
+            requested_by: requested_by,
+        });
+    });
+    nodes.sort_by_key(|node| node.cnum);
+    println!("{}", json::as_pretty_json(&nodes));
+}
+
 fn should_link(i: &ast::Item) -> bool {
     !attr::contains_name(&i.attrs, "no_link")
 }
@@ -312,11 +383,17 @@ impl<'a> CrateReader<'a> {
                       name: &str,
                       span: Span,
                       lib: loader::Library,
-                      explicitly_linked: bool)
+                      explicitly_linked: bool,
+                      cause: LoadCause)
                       -> (ast::CrateNum, Rc<cstore::crate_metadata>,
                           cstore::CrateSource) {
         self.verify_rustc_version(name, span, &lib.metadata);
-        self.verify_no_symbol_conflicts(span, &lib.metadata);
+        match cause {
+            // Two builds of the same crate may well be inspected side by
+            // side; as they are not linked, their symbols cannot conflict.
+            LoadCause::Inspection => {}
+            _ => self.verify_no_symbol_conflicts(span, &lib.metadata),
+        }
 
         // Claim this crate number and cache it
         let cnum = self.next_crate_num;
@@ -337,20 +414,31 @@ impl<'a> CrateReader<'a> {
 
         let loader::Library { dylib, rlib, metadata } = lib;
 
-        let cnum_map = self.resolve_crate_deps(root, metadata.as_slice(), span);
-        let staged_api = self.is_staged_api(metadata.as_slice());
+        // Decoding the def-path table can start on a worker thread while
+        // we go on loading the dependencies of this crate.
+        let def_path_table = match self.sess.opts.debugging_opts.metadata_threads {
+            0 => cstore::DefPathTableState::Unloaded,
+            threads => self.cstore.decode_in_background(metadata.as_slice(), threads),
+        };
+
+        // The dependencies have to be loaded right away, since the crate
+        // numbers they get are needed to translate the def-ids of this
+        // crate; everything else is decoded on first use.
+        let cnum_map = self.resolve_crate_deps(root, metadata.as_slice(), cnum, span);
 
         let cmeta = Rc::new(cstore::crate_metadata {
             name: name.to_string(),
             extern_crate: Cell::new(None),
-            index: decoder::load_index(metadata.as_slice()),
-            xref_index: decoder::load_xrefs(metadata.as_slice()),
+            index: Cell::new(None),
+            xref_index: Cell::new(None),
+            def_path_table: RefCell::new(def_path_table),
             data: metadata,
             cnum_map: RefCell::new(cnum_map),
             cnum: cnum,
             codemap_import_info: RefCell::new(vec![]),
-            staged_api: staged_api,
+            staged_api: Cell::new(None),
             explicitly_linked: Cell::new(explicitly_linked),
+            requested_by: RefCell::new(vec![cause]),
         });
 
         let source = cstore::CrateSource {
@@ -364,16 +452,6 @@ impl<'a> CrateReader<'a> {
         (cnum, cmeta, source)
     }
 
-    fn is_staged_api(&self, data: &[u8]) -> bool {
-        let attrs = decoder::get_crate_attributes(data);
-        for attr in &attrs {
-            if attr.name() == "stable" || attr.name() == "unstable" {
-                return true
-            }
-        }
-        false
-    }
-
     fn resolve_crate(&mut self,
                      root: &Option<CratePaths>,
                      ident: &str,
@@ -381,7 +459,8 @@ impl<'a> CrateReader<'a> {
                      hash: Option<&Svh>,
                      span: Span,
                      kind: PathKind,
-                     explicitly_linked: bool)
+                     explicitly_linked: bool,
+                     cause: LoadCause)
                      -> (ast::CrateNum, Rc<cstore::crate_metadata>, cstore::CrateSource) {
         enum LookupResult {
             Previous(ast::CrateNum),
@@ -430,11 +509,12 @@ impl<'a> CrateReader<'a> {
                 if explicitly_linked && !data.explicitly_linked.get() {
                     data.explicitly_linked.set(explicitly_linked);
                 }
+                data.requested_by.borrow_mut().push(cause);
                 (cnum, data, self.cstore.used_crate_source(cnum))
             }
             LookupResult::Loaded(library) => {
                 self.register_crate(root, ident, name, span, library,
-                                    explicitly_linked)
+                                    explicitly_linked, cause)
             }
         }
     }
@@ -473,6 +553,7 @@ impl<'a> CrateReader<'a> {
     fn resolve_crate_deps(&mut self,
                           root: &Option<CratePaths>,
                           cdata: &[u8],
+                          parent: ast::CrateNum,
                           span : Span)
                           -> cstore::cnum_map {
         debug!("resolving deps of external crate");
@@ -486,7 +567,8 @@ impl<'a> CrateReader<'a> {
                                                         Some(&dep.hash),
                                                         span,
                                                         PathKind::Dependency,
-                                                        dep.explicitly_linked);
+                                                        dep.explicitly_linked,
+                                                        LoadCause::Dependency(parent));
             (dep.cnum, local_cnum)
         }).collect()
     }
@@ -537,7 +619,7 @@ impl<'a> CrateReader<'a> {
             // Register crate now to avoid double-reading metadata
             let (_, cmd, _) = self.register_crate(&None, &info.ident,
                                                   &info.name, span, library,
-                                                  true);
+                                                  true, LoadCause::Extension(span));
             PMDSource::Registered(cmd)
         } else {
             // Not registering the crate; just hold on to the metadata
@@ -551,6 +633,20 @@ impl<'a> CrateReader<'a> {
         }
     }
 
+    /// Loads the library at `path` just to inspect its metadata. Unlike
+    /// with `extern crate`, the library is not looked up by name, and
+    /// nothing stops two builds of the same crate from being loaded.
+    pub fn load_crate_for_inspection(&mut self, path: &Path, span: Span) -> ast::CrateNum {
+        let library = match loader::load_file(&self.sess.target.target, path) {
+            Ok(library) => library,
+            Err(msg) => self.sess.span_fatal(span, &msg),
+        };
+        let name = decoder::get_crate_name(library.metadata.as_slice()).to_string();
+        let (cnum, _, _) = self.register_crate(&None, &name, &name, span, library,
+                                               false, LoadCause::Inspection);
+        cnum
+    }
+
     /// Read exported macros.
     pub fn read_exported_macros(&mut self, item: &ast::Item) -> Vec<ast::MacroDef> {
         let ci = self.extract_crate_info(item).unwrap();
@@ -717,7 +813,8 @@ impl<'a> CrateReader<'a> {
         };
         let (cnum, data, _) = self.resolve_crate(&None, name, name, None,
                                                  codemap::DUMMY_SP,
-                                                 PathKind::Crate, false);
+                                                 PathKind::Crate, false,
+                                                 LoadCause::InjectedAllocator);
 
         // To ensure that the `-Z allocation-crate=foo` option isn't abused, and
         // to ensure that the allocator is indeed an allocator, we verify that
@@ -804,6 +901,9 @@ impl<'a, 'b> LocalCrateReader<'a, 'b> {
         if log_enabled!(log::INFO) {
             dump_crates(&self.cstore);
         }
+        if self.sess.opts.debugging_opts.dump_crate_graph {
+            dump_crate_graph(self.sess, &self.cstore);
+        }
 
         for &(ref name, kind) in &self.sess.opts.libs {
             register_native_lib(self.sess, self.cstore, None, name.clone(), kind);
@@ -829,13 +929,15 @@ impl<'a, 'b> LocalCrateReader<'a, 'b> {
 
                 match self.creader.extract_crate_info_hir(i) {
                     Some(info) => {
+                        let cause = LoadCause::ExternCrate(i.span);
                         let (cnum, _, _) = self.creader.resolve_crate(&None,
                                                                           &info.ident,
                                                                           &info.name,
                                                                           None,
                                                                           i.span,
                                                                           PathKind::Crate,
-                                                                          true);
+                                                                          true,
+                                                                          cause);
                         let def_id = self.ast_map.local_def_id(i.id);
 
                         let len = self.ast_map.def_path(def_id).data.len();
@@ -291,9 +291,19 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         decoder::get_missing_lang_items(&cdata)
     }
 
+    fn diagnostic_items(&self, cnum: ast::CrateNum) -> Vec<(DefIndex, ast::Name)>
+    {
+        let mut result = vec![];
+        let crate_data = self.get_crate_data(cnum);
+        decoder::each_diagnostic_item(&crate_data, |index, name| {
+            result.push((index, name)); true
+        });
+        result
+    }
+
     fn is_staged_api(&self, cnum: ast::CrateNum) -> bool
     {
-        self.get_crate_data(cnum).staged_api
+        self.get_crate_data(cnum).is_staged_api()
     }
 
     fn is_explicitly_linked(&self, cnum: ast::CrateNum) -> bool
@@ -365,6 +375,12 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         decoder::get_reachable_ids(&cdata)
     }
 
+    fn crate_trait_impls(&self, cnum: ast::CrateNum) -> Vec<DefId>
+    {
+        let cdata = self.get_crate_data(cnum);
+        decoder::get_trait_impls(&cdata)
+    }
+
     /// Returns the `DefKey` for a given `DefId`. This indicates the
     /// parent `DefId` as well as some idea of what kind of data the
     /// `DefId` refers to.
@@ -378,6 +394,19 @@ impl<'tcx> CrateStore<'tcx> for cstore::CStore {
         decoder::def_path(&cdata, def.index)
     }
 
+    fn retrace_path(&self, cnum: ast::CrateNum, path: &hir_map::DefPath) -> Option<DefId> {
+        let cdata = self.get_crate_data(cnum);
+        cdata.def_path_table().lookup(path).and_then(|index| {
+            // The table is keyed by hash, so make sure that we found
+            // the item we were looking for.
+            if decoder::def_path(&cdata, index).data == path.data {
+                Some(DefId { krate: cnum, index: index })
+            } else {
+                None
+            }
+        })
+    }
+
     fn variant_kind(&self, def_id: DefId) -> Option<VariantKind> {
         let cdata = self.get_crate_data(def_id.krate);
         decoder::get_variant_kind(&cdata, def_id.index)
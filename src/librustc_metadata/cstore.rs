@@ -22,15 +22,18 @@ use loader;
 
 use rustc::hir::def_id::DefId;
 use rustc::hir::svh::Svh;
+use rustc::hir::map as hir_map;
 use rustc::middle::cstore::{ExternCrate};
 use rustc::util::nodemap::{FnvHashMap, NodeMap, NodeSet, DefIdMap};
 
 use std::cell::{RefCell, Ref, Cell};
 use std::rc::Rc;
 use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 use flate::Bytes;
 use syntax::ast;
-use syntax::attr;
+use syntax::attr::{self, AttrMetaMethods};
 use syntax::codemap;
 use syntax::parse::token::IdentInterner;
 
@@ -72,16 +75,90 @@ pub struct crate_metadata {
     pub cnum_map: RefCell<cnum_map>,
     pub cnum: ast::CrateNum,
     pub codemap_import_info: RefCell<Vec<ImportedFileMap>>,
-    pub staged_api: bool,
+    /// Whether the crate uses the stability attributes, computed on
+    /// first use. See `is_staged_api`.
+    pub staged_api: Cell<Option<bool>>,
 
-    pub index: index::Index,
-    pub xref_index: index::DenseIndex,
+    /// See `index()` and `xref_index()`, which locate these on first use.
+    pub index: Cell<Option<index::Index>>,
+    pub xref_index: Cell<Option<index::DenseIndex>>,
+    /// See `def_path_table()`, which should be used to read this.
+    pub def_path_table: RefCell<DefPathTableState>,
 
     /// Flag if this crate is required by an rlib version of this crate, or in
     /// other words whether it was explicitly linked to. An example of a crate
     /// where this is false is when an allocator crate is injected into the
     /// dependency list, and therefore isn't actually needed to link an rlib.
     pub explicitly_linked: Cell<bool>,
+
+    /// Every request that resolved to this crate, in order. The first
+    /// one is what caused the crate to be loaded.
+    pub requested_by: RefCell<Vec<LoadCause>>,
+}
+
+/// Why the crate reader was asked for a crate.
+#[derive(Copy, Clone, Debug)]
+pub enum LoadCause {
+    /// An `extern crate` item of the local crate.
+    ExternCrate(codemap::Span),
+    /// An `extern crate` item whose macros or plugin registrar were
+    /// loaded during expansion.
+    Extension(codemap::Span),
+    /// A dependency recorded in the metadata of the given crate.
+    Dependency(ast::CrateNum),
+    /// The allocator crate injected into this compilation.
+    InjectedAllocator,
+    /// A library given by path, loaded only to inspect its metadata,
+    /// as by `-Z diff-metadata`. Such crates are never linked.
+    Inspection,
+}
+
+/// How far the def-path table of a crate has been decoded. Decoding it
+/// is among the costlier parts of loading a crate, and many crates never
+/// have a path looked up in them, so it is put off until first use or
+/// handed to a metadata worker thread (see `-Z metadata-threads`).
+pub enum DefPathTableState {
+    /// Not decoded yet; the first lookup decodes it.
+    Unloaded,
+    /// Being decoded by a metadata worker, which sends it here when done.
+    Decoding(mpsc::Receiver<hir_map::DefPathTable>),
+    Loaded(hir_map::DefPathTable),
+}
+
+/// Threads that decode the parts of crate metadata which need no type
+/// context, so that the dependencies of a crate are not decoded one after
+/// another on the main thread. The threads exit once the pool is dropped
+/// and its queue is drained.
+struct MetadataWorkers {
+    jobs: mpsc::Sender<DecodeJob>,
+}
+
+struct DecodeJob {
+    /// A copy of the def-path table document of the crate.
+    def_path_table: Vec<u8>,
+    result: mpsc::Sender<hir_map::DefPathTable>,
+}
+
+impl MetadataWorkers {
+    fn new(threads: usize) -> MetadataWorkers {
+        let (tx, rx) = mpsc::channel::<DecodeJob>();
+        let rx = Arc::new(Mutex::new(rx));
+        for i in 0..threads {
+            let rx = rx.clone();
+            thread::Builder::new().name(format!("metadata-{}", i)).spawn(move || {
+                loop {
+                    let job = match rx.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+                    let table = decoder::decode_def_path_table(&job.def_path_table);
+                    // The crate may have been dropped in the meantime.
+                    let _ = job.result.send(table);
+                }
+            }).unwrap();
+        }
+        MetadataWorkers { jobs: tx }
+    }
 }
 
 pub struct CStore {
@@ -94,6 +171,7 @@ pub struct CStore {
     statically_included_foreign_items: RefCell<NodeSet>,
     pub intr: Rc<IdentInterner>,
     pub visible_parent_map: RefCell<DefIdMap<DefId>>,
+    metadata_workers: RefCell<Option<MetadataWorkers>>,
 }
 
 impl CStore {
@@ -107,6 +185,27 @@ impl CStore {
             intr: intr,
             statically_included_foreign_items: RefCell::new(NodeSet()),
             visible_parent_map: RefCell::new(FnvHashMap()),
+            metadata_workers: RefCell::new(None),
+        }
+    }
+
+    /// Hands the def-path table of the crate in `data` to one of `threads`
+    /// metadata workers, starting them if this is the first crate to be
+    /// decoded in the background.
+    pub fn decode_in_background(&self, data: &[u8], threads: usize) -> DefPathTableState {
+        let table = match decoder::def_path_table_data(data) {
+            Some(table) => table.to_vec(),
+            None => return DefPathTableState::Unloaded,
+        };
+        let mut workers = self.metadata_workers.borrow_mut();
+        if workers.is_none() {
+            *workers = Some(MetadataWorkers::new(threads));
+        }
+        let (tx, rx) = mpsc::channel();
+        let job = DecodeJob { def_path_table: table, result: tx };
+        match workers.as_ref().unwrap().jobs.send(job) {
+            Ok(()) => DefPathTableState::Decoding(rx),
+            Err(_) => DefPathTableState::Unloaded,
         }
     }
 
@@ -272,6 +371,60 @@ impl crate_metadata {
         }
     }
 
+    /// The def-path table of the crate, waiting for the metadata worker
+    /// decoding it or decoding it on this thread as needed.
+    pub fn def_path_table(&self) -> Ref<hir_map::DefPathTable> {
+        let loaded = match *self.def_path_table.borrow() {
+            DefPathTableState::Loaded(_) => true,
+            _ => false,
+        };
+        if !loaded {
+            let mut state = self.def_path_table.borrow_mut();
+            let decoded = match *state {
+                // A worker that panicked never sends the table, so fall
+                // back to decoding it here.
+                DefPathTableState::Decoding(ref rx) => rx.recv().ok(),
+                _ => None,
+            };
+            let table = decoded.unwrap_or_else(|| decoder::load_def_path_table(self.data()));
+            *state = DefPathTableState::Loaded(table);
+        }
+        Ref::map(self.def_path_table.borrow(), |state| match *state {
+            DefPathTableState::Loaded(ref table) => table,
+            _ => bug!("def-path table of `{}` not loaded", self.name),
+        })
+    }
+
+    pub fn index(&self) -> index::Index {
+        if let Some(index) = self.index.get() {
+            return index;
+        }
+        let index = decoder::load_index(self.data());
+        self.index.set(Some(index));
+        index
+    }
+
+    pub fn xref_index(&self) -> index::DenseIndex {
+        if let Some(xref_index) = self.xref_index.get() {
+            return xref_index;
+        }
+        let xref_index = decoder::load_xrefs(self.data());
+        self.xref_index.set(Some(xref_index));
+        xref_index
+    }
+
+    pub fn is_staged_api(&self) -> bool {
+        if let Some(staged_api) = self.staged_api.get() {
+            return staged_api;
+        }
+        let attrs = decoder::get_crate_attributes(self.data());
+        let staged_api = attrs.iter().any(|attr| {
+            attr.name() == "stable" || attr.name() == "unstable"
+        });
+        self.staged_api.set(Some(staged_api));
+        staged_api
+    }
+
     pub fn is_allocator(&self) -> bool {
         let attrs = decoder::get_crate_attributes(self.data());
         attr::contains_name(&attrs, "allocator")
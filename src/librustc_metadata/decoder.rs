@@ -64,7 +64,7 @@ pub type Cmd<'a> = &'a crate_metadata;
 
 impl crate_metadata {
     fn get_item(&self, item_id: DefIndex) -> Option<rbml::Doc> {
-        self.index.lookup_item(self.data(), item_id).map(|pos| {
+        self.index().lookup_item(self.data(), item_id).map(|pos| {
             reader::doc_at(self.data(), pos as usize).unwrap().doc
         })
     }
@@ -92,6 +92,28 @@ pub fn load_xrefs(data: &[u8]) -> index::DenseIndex {
     index::DenseIndex::from_buf(index.data, index.start, index.end)
 }
 
+pub fn load_def_path_table(data: &[u8]) -> hir_map::DefPathTable {
+    decode_def_path_table(def_path_table_data(data).unwrap_or(&[]))
+}
+
+/// The contents of the def-path table document, which can be decoded
+/// on their own (and so on another thread) by `decode_def_path_table`.
+pub fn def_path_table_data(data: &[u8]) -> Option<&[u8]> {
+    reader::maybe_get_doc(rbml::Doc::new(data), tag_def_path_table)
+        .map(|table_doc| &table_doc.data[table_doc.start..table_doc.end])
+}
+
+pub fn decode_def_path_table(table: &[u8]) -> hir_map::DefPathTable {
+    let mut def_path_table = hir_map::DefPathTable::new();
+    for entry_doc in reader::tagged_docs(rbml::Doc::new(table), tag_def_path_table_entry) {
+        let hash_doc = reader::get_doc(entry_doc, tag_def_path_table_entry_hash);
+        let index_doc = reader::get_doc(entry_doc, tag_def_path_table_entry_index);
+        def_path_table.insert(reader::doc_as_u64(hash_doc),
+                              DefIndex::from_u32(reader::doc_as_u32(index_doc)));
+    }
+    def_path_table
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Family {
     ImmStatic,             // c
@@ -107,6 +129,7 @@ enum Family {
     Impl,                  // i
     DefaultImpl,           // d
     Trait,                 // I
+    TraitAlias,            // A
     Struct(VariantKind),   // S, s, u
     PublicField,           // g
     InheritedField,        // N
@@ -132,6 +155,7 @@ fn item_family(item: rbml::Doc) -> Family {
       'i' => Impl,
       'd' => DefaultImpl,
       'I' => Trait,
+      'A' => TraitAlias,
       'S' => Struct(VariantKind::Struct),
       's' => Struct(VariantKind::Tuple),
       'u' => Struct(VariantKind::Unit),
@@ -314,6 +338,7 @@ fn item_to_def_like(cdata: Cmd, item: rbml::Doc, did: DefId) -> DefLike {
             DlDef(Def::Variant(enum_did, did))
         }
         Trait => DlDef(Def::Trait(did)),
+        TraitAlias => DlDef(Def::TraitAlias(did)),
         Enum => DlDef(Def::Enum(did)),
         Impl | DefaultImpl => DlImpl(did),
         PublicField | InheritedField => DlField,
@@ -360,11 +385,15 @@ pub fn get_trait_def<'tcx>(cdata: Cmd,
     let associated_type_names = parse_associated_type_names(item_doc);
     let paren_sugar = parse_paren_sugar(item_doc);
 
-    ty::TraitDef::new(unsafety,
-                      paren_sugar,
-                      generics,
-                      item_trait_ref(item_doc, tcx, cdata),
-                      associated_type_names)
+    let trait_def = ty::TraitDef::new(unsafety,
+                                      paren_sugar,
+                                      generics,
+                                      item_trait_ref(item_doc, tcx, cdata),
+                                      associated_type_names);
+    if item_family(item_doc) == TraitAlias {
+        trait_def.set_is_alias();
+    }
+    trait_def
 }
 
 pub fn get_adt_def<'tcx>(intr: &IdentInterner,
@@ -635,6 +664,25 @@ pub fn each_lang_item<F>(cdata: Cmd, mut f: F) -> bool where
     })
 }
 
+/// Iterates over the diagnostic items in the given crate.
+pub fn each_diagnostic_item<F>(cdata: Cmd, mut f: F) -> bool where
+    F: FnMut(DefIndex, ast::Name) -> bool,
+{
+    let root = rbml::Doc::new(cdata.data());
+    let diagnostic_items = match reader::maybe_get_doc(root, tag_diagnostic_items) {
+        Some(doc) => doc,
+        None => return true,
+    };
+    reader::tagged_docs(diagnostic_items, tag_diagnostic_items_item).all(|item_doc| {
+        let name_doc = reader::get_doc(item_doc, tag_diagnostic_items_item_name);
+        let name = token::intern(name_doc.as_str_slice());
+        let index_doc = reader::get_doc(item_doc, tag_diagnostic_items_item_index);
+        let index = DefIndex::from_u32(reader::doc_as_u32(index_doc));
+
+        f(index, name)
+    })
+}
+
 fn each_child_of_item_or_crate<F, G>(intr: Rc<IdentInterner>,
                                      cdata: Cmd,
                                      item_doc: rbml::Doc,
@@ -1439,6 +1487,15 @@ pub fn each_implementation_for_trait<F>(cdata: Cmd,
     }
 }
 
+pub fn get_trait_impls(cdata: Cmd) -> Vec<DefId> {
+    let impls_doc = reader::get_doc(rbml::Doc::new(cdata.data()), tag_impls);
+    reader::tagged_docs(impls_doc, tag_impls_trait).flat_map(|trait_doc| {
+        reader::tagged_docs(trait_doc, tag_impls_trait_impl).map(move |impl_doc| {
+            translated_def_id(cdata, impl_doc)
+        })
+    }).collect()
+}
+
 pub fn get_trait_of_item(cdata: Cmd, id: DefIndex, tcx: &TyCtxt)
                          -> Option<DefId> {
     let item_doc = cdata.lookup_item(id);
@@ -1641,6 +1698,9 @@ fn doc_generics<'tcx>(base_doc: rbml::Doc,
         let doc = reader::get_doc(rp_doc, tag_region_param_def_index);
         let index = reader::doc_as_u64(doc) as u32;
 
+        let doc = reader::get_doc(rp_doc, tag_region_param_def_pure_wrt_drop);
+        let pure_wrt_drop = reader::doc_as_u8(doc) != 0;
+
         let bounds = reader::tagged_docs(rp_doc, tag_items_data_region).map(|p| {
             TyDecoder::with_doc(tcx, cdata.cnum, p,
                                 &mut |did| translate_def_id(cdata, did))
@@ -1651,7 +1711,8 @@ fn doc_generics<'tcx>(base_doc: rbml::Doc,
                                                      def_id: def_id,
                                                      space: space,
                                                      index: index,
-                                                     bounds: bounds });
+                                                     bounds: bounds,
+                                                     pure_wrt_drop: pure_wrt_drop });
     }
 
     ty::Generics { types: types, regions: regions }
@@ -1662,7 +1723,7 @@ fn doc_predicate<'tcx>(cdata: Cmd,
                        tcx: &TyCtxt<'tcx>)
                        -> ty::Predicate<'tcx>
 {
-    let predicate_pos = cdata.xref_index.lookup(
+    let predicate_pos = cdata.xref_index().lookup(
         cdata.data(), reader::doc_as_u32(doc)).unwrap() as usize;
     TyDecoder::new(
         cdata.data(), cdata.cnum, predicate_pos, tcx,
@@ -1697,7 +1758,8 @@ fn doc_predicates<'tcx>(base_doc: rbml::Doc,
 
 pub fn is_defaulted_trait(cdata: Cmd, trait_id: DefIndex) -> bool {
     let trait_doc = cdata.lookup_item(trait_id);
-    assert!(item_family(trait_doc) == Family::Trait);
+    assert!(item_family(trait_doc) == Family::Trait ||
+            item_family(trait_doc) == Family::TraitAlias);
     let defaulted_doc = reader::get_doc(trait_doc, tag_defaulted_trait);
     reader::doc_as_u8(defaulted_doc) != 0
 }
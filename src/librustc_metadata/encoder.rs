@@ -23,7 +23,7 @@ use index::{self, IndexData};
 
 use middle::cstore::{LOCAL_CRATE, CrateStore, InlinedItemRef, LinkMeta, tls};
 use rustc::hir::def;
-use rustc::hir::def_id::{CRATE_DEF_INDEX, DefId};
+use rustc::hir::def_id::{CRATE_DEF_INDEX, DefId, DefIndex};
 use middle::dependency_format::Linkage;
 use middle::stability;
 use rustc::ty::subst;
@@ -564,6 +564,9 @@ fn encode_generics<'a, 'tcx>(rbml_w: &mut Encoder,
         rbml_w.wr_tagged_u64(tag_region_param_def_index,
                              param.index as u64);
 
+        rbml_w.wr_tagged_u8(tag_region_param_def_pure_wrt_drop,
+                            param.pure_wrt_drop as u8);
+
         for &bound_region in &param.bounds {
             encode_region(ecx, rbml_w, bound_region);
         }
@@ -1329,6 +1332,32 @@ fn encode_info_for_item<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
             rbml_w.end_tag();
         }
       }
+      hir::ItemTraitAlias(..) => {
+        index.record(def_id, rbml_w);
+        rbml_w.start_tag(tag_items_data_item);
+        encode_def_id_and_key(ecx, rbml_w, def_id);
+        encode_family(rbml_w, 'A');
+        encode_item_variances(rbml_w, ecx, item.id);
+        let trait_def = tcx.lookup_trait_def(def_id);
+        let trait_predicates = tcx.lookup_predicates(def_id);
+        encode_unsafety(rbml_w, trait_def.unsafety);
+        encode_paren_sugar(rbml_w, trait_def.paren_sugar);
+        encode_defaulted(rbml_w, false);
+        encode_associated_type_names(rbml_w, &trait_def.associated_type_names);
+        encode_generics(rbml_w, ecx, index,
+                        &trait_def.generics, &trait_predicates,
+                        tag_item_generics);
+        encode_predicates(rbml_w, ecx, index,
+                          &tcx.lookup_super_predicates(def_id),
+                          tag_item_super_predicates);
+        encode_trait_ref(rbml_w, ecx, trait_def.trait_ref, tag_item_trait_ref);
+        encode_name(rbml_w, item.name);
+        encode_attributes(rbml_w, &item.attrs);
+        encode_visibility(rbml_w, vis);
+        encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
+        rbml_w.end_tag();
+      }
       hir::ItemExternCrate(_) | hir::ItemUse(_) => {
         // these are encoded separately
       }
@@ -1471,6 +1500,28 @@ fn encode_item_index(rbml_w: &mut Encoder, index: IndexData) {
     rbml_w.end_tag();
 }
 
+/// Writes the `DefPathTable` of the items in `index`, so that other
+/// crates can find our items by `DefPath`. Inlined items are left out,
+/// since their paths belong to the crate they were inlined from.
+fn encode_def_path_table(ecx: &EncodeContext, rbml_w: &mut Encoder, index: &IndexData) {
+    rbml_w.start_tag(tag_def_path_table);
+    for i in 0..ecx.tcx.map.num_local_def_ids() {
+        let def_index = DefIndex::new(i);
+        if !index.contains(def_index) {
+            continue;
+        }
+        let def_id = DefId::local(def_index);
+        if !ecx.tcx.map.def_path(def_id).is_local() {
+            continue;
+        }
+        rbml_w.start_tag(tag_def_path_table_entry);
+        rbml_w.wr_tagged_u64(tag_def_path_table_entry_hash, ecx.tcx.map.def_path_hash(def_id));
+        rbml_w.wr_tagged_u32(tag_def_path_table_entry_index, def_index.as_u32());
+        rbml_w.end_tag();
+    }
+    rbml_w.end_tag();
+}
+
 fn encode_meta_item(rbml_w: &mut Encoder, mi: &ast::MetaItem) {
     match mi.node {
       ast::MetaItemKind::Word(ref name) => {
@@ -1599,6 +1650,21 @@ fn encode_lang_items(ecx: &EncodeContext, rbml_w: &mut Encoder) {
     rbml_w.end_tag();   // tag_lang_items
 }
 
+fn encode_diagnostic_items(ecx: &EncodeContext, rbml_w: &mut Encoder) {
+    rbml_w.start_tag(tag_diagnostic_items);
+
+    for (name, def_id) in ecx.tcx.diagnostic_items.iter() {
+        if def_id.is_local() {
+            rbml_w.start_tag(tag_diagnostic_items_item);
+            rbml_w.wr_tagged_str(tag_diagnostic_items_item_name, &name.as_str());
+            rbml_w.wr_tagged_u32(tag_diagnostic_items_item_index, def_id.index.as_u32());
+            rbml_w.end_tag();
+        }
+    }
+
+    rbml_w.end_tag();   // tag_diagnostic_items
+}
+
 fn encode_native_libraries(ecx: &EncodeContext, rbml_w: &mut Encoder) {
     rbml_w.start_tag(tag_native_libraries);
 
@@ -1881,6 +1947,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
         attr_bytes: u64,
         dep_bytes: u64,
         lang_item_bytes: u64,
+        diagnostic_item_bytes: u64,
         native_lib_bytes: u64,
         plugin_registrar_fn_bytes: u64,
         codemap_bytes: u64,
@@ -1897,6 +1964,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
         attr_bytes: 0,
         dep_bytes: 0,
         lang_item_bytes: 0,
+        diagnostic_item_bytes: 0,
         native_lib_bytes: 0,
         plugin_registrar_fn_bytes: 0,
         codemap_bytes: 0,
@@ -1930,6 +1998,11 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
     encode_lang_items(&ecx, rbml_w);
     stats.lang_item_bytes = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap() - i;
 
+    // Encode the diagnostic items.
+    i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
+    encode_diagnostic_items(&ecx, rbml_w);
+    stats.diagnostic_item_bytes = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap() - i;
+
     // Encode the native libraries used
     i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
     encode_native_libraries(&ecx, rbml_w);
@@ -1969,6 +2042,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
     rbml_w.end_tag();
 
     i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
+    encode_def_path_table(&ecx, rbml_w, &index.items);
     encode_item_index(rbml_w, index.items);
     stats.index_bytes = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap() - i;
 
@@ -1991,6 +2065,7 @@ fn encode_metadata_inner(rbml_w: &mut Encoder,
         println!("       attribute bytes: {}", stats.attr_bytes);
         println!("             dep bytes: {}", stats.dep_bytes);
         println!("       lang item bytes: {}", stats.lang_item_bytes);
+        println!(" diagnostic item bytes: {}", stats.diagnostic_item_bytes);
         println!("          native bytes: {}", stats.native_lib_bytes);
         println!("plugin registrar bytes: {}", stats.plugin_registrar_fn_bytes);
         println!("         codemap bytes: {}", stats.codemap_bytes);
@@ -22,6 +22,7 @@ use std::u32;
 /// and end of this slice. These are actually part of an RBML
 /// document, but for looking things up in the metadata, we just
 /// discard the RBML positioning and jump directly to the data.
+#[derive(Copy, Clone)]
 pub struct Index {
     data_start: usize,
     data_end: usize,
@@ -91,6 +92,11 @@ impl IndexData {
         self.positions[item] = position;
     }
 
+    /// Whether a position has been recorded for `item`.
+    pub fn contains(&self, item: DefIndex) -> bool {
+        self.positions[item.as_usize()] != u32::MAX
+    }
+
     pub fn write_index(&self, buf: &mut Cursor<Vec<u8>>) {
         for &position in &self.positions {
             write_be_u32(buf, position);
@@ -100,6 +106,7 @@ impl IndexData {
 
 /// A dense index with integer keys. Different API from IndexData (should
 /// these be merged?)
+#[derive(Copy, Clone)]
 pub struct DenseIndex {
     start: usize,
     end: usize
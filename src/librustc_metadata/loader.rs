@@ -865,12 +865,27 @@ pub fn read_meta_section_name(target: &Target) -> &'static str {
     }
 }
 
+fn file_flavor(path: &Path) -> CrateFlavor {
+    let filename = path.file_name().unwrap().to_str().unwrap();
+    if filename.ends_with(".rlib") { CrateFlavor::Rlib } else { CrateFlavor::Dylib }
+}
+
+/// Reads the library at `path` as is, without looking for it in the
+/// search paths or checking its name, hash or target.
+pub fn load_file(target: &Target, path: &Path) -> Result<Library, String> {
+    let flavor = file_flavor(path);
+    let metadata = get_metadata_section(target, flavor, path)?;
+    let path = Some((path.to_path_buf(), PathKind::All));
+    Ok(match flavor {
+        CrateFlavor::Rlib => Library { dylib: None, rlib: path, metadata: metadata },
+        CrateFlavor::Dylib => Library { dylib: path, rlib: None, metadata: metadata },
+    })
+}
+
 // A diagnostic function for dumping crate metadata to an output stream
 pub fn list_file_metadata(target: &Target, path: &Path,
                           out: &mut io::Write) -> io::Result<()> {
-    let filename = path.file_name().unwrap().to_str().unwrap();
-    let flavor = if filename.ends_with(".rlib") { CrateFlavor::Rlib } else { CrateFlavor::Dylib };
-    match get_metadata_section(target, flavor, path) {
+    match get_metadata_section(target, file_flavor(path), path) {
         Ok(bytes) => decoder::list_crate_metadata(bytes.as_slice(), out),
         Err(msg) => {
             write!(out, "{}\n", msg)
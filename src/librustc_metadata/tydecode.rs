@@ -343,6 +343,18 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
                 let name = token::intern(&self.parse_str(']'));
                 return tcx.mk_param(space, index, name);
             }
+            'q' => {
+                assert_eq!(self.next(), '[');
+                let debruijn = ty::DebruijnIndex::new(self.parse_u32());
+                assert_eq!(self.next(), '|');
+                let index = self.parse_u32();
+                assert_eq!(self.next(), '|');
+                let name = token::intern(&self.parse_str(']'));
+                return tcx.mk_ty(ty::TyBound(debruijn, ty::BoundTy {
+                    index: index,
+                    name: name,
+                }));
+            }
             '~' => return tcx.mk_box(self.parse_ty()),
             '*' => return tcx.mk_ptr(self.parse_mt()),
             '&' => {
@@ -540,6 +552,10 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
     pub fn parse_predicate(&mut self) -> ty::Predicate<'tcx> {
         match self.next() {
             't' => ty::Binder(self.parse_trait_ref()).to_predicate(),
+            'n' => {
+                let trait_ref = ty::Binder(self.parse_trait_ref());
+                ty::Predicate::NegativeTrait(trait_ref.to_poly_trait_predicate())
+            }
             'e' => ty::Binder(ty::EquatePredicate(self.parse_ty(),
                                                   self.parse_ty())).to_predicate(),
             'r' => ty::Binder(ty::OutlivesPredicate(self.parse_region(),
@@ -589,6 +605,11 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
         let default_def_id = self.parse_def();
         let default = self.parse_opt(|this| this.parse_ty());
         let object_lifetime_default = self.parse_object_lifetime_default();
+        let pure_wrt_drop = match self.next() {
+            'P' => true,
+            'N' => false,
+            c => bug!("parse_type_param_def: bad pure_wrt_drop ('{}')", c),
+        };
 
         ty::TypeParameterDef {
             name: name,
@@ -598,6 +619,7 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
             default_def_id: default_def_id,
             default: default,
             object_lifetime_default: object_lifetime_default,
+            pure_wrt_drop: pure_wrt_drop,
         }
     }
 
@@ -618,12 +640,18 @@ impl<'a,'tcx> TyDecoder<'a,'tcx> {
                 }
             }
         }
+        let pure_wrt_drop = match self.next() {
+            'P' => true,
+            'N' => false,
+            c => bug!("parse_region_param_def: bad pure_wrt_drop ('{}')", c),
+        };
         ty::RegionParameterDef {
             name: name,
             def_id: def_id,
             space: space,
             index: index,
-            bounds: bounds
+            bounds: bounds,
+            pure_wrt_drop: pure_wrt_drop,
         }
     }
 
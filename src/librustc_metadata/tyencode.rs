@@ -151,6 +151,9 @@ pub fn enc_ty<'a, 'tcx>(w: &mut Cursor<Vec<u8>>, cx: &ctxt<'a, 'tcx>, t: Ty<'tcx
         ty::TyParam(ParamTy {space, idx, name}) => {
             write!(w, "p[{}|{}|{}]", idx, space.to_uint(), name);
         }
+        ty::TyBound(debruijn, ty::BoundTy {index, name}) => {
+            write!(w, "q[{}|{}|{}]", debruijn.depth, index, name);
+        }
         ty::TyStruct(def, substs) => {
             write!(w, "a[{}|", (cx.ds)(cx.tcx, def.did));
             enc_substs(w, cx, substs);
@@ -412,6 +415,11 @@ pub fn enc_type_param_def<'a, 'tcx>(w: &mut Cursor<Vec<u8>>, cx: &ctxt<'a, 'tcx>
              v.space.to_uint(), v.index, (cx.ds)(cx.tcx, v.default_def_id));
     enc_opt(w, v.default, |w, t| enc_ty(w, cx, t));
     enc_object_lifetime_default(w, cx, v.object_lifetime_default);
+    if v.pure_wrt_drop {
+        write!(w, "P");
+    } else {
+        write!(w, "N");
+    }
 }
 
 pub fn enc_region_param_def(w: &mut Cursor<Vec<u8>>, cx: &ctxt,
@@ -424,6 +432,11 @@ pub fn enc_region_param_def(w: &mut Cursor<Vec<u8>>, cx: &ctxt,
         enc_region(w, cx, r);
     }
     write!(w, ".");
+    if v.pure_wrt_drop {
+        write!(w, "P");
+    } else {
+        write!(w, "N");
+    }
 }
 
 fn enc_object_lifetime_default<'a, 'tcx>(w: &mut Cursor<Vec<u8>>,
@@ -453,6 +466,10 @@ pub fn enc_predicate<'a, 'tcx>(w: &mut Cursor<Vec<u8>>,
             write!(w, "t");
             enc_trait_ref(w, cx, trait_ref.0.trait_ref);
         }
+        ty::Predicate::NegativeTrait(ref trait_ref) => {
+            write!(w, "n");
+            enc_trait_ref(w, cx, trait_ref.0.trait_ref);
+        }
         ty::Predicate::Equate(ty::Binder(ty::EquatePredicate(a, b))) => {
             write!(w, "e");
             enc_ty(w, cx, a);
@@ -99,6 +99,7 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             ExprKind::Cast { .. } |
             ExprKind::ReifyFnPointer { .. } |
             ExprKind::UnsafeFnPointer { .. } |
+            ExprKind::ClosureFnPointer { .. } |
             ExprKind::Unsize { .. } |
             ExprKind::Repeat { .. } |
             ExprKind::Borrow { .. } |
@@ -111,6 +112,7 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             ExprKind::Break { .. } |
             ExprKind::Continue { .. } |
             ExprKind::Return { .. } |
+            ExprKind::Become { .. } |
             ExprKind::Literal { .. } |
             ExprKind::InlineAsm { .. } |
             ExprKind::Call { .. } => {
@@ -45,6 +45,12 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                 let constant = this.as_constant(expr);
                 block.and(Operand::Constant(constant))
             }
+            Category::Lvalue if !this.hir.is_sized(expr.ty, expr.span) => {
+                // An unsized value cannot be copied into a temporary, so it
+                // is used in place (see the `unsized_locals` feature).
+                let lvalue = unpack!(block = this.as_lvalue(block, expr));
+                block.and(Operand::Consume(lvalue))
+            }
             Category::Lvalue |
             Category::Rvalue(..) => {
                 let operand = unpack!(block = this.as_temp(block, expr));
@@ -15,7 +15,11 @@ use rustc_data_structures::fnv::FnvHashMap;
 use build::{BlockAnd, BlockAndExtension, Builder};
 use build::expr::category::{Category, RvalueFunc};
 use hair::*;
+use rustc::middle::region::CodeExtent;
 use rustc::mir::repr::*;
+use rustc::ty::{self, Ty};
+use rustc_const_math::overflow;
+use syntax::codemap::Span;
 
 impl<'a,'tcx> Builder<'a,'tcx> {
     /// Compile `expr`, yielding an rvalue.
@@ -26,6 +30,148 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         self.expr_as_rvalue(block, expr)
     }
 
+    /// Initializes `*ptr` with `value`, which is evaluated directly
+    /// into the pointee rather than into a temporary that is copied
+    /// afterwards. `ptr` must already point at memory suitable for
+    /// `value`; `on_unwind` schedules whatever cleanup releases that
+    /// memory if the evaluation panics, and is only in effect inside
+    /// `value_extents`.
+    ///
+    /// The initialization gets a block of its own and is followed by a
+    /// fresh block, so that allocation, initialization and the use of
+    /// the initialized pointer remain distinct steps in the MIR. This is
+    /// shared by `box EXPR` and is meant to become the lowering target
+    /// of placement expressions as well.
+    pub fn init_in_place<F>(&mut self,
+                            block: BasicBlock,
+                            span: Span,
+                            value_extents: CodeExtent,
+                            ptr: &Lvalue<'tcx>,
+                            value: Expr<'tcx>,
+                            on_unwind: F)
+                            -> BlockAnd<()>
+        where F: FnOnce(&mut Builder<'a, 'tcx>)
+    {
+        let scope_id = self.innermost_scope_id();
+        let init_block = self.cfg.start_new_block();
+        self.cfg.terminate(block, scope_id, span, TerminatorKind::Goto { target: init_block });
+
+        let mut block = init_block;
+        unpack!(block = self.in_scope(value_extents, block, |this, _| {
+            on_unwind(this);
+            this.into(&ptr.clone().deref(), block, value)
+        }));
+
+        let finalize_block = self.cfg.start_new_block();
+        self.cfg.terminate(block, scope_id, span, TerminatorKind::Goto { target: finalize_block });
+        finalize_block.unit()
+    }
+
+    /// Builds `lhs op rhs`, where `lhs` has type `ty`, preceded by the
+    /// runtime checks that `rustc_const_math::overflow` prescribes for
+    /// `op` on integers.
+    pub fn build_binary_op(&mut self,
+                           mut block: BasicBlock,
+                           op: BinOp,
+                           span: Span,
+                           ty: Ty<'tcx>,
+                           lhs: Operand<'tcx>,
+                           rhs: Operand<'tcx>)
+                           -> BlockAnd<Rvalue<'tcx>> {
+        let (target_int_ty, target_uint_ty) = {
+            let target = &self.hir.tcx().sess.target;
+            (target.int_type, target.uint_type)
+        };
+        let (int_ty, bits) = match ty.sty {
+            ty::TyInt(t) => (Some(t), overflow::int_ty_bits(t, target_int_ty)),
+            ty::TyUint(t) => (None, overflow::uint_ty_bits(t, target_uint_ty)),
+            _ => return block.and(Rvalue::BinaryOp(op, lhs, rhs)),
+        };
+        let mode = self.hir.tcx().sess.overflow_mode();
+        let bool_ty = self.hir.bool_ty();
+        match op {
+            BinOp::Div | BinOp::Rem if mode.checks_div() => {
+                let (zero_msg, overflow_msg) = if op == BinOp::Div {
+                    ("attempted to divide by zero",
+                     "attempted to divide with overflow")
+                } else {
+                    ("attempted remainder with a divisor of zero",
+                     "attempted remainder with overflow")
+                };
+                let is_zero = self.is_literal(block, span, ty, &rhs, 0);
+                block = self.panic_if(block, is_zero, zero_msg, span);
+
+                if let Some(int_ty) = int_ty {
+                    // `MIN / -1` overflows.
+                    let (min, _) = overflow::int_ty_range(int_ty, target_int_ty);
+                    let is_neg_one = self.is_literal(block, span, ty, &rhs, -1);
+                    let is_min = self.is_literal(block, span, ty, &lhs, min);
+                    let overflows = self.temp(bool_ty);
+                    let scope_id = self.innermost_scope_id();
+                    self.cfg.push_assign(block, scope_id, span, &overflows,
+                                         Rvalue::BinaryOp(BinOp::BitAnd, is_neg_one, is_min));
+                    block = self.panic_if(block, Operand::Consume(overflows), overflow_msg, span);
+                }
+            }
+            BinOp::Shl | BinOp::Shr if mode.checks_arith() => {
+                // The shift amount may be of any integer type; widening it to
+                // `u64` turns a negative amount into one that is too large.
+                let u64_ty = self.hir.tcx().types.u64;
+                let (amount, too_large) = (self.temp(u64_ty), self.temp(bool_ty));
+                let bits = Operand::Constant(Constant {
+                    span: span,
+                    ty: u64_ty,
+                    literal: self.hir.integer_literal(u64_ty, bits as i64),
+                });
+                let scope_id = self.innermost_scope_id();
+                self.cfg.push_assign(block, scope_id, span, &amount,
+                                     Rvalue::Cast(CastKind::Misc, rhs.clone(), u64_ty));
+                self.cfg.push_assign(block, scope_id, span, &too_large,
+                                     Rvalue::BinaryOp(BinOp::Ge, Operand::Consume(amount), bits));
+                block = self.panic_if(block, Operand::Consume(too_large),
+                                      "shift operation overflowed", span);
+            }
+            BinOp::Add | BinOp::Sub | BinOp::Mul if mode.checks_arith() => {
+                // Compute the result together with whether it overflowed, and
+                // only hand out the result once the latter has been checked.
+                let result_ty = self.hir.tcx().mk_tup(vec![ty, bool_ty]);
+                let result = self.temp(result_ty);
+                let value = result.clone().field(Field::new(0), ty);
+                let overflowed = result.clone().field(Field::new(1), bool_ty);
+                let scope_id = self.innermost_scope_id();
+                self.cfg.push_assign(block, scope_id, span, &result,
+                                     Rvalue::CheckedBinaryOp(op, lhs, rhs));
+                block = self.panic_if(block, Operand::Consume(overflowed),
+                                      "arithmetic operation overflowed", span);
+                return block.and(Rvalue::Use(Operand::Consume(value)));
+            }
+            _ => {}
+        }
+        block.and(Rvalue::BinaryOp(op, lhs, rhs))
+    }
+
+    /// Emits `operand == value` into `block`, where `operand` is of the
+    /// integer type `ty`, and returns the boolean result.
+    fn is_literal(&mut self,
+                  block: BasicBlock,
+                  span: Span,
+                  ty: Ty<'tcx>,
+                  operand: &Operand<'tcx>,
+                  value: i64)
+                  -> Operand<'tcx> {
+        let constant = Operand::Constant(Constant {
+            span: span,
+            ty: ty,
+            literal: self.hir.integer_literal(ty, value),
+        });
+        let bool_ty = self.hir.bool_ty();
+        let result = self.temp(bool_ty);
+        let scope_id = self.innermost_scope_id();
+        self.cfg.push_assign(block, scope_id, span, &result,
+                             Rvalue::BinaryOp(BinOp::Eq, operand.clone(), constant));
+        Operand::Consume(result)
+    }
+
     fn expr_as_rvalue(&mut self,
                       mut block: BasicBlock,
                       expr: Expr<'tcx>)
@@ -64,26 +210,43 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                 block.and(Rvalue::Ref(region, borrow_kind, arg_lvalue))
             }
             ExprKind::Binary { op, lhs, rhs } => {
+                let lhs = this.hir.mirror(lhs);
+                let lhs_ty = lhs.ty;
                 let lhs = unpack!(block = this.as_operand(block, lhs));
                 let rhs = unpack!(block = this.as_operand(block, rhs));
-                block.and(Rvalue::BinaryOp(op, lhs, rhs))
+                this.build_binary_op(block, op, expr_span, lhs_ty, lhs, rhs)
             }
             ExprKind::Unary { op, arg } => {
                 let arg = unpack!(block = this.as_operand(block, arg));
+                // Negating the most negative value of a signed type overflows.
+                if op == UnOp::Neg && this.hir.tcx().sess.overflow_mode().checks_arith() {
+                    if let ty::TyInt(int_ty) = expr.ty.sty {
+                        let target_int_ty = this.hir.tcx().sess.target.int_type;
+                        let (min, _) = overflow::int_ty_range(int_ty, target_int_ty);
+                        let is_min = this.is_literal(block, expr_span, expr.ty, &arg, min);
+                        block = this.panic_if(block, is_min,
+                                              "attempted to negate with overflow", expr_span);
+                    }
+                }
                 block.and(Rvalue::UnaryOp(op, arg))
             }
             ExprKind::Box { value, value_extents } => {
                 let value = this.hir.mirror(value);
                 let result = this.temp(expr.ty);
-                // to start, malloc some memory of suitable type (thus far, uninitialized):
+
+                // allocate: malloc some memory of suitable type (thus far, uninitialized):
                 this.cfg.push_assign(block, scope_id, expr_span, &result, Rvalue::Box(value.ty));
-                this.in_scope(value_extents, block, |this, _| {
+
+                // initialize: evaluate the contents straight into the allocation.
+                let value_ty = value.ty;
+                unpack!(block = this.init_in_place(block, expr_span, value_extents,
+                                                   &result, value, |this| {
                     // schedule a shallow free of that memory, lest we unwind:
-                    this.schedule_box_free(expr_span, value_extents, &result, value.ty);
-                    // initialize the box contents:
-                    unpack!(block = this.into(&result.clone().deref(), block, value));
-                    block.and(Rvalue::Use(Operand::Consume(result)))
-                })
+                    this.schedule_box_free(expr_span, value_extents, &result, value_ty);
+                }));
+
+                // finalize: the box is fully initialized and now owns its contents.
+                block.and(Rvalue::Use(Operand::Consume(result)))
             }
             ExprKind::Cast { source } => {
                 let source = this.hir.mirror(source);
@@ -102,6 +265,10 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                 let source = unpack!(block = this.as_operand(block, source));
                 block.and(Rvalue::Cast(CastKind::UnsafeFnPointer, source, expr.ty))
             }
+            ExprKind::ClosureFnPointer { source } => {
+                let source = unpack!(block = this.as_operand(block, source));
+                block.and(Rvalue::Cast(CastKind::ClosureFnPointer, source, expr.ty))
+            }
             ExprKind::Unsize { source } => {
                 let source = unpack!(block = this.as_operand(block, source));
                 block.and(Rvalue::Cast(CastKind::Unsize, source, expr.ty))
@@ -206,6 +373,7 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             ExprKind::Break { .. } |
             ExprKind::Continue { .. } |
             ExprKind::Return { .. } |
+            ExprKind::Become { .. } |
             ExprKind::StaticRef { .. } => {
                 // these do not have corresponding `Rvalue` variants,
                 // so make an operand and then return that
@@ -69,6 +69,7 @@ impl Category {
             ExprKind::Cast { .. } |
             ExprKind::ReifyFnPointer { .. } |
             ExprKind::UnsafeFnPointer { .. } |
+            ExprKind::ClosureFnPointer { .. } |
             ExprKind::Unsize { .. } |
             ExprKind::Repeat { .. } |
             ExprKind::Borrow { .. } |
@@ -84,7 +85,8 @@ impl Category {
             ExprKind::Block { .. } |
             ExprKind::Break { .. } |
             ExprKind::Continue { .. } |
-            ExprKind::Return { .. } =>
+            ExprKind::Return { .. } |
+            ExprKind::Become { .. } =>
                 // FIXME(#27840) these probably want their own
                 // category, like "nonterminating"
                 Some(Category::Rvalue(RvalueFunc::Into)),
@@ -230,15 +230,17 @@ impl<'a,'tcx> Builder<'a,'tcx> {
 
                 // As above, RTL.
                 let rhs = unpack!(block = this.as_operand(block, rhs));
+                let lhs = this.hir.mirror(lhs);
+                let lhs_ty = lhs.ty;
                 let lhs = unpack!(block = this.as_lvalue(block, lhs));
 
                 // we don't have to drop prior contents or anything
                 // because AssignOp is only legal for Copy types
                 // (overloaded ops should be desugared into a call).
-                this.cfg.push_assign(block, scope_id, expr_span, &lhs,
-                                     Rvalue::BinaryOp(op,
-                                                      Operand::Consume(lhs.clone()),
-                                                      rhs));
+                let result = unpack!(block = this.build_binary_op(block, op, expr_span, lhs_ty,
+                                                                  Operand::Consume(lhs.clone()),
+                                                                  rhs));
+                this.cfg.push_assign(block, scope_id, expr_span, &lhs, result);
 
                 block.unit()
             }
@@ -265,6 +267,9 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                 this.exit_scope(expr_span, extent, block, END_BLOCK);
                 this.cfg.start_new_block().unit()
             }
+            ExprKind::Become { value } => {
+                this.into_tail_call(block, expr_span, value)
+            }
             ExprKind::Call { ty, fun, args } => {
                 let diverges = match ty.sty {
                     ty::TyFnDef(_, _, ref f) | ty::TyFnPtr(ref f) => {
@@ -300,6 +305,7 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             ExprKind::Cast { .. } |
             ExprKind::ReifyFnPointer { .. } |
             ExprKind::UnsafeFnPointer { .. } |
+            ExprKind::ClosureFnPointer { .. } |
             ExprKind::Unsize { .. } |
             ExprKind::Repeat { .. } |
             ExprKind::Borrow { .. } |
@@ -342,4 +348,39 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         self.exit_scope(span, extent, block, exit_block);
         self.cfg.start_new_block().unit()
     }
+
+    /// Compile the call in `become call`. Control never comes back to
+    /// this function, so unlike `return` no scopes are exited: instead
+    /// every value still awaiting a drop must have been moved out by the
+    /// time of the call, which `check_tail_call_drops` verifies.
+    fn into_tail_call(&mut self,
+                      mut block: BasicBlock,
+                      span: Span,
+                      call: ExprRef<'tcx>)
+                      -> BlockAnd<()>
+    {
+        let call = self.hir.mirror(call);
+        match call.kind {
+            ExprKind::Scope { extent, value } => {
+                self.in_scope(extent, block, |this, _| this.into_tail_call(block, span, value))
+            }
+            ExprKind::Call { fun, args, .. } => {
+                let fun = unpack!(block = self.as_operand(block, fun));
+                let args: Vec<_> =
+                    args.into_iter()
+                        .map(|arg| unpack!(block = self.as_operand(block, arg)))
+                        .collect();
+
+                self.record_tail_call(block, span);
+
+                let scope_id = self.innermost_scope_id();
+                self.cfg.terminate(block, scope_id, span, TerminatorKind::TailCall {
+                    func: fun,
+                    args: args,
+                });
+                self.cfg.start_new_block().unit()
+            }
+            _ => span_bug!(call.span, "`become` of a non-call expression: {:?}", call)
+        }
+    }
 }
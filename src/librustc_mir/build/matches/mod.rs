@@ -22,6 +22,8 @@ use hair::*;
 use syntax::ast::{Name, NodeId};
 use syntax::codemap::Span;
 
+use std::cmp::Ordering;
+
 // helper functions, broken out by category:
 mod simplify;
 mod test;
@@ -494,6 +496,13 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                         break;
                     }
                 }
+
+                // Emit the values in ascending order; a sorted, dense
+                // switch is what LLVM lowers best into a jump table.
+                options.sort_by(|a, b| a.try_cmp(b).unwrap_or(Ordering::Equal));
+                for (index, value) in options.iter().enumerate() {
+                    indices.insert(value.clone(), index);
+                }
             }
             _ => { }
         }
@@ -588,10 +597,14 @@ impl<'a,'tcx> Builder<'a,'tcx> {
 
     fn bind_matched_candidate(&mut self,
                               block: BasicBlock,
-                              bindings: Vec<Binding<'tcx>>) {
+                              mut bindings: Vec<Binding<'tcx>>) {
         debug!("bind_matched_candidate(block={:?}, bindings={:?})",
                block, bindings);
 
+        // Bind the deepest lvalues first, so that in `x @ Foo(y)` the
+        // copy into `y` reads the value before `x` moves it.
+        bindings.sort_by(|a, b| lvalue_depth(&b.source).cmp(&lvalue_depth(&a.source)));
+
         // Assign each of the bindings. This may trigger moves out of the candidate.
         for binding in bindings {
             // Find the variable for the `var_id` being bound. It
@@ -642,3 +655,11 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         index
     }
 }
+
+/// The number of projections from the local that `lvalue` is based on.
+fn lvalue_depth(lvalue: &Lvalue) -> usize {
+    match *lvalue {
+        Lvalue::Projection(ref proj) => 1 + lvalue_depth(&proj.base),
+        _ => 0,
+    }
+}
@@ -24,6 +24,9 @@ use rustc::ty::{self, Ty};
 use rustc::mir::repr::*;
 use syntax::codemap::Span;
 
+use std::char;
+use std::cmp::Ordering;
+
 impl<'a,'tcx> Builder<'a,'tcx> {
     /// Identifies what test is needed to decide if `match_pair` is applicable.
     ///
@@ -41,17 +44,13 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             if is_switch_ty(match_pair.pattern.ty) => {
                 // for integers, we use a SwitchInt match, which allows
                 // us to handle more cases
-                Test {
-                    span: match_pair.pattern.span,
-                    kind: TestKind::SwitchInt {
-                        switch_ty: match_pair.pattern.ty,
+                self.switch_int_test(match_pair)
+            }
 
-                        // these maps are empty to start; cases are
-                        // added below in add_cases_to_switch
-                        options: vec![],
-                        indices: FnvHashMap(),
-                    }
-                }
+            PatternKind::Range { ref lo, ref hi }
+            if is_switch_ty(match_pair.pattern.ty) && switch_range_values(lo, hi).is_some() => {
+                // small ranges become cases of a SwitchInt as well
+                self.switch_int_test(match_pair)
             }
 
             PatternKind::Constant { ref value } => {
@@ -100,6 +99,20 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         }
     }
 
+    fn switch_int_test<'pat>(&self, match_pair: &MatchPair<'pat, 'tcx>) -> Test<'tcx> {
+        Test {
+            span: match_pair.pattern.span,
+            kind: TestKind::SwitchInt {
+                switch_ty: match_pair.pattern.ty,
+
+                // these maps are empty to start; cases are
+                // added below in add_cases_to_switch
+                options: vec![],
+                indices: FnvHashMap(),
+            }
+        }
+    }
+
     pub fn add_cases_to_switch<'pat>(&mut self,
                                      test_lvalue: &Lvalue<'tcx>,
                                      candidate: &Candidate<'pat, 'tcx>,
@@ -126,7 +139,24 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                 true
             }
 
-            PatternKind::Range { .. } |
+            PatternKind::Range { ref lo, ref hi } => {
+                // small ranges are added value by value, so that a match
+                // mixing ranges and constants still becomes one switch
+                match switch_range_values(lo, hi) {
+                    Some(values) => {
+                        for value in values {
+                            indices.entry(value.clone())
+                                   .or_insert_with(|| {
+                                       options.push(value.clone());
+                                       options.len() - 1
+                                   });
+                        }
+                        true
+                    }
+                    None => false
+                }
+            }
+
             PatternKind::Variant { .. } |
             PatternKind::Slice { .. } |
             PatternKind::Array { .. } |
@@ -403,7 +433,8 @@ impl<'a,'tcx> Builder<'a,'tcx> {
             }
 
             // If we are performing a switch over integers, then this informs integer
-            // equality, but nothing else.
+            // equality, and membership in the small ranges that
+            // `add_cases_to_switch` expanded into the switch, but nothing else.
             //
             // FIXME(#29623) we could use TestKind::Range to rule
             // things out here, in some cases.
@@ -417,6 +448,21 @@ impl<'a,'tcx> Builder<'a,'tcx> {
                         resulting_candidates[index].push(new_candidate);
                         true
                     }
+                    PatternKind::Range { ref lo, ref hi }
+                    if is_switch_ty(match_pair.pattern.ty) => {
+                        match switch_range_values(lo, hi) {
+                            Some(values) => {
+                                for value in &values {
+                                    let new_candidate =
+                                        self.candidate_without_match_pair(match_pair_index,
+                                                                          candidate);
+                                    resulting_candidates[indices[value]].push(new_candidate);
+                                }
+                                true
+                            }
+                            None => false
+                        }
+                    }
                     _ => {
                         false
                     }
@@ -530,3 +576,46 @@ impl<'a,'tcx> Builder<'a,'tcx> {
 fn is_switch_ty<'tcx>(ty: Ty<'tcx>) -> bool {
     ty.is_integral() || ty.is_char() || ty.is_bool()
 }
+
+/// Ranges with at most this many values are matched as part of a
+/// `SwitchInt` rather than with a pair of comparisons.
+const MAX_SWITCH_RANGE_LEN: usize = 32;
+
+/// The values matched by the range pattern `lo...hi`, if it is small
+/// enough to be matched as part of a `SwitchInt`.
+fn switch_range_values(lo: &Literal, hi: &Literal) -> Option<Vec<ConstVal>> {
+    let (lo, hi) = match (lo, hi) {
+        (&Literal::Value { value: ref lo }, &Literal::Value { value: ref hi }) => (lo, hi),
+        _ => return None
+    };
+
+    let mut values = vec![];
+    match (lo, hi) {
+        (&ConstVal::Integral(lo), &ConstVal::Integral(hi)) => {
+            match lo.try_cmp(hi) {
+                Ok(Ordering::Less) | Ok(Ordering::Equal) if lo.int_type().is_some() => {}
+                _ => return None
+            }
+            let mut value = lo;
+            loop {
+                if values.len() == MAX_SWITCH_RANGE_LEN {
+                    return None;
+                }
+                values.push(ConstVal::Integral(value));
+                if value == hi {
+                    break;
+                }
+                value = value.wrap_incr();
+            }
+        }
+        (&ConstVal::Char(lo), &ConstVal::Char(hi)) => {
+            let (lo, hi) = (lo as u32, hi as u32);
+            if lo > hi || (hi - lo) as usize >= MAX_SWITCH_RANGE_LEN {
+                return None;
+            }
+            values.extend((lo..hi + 1).filter_map(char::from_u32).map(ConstVal::Char));
+        }
+        _ => return None
+    }
+    Some(values)
+}
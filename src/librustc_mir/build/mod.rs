@@ -51,6 +51,10 @@ pub struct Builder<'a, 'tcx: 'a> {
     // cached block with a RESUME terminator; we create this at the
     // first panic
     cached_resume_block: Option<BasicBlock>,
+
+    // the `become` expressions built so far, whose drops are checked
+    // once the CFG is complete
+    tail_calls: Vec<scope::TailCall<'tcx>>,
 }
 
 struct CFG<'tcx> {
@@ -180,6 +184,7 @@ pub fn construct<'a,'tcx>(hir: Cx<'a,'tcx>,
         var_indices: FnvHashMap(),
         unit_temp: None,
         cached_resume_block: None,
+        tail_calls: vec![],
     };
 
     assert_eq!(builder.cfg.start_new_block(), START_BLOCK);
@@ -224,6 +229,8 @@ pub fn construct<'a,'tcx>(hir: Cx<'a,'tcx>,
                        true
                    }));
 
+    builder.check_tail_call_drops();
+
     (
         Mir {
             basic_blocks: builder.cfg.basic_blocks,
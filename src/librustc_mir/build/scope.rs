@@ -92,6 +92,8 @@ use rustc::middle::lang_items;
 use rustc::ty::subst::{Substs, Subst, VecPerParamSpace};
 use rustc::ty::{self, Ty, TyCtxt};
 use rustc::mir::repr::*;
+use rustc::mir::visit::{LvalueContext, Visitor};
+use rustc::util::nodemap::FnvHashSet;
 use syntax::codemap::{Span, DUMMY_SP};
 use syntax::parse::token::intern_and_get_ident;
 use rustc::middle::const_val::ConstVal;
@@ -134,6 +136,68 @@ pub struct Scope<'tcx> {
     cached_block: Option<BasicBlock>
 }
 
+/// A local whose initialization `check_tail_call_drops` tracks.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum MovedLocal {
+    Var(u32),
+    Temp(u32),
+    Arg(u32),
+}
+
+impl MovedLocal {
+    fn of(lvalue: &Lvalue) -> Option<MovedLocal> {
+        match *lvalue {
+            Lvalue::Var(index) => Some(MovedLocal::Var(index)),
+            Lvalue::Temp(index) => Some(MovedLocal::Temp(index)),
+            Lvalue::Arg(index) => Some(MovedLocal::Arg(index)),
+            Lvalue::Static(..) | Lvalue::ReturnPointer | Lvalue::Projection(..) => None,
+        }
+    }
+}
+
+/// A `become` in the function being built; see `record_tail_call`.
+pub struct TailCall<'tcx> {
+    /// The block terminated by the tail call.
+    block: BasicBlock,
+
+    /// span of the `become` expression
+    span: Span,
+
+    /// The values scheduled for a drop when the call is made, with the
+    /// spans where the drop obligations were incurred.
+    drops: Vec<(Lvalue<'tcx>, Span)>,
+}
+
+/// Applies the effect of a block on the set of tracked locals that may
+/// still be initialized: assigning a local initializes it, while moving
+/// or dropping it leaves it uninitialized.
+struct MaybeInitLocals<'a> {
+    tracked: &'a FnvHashSet<MovedLocal>,
+    live: FnvHashSet<MovedLocal>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for MaybeInitLocals<'a> {
+    fn visit_lvalue(&mut self, lvalue: &Lvalue<'tcx>, context: LvalueContext) {
+        if let Some(local) = MovedLocal::of(lvalue) {
+            if self.tracked.contains(&local) {
+                match context {
+                    LvalueContext::Store | LvalueContext::Call => {
+                        self.live.insert(local);
+                    }
+                    LvalueContext::Consume | LvalueContext::Drop => {
+                        self.live.remove(&local);
+                    }
+                    LvalueContext::Inspect |
+                    LvalueContext::Borrow { .. } |
+                    LvalueContext::Slice { .. } |
+                    LvalueContext::Projection => {}
+                }
+            }
+        }
+        self.super_lvalue(lvalue, context);
+    }
+}
+
 struct DropData<'tcx> {
     /// span where drop obligation was incurred (typically where lvalue was declared)
     span: Span,
@@ -384,6 +448,90 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         self.scopes[1].extent
     }
 
+    /// Records the tail call that terminates `block`. Whatever is still
+    /// scheduled for a drop at this point must have been moved out by the
+    /// time the call is made, as the caller's frame is gone once it
+    /// returns; that is checked by `check_tail_call_drops` once the whole
+    /// CFG is built.
+    pub fn record_tail_call(&mut self, block: BasicBlock, span: Span) {
+        // `scopes[0]` is the call site scope, which holds no drops.
+        let drops = self.scopes.iter().skip(1).flat_map(|scope| {
+            scope.drops.iter().map(|drop_data| (drop_data.value.clone(), drop_data.span))
+        }).collect();
+        self.tail_calls.push(TailCall {
+            block: block,
+            span: span,
+            drops: drops,
+        });
+    }
+
+    /// Checks that no value left to drop at a `become` may still be
+    /// initialized when the tail call is made. Values moved anywhere on
+    /// every path to the call -- into its arguments, or e.g. by
+    /// `drop(x)` beforehand -- are fine; anything else is an error.
+    pub fn check_tail_call_drops(&self) {
+        if self.tail_calls.is_empty() {
+            return;
+        }
+
+        let tracked: FnvHashSet<_> = self.tail_calls.iter().flat_map(|tail_call| {
+            tail_call.drops.iter().filter_map(|&(ref value, _)| MovedLocal::of(value))
+        }).collect();
+
+        // A forward dataflow of the locals that may be initialized on
+        // entry to each block. Arguments start out initialized.
+        let blocks = &self.cfg.basic_blocks;
+        let mut on_entry: Vec<FnvHashSet<MovedLocal>> =
+            blocks.iter().map(|_| FnvHashSet()).collect();
+        on_entry[START_BLOCK.index()] = tracked.iter().cloned().filter(|local| {
+            match *local {
+                MovedLocal::Arg(_) => true,
+                MovedLocal::Var(_) | MovedLocal::Temp(_) => false,
+            }
+        }).collect();
+
+        let on_exit = |bb: BasicBlock, on_entry: &[FnvHashSet<MovedLocal>]| {
+            let mut maybe_init = MaybeInitLocals {
+                tracked: &tracked,
+                live: on_entry[bb.index()].clone(),
+            };
+            maybe_init.visit_basic_block_data(bb, &blocks[bb.index()]);
+            maybe_init.live
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in 0..blocks.len() {
+                let bb = BasicBlock::new(index);
+                let live = on_exit(bb, &on_entry[..]);
+                for &succ in blocks[index].terminator().successors().iter() {
+                    for &local in &live {
+                        changed |= on_entry[succ.index()].insert(local);
+                    }
+                }
+            }
+        }
+
+        for tail_call in &self.tail_calls {
+            let live = on_exit(tail_call.block, &on_entry[..]);
+            let still_live = tail_call.drops.iter().find(|&&(ref value, _)| {
+                MovedLocal::of(value).map_or(true, |local| live.contains(&local))
+            });
+            if let Some(&(_, drop_span)) = still_live {
+                let tcx = self.hir.tcx();
+                let mut err = tcx.sess.struct_span_err(
+                    tail_call.span,
+                    "`become` would leave a value with a destructor live \
+                     after the call");
+                err.span_note(drop_span,
+                              "this value would have to be dropped after the call \
+                               returns; move it into the call or drop it beforehand");
+                err.emit();
+            }
+        }
+    }
+
     // Scheduling drops
     // ================
     /// Indicates that `lvalue` should be dropped on exit from
@@ -574,7 +722,8 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         let message = Constant {
             span: span,
             ty: self.hir.tcx().mk_static_str(),
-            literal: self.hir.str_literal(intern_and_get_ident(msg))
+            literal: self.hir.str_literal(
+                self.hir.tcx().sess.panic_message(span, intern_and_get_ident(msg)))
         };
         let elems = vec![Operand::Constant(message),
                          Operand::Constant(file),
@@ -598,6 +747,24 @@ impl<'a,'tcx> Builder<'a,'tcx> {
         });
     }
 
+    /// Branch from `block` to a panic with `msg` if `cond` holds, and
+    /// return the block in which execution continues otherwise.
+    pub fn panic_if(&mut self,
+                    block: BasicBlock,
+                    cond: Operand<'tcx>,
+                    msg: &'static str,
+                    span: Span)
+                    -> BasicBlock {
+        let (success, failure) = (self.cfg.start_new_block(), self.cfg.start_new_block());
+        let scope_id = self.innermost_scope_id();
+        self.cfg.terminate(block, scope_id, span, TerminatorKind::If {
+            cond: cond,
+            targets: (failure, success),
+        });
+        self.panic(failure, msg, span);
+        success
+    }
+
     fn lang_function(&mut self, lang_item: lang_items::LangItem) -> Constant<'tcx> {
         let funcdid = match self.hir.tcx().lang_items.require(lang_item) {
             Ok(d) => d,
@@ -616,16 +783,16 @@ impl<'a,'tcx> Builder<'a,'tcx> {
     }
 
     fn span_to_fileline_args(&mut self, span: Span) -> (Constant<'tcx>, Constant<'tcx>) {
-        let span_lines = self.hir.tcx().sess.codemap().lookup_char_pos(span.lo);
+        let (file, line) = self.hir.tcx().sess.panic_location(span);
         (Constant {
             span: span,
             ty: self.hir.tcx().mk_static_str(),
-            literal: self.hir.str_literal(intern_and_get_ident(&span_lines.file.name))
+            literal: self.hir.str_literal(file)
         }, Constant {
             span: span,
             ty: self.hir.tcx().types.u32,
             literal: Literal::Value {
-                value: ConstVal::Integral(ConstInt::U32(span_lines.line as u32)),
+                value: ConstVal::Integral(ConstInt::U32(line)),
             },
         })
     }
@@ -179,7 +179,6 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
                     overloaded_operator(cx, self, ty::MethodCall::expr(self.id),
                                         pass_args, lhs.to_ref(), vec![rhs])
                 } else {
-                    // FIXME overflow
                     match op.node {
                         hir::BinOp_::BiAnd => {
                             ExprKind::LogicalOp {
@@ -245,7 +244,6 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
                     overloaded_operator(cx, self, ty::MethodCall::expr(self.id),
                                         PassArgs::ByValue, arg.to_ref(), vec![])
                 } else {
-                    // FIXME runtime-overflow
                     if let hir::ExprLit(_) = arg.node {
                         ExprKind::Literal {
                             literal: cx.const_eval_literal(self),
@@ -362,6 +360,8 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
             },
             hir::ExprRet(ref v) =>
                 ExprKind::Return { value: v.to_ref() },
+            hir::ExprBecome(ref v) =>
+                ExprKind::Become { value: v.to_ref() },
             hir::ExprBreak(label) =>
                 ExprKind::Break { label: label.map(|_| loop_label(cx, self)) },
             hir::ExprAgain(label) =>
@@ -446,6 +446,15 @@ impl<'tcx> Mirror<'tcx> for &'tcx hir::Expr {
                     kind: ExprKind::UnsafeFnPointer { source: expr.to_ref() },
                 };
             }
+            Some(&ty::adjustment::AdjustClosureFnPointer) => {
+                let adjusted_ty = cx.tcx.expr_ty_adjusted(self);
+                expr = Expr {
+                    temp_lifetime: temp_lifetime,
+                    ty: adjusted_ty,
+                    span: self.span,
+                    kind: ExprKind::ClosureFnPointer { source: expr.to_ref() },
+                };
+            }
             Some(&ty::adjustment::AdjustMutToConstPointer) => {
                 let adjusted_ty = cx.tcx.expr_ty_adjusted(self);
                 expr = Expr {
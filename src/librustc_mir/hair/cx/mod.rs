@@ -24,9 +24,11 @@ use rustc::hir::def_id::DefId;
 use rustc::infer::InferCtxt;
 use rustc::ty::subst::{Subst, Substs};
 use rustc::ty::{self, Ty, TyCtxt};
+use syntax::ast;
+use syntax::codemap::Span;
 use syntax::parse::token;
 use rustc::hir;
-use rustc_const_math::{ConstInt, ConstUsize};
+use rustc_const_math::{ConstInt, ConstIsize, ConstUsize};
 
 #[derive(Copy, Clone)]
 pub struct Cx<'a, 'tcx: 'a> {
@@ -60,6 +62,32 @@ impl<'a,'tcx:'a> Cx<'a, 'tcx> {
         }
     }
 
+    /// The constant `value` at the integer type `ty`, truncated to the
+    /// width of `ty`.
+    pub fn integer_literal(&mut self, ty: Ty<'tcx>, value: i64) -> Literal<'tcx> {
+        let target = &self.tcx.sess.target;
+        let value = match ty.sty {
+            ty::TyInt(ast::IntTy::I8) => ConstInt::I8(value as i8),
+            ty::TyInt(ast::IntTy::I16) => ConstInt::I16(value as i16),
+            ty::TyInt(ast::IntTy::I32) => ConstInt::I32(value as i32),
+            ty::TyInt(ast::IntTy::I64) => ConstInt::I64(value),
+            ty::TyInt(ast::IntTy::Is) => match ConstIsize::new(value, target.int_type) {
+                Ok(val) => ConstInt::Isize(val),
+                Err(_) => bug!("isize literal out of range for target"),
+            },
+            ty::TyUint(ast::UintTy::U8) => ConstInt::U8(value as u8),
+            ty::TyUint(ast::UintTy::U16) => ConstInt::U16(value as u16),
+            ty::TyUint(ast::UintTy::U32) => ConstInt::U32(value as u32),
+            ty::TyUint(ast::UintTy::U64) => ConstInt::U64(value as u64),
+            ty::TyUint(ast::UintTy::Us) => match ConstUsize::new(value as u64, target.uint_type) {
+                Ok(val) => ConstInt::Usize(val),
+                Err(_) => bug!("usize literal out of range for target"),
+            },
+            _ => bug!("integer_literal: unexpected type {:?}", ty),
+        };
+        Literal::Value { value: ConstVal::Integral(value) }
+    }
+
     pub fn bool_ty(&mut self) -> Ty<'tcx> {
         self.tcx.types.bool
     }
@@ -140,6 +168,10 @@ impl<'a,'tcx:'a> Cx<'a, 'tcx> {
         self.tcx.type_needs_drop_given_env(ty, &self.infcx.parameter_environment)
     }
 
+    pub fn is_sized(&mut self, ty: Ty<'tcx>, span: Span) -> bool {
+        ty.is_sized(&self.infcx.parameter_environment, span)
+    }
+
     pub fn tcx(&self) -> &'a TyCtxt<'tcx> {
         self.tcx
     }
@@ -145,6 +145,9 @@ pub enum ExprKind<'tcx> {
     UnsafeFnPointer {
         source: ExprRef<'tcx>,
     },
+    ClosureFnPointer {
+        source: ExprRef<'tcx>,
+    },
     Unsize {
         source: ExprRef<'tcx>,
     },
@@ -203,6 +206,9 @@ pub enum ExprKind<'tcx> {
     Return {
         value: Option<ExprRef<'tcx>>,
     },
+    Become {
+        value: ExprRef<'tcx>,
+    },
     Repeat {
         value: ExprRef<'tcx>,
         count: TypedConstVal<'tcx>,
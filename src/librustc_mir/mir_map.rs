@@ -20,7 +20,9 @@ extern crate syntax;
 
 use build;
 use rustc::dep_graph::DepNode;
-use rustc::mir::repr::Mir;
+use rustc::mir::repr::{AggregateKind, ArgDecl, BasicBlockData, Lvalue, Mir, Operand, Rvalue};
+use rustc::mir::repr::{ScopeData, ScopeId, Statement, StatementKind, Terminator};
+use rustc::mir::repr::{TerminatorKind, END_BLOCK};
 use pretty;
 use hair::cx::Cx;
 
@@ -60,6 +62,16 @@ struct OuterDump<'a, 'tcx: 'a> {
 }
 
 impl<'a, 'tcx> OuterDump<'a, 'tcx> {
+    fn insert_ctor_shim(&mut self,
+                        ctor_id: ast::NodeId,
+                        adt: ty::AdtDef<'tcx>,
+                        variant_index: usize,
+                        span: Span) {
+        let mir = build_ctor_shim(self.tcx, ctor_id, adt, variant_index, span);
+        pretty::dump_mir(self.tcx, "mir_map", &0, ctor_id, &mir, None);
+        assert!(self.map.map.insert(ctor_id, mir).is_none());
+    }
+
     fn visit_mir<OP>(&mut self, attributes: &'a [ast::Attribute], mut walk_op: OP)
         where OP: for<'m> FnMut(&mut InnerDump<'a, 'm, 'tcx>)
     {
@@ -79,8 +91,31 @@ impl<'a, 'tcx> OuterDump<'a, 'tcx> {
 
 
 impl<'a, 'tcx> Visitor<'tcx> for OuterDump<'a, 'tcx> {
+    fn visit_body_id(&mut self, id: hir::BodyId) {
+        self.tcx.map.read_body(id);
+    }
+
     fn visit_item(&mut self, item: &'tcx hir::Item) {
         self.visit_mir(&item.attrs, |c| intravisit::walk_item(c, item));
+
+        // Tuple structs and variants get a constructor function, whose
+        // body is built here since there is no source for it.
+        match item.node {
+            hir::ItemStruct(ref struct_def, _) if struct_def.is_tuple() => {
+                let adt = self.tcx.lookup_adt_def(self.tcx.map.local_def_id(item.id));
+                self.insert_ctor_shim(struct_def.id(), adt, 0, item.span);
+            }
+            hir::ItemEnum(ref enum_def, _) => {
+                let adt = self.tcx.lookup_adt_def(self.tcx.map.local_def_id(item.id));
+                for (index, variant) in enum_def.variants.iter().enumerate() {
+                    if variant.node.data.is_tuple() {
+                        self.insert_ctor_shim(variant.node.data.id(), adt, index, variant.span);
+                    }
+                }
+            }
+            _ => {}
+        }
+
         intravisit::walk_item(self, item);
     }
 
@@ -207,6 +242,59 @@ fn build_mir<'a,'tcx:'a>(cx: Cx<'a,'tcx>,
     Ok(mir)
 }
 
+/// Builds the body of the constructor of a tuple struct or variant,
+/// which takes the fields as its arguments and returns the value built
+/// from them:
+///
+/// ```
+/// fn Foo(arg0: A, arg1: B) -> Foo {
+///     return = Foo(arg0, arg1);
+/// }
+/// ```
+fn build_ctor_shim<'tcx>(tcx: &TyCtxt<'tcx>,
+                         ctor_id: ast::NodeId,
+                         adt: ty::AdtDef<'tcx>,
+                         variant_index: usize,
+                         span: Span)
+                         -> Mir<'tcx> {
+    let ctor_ty = tcx.lookup_item_type(tcx.map.local_def_id(ctor_id)).ty;
+    let (substs, sig) = match ctor_ty.sty {
+        // Constructors never bind late-bound regions of their own.
+        ty::TyFnDef(_, substs, f) => (substs, f.sig.skip_binder().clone()),
+        _ => span_bug!(span, "constructor with non-fn type {:?}", ctor_ty)
+    };
+
+    let scope = ScopeId::new(0);
+    let fields = (0..sig.inputs.len())
+        .map(|index| Operand::Consume(Lvalue::Arg(index as u32)))
+        .collect();
+    let construct = Statement {
+        span: span,
+        scope: scope,
+        kind: StatementKind::Assign(
+            Lvalue::ReturnPointer,
+            Rvalue::Aggregate(AggregateKind::Adt(adt, variant_index, substs), fields))
+    };
+    let block = |statements, kind| BasicBlockData {
+        statements: statements,
+        terminator: Some(Terminator { span: span, scope: scope, kind: kind }),
+        is_cleanup: false,
+    };
+
+    Mir {
+        basic_blocks: vec![
+            block(vec![construct], TerminatorKind::Goto { target: END_BLOCK }),
+            block(vec![], TerminatorKind::Return),
+        ],
+        scopes: vec![ScopeData { span: span, parent_scope: None }],
+        return_ty: sig.output,
+        var_decls: vec![],
+        arg_decls: sig.inputs.iter().map(|&ty| ArgDecl { ty: ty, spread: false }).collect(),
+        temp_decls: vec![],
+        span: span,
+    }
+}
+
 fn closure_self_ty<'a, 'tcx>(tcx: &TyCtxt<'tcx>,
                              closure_expr_id: ast::NodeId,
                              body_id: ast::NodeId)
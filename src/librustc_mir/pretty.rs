@@ -9,6 +9,7 @@
 // except according to those terms.
 
 use build::{Location, ScopeAuxiliaryVec};
+use dot;
 use rustc::mir::repr::*;
 use rustc::ty::{self, TyCtxt};
 use rustc_data_structures::fnv::FnvHashMap;
@@ -34,28 +35,20 @@ const INDENT: &'static str = "    ";
 /// - `substring1&substring2,...` -- `&`-separated list of substrings
 ///   that can appear in the pass-name or the `item_path_str` for the given
 ///   node-id. If any one of the substrings match, the data is dumped out.
+///
+/// If `-Z dump-mir-html` is also passed, an HTML rendering of the same
+/// MIR is written alongside, into `rustc.node<node_id>.<pass_name>.<disambiguator>.html`.
 pub fn dump_mir<'a, 'tcx>(tcx: &TyCtxt<'tcx>,
                           pass_name: &str,
                           disambiguator: &Display,
                           node_id: NodeId,
                           mir: &Mir<'tcx>,
                           auxiliary: Option<&ScopeAuxiliaryVec>) {
-    let filters = match tcx.sess.opts.debugging_opts.dump_mir {
-        None => return,
-        Some(ref filters) => filters,
-    };
-    let node_path = tcx.item_path_str(tcx.map.local_def_id(node_id));
-    let is_matched =
-        filters.split("&")
-               .any(|filter| {
-                   filter == "all" ||
-                       pass_name.contains(filter) ||
-                       node_path.contains(filter)
-               });
-    if !is_matched {
+    if !dump_enabled(tcx, pass_name, node_id) {
         return;
     }
 
+    let node_path = tcx.item_path_str(tcx.map.local_def_id(node_id));
     let file_name = format!("rustc.node{}.{}.{}.mir",
                             node_id, pass_name, disambiguator);
     let _ = fs::File::create(&file_name).and_then(|mut file| {
@@ -67,6 +60,50 @@ pub fn dump_mir<'a, 'tcx>(tcx: &TyCtxt<'tcx>,
         try!(write_mir_fn(tcx, node_id, mir, &mut file, auxiliary));
         Ok(())
     });
+
+    dump_mir_html(tcx, pass_name, disambiguator, node_id, mir, |_, _| vec![]);
+}
+
+/// Like `dump_mir`, but only writes the HTML rendering, and lets the
+/// caller interleave facts about each program point with the
+/// statements. `dataflow(block, index)` is called for every statement
+/// (and, with `index == statements.len()`, for the terminator) and
+/// returns the facts that hold on entry to it, one line each.
+///
+/// Output is controlled by `-Z dump-mir-html`, filtered by
+/// `-Z dump-mir` in the same way as `dump_mir`.
+pub fn dump_mir_html<'tcx, F>(tcx: &TyCtxt<'tcx>,
+                              pass_name: &str,
+                              disambiguator: &Display,
+                              node_id: NodeId,
+                              mir: &Mir<'tcx>,
+                              dataflow: F)
+    where F: Fn(BasicBlock, usize) -> Vec<String>
+{
+    if !tcx.sess.opts.debugging_opts.dump_mir_html ||
+       !dump_enabled(tcx, pass_name, node_id) {
+        return;
+    }
+
+    let file_name = format!("rustc.node{}.{}.{}.html",
+                            node_id, pass_name, disambiguator);
+    let _ = fs::File::create(&file_name).and_then(|mut file| {
+        write_mir_fn_html(tcx, node_id, pass_name, mir, &mut file, &dataflow)
+    });
+}
+
+fn dump_enabled(tcx: &TyCtxt, pass_name: &str, node_id: NodeId) -> bool {
+    let filters = match tcx.sess.opts.debugging_opts.dump_mir {
+        None => return false,
+        Some(ref filters) => filters,
+    };
+    let node_path = tcx.item_path_str(tcx.map.local_def_id(node_id));
+    filters.split("&")
+           .any(|filter| {
+               filter == "all" ||
+                   pass_name.contains(filter) ||
+                   node_path.contains(filter)
+           })
 }
 
 /// Write out a human-readable textual representation for the given MIR.
@@ -174,6 +211,95 @@ fn write_basic_block(tcx: &TyCtxt,
     writeln!(w, "{}}}", INDENT)
 }
 
+/// Write out an HTML rendering of the given MIR, with one table per
+/// basic block. The facts returned by `dataflow` for a program point
+/// are shown in a highlighted row just above it.
+pub fn write_mir_fn_html<'tcx>(tcx: &TyCtxt<'tcx>,
+                               node_id: NodeId,
+                               pass_name: &str,
+                               mir: &Mir<'tcx>,
+                               w: &mut Write,
+                               dataflow: &Fn(BasicBlock, usize) -> Vec<String>)
+                               -> io::Result<()> {
+    let node_path = tcx.item_path_str(tcx.map.local_def_id(node_id));
+    let title = dot::escape_html(&format!("MIR for `{}` ({})", node_path, pass_name));
+
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html><head><meta charset=\"utf-8\"><title>{}</title>", title)?;
+    writeln!(w, "<style>")?;
+    writeln!(w, "body {{ font-family: monospace; }}")?;
+    writeln!(w, "table.block {{ border-collapse: collapse; margin: 1em 0; }}")?;
+    writeln!(w, "table.block td, table.block th {{ padding: 0 0.5em; text-align: left; }}")?;
+    writeln!(w, "tr.flow td {{ background: #fde; color: #555; }}")?;
+    writeln!(w, "td.comment {{ color: #888; }}")?;
+    writeln!(w, "</style></head><body>")?;
+    writeln!(w, "<h1>{}</h1>", title)?;
+
+    let mut intro = Vec::new();
+    write_mir_intro(tcx, node_id, mir, &mut intro)?;
+    writeln!(w, "<pre>{}}}</pre>", dot::escape_html(&String::from_utf8_lossy(&intro)))?;
+
+    for block in mir.all_basic_blocks() {
+        let data = mir.basic_block_data(block);
+        writeln!(w, "<table class=\"block\" id=\"{:?}\">", block)?;
+        writeln!(w, "<tr><th colspan=\"3\">{:?}{}</th></tr>",
+                 block, if data.is_cleanup { " (cleanup)" } else { "" })?;
+
+        for (index, statement) in data.statements.iter().enumerate() {
+            write_html_flow_row(block, index, w, dataflow)?;
+            write_html_row(index,
+                           &format!("{:?};", statement),
+                           &comment(tcx, statement.scope, statement.span),
+                           w)?;
+        }
+
+        let index = data.statements.len();
+        let terminator = data.terminator();
+        write_html_flow_row(block, index, w, dataflow)?;
+        write_html_row(index,
+                       &format!("{:?};", terminator.kind),
+                       &comment(tcx, terminator.scope, terminator.span),
+                       w)?;
+
+        let successors = terminator.successors();
+        if !successors.is_empty() {
+            write!(w, "<tr><td></td><td colspan=\"2\">-&gt;")?;
+            for succ in successors.iter() {
+                write!(w, " <a href=\"#{0:?}\">{0:?}</a>", succ)?;
+            }
+            writeln!(w, "</td></tr>")?;
+        }
+        writeln!(w, "</table>")?;
+    }
+
+    writeln!(w, "</body></html>")
+}
+
+fn write_html_flow_row(block: BasicBlock,
+                       index: usize,
+                       w: &mut Write,
+                       dataflow: &Fn(BasicBlock, usize) -> Vec<String>)
+                       -> io::Result<()> {
+    let facts = dataflow(block, index);
+    if facts.is_empty() {
+        return Ok(());
+    }
+    write!(w, "<tr class=\"flow\"><td></td><td colspan=\"2\">")?;
+    for (i, fact) in facts.iter().enumerate() {
+        if i > 0 {
+            write!(w, "<br>")?;
+        }
+        write!(w, "{}", dot::escape_html(fact))?;
+    }
+    writeln!(w, "</td></tr>")
+}
+
+fn write_html_row(index: usize, text: &str, comment: &str, w: &mut Write)
+                  -> io::Result<()> {
+    writeln!(w, "<tr><td>{}</td><td>{}</td><td class=\"comment\">// {}</td></tr>",
+             index, dot::escape_html(text), dot::escape_html(comment))
+}
+
 fn comment(tcx: &TyCtxt,
            scope: ScopeId,
            span: Span)
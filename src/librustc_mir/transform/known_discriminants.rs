@@ -0,0 +1,262 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A pass that removes enum switches whose outcome is already known.
+//!
+//! Nested matches over the same value switch on its discriminant
+//! over and over again:
+//!
+//! ```rust
+//! match x {
+//!     Some(_) => match x { Some(y) => y, None => 0 },
+//!     None => 1,
+//! }
+//! ```
+//!
+//! This pass tracks, along the edges of the CFG, which variants each
+//! enum lvalue may still have, learning that either from the edge of a
+//! `Switch` taken on it or from the enum being built in place. A
+//! `Switch` whose outcome is known becomes a `Goto`, and targets that
+//! can never be taken are redirected to one that can. As in sparse
+//! conditional constant propagation, the edges ruled out are not
+//! followed, so blocks only reachable through them are left dead.
+//!
+//! Only lvalues rooted in a local that is never borrowed, and reached
+//! through fields and downcasts alone, are tracked: those can only
+//! change by being assigned to, by receiving the result of a call, or
+//! by being dropped.
+
+use rustc_data_structures::bitvec::BitVector;
+use rustc::ty::{AdtKind, TyCtxt};
+use rustc::mir::repr::*;
+use rustc::mir::transform::{MirPass, Pass};
+use pretty;
+use syntax::ast::NodeId;
+
+pub struct KnownDiscriminants;
+
+/// The variants each tracked lvalue may have; an lvalue that is not
+/// listed may have any variant.
+type Facts<'tcx> = Vec<(Lvalue<'tcx>, BitVector)>;
+
+impl<'tcx> MirPass<'tcx> for KnownDiscriminants {
+    fn run_pass(&mut self, tcx: &TyCtxt<'tcx>, id: NodeId, mir: &mut Mir<'tcx>) {
+        if tcx.sess.opts.mir_opt_level < 2 {
+            return;
+        }
+
+        let borrowed = borrowed_roots(mir);
+        let entry_facts = entry_facts(mir, &borrowed);
+
+        for bb in mir.all_basic_blocks() {
+            let facts = match entry_facts[bb.index()] {
+                Some(ref facts) => facts_at_terminator(mir, bb, facts.clone(), &borrowed),
+                None => continue
+            };
+
+            let kind = match mir.basic_block_data(bb).terminator().kind {
+                TerminatorKind::Switch { ref discr, adt_def, ref targets } => {
+                    let possible = match known_variants(&facts, discr) {
+                        Some(possible) => possible,
+                        None => continue
+                    };
+                    let live: Vec<_> = possible.iter().collect();
+                    if live.len() == 1 {
+                        TerminatorKind::Goto { target: targets[live[0]] }
+                    } else if !live.is_empty() && live.len() < targets.len() {
+                        let fallback = targets[live[0]];
+                        TerminatorKind::Switch {
+                            discr: discr.clone(),
+                            adt_def: adt_def,
+                            targets: targets.iter().enumerate().map(|(variant, &target)| {
+                                if possible.contains(variant) { target } else { fallback }
+                            }).collect()
+                        }
+                    } else {
+                        continue
+                    }
+                }
+                _ => continue
+            };
+            debug!("known_discriminants: terminator of {:?} rewritten to {:?}", bb, kind);
+            mir.basic_block_data_mut(bb).terminator_mut().kind = kind;
+        }
+
+        pretty::dump_mir(tcx, "known_discriminants", &0, id, mir, None);
+    }
+}
+
+impl Pass for KnownDiscriminants {}
+
+/// Computes the facts holding on entry to each block. Blocks that can
+/// only be reached through switch edges that are never taken get
+/// `None`.
+fn entry_facts<'tcx>(mir: &Mir<'tcx>, borrowed: &[Lvalue<'tcx>]) -> Vec<Option<Facts<'tcx>>> {
+    let mut entry_facts = vec![None; mir.basic_blocks.len()];
+    entry_facts[START_BLOCK.index()] = Some(vec![]);
+
+    let mut worklist = vec![START_BLOCK];
+    while let Some(bb) = worklist.pop() {
+        let facts = entry_facts[bb.index()].clone().unwrap();
+        let facts = facts_at_terminator(mir, bb, facts, borrowed);
+        let terminator = mir.basic_block_data(bb).terminator();
+        for (target, facts) in successor_facts(terminator, facts, borrowed) {
+            if merge_facts(&mut entry_facts[target.index()], facts) {
+                worklist.push(target);
+            }
+        }
+    }
+
+    entry_facts
+}
+
+/// Applies the statements of `bb` to the facts holding on its entry.
+fn facts_at_terminator<'tcx>(mir: &Mir<'tcx>,
+                             bb: BasicBlock,
+                             mut facts: Facts<'tcx>,
+                             borrowed: &[Lvalue<'tcx>])
+                             -> Facts<'tcx> {
+    for statement in &mir.basic_block_data(bb).statements {
+        match statement.kind {
+            StatementKind::Assign(ref lvalue, ref rvalue) => {
+                kill(&mut facts, lvalue);
+                if let Rvalue::Aggregate(AggregateKind::Adt(adt_def, variant, _), _) = *rvalue {
+                    if adt_def.adt_kind() == AdtKind::Enum && is_tracked(lvalue, borrowed) {
+                        set_variant(&mut facts, lvalue, adt_def.variants.len(), variant);
+                    }
+                }
+            }
+        }
+    }
+    facts
+}
+
+/// The facts holding on each edge leaving a block whose terminator is
+/// reached with `facts`. Switch edges that can't be taken are left out.
+fn successor_facts<'tcx>(terminator: &Terminator<'tcx>,
+                         mut facts: Facts<'tcx>,
+                         borrowed: &[Lvalue<'tcx>])
+                         -> Vec<(BasicBlock, Facts<'tcx>)> {
+    match terminator.kind {
+        TerminatorKind::Switch { ref discr, ref targets, .. } if is_tracked(discr, borrowed) => {
+            let possible = known_variants(&facts, discr).cloned();
+            return targets.iter().enumerate().filter(|&(variant, _)| {
+                possible.as_ref().map_or(true, |possible| possible.contains(variant))
+            }).map(|(variant, &target)| {
+                let mut facts = facts.clone();
+                set_variant(&mut facts, discr, targets.len(), variant);
+                (target, facts)
+            }).collect();
+        }
+        TerminatorKind::Call { destination: Some((ref lvalue, _)), .. } => {
+            kill(&mut facts, lvalue);
+        }
+        TerminatorKind::Drop { ref location, .. } => {
+            kill(&mut facts, location);
+        }
+        _ => {}
+    }
+
+    terminator.successors().iter().map(|&target| (target, facts.clone())).collect()
+}
+
+/// Merges the facts flowing in over an edge into those known on entry
+/// to its target, returning whether the latter changed.
+fn merge_facts<'tcx>(entry: &mut Option<Facts<'tcx>>, incoming: Facts<'tcx>) -> bool {
+    if entry.is_none() {
+        *entry = Some(incoming);
+        return true;
+    }
+    let known = entry.as_mut().unwrap();
+
+    // an lvalue only keeps a known set of variants if every edge into
+    // the block knows one, and then it may have any of them
+    let before = known.len();
+    known.retain(|&(ref lvalue, _)| known_variants(&incoming, lvalue).is_some());
+    let mut changed = known.len() != before;
+    for &mut (ref lvalue, ref mut possible) in known.iter_mut() {
+        changed |= possible.insert_all(known_variants(&incoming, lvalue).unwrap());
+    }
+    changed
+}
+
+fn known_variants<'a, 'tcx>(facts: &'a Facts<'tcx>, lvalue: &Lvalue<'tcx>)
+                            -> Option<&'a BitVector> {
+    facts.iter().find(|&&(ref known, _)| known == lvalue).map(|&(_, ref possible)| possible)
+}
+
+fn set_variant<'tcx>(facts: &mut Facts<'tcx>,
+                     lvalue: &Lvalue<'tcx>,
+                     num_variants: usize,
+                     variant: usize) {
+    let mut possible = BitVector::new(num_variants);
+    possible.insert(variant);
+    facts.retain(|&(ref known, _)| known != lvalue);
+    facts.push((lvalue.clone(), possible));
+}
+
+/// Forgets everything known about lvalues that share a root with
+/// `lvalue`, which is being written to.
+fn kill<'tcx>(facts: &mut Facts<'tcx>, lvalue: &Lvalue<'tcx>) {
+    let root = root(lvalue);
+    facts.retain(|&(ref known, _)| self::root(known) != root);
+}
+
+fn root<'a, 'tcx>(lvalue: &'a Lvalue<'tcx>) -> &'a Lvalue<'tcx> {
+    match *lvalue {
+        Lvalue::Projection(ref proj) => root(&proj.base),
+        _ => lvalue
+    }
+}
+
+fn is_tracked<'tcx>(lvalue: &Lvalue<'tcx>, borrowed: &[Lvalue<'tcx>]) -> bool {
+    match *lvalue {
+        Lvalue::Static(_) => false,
+        Lvalue::Projection(ref proj) => {
+            match proj.elem {
+                ProjectionElem::Field(..) |
+                ProjectionElem::Downcast(..) => is_tracked(&proj.base, borrowed),
+                _ => false
+            }
+        }
+        _ => !borrowed.contains(lvalue)
+    }
+}
+
+/// The roots of all lvalues that are borrowed, or otherwise written to
+/// behind our back, anywhere in the function.
+fn borrowed_roots<'tcx>(mir: &Mir<'tcx>) -> Vec<Lvalue<'tcx>> {
+    let mut borrowed = vec![];
+    {
+        let mut add = |lvalue: &Lvalue<'tcx>| {
+            let root = root(lvalue);
+            if !borrowed.contains(root) {
+                borrowed.push(root.clone());
+            }
+        };
+        for data in &mir.basic_blocks {
+            for statement in &data.statements {
+                match statement.kind {
+                    StatementKind::Assign(_, Rvalue::Ref(_, _, ref lvalue)) |
+                    StatementKind::Assign(_, Rvalue::Slice { input: ref lvalue, .. }) => {
+                        add(lvalue);
+                    }
+                    StatementKind::Assign(_, Rvalue::InlineAsm { ref outputs, .. }) => {
+                        for output in outputs {
+                            add(output);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    borrowed
+}
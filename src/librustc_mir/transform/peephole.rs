@@ -0,0 +1,215 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A small framework for local, pattern-based MIR rewrites.
+//!
+//! A peephole rule looks at a single statement or terminator in
+//! isolation and, if it recognizes the pattern it is interested in,
+//! returns the replacement. Rules need no dataflow and no knowledge of
+//! the rest of the function, so a micro-optimization can be added by
+//! implementing `PeepholeRule` and registering it in
+//! `Peephole::with_default_rules`.
+//!
+//! The `Peephole` pass applies its rules to every statement and
+//! terminator until none of them matches any more, so each rule must
+//! make its input strictly simpler. It only runs at
+//! `-Z mir-opt-level=2` and above.
+
+use rustc::hir;
+use rustc::middle::const_val::ConstVal;
+use rustc::ty::{self, TyCtxt};
+use rustc::mir::repr::*;
+use rustc::mir::transform::{MirPass, Pass};
+use pretty;
+use syntax::ast::NodeId;
+
+/// A local rewrite of a statement or terminator. Both methods default
+/// to not matching anything.
+pub trait PeepholeRule<'tcx> {
+    fn rewrite_statement(&self,
+                         _tcx: &TyCtxt<'tcx>,
+                         _mir: &Mir<'tcx>,
+                         _statement: &StatementKind<'tcx>)
+                         -> Option<StatementKind<'tcx>> {
+        None
+    }
+
+    fn rewrite_terminator(&self,
+                          _tcx: &TyCtxt<'tcx>,
+                          _mir: &Mir<'tcx>,
+                          _terminator: &TerminatorKind<'tcx>)
+                          -> Option<TerminatorKind<'tcx>> {
+        None
+    }
+}
+
+/// Applies a set of `PeepholeRule`s to a function until it reaches a
+/// fixed point.
+pub struct Peephole {
+    rules: Vec<Box<for<'tcx> PeepholeRule<'tcx>>>,
+}
+
+impl Peephole {
+    pub fn new() -> Peephole {
+        Peephole { rules: Vec::new() }
+    }
+
+    /// A pass running the built-in rules.
+    pub fn with_default_rules() -> Peephole {
+        let mut peephole = Peephole::new();
+        peephole.add_rule(Box::new(CompareWithBool));
+        peephole.add_rule(Box::new(ReborrowOfSharedRef));
+        peephole.add_rule(Box::new(NoOpCast));
+        peephole
+    }
+
+    pub fn add_rule(&mut self, rule: Box<for<'tcx> PeepholeRule<'tcx>>) {
+        self.rules.push(rule);
+    }
+
+    fn rewrite_statement<'tcx>(&self,
+                               tcx: &TyCtxt<'tcx>,
+                               mir: &Mir<'tcx>,
+                               statement: &StatementKind<'tcx>)
+                               -> Option<StatementKind<'tcx>> {
+        self.rules.iter().filter_map(|rule| rule.rewrite_statement(tcx, mir, statement)).next()
+    }
+
+    fn rewrite_terminator<'tcx>(&self,
+                                tcx: &TyCtxt<'tcx>,
+                                mir: &Mir<'tcx>,
+                                terminator: &TerminatorKind<'tcx>)
+                                -> Option<TerminatorKind<'tcx>> {
+        self.rules.iter().filter_map(|rule| rule.rewrite_terminator(tcx, mir, terminator)).next()
+    }
+}
+
+impl<'tcx> MirPass<'tcx> for Peephole {
+    fn run_pass(&mut self, tcx: &TyCtxt<'tcx>, id: NodeId, mir: &mut Mir<'tcx>) {
+        if tcx.sess.opts.mir_opt_level < 2 {
+            return;
+        }
+
+        for bb in mir.all_basic_blocks() {
+            for index in 0..mir.basic_block_data(bb).statements.len() {
+                loop {
+                    let kind = match self.rewrite_statement(
+                        tcx, mir, &mir.basic_block_data(bb).statements[index].kind) {
+                        Some(kind) => kind,
+                        None => break
+                    };
+                    debug!("peephole: {:?}[{}] rewritten to {:?}", bb, index, kind);
+                    mir.basic_block_data_mut(bb).statements[index].kind = kind;
+                }
+            }
+
+            loop {
+                let kind = match self.rewrite_terminator(
+                    tcx, mir, &mir.basic_block_data(bb).terminator().kind) {
+                    Some(kind) => kind,
+                    None => break
+                };
+                debug!("peephole: terminator of {:?} rewritten to {:?}", bb, kind);
+                mir.basic_block_data_mut(bb).terminator_mut().kind = kind;
+            }
+        }
+
+        pretty::dump_mir(tcx, "peephole", &0, id, mir, None);
+    }
+}
+
+impl Pass for Peephole {}
+
+fn bool_constant(operand: &Operand) -> Option<bool> {
+    match *operand {
+        Operand::Constant(Constant {
+            literal: Literal::Value { value: ConstVal::Bool(b) }, ..
+        }) => Some(b),
+        _ => None
+    }
+}
+
+/// `x == true` and `x != false` become `x`; `x == false` and
+/// `x != true` become `!x`.
+pub struct CompareWithBool;
+
+impl<'tcx> PeepholeRule<'tcx> for CompareWithBool {
+    fn rewrite_statement(&self,
+                         _tcx: &TyCtxt<'tcx>,
+                         _mir: &Mir<'tcx>,
+                         statement: &StatementKind<'tcx>)
+                         -> Option<StatementKind<'tcx>> {
+        let StatementKind::Assign(ref lvalue, ref rvalue) = *statement;
+        let (eq, lhs, rhs) = match *rvalue {
+            Rvalue::BinaryOp(BinOp::Eq, ref lhs, ref rhs) => (true, lhs, rhs),
+            Rvalue::BinaryOp(BinOp::Ne, ref lhs, ref rhs) => (false, lhs, rhs),
+            _ => return None
+        };
+        let (value, constant) = match (bool_constant(lhs), bool_constant(rhs)) {
+            (None, Some(constant)) => (lhs, constant),
+            (Some(constant), None) => (rhs, constant),
+            _ => return None
+        };
+        let rvalue = if eq == constant {
+            Rvalue::Use(value.clone())
+        } else {
+            Rvalue::UnaryOp(UnOp::Not, value.clone())
+        };
+        Some(StatementKind::Assign(lvalue.clone(), rvalue))
+    }
+}
+
+/// `&*p` becomes a copy of `p` when `p` is itself a shared reference.
+pub struct ReborrowOfSharedRef;
+
+impl<'tcx> PeepholeRule<'tcx> for ReborrowOfSharedRef {
+    fn rewrite_statement(&self,
+                         tcx: &TyCtxt<'tcx>,
+                         mir: &Mir<'tcx>,
+                         statement: &StatementKind<'tcx>)
+                         -> Option<StatementKind<'tcx>> {
+        let StatementKind::Assign(ref lvalue, ref rvalue) = *statement;
+        let base = match *rvalue {
+            Rvalue::Ref(_, BorrowKind::Shared, Lvalue::Projection(ref proj)) => {
+                match proj.elem {
+                    ProjectionElem::Deref => &proj.base,
+                    _ => return None
+                }
+            }
+            _ => return None
+        };
+        match mir.lvalue_ty(tcx, base).to_ty(tcx).sty {
+            ty::TyRef(_, ty::TypeAndMut { mutbl: hir::MutImmutable, .. }) => {
+                let rvalue = Rvalue::Use(Operand::Consume(base.clone()));
+                Some(StatementKind::Assign(lvalue.clone(), rvalue))
+            }
+            _ => None
+        }
+    }
+}
+
+/// `x as T` becomes `x` when `x` already has type `T`.
+pub struct NoOpCast;
+
+impl<'tcx> PeepholeRule<'tcx> for NoOpCast {
+    fn rewrite_statement(&self,
+                         tcx: &TyCtxt<'tcx>,
+                         mir: &Mir<'tcx>,
+                         statement: &StatementKind<'tcx>)
+                         -> Option<StatementKind<'tcx>> {
+        let StatementKind::Assign(ref lvalue, ref rvalue) = *statement;
+        match *rvalue {
+            Rvalue::Cast(CastKind::Misc, ref operand, ty) if mir.operand_ty(tcx, operand) == ty => {
+                Some(StatementKind::Assign(lvalue.clone(), Rvalue::Use(operand.clone())))
+            }
+            _ => None
+        }
+    }
+}
@@ -15,6 +15,8 @@ use rustc::mir::transform::{MirPass, Pass};
 use pretty;
 use syntax::ast::NodeId;
 
+use std::cmp::Ordering;
+
 use super::remove_dead_blocks::RemoveDeadBlocks;
 
 pub struct SimplifyCfg;
@@ -109,6 +111,76 @@ impl SimplifyCfg {
 
         changed
     }
+
+    /// Merges a `SwitchInt` whose otherwise-branch goes to another
+    /// `SwitchInt` on the same value (with nothing in between) into a
+    /// single switch with sorted values, which LLVM can then turn into
+    /// a jump table.
+    fn merge_switch_chains(&self, mir: &mut Mir) -> bool {
+        let mut changed = false;
+
+        for bb in mir.all_basic_blocks() {
+            let terminator = mir.basic_block_data(bb).terminator().clone();
+            let (discr, mut values, mut targets) = match terminator.kind {
+                TerminatorKind::SwitchInt { discr, values, targets, .. } => {
+                    (discr, values, targets)
+                }
+                _ => continue
+            };
+            let mut otherwise = targets.pop().unwrap();
+
+            // Follow the chain of switches, giving up on cycles.
+            let mut seen = vec![bb];
+            let mut cyclic = false;
+            loop {
+                if seen.contains(&otherwise) {
+                    cyclic = true;
+                    break;
+                }
+                seen.push(otherwise);
+                let next = mir.basic_block_data(otherwise);
+                if !next.statements.is_empty() {
+                    break;
+                }
+                match next.terminator().kind {
+                    TerminatorKind::SwitchInt { discr: ref next_discr,
+                                                values: ref next_values,
+                                                targets: ref next_targets, .. }
+                    if *next_discr == discr => {
+                        // values already tested earlier in the chain
+                        // can never reach this switch
+                        for (value, &target) in next_values.iter().zip(next_targets) {
+                            if !values.contains(value) {
+                                values.push(value.clone());
+                                targets.push(target);
+                            }
+                        }
+                        otherwise = *next_targets.last().unwrap();
+                    }
+                    _ => break
+                }
+            }
+            if cyclic || seen.len() == 2 {
+                continue;
+            }
+
+            let mut cases: Vec<_> = values.into_iter().zip(targets).collect();
+            cases.sort_by(|a, b| a.0.try_cmp(&b.0).unwrap_or(Ordering::Equal));
+            let (values, mut targets): (Vec<_>, Vec<_>) = cases.into_iter().unzip();
+            targets.push(otherwise);
+
+            debug!("merge_switch_chains: bb={:?} values={:?} targets={:?}", bb, values, targets);
+            if let TerminatorKind::SwitchInt { values: ref mut old_values,
+                                               targets: ref mut old_targets, .. } =
+                mir.basic_block_data_mut(bb).terminator_mut().kind {
+                *old_values = values;
+                *old_targets = targets;
+            }
+            changed = true;
+        }
+
+        changed
+    }
 }
 
 impl<'tcx> MirPass<'tcx> for SimplifyCfg {
@@ -119,6 +191,7 @@ impl<'tcx> MirPass<'tcx> for SimplifyCfg {
             pretty::dump_mir(tcx, "simplify_cfg", &counter, id, mir, None);
             counter += 1;
             changed = self.simplify_branches(mir);
+            changed |= self.merge_switch_chains(mir);
             changed |= self.remove_goto_chains(mir);
             RemoveDeadBlocks.run_pass(tcx, id, mir);
         }
@@ -441,6 +441,34 @@ impl<'a, 'tcx> TypeChecker<'a, 'tcx> {
                     self.check_call_inputs(mir, term, &sig, args);
                 }
             }
+            TerminatorKind::TailCall { ref func, ref args } => {
+                let func_ty = mir.operand_ty(tcx, func);
+                debug!("check_terminator: tail call, func_ty={:?}", func_ty);
+                let func_ty = match func_ty.sty {
+                    ty::TyFnDef(_, _, func_ty) | ty::TyFnPtr(func_ty) => func_ty,
+                    _ => {
+                        span_mirbug!(self, term, "tail call to non-function {:?}", func_ty);
+                        return;
+                    }
+                };
+                let sig = tcx.erase_late_bound_regions(&func_ty.sig);
+                match (sig.output, mir.return_ty) {
+                    (ty::FnConverging(ty), ty::FnConverging(ret_ty)) => {
+                        if let Err(terr) = self.mk_eqty(self.last_span, ty, ret_ty) {
+                            span_mirbug!(self, term,
+                                         "tail call return mismatch ({:?} <- {:?}): {:?}",
+                                         ret_ty, ty, terr);
+                        }
+                    }
+                    (ty::FnDiverging, ty::FnDiverging) => {}
+                    (output, ret_ty) => {
+                        span_mirbug!(self, term,
+                                     "tail call return mismatch ({:?} <- {:?})",
+                                     ret_ty, output);
+                    }
+                }
+                self.check_call_inputs(mir, term, &sig, args);
+            }
         }
     }
 
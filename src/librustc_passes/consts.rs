@@ -27,6 +27,7 @@
 use rustc::dep_graph::DepNode;
 use rustc::ty::cast::{CastKind};
 use rustc_const_eval::{ConstEvalErr, lookup_const_fn_by_id, compare_lit_exprs};
+use rustc_const_eval::is_variant_count_intrinsic;
 use rustc_const_eval::{eval_const_expr_partial, lookup_const_by_id};
 use rustc_const_eval::ErrKind::{IndexOpFeatureGated, UnimplementedConstVal};
 use rustc_const_eval::ErrKind::ErroneousReferencedConstant;
@@ -280,9 +281,21 @@ impl<'a, 'tcx> CheckCrateVisitor<'a, 'tcx> {
 }
 
 impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
+    fn visit_body_id(&mut self, id: hir::BodyId) {
+        self.tcx.map.read_body(id);
+    }
+
     fn visit_item(&mut self, i: &hir::Item) {
         debug!("visit_item(item={})", self.tcx.map.node_to_string(i.id));
         assert_eq!(self.mode, Mode::Var);
+        // Statics and consts are checked without walking the item, so
+        // the read of their bodies has to be recorded here.
+        match i.node {
+            hir::ItemStatic(_, _, ref expr) | hir::ItemConst(_, ref expr) => {
+                self.tcx.map.read_body(hir::BodyId { node_id: expr.id });
+            }
+            _ => {}
+        }
         match i.node {
             hir::ItemStatic(_, hir::MutImmutable, ref expr) => {
                 self.check_static_type(&expr);
@@ -312,6 +325,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
         match t.node {
             hir::ConstTraitItem(_, ref default) => {
                 if let Some(ref expr) = *default {
+                    self.tcx.map.read_body(hir::BodyId { node_id: expr.id });
                     self.global_expr(Mode::Const, &expr);
                 } else {
                     intravisit::walk_trait_item(self, t);
@@ -324,6 +338,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for CheckCrateVisitor<'a, 'tcx> {
     fn visit_impl_item(&mut self, i: &'v hir::ImplItem) {
         match i.node {
             hir::ImplItemKind::Const(_, ref expr) => {
+                self.tcx.map.read_body(hir::BodyId { node_id: expr.id });
                 self.global_expr(Mode::Const, &expr);
             }
             _ => self.with_mode(Mode::Var, |v| intravisit::walk_impl_item(v, i)),
@@ -664,6 +679,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
                     v.add_qualif(ConstQualif::NON_ZERO_SIZED);
                     true
                 }
+                Some(Def::Fn(did)) if is_variant_count_intrinsic(v.tcx, did) => true,
                 Some(Def::Fn(did)) => {
                     v.handle_const_fn_call(e, did, node_ty)
                 }
@@ -765,6 +781,7 @@ fn check_expr<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>,
         hir::ExprBreak(_) |
         hir::ExprAgain(_) |
         hir::ExprRet(_) |
+        hir::ExprBecome(_) |
 
         // Expressions with side-effects.
         hir::ExprAssign(..) |
@@ -785,6 +802,7 @@ fn check_adjustments<'a, 'tcx>(v: &mut CheckCrateVisitor<'a, 'tcx>, e: &hir::Exp
         None |
         Some(&ty::adjustment::AdjustReifyFnPointer) |
         Some(&ty::adjustment::AdjustUnsafeFnPointer) |
+        Some(&ty::adjustment::AdjustClosureFnPointer) |
         Some(&ty::adjustment::AdjustMutToConstPointer) => {}
 
         Some(&ty::adjustment::AdjustDerefRef(
@@ -38,7 +38,7 @@ use rustc::hir::{Block, DeclItem};
 use rustc::hir::{ForeignItem, ForeignItemFn, ForeignItemStatic};
 use rustc::hir::{Item, ItemConst, ItemEnum, ItemExternCrate, ItemFn};
 use rustc::hir::{ItemForeignMod, ItemImpl, ItemMod, ItemStatic, ItemDefaultImpl};
-use rustc::hir::{ItemStruct, ItemTrait, ItemTy, ItemUse};
+use rustc::hir::{ItemStruct, ItemTrait, ItemTraitAlias, ItemTy, ItemUse};
 use rustc::hir::{PathListIdent, PathListMod, StmtDecl};
 use rustc::hir::{Variant, ViewPathGlob, ViewPathList, ViewPathSimple};
 use rustc::hir::intravisit::{self, Visitor};
@@ -287,6 +287,10 @@ impl<'b, 'tcx:'b> Resolver<'b, 'tcx> {
                 let def = Def::TyAlias(self.ast_map.local_def_id(item.id));
                 self.define(parent, name, TypeNS, (def, sp, modifiers));
             }
+            ItemTraitAlias(..) => {
+                let def = Def::TraitAlias(self.ast_map.local_def_id(item.id));
+                self.define(parent, name, TypeNS, (def, sp, modifiers));
+            }
 
             ItemEnum(ref enum_definition, _) => {
                 let parent_link = ModuleParentLink(parent, name);
@@ -496,7 +500,7 @@ impl<'b, 'tcx:'b> Resolver<'b, 'tcx> {
                 let module = self.new_module(parent_link, Some(def), true, is_public);
                 self.try_define(parent, name, TypeNS, (module, DUMMY_SP));
             }
-            Def::TyAlias(..) | Def::AssociatedTy(..) => {
+            Def::TyAlias(..) | Def::TraitAlias(..) | Def::AssociatedTy(..) => {
                 debug!("(building reduced graph for external crate) building type {}", name);
                 self.try_define(parent, name, TypeNS, (def, DUMMY_SP, modifiers));
             }
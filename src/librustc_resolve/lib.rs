@@ -77,7 +77,7 @@ use rustc::hir::{ExprPath, ExprStruct, FnDecl};
 use rustc::hir::{ForeignItemFn, ForeignItemStatic, Generics};
 use rustc::hir::{ImplItem, Item, ItemConst, ItemEnum, ItemExternCrate};
 use rustc::hir::{ItemFn, ItemForeignMod, ItemImpl, ItemMod, ItemStatic, ItemDefaultImpl};
-use rustc::hir::{ItemStruct, ItemTrait, ItemTy, ItemUse};
+use rustc::hir::{ItemStruct, ItemTrait, ItemTraitAlias, ItemTy, ItemUse};
 use rustc::hir::Local;
 use rustc::hir::{Pat, PatKind, Path, PrimTy};
 use rustc::hir::{PathSegment, PathParameters};
@@ -1694,6 +1694,19 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                 });
             }
 
+            ItemTraitAlias(ref generics, ref bounds) => {
+                self.with_type_parameter_rib(HasTypeParameters(generics,
+                                                               TypeSpace,
+                                                               ItemRibKind),
+                                             |this| {
+                    let local_def_id = this.ast_map.local_def_id(item.id);
+                    this.with_self_rib(Def::SelfTy(Some(local_def_id), None), |this| {
+                        this.visit_generics(generics);
+                        walk_list!(this, visit_ty_param_bound, bounds);
+                    });
+                });
+            }
+
             ItemMod(_) | ItemForeignMod(_) => {
                 self.with_scope(item.id, |this| {
                     intravisit::walk_item(this, item);
@@ -1837,7 +1850,11 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                path_depth: usize)
                                -> Result<PathResolution, ()> {
         self.resolve_path(id, trait_path, path_depth, TypeNS).and_then(|path_res| {
-            if let Def::Trait(_) = path_res.base_def {
+            let is_trait = match path_res.base_def {
+                Def::Trait(_) | Def::TraitAlias(_) => true,
+                _ => false,
+            };
+            if is_trait {
                 debug!("(resolving trait) found trait def: {:?}", path_res);
                 Ok(path_res)
             } else {
@@ -1868,8 +1885,8 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                     trait_name,
                     TypeNS,
                     |def| match def {
-                        Def::Trait(_) => true,
-                        _             => false,
+                        Def::Trait(_) | Def::TraitAlias(_) => true,
+                        _ => false,
                     },
                 );
 
@@ -2210,6 +2227,7 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                     TypeNS,
                                     |def| match def {
                                         Def::Trait(_) |
+                                        Def::TraitAlias(_) |
                                         Def::Enum(_) |
                                         Def::Struct(_) |
                                         Def::TyAlias(_) => true,
@@ -2570,6 +2588,23 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
         ResolveAttempt(resolution)
     }
 
+    /// Handles `Self` used as a value inside an impl, as in `Self(..)` for a
+    /// tuple struct. Only the self type is known here; typeck maps it to the
+    /// constructor of the struct it names.
+    fn resolve_self_value_path(&mut self, id: NodeId, path: &Path) -> Option<PathResolution> {
+        if path.global || path.segments.len() != 1 ||
+           path.segments[0].identifier.name != special_idents::type_self.name {
+            return None;
+        }
+        match self.with_no_errors(|this| this.resolve_path(id, path, 0, TypeNS)) {
+            Ok(resolution) => match resolution.base_def {
+                Def::SelfTy(_, Some(_)) => Some(resolution),
+                _ => None,
+            },
+            Err(_) => None,
+        }
+    }
+
     /// Skips `path_depth` trailing segments, which is also reflected in the
     /// returned value. See `hir::def::PathResolution` for more info.
     fn resolve_path(&mut self, id: NodeId, path: &Path, path_depth: usize, namespace: Namespace)
@@ -2984,7 +3019,9 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                         intravisit::walk_expr(self, expr);
                         return;
                     }
-                    ResolveAttempt(resolution) => resolution,
+                    ResolveAttempt(resolution) => {
+                        resolution.or_else(|| self.resolve_self_value_path(expr.id, path))
+                    }
                 };
 
                 // This is a local path in the value namespace. Walk through
@@ -96,6 +96,24 @@ impl<'a, 'b, W: Write + 'b> Dump for CsvDumper<'a, 'b, W> {
         self.record("enum", data.span, values);
     }
 
+    fn expanded_token(&mut self, span: Span, data: ExpandedTokenData) {
+        if self.dump_spans {
+            self.dump_span("expanded_token", span);
+            return;
+        }
+
+        let callsite_loc = self.span.sess.codemap().lookup_char_pos(data.callsite.lo);
+        let callsite_line = callsite_loc.line.to_string();
+        let callsite_col = callsite_loc.col.to_usize().to_string();
+        let values = make_values_str(&[
+            ("callee_name", &data.callee_name),
+            ("callsite_line", &callsite_line),
+            ("callsite_col", &callsite_col)
+        ]);
+
+        self.record("expanded_token", data.span, values);
+    }
+
     fn extern_crate(&mut self, span: Span, data: ExternCrateData) {
         if self.dump_spans {
             self.dump_span("extern_crate", span);
@@ -31,6 +31,8 @@ pub struct CrateData {
 pub enum Data {
     /// Data for Enums.
     EnumData(EnumData),
+    /// Data about a token passed in to a macro and copied to its output.
+    ExpandedTokenData(ExpandedTokenData),
     /// Data for extern crates.
     ExternCrateData(ExternCrateData),
     /// Data about a function call.
@@ -103,6 +105,16 @@ pub struct EnumData {
     pub scope: NodeId,
 }
 
+/// Data about a token passed in to a macro and copied to its output.
+#[derive(Debug)]
+pub struct ExpandedTokenData {
+    /// The token as written.
+    pub span: Span,
+    /// The macro whose output the token was copied to.
+    pub callee_name: String,
+    pub callsite: Span,
+}
+
 /// Data for extern crates.
 #[derive(Debug)]
 pub struct ExternCrateData {
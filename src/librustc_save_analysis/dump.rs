@@ -15,6 +15,7 @@ use super::data::*;
 pub trait Dump {
     fn crate_prelude(&mut self, _: Span, _: CratePreludeData) {}
     fn enum_data(&mut self, _: Span, _: EnumData) {}
+    fn expanded_token(&mut self, _: Span, _: ExpandedTokenData) {}
     fn extern_crate(&mut self, _: Span, _: ExternCrateData) {}
     fn impl_data(&mut self, _: Span, _: ImplData) {}
     fn inheritance(&mut self, _: InheritanceData) {}
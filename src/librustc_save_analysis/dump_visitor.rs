@@ -138,6 +138,24 @@ where D: Dump
         self.dumper.crate_prelude(krate.span, data);
     }
 
+    /// Dumps where each token passed in to a macro was copied to, as
+    /// recorded by macro expansion.
+    pub fn dump_expanded_tokens(&mut self) {
+        let codemap = self.tcx.sess.codemap();
+        for (expanded, source) in codemap.expanded_token_sources() {
+            let callee = codemap.with_expn_info(expanded.expn_id, |info| {
+                info.map(|info| (info.callee.name(), info.call_site))
+            });
+            if let Some((name, callsite)) = callee {
+                self.dumper.expanded_token(expanded, ExpandedTokenData {
+                    span: source,
+                    callee_name: name.to_string(),
+                    callsite: callsite,
+                });
+            }
+        }
+    }
+
     // Return all non-empty prefixes of a path.
     // For each prefix, we return the span for the last segment in the prefix and
     // a str representation of the entire prefix.
@@ -312,7 +330,8 @@ where D: Dump
             Def::Enum(..) |
             Def::TyAlias(..) |
             Def::AssociatedTy(..) |
-            Def::Trait(_) => {
+            Def::Trait(_) |
+            Def::TraitAlias(_) => {
                 self.dumper.type_ref(span, TypeRefData {
                     span: sub_span.expect("No span found for type ref"),
                     ref_id: Some(def_id),
@@ -467,6 +467,7 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
             Def::Enum(def_id) |
             Def::TyAlias(def_id) |
             Def::Trait(def_id) |
+            Def::TraitAlias(def_id) |
             Def::TyParam(_, _, def_id, _) => {
                 Some(Data::TypeRefData(TypeRefData {
                     span: sub_span.unwrap(),
@@ -608,6 +609,21 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
         })
     }
 
+    /// For a span in macro-expanded code, returns the span of the token the
+    /// user passed in to the macro, if that is where it was copied from.
+    ///
+    /// Lets tools point a definition found inside an expansion at the
+    /// identifier that was actually written.
+    pub fn source_token_span(&self, span: Span) -> Option<Span> {
+        self.tcx.sess.codemap().source_token(span)
+    }
+
+    /// Returns every span in macro-expanded code that the token at `span`
+    /// was copied to. The inverse of `source_token_span`.
+    pub fn expanded_token_spans(&self, span: Span) -> Vec<Span> {
+        self.tcx.sess.codemap().expanded_tokens(span)
+    }
+
     pub fn get_data_for_id(&self, _id: &NodeId) -> Data {
         // FIXME
         bug!();
@@ -733,6 +749,7 @@ pub fn process_crate<'l, 'tcx>(tcx: &'l TyCtxt<'tcx>,
 
     visitor.dump_crate_info(cratename, krate);
     visit::walk_crate(&mut visitor, krate);
+    visitor.dump_expanded_tokens();
 }
 
 // Utility functions for the module.
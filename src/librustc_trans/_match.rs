@@ -952,7 +952,15 @@ fn insert_lllocals<'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                                bindings_map: &BindingsMap<'tcx>,
                                cs: Option<cleanup::ScopeId>)
                                -> Block<'blk, 'tcx> {
-    for (&name, &binding_info) in bindings_map {
+    // Copies out of the matched value are made before any binding moves
+    // out of it, as in `x @ Foo(y)`.
+    let (moves, copies): (Vec<_>, Vec<_>) = bindings_map.iter().partition(|&(_, info)| {
+        match info.trmode {
+            TrByMoveIntoCopy(..) | TrByMoveRef => true,
+            TrByCopy(..) | TrByRef => false,
+        }
+    });
+    for (&name, &binding_info) in copies.into_iter().chain(moves) {
         let (llval, aliases_other_state) = match binding_info.trmode {
             // By value mut binding for a copy type: load from the ptr
             // into the matched value and copy to our alloca
@@ -1801,6 +1809,12 @@ pub fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     let ccx = bcx.ccx();
     match pat.node {
         PatKind::Ident(pat_binding_mode, ref path1, ref inner) => {
+            // Bind the subpattern of `x @ P` first, so that bindings in
+            // `P` copy out of `val` before `x` moves it.
+            if let Some(ref inner_pat) = *inner {
+                bcx = bind_irrefutable_pat(bcx, &inner_pat, val, cleanup_scope);
+            }
+
             if pat_is_binding(&tcx.def_map.borrow(), &pat) {
                 // Allocate the stack slot where the value of this
                 // binding will live and place it into the appropriate
@@ -1832,10 +1846,6 @@ pub fn bind_irrefutable_pat<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                         }
                     });
             }
-
-            if let Some(ref inner_pat) = *inner {
-                bcx = bind_irrefutable_pat(bcx, &inner_pat, val, cleanup_scope);
-            }
         }
         PatKind::TupleStruct(_, ref sub_pats) => {
             let opt_def = bcx.tcx().def_map.borrow().get(&pat.id).map(|d| d.full_def());
@@ -11,7 +11,7 @@
 use llvm::{self, ValueRef};
 use base;
 use builder::Builder;
-use common::{type_is_fat_ptr, BlockAndBuilder};
+use common::{type_is_fat_ptr, type_is_sized, BlockAndBuilder};
 use context::CrateContext;
 use cabi_x86;
 use cabi_x86_64;
@@ -366,7 +366,14 @@ impl FnType {
             _ => None
         };
 
-        for ty in inputs.iter().chain(extra_args.iter()) {
+        for &ty in inputs.iter().chain(extra_args.iter()) {
+            // An unsized argument (see the `unsized_locals` feature) stays
+            // where the caller has it, and is passed as a fat pointer to it.
+            let ty = if type_is_sized(ccx.tcx(), ty) {
+                ty
+            } else {
+                ccx.tcx().mk_mut_ptr(ty)
+            };
             let mut arg = arg_of(ty, false);
 
             if type_is_fat_ptr(ccx.tcx(), ty) {
@@ -698,6 +698,14 @@ fn ensure_struct_fits_in_address_space<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     }
 }
 
+/// The offsets of the (sized) fields of `st`, in bytes.
+pub fn struct_field_offsets<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, st: &Struct<'tcx>)
+                                      -> Vec<u64> {
+    let llfields = struct_llfields(cx, st, true, !st.sized);
+    let llty = Type::struct_(cx, &llfields, st.packed);
+    (0..llfields.len()).map(|i| machine::llelement_offset(cx, llty, i)).collect()
+}
+
 fn union_size_and_align(sts: &[Struct]) -> (machine::llsize, machine::llalign) {
     let size = sts.iter().map(|st| st.size).max().unwrap();
     let align = sts.iter().map(|st| st.align).max().unwrap();
@@ -12,8 +12,8 @@
 use libc::c_uint;
 use llvm::{self, ValueRef};
 use session::config::NoDebugInfo;
-pub use syntax::attr::InlineAttr;
-use syntax::ast;
+pub use syntax::attr::{InlineAttr, OptimizeAttr};
+use rustc::hir::def_id::DefId;
 use context::CrateContext;
 
 /// Mark LLVM function to use provided inline heuristic.
@@ -55,7 +55,6 @@ pub fn unwind(val: ValueRef, can_unwind: bool) {
 
 /// Tell LLVM whether it should optimise function for size.
 #[inline]
-#[allow(dead_code)] // possibly useful function
 pub fn set_optimize_for_size(val: ValueRef, optimize: bool) {
     if optimize {
         llvm::SetFunctionAttribute(val, llvm::Attribute::OptimizeForSize);
@@ -76,9 +75,14 @@ pub fn naked(val: ValueRef, is_naked: bool) {
 
 /// Composite function which sets LLVM attributes for function depending on its AST (#[attribute])
 /// attributes.
-pub fn from_fn_attrs(ccx: &CrateContext, attrs: &[ast::Attribute], llfn: ValueRef) {
-    use syntax::attr::*;
-    inline(llfn, find_inline_attr(Some(ccx.sess().diagnostic()), attrs));
+pub fn from_fn_attrs(ccx: &CrateContext, def_id: DefId, llfn: ValueRef) {
+    let codegen_attrs = ccx.tcx().codegen_fn_attrs(def_id);
+    inline(llfn, codegen_attrs.inline);
+    if codegen_attrs.cold {
+        llvm::Attributes::default().set(llvm::Attribute::Cold)
+            .apply_llfn(llvm::FunctionIndex as usize, llfn)
+    }
+    set_optimize_for_size(llfn, codegen_attrs.optimize == OptimizeAttr::Size);
 
     // FIXME: #11906: Omitting frame pointers breaks retrieving the value of a
     // parameter.
@@ -94,11 +98,8 @@ pub fn from_fn_attrs(ccx: &CrateContext, attrs: &[ast::Attribute], llfn: ValueRe
         }
     }
 
-    for attr in attrs {
-        if attr.check_name("cold") {
-            llvm::Attributes::default().set(llvm::Attribute::Cold)
-                .apply_llfn(llvm::FunctionIndex as usize, llfn)
-        } else if attr.check_name("naked") {
+    for attr in ccx.tcx().get_attrs(def_id).iter() {
+        if attr.check_name("naked") {
             naked(llfn, true);
         } else if attr.check_name("allocator") {
             llvm::Attributes::default().set(llvm::Attribute::NoAlias)
@@ -202,9 +202,45 @@ pub fn link_binary(sess: &Session,
         remove(sess, &outputs.with_extension("metadata.o"));
     }
 
+    if sess.opts.debugging_opts.size_report {
+        print_size_report(sess, &out_filenames);
+    }
+
     out_filenames
 }
 
+/// Prints the size of each linked output along with the options that
+/// affect it, for `-Z size-report`. Only the effect of
+/// `-Z no-panic-messages` is known exactly; the others are listed so that
+/// reports from different builds can be compared.
+fn print_size_report(sess: &Session, out_filenames: &[PathBuf]) {
+    println!("size report:");
+    for out_file in out_filenames {
+        match fs::metadata(out_file) {
+            Ok(m) => println!("  {}: {} bytes", out_file.display(), m.len()),
+            Err(e) => println!("  {}: unknown size ({})", out_file.display(), e),
+        }
+    }
+    let opt_level = match sess.opts.optimize {
+        config::OptLevel::No => "0",
+        config::OptLevel::Less => "1",
+        config::OptLevel::Default => "2",
+        config::OptLevel::Aggressive => "3",
+        config::OptLevel::Size => "s",
+        config::OptLevel::SizeMin => "z",
+    };
+    println!("  opt-level: {}", opt_level);
+    println!("  lto: {}", sess.lto());
+    println!("  codegen-units: {}", sess.opts.cg.codegen_units);
+    if sess.no_panic_messages() {
+        let (sites, bytes) = sess.stripped_panics();
+        println!("  no-panic-messages: {} bytes of message and file name omitted \
+                  from {} panic sites", bytes, sites);
+    } else {
+        println!("  no-panic-messages: off");
+    }
+}
+
 
 /// Returns default crate type for target
 ///
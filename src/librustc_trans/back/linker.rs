@@ -151,7 +151,9 @@ impl<'a> Linker for GnuLinker<'a> {
         // GNU-style linkers support optimization with -O. GNU ld doesn't
         // need a numeric argument, but other linkers do.
         if self.sess.opts.optimize == config::OptLevel::Default ||
-           self.sess.opts.optimize == config::OptLevel::Aggressive {
+           self.sess.opts.optimize == config::OptLevel::Aggressive ||
+           self.sess.opts.optimize == config::OptLevel::Size ||
+           self.sess.opts.optimize == config::OptLevel::SizeMin {
             self.cmd.arg("-Wl,-O1");
         }
     }
@@ -0,0 +1,120 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The report printed by `-Z size-attribution`.
+//!
+//! Translation records every function it translates from source (glue
+//! and shims are left out), along with the instance it was translated
+//! for and the item that instance was instantiated from. Once the
+//! object files are written, the sizes of the functions' symbols are
+//! looked up in them and added up per item, so that the generic
+//! functions that account for the most machine code stand out.
+
+use llvm::{self, False, ObjectFile};
+use session::Session;
+use util::common::path2cstr;
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+
+/// A function that was translated from source.
+pub struct TranslatedFn {
+    pub symbol: String,
+    /// The instance the function was translated for, e.g. `foo::bar<u32>`.
+    pub instance: String,
+    /// The item the instance was instantiated from, e.g. `foo::bar`.
+    pub origin: String,
+}
+
+struct ItemSize<'a> {
+    size: u64,
+    instances: Vec<(u64, &'a str)>,
+}
+
+pub fn report(sess: &Session, fns: &[TranslatedFn], objects: &[PathBuf]) {
+    let mut symbol_sizes = HashMap::new();
+    for object in objects {
+        read_symbol_sizes(sess, object, &mut symbol_sizes);
+    }
+
+    // With several codegen units, a function may have been translated
+    // into more than one of them; its symbol size already covers all
+    // the copies.
+    let mut seen = HashSet::new();
+    let mut items: HashMap<&str, ItemSize> = HashMap::new();
+    for f in fns {
+        if !seen.insert(&f.symbol[..]) {
+            continue;
+        }
+        // Functions that were inlined into all their callers have no
+        // code of their own left.
+        let size = match symbol_sizes.get(&f.symbol) {
+            Some(&size) if size > 0 => size,
+            _ => continue,
+        };
+        let item = items.entry(&f.origin[..]).or_insert(ItemSize {
+            size: 0,
+            instances: vec![],
+        });
+        item.size += size;
+        item.instances.push((size, &f.instance[..]));
+    }
+
+    let mut items: Vec<_> = items.into_iter().collect();
+    // Largest items first.
+    items.sort_by(|&(a_origin, ref a), &(b_origin, ref b)| {
+        (b.size, a_origin).cmp(&(a.size, b_origin))
+    });
+
+    let total = items.iter().fold(0, |total, &(_, ref item)| total + item.size);
+    println!("code size attribution: {} bytes in {} items", total, items.len());
+    for (origin, mut item) in items {
+        println!("{:9} {} ({} instances)", item.size, origin, item.instances.len());
+        if item.instances.len() > 1 {
+            item.instances.sort_by(|a, b| b.cmp(a));
+            for (size, instance) in item.instances {
+                println!("{:9}     {}", size, instance);
+            }
+        }
+    }
+}
+
+fn read_symbol_sizes(sess: &Session, path: &Path, sizes: &mut HashMap<String, u64>) {
+    // Mach-O prefixes all symbol names with an underscore.
+    let strip_underscore = sess.target.target.options.is_like_osx;
+    unsafe {
+        let buf = path2cstr(path);
+        let mb = llvm::LLVMRustCreateMemoryBufferWithContentsOfFile(buf.as_ptr());
+        if mb.is_null() {
+            sess.warn(&format!("could not read `{}` for size attribution", path.display()));
+            return;
+        }
+        let of = match ObjectFile::new(mb) {
+            Some(of) => of,
+            None => {
+                sess.warn(&format!("could not read `{}` for size attribution",
+                                   path.display()));
+                return;
+            }
+        };
+        let si = llvm::mk_symbol_iter(of.llof);
+        while llvm::LLVMIsSymbolIteratorAtEnd(of.llof, si.llsi) == False {
+            let name = CStr::from_ptr(llvm::LLVMGetSymbolName(si.llsi)).to_string_lossy();
+            let name = if strip_underscore && name.starts_with('_') {
+                &name[1..]
+            } else {
+                &name[..]
+            };
+            *sizes.entry(name.to_string()).or_insert(0) += llvm::LLVMGetSymbolSize(si.llsi);
+            llvm::LLVMMoveToNextSymbol(si.llsi);
+        }
+    }
+}
@@ -10,6 +10,7 @@
 
 use back::lto;
 use back::link::{get_linker, remove};
+use back::size_attribution;
 use session::config::{OutputFilenames, Passes, SomePasses, AllPasses};
 use session::Session;
 use session::config::{self, OutputType};
@@ -23,10 +24,13 @@ use syntax::codemap;
 use syntax::errors::{self, Handler, Level};
 use syntax::errors::emitter::Emitter;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::fs;
+use std::mem;
 use std::path::{Path, PathBuf};
+use std::ptr;
 use std::str;
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc::channel;
@@ -140,6 +144,15 @@ fn get_llvm_opt_level(optimize: config::OptLevel) -> llvm::CodeGenOptLevel {
       config::OptLevel::Less => llvm::CodeGenLevelLess,
       config::OptLevel::Default => llvm::CodeGenLevelDefault,
       config::OptLevel::Aggressive => llvm::CodeGenLevelAggressive,
+      _ => llvm::CodeGenLevelDefault,
+    }
+}
+
+fn get_llvm_opt_size(optimize: config::OptLevel) -> llvm::CodeGenOptSize {
+    match optimize {
+      config::OptLevel::Size => llvm::CodeGenOptSizeDefault,
+      config::OptLevel::SizeMin => llvm::CodeGenOptSizeAggressive,
+      _ => llvm::CodeGenOptSizeNone,
     }
 }
 
@@ -194,15 +207,17 @@ pub fn create_target_machine(sess: &Session) -> TargetMachineRef {
         }
     };
 
+    if sess.opts.debugging_opts.emit_stack_sizes &&
+       (sess.target.target.options.is_like_osx || sess.target.target.options.is_like_windows) {
+        sess.err("-Z emit-stack-sizes is only supported for ELF targets");
+        sess.abort_if_errors();
+    }
+
     let triple = &sess.target.target.llvm_target;
 
     let tm = unsafe {
         let triple = CString::new(triple.as_bytes()).unwrap();
-        let cpu = match sess.opts.cg.target_cpu {
-            Some(ref s) => &**s,
-            None => &*sess.target.target.options.cpu
-        };
-        let cpu = CString::new(cpu.as_bytes()).unwrap();
+        let cpu = CString::new(sess.target_cpu().as_bytes()).unwrap();
         let features = CString::new(target_feature(sess).as_bytes()).unwrap();
         llvm::LLVMRustCreateTargetMachine(
             triple.as_ptr(), cpu.as_ptr(), features.as_ptr(),
@@ -225,6 +240,100 @@ pub fn create_target_machine(sess: &Session) -> TargetMachineRef {
     };
 }
 
+/// A target machine for the session's target, for asking LLVM which CPUs
+/// and features it knows and which features are enabled. LLVM must have
+/// been initialized with `init_llvm`.
+pub struct TargetInfo {
+    tm: TargetMachineRef,
+}
+
+impl TargetInfo {
+    /// A target machine with the target's default CPU and no features,
+    /// so that LLVM does not complain about the very options we are
+    /// about to check. Returns `None` if LLVM does not support the target
+    /// at all.
+    pub fn new(sess: &Session) -> Option<TargetInfo> {
+        TargetInfo::create(sess, &sess.target.target.options.cpu, "")
+    }
+
+    /// A target machine with the CPU and features that code is generated
+    /// for. Returns `None` if LLVM does not support the target at all.
+    pub fn for_codegen(sess: &Session) -> Option<TargetInfo> {
+        TargetInfo::create(sess, sess.target_cpu(), &target_feature(sess))
+    }
+
+    fn create(sess: &Session, cpu: &str, features: &str) -> Option<TargetInfo> {
+        let triple = CString::new(sess.target.target.llvm_target.as_bytes()).unwrap();
+        let cpu = CString::new(cpu.as_bytes()).unwrap();
+        let features = CString::new(features.as_bytes()).unwrap();
+        let tm = unsafe {
+            llvm::LLVMRustCreateTargetMachine(
+                triple.as_ptr(), cpu.as_ptr(), features.as_ptr(),
+                llvm::CodeModelDefault,
+                llvm::RelocDefault,
+                llvm::CodeGenLevelNone,
+                false,
+                false,
+                false,
+                false,
+            )
+        };
+        if tm.is_null() { None } else { Some(TargetInfo { tm: tm }) }
+    }
+
+    /// The names of the CPUs LLVM knows for the target. Empty if LLVM
+    /// cannot list them.
+    pub fn cpus(&self) -> Vec<String> {
+        unsafe {
+            (0..llvm::LLVMRustGetTargetCPUsCount(self.tm)).map(|i| {
+                let (mut name, mut desc) = (ptr::null(), ptr::null());
+                llvm::LLVMRustGetTargetCPU(self.tm, i, &mut name, &mut desc);
+                CStr::from_ptr(name).to_string_lossy().into_owned()
+            }).collect()
+        }
+    }
+
+    /// The names of the features LLVM knows for the target. Empty if LLVM
+    /// cannot list them.
+    pub fn features(&self) -> Vec<String> {
+        unsafe {
+            (0..llvm::LLVMRustGetTargetFeaturesCount(self.tm)).map(|i| {
+                let (mut name, mut desc) = (ptr::null(), ptr::null());
+                llvm::LLVMRustGetTargetFeature(self.tm, i, &mut name, &mut desc);
+                CStr::from_ptr(name).to_string_lossy().into_owned()
+            }).collect()
+        }
+    }
+
+    pub fn has_cpu(&self, cpu: &str) -> bool {
+        let cpu = CString::new(cpu).unwrap();
+        unsafe { llvm::LLVMRustHasTargetCPU(self.tm, cpu.as_ptr()) }
+    }
+
+    pub fn has_feature(&self, feature: &str) -> bool {
+        let feature = CString::new(feature).unwrap();
+        unsafe { llvm::LLVMRustHasTargetFeature(self.tm, feature.as_ptr()) }
+    }
+
+    /// The features enabled for this target machine's CPU and features,
+    /// including those they imply, sorted by name. Empty if LLVM cannot
+    /// list the target's features.
+    pub fn enabled_features(&self) -> Vec<String> {
+        let mut enabled: Vec<_> = self.features().into_iter().filter(|feature| {
+            let feature = CString::new(&feature[..]).unwrap();
+            unsafe { llvm::LLVMRustIsTargetFeatureEnabled(self.tm, feature.as_ptr()) }
+        }).collect();
+        enabled.sort();
+        enabled
+    }
+}
+
+impl Drop for TargetInfo {
+    fn drop(&mut self) {
+        unsafe { llvm::LLVMRustDisposeTargetMachine(self.tm) }
+    }
+}
+
 
 /// Module-specific configuration for `optimize_and_codegen`.
 #[derive(Clone)]
@@ -236,6 +345,7 @@ pub struct ModuleConfig {
     /// Some(level) to optimize at a certain level, or None to run
     /// absolutely no optimizations (used for the metadata module).
     opt_level: Option<llvm::CodeGenOptLevel>,
+    opt_size: Option<llvm::CodeGenOptSize>,
 
     // Flags indicating which outputs to produce.
     emit_no_opt_bc: bool,
@@ -254,6 +364,10 @@ pub struct ModuleConfig {
     vectorize_slp: bool,
     merge_functions: bool,
     inline_threshold: Option<usize>,
+    emit_stack_sizes: bool,
+    // The assembler directive for a target address, used for the entries
+    // of the `.stack_sizes` section.
+    address_directive: &'static str,
     // Instead of creating an object file by doing LLVM codegen, just
     // make the object file bitcode. Provides easy compatibility with
     // emscripten's ecc compiler, when used as the linker.
@@ -268,6 +382,7 @@ impl ModuleConfig {
             tm: tm,
             passes: passes,
             opt_level: None,
+            opt_size: None,
 
             emit_no_opt_bc: false,
             emit_bc: false,
@@ -284,7 +399,9 @@ impl ModuleConfig {
             vectorize_loop: false,
             vectorize_slp: false,
             merge_functions: false,
-            inline_threshold: None
+            inline_threshold: None,
+            emit_stack_sizes: false,
+            address_directive: ".quad",
         }
     }
 
@@ -295,18 +412,26 @@ impl ModuleConfig {
         self.time_passes = sess.time_passes();
         self.inline_threshold = sess.opts.cg.inline_threshold;
         self.obj_is_bitcode = sess.target.target.options.obj_is_bitcode;
+        self.emit_stack_sizes = sess.opts.debugging_opts.emit_stack_sizes;
+        self.address_directive = match &sess.target.target.target_pointer_width[..] {
+            "64" => ".quad",
+            _ => ".long",
+        };
 
-        // Copy what clang does by turning on loop vectorization at O2 and
-        // slp vectorization at O3. Otherwise configure other optimization aspects
-        // of this pass manager builder.
+        // Copy what clang does by turning on loop vectorization at O2 and Os
+        // and slp vectorization at O3. Otherwise configure other optimization
+        // aspects of this pass manager builder.
         self.vectorize_loop = !sess.opts.cg.no_vectorize_loops &&
                              (sess.opts.optimize == config::OptLevel::Default ||
-                              sess.opts.optimize == config::OptLevel::Aggressive);
+                              sess.opts.optimize == config::OptLevel::Aggressive ||
+                              sess.opts.optimize == config::OptLevel::Size);
         self.vectorize_slp = !sess.opts.cg.no_vectorize_slp &&
                             sess.opts.optimize == config::OptLevel::Aggressive;
 
         self.merge_functions = sess.opts.optimize == config::OptLevel::Default ||
-                               sess.opts.optimize == config::OptLevel::Aggressive;
+                               sess.opts.optimize == config::OptLevel::Aggressive ||
+                               sess.opts.optimize == config::OptLevel::Size ||
+                               sess.opts.optimize == config::OptLevel::SizeMin;
     }
 }
 
@@ -340,6 +465,8 @@ impl<'a> CodegenContext<'a> {
 struct HandlerFreeVars<'a> {
     llcx: ContextRef,
     cgcx: &'a CodegenContext<'a>,
+    // The stack size LLVM reported for each function, by symbol name.
+    stack_sizes: &'a RefCell<HashMap<String, u64>>,
 }
 
 unsafe extern "C" fn report_inline_asm<'a, 'b>(cgcx: &'a CodegenContext<'a>,
@@ -375,7 +502,7 @@ unsafe extern "C" fn inline_asm_handler(diag: SMDiagnosticRef,
 }
 
 unsafe extern "C" fn diagnostic_handler(info: DiagnosticInfoRef, user: *mut c_void) {
-    let HandlerFreeVars { llcx, cgcx } = *(user as *const HandlerFreeVars);
+    let HandlerFreeVars { llcx, cgcx, stack_sizes } = *(user as *const HandlerFreeVars);
 
     match llvm::diagnostic::Diagnostic::unpack(info) {
         llvm::diagnostic::InlineAsm(inline) => {
@@ -403,6 +530,12 @@ unsafe extern "C" fn diagnostic_handler(info: DiagnosticInfoRef, user: *mut c_vo
             }
         }
 
+        llvm::diagnostic::StackSize(ss) => {
+            let name = CStr::from_ptr(llvm::LLVMGetValueName(ss.function));
+            stack_sizes.borrow_mut().insert(name.to_string_lossy().into_owned(),
+                                            ss.stack_size);
+        }
+
         _ => (),
     }
 }
@@ -417,9 +550,11 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
     let tm = config.tm;
 
     // llcx doesn't outlive this function, so we can put this on the stack.
+    let stack_sizes = RefCell::new(HashMap::new());
     let fv = HandlerFreeVars {
         llcx: llcx,
         cgcx: cgcx,
+        stack_sizes: &stack_sizes,
     };
     let fv = &fv as *const HandlerFreeVars as *mut c_void;
 
@@ -515,26 +650,6 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
         }
     }
 
-    // A codegen-specific pass manager is used to generate object
-    // files for an LLVM module.
-    //
-    // Apparently each of these pass managers is a one-shot kind of
-    // thing, so we create a new one for each type of output. The
-    // pass manager passed to the closure should be ensured to not
-    // escape the closure itself, and the manager should only be
-    // used once.
-    unsafe fn with_codegen<F>(tm: TargetMachineRef,
-                              llmod: ModuleRef,
-                              no_builtins: bool,
-                              f: F) where
-        F: FnOnce(PassManagerRef),
-    {
-        let cpm = llvm::LLVMCreatePassManager();
-        llvm::LLVMRustAddAnalysisPasses(tm, cpm, llmod);
-        llvm::LLVMRustAddLibraryInfo(cpm, llmod, no_builtins);
-        f(cpm);
-    }
-
     // Change what we write and cleanup based on whether obj files are
     // just llvm bitcode. In that case write bitcode, and possibly
     // delete the bitcode if it wasn't requested. Don't generate the
@@ -553,6 +668,11 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
     }
 
     time(config.time_passes, &format!("codegen passes [{}]", cgcx.worker), || {
+        if config.emit_stack_sizes && (config.emit_asm || write_obj) {
+            let path = output_names.with_extension(&format!("{}.stack-sizes.s", name_extra));
+            add_stack_size_section(cgcx, &config, llmod, &stack_sizes, &path);
+        }
+
         if config.emit_ir {
             let ext = format!("{}.ll", name_extra);
             let out = output_names.with_extension(&ext);
@@ -609,6 +729,71 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
     llvm::LLVMRustDisposeTargetMachine(tm);
 }
 
+// A codegen-specific pass manager is used to generate object
+// files for an LLVM module.
+//
+// Apparently each of these pass managers is a one-shot kind of
+// thing, so we create a new one for each type of output. The
+// pass manager passed to the closure should be ensured to not
+// escape the closure itself, and the manager should only be
+// used once.
+unsafe fn with_codegen<F>(tm: TargetMachineRef,
+                          llmod: ModuleRef,
+                          no_builtins: bool,
+                          f: F) where
+    F: FnOnce(PassManagerRef),
+{
+    let cpm = llvm::LLVMCreatePassManager();
+    llvm::LLVMRustAddAnalysisPasses(tm, cpm, llmod);
+    llvm::LLVMRustAddLibraryInfo(cpm, llmod, no_builtins);
+    f(cpm);
+}
+
+/// Appends a `.stack_sizes` section to `llmod`, laid out like the one
+/// newer LLVMs emit: for each function, its address followed by the size
+/// of its stack frame as a ULEB128. The sizes are only known once the
+/// module has been compiled, so a copy of it is compiled first, with
+/// `-warn-stack-size=0` making LLVM report the frame of every function.
+/// Functions LLVM reports nothing for have no frame.
+///
+/// The section is not allocated, so it survives `--gc-sections`.
+unsafe fn add_stack_size_section(cgcx: &CodegenContext,
+                                 config: &ModuleConfig,
+                                 llmod: ModuleRef,
+                                 stack_sizes: &RefCell<HashMap<String, u64>>,
+                                 scratch: &Path) {
+    let copy = llvm::LLVMCloneModule(llmod);
+    with_codegen(config.tm, copy, config.no_builtins, |cpm| {
+        write_output_file(cgcx.handler, config.tm, cpm, copy, scratch,
+                          llvm::AssemblyFileType);
+    });
+    llvm::LLVMDisposeModule(copy);
+    if let Err(e) = fs::remove_file(scratch) {
+        cgcx.handler.err(&format!("failed to remove {}: {}", scratch.display(), e));
+    }
+    let stack_sizes = mem::replace(&mut *stack_sizes.borrow_mut(), HashMap::new());
+
+    let mut asm = String::from(".pushsection .stack_sizes,\"\",@progbits\n");
+    let mut f = llvm::LLVMGetFirstFunction(llmod);
+    while !f.is_null() {
+        let linkage = llvm::LLVMGetLinkage(f);
+        // Private functions have no symbol the assembler could refer to.
+        if llvm::LLVMIsDeclaration(f) == 0 &&
+           linkage != llvm::AvailableExternallyLinkage as c_uint &&
+           linkage != llvm::PrivateLinkage as c_uint {
+            let name = CStr::from_ptr(llvm::LLVMGetValueName(f)).to_string_lossy();
+            let size = stack_sizes.get(&*name).cloned().unwrap_or(0);
+            asm.push_str(&format!("{} {}\n.uleb128 {}\n",
+                                  config.address_directive, name, size));
+        }
+        f = llvm::LLVMGetNextFunction(f);
+    }
+    asm.push_str(".popsection\n");
+
+    let asm = CString::new(asm).unwrap();
+    llvm::LLVMRustAppendModuleInlineAsm(llmod, asm.as_ptr());
+}
+
 pub fn run_passes(sess: &Session,
                   trans: &CrateTranslation,
                   output_types: &HashMap<OutputType, Option<PathBuf>>,
@@ -637,6 +822,7 @@ pub fn run_passes(sess: &Session,
     let mut metadata_config = ModuleConfig::new(tm, vec!());
 
     modules_config.opt_level = Some(get_llvm_opt_level(sess.opts.optimize));
+    modules_config.opt_size = Some(get_llvm_opt_size(sess.opts.optimize));
 
     // Save all versions of the bytecode if we're saving our temporaries.
     if sess.opts.cg.save_temps {
@@ -719,6 +905,18 @@ pub fn run_passes(sess: &Session,
         llvm::LLVMRustDisposeTargetMachine(tm);
     }
 
+    // Measure the object code before the temporary objects go away.
+    if sess.opts.debugging_opts.size_attribution {
+        if modules_config.emit_obj {
+            let objects: Vec<_> = (0..trans.modules.len()).map(|i| {
+                crate_output.with_extension(&format!("{}.o", i))
+            }).collect();
+            size_attribution::report(sess, &trans.translated_fns, &objects);
+        } else {
+            sess.warn("-Z size-attribution has no effect unless object code is emitted");
+        }
+    }
+
     // Produce final compile outputs.
     let copy_gracefully = |from: &Path, to: &Path| {
         if let Err(e) = fs::copy(from, to) {
@@ -984,7 +1182,56 @@ pub fn run_assembler(sess: &Session, outputs: &OutputFilenames) {
     }
 }
 
-pub unsafe fn configure_llvm(sess: &Session) {
+/// Prepares LLVM for use by this process. Only the first call has any
+/// effect, so later sessions in the same process (see `-Z batch`) must
+/// ask for the same LLVM options as the first one.
+pub fn init_llvm(sess: &Session) {
+    unsafe {
+        use std::sync::Once;
+        static INIT: Once = Once::new();
+        static mut POISONED: bool = false;
+        static mut CONFIGURED_ARGS: *const Vec<String> = 0 as *const _;
+        INIT.call_once(|| {
+            if llvm::LLVMStartMultithreaded() != 1 {
+                // use an extra bool to make sure that all future usage of LLVM
+                // cannot proceed despite the Once not running more than once.
+                POISONED = true;
+            }
+
+            let args = llvm_args(sess);
+            configure_llvm(&args);
+            CONFIGURED_ARGS = Box::into_raw(box args);
+        });
+
+        if POISONED {
+            bug!("couldn't enable multi-threaded LLVM");
+        }
+
+        if *CONFIGURED_ARGS != llvm_args(sess) {
+            sess.err("LLVM was already configured with different options by an earlier \
+                      crate compiled in this process");
+        }
+    }
+}
+
+fn llvm_args(sess: &Session) -> Vec<String> {
+    let mut args = vec![];
+    if sess.time_llvm_passes() { args.push("-time-passes".to_string()); }
+    if sess.print_llvm_passes() { args.push("-debug-pass=Structure".to_string()); }
+    // Have LLVM report the stack size of every function it compiles; see
+    // `add_stack_size_section`.
+    if sess.opts.debugging_opts.emit_stack_sizes {
+        args.push("-warn-stack-size=0".to_string());
+    }
+
+    // FIXME #21627 disable faulty FastISel on AArch64 (even for -O0)
+    if sess.target.target.arch == "aarch64" { args.push("-fast-isel=0".to_string()); }
+
+    args.extend(sess.opts.cg.llvm_args.iter().cloned());
+    args
+}
+
+unsafe fn configure_llvm(args: &[String]) {
     let mut llvm_c_strs = Vec::new();
     let mut llvm_args = Vec::new();
 
@@ -995,14 +1242,8 @@ pub unsafe fn configure_llvm(sess: &Session) {
             llvm_c_strs.push(s);
         };
         add("rustc"); // fake program name
-        if sess.time_llvm_passes() { add("-time-passes"); }
-        if sess.print_llvm_passes() { add("-debug-pass=Structure"); }
-
-        // FIXME #21627 disable faulty FastISel on AArch64 (even for -O0)
-        if sess.target.target.arch == "aarch64" { add("-fast-isel=0"); }
-
-        for arg in &sess.opts.cg.llvm_args {
-            add(&(*arg));
+        for arg in args {
+            add(arg);
         }
     }
 
@@ -1022,33 +1263,42 @@ pub unsafe fn with_llvm_pmb(llmod: ModuleRef,
     // manager.
     let builder = llvm::LLVMPassManagerBuilderCreate();
     let opt = config.opt_level.unwrap_or(llvm::CodeGenLevelNone);
+    let opt_size = config.opt_size.unwrap_or(llvm::CodeGenOptSizeNone);
     let inline_threshold = config.inline_threshold;
 
     llvm::LLVMRustConfigurePassManagerBuilder(builder, opt,
                                               config.merge_functions,
                                               config.vectorize_slp,
                                               config.vectorize_loop);
+    llvm::LLVMPassManagerBuilderSetSizeLevel(builder, opt_size as llvm::Bool);
 
     llvm::LLVMRustAddBuilderLibraryInfo(builder, llmod, config.no_builtins);
 
     // Here we match what clang does (kinda). For O0 we only inline
     // always-inline functions (but don't add lifetime intrinsics), at O1 we
     // inline with lifetime intrinsics, and O2+ we add an inliner with a
-    // thresholds copied from clang.
-    match (opt, inline_threshold) {
-        (_, Some(t)) => {
+    // thresholds copied from clang. Os and Oz run the O2 pipeline with
+    // clang's much lower size-oriented inlining thresholds.
+    match (opt, opt_size, inline_threshold) {
+        (_, _, Some(t)) => {
             llvm::LLVMPassManagerBuilderUseInlinerWithThreshold(builder, t as u32);
         }
-        (llvm::CodeGenLevelNone, _) => {
+        (_, llvm::CodeGenOptSizeDefault, _) => {
+            llvm::LLVMPassManagerBuilderUseInlinerWithThreshold(builder, 75);
+        }
+        (_, llvm::CodeGenOptSizeAggressive, _) => {
+            llvm::LLVMPassManagerBuilderUseInlinerWithThreshold(builder, 25);
+        }
+        (llvm::CodeGenLevelNone, _, _) => {
             llvm::LLVMRustAddAlwaysInlinePass(builder, false);
         }
-        (llvm::CodeGenLevelLess, _) => {
+        (llvm::CodeGenLevelLess, _, _) => {
             llvm::LLVMRustAddAlwaysInlinePass(builder, true);
         }
-        (llvm::CodeGenLevelDefault, _) => {
+        (llvm::CodeGenLevelDefault, _, _) => {
             llvm::LLVMPassManagerBuilderUseInlinerWithThreshold(builder, 225);
         }
-        (llvm::CodeGenLevelAggressive, _) => {
+        (llvm::CodeGenLevelAggressive, _, _) => {
             llvm::LLVMPassManagerBuilderUseInlinerWithThreshold(builder, 275);
         }
     }
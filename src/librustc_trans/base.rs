@@ -1123,6 +1123,56 @@ pub fn call_memcpy<'bcx, 'tcx>(b: &Builder<'bcx, 'tcx>,
     b.call(memcpy, &[dst_ptr, src_ptr, size, align, volatile], None);
 }
 
+/// The bounds used to saturate a cast from a float to an integer of
+/// `int_ty`: floats below the first value become the integer minimum,
+/// floats at or above the second become the integer maximum. Both are
+/// powers of two (or zero), so they are exact in either float type.
+pub fn float_to_int_saturation_bounds(signed: bool, int_ty: Type) -> (f64, f64) {
+    let bits = int_ty.int_width() as i32;
+    if signed {
+        (-(2f64.powi(bits - 1)), 2f64.powi(bits - 1))
+    } else {
+        (0.0, 2f64.powi(bits))
+    }
+}
+
+/// The smallest and largest values of the integer type `int_ty`.
+pub fn int_min_max(signed: bool, int_ty: Type) -> (ValueRef, ValueRef) {
+    let bits = int_ty.int_width();
+    if signed {
+        (C_integral(int_ty, 1 << (bits - 1), false),
+         C_integral(int_ty, (1 << (bits - 1)) - 1, false))
+    } else {
+        (C_integral(int_ty, 0, false), C_integral(int_ty, !0, false))
+    }
+}
+
+/// Converts the float `val` to the integer type `int_ty`, rounding
+/// towards zero. A bare `fptosi` or `fptoui` gives an undefined result
+/// for NaN and for values out of the integer's range; here those
+/// saturate instead, NaN becoming zero, unless `-Z
+/// unsaturated-float-casts` asks for the bare instruction.
+pub fn cast_float_to_int<'bcx, 'tcx>(b: &Builder<'bcx, 'tcx>,
+                                     val: ValueRef,
+                                     signed: bool,
+                                     int_ty: Type)
+                                     -> ValueRef {
+    let cast = if signed { b.fptosi(val, int_ty) } else { b.fptoui(val, int_ty) };
+    if b.ccx.sess().opts.debugging_opts.unsaturated_float_casts {
+        return cast;
+    }
+
+    let float_ty = val_ty(val);
+    let (lower, upper) = float_to_int_saturation_bounds(signed, int_ty);
+    let (min, max) = int_min_max(signed, int_ty);
+    let too_small = b.fcmp(llvm::RealOLT, val, common::C_floating_f64(lower, float_ty));
+    let too_large = b.fcmp(llvm::RealOGE, val, common::C_floating_f64(upper, float_ty));
+    let is_nan = b.fcmp(llvm::RealUNO, val, val);
+    let result = b.select(too_small, min, cast);
+    let result = b.select(too_large, max, result);
+    b.select(is_nan, C_integral(int_ty, 0, false), result)
+}
+
 pub fn memcpy_ty<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, dst: ValueRef, src: ValueRef, t: Ty<'tcx>) {
     let _icx = push_ctxt("memcpy_ty");
     let ccx = bcx.ccx();
@@ -1394,6 +1444,20 @@ fn has_nested_returns(tcx: &TyCtxt, cfg: &cfg::CFG, blk_id: ast::NodeId) -> bool
     return false;
 }
 
+// Checks for the presence of `become` expressions in a function. Tail
+// calls are only implemented by MIR trans, so such functions are always
+// translated from MIR.
+fn has_tail_calls(tcx: &TyCtxt, cfg: &cfg::CFG) -> bool {
+    cfg.graph.all_nodes().iter().any(|n| {
+        match tcx.map.find(n.data.id()) {
+            Some(hir_map::NodeExpr(ex)) => {
+                if let hir::ExprBecome(..) = ex.node { true } else { false }
+            }
+            _ => false
+        }
+    })
+}
+
 impl<'blk, 'tcx> FunctionContext<'blk, 'tcx> {
     /// Create a function context for the given function.
     /// Beware that you must call `fcx.init` or `fcx.bind_args`
@@ -1425,6 +1489,11 @@ impl<'blk, 'tcx> FunctionContext<'blk, 'tcx> {
         } else {
             false
         };
+        let tail_calls = if let Some((_, Some(ref cfg))) = cfg {
+            has_tail_calls(ccx.tcx(), cfg)
+        } else {
+            false
+        };
 
         let check_attrs = |attrs: &[ast::Attribute]| {
             let default_to_mir = ccx.sess().opts.debugging_opts.orbit;
@@ -1441,7 +1510,14 @@ impl<'blk, 'tcx> FunctionContext<'blk, 'tcx> {
             check_attrs(&[])
         };
 
-        let mir = if use_mir {
+        // Like tail calls, unsized arguments are only implemented by MIR
+        // trans, so functions that may take or pass them use it as well.
+        let unsized_locals = ccx.sess().features.borrow().unsized_locals ||
+            definition.map_or(false, |(_, sig, _)| {
+                sig.inputs.iter().any(|&ty| !common::type_is_sized(ccx.tcx(), ty))
+            });
+
+        let mir = if use_mir || tail_calls || unsized_locals {
             def_id.and_then(|id| ccx.get_mir(id))
         } else {
             None
@@ -1468,6 +1544,7 @@ impl<'blk, 'tcx> FunctionContext<'blk, 'tcx> {
             lldropflag_hints: RefCell::new(DropFlagHintsMap::new()),
             fn_ty: fn_ty,
             param_substs: param_substs,
+            instance: definition.map(|(instance, _, _)| instance),
             span: inlined_id.and_then(|id| ccx.tcx().map.opt_span(id)),
             block_arena: block_arena,
             lpad_arena: TypedArena::new(),
@@ -1829,6 +1906,7 @@ pub fn trans_closure<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     if collector::collecting_debug_information(ccx) {
         ccx.record_translation_item_as_generated(TransItem::Fn(instance));
     }
+    ccx.record_translated_fn(llfndecl, instance);
 
     let _icx = push_ctxt("trans_closure");
     attributes::emit_uwtable(llfndecl, true);
@@ -2260,13 +2338,14 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
     match item.node {
         hir::ItemFn(ref decl, _, _, _, ref generics, ref body) => {
             if !generics.is_type_parameterized() {
-                let trans_everywhere = attr::requests_inline(&item.attrs);
+                tcx.map.read_body(hir::BodyId { node_id: body.id });
+                let def_id = tcx.map.local_def_id(item.id);
+                let trans_everywhere = tcx.codegen_fn_attrs(def_id).requests_inline();
                 // Ignore `trans_everywhere` for cross-crate inlined items
                 // (`from_external`).  `trans_item` will be called once for each
                 // compilation unit that references the item, so it will still get
                 // translated everywhere it's needed.
                 for (ref ccx, is_origin) in ccx.maybe_iter(!from_external && trans_everywhere) {
-                    let def_id = tcx.map.local_def_id(item.id);
                     let empty_substs = ccx.empty_substs_for_def_id(def_id);
                     let llfn = Callee::def(ccx, def_id, empty_substs).reify(ccx).val;
                     trans_fn(ccx, &decl, &body, llfn, empty_substs, item.id);
@@ -2303,9 +2382,10 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
             for impl_item in impl_items {
                 if let hir::ImplItemKind::Method(ref sig, ref body) = impl_item.node {
                     if sig.generics.ty_params.is_empty() {
-                        let trans_everywhere = attr::requests_inline(&impl_item.attrs);
+                        tcx.map.read_body(hir::BodyId { node_id: body.id });
+                        let def_id = tcx.map.local_def_id(impl_item.id);
+                        let trans_everywhere = tcx.codegen_fn_attrs(def_id).requests_inline();
                         for (ref ccx, is_origin) in ccx.maybe_iter(trans_everywhere) {
-                            let def_id = tcx.map.local_def_id(impl_item.id);
                             let empty_substs = ccx.empty_substs_for_def_id(def_id);
                             let llfn = Callee::def(ccx, def_id, empty_substs).reify(ccx).val;
                             trans_fn(ccx, &sig.decl, body, llfn, empty_substs, impl_item.id);
@@ -2324,9 +2404,16 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
             if gens.ty_params.is_empty() {
                 // sizes only make sense for non-generic types
                 enum_variant_size_lint(ccx, enum_definition, item.span, item.id);
+                report_layout_if_requested(ccx, item);
+            }
+        }
+        hir::ItemStruct(_, ref gens) => {
+            if gens.ty_params.is_empty() {
+                report_layout_if_requested(ccx, item);
             }
         }
         hir::ItemStatic(_, m, ref expr) => {
+            tcx.map.read_body(hir::BodyId { node_id: expr.id });
             let g = match consts::trans_static(ccx, m, expr, item.id, &item.attrs) {
                 Ok(g) => g,
                 Err(err) => ccx.tcx().sess.span_fatal(expr.span, &err.description()),
@@ -2347,6 +2434,54 @@ pub fn trans_item(ccx: &CrateContext, item: &hir::Item) {
     }
 }
 
+/// For unit testing: reports the layout of a type annotated with
+/// `#[rustc_layout]` as an error, with a note per variant giving the
+/// offsets of its fields.
+fn report_layout_if_requested(ccx: &CrateContext, item: &hir::Item) {
+    let tcx = ccx.tcx();
+    if !tcx.has_attr(tcx.map.local_def_id(item.id), "rustc_layout") {
+        return;
+    }
+
+    let ty = tcx.node_id_to_type(item.id);
+    let llty = type_of::sizing_type_of(ccx, ty);
+    let repr = adt::represent_type(ccx, ty);
+    let int_name = |ity| match ity {
+        attr::SignedInt(t) => t.to_string(),
+        attr::UnsignedInt(t) => t.to_string()
+    };
+    let (kind, variants) = match *repr {
+        adt::CEnum(ity, min, max) => {
+            (format!("c-like enum ({}, discriminants {} to {})", int_name(ity), min.0, max.0),
+             vec![])
+        }
+        adt::Univariant(ref st, _) => {
+            ("univariant".to_string(), vec![st])
+        }
+        adt::General(ity, ref sts, _) => {
+            (format!("general enum ({} discriminant)", int_name(ity)), sts.iter().collect())
+        }
+        adt::RawNullablePointer { nndiscr, .. } => {
+            (format!("nullable pointer (non-null variant {})", nndiscr.0), vec![])
+        }
+        adt::StructWrappedNullablePointer { ref nonnull, nndiscr, ref discrfield, .. } => {
+            (format!("struct-wrapped nullable pointer (non-null variant {}, \
+                      discriminant field {:?})", nndiscr.0, discrfield),
+             vec![nonnull])
+        }
+    };
+
+    let mut err = tcx.sess.struct_span_err(item.span,
+        &format!("layout of `{}`: size {}, align {}, {}",
+                 ty, machine::llsize_of_alloc(ccx, llty), llalign_of_min(ccx, llty), kind));
+    for (i, st) in variants.into_iter().enumerate() {
+        err.span_note(item.span,
+                      &format!("variant {}: field offsets {:?}",
+                               i, adt::struct_field_offsets(ccx, st)));
+    }
+    err.emit();
+}
+
 pub fn is_entry_fn(sess: &Session, node_id: ast::NodeId) -> bool {
     match *sess.entry_fn.borrow() {
         Some((entry_id, _)) => node_id == entry_id,
@@ -2702,11 +2837,7 @@ pub fn trans_crate<'tcx>(tcx: &TyCtxt<'tcx>,
 
     let ty::CrateAnalysis { export_map, reachable, name, .. } = analysis;
 
-    let check_overflow = if let Some(v) = tcx.sess.opts.debugging_opts.force_overflow_checks {
-        v
-    } else {
-        tcx.sess.opts.debug_assertions
-    };
+    let check_overflow = tcx.sess.overflow_mode().checks_arith();
 
     let check_dropflag = if let Some(v) = tcx.sess.opts.debugging_opts.force_dropflag_checks {
         v
@@ -2715,24 +2846,7 @@ pub fn trans_crate<'tcx>(tcx: &TyCtxt<'tcx>,
     };
 
     // Before we touch LLVM, make sure that multithreading is enabled.
-    unsafe {
-        use std::sync::Once;
-        static INIT: Once = Once::new();
-        static mut POISONED: bool = false;
-        INIT.call_once(|| {
-            if llvm::LLVMStartMultithreaded() != 1 {
-                // use an extra bool to make sure that all future usage of LLVM
-                // cannot proceed despite the Once not running more than once.
-                POISONED = true;
-            }
-
-            ::back::write::configure_llvm(&tcx.sess);
-        });
-
-        if POISONED {
-            bug!("couldn't enable multi-threaded LLVM");
-        }
-    }
+    ::back::write::init_llvm(&tcx.sess);
 
     let link_meta = link::build_link_meta(&tcx, name);
 
@@ -2773,6 +2887,9 @@ pub fn trans_crate<'tcx>(tcx: &TyCtxt<'tcx>,
         if ccx.sess().opts.debuginfo != NoDebugInfo {
             debuginfo::finalize(&ccx);
         }
+        if ccx.sess().opts.debugging_opts.virtual_function_elimination {
+            meth::eliminate_virtual_functions(&ccx);
+        }
         for &(old_g, new_g) in ccx.statics_to_rauw().borrow().iter() {
             unsafe {
                 let bitcast = llvm::LLVMConstPointerCast(new_g, llvm::LLVMTypeOf(old_g));
@@ -2867,6 +2984,7 @@ pub fn trans_crate<'tcx>(tcx: &TyCtxt<'tcx>,
         metadata: metadata,
         reachable: reachable_symbols,
         no_builtins: no_builtins,
+        translated_fns: shared_ccx.translated_fns().borrow_mut().drain(..).collect(),
     }
 }
 
@@ -2917,6 +3035,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for TransItemsWithinModVisitor<'a, 'tcx> {
 
                 // Create a subtask for trans'ing a particular item. We are
                 // giving `trans_item` access to this item, so also record a read.
+                // `trans_item` records reads of the bodies it translates.
                 tcx.dep_graph.with_task(DepNode::TransCrateItem(def_id), || {
                     tcx.dep_graph.read(DepNode::Hir(def_id));
 
@@ -2959,9 +3078,11 @@ fn collect_translation_items<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>) {
         None => TransItemCollectionMode::Lazy
     };
 
-    let (items, inlining_map) = time(time_passes, "translation item collection", || {
-        collector::collect_crate_translation_items(&ccx, collection_mode)
-    });
+    let (items, inlining_map, instantiation_map) =
+        time(time_passes, "translation item collection", || {
+            collector::collect_crate_translation_items(&ccx, collection_mode)
+        });
+    *ccx.instantiation_map().borrow_mut() = instantiation_map;
 
     let codegen_units = time(time_passes, "codegen unit partitioning", || {
         partitioning::partition(ccx.tcx(), items.iter().cloned(), &inlining_map)
@@ -71,8 +71,9 @@ pub enum CalleeData {
 
     Intrinsic,
 
-    /// Trait object found in the vtable at that index.
-    Virtual(usize)
+    /// Trait object found in the vtable at that index, for the given
+    /// trait method.
+    Virtual(usize, DefId)
 }
 
 #[derive(Debug)]
@@ -203,7 +204,7 @@ impl<'tcx> Callee<'tcx> {
             traits::VtableObject(ref data) => {
                 Callee {
                     data: Virtual(traits::get_vtable_index_of_object_method(
-                        tcx, data, def_id)),
+                        tcx, data, def_id), def_id),
                     ty: def_ty(tcx, def_id, substs)
                 }
             }
@@ -222,7 +223,7 @@ impl<'tcx> Callee<'tcx> {
         let sig = ccx.tcx().erase_late_bound_regions(self.ty.fn_sig());
         let sig = infer::normalize_associated_type(ccx.tcx(), &sig);
         let mut fn_ty = FnType::unadjusted(ccx, abi, &sig, extra_args);
-        if let Virtual(_) = self.data {
+        if let Virtual(..) = self.data {
             // Don't pass the vtable, it's not an argument of the virtual fn.
             fn_ty.args[1].ignore();
         }
@@ -260,7 +261,7 @@ impl<'tcx> Callee<'tcx> {
             Fn(llfn) => {
                 immediate_rvalue(llfn, fn_ptr_ty)
             }
-            Virtual(idx) => {
+            Virtual(idx, _) => {
                 let llfn = meth::trans_object_shim(ccx, self.ty, idx);
                 immediate_rvalue(llfn, fn_ptr_ty)
             }
@@ -500,10 +501,9 @@ fn get_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         return immediate_rvalue(llfn, fn_ptr_ty);
     }
 
-    let attrs;
     let local_id = ccx.tcx().map.as_local_node_id(def_id);
     let maybe_node = local_id.and_then(|id| tcx.map.find(id));
-    let (sym, attrs, local_item) = match maybe_node {
+    let (sym, local_item) = match maybe_node {
         Some(hir_map::NodeItem(&hir::Item {
             ref attrs, id, span, node: hir::ItemFn(..), ..
         })) |
@@ -520,19 +520,16 @@ fn get_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                     &format!("symbol `{}` is already defined", sym));
             }
 
-            (sym, &attrs[..], Some(id))
+            (sym, Some(id))
         }
 
         Some(hir_map::NodeForeignItem(&hir::ForeignItem {
             ref attrs, name, node: hir::ForeignItemFn(..), ..
         })) => {
-            (imported_name(name, attrs).to_string(), &attrs[..], None)
+            (imported_name(name, attrs).to_string(), None)
         }
 
-        None => {
-            attrs = ccx.sess().cstore.item_attrs(def_id);
-            (ccx.sess().cstore.item_symbol(def_id), &attrs[..], None)
-        }
+        None => (ccx.sess().cstore.item_symbol(def_id), None),
 
         ref variant => {
             bug!("get_fn: unexpected variant: {:?}", variant)
@@ -581,7 +578,7 @@ fn get_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         assert_eq!(common::val_ty(llfn), llptrty);
         debug!("get_fn: not casting pointer!");
 
-        attributes::from_fn_attrs(ccx, attrs, llfn);
+        attributes::from_fn_attrs(ccx, def_id, llfn);
         if local_item.is_some() {
             // FIXME(eddyb) Doubt all extern fn should allow unwinding.
             attributes::unwind(llfn, true);
@@ -916,10 +913,10 @@ pub fn trans_args<'a, 'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         }
         ArgVals(vs) => {
             match *callee {
-                Virtual(idx) => {
+                Virtual(idx, method_id) => {
                     llargs.push(vs[0]);
 
-                    let fn_ptr = meth::get_virtual_method(bcx, vs[1], idx);
+                    let fn_ptr = meth::get_virtual_method(bcx, vs[1], idx, method_id);
                     let llty = fn_ty.llvm_type(bcx.ccx()).ptr_to();
                     *callee = Fn(PointerCast(bcx, fn_ptr, llty));
                     llargs.extend_from_slice(&vs[2..]);
@@ -996,12 +993,12 @@ fn trans_arg_datum<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         assert!(!info_arg.is_indirect() && info_arg.cast.is_none());
         let info = Load(bcx, expr::get_meta(bcx, val));
 
-        if let Virtual(idx) = *callee {
+        if let Virtual(idx, method_id) = *callee {
             // We have to grab the fn pointer from the vtable when
             // handling the first argument, ensure that here.
             assert_eq!(*next_idx, 2);
             assert!(info_arg.is_ignore());
-            let fn_ptr = meth::get_virtual_method(bcx, info, idx);
+            let fn_ptr = meth::get_virtual_method(bcx, info, idx, method_id);
             let llty = fn_ty.llvm_type(bcx.ccx()).ptr_to();
             *callee = Fn(PointerCast(bcx, fn_ptr, llty));
         } else {
@@ -333,6 +333,90 @@ pub fn trans_closure_method<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
     }
 }
 
+/// Returns a function with the signature of the non-capturing closure
+/// `closure_def_id`, taking its arguments individually, that calls the
+/// closure body. This is the fn pointer an `AdjustClosureFnPointer`
+/// coercion produces.
+pub fn trans_closure_fn_pointer<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
+                                          closure_def_id: DefId,
+                                          substs: &ty::ClosureSubsts<'tcx>)
+                                          -> ValueRef
+{
+    let _icx = push_ctxt("trans_closure_fn_pointer");
+    let tcx = ccx.tcx();
+
+    // Normalize the type for better caching.
+    let substs = tcx.erase_regions(substs);
+    let closure_ty = tcx.mk_closure_from_closure_substs(closure_def_id, Box::new(substs.clone()));
+
+    // Check if we already trans'd this wrapper. Closure types never
+    // collide with the fn types the other shims are keyed by.
+    if let Some(&llfn) = ccx.fn_pointer_shims().borrow().get(&closure_ty) {
+        return llfn;
+    }
+
+    debug!("trans_closure_fn_pointer(closure_def_id={:?}, substs={:?})",
+           closure_def_id, substs);
+
+    let infcx = infer::normalizing_infer_ctxt(ccx.tcx(), &ccx.tcx().tables, ProjectionMode::Any);
+    let ty::ClosureTy { unsafety, abi, mut sig } = infcx.closure_type(closure_def_id, &substs);
+
+    // The wrapper takes the closure arguments untupled, by the Rust ABI.
+    let fn_ptr_sig = match tcx.coerce_closure_fn_ty(&sig).sty {
+        ty::TyFnPtr(bare_fn) => tcx.erase_late_bound_regions(&bare_fn.sig),
+        _ => bug!("trans_closure_fn_pointer: closure {:?} is not a fn", closure_def_id)
+    };
+    let fn_ptr_sig = infer::normalize_associated_type(ccx.tcx(), &fn_ptr_sig);
+    let fn_ty = FnType::new(ccx, Abi::Rust, &fn_ptr_sig, &[]);
+    let fn_ptr_ty = tcx.mk_fn_ptr(ty::BareFnTy {
+        unsafety: hir::Unsafety::Normal,
+        abi: Abi::Rust,
+        sig: ty::Binder(fn_ptr_sig)
+    });
+
+    // Called through `FnOnce`, the closure takes its (zero-sized)
+    // environment by value, which is ignored; the remaining arguments
+    // are passed just like those of the wrapper.
+    let lloncefn = trans_closure_method(ccx, closure_def_id, substs.clone(),
+                                        ty::ClosureKind::FnOnce);
+    sig.0.inputs.insert(0, closure_ty);
+    let llonce_fn_ty = tcx.mk_fn_ptr(ty::BareFnTy {
+        unsafety: unsafety,
+        abi: abi,
+        sig: sig
+    });
+
+    let function_name =
+        symbol_names::internal_name_from_type_and_suffix(ccx, closure_ty, "fn_pointer");
+    let llfn = declare::define_internal_fn(ccx, &function_name, fn_ptr_ty);
+
+    let (block_arena, fcx): (TypedArena<_>, FunctionContext);
+    block_arena = TypedArena::new();
+    fcx = FunctionContext::new(ccx, llfn, fn_ty, None, &block_arena);
+    let mut bcx = fcx.init(false, None);
+
+    let llargs = get_params(fcx.llfn);
+    let self_idx = fcx.fn_ty.ret.is_indirect() as usize;
+
+    assert!(!fcx.needs_ret_allocas);
+
+    let dest = fcx.llretslotptr.get().map(|_|
+        expr::SaveIn(fcx.get_ret_slot(bcx, "ret_slot"))
+    );
+
+    let callee = Callee {
+        data: callee::Fn(lloncefn),
+        ty: llonce_fn_ty
+    };
+    bcx = callee.call(bcx, DebugLoc::None, ArgVals(&llargs[self_idx..]), dest).bcx;
+
+    fcx.finish(bcx, DebugLoc::None);
+
+    ccx.fn_pointer_shims().borrow_mut().insert(closure_ty, llfn);
+
+    llfn
+}
+
 fn trans_fn_once_adapter_shim<'a, 'tcx>(
     ccx: &'a CrateContext<'a, 'tcx>,
     closure_def_id: DefId,
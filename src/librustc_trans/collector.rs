@@ -173,6 +173,17 @@
 //!   otherwise is only done on demand.
 //!
 //!
+//! Instantiation Backtraces
+//! ------------------------
+//! Some errors, like an intrinsic being used with a type it does not support,
+//! can only be detected once a generic function has been monomorphized, at
+//! which point the source location of the error alone does not say which
+//! instantiation is at fault. The collector therefore remembers, for every
+//! translation item, the item and source location that first required it (see
+//! `InstantiationMap`). `note_instantiation_backtrace()` follows this chain
+//! back to a root and explains it to the user as a series of notes.
+//!
+//!
 //! Open Issues
 //! -----------
 //! Some things are not yet fully implemented in the current version of this
@@ -203,8 +214,9 @@ use rustc::mir::visit as mir_visit;
 use rustc::mir::visit::Visitor as MirVisitor;
 
 use syntax::ast::{self, NodeId};
-use syntax::codemap::DUMMY_SP;
+use syntax::codemap::{Span, DUMMY_SP};
 use syntax::{attr, errors};
+use syntax::errors::DiagnosticBuilder;
 use syntax::parse::token;
 
 use base::{custom_coerce_unsize_info, llvm_linkage_by_name};
@@ -254,10 +266,23 @@ impl<'tcx> Hash for TransItem<'tcx> {
 
 pub type InliningMap<'tcx> = FnvHashMap<TransItem<'tcx>, FnvHashSet<TransItem<'tcx>>>;
 
+/// Maps each translation item to the one that required it first.
+pub type InstantiationMap<'tcx> = FnvHashMap<TransItem<'tcx>, InstantiationSource<'tcx>>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct InstantiationSource<'tcx> {
+    /// The item whose translation requires the instantiation.
+    pub user: TransItem<'tcx>,
+    /// Where in `user` the instantiation is required, if it comes from
+    /// its source code rather than, say, its drop glue.
+    pub span: Option<Span>,
+}
+
 pub fn collect_crate_translation_items<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                                  mode: TransItemCollectionMode)
                                                  -> (FnvHashSet<TransItem<'tcx>>,
-                                                     InliningMap<'tcx>) {
+                                                     InliningMap<'tcx>,
+                                                     InstantiationMap<'tcx>) {
     // We are not tracking dependencies of this pass as it has to be re-executed
     // every time no matter what.
     ccx.tcx().dep_graph.with_ignore(|| {
@@ -267,16 +292,18 @@ pub fn collect_crate_translation_items<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         let mut visited = FnvHashSet();
         let mut recursion_depths = DefIdMap();
         let mut inlining_map = FnvHashMap();
+        let mut instantiation_map = FnvHashMap();
 
         for root in roots {
             collect_items_rec(ccx,
                               root,
                               &mut visited,
                               &mut recursion_depths,
-                              &mut inlining_map);
+                              &mut inlining_map,
+                              &mut instantiation_map);
         }
 
-        (visited, inlining_map)
+        (visited, inlining_map, instantiation_map)
     })
 }
 
@@ -307,7 +334,8 @@ fn collect_items_rec<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
                                    starting_point: TransItem<'tcx>,
                                    visited: &mut FnvHashSet<TransItem<'tcx>>,
                                    recursion_depths: &mut DefIdMap<usize>,
-                                   inlining_map: &mut InliningMap<'tcx>) {
+                                   inlining_map: &mut InliningMap<'tcx>,
+                                   instantiation_map: &mut InstantiationMap<'tcx>) {
     if !visited.insert(starting_point.clone()) {
         // We've been here already, no need to search again.
         return;
@@ -315,6 +343,8 @@ fn collect_items_rec<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
     debug!("BEGIN collect_items_rec({})", starting_point.to_string(ccx));
 
     let mut neighbors = Vec::new();
+    // The source location of each neighbor, as far as it is known.
+    let mut neighbor_spans = Vec::new();
     let recursion_depth_reset;
 
     match starting_point {
@@ -333,7 +363,8 @@ fn collect_items_rec<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
             // Keep track of the monomorphization recursion depth
             recursion_depth_reset = Some(check_recursion_limit(ccx,
                                                                instance,
-                                                               recursion_depths));
+                                                               recursion_depths,
+                                                               instantiation_map));
 
             // Scan the MIR in order to find function calls, closures, and
             // drop-glue
@@ -344,16 +375,29 @@ fn collect_items_rec<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
                 ccx: ccx,
                 mir: &mir,
                 output: &mut neighbors,
+                output_spans: &mut neighbor_spans,
                 param_substs: instance.substs
             };
 
             visitor.visit_mir(&mir);
         }
     }
+    neighbor_spans.resize(neighbors.len(), None);
 
-    for neighbour in neighbors {
+    for (neighbour, span) in neighbors.into_iter().zip(neighbor_spans) {
         record_inlined_use(ccx, starting_point, neighbour, inlining_map);
-        collect_items_rec(ccx, neighbour, visited, recursion_depths, inlining_map);
+        if !visited.contains(&neighbour) {
+            instantiation_map.entry(neighbour).or_insert(InstantiationSource {
+                user: starting_point,
+                span: span,
+            });
+        }
+        collect_items_rec(ccx,
+                          neighbour,
+                          visited,
+                          recursion_depths,
+                          inlining_map,
+                          instantiation_map);
     }
 
     if let Some((def_id, depth)) = recursion_depth_reset {
@@ -377,7 +421,8 @@ fn record_inlined_use<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
 
 fn check_recursion_limit<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
                                        instance: Instance<'tcx>,
-                                       recursion_depths: &mut DefIdMap<usize>)
+                                       recursion_depths: &mut DefIdMap<usize>,
+                                       instantiation_map: &InstantiationMap<'tcx>)
                                        -> (DefId, usize) {
     let recursion_depth = recursion_depths.get(&instance.def)
                                           .map(|x| *x)
@@ -390,11 +435,14 @@ fn check_recursion_limit<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
     if recursion_depth > ccx.sess().recursion_limit.get() {
         let error = format!("reached the recursion limit while instantiating `{}`",
                             instance);
-        if let Some(node_id) = ccx.tcx().map.as_local_node_id(instance.def) {
-            ccx.sess().span_fatal(ccx.tcx().map.span(node_id), &error);
+        let mut err = if let Some(node_id) = ccx.tcx().map.as_local_node_id(instance.def) {
+            ccx.sess().struct_span_fatal(ccx.tcx().map.span(node_id), &error)
         } else {
-            ccx.sess().fatal(&error);
-        }
+            ccx.sess().struct_fatal(&error)
+        };
+        note_instantiation_backtrace(ccx, instantiation_map, TransItem::Fn(instance), &mut err);
+        err.emit();
+        panic!(errors::FatalError);
     }
 
     recursion_depths.insert(instance.def, recursion_depth + 1);
@@ -402,15 +450,85 @@ fn check_recursion_limit<'a, 'tcx: 'a>(ccx: &CrateContext<'a, 'tcx>,
     (instance.def, recursion_depth)
 }
 
+/// The number of instantiations `note_instantiation_backtrace` describes
+/// before summarizing the rest of the chain.
+const MAX_BACKTRACE_NOTES: usize = 8;
+
+/// Explains to the user why `item` is being translated, by adding a note to
+/// `err` for each instantiation in the chain that led from a root to `item`,
+/// innermost first.
+pub fn note_instantiation_backtrace<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
+                                              instantiation_map: &InstantiationMap<'tcx>,
+                                              item: TransItem<'tcx>,
+                                              err: &mut DiagnosticBuilder) {
+    let mut item = item;
+    let mut notes = 0;
+    while let Some(source) = instantiation_map.get(&item) {
+        if notes == MAX_BACKTRACE_NOTES {
+            let mut omitted = 1;
+            let mut user = source.user;
+            while let Some(source) = instantiation_map.get(&user) {
+                omitted += 1;
+                user = source.user;
+            }
+            err.note(&format!("... and {} more instantiations", omitted));
+            break;
+        }
+
+        let msg = format!("required by the instantiation of {} in {}",
+                          describe_item(ccx, item),
+                          describe_item(ccx, source.user));
+        match source.span {
+            Some(span) => { err.span_note(span, &msg); }
+            None => { err.note(&msg); }
+        }
+
+        item = source.user;
+        notes += 1;
+    }
+
+    fn describe_item<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, item: TransItem<'tcx>) -> String {
+        match item {
+            TransItem::DropGlue(t) => format!("the drop glue for `{}`", t),
+            TransItem::Fn(instance) => format!("`{}`", instance),
+            TransItem::Static(node_id) => format!("`{}`", ccx.tcx().node_path_str(node_id)),
+        }
+    }
+}
+
 struct MirNeighborCollector<'a, 'tcx: 'a> {
     ccx: &'a CrateContext<'a, 'tcx>,
     mir: &'a mir::Mir<'tcx>,
     output: &'a mut Vec<TransItem<'tcx>>,
+    // The span of each item in `output`, or `None` if it was not pushed
+    // while visiting a statement or terminator.
+    output_spans: &'a mut Vec<Option<Span>>,
     param_substs: &'tcx Substs<'tcx>
 }
 
+impl<'a, 'tcx> MirNeighborCollector<'a, 'tcx> {
+    fn record_spans(&mut self, span: Span) {
+        let len = self.output.len();
+        self.output_spans.resize(len, Some(span));
+    }
+}
+
 impl<'a, 'tcx> MirVisitor<'tcx> for MirNeighborCollector<'a, 'tcx> {
 
+    fn visit_statement(&mut self, block: mir::BasicBlock, statement: &mir::Statement<'tcx>) {
+        self.output_spans.resize(self.output.len(), None);
+        self.super_statement(block, statement);
+        self.record_spans(statement.span);
+    }
+
+    fn visit_terminator(&mut self,
+                        block: mir::BasicBlock,
+                        terminator: &mir::Terminator<'tcx>) {
+        self.output_spans.resize(self.output.len(), None);
+        self.super_terminator(block, terminator);
+        self.record_spans(terminator.span);
+    }
+
     fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>) {
         debug!("visiting rvalue {:?}", *rvalue);
 
@@ -690,6 +808,7 @@ fn find_drop_glue_neighbors<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         }
         ty::TyProjection(_) |
         ty::TyParam(_)      |
+        ty::TyBound(..)     |
         ty::TyInfer(_)      |
         ty::TyError         => {
             bug!("encountered unexpected type");
@@ -973,6 +1092,7 @@ impl<'b, 'a, 'v> hir_visit::Visitor<'v> for RootCollector<'b, 'a, 'v> {
             hir::ItemTy(..)          |
             hir::ItemDefaultImpl(..) |
             hir::ItemTrait(..)       |
+            hir::ItemTraitAlias(..)  |
             hir::ItemConst(..)       |
             hir::ItemMod(..)         => {
                 // Nothing to do, just keep recursing...
@@ -1277,7 +1397,8 @@ pub fn push_unique_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         ty::TyError |
         ty::TyInfer(_) |
         ty::TyProjection(..) |
-        ty::TyParam(_) => {
+        ty::TyParam(_) |
+        ty::TyBound(..) => {
             bug!("debuginfo: Trying to create type name for \
                   unexpected type: {:?}", t);
         }
@@ -1335,9 +1456,9 @@ fn push_type_params<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
     output.push('>');
 }
 
-fn push_instance_as_string<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
-                                     instance: Instance<'tcx>,
-                                     output: &mut String) {
+pub fn push_instance_as_string<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
+                                         instance: Instance<'tcx>,
+                                         output: &mut String) {
     push_item_name(ccx, instance.def, output);
     push_type_params(ccx, &instance.substs.types, &[], output);
 }
@@ -1361,8 +1482,7 @@ impl<'tcx> TransItem<'tcx> {
     pub fn requests_inline(&self, tcx: &TyCtxt<'tcx>) -> bool {
         match *self {
             TransItem::Fn(ref instance) => {
-                let attributes = tcx.get_attrs(instance.def);
-                attr::requests_inline(&attributes[..])
+                tcx.codegen_fn_attrs(instance.def).requests_inline()
             }
             TransItem::DropGlue(..) => true,
             TransItem::Static(..)   => false,
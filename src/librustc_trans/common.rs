@@ -34,7 +34,7 @@ use debuginfo::{self, DebugLoc};
 use declare;
 use machine;
 use mir::CachedMir;
-use monomorphize;
+use monomorphize::{self, Instance};
 use type_::Type;
 use value::Value;
 use rustc::ty::{self, Ty, TyCtxt};
@@ -327,6 +327,10 @@ pub struct FunctionContext<'a, 'tcx: 'a> {
     // substitutions used.
     pub param_substs: &'tcx Substs<'tcx>,
 
+    // The instance being translated, if any. Errors that only arise
+    // after monomorphization use it to explain how it was instantiated.
+    pub instance: Option<Instance<'tcx>>,
+
     // The source span and nesting context where this function comes from, for
     // error reporting and symbol generation.
     pub span: Option<Span>,
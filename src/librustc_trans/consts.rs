@@ -23,7 +23,8 @@ use base::{self, exported_name, imported_name, push_ctxt};
 use callee::Callee;
 use collector::{self, TransItem};
 use common::{type_is_sized, C_nil, const_get_elt};
-use common::{CrateContext, C_integral, C_floating, C_bool, C_str_slice, C_bytes, val_ty};
+use common::{CrateContext, C_integral, C_floating, C_floating_f64, C_bool, C_str_slice};
+use common::{C_bytes, val_ty};
 use common::{C_struct, C_undef, const_to_opt_int, const_to_opt_uint, VariantInfo, C_uint};
 use common::{type_is_fat_ptr, Field, C_vector, C_array, C_null};
 use datum::{Datum, Lvalue};
@@ -36,6 +37,7 @@ use Disr;
 use rustc::ty::subst::Substs;
 use rustc::ty::adjustment::{AdjustDerefRef, AdjustReifyFnPointer};
 use rustc::ty::adjustment::{AdjustUnsafeFnPointer, AdjustMutToConstPointer};
+use rustc::ty::adjustment::AdjustClosureFnPointer;
 use rustc::ty::{self, Ty, TyCtxt};
 use rustc::ty::cast::{CastTy,IntTy};
 use util::nodemap::NodeMap;
@@ -191,6 +193,30 @@ fn const_deref<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
     }
 }
 
+/// The constant counterpart of `base::cast_float_to_int`.
+fn const_float_to_int(cx: &CrateContext, v: ValueRef, signed: bool, llty: Type) -> ValueRef {
+    unsafe {
+        let cast = if signed {
+            llvm::LLVMConstFPToSI(v, llty.to_ref())
+        } else {
+            llvm::LLVMConstFPToUI(v, llty.to_ref())
+        };
+        if cx.sess().opts.debugging_opts.unsaturated_float_casts {
+            return cast;
+        }
+
+        let float_ty = val_ty(v);
+        let (lower, upper) = base::float_to_int_saturation_bounds(signed, llty);
+        let (min, max) = base::int_min_max(signed, llty);
+        let too_small = ConstFCmp(llvm::RealOLT, v, C_floating_f64(lower, float_ty));
+        let too_large = ConstFCmp(llvm::RealOGE, v, C_floating_f64(upper, float_ty));
+        let is_nan = ConstFCmp(llvm::RealUNO, v, v);
+        let result = llvm::LLVMConstSelect(too_small, min, cast);
+        let result = llvm::LLVMConstSelect(too_large, max, result);
+        llvm::LLVMConstSelect(is_nan, C_integral(llty, 0, false), result)
+    }
+}
+
 fn const_fn_call<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                            def_id: DefId,
                            substs: Substs<'tcx>,
@@ -360,6 +386,16 @@ pub fn const_expr<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                 }
             }
         }
+        Some(AdjustClosureFnPointer) => {
+            match ety.sty {
+                ty::TyClosure(def_id, ref substs) => {
+                    llconst = closure::trans_closure_fn_pointer(cx, def_id, substs);
+                }
+                _ => {
+                    bug!("{} cannot be coerced to a fn ptr", ety)
+                }
+            }
+        }
         Some(AdjustUnsafeFnPointer) | Some(AdjustMutToConstPointer) => {
             // purely a type-level thing
         }
@@ -771,8 +807,8 @@ fn const_expr_unadjusted<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                     }
                 },
                 (CastTy::Float, CastTy::Float) => llvm::LLVMConstFPCast(v, llty.to_ref()),
-                (CastTy::Float, CastTy::Int(IntTy::I)) => llvm::LLVMConstFPToSI(v, llty.to_ref()),
-                (CastTy::Float, CastTy::Int(_)) => llvm::LLVMConstFPToUI(v, llty.to_ref()),
+                (CastTy::Float, CastTy::Int(IntTy::I)) => const_float_to_int(cx, v, true, llty),
+                (CastTy::Float, CastTy::Int(_)) => const_float_to_int(cx, v, false, llty),
                 (CastTy::Ptr(_), CastTy::Ptr(_)) | (CastTy::FnPtr, CastTy::Ptr(_))
                 | (CastTy::RPtr(_), CastTy::Ptr(_)) => {
                     ptrcast(v, llty)
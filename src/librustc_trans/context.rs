@@ -27,16 +27,17 @@ use glue::DropGlueKind;
 use mir::CachedMir;
 use monomorphize::Instance;
 
-use collector::{TransItem, TransItemState};
+use back::size_attribution::TranslatedFn;
+use collector::{self, InstantiationMap, TransItem, TransItemState};
 use type_::{Type, TypeNames};
 use rustc::ty::subst::{Substs, VecPerParamSpace};
 use rustc::ty::{self, Ty, TyCtxt};
 use session::config::NoDebugInfo;
 use session::Session;
 use util::sha2::Sha256;
-use util::nodemap::{NodeMap, NodeSet, DefIdMap, FnvHashMap, FnvHashSet};
+use util::nodemap::{NodeMap, NodeSet, DefIdMap, DefIdSet, FnvHashMap, FnvHashSet};
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::cell::{Cell, RefCell};
 use std::marker::PhantomData;
 use std::ptr;
@@ -85,6 +86,16 @@ pub struct SharedCrateContext<'a, 'tcx: 'a> {
     use_dll_storage_attrs: bool,
 
     translation_items: RefCell<FnvHashMap<TransItem<'tcx>, TransItemState>>,
+
+    // What required each translation item, as found by the collector.
+    instantiation_map: RefCell<InstantiationMap<'tcx>>,
+
+    /// The functions translated so far, under `-Z size-attribution`.
+    translated_fns: RefCell<Vec<TranslatedFn>>,
+
+    /// The trait methods called through a vtable, under
+    /// `-Z virtual-function-elimination`.
+    virtual_calls: RefCell<DefIdSet>,
 }
 
 /// The local portion of a `CrateContext`.  There is one `LocalCrateContext`
@@ -361,6 +372,9 @@ impl<'b, 'tcx> SharedCrateContext<'b, 'tcx> {
             available_drop_glues: RefCell::new(FnvHashMap()),
             use_dll_storage_attrs: use_dll_storage_attrs,
             translation_items: RefCell::new(FnvHashMap()),
+            instantiation_map: RefCell::new(FnvHashMap()),
+            translated_fns: RefCell::new(Vec::new()),
+            virtual_calls: RefCell::new(DefIdSet()),
         };
 
         for i in 0..local_count {
@@ -446,6 +460,10 @@ impl<'b, 'tcx> SharedCrateContext<'b, 'tcx> {
         &self.stats
     }
 
+    pub fn translated_fns(&self) -> &RefCell<Vec<TranslatedFn>> {
+        &self.translated_fns
+    }
+
     pub fn use_dll_storage_attrs(&self) -> bool {
         self.use_dll_storage_attrs
     }
@@ -664,6 +682,10 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         &self.local.vtables
     }
 
+    pub fn virtual_calls<'a>(&'a self) -> &'a RefCell<DefIdSet> {
+        &self.shared.virtual_calls
+    }
+
     pub fn const_cstr_cache<'a>(&'a self) -> &'a RefCell<FnvHashMap<InternedString, ValueRef>> {
         &self.local.const_cstr_cache
     }
@@ -843,6 +865,29 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         &self.shared.translation_items
     }
 
+    pub fn instantiation_map(&self) -> &RefCell<InstantiationMap<'tcx>> {
+        &self.shared.instantiation_map
+    }
+
+    /// Records that `llfn` was translated for `instance`, so that its
+    /// size can be attributed to the item it was instantiated from.
+    pub fn record_translated_fn(&self, llfn: ValueRef, instance: Instance<'tcx>) {
+        if !self.sess().opts.debugging_opts.size_attribution {
+            return;
+        }
+
+        let symbol = unsafe {
+            CStr::from_ptr(llvm::LLVMGetValueName(llfn)).to_string_lossy().into_owned()
+        };
+        let mut description = String::new();
+        collector::push_instance_as_string(self, instance, &mut description);
+        self.shared.translated_fns.borrow_mut().push(TranslatedFn {
+            symbol: symbol,
+            instance: description,
+            origin: collector::def_id_to_string(self, instance.def),
+        });
+    }
+
     pub fn record_translation_item_as_generated(&self, cgi: TransItem<'tcx>) {
         if self.sess().opts.debugging_opts.print_trans_items.is_none() {
             return;
@@ -29,7 +29,6 @@ use rustc::hir;
 
 use syntax::ast;
 use syntax::parse::token::InternedString;
-use syntax::parse::token;
 
 pub fn trans_stmt<'blk, 'tcx>(cx: Block<'blk, 'tcx>,
                               s: &hir::Stmt)
@@ -391,11 +390,10 @@ pub fn trans_fail<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         return bcx;
     }
 
-    let v_str = C_str_slice(ccx, fail_str);
-    let loc = bcx.sess().codemap().lookup_char_pos(call_info.span.lo);
-    let filename = token::intern_and_get_ident(&loc.file.name);
+    let v_str = C_str_slice(ccx, bcx.sess().panic_message(call_info.span, fail_str));
+    let (filename, line) = bcx.sess().panic_location(call_info.span);
     let filename = C_str_slice(ccx, filename);
-    let line = C_u32(ccx, loc.line as u32);
+    let line = C_u32(ccx, line);
     let expr_file_line_const = C_struct(ccx, &[v_str, filename, line], false);
     let align = machine::llalign_of_min(ccx, val_ty(expr_file_line_const));
     let expr_file_line = consts::addr_of(ccx, expr_file_line_const, align, "panic_loc");
@@ -418,12 +416,11 @@ pub fn trans_fail_bounds_check<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     }
 
     // Extract the file/line from the span
-    let loc = bcx.sess().codemap().lookup_char_pos(call_info.span.lo);
-    let filename = token::intern_and_get_ident(&loc.file.name);
+    let (filename, line) = bcx.sess().panic_location(call_info.span);
 
     // Invoke the lang item
     let filename = C_str_slice(ccx,  filename);
-    let line = C_u32(ccx, loc.line as u32);
+    let line = C_u32(ccx, line);
     let file_line_const = C_struct(ccx, &[filename, line], false);
     let align = machine::llalign_of_min(ccx, val_ty(file_line_const));
     let file_line = consts::addr_of(ccx, file_line_const, align, "panic_bounds_check_loc");
@@ -407,6 +407,10 @@ fn walk_expr(cx: &CrateContext,
             None => ()
         },
 
+        hir::ExprBecome(ref sub_exp) => {
+            walk_expr(cx, &sub_exp, scope_stack, scope_map);
+        }
+
         hir::ExprUnary(_, ref sub_exp) => {
             walk_expr(cx, &sub_exp, scope_stack, scope_map);
         }
@@ -88,13 +88,19 @@ pub fn get_or_insert_gdb_debug_scripts_section_global(ccx: &CrateContext)
     }
 }
 
+/// The section is only understood by GDB on ELF targets and is pointless
+/// without debuginfo. Otherwise `-C gdb-scripts-section` decides, falling back
+/// to the absence of `#![omit_gdb_pretty_printer_section]`.
 pub fn needs_gdb_debug_scripts_section(ccx: &CrateContext) -> bool {
-    let omit_gdb_pretty_printer_section =
-        attr::contains_name(&ccx.tcx().map.krate_attrs(),
-                            "omit_gdb_pretty_printer_section");
+    if ccx.sess().target.target.options.is_like_osx ||
+       ccx.sess().target.target.options.is_like_windows ||
+       ccx.sess().opts.debuginfo == NoDebugInfo {
+        return false;
+    }
 
-    !omit_gdb_pretty_printer_section &&
-    !ccx.sess().target.target.options.is_like_osx &&
-    !ccx.sess().target.target.options.is_like_windows &&
-    ccx.sess().opts.debuginfo != NoDebugInfo
+    match ccx.sess().opts.cg.gdb_scripts_section {
+        Some(emit) => emit,
+        None => !attr::contains_name(&ccx.tcx().map.krate_attrs(),
+                                     "omit_gdb_pretty_printer_section"),
+    }
 }
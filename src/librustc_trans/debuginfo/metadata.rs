@@ -15,7 +15,7 @@ use self::EnumDiscriminantInfo::*;
 
 use super::utils::{debug_context, DIB, span_start, bytes_to_bits, size_and_align_of,
                    get_namespace_and_span_for_item, create_DIArray,
-                   fn_should_be_ignored, is_node_local_to_unit};
+                   fn_should_be_ignored, is_node_local_to_unit, cpp_like_names};
 use super::namespace::mangled_name_of_item;
 use super::type_names::{compute_debuginfo_type_name, push_debuginfo_type_name};
 use super::{declare_local, VariableKind, VariableAccess};
@@ -47,7 +47,6 @@ use syntax;
 use syntax::util::interner::Interner;
 use syntax::codemap::Span;
 use syntax::{ast, codemap};
-use syntax::parse::token;
 
 
 // From DWARF 5.
@@ -72,6 +71,8 @@ pub const NO_FILE_METADATA: DIFile = (0 as DIFile);
 pub const NO_SCOPE_METADATA: DIScope = (0 as DIScope);
 
 const FLAGS_NONE: c_uint = 0;
+// Corresponds to LLVM's DIFlags::FlagArtificial.
+const FLAGS_ARTIFICIAL: c_uint = 1 << 6;
 
 #[derive(Copy, Debug, Hash, Eq, PartialEq, Clone)]
 pub struct UniqueTypeId(ast::Name);
@@ -633,9 +634,11 @@ fn trait_pointer_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                     trait_object_type: Option<Ty<'tcx>>,
                                     unique_type_id: UniqueTypeId)
                                     -> DIType {
-    // The implementation provided here is a stub. It makes sure that the trait
-    // type is assigned the correct name, size, namespace, and source location.
-    // But it does not describe the trait's methods.
+    // The implementation provided here makes sure that the trait type is
+    // assigned the correct name, size, namespace, and source location. For
+    // trait object pointers it also describes the data and vtable pointers so
+    // that debugger formatters can find the concrete value. It does not
+    // describe the trait's methods.
 
     let def_id = match trait_type.sty {
         ty::TyTrait(ref data) => data.principal_def_id(),
@@ -654,11 +657,46 @@ fn trait_pointer_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 
     let trait_llvm_type = type_of::type_of(cx, trait_object_type);
 
+    let member_descriptions = if trait_object_type != trait_type {
+        let data_ptr_type = cx.tcx().mk_mut_ptr(cx.tcx().types.u8);
+        let vtable_type = cx.tcx().mk_imm_ptr(cx.tcx().mk_array(cx.tcx().types.usize, 3));
+        let data_ptr_metadata = type_metadata(cx, data_ptr_type, codemap::DUMMY_SP);
+        let vtable_metadata = type_metadata(cx, vtable_type, codemap::DUMMY_SP);
+
+        let member_llvm_types = trait_llvm_type.field_types();
+        assert_eq!(member_llvm_types.len(), 2);
+
+        vec![
+            MemberDescription {
+                name: "pointer".to_string(),
+                llvm_type: member_llvm_types[0],
+                type_metadata: data_ptr_metadata,
+                offset: ComputedMemberOffset,
+                flags: FLAGS_ARTIFICIAL
+            },
+            MemberDescription {
+                name: "vtable".to_string(),
+                llvm_type: member_llvm_types[1],
+                type_metadata: vtable_metadata,
+                offset: ComputedMemberOffset,
+                flags: FLAGS_ARTIFICIAL
+            },
+        ]
+    } else {
+        vec![]
+    };
+
+    if let Some(metadata) = debug_context(cx).type_map
+                                             .borrow()
+                                             .find_metadata_for_unique_id(unique_type_id) {
+        return metadata;
+    }
+
     composite_type_metadata(cx,
                             trait_llvm_type,
                             &trait_type_name[..],
                             unique_type_id,
-                            &[],
+                            &member_descriptions[..],
                             containing_scope,
                             NO_FILE_METADATA,
                             codemap::DUMMY_SP)
@@ -1292,7 +1330,7 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
                                                       variant_llvm_type,
                                                       &member_descriptions);
                         MemberDescription {
-                            name: "".to_string(),
+                            name: variant_member_name(cx, i),
                             llvm_type: variant_llvm_type,
                             type_metadata: variant_type_metadata,
                             offset: FixedMemberOffset { bytes: 0 },
@@ -1326,7 +1364,7 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
                                                   &member_descriptions[..]);
                     vec![
                         MemberDescription {
-                            name: "".to_string(),
+                            name: variant_member_name(cx, 0),
                             llvm_type: variant_llvm_type,
                             type_metadata: variant_type_metadata,
                             offset: FixedMemberOffset { bytes: 0 },
@@ -1456,6 +1494,19 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
     }
 }
 
+// The name of the union member holding the variant with the given index.
+// Debuggers reading DWARF expect these members to be anonymous, but
+// CodeView records cannot refer to anonymous members, so on MSVC they are
+// named `variant0`, `variant1`, ..., for natvis visualizers to look into.
+// Each of them starts with the `RUST$ENUM$DISR` discriminant field.
+fn variant_member_name(cx: &CrateContext, index: usize) -> String {
+    if cpp_like_names(cx) {
+        format!("variant{}", index)
+    } else {
+        String::new()
+    }
+}
+
 // Creates MemberDescriptions for the fields of a single enum variant.
 struct VariantMemberDescriptionFactory<'tcx> {
     args: Vec<(String, Ty<'tcx>)>,
@@ -1475,7 +1526,12 @@ impl<'tcx> VariantMemberDescriptionFactory<'tcx> {
                     _ => type_metadata(cx, ty, self.span)
                 },
                 offset: ComputedMemberOffset,
-                flags: FLAGS_NONE
+                // Mark the discriminant as compiler-generated so debuggers can
+                // tell it apart from the variant's own fields.
+                flags: match self.discriminant_type_metadata {
+                    Some(_) if i == 0 => FLAGS_ARTIFICIAL,
+                    _ => FLAGS_NONE
+                }
             }
         }).collect()
     }
@@ -1694,10 +1750,17 @@ fn prepare_enum_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         }),
     );
 
+    // On MSVC the discriminant type cannot share the name of the union
+    // describing the enum, which lives in the same namespace.
     fn get_enum_discriminant_name(cx: &CrateContext,
                                   def_id: DefId)
-                                  -> token::InternedString {
-        cx.tcx().item_name(def_id).as_str()
+                                  -> String {
+        let name = cx.tcx().item_name(def_id).as_str();
+        if cpp_like_names(cx) {
+            format!("{}$Discriminant", name)
+        } else {
+            name.to_string()
+        }
     }
 }
 
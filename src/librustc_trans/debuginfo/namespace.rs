@@ -11,12 +11,12 @@
 // Namespace Handling.
 
 use super::metadata::{file_metadata, NO_FILE_METADATA, UNKNOWN_LINE_NUMBER};
-use super::utils::{DIB, debug_context, span_start};
+use super::utils::{DIB, cpp_like_names, debug_context, span_start};
 
 use llvm;
 use llvm::debuginfo::DIScope;
 use rustc::hir::def_id::DefId;
-use rustc::hir::map::DefPathData;
+use rustc::hir::map::{DefPathData, DisambiguatedDefPathData};
 use common::CrateContext;
 
 use libc::c_uint;
@@ -50,6 +50,20 @@ pub fn mangled_name_of_item(ccx: &CrateContext, def_id: DefId, extra: &str) -> S
     name
 }
 
+/// The name of the namespace or type standing for one element of a
+/// definition path. Impls and closures are spelled `impl$N` and
+/// `closure$N` on MSVC, where `{{impl}}` is not a valid C++ name.
+pub fn path_element_name(ccx: &CrateContext, element: &DisambiguatedDefPathData) -> String {
+    if cpp_like_names(ccx) {
+        match element.data {
+            DefPathData::Impl => return format!("impl${}", element.disambiguator),
+            DefPathData::ClosureExpr => return format!("closure${}", element.disambiguator),
+            _ => {}
+        }
+    }
+    element.data.to_string()
+}
+
 pub fn item_namespace(ccx: &CrateContext, def_id: DefId) -> DIScope {
     if let Some(&scope) = debug_context(ccx).namespace_map.borrow().get(&def_id) {
         return scope;
@@ -64,8 +78,8 @@ pub fn item_namespace(ccx: &CrateContext, def_id: DefId) -> DIScope {
     });
 
     let namespace_name = match def_key.disambiguated_data.data {
-        DefPathData::CrateRoot => ccx.tcx().crate_name(def_id.krate),
-        data => data.as_interned_str()
+        DefPathData::CrateRoot => ccx.tcx().crate_name(def_id.krate).to_string(),
+        _ => path_element_name(ccx, &def_key.disambiguated_data)
     };
 
     let namespace_name = CString::new(namespace_name.as_bytes()).unwrap();
@@ -10,6 +10,9 @@
 
 // Type Names for Debug Info.
 
+use super::namespace::path_element_name;
+use super::utils::cpp_like_names;
+
 use common::CrateContext;
 use rustc::hir::def_id::DefId;
 use rustc::infer;
@@ -33,10 +36,17 @@ pub fn compute_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 
 // Pushes the name of the type as it should be stored in debuginfo on the
 // `output` String. See also compute_debuginfo_type_name().
+//
+// On MSVC, types that C++ has no syntax for are named like C++ templates
+// (`tuple$<A,B>`, `ref$<T>`, `slice$<T>`, ...), so that natvis
+// visualizers can be written against them.
 pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                           t: Ty<'tcx>,
                                           qualified: bool,
                                           output: &mut String) {
+    let cpp_like_names = cpp_like_names(cx);
+    let separator = if cpp_like_names { "," } else { ", " };
+
     match t.sty {
         ty::TyBool => output.push_str("bool"),
         ty::TyChar => output.push_str("char"),
@@ -50,49 +60,85 @@ pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             push_type_params(cx, substs, output);
         },
         ty::TyTuple(ref component_types) => {
-            output.push('(');
+            output.push_str(if cpp_like_names { "tuple$<" } else { "(" });
             for &component_type in component_types {
                 push_debuginfo_type_name(cx, component_type, true, output);
-                output.push_str(", ");
+                output.push_str(separator);
             }
             if !component_types.is_empty() {
-                output.pop();
-                output.pop();
+                let len = output.len() - separator.len();
+                output.truncate(len);
+            }
+            if cpp_like_names {
+                push_close_angle_bracket(cpp_like_names, output);
+            } else {
+                output.push(')');
             }
-            output.push(')');
         },
         ty::TyBox(inner_type) => {
             output.push_str("Box<");
             push_debuginfo_type_name(cx, inner_type, true, output);
-            output.push('>');
+            push_close_angle_bracket(cpp_like_names, output);
         },
         ty::TyRawPtr(ty::TypeAndMut { ty: inner_type, mutbl } ) => {
-            output.push('*');
-            match mutbl {
-                hir::MutImmutable => output.push_str("const "),
-                hir::MutMutable => output.push_str("mut "),
+            if cpp_like_names {
+                output.push_str(match mutbl {
+                    hir::MutImmutable => "ptr_const$<",
+                    hir::MutMutable => "ptr_mut$<",
+                });
+            } else {
+                output.push('*');
+                match mutbl {
+                    hir::MutImmutable => output.push_str("const "),
+                    hir::MutMutable => output.push_str("mut "),
+                }
             }
 
             push_debuginfo_type_name(cx, inner_type, true, output);
+
+            if cpp_like_names {
+                push_close_angle_bracket(cpp_like_names, output);
+            }
         },
         ty::TyRef(_, ty::TypeAndMut { ty: inner_type, mutbl }) => {
-            output.push('&');
-            if mutbl == hir::MutMutable {
-                output.push_str("mut ");
+            if cpp_like_names {
+                output.push_str(match mutbl {
+                    hir::MutImmutable => "ref$<",
+                    hir::MutMutable => "ref_mut$<",
+                });
+            } else {
+                output.push('&');
+                if mutbl == hir::MutMutable {
+                    output.push_str("mut ");
+                }
             }
 
             push_debuginfo_type_name(cx, inner_type, true, output);
+
+            if cpp_like_names {
+                push_close_angle_bracket(cpp_like_names, output);
+            }
         },
         ty::TyArray(inner_type, len) => {
-            output.push('[');
-            push_debuginfo_type_name(cx, inner_type, true, output);
-            output.push_str(&format!("; {}", len));
-            output.push(']');
+            if cpp_like_names {
+                output.push_str("array$<");
+                push_debuginfo_type_name(cx, inner_type, true, output);
+                output.push_str(&format!(",{}>", len));
+            } else {
+                output.push('[');
+                push_debuginfo_type_name(cx, inner_type, true, output);
+                output.push_str(&format!("; {}", len));
+                output.push(']');
+            }
         },
         ty::TySlice(inner_type) => {
-            output.push('[');
+            output.push_str(if cpp_like_names { "slice$<" } else { "[" });
             push_debuginfo_type_name(cx, inner_type, true, output);
-            output.push(']');
+            if cpp_like_names {
+                push_close_angle_bracket(cpp_like_names, output);
+            } else {
+                output.push(']');
+            }
         },
         ty::TyTrait(ref trait_data) => {
             let principal = cx.tcx().erase_late_bound_regions(&trait_data.principal);
@@ -151,7 +197,8 @@ pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         ty::TyError |
         ty::TyInfer(_) |
         ty::TyProjection(..) |
-        ty::TyParam(_) => {
+        ty::TyParam(_) |
+        ty::TyBound(..) => {
             bug!("debuginfo: Trying to create type name for \
                 unexpected type: {:?}", t);
         }
@@ -165,7 +212,7 @@ pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             output.push_str(&cx.tcx().crate_name(def_id.krate));
             for path_element in cx.tcx().def_path(def_id).data {
                 output.push_str("::");
-                output.push_str(&path_element.data.as_interned_str());
+                output.push_str(&path_element_name(cx, &path_element));
             }
         } else {
             output.push_str(&cx.tcx().item_name(def_id).as_str());
@@ -184,16 +231,28 @@ pub fn push_debuginfo_type_name<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             return;
         }
 
+        let cpp_like_names = cpp_like_names(cx);
+        let separator = if cpp_like_names { "," } else { ", " };
+
         output.push('<');
 
         for &type_parameter in &substs.types {
             push_debuginfo_type_name(cx, type_parameter, true, output);
-            output.push_str(", ");
+            output.push_str(separator);
         }
 
-        output.pop();
-        output.pop();
+        let len = output.len() - separator.len();
+        output.truncate(len);
 
+        push_close_angle_bracket(cpp_like_names, output);
+    }
+
+    // MSVC spells nested templates `A<B<C> >`, with a space between the
+    // closing brackets, and natvis patterns have to match that.
+    fn push_close_angle_bracket(cpp_like_names: bool, output: &mut String) {
+        if cpp_like_names && output.ends_with('>') {
+            output.push(' ');
+        }
         output.push('>');
     }
 }
@@ -77,6 +77,13 @@ pub fn fn_should_be_ignored(fcx: &FunctionContext) -> bool {
     }
 }
 
+/// Whether type and namespace names should be spelled so that C++ tools
+/// can parse them. CodeView debuggers and natvis visualizers on MSVC
+/// expect names like `ref$<T>` or `impl$0` rather than `&T` or `{{impl}}`.
+pub fn cpp_like_names(cx: &CrateContext) -> bool {
+    cx.sess().target.target.options.is_like_msvc
+}
+
 pub fn get_namespace_and_span_for_item(cx: &CrateContext, def_id: DefId)
                                    -> (DIScope, Span) {
     let containing_scope = item_namespace(cx, DefId {
@@ -21,6 +21,7 @@
 //! * When in doubt, define.
 use llvm::{self, ValueRef};
 use rustc::ty;
+use rustc::session::config;
 use rustc::infer;
 use abi::{Abi, FnType};
 use attributes;
@@ -69,6 +70,19 @@ fn declare_raw_fn(ccx: &CrateContext, name: &str, callconv: llvm::CallConv, ty:
         llvm::SetFunctionAttribute(llfn, llvm::Attribute::NoRedZone)
     }
 
+    // Codegen only optimizes for size where functions ask for it, so the
+    // pass manager's size level alone is not enough for -Os and -Oz.
+    match ccx.sess().opts.optimize {
+        config::OptLevel::Size => {
+            llvm::SetFunctionAttribute(llfn, llvm::Attribute::OptimizeForSize);
+        }
+        config::OptLevel::SizeMin => {
+            llvm::SetFunctionAttribute(llfn, llvm::Attribute::OptimizeForSize);
+            llvm::SetFunctionAttribute(llfn, llvm::Attribute::MinSize);
+        }
+        _ => {}
+    }
+
     llfn
 }
 
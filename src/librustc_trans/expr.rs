@@ -72,6 +72,7 @@ use value::Value;
 use Disr;
 use rustc::ty::adjustment::{AdjustDerefRef, AdjustReifyFnPointer};
 use rustc::ty::adjustment::{AdjustUnsafeFnPointer, AdjustMutToConstPointer};
+use rustc::ty::adjustment::AdjustClosureFnPointer;
 use rustc::ty::adjustment::CustomCoerceUnsized;
 use rustc::ty::{self, Ty, TyCtxt};
 use rustc::ty::MethodCall;
@@ -348,7 +349,7 @@ fn adjustment_required<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     }
 
     match adjustment {
-        AdjustReifyFnPointer => true,
+        AdjustReifyFnPointer | AdjustClosureFnPointer => true,
         AdjustUnsafeFnPointer | AdjustMutToConstPointer => {
             // purely a type-level thing
             false
@@ -391,6 +392,18 @@ fn apply_adjustments<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                 }
             }
         }
+        AdjustClosureFnPointer => {
+            match datum.ty.sty {
+                ty::TyClosure(def_id, ref substs) => {
+                    let llfn = closure::trans_closure_fn_pointer(bcx.ccx(), def_id, substs);
+                    let fn_ptr_ty = expr_ty_adjusted(bcx, expr);
+                    datum = immediate_rvalue(llfn, fn_ptr_ty).to_expr_datum();
+                }
+                _ => {
+                    bug!("{} cannot be coerced to a fn ptr", datum.ty)
+                }
+            }
+        }
         AdjustUnsafeFnPointer | AdjustMutToConstPointer => {
             // purely a type-level thing
         }
@@ -960,6 +973,11 @@ fn trans_rvalue_stmt_unadjusted<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                 bcx
             }
         }
+        hir::ExprBecome(_) => {
+            // Functions containing `become` are always translated from MIR;
+            // see `FunctionContext::new`.
+            span_bug!(expr.span, "`become` in a function translated without MIR")
+        }
         hir::ExprWhile(ref cond, ref body, _) => {
             controlflow::trans_while(bcx, expr, &cond, &body)
         }
@@ -1958,8 +1976,9 @@ fn trans_imm_cast<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
         (Float, Float) => float_cast(bcx, ll_t_out, ll_t_in, llexpr),
         (Int(_), Float) if signed => SIToFP(bcx, llexpr, ll_t_out),
         (Int(_), Float) => UIToFP(bcx, llexpr, ll_t_out),
-        (Float, Int(I)) => FPToSI(bcx, llexpr, ll_t_out),
-        (Float, Int(_)) => FPToUI(bcx, llexpr, ll_t_out),
+        (Float, Int(_)) if bcx.unreachable.get() => C_undef(ll_t_out),
+        (Float, Int(I)) => cast_float_to_int(&B(bcx), llexpr, true, ll_t_out),
+        (Float, Int(_)) => cast_float_to_int(&B(bcx), llexpr, false, ll_t_out),
 
         _ => span_bug!(expr.span,
                        "translating unsupported cast: \
@@ -2176,14 +2195,14 @@ enum OverflowCodegen {
 enum OverflowOpViaInputCheck { Shl, Shr, }
 
 #[derive(Debug)]
-enum OverflowOpViaIntrinsic { Add, Sub, Mul, }
+pub enum OverflowOpViaIntrinsic { Add, Sub, Mul, }
 
 impl OverflowOpViaIntrinsic {
     fn to_intrinsic<'blk, 'tcx>(&self, bcx: Block<'blk, 'tcx>, lhs_ty: Ty) -> ValueRef {
         let name = self.to_intrinsic_name(bcx.tcx(), lhs_ty);
         bcx.ccx().get_intrinsic(&name)
     }
-    fn to_intrinsic_name(&self, tcx: &TyCtxt, ty: Ty) -> &'static str {
+    pub fn to_intrinsic_name(&self, tcx: &TyCtxt, ty: Ty) -> &'static str {
         use syntax::ast::IntTy::*;
         use syntax::ast::UintTy::*;
         use rustc::ty::{TyInt, TyUint};
@@ -2455,6 +2474,7 @@ fn expr_kind(tcx: &TyCtxt, expr: &hir::Expr) -> ExprKind {
         hir::ExprBreak(..) |
         hir::ExprAgain(..) |
         hir::ExprRet(..) |
+        hir::ExprBecome(..) |
         hir::ExprWhile(..) |
         hir::ExprLoop(..) |
         hir::ExprAssign(..) |
@@ -26,6 +26,7 @@ use build::*;
 use callee::{self, Callee};
 use cleanup;
 use cleanup::CleanupMethods;
+use collector::{self, TransItem};
 use common::*;
 use consts;
 use datum::*;
@@ -102,19 +103,12 @@ pub fn span_transmute_size_error(a: &Session, b: Span, msg: &str) {
 }
 
 /// Performs late verification that intrinsics are used correctly. At present,
-/// the only intrinsic that needs such verification is `transmute`.
+/// the only intrinsic that needs such verification is `transmute`, whose uses
+/// with sizes that do not depend on type parameters are checked here; the
+/// others are checked by `check_transmute_sizes` as they get translated.
 pub fn check_intrinsics(ccx: &CrateContext) {
     let _task = ccx.tcx().dep_graph.in_task(DepNode::IntrinsicUseCheck);
-    let mut last_failing_id = None;
     for transmute_restriction in ccx.tcx().transmute_restrictions.borrow().iter() {
-        // Sometimes, a single call to transmute will push multiple
-        // type pairs to test in order to exhaustively test the
-        // possibility around a type parameter. If one of those fails,
-        // there is no sense reporting errors on the others.
-        if last_failing_id == Some(transmute_restriction.id) {
-            continue;
-        }
-
         debug!("transmute_restriction: {:?}", transmute_restriction);
 
         assert!(!transmute_restriction.substituted_from.has_param_types());
@@ -127,7 +121,7 @@ pub fn check_intrinsics(ccx: &CrateContext) {
         let from_type_size = machine::llbitsize_of_real(ccx, llfromtype);
         let to_type_size = machine::llbitsize_of_real(ccx, lltotype);
 
-        if let ty::TyFnDef(..) = transmute_restriction.substituted_from.sty {
+        if let ty::TyFnDef(..) = transmute_restriction.original_from.sty {
             if to_type_size == machine::llbitsize_of_real(ccx, ccx.int_type()) {
                 // FIXME #19925 Remove this warning after a release cycle.
                 lint::raw_emit_lint(&ccx.tcx().sess,
@@ -137,36 +131,63 @@ pub fn check_intrinsics(ccx: &CrateContext) {
                                     Some(transmute_restriction.span),
                                     &format!("`{}` is now zero-sized and has to be cast \
                                               to a pointer before transmuting to `{}`",
-                                             transmute_restriction.substituted_from,
-                                             transmute_restriction.substituted_to));
+                                             transmute_restriction.original_from,
+                                             transmute_restriction.original_to));
                 continue;
             }
         }
         if from_type_size != to_type_size {
-            last_failing_id = Some(transmute_restriction.id);
-
-            if transmute_restriction.original_from != transmute_restriction.substituted_from {
-                span_transmute_size_error(ccx.sess(), transmute_restriction.span,
-                    &format!("transmute called with differently sized types: \
-                              {} (could be {} bits) to {} (could be {} bits)",
-                             transmute_restriction.original_from,
-                             from_type_size,
-                             transmute_restriction.original_to,
-                             to_type_size));
-            } else {
-                span_transmute_size_error(ccx.sess(), transmute_restriction.span,
-                    &format!("transmute called with differently sized types: \
-                              {} ({} bits) to {} ({} bits)",
-                             transmute_restriction.original_from,
-                             from_type_size,
-                             transmute_restriction.original_to,
-                             to_type_size));
-            }
+            span_transmute_size_error(ccx.sess(), transmute_restriction.span,
+                &format!("transmute called with differently sized types: \
+                          {} ({} bits) to {} ({} bits)",
+                         transmute_restriction.original_from,
+                         from_type_size,
+                         transmute_restriction.original_to,
+                         to_type_size));
         }
     }
     ccx.sess().abort_if_errors();
 }
 
+/// Checks that a call to `transmute` from `from` to `to` in the function being
+/// translated is between types of the same size. If the call involves type
+/// parameters, this is only known once the function has been monomorphized, so
+/// an error reported here explains which instantiation led to it. Returns
+/// whether the sizes match.
+pub fn check_transmute_sizes<'blk, 'tcx>(fcx: &FunctionContext<'blk, 'tcx>,
+                                         span: Span,
+                                         from: Ty<'tcx>,
+                                         to: Ty<'tcx>)
+                                         -> bool {
+    let ccx = fcx.ccx;
+    let from_type_size = machine::llbitsize_of_real(ccx, type_of::sizing_type_of(ccx, from));
+    let to_type_size = machine::llbitsize_of_real(ccx, type_of::sizing_type_of(ccx, to));
+    if from_type_size == to_type_size {
+        return true;
+    }
+
+    // Fn items are reified when transmuted to something pointer-sized, see
+    // the FIXME #19925 in `check_intrinsics`.
+    if let ty::TyFnDef(..) = from.sty {
+        if to_type_size == machine::llbitsize_of_real(ccx, ccx.int_type()) {
+            return true;
+        }
+    }
+
+    let mut err = struct_span_err!(ccx.sess(), span, E0512,
+                                   "transmute called with differently sized types: \
+                                    {} ({} bits) to {} ({} bits)",
+                                   from, from_type_size, to, to_type_size);
+    if let Some(instance) = fcx.instance {
+        collector::note_instantiation_backtrace(ccx,
+                                                &ccx.instantiation_map().borrow(),
+                                                TransItem::Fn(instance),
+                                                &mut err);
+    }
+    err.emit();
+    false
+}
+
 /// Remember to add all intrinsics here, in librustc_typeck/check/mod.rs,
 /// and in libcore/intrinsics.rs; if you need access to any llvm intrinsics,
 /// add them to librustc_trans/trans/context.rs
@@ -216,7 +237,6 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                 let llintype = type_of::type_of(ccx, in_type);
                 let llouttype = type_of::type_of(ccx, out_type);
 
-                let in_type_size = machine::llbitsize_of_real(ccx, llintype);
                 let out_type_size = machine::llbitsize_of_real(ccx, llouttype);
 
                 if let ty::TyFnDef(def_id, substs, _) = in_type.sty {
@@ -240,8 +260,11 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                     }
                 }
 
-                // This should be caught by the intrinsicck pass
-                assert_eq!(in_type_size, out_type_size);
+                // Transmutes involving type parameters are only checked now
+                // that they have been monomorphized. If the sizes differ, an
+                // error has been reported and we only need to produce some
+                // valid code, which the pointer cast below does.
+                let sizes_match = check_transmute_sizes(fcx, span, in_type, out_type);
 
                 let nonpointer_nonaggregate = |llkind: TypeKind| -> bool {
                     use llvm::TypeKind::*;
@@ -257,10 +280,10 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                 // but does, importantly, cover SIMD types.
                 let in_kind = llintype.kind();
                 let ret_kind = llret_ty.kind();
-                let bitcast_compatible =
+                let bitcast_compatible = sizes_match && (
                     (nonpointer_nonaggregate(in_kind) && nonpointer_nonaggregate(ret_kind)) || {
                         in_kind == TypeKind::Pointer && ret_kind == TypeKind::Pointer
-                    };
+                    });
 
                 let dest = if bitcast_compatible {
                     // if we're here, the type is scalar-like (a primitive, a
@@ -658,7 +681,7 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                     },
                 None => {
                     span_invalid_monomorphization_error(
-                        tcx.sess, span,
+                        fcx, span,
                         &format!("invalid monomorphization of `{}` intrinsic: \
                                   expected basic integer type, found `{}`", name, sty));
                         C_nil(ccx)
@@ -670,6 +693,17 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
         (_, "frem_fast") => {
             let sty = &arg_tys[0].sty;
             match float_type_width(sty) {
+                // Without `-Z fast-math` these are ordinary operations, so
+                // that results don't change with the optimization level.
+                Some(_width) if !tcx.sess.opts.debugging_opts.fast_math =>
+                    match &*name {
+                        "fadd_fast" => FAdd(bcx, llargs[0], llargs[1], call_debug_location),
+                        "fsub_fast" => FSub(bcx, llargs[0], llargs[1], call_debug_location),
+                        "fmul_fast" => FMul(bcx, llargs[0], llargs[1], call_debug_location),
+                        "fdiv_fast" => FDiv(bcx, llargs[0], llargs[1], call_debug_location),
+                        "frem_fast" => FRem(bcx, llargs[0], llargs[1], call_debug_location),
+                        _ => bug!(),
+                    },
                 Some(_width) =>
                     match &*name {
                         "fadd_fast" => FAddFast(bcx, llargs[0], llargs[1], call_debug_location),
@@ -681,7 +715,7 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                     },
                 None => {
                     span_invalid_monomorphization_error(
-                        tcx.sess, span,
+                        fcx, span,
                         &format!("invalid monomorphization of `{}` intrinsic: \
                                   expected basic float type, found `{}`", name, sty));
                         C_nil(ccx)
@@ -702,6 +736,12 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
             }
         }
 
+        (_, "variant_count") => {
+            match substs.types.get(FnSpace, 0).sty {
+                ty::TyEnum(adt, _) => C_uint(ccx, adt.variants.len()),
+                _ => C_uint(ccx, 0usize)
+            }
+        }
         (_, "discriminant_value") => {
             let val_ty = substs.types.get(FnSpace, 0);
             match val_ty.sty {
@@ -766,7 +806,7 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                         Store(bcx, success, StructGEP(bcx, llresult, 1));
                     } else {
                         span_invalid_monomorphization_error(
-                            tcx.sess, span,
+                            fcx, span,
                             &format!("invalid monomorphization of `{}` intrinsic: \
                                       expected basic integer type, found `{}`", name, sty));
                     }
@@ -779,7 +819,7 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                         AtomicLoad(bcx, llargs[0], order)
                     } else {
                         span_invalid_monomorphization_error(
-                            tcx.sess, span,
+                            fcx, span,
                             &format!("invalid monomorphization of `{}` intrinsic: \
                                       expected basic integer type, found `{}`", name, sty));
                         C_nil(ccx)
@@ -792,7 +832,7 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                         AtomicStore(bcx, llargs[1], llargs[0], order);
                     } else {
                         span_invalid_monomorphization_error(
-                            tcx.sess, span,
+                            fcx, span,
                             &format!("invalid monomorphization of `{}` intrinsic: \
                                       expected basic integer type, found `{}`", name, sty));
                     }
@@ -831,7 +871,7 @@ pub fn trans_intrinsic_call<'a, 'blk, 'tcx>(mut bcx: Block<'blk, 'tcx>,
                         AtomicRMW(bcx, atom_op, llargs[0], llargs[1], order)
                     } else {
                         span_invalid_monomorphization_error(
-                            tcx.sess, span,
+                            fcx, span,
                             &format!("invalid monomorphization of `{}` intrinsic: \
                                       expected basic integer type, found `{}`", name, sty));
                         C_nil(ccx)
@@ -1444,8 +1484,16 @@ fn generate_filter_fn<'a, 'tcx>(fcx: &FunctionContext<'a, 'tcx>,
     }
 }
 
-fn span_invalid_monomorphization_error(a: &Session, b: Span, c: &str) {
-    span_err!(a, b, E0511, "{}", c);
+fn span_invalid_monomorphization_error(fcx: &FunctionContext, span: Span, msg: &str) {
+    let ccx = fcx.ccx;
+    let mut err = struct_span_err!(ccx.sess(), span, E0511, "{}", msg);
+    if let Some(instance) = fcx.instance {
+        collector::note_instantiation_backtrace(ccx,
+                                                &ccx.instantiation_map().borrow(),
+                                                TransItem::Fn(instance),
+                                                &mut err);
+    }
+    err.emit();
 }
 
 fn generic_simd_intrinsic<'blk, 'tcx, 'a>
@@ -1467,7 +1515,7 @@ fn generic_simd_intrinsic<'blk, 'tcx, 'a>
         };
         ($msg: tt, $($fmt: tt)*) => {
             span_invalid_monomorphization_error(
-                bcx.sess(), span,
+                bcx.fcx, span,
                 &format!(concat!("invalid monomorphization of `{}` intrinsic: ",
                                  $msg),
                          name, $($fmt)*));
@@ -73,6 +73,7 @@ pub mod back {
     pub mod linker;
     pub mod link;
     pub mod lto;
+    pub mod size_attribution;
     pub mod symbol_names;
     pub mod write;
     pub mod msvc;
@@ -144,6 +145,7 @@ pub struct CrateTranslation {
     pub metadata: Vec<u8>,
     pub reachable: Vec<String>,
     pub no_builtins: bool,
+    pub translated_fns: Vec<back::size_attribution::TranslatedFn>,
 }
 
 __build_diagnostic_array! { librustc_trans, DIAGNOSTICS }
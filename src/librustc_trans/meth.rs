@@ -12,7 +12,8 @@ use std::rc::Rc;
 
 use arena::TypedArena;
 use back::symbol_names;
-use llvm::{ValueRef, get_params};
+use llvm::{self, ValueRef, get_params};
+use libc::c_uint;
 use rustc::hir::def_id::DefId;
 use rustc::infer;
 use rustc::ty::subst::{FnSpace, Subst, Substs};
@@ -21,7 +22,7 @@ use rustc::traits::{self, ProjectionMode};
 use abi::FnType;
 use base::*;
 use build::*;
-use callee::{Callee, Virtual, ArgVals, trans_fn_pointer_shim};
+use callee::{Callee, Fn, Virtual, ArgVals, trans_fn_pointer_shim};
 use closure;
 use common::*;
 use consts;
@@ -34,6 +35,7 @@ use type_::Type;
 use type_of::*;
 use value::Value;
 use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
+use rustc::hir;
 
 use syntax::ast::Name;
 use syntax::codemap::DUMMY_SP;
@@ -42,14 +44,21 @@ use syntax::codemap::DUMMY_SP;
 const VTABLE_OFFSET: usize = 3;
 
 /// Extracts a method from a trait object's vtable, at the specified index.
+/// `method_id` is the trait method being called.
 pub fn get_virtual_method<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
                                       llvtable: ValueRef,
-                                      vtable_index: usize)
+                                      vtable_index: usize,
+                                      method_id: DefId)
                                       -> ValueRef {
     // Load the data pointer from the object.
     debug!("get_virtual_method(vtable_index={}, llvtable={:?})",
            vtable_index, Value(llvtable));
 
+    let ccx = bcx.ccx();
+    if ccx.sess().opts.debugging_opts.virtual_function_elimination {
+        ccx.virtual_calls().borrow_mut().insert(method_id);
+    }
+
     Load(bcx, GEPi(bcx, llvtable, &[vtable_index + VTABLE_OFFSET]))
 }
 
@@ -110,8 +119,12 @@ pub fn trans_object_shim<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
     let llargs = get_params(fcx.llfn);
     let args = ArgVals(&llargs[fcx.fn_ty.ret.is_indirect() as usize..]);
 
+    let method_id = match method_ty.sty {
+        ty::TyFnDef(def_id, _, _) => def_id,
+        _ => bug!("expected fn item type, found {}", method_ty)
+    };
     let callee = Callee {
-        data: Virtual(vtable_index),
+        data: Virtual(vtable_index, method_id),
         ty: method_ty
     };
     bcx = callee.call(bcx, DebugLoc::None, args, dest).bcx;
@@ -121,6 +134,104 @@ pub fn trans_object_shim<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
     llfn
 }
 
+/// Generates a shim taking the receiver of a method that takes `self` by
+/// value by reference instead, for its entry in a vtable. Such a receiver
+/// is unsized in the trait object, so the virtual call passes it in place
+/// (see the `unsized_locals` feature), as a pointer to it:
+///
+///    fn shim(self: *mut Self, args...) -> R {
+///        method(ptr::read(self), args...)
+///    }
+fn trans_by_value_self_shim<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
+                                      callee: Callee<'tcx>)
+                                      -> ValueRef {
+    let _icx = push_ctxt("trans_by_value_self_shim");
+    let tcx = ccx.tcx();
+
+    debug!("trans_by_value_self_shim(callee={:?})", callee);
+
+    let abi = callee.ty.fn_abi();
+    let sig = tcx.erase_late_bound_regions(&callee.ty.fn_sig());
+    let sig = infer::normalize_associated_type(tcx, &sig);
+    let self_ty = sig.inputs[0];
+    let mut inputs = sig.inputs.clone();
+    inputs[0] = tcx.mk_mut_ptr(self_ty);
+    let sig = ty::FnSig {
+        inputs: inputs,
+        output: sig.output,
+        variadic: false
+    };
+    let fn_ty = FnType::new(ccx, abi, &sig, &[]);
+    let shim_fn_ty = tcx.mk_fn_ptr(ty::BareFnTy {
+        unsafety: hir::Unsafety::Unsafe,
+        abi: abi,
+        sig: ty::Binder(sig)
+    });
+
+    let function_name =
+        symbol_names::internal_name_from_type_and_suffix(ccx, callee.ty, "by_value_self_shim");
+    let llfn = declare::define_internal_fn(ccx, &function_name, shim_fn_ty);
+
+    let (block_arena, fcx): (TypedArena<_>, FunctionContext);
+    block_arena = TypedArena::new();
+    fcx = FunctionContext::new(ccx, llfn, fn_ty, None, &block_arena);
+    let mut bcx = fcx.init(false, None);
+    assert!(!fcx.needs_ret_allocas);
+
+    let dest =
+        fcx.llretslotptr.get().map(
+            |_| expr::SaveIn(fcx.get_ret_slot(bcx, "ret_slot")));
+
+    let llargs = get_params(fcx.llfn);
+    let self_idx = fcx.fn_ty.ret.is_indirect() as usize;
+    let llself = llargs[self_idx];
+
+    // Pass the receiver the way the method expects it.
+    let callee_fn_ty = callee.direct_fn_type(ccx, &[]);
+    let self_arg = &callee_fn_ty.args[0];
+    let mut args = Vec::with_capacity(llargs.len());
+    if !self_arg.is_ignore() {
+        if self_arg.is_indirect() {
+            args.push(llself);
+        } else if let Some(ty) = self_arg.cast {
+            args.push(Load(bcx, PointerCast(bcx, llself, ty.ptr_to())));
+        } else {
+            args.push(load_ty(bcx, llself, self_ty));
+        }
+    }
+    args.extend_from_slice(&llargs[self_idx + 1..]);
+
+    bcx = callee.call(bcx, DebugLoc::None, ArgVals(&args), dest).bcx;
+
+    fcx.finish(bcx, DebugLoc::None);
+
+    llfn
+}
+
+/// The vtable entry for `llfn`, the `call_once` method of a closure or fn
+/// pointer for the closure trait `trait_ref`, which takes `self` by value.
+fn by_value_call_once_shim<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
+                                     trait_ref: &ty::PolyTraitRef<'tcx>,
+                                     kind: ty::ClosureKind,
+                                     llfn: ValueRef)
+                                     -> ValueRef {
+    match kind {
+        ty::ClosureKind::Fn | ty::ClosureKind::FnMut => return llfn,
+        ty::ClosureKind::FnOnce => {}
+    }
+
+    let tcx = ccx.tcx();
+    let trait_ref = tcx.erase_late_bound_regions(trait_ref);
+    let call_once = tcx.trait_items(trait_ref.def_id).iter().filter_map(|item| {
+        match *item {
+            ty::MethodTraitItem(ref method) => Some(method.def_id),
+            _ => None
+        }
+    }).next().unwrap();
+    let method_ty = tcx.lookup_item_type(call_once).ty.subst(tcx, trait_ref.substs);
+    trans_by_value_self_shim(ccx, Callee { data: Fn(llfn), ty: method_ty })
+}
+
 /// Creates a returns a dynamic vtable for the given type and vtable origin.
 /// This is used only for objects.
 ///
@@ -160,7 +271,13 @@ pub fn get_vtable<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                 get_vtable_methods(ccx, id, substs)
                     .into_iter()
                     .map(|opt_mth| opt_mth.map_or(nullptr, |mth| {
-                        Callee::def(ccx, mth.method.def_id, &mth.substs).reify(ccx).val
+                        let callee = Callee::def(ccx, mth.method.def_id, &mth.substs);
+                        match mth.method.explicit_self {
+                            ty::ExplicitSelfCategory::ByValue => {
+                                trans_by_value_self_shim(ccx, callee)
+                            }
+                            _ => callee.reify(ccx).val
+                        }
                     }))
                     .collect::<Vec<_>>()
                     .into_iter()
@@ -175,11 +292,14 @@ pub fn get_vtable<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                                          closure_def_id,
                                                          substs,
                                                          trait_closure_kind);
-                vec![llfn].into_iter()
+                vec![by_value_call_once_shim(ccx, &trait_ref, trait_closure_kind, llfn)]
+                    .into_iter()
             }
             traits::VtableFnPointer(bare_fn_ty) => {
                 let trait_closure_kind = tcx.lang_items.fn_trait_kind(trait_ref.def_id()).unwrap();
-                vec![trans_fn_pointer_shim(ccx, trait_closure_kind, bare_fn_ty)].into_iter()
+                let llfn = trans_fn_pointer_shim(ccx, trait_closure_kind, bare_fn_ty);
+                vec![by_value_call_once_shim(ccx, &trait_ref, trait_closure_kind, llfn)]
+                    .into_iter()
             }
             traits::VtableObject(ref data) => {
                 // this would imply that the Self type being erased is
@@ -215,6 +335,62 @@ pub fn get_vtable<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     vtable
 }
 
+/// Under `-Z virtual-function-elimination`, empties the slots of the
+/// vtables of crate-private traits whose method is never called through
+/// a vtable. Only this crate can make virtual calls through such traits,
+/// and all of it has been translated by now, so nothing can read those
+/// slots; with the methods no longer referenced, LTO removes them.
+pub fn eliminate_virtual_functions(ccx: &CrateContext) {
+    let tcx = ccx.tcx();
+    let virtual_calls = ccx.virtual_calls().borrow();
+    for (trait_ref, &vtable) in ccx.vtables().borrow().iter() {
+        if !is_crate_private_trait(ccx, trait_ref.def_id()) {
+            continue;
+        }
+
+        // The method each slot after the header holds, in the order
+        // `get_vtable` lays them out.
+        let slots: Vec<_> = traits::supertraits(tcx, trait_ref.clone()).flat_map(|trait_ref| {
+            tcx.trait_item_def_ids(trait_ref.def_id()).iter().filter_map(|item_def_id| {
+                match *item_def_id {
+                    ty::MethodTraitItemId(def_id) => Some(def_id),
+                    _ => None,
+                }
+            }).collect::<Vec<_>>()
+        }).collect();
+
+        unsafe {
+            let init = llvm::LLVMGetInitializer(vtable);
+            let len = llvm::LLVMGetNumOperands(init) as usize;
+            if len != VTABLE_OFFSET + slots.len() {
+                // Some supertrait's methods are not laid out one per
+                // slot; leave the vtable alone.
+                continue;
+            }
+            let components: Vec<_> = (0..len).map(|i| {
+                let component = llvm::LLVMGetOperand(init, i as c_uint);
+                if i < VTABLE_OFFSET || virtual_calls.contains(&slots[i - VTABLE_OFFSET]) {
+                    component
+                } else {
+                    C_null(val_ty(component))
+                }
+            }).collect();
+            llvm::LLVMSetInitializer(vtable, C_struct(ccx, &components, false));
+        }
+    }
+}
+
+/// Whether virtual calls through trait objects of the given trait can
+/// only happen in this crate. This is conservative: only traits that
+/// are not declared `pub` are considered private.
+fn is_crate_private_trait(ccx: &CrateContext, trait_id: DefId) -> bool {
+    let tcx = ccx.tcx();
+    match tcx.map.as_local_node_id(trait_id) {
+        Some(node_id) => tcx.map.expect_item(node_id).vis != hir::Public,
+        None => false
+    }
+}
+
 pub fn get_vtable_methods<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                     impl_id: DefId,
                                     substs: &'tcx subst::Substs<'tcx>)
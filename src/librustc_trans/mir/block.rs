@@ -23,10 +23,12 @@ use machine::{llalign_of_min, llbitsize_of_real};
 use meth;
 use type_of;
 use glue;
+use intrinsic;
 use type_::Type;
+use syntax::codemap::Span;
 
 use super::{MirContext, TempRef, drop};
-use super::lvalue::{LvalueRef, load_fat_ptr};
+use super::lvalue::{LvalueRef, get_dataptr, get_meta, load_fat_ptr};
 use super::operand::OperandRef;
 use super::operand::OperandValue::{self, FatPtr, Immediate, Ref};
 
@@ -136,7 +138,16 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 }
                 let drop_fn = glue::get_drop_glue(bcx.ccx(), ty);
                 let drop_ty = glue::get_drop_glue_type(bcx.ccx(), ty);
-                let llvalue = if drop_ty != ty {
+                let llvalue = if !common::type_is_sized(bcx.tcx(), ty) {
+                    // An unsized argument. The drop glue of an unsized type
+                    // takes a pointer to a fat pointer to the value.
+                    let llfatptr = bcx.with_block(|bcx| {
+                        base::alloc_ty(bcx, bcx.tcx().mk_mut_ptr(ty), "drop_arg")
+                    });
+                    bcx.store(lvalue.llval, get_dataptr(&bcx, llfatptr));
+                    bcx.store(lvalue.llextra, get_meta(&bcx, llfatptr));
+                    llfatptr
+                } else if drop_ty != ty {
                     bcx.pointercast(lvalue.llval, type_of::type_of(bcx.ccx(), drop_ty).ptr_to())
                 } else {
                     lvalue.llval
@@ -151,11 +162,11 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                                cleanup_bundle.as_ref());
                     self.bcx(target).at_start(|bcx| {
                         debug_loc.apply_to_bcx(bcx);
-                        drop::drop_fill(bcx, lvalue.llval, ty)
+                        drop::drop_fill_lvalue(bcx, &lvalue, ty)
                     });
                 } else {
                     bcx.call(drop_fn, &[llvalue], cleanup_bundle.as_ref());
-                    drop::drop_fill(&bcx, lvalue.llval, ty);
+                    drop::drop_fill_lvalue(&bcx, &lvalue, ty);
                     funclet_br(bcx, self.llblock(target));
                 }
             }
@@ -202,7 +213,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 if intrinsic == Some("transmute") {
                     let &(ref dest, target) = destination.as_ref().unwrap();
                     self.with_lvalue_ref(&bcx, dest, |this, dest| {
-                        this.trans_transmute(&bcx, &args[0], dest);
+                        this.trans_transmute(&bcx, terminator.span, &args[0], dest);
                     });
 
                     self.set_operand_dropped(&bcx, &args[0]);
@@ -242,7 +253,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
 
                 let mut idx = 0;
                 for arg in first_args {
-                    let val = self.trans_operand(&bcx, arg).val;
+                    let val = self.trans_argument_value(&bcx, arg);
                     self.trans_argument(&bcx, val, &mut llargs, &fn_ty,
                                         &mut idx, &mut callee.data);
                 }
@@ -300,7 +311,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                         return;
                     }
                     Fn(f) => f,
-                    Virtual(_) => bug!("Virtual fn ptr not extracted")
+                    Virtual(..) => bug!("Virtual fn ptr not extracted")
                 };
 
                 // Many different ways to call a function handled here
@@ -359,9 +370,103 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                     }
                 }
             }
+
+            mir::TerminatorKind::TailCall { ref func, ref args } => {
+                let callee = self.trans_operand(&bcx, func);
+
+                // Typeck made sure that the callee has the same signature and
+                // ABI as this function, so its `FnType` matches ours too.
+                let (mut callee, abi) = match callee.ty.sty {
+                    ty::TyFnDef(def_id, substs, f) => {
+                        (Callee::def(bcx.ccx(), def_id, substs), f.abi)
+                    }
+                    ty::TyFnPtr(f) => {
+                        (Callee {
+                            data: Fn(callee.immediate()),
+                            ty: callee.ty
+                        }, f.abi)
+                    }
+                    _ => bug!("{} is not callable", callee.ty)
+                };
+                let fn_ty = callee.direct_fn_type(bcx.ccx(), &[]);
+
+                // An argument passed indirectly would point into our own
+                // frame, which the callee is about to take over.
+                if fn_ty.args.iter().any(|arg| arg.is_indirect()) {
+                    bcx.tcx().sess.span_err(terminator.span,
+                                            "`become` is not supported for calls that pass \
+                                             arguments by reference in this ABI");
+                    bcx.unreachable();
+                    return;
+                }
+
+                let mut llargs = Vec::with_capacity(fn_ty.args.len() + 1);
+                if fn_ty.ret.is_indirect() {
+                    // The callee writes its result straight into our caller's
+                    // return slot.
+                    let llretptr = self.trans_lvalue(&bcx, &mir::Lvalue::ReturnPointer).llval;
+                    llargs.push(llretptr);
+                }
+
+                // Split the rust-call tupled arguments off.
+                let (first_args, untuple) = if abi == Abi::RustCall && !args.is_empty() {
+                    let (tup, args) = args.split_last().unwrap();
+                    (args, Some(tup))
+                } else {
+                    (&args[..], None)
+                };
+
+                let mut idx = 0;
+                for arg in first_args {
+                    let val = self.trans_argument_value(&bcx, arg);
+                    self.trans_argument(&bcx, val, &mut llargs, &fn_ty,
+                                        &mut idx, &mut callee.data);
+                }
+                if let Some(tup) = untuple {
+                    self.trans_arguments_untupled(&bcx, tup, &mut llargs, &fn_ty,
+                                                  &mut idx, &mut callee.data)
+                }
+
+                let fn_ptr = match callee.data {
+                    NamedTupleConstructor(_) => callee.reify(bcx.ccx()).val,
+                    Fn(f) => f,
+                    Intrinsic => bug!("tail call to an intrinsic"),
+                    Virtual(..) => bug!("Virtual fn ptr not extracted")
+                };
+
+                let llret = bcx.call(fn_ptr, &llargs, cleanup_bundle.as_ref());
+                fn_ty.apply_attrs_callsite(llret);
+                unsafe {
+                    llvm::LLVMRustSetMustTailCall(llret);
+                }
+
+                // `musttail` requires the call to be followed directly by a
+                // return of its result.
+                if fn_ty.ret.is_indirect() || fn_ty.ret.is_ignore() {
+                    bcx.ret_void();
+                } else {
+                    bcx.ret(llret);
+                }
+            }
         }
     }
 
+    /// Translates the value of the argument `arg`. An unsized argument is
+    /// passed in place, as a fat pointer to it (see `FnType::unadjusted`).
+    fn trans_argument_value(&mut self,
+                            bcx: &BlockAndBuilder<'bcx, 'tcx>,
+                            arg: &mir::Operand<'tcx>)
+                            -> OperandValue {
+        if let mir::Operand::Consume(ref lvalue) = *arg {
+            let arg_ty = bcx.monomorphize(&self.mir.operand_ty(bcx.tcx(), arg));
+            if !common::type_is_sized(bcx.tcx(), arg_ty) {
+                let lvalue = self.trans_lvalue(bcx, lvalue);
+                return FatPtr(lvalue.llval, lvalue.llextra);
+            }
+        }
+        self.trans_operand(bcx, arg).val
+    }
+
     fn trans_argument(&mut self,
                       bcx: &BlockAndBuilder<'bcx, 'tcx>,
                       val: OperandValue,
@@ -372,9 +477,9 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
         // Treat the values in a fat pointer separately.
         if let FatPtr(ptr, meta) = val {
             if *next_idx == 0 {
-                if let Virtual(idx) = *callee {
+                if let Virtual(idx, method_id) = *callee {
                     let llfn = bcx.with_block(|bcx| {
-                        meth::get_virtual_method(bcx, meta, idx)
+                        meth::get_virtual_method(bcx, meta, idx, method_id)
                     });
                     let llty = fn_ty.llvm_type(bcx.ccx()).ptr_to();
                     *callee = Fn(bcx.pointercast(llfn, llty));
@@ -613,11 +718,20 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
         }
     }
 
-    fn trans_transmute(&mut self, bcx: &BlockAndBuilder<'bcx, 'tcx>,
+    fn trans_transmute(&mut self, bcx: &BlockAndBuilder<'bcx, 'tcx>, span: Span,
                        src: &mir::Operand<'tcx>, dst: LvalueRef<'tcx>) {
         let mut val = self.trans_operand(bcx, src);
+
+        // Transmutes involving type parameters are only checked now that
+        // they have been monomorphized. If the sizes differ, an error has
+        // been reported, and the store below would be ill-formed.
+        let dst_ty = dst.ty.to_ty(bcx.tcx());
+        if !intrinsic::check_transmute_sizes(bcx.fcx(), span, val.ty, dst_ty) {
+            return;
+        }
+
         if let ty::TyFnDef(def_id, substs, _) = val.ty.sty {
-            let llouttype = type_of::type_of(bcx.ccx(), dst.ty.to_ty(bcx.tcx()));
+            let llouttype = type_of::type_of(bcx.ccx(), dst_ty);
             let out_type_size = llbitsize_of_real(bcx.ccx(), llouttype);
             if out_type_size != 0 {
                 // FIXME #19925 Remove this hack after a release cycle.
@@ -13,10 +13,13 @@ use rustc::ty::Ty;
 use adt;
 use base;
 use common::{self, BlockAndBuilder};
+use glue;
 use machine;
 use type_of;
 use type_::Type;
 
+use super::lvalue::LvalueRef;
+
 pub fn drop_fill<'bcx, 'tcx>(bcx: &BlockAndBuilder<'bcx, 'tcx>, value: ValueRef, ty: Ty<'tcx>) {
     let llty = type_of::type_of(bcx.ccx(), ty);
     let llptr = bcx.pointercast(value, Type::i8(bcx.ccx()).ptr_to());
@@ -25,3 +28,28 @@ pub fn drop_fill<'bcx, 'tcx>(bcx: &BlockAndBuilder<'bcx, 'tcx>, value: ValueRef,
     let align = common::C_u32(bcx.ccx(), machine::llalign_of_min(bcx.ccx(), llty));
     base::call_memset(&bcx, llptr, filling, size, align, false);
 }
+
+/// Fills the lvalue `lvalue` of type `ty`, which may be unsized, e.g. an
+/// unsized argument.
+pub fn drop_fill_lvalue<'bcx, 'tcx>(bcx: &BlockAndBuilder<'bcx, 'tcx>,
+                                    lvalue: &LvalueRef<'tcx>,
+                                    ty: Ty<'tcx>) {
+    if common::type_is_sized(bcx.tcx(), ty) {
+        drop_fill(bcx, lvalue.llval, ty);
+    } else {
+        drop_fill_unsized(bcx, lvalue.llval, lvalue.llextra, ty);
+    }
+}
+
+/// Like `drop_fill`, for a value of the unsized type `ty`, whose size is
+/// found from `llextra`.
+fn drop_fill_unsized<'bcx, 'tcx>(bcx: &BlockAndBuilder<'bcx, 'tcx>,
+                                 value: ValueRef,
+                                 llextra: ValueRef,
+                                 ty: Ty<'tcx>) {
+    let (size, _) = glue::size_and_align_of_dst(bcx, ty, llextra);
+    let llptr = bcx.pointercast(value, Type::i8(bcx.ccx()).ptr_to());
+    let filling = common::C_u8(bcx.ccx(), adt::DTOR_DONE);
+    let align = common::C_u32(bcx.ccx(), 1);
+    base::call_memset(&bcx, llptr, filling, size, align, false);
+}
@@ -44,6 +44,11 @@ impl<'tcx> LvalueRef<'tcx> {
         LvalueRef { llval: llval, llextra: ptr::null_mut(), ty: lvalue_ty }
     }
 
+    pub fn new_unsized(llval: ValueRef, llextra: ValueRef, lvalue_ty: LvalueTy<'tcx>)
+                       -> LvalueRef<'tcx> {
+        LvalueRef { llval: llval, llextra: llextra, ty: lvalue_ty }
+    }
+
     pub fn alloca<'bcx>(bcx: &BlockAndBuilder<'bcx, 'tcx>,
                         ty: Ty<'tcx>,
                         name: &str)
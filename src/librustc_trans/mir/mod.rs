@@ -297,6 +297,17 @@ fn arg_value_refs<'bcx, 'tcx>(bcx: &BlockAndBuilder<'bcx, 'tcx>,
             return LvalueRef::new_sized(lltemp, LvalueTy::from_ty(arg_ty));
         }
 
+        if !common::type_is_sized(tcx, arg_ty) {
+            // An unsized argument is passed as a fat pointer to where the
+            // caller has the value, which the argument refers to in turn.
+            // FIXME: debuginfo
+            let llarg = llvm::get_param(fcx.llfn, llarg_idx as c_uint);
+            let llmeta = llvm::get_param(fcx.llfn, (llarg_idx + 1) as c_uint);
+            llarg_idx += 2;
+            idx += 2;
+            return LvalueRef::new_unsized(llarg, llmeta, LvalueTy::from_ty(arg_ty));
+        }
+
         let arg = &fcx.fn_ty.args[idx];
         idx += 1;
         let llval = if arg.is_indirect() && bcx.sess().opts.debuginfo != FullDebugInfo {
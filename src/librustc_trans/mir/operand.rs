@@ -192,7 +192,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 if !glue::type_needs_drop(bcx.tcx(), ty) {
                     return
                 } else {
-                    drop::drop_fill(bcx, lvalue.llval, ty);
+                    drop::drop_fill_lvalue(bcx, &lvalue, ty);
                 }
             }
         }
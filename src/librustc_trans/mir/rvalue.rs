@@ -18,10 +18,12 @@ use rustc::mir::repr as mir;
 use asm;
 use base;
 use callee::Callee;
+use closure;
 use common::{self, C_uint, BlockAndBuilder, Result};
 use datum::{Datum, Lvalue};
 use debuginfo::DebugLoc;
 use declare;
+use expr::OverflowOpViaIntrinsic;
 use adt;
 use machine;
 use type_::Type;
@@ -195,6 +197,25 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                 bcx
             }
 
+            mir::Rvalue::CheckedBinaryOp(op, ref lhs, ref rhs) => {
+                let lhs = self.trans_operand(&bcx, lhs);
+                let rhs = self.trans_operand(&bcx, rhs);
+                let (llresult, lloverflow) =
+                    self.trans_scalar_checked_binop(&bcx, op,
+                                                    lhs.immediate(), rhs.immediate(),
+                                                    lhs.ty);
+                let fields = [(llresult, lhs.ty), (lloverflow, bcx.tcx().types.bool)];
+                for (i, &(llval, ty)) in fields.iter().enumerate() {
+                    let operand = OperandRef {
+                        val: OperandValue::Immediate(llval),
+                        ty: ty,
+                    };
+                    let lldest = bcx.gepi(dest.llval, &[0, i]);
+                    self.store_operand(&bcx, lldest, operand);
+                }
+                bcx
+            }
+
             mir::Rvalue::InlineAsm { ref asm, ref outputs, ref inputs } => {
                 let outputs = outputs.iter().map(|output| {
                     let lvalue = self.trans_lvalue(&bcx, output);
@@ -256,6 +277,17 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                         // this is a no-op at the LLVM level
                         operand.val
                     }
+                    mir::CastKind::ClosureFnPointer => {
+                        match operand.ty.sty {
+                            ty::TyClosure(def_id, ref substs) => {
+                                OperandValue::Immediate(
+                                    closure::trans_closure_fn_pointer(bcx.ccx(), def_id, substs))
+                            }
+                            _ => {
+                                bug!("{} cannot be coerced to a fn ptr", operand.ty)
+                            }
+                        }
+                    }
                     mir::CastKind::Unsize => {
                         // unsize targets other than to a fat pointer currently
                         // can't be operands.
@@ -339,9 +371,9 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
                             (CastTy::Int(_), CastTy::Float) =>
                                 bcx.uitofp(llval, ll_t_out),
                             (CastTy::Float, CastTy::Int(IntTy::I)) =>
-                                bcx.fptosi(llval, ll_t_out),
+                                base::cast_float_to_int(&bcx, llval, true, ll_t_out),
                             (CastTy::Float, CastTy::Int(_)) =>
-                                bcx.fptoui(llval, ll_t_out),
+                                base::cast_float_to_int(&bcx, llval, false, ll_t_out),
                             _ => bug!("unsupported cast: {:?} to {:?}", operand.ty, cast_ty)
                         };
                         OperandValue::Immediate(newval)
@@ -485,6 +517,7 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
 
             mir::Rvalue::Use(..) |
             mir::Rvalue::Repeat(..) |
+            mir::Rvalue::CheckedBinaryOp(..) |
             mir::Rvalue::Aggregate(..) |
             mir::Rvalue::Slice { .. } |
             mir::Rvalue::InlineAsm { .. } => {
@@ -494,6 +527,26 @@ impl<'bcx, 'tcx> MirContext<'bcx, 'tcx> {
         }
     }
 
+    /// Translates `lhs op rhs` for an integer `Add`, `Sub` or `Mul`, and
+    /// returns the (wrapped) result along with an `i1` that is set if the
+    /// operation overflowed.
+    pub fn trans_scalar_checked_binop(&mut self,
+                                      bcx: &BlockAndBuilder<'bcx, 'tcx>,
+                                      op: mir::BinOp,
+                                      lhs: ValueRef,
+                                      rhs: ValueRef,
+                                      input_ty: Ty<'tcx>) -> (ValueRef, ValueRef) {
+        let oop = match op {
+            mir::BinOp::Add => OverflowOpViaIntrinsic::Add,
+            mir::BinOp::Sub => OverflowOpViaIntrinsic::Sub,
+            mir::BinOp::Mul => OverflowOpViaIntrinsic::Mul,
+            _ => bug!("no overflow-checked version of {:?}", op),
+        };
+        let llfn = bcx.ccx().get_intrinsic(&oop.to_intrinsic_name(bcx.tcx(), input_ty));
+        let val = bcx.call(llfn, &[lhs, rhs], None);
+        (bcx.extract_value(val, 0), bcx.extract_value(val, 1))
+    }
+
     pub fn trans_scalar_binop(&mut self,
                               bcx: &BlockAndBuilder<'bcx, 'tcx>,
                               op: mir::BinOp,
@@ -610,6 +663,7 @@ pub fn rvalue_creates_operand<'tcx>(rvalue: &mir::Rvalue<'tcx>) -> bool {
             true,
         mir::Rvalue::Use(..) | // (**)
         mir::Rvalue::Repeat(..) |
+        mir::Rvalue::CheckedBinaryOp(..) |
         mir::Rvalue::Aggregate(..) |
         mir::Rvalue::Slice { .. } |
         mir::Rvalue::InlineAsm { .. } =>
@@ -19,6 +19,7 @@ use rustc::ty::{self, Ty, TypeFoldable, TyCtxt};
 use attributes;
 use base::{push_ctxt};
 use base::trans_fn;
+use collector::{self, TransItem};
 use base;
 use common::*;
 use declare;
@@ -28,7 +29,6 @@ use rustc::util::ppaux;
 
 use rustc::hir;
 
-use syntax::attr;
 use syntax::errors;
 
 use std::fmt;
@@ -78,11 +78,17 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         if depth > ccx.sess().recursion_limit.get() {
             let error = format!("reached the recursion limit while instantiating `{}`",
                                 instance);
-            if let Some(id) = ccx.tcx().map.as_local_node_id(fn_id) {
-                ccx.sess().span_fatal(ccx.tcx().map.span(id), &error);
+            let mut err = if let Some(id) = ccx.tcx().map.as_local_node_id(fn_id) {
+                ccx.sess().struct_span_fatal(ccx.tcx().map.span(id), &error)
             } else {
-                ccx.sess().fatal(&error);
-            }
+                ccx.sess().struct_fatal(&error)
+            };
+            collector::note_instantiation_backtrace(ccx,
+                                                    &ccx.instantiation_map().borrow(),
+                                                    TransItem::Fn(instance),
+                                                    &mut err);
+            err.emit();
+            panic!(errors::FatalError);
         }
 
         monomorphizing.insert(fn_id, depth + 1);
@@ -113,17 +119,17 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         });
     match map_node {
         hir_map::NodeItem(&hir::Item {
-            ref attrs, node: hir::ItemFn(ref decl, _, _, _, _, ref body), ..
+            node: hir::ItemFn(ref decl, _, _, _, _, ref body), ..
         }) |
         hir_map::NodeTraitItem(&hir::TraitItem {
-            ref attrs, node: hir::MethodTraitItem(
+            node: hir::MethodTraitItem(
                 hir::MethodSig { ref decl, .. }, Some(ref body)), ..
         }) |
         hir_map::NodeImplItem(&hir::ImplItem {
-            ref attrs, node: hir::ImplItemKind::Method(
+            node: hir::ImplItemKind::Method(
                 hir::MethodSig { ref decl, .. }, ref body), ..
         }) => {
-            attributes::from_fn_attrs(ccx, attrs, lldecl);
+            attributes::from_fn_attrs(ccx, fn_id, lldecl);
 
             let is_first = !ccx.available_monomorphizations().borrow()
                                                              .contains(&symbol);
@@ -131,7 +137,7 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                 ccx.available_monomorphizations().borrow_mut().insert(symbol.clone());
             }
 
-            let trans_everywhere = attr::requests_inline(attrs);
+            let trans_everywhere = ccx.tcx().codegen_fn_attrs(fn_id).requests_inline();
             if trans_everywhere || is_first {
                 let origin = if is_first { base::OriginalTranslation } else { base::InlinedCopy };
                 base::update_linkage(ccx, lldecl, None, origin);
@@ -112,7 +112,8 @@ pub fn sizing_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, t: Ty<'tcx>) -> Typ
             }
         }
 
-        ty::TyProjection(..) | ty::TyInfer(..) | ty::TyParam(..) | ty::TyError => {
+        ty::TyProjection(..) | ty::TyInfer(..) | ty::TyParam(..) | ty::TyBound(..) |
+        ty::TyError => {
             bug!("fictitious type {:?} in sizing_type_of()", t)
         }
         ty::TySlice(_) | ty::TyTrait(..) | ty::TyStr => bug!()
@@ -298,6 +299,7 @@ pub fn in_memory_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, t: Ty<'tcx>) ->
       ty::TyInfer(..) => bug!("type_of with TyInfer"),
       ty::TyProjection(..) => bug!("type_of with TyProjection"),
       ty::TyParam(..) => bug!("type_of with ty_param"),
+      ty::TyBound(..) => bug!("type_of with TyBound"),
       ty::TyError => bug!("type_of with TyError"),
     };
 
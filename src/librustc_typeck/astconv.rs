@@ -57,6 +57,7 @@ use hir::def::{self, Def};
 use hir::def_id::DefId;
 use middle::resolve_lifetime as rl;
 use rustc::ty::subst::{FnSpace, TypeSpace, SelfSpace, Subst, Substs, ParamSpace};
+use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::traits;
 use rustc::ty::{self, Ty, TyCtxt, ToPredicate, TypeFoldable};
 use rustc::ty::wf::object_region_bounds;
@@ -122,7 +123,7 @@ pub trait AstConv<'tcx> {
                     param_and_substs: Option<ty::TypeParameterDef<'tcx>>,
                     substs: Option<&mut Substs<'tcx>>,
                     space: Option<ParamSpace>,
-                    span: Span) -> Ty<'tcx>;
+                    origin: TypeVariableOrigin) -> Ty<'tcx>;
 
     /// Projecting an associated type from a (potentially)
     /// higher-ranked trait reference is more complicated, because of
@@ -149,12 +150,18 @@ pub trait AstConv<'tcx> {
     }
 
     /// Project an associated type from a non-higher-ranked trait reference.
-    /// This is fairly straightforward and can be accommodated in any context.
+    /// The projection is not normalized here: that would need an inference
+    /// context, and can recurse into the very item being converted. Code
+    /// that needs the concrete type normalizes it later, through its own
+    /// inference context or `traits::normalize_projection_ty`.
     fn projected_ty(&self,
-                    span: Span,
-                    _trait_ref: ty::TraitRef<'tcx>,
-                    _item_name: ast::Name)
-                    -> Ty<'tcx>;
+                    _span: Span,
+                    trait_ref: ty::TraitRef<'tcx>,
+                    item_name: ast::Name)
+                    -> Ty<'tcx>
+    {
+        self.tcx().mk_projection(trait_ref, item_name)
+    }
 }
 
 pub fn ast_region_to_region(tcx: &TyCtxt, lifetime: &hir::Lifetime)
@@ -525,7 +532,8 @@ fn get_type_substs_for_defs<'tcx>(this: &AstConv<'tcx>,
         ty_param_defs
             .iter()
             .map(|p| this.ty_infer(default_type_parameter(p, self_ty), Some(&mut substs),
-                                   Some(TypeSpace), span))
+                                   Some(TypeSpace),
+                                   TypeVariableOrigin::TypeParameterDefinition(span, p.name)))
             .collect()
     } else {
         types_provided
@@ -697,7 +705,8 @@ pub fn instantiate_poly_trait_ref<'tcx>(
 
 /// Instantiates the path for the given trait reference, assuming that it's
 /// bound to a valid trait type. Returns the def_id for the defining trait.
-/// Fails if the type is a type other than a trait type.
+/// Fails if the type is a type other than a trait type, or is a trait alias,
+/// since this is used for the trait refs of impls.
 ///
 /// If the `projections` argument is `None`, then assoc type bindings like `Foo<T=X>`
 /// are disallowed. Otherwise, they are pushed onto the vector given.
@@ -709,6 +718,11 @@ pub fn instantiate_mono_trait_ref<'tcx>(
     -> ty::TraitRef<'tcx>
 {
     let trait_def_id = trait_def_id(this, trait_ref);
+    if let Def::TraitAlias(_) = ::lookup_full_def(this.tcx(), trait_ref.path.span,
+                                                  trait_ref.ref_id) {
+        span_fatal!(this.tcx().sess, trait_ref.path.span, E0529,
+                    "`{}` is a trait alias and cannot be implemented", trait_ref.path);
+    }
     ast_path_to_mono_trait_ref(this,
                                rscope,
                                trait_ref.path.span,
@@ -721,7 +735,7 @@ pub fn instantiate_mono_trait_ref<'tcx>(
 fn trait_def_id<'tcx>(this: &AstConv<'tcx>, trait_ref: &hir::TraitRef) -> DefId {
     let path = &trait_ref.path;
     match ::lookup_full_def(this.tcx(), path.span, trait_ref.ref_id) {
-        Def::Trait(trait_def_id) => trait_def_id,
+        Def::Trait(trait_def_id) | Def::TraitAlias(trait_def_id) => trait_def_id,
         Def::Err => {
             this.tcx().sess.fatal("cannot continue compilation due to previous error");
         }
@@ -1031,7 +1045,7 @@ fn ast_ty_to_trait_ref<'tcx>(this: &AstConv<'tcx>,
                 _ => None
             };
             match def {
-                Some(Def::Trait(trait_def_id)) => {
+                Some(Def::Trait(trait_def_id)) | Some(Def::TraitAlias(trait_def_id)) => {
                     let mut projection_bounds = Vec::new();
                     let trait_ref = object_path_to_poly_trait_ref(this,
                                                                   rscope,
@@ -1453,7 +1467,7 @@ fn base_def_to_ty<'tcx>(this: &AstConv<'tcx>,
     let tcx = this.tcx();
 
     match *def {
-        Def::Trait(trait_def_id) => {
+        Def::Trait(trait_def_id) | Def::TraitAlias(trait_def_id) => {
             // N.B. this case overlaps somewhat with
             // TyObjectSum, see that fn for details
             let mut projection_bounds = Vec::new();
@@ -1719,7 +1733,7 @@ pub fn ast_ty_to_ty<'tcx>(this: &AstConv<'tcx>,
             // values in a ExprClosure, or as
             // the type of local variables. Both of these cases are
             // handled specially and will not descend into this routine.
-            this.ty_infer(None, None, None, ast_ty.span)
+            this.ty_infer(None, None, None, TypeVariableOrigin::TypeInference(ast_ty.span))
         }
     };
 
@@ -1736,7 +1750,9 @@ pub fn ty_of_arg<'tcx>(this: &AstConv<'tcx>,
 {
     match a.ty.node {
         hir::TyInfer if expected_ty.is_some() => expected_ty.unwrap(),
-        hir::TyInfer => this.ty_infer(None, None, None, a.ty.span),
+        hir::TyInfer => {
+            this.ty_infer(None, None, None, TypeVariableOrigin::TypeInference(a.ty.span))
+        }
         _ => ast_ty_to_ty(this, rscope, &a.ty),
     }
 }
@@ -1947,7 +1963,13 @@ pub fn ty_of_closure<'tcx>(
                 None
             }
         });
-        ty_of_arg(this, &rb, a, expected_arg_ty)
+        match a.ty.node {
+            hir::TyInfer if expected_arg_ty.is_none() => {
+                let origin = TypeVariableOrigin::ClosureArgument(a.ty.span);
+                this.ty_infer(None, None, None, origin)
+            }
+            _ => ty_of_arg(this, &rb, a, expected_arg_ty),
+        }
     }).collect();
 
     let expected_ret_ty = expected_sig.map(|e| e.output);
@@ -1962,7 +1984,8 @@ pub fn ty_of_closure<'tcx>(
         _ if is_infer && expected_ret_ty.is_some() =>
             expected_ret_ty.unwrap(),
         _ if is_infer =>
-            ty::FnConverging(this.ty_infer(None, None, None, decl.output.span())),
+            ty::FnConverging(this.ty_infer(None, None, None,
+                TypeVariableOrigin::ClosureReturnType(decl.output.span()))),
         hir::Return(ref output) =>
             ty::FnConverging(ast_ty_to_ty(this, &rb, &output)),
         hir::DefaultReturn(..) => bug!(),
@@ -2046,6 +2069,7 @@ pub fn conv_existential_bounds_from_partitioned_bounds<'tcx>(
 {
     let PartitionedBounds { builtin_bounds,
                             trait_bounds,
+                            negative_bounds,
                             region_bounds } =
         partitioned_bounds;
 
@@ -2055,6 +2079,12 @@ pub fn conv_existential_bounds_from_partitioned_bounds<'tcx>(
                   "only the builtin traits can be used as closure or object bounds");
     }
 
+    if !negative_bounds.is_empty() {
+        let b = &negative_bounds[0];
+        span_err!(this.tcx().sess, b.trait_ref.path.span, E0530,
+                  "negative bounds cannot be used as closure or object bounds");
+    }
+
     let region_bound =
         compute_object_lifetime_bound(this,
                                       span,
@@ -2148,11 +2178,12 @@ fn compute_object_lifetime_bound<'tcx>(
 pub struct PartitionedBounds<'a> {
     pub builtin_bounds: ty::BuiltinBounds,
     pub trait_bounds: Vec<&'a hir::PolyTraitRef>,
+    pub negative_bounds: Vec<&'a hir::PolyTraitRef>,
     pub region_bounds: Vec<&'a hir::Lifetime>,
 }
 
-/// Divides a list of bounds from the AST into three groups: builtin bounds (Copy, Sized etc),
-/// general trait bounds, and region bounds.
+/// Divides a list of bounds from the AST into four groups: builtin bounds (Copy, Sized etc),
+/// general trait bounds, negative trait bounds (`!Trait`), and region bounds.
 pub fn partition_bounds<'a>(tcx: &TyCtxt,
                             _span: Span,
                             ast_bounds: &'a [hir::TyParamBound])
@@ -2161,6 +2192,7 @@ pub fn partition_bounds<'a>(tcx: &TyCtxt,
     let mut builtin_bounds = ty::BuiltinBounds::empty();
     let mut region_bounds = Vec::new();
     let mut trait_bounds = Vec::new();
+    let mut negative_bounds = Vec::new();
     for ast_bound in ast_bounds {
         match *ast_bound {
             hir::TraitTyParamBound(ref b, hir::TraitBoundModifier::None) => {
@@ -2188,6 +2220,9 @@ pub fn partition_bounds<'a>(tcx: &TyCtxt,
                 }
                 trait_bounds.push(b);
             }
+            hir::TraitTyParamBound(ref b, hir::TraitBoundModifier::Negative) => {
+                negative_bounds.push(b);
+            }
             hir::TraitTyParamBound(_, hir::TraitBoundModifier::Maybe) => {}
             hir::RegionTyParamBound(ref l) => {
                 region_bounds.push(l);
@@ -2198,6 +2233,7 @@ pub fn partition_bounds<'a>(tcx: &TyCtxt,
     PartitionedBounds {
         builtin_bounds: builtin_bounds,
         trait_bounds: trait_bounds,
+        negative_bounds: negative_bounds,
         region_bounds: region_bounds,
     }
 }
@@ -2248,6 +2284,7 @@ pub struct Bounds<'tcx> {
     pub region_bounds: Vec<ty::Region>,
     pub builtin_bounds: ty::BuiltinBounds,
     pub trait_bounds: Vec<ty::PolyTraitRef<'tcx>>,
+    pub negative_bounds: Vec<ty::PolyTraitRef<'tcx>>,
     pub projection_bounds: Vec<ty::PolyProjectionPredicate<'tcx>>,
 }
 
@@ -2277,6 +2314,10 @@ impl<'tcx> Bounds<'tcx> {
             vec.push(bound_trait_ref.to_predicate());
         }
 
+        for bound_trait_ref in &self.negative_bounds {
+            vec.push(ty::Predicate::NegativeTrait(bound_trait_ref.to_poly_trait_predicate()));
+        }
+
         for projection in &self.projection_bounds {
             vec.push(projection.to_predicate());
         }
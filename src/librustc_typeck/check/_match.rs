@@ -10,6 +10,7 @@
 
 use hir::def::{self, Def};
 use rustc::infer::{self, InferOk, TypeOrigin};
+use rustc::infer::type_variable::TypeVariableOrigin;
 use hir::pat_util::{PatIdMap, pat_id_map, pat_is_binding};
 use hir::pat_util::pat_is_resolved_const;
 use rustc::ty::subst::Substs;
@@ -246,8 +247,9 @@ pub fn check_pat<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
         }
         PatKind::Tup(ref elements) => {
             let element_tys: Vec<_> =
-                (0..elements.len()).map(|_| fcx.infcx().next_ty_var())
-                                        .collect();
+                elements.iter().map(|element| {
+                    fcx.infcx().next_ty_var(TypeVariableOrigin::TypeInference(element.span))
+                }).collect();
             let pat_ty = tcx.mk_tup(element_tys.clone());
             fcx.write_ty(pat.id, pat_ty);
             demand::eqtype(fcx, pat.span, expected, pat_ty);
@@ -256,7 +258,7 @@ pub fn check_pat<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
             }
         }
         PatKind::Box(ref inner) => {
-            let inner_ty = fcx.infcx().next_ty_var();
+            let inner_ty = fcx.infcx().next_ty_var(TypeVariableOrigin::TypeInference(inner.span));
             let uniq_ty = tcx.mk_box(inner_ty);
 
             if check_dereferencable(pcx, pat.span, expected, &inner) {
@@ -287,7 +289,8 @@ pub fn check_pat<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
                         (expected, mt.ty)
                     }
                     _ => {
-                        let inner_ty = fcx.infcx().next_ty_var();
+                        let inner_ty = fcx.infcx().next_ty_var(
+                            TypeVariableOrigin::TypeInference(inner.span));
                         let mt = ty::TypeAndMut { ty: inner_ty, mutbl: mutbl };
                         let region = fcx.infcx().next_region_var(infer::PatternRegion(pat.span));
                         let rptr_ty = tcx.mk_ref(tcx.mk_region(region), mt);
@@ -305,7 +308,7 @@ pub fn check_pat<'a, 'tcx>(pcx: &pat_ctxt<'a, 'tcx>,
         }
         PatKind::Vec(ref before, ref slice, ref after) => {
             let expected_ty = structurally_resolved_type(fcx, pat.span, expected);
-            let inner_ty = fcx.infcx().next_ty_var();
+            let inner_ty = fcx.infcx().next_ty_var(TypeVariableOrigin::TypeInference(pat.span));
             let pat_ty = match expected_ty.sty {
                 ty::TyArray(_, size) => tcx.mk_array(inner_ty, {
                     let min_len = before.len() + after.len();
@@ -465,7 +468,7 @@ pub fn check_match<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         // ...but otherwise we want to use any supertype of the
         // discriminant. This is sort of a workaround, see note (*) in
         // `check_pat` for some details.
-        discrim_ty = fcx.infcx().next_ty_var();
+        discrim_ty = fcx.infcx().next_ty_var(TypeVariableOrigin::Adjustment(discrim.span));
         check_expr_has_type(fcx, discrim, discrim_ty);
     };
 
@@ -491,7 +494,8 @@ pub fn check_match<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
     // of execution reach it, we will panic, so bottom is an appropriate
     // type in that case)
     let expected = expected.adjust_for_branches(fcx);
-    let mut result_ty = fcx.infcx().next_diverging_ty_var();
+    let mut result_ty = fcx.infcx().next_diverging_ty_var(
+        TypeVariableOrigin::Diverging(expr.span));
     let coerce_first = match expected {
         // We don't coerce to `()` so that if the match expression is a
         // statement it's branches can have any consistent type. That allows
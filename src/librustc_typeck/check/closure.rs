@@ -13,6 +13,7 @@
 use super::{check_fn, Expectation, FnCtxt};
 
 use astconv;
+use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::ty::subst;
 use rustc::ty::{self, ToPolyTraitRef, Ty};
 use std::cmp;
@@ -51,17 +52,21 @@ fn check_closure<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>,
            opt_kind,
            expected_sig);
 
-    let mut fn_ty = astconv::ty_of_closure(fcx,
-                                           hir::Unsafety::Normal,
-                                           decl,
-                                           Abi::RustCall,
-                                           expected_sig);
+    let fn_ty = astconv::ty_of_closure(fcx,
+                                       hir::Unsafety::Normal,
+                                       decl,
+                                       Abi::RustCall,
+                                       expected_sig);
+    let mut fn_ty = fcx.normalize_associated_types_in(expr.span, &fn_ty);
 
     // Create type variables (for now) to represent the transformed
     // types of upvars. These will be unified during the upvar
     // inference phase (`upvar.rs`).
-    let num_upvars = fcx.tcx().with_freevars(expr.id, |fv| fv.len());
-    let upvar_tys = fcx.infcx().next_ty_vars(num_upvars);
+    let upvar_tys: Vec<_> = fcx.tcx().with_freevars(expr.id, |fv| {
+        fv.iter().map(|fv| {
+            fcx.infcx().next_ty_var(TypeVariableOrigin::ClosureUpvar(fv.span))
+        }).collect()
+    });
 
     debug!("check_closure: expr.id={:?} upvar_tys={:?}",
            expr.id, upvar_tys);
@@ -76,6 +81,7 @@ fn check_closure<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>,
 
     let fn_sig = fcx.tcx().liberate_late_bound_regions(
         fcx.tcx().region_maps.call_site_extent(expr.id, body.id), &fn_ty.sig);
+    let fn_sig = fcx.normalize_associated_types_in(body.span, &fn_sig);
 
     check_fn(fcx.ccx,
              hir::Unsafety::Normal,
@@ -123,6 +129,11 @@ fn deduce_expectations_from_expected_type<'a,'tcx>(
         ty::TyInfer(ty::TyVar(vid)) => {
             deduce_expectations_from_obligations(fcx, vid)
         }
+        ty::TyFnPtr(bare_fn) => {
+            // A closure expected to coerce to a fn pointer must take
+            // the same arguments; its kind is left to upvar inference.
+            (Some(bare_fn.sig.skip_binder().clone()), None)
+        }
         _ => {
             (None, None)
         }
@@ -174,6 +185,7 @@ fn deduce_expectations_from_obligations<'a,'tcx>(
             let opt_trait_ref = match obligation.predicate {
                 ty::Predicate::Projection(ref data) => Some(data.to_poly_trait_ref()),
                 ty::Predicate::Trait(ref data) => Some(data.to_poly_trait_ref()),
+                ty::Predicate::NegativeTrait(..) => None,
                 ty::Predicate::Equate(..) => None,
                 ty::Predicate::RegionOutlives(..) => None,
                 ty::Predicate::TypeOutlives(..) => None,
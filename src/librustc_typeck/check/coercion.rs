@@ -62,18 +62,22 @@
 
 use check::{autoderef, FnCtxt, UnresolvedTypeAction};
 
-use rustc::infer::{Coercion, InferOk, TypeOrigin, TypeTrace};
+use rustc::hir::def_id::DefId;
+use rustc::infer::{Coercion, InferCtxt, InferOk, TypeOrigin, TypeTrace};
 use rustc::traits::{self, ObligationCause};
 use rustc::traits::{predicate_for_trait_def, report_selection_error};
 use rustc::ty::adjustment::{AutoAdjustment, AutoDerefRef, AdjustDerefRef};
 use rustc::ty::adjustment::{AutoPtr, AutoUnsafe, AdjustReifyFnPointer};
 use rustc::ty::adjustment::{AdjustUnsafeFnPointer, AdjustMutToConstPointer};
+use rustc::ty::adjustment::AdjustClosureFnPointer;
 use rustc::ty::{self, LvaluePreference, TypeAndMut, Ty, TyCtxt};
 use rustc::ty::fold::TypeFoldable;
 use rustc::ty::error::TypeError;
 use rustc::ty::relate::{RelateResult, TypeRelation};
 use util::common::indent;
 
+use syntax::feature_gate::{GateIssue, emit_feature_err};
+
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use rustc::hir;
@@ -117,12 +121,7 @@ impl<'f, 'tcx> Coerce<'f, 'tcx> {
         infcx.commit_if_ok(|_| {
             let trace = TypeTrace::types(self.origin, false, a, b);
             if self.use_lub {
-                infcx.lub(false, trace, &a, &b)
-                    .map(|InferOk { value, obligations }| {
-                        // FIXME(#32730) propagate obligations
-                        assert!(obligations.is_empty());
-                        value
-                    })
+                lub_or_sub(infcx, false, trace, a, b)
             } else {
                 infcx.sub(false, trace, &a, &b)
                     .map(|InferOk { value, obligations }| {
@@ -199,6 +198,11 @@ impl<'f, 'tcx> Coerce<'f, 'tcx> {
                 // unsafe qualifier.
                 self.coerce_from_fn_pointer(a, a_f, b)
             }
+            ty::TyClosure(def_id, ref substs) => {
+                // Non-capturing closures are coercible to
+                // function pointers.
+                self.coerce_closure_to_fn(a, def_id, substs, b)
+            }
             _ => {
                 // Otherwise, just use unification rules.
                 self.unify_and_identity(a, b)
@@ -438,7 +442,7 @@ impl<'f, 'tcx> Coerce<'f, 'tcx> {
         };
         let source = source.adjust_for_autoref(self.tcx(), reborrow);
 
-        let mut selcx = traits::SelectionContext::new(self.fcx.infcx());
+        let mut selcx = traits::SelectionContext::probing(self.fcx.infcx());
 
         // Use a FIFO queue for this custom fulfillment procedure.
         let mut queue = VecDeque::new();
@@ -469,8 +473,8 @@ impl<'f, 'tcx> Coerce<'f, 'tcx> {
                 }
             };
             match selcx.select(&obligation.with(trait_ref)) {
-                // Uncertain or unimplemented.
-                Ok(None) | Err(traits::Unimplemented) => {
+                // Uncertain, unimplemented or overflowing.
+                Ok(None) | Err(traits::Unimplemented) | Err(traits::Overflow) => {
                     debug!("coerce_unsized: early return - can't prove obligation");
                     return Err(TypeError::Mismatch);
                 }
@@ -554,6 +558,40 @@ impl<'f, 'tcx> Coerce<'f, 'tcx> {
         }
     }
 
+    fn coerce_closure_to_fn(&self,
+                            a: Ty<'tcx>,
+                            def_id_a: DefId,
+                            substs_a: &ty::ClosureSubsts<'tcx>,
+                            b: Ty<'tcx>)
+                            -> CoerceResult<'tcx> {
+        /*!
+         * Attempts to coerce from the type of a non-capturing closure
+         * into a function pointer.
+         */
+
+        let b = self.fcx.infcx().shallow_resolve(b);
+        debug!("coerce_closure_to_fn(a={:?}, b={:?})", a, b);
+
+        let node_id_a = self.tcx().map.as_local_node_id(def_id_a);
+        match (&b.sty, node_id_a) {
+            (&ty::TyFnPtr(_), Some(node_id_a))
+                if self.tcx().with_freevars(node_id_a, |fv| fv.is_empty()) => {
+                if !self.tcx().sess.features.borrow().closure_to_fn_coercion {
+                    emit_feature_err(&self.tcx().sess.parse_sess.span_diagnostic,
+                                     "closure_to_fn_coercion", self.origin.span(),
+                                     GateIssue::Language,
+                                     "non-capturing closure to fn coercion is experimental");
+                }
+                let closure_ty = self.fcx.infcx().closure_type(def_id_a, substs_a);
+                let a_fn_pointer = self.tcx().coerce_closure_fn_ty(&closure_ty.sig);
+                self.unify_and_identity(a_fn_pointer, b).map(|(ty, _)| {
+                    (ty, AdjustClosureFnPointer)
+                })
+            }
+            _ => self.unify_and_identity(a, b)
+        }
+    }
+
     fn coerce_unsafe_ptr(&self,
                          a: Ty<'tcx>,
                          b: Ty<'tcx>,
@@ -640,6 +678,40 @@ pub fn try<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
     })
 }
 
+/// Finds the LUB of `a` and `b`. If that cannot be decided while some
+/// type variables are unresolved, requires `b <: a` or, failing that,
+/// `a <: b` instead and returns the supertype, which is a common
+/// supertype even if not always the least one.
+pub fn lub_or_sub<'a, 'tcx>(infcx: &'a InferCtxt<'a, 'tcx>,
+                            a_is_expected: bool,
+                            trace: TypeTrace<'tcx>,
+                            a: Ty<'tcx>,
+                            b: Ty<'tcx>)
+                            -> RelateResult<'tcx, Ty<'tcx>> {
+    let lub = infcx.commit_if_ok(|_| infcx.lub(a_is_expected, trace.clone(), &a, &b));
+    match lub {
+        Err(TypeError::Ambiguous) => {
+            debug!("lub_or_sub({:?}, {:?}): ambiguous, falling back to subtyping", a, b);
+            infcx.commit_if_ok(|_| {
+                infcx.sub(!a_is_expected, trace.clone(), &b, &a).map(|ok| (ok, a))
+            }).or_else(|_| {
+                infcx.sub(a_is_expected, trace, &a, &b).map(|ok| (ok, b))
+            }).map(|(InferOk { obligations, .. }, supertype)| {
+                // FIXME(#32730) propagate obligations
+                assert!(obligations.is_empty());
+                supertype
+            })
+        }
+        result => {
+            result.map(|InferOk { value, obligations }| {
+                // FIXME(#32730) propagate obligations
+                assert!(obligations.is_empty());
+                value
+            })
+        }
+    }
+}
+
 /// Given some expressions, their known unified type and another expression,
 /// tries to unify the types, potentially inserting coercions on any of the
 /// provided expressions and returns their LUB (aka "common supertype").
@@ -743,12 +815,7 @@ pub fn try_find_lub<'a, 'b, 'tcx, E, I>(fcx: &FnCtxt<'a, 'tcx>,
 
         if !noop {
             return fcx.infcx().commit_if_ok(|_| {
-                fcx.infcx().lub(true, trace.clone(), &prev_ty, &new_ty)
-                    .map(|InferOk { value, obligations }| {
-                        // FIXME(#32730) propagate obligations
-                        assert!(obligations.is_empty());
-                        value
-                    })
+                lub_or_sub(fcx.infcx(), true, trace.clone(), prev_ty, new_ty)
             });
         }
     }
@@ -760,12 +827,7 @@ pub fn try_find_lub<'a, 'b, 'tcx, E, I>(fcx: &FnCtxt<'a, 'tcx>,
                 Err(e)
             } else {
                 fcx.infcx().commit_if_ok(|_| {
-                    fcx.infcx().lub(true, trace, &prev_ty, &new_ty)
-                        .map(|InferOk { value, obligations }| {
-                            // FIXME(#32730) propagate obligations
-                            assert!(obligations.is_empty());
-                            value
-                        })
+                    lub_or_sub(fcx.infcx(), true, trace, prev_ty, new_ty)
                 })
             }
         }
@@ -21,10 +21,11 @@ use rustc::hir;
 pub fn suptype<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>, sp: Span,
                          expected: Ty<'tcx>, actual: Ty<'tcx>) {
     let origin = TypeOrigin::Misc(sp);
-    match fcx.infcx().sub_types(false, origin, actual, expected) {
+    match fcx.infcx().deferring_projection_equality(|| {
+        fcx.infcx().sub_types(false, origin, actual, expected)
+    }) {
         Ok(InferOk { obligations, .. }) => {
-            // FIXME(#32730) propagate obligations
-            assert!(obligations.is_empty());
+            fcx.register_deferred_obligations(sp, obligations);
         },
         Err(e) => {
             fcx.infcx().report_mismatched_types(origin, expected, actual, e);
@@ -35,10 +36,11 @@ pub fn suptype<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>, sp: Span,
 pub fn eqtype<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>, sp: Span,
                         expected: Ty<'tcx>, actual: Ty<'tcx>) {
     let origin = TypeOrigin::Misc(sp);
-    match fcx.infcx().eq_types(false, origin, actual, expected) {
+    match fcx.infcx().deferring_projection_equality(|| {
+        fcx.infcx().eq_types(false, origin, actual, expected)
+    }) {
         Ok(InferOk { obligations, .. }) => {
-            // FIXME(#32730) propagate obligations
-            assert!(obligations.is_empty());
+            fcx.register_deferred_obligations(sp, obligations);
         },
         Err(e) => {
             fcx.infcx().report_mismatched_types(origin, expected, actual, e);
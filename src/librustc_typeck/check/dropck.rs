@@ -404,12 +404,51 @@ fn iterate_over_potentially_unsafe_regions_in_type<'a, 'b, 'tcx>(
     // unbounded type parameter `T`, we must resume the recursive
     // analysis on `T` (since it would be ignored by
     // type_must_outlive).
+    //
+    // RFC 1327 refines this per parameter: a Drop impl may mark
+    // individual parameters with `#[may_dangle]`,
+    //
+    // ```rust
+    // unsafe impl<#[may_dangle] T, A: Allocator> Drop for RawVec<T, A> { ... }
+    // ```
+    //
+    // in which case only the remaining parameters (here `A`) must
+    // strictly outlive the value, while the analysis resumes on the
+    // type expressions passed in for the dangling ones.
     if has_dtor_of_interest(tcx, ty) {
         debug!("iterate_over_potentially_unsafe_regions_in_type \
                 {}ty: {} - is a dtorck type!",
                (0..depth).map(|_| ' ').collect::<String>(),
                ty);
 
+        let substs = match ty.sty {
+            ty::TyEnum(_, substs) | ty::TyStruct(_, substs) => Some(substs),
+            _ => None,
+        };
+        if let (Some(substs), Some(eyepatch)) = (substs, dtor_eyepatch(tcx, ty)) {
+            let (dangling_types, dangling_regions) = eyepatch;
+            let regions = substs.regions.get_slice(subst::TypeSpace);
+            for (&r, &dangles) in regions.iter().zip(&dangling_regions) {
+                if !dangles {
+                    cx.rcx.fcx.mk_subr(infer::SubregionOrigin::SafeDestructor(cx.span),
+                                       ty::ReScope(cx.parent_scope),
+                                       r);
+                }
+            }
+            let types = substs.types.get_slice(subst::TypeSpace);
+            for (&t, &dangles) in types.iter().zip(&dangling_types) {
+                if dangles {
+                    iterate_over_potentially_unsafe_regions_in_type(cx, context, t, depth+1)?;
+                } else {
+                    regionck::type_must_outlive(cx.rcx,
+                                                infer::SubregionOrigin::SafeDestructor(cx.span),
+                                                t,
+                                                ty::ReScope(cx.parent_scope));
+                }
+            }
+            return Ok(());
+        }
+
         regionck::type_must_outlive(cx.rcx,
                                     infer::SubregionOrigin::SafeDestructor(cx.span),
                                     ty,
@@ -473,7 +512,7 @@ fn iterate_over_potentially_unsafe_regions_in_type<'a, 'b, 'tcx>(
             Ok(())
         }
 
-        ty::TyRawPtr(..) | ty::TyRef(..) | ty::TyParam(..) => {
+        ty::TyRawPtr(..) | ty::TyRef(..) | ty::TyParam(..) | ty::TyBound(..) => {
             // these always come with a witness of liveness (references
             // explicitly, pointers implicitly, parameters by the
             // caller).
@@ -496,6 +535,51 @@ fn iterate_over_potentially_unsafe_regions_in_type<'a, 'b, 'tcx>(
     }
 }
 
+/// For an ADT whose Drop impl marks some of its parameters with
+/// `#[may_dangle]`, returns which of the ADT's own type and region
+/// parameters (in that order) are dangling. Returns `None` if `ty`
+/// is not such an ADT.
+fn dtor_eyepatch<'tcx>(tcx: &TyCtxt<'tcx>,
+                       ty: ty::Ty<'tcx>) -> Option<(Vec<bool>, Vec<bool>)> {
+    let dtor_method = match ty.sty {
+        ty::TyEnum(def, _) | ty::TyStruct(def, _) => def.destructor(),
+        _ => None,
+    };
+    let impl_did = match dtor_method.and_then(|m| tcx.impl_of_method(m)) {
+        Some(impl_did) => impl_did,
+        None => return None,
+    };
+
+    // The Drop impl is as generic as the ADT itself (see
+    // `check_drop_impl`), so the substs of its self type map each ADT
+    // parameter to the impl parameter declared for it.
+    let ty::TypeScheme { generics: impl_generics, ty: impl_self_ty } =
+        tcx.lookup_item_type(impl_did);
+    let impl_substs = match impl_self_ty.sty {
+        ty::TyEnum(_, substs) | ty::TyStruct(_, substs) => substs,
+        _ => return None,
+    };
+
+    let dangling_types: Vec<bool> =
+        impl_substs.types.get_slice(subst::TypeSpace).iter().map(|t| match t.sty {
+            ty::TyParam(ref p) => impl_generics.types.get(p.space, p.idx as usize).pure_wrt_drop,
+            _ => false,
+        }).collect();
+    let dangling_regions: Vec<bool> =
+        impl_substs.regions.get_slice(subst::TypeSpace).iter().map(|r| match *r {
+            ty::ReEarlyBound(ref data) => {
+                impl_generics.regions.get(data.space, data.index as usize).pure_wrt_drop
+            }
+            _ => false,
+        }).collect();
+
+    if dangling_types.iter().chain(&dangling_regions).any(|&d| d) {
+        Some((dangling_types, dangling_regions))
+    } else {
+        None
+    }
+}
+
 fn has_dtor_of_interest<'tcx>(tcx: &TyCtxt<'tcx>,
                               ty: ty::Ty<'tcx>) -> bool {
     match ty.sty {
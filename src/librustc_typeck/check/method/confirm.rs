@@ -20,6 +20,7 @@ use rustc::ty::adjustment::{AdjustDerefRef, AutoDerefRef, AutoPtr};
 use rustc::ty::fold::TypeFoldable;
 use rustc::infer;
 use rustc::infer::{InferCtxt, TypeOrigin};
+use rustc::infer::type_variable::TypeVariableOrigin;
 use syntax::codemap::Span;
 use rustc::hir;
 
@@ -263,9 +264,11 @@ impl<'a,'tcx> ConfirmContext<'a,'tcx> {
                 // the process we will unify the transformed-self-type
                 // of the method with the actual type in order to
                 // unify some of these variables.
+                let self_ty = self.infcx().next_ty_var(
+                    TypeVariableOrigin::MiscVariable(self.span));
                 self.infcx().fresh_substs_for_trait(self.span,
                                                     &trait_def.generics,
-                                                    self.infcx().next_ty_var())
+                                                    self_ty)
             }
 
             probe::WhereClausePick(ref poly_trait_ref) => {
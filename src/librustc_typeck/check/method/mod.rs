@@ -28,7 +28,8 @@ use rustc::hir;
 pub use self::MethodError::*;
 pub use self::CandidateSource::*;
 
-pub use self::suggest::{report_error, AllTraitsVec};
+pub use self::probe::{Pick, ProbeCacheKey};
+pub use self::suggest::{report_error, AllTraitsVec, TraitItemIndex};
 
 mod confirm;
 mod probe;
@@ -206,7 +207,7 @@ pub fn lookup_in_trait_adjusted<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                               poly_trait_ref.to_predicate());
 
     // Now we want to know if this can be matched
-    let mut selcx = traits::SelectionContext::new(fcx.infcx());
+    let mut selcx = traits::SelectionContext::probing(fcx.infcx());
     if !selcx.evaluate_obligation(&obligation) {
         debug!("--> Cannot match obligation");
         return None; // Cannot be matched, no such method resolution is possible.
@@ -22,6 +22,7 @@ use rustc::ty::subst::Subst;
 use rustc::traits;
 use rustc::ty::{self, NoPreference, Ty, TyCtxt, ToPolyTraitRef, TraitRef, TypeFoldable};
 use rustc::infer::{self, InferCtxt, InferOk, TypeOrigin};
+use rustc::infer::type_variable::TypeVariableOrigin;
 use syntax::ast;
 use syntax::codemap::{Span, DUMMY_SP};
 use rustc::hir;
@@ -80,7 +81,7 @@ enum CandidateKind<'tcx> {
     WhereClauseCandidate(/* Trait */ ty::PolyTraitRef<'tcx>),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Pick<'tcx> {
     pub item: ty::ImplOrTraitItem<'tcx>,
     pub kind: PickKind<'tcx>,
@@ -114,7 +115,7 @@ pub enum PickKind<'tcx> {
 
 pub type PickResult<'tcx> = Result<Pick<'tcx>, MethodError<'tcx>>;
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
 pub enum Mode {
     // An expression of the form `receiver.method_name(...)`.
     // Autoderefs are performed on `receiver`, lookup is done based on the
@@ -126,6 +127,16 @@ pub enum Mode {
     Path
 }
 
+/// The inputs a probe depends on, used to reuse picks for repeated
+/// method calls within a body (see `FnCtxt::method_probe_cache`).
+#[derive(PartialEq, Eq, Hash)]
+pub struct ProbeCacheKey<'tcx> {
+    self_ty: Ty<'tcx>,
+    item_name: ast::Name,
+    mode: Mode,
+    traits_in_scope: Vec<DefId>,
+}
+
 pub fn probe<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                        span: Span,
                        mode: Mode,
@@ -139,6 +150,31 @@ pub fn probe<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
            item_name,
            scope_expr_id);
 
+    // A pick only depends on the receiver type, the item name, the mode
+    // and the traits in scope, so builder-style code that calls the
+    // same methods on the same types over and over can skip the
+    // autoderef and candidate assembly below. Receivers that still
+    // contain inference variables are never cached, as their pick may
+    // change once those variables are resolved; once they are, the
+    // resolved type is a different key.
+    let resolved_self_ty = fcx.infcx().resolve_type_vars_if_possible(&self_ty);
+    let cache_key = if resolved_self_ty.has_infer_types() {
+        None
+    } else {
+        Some(ProbeCacheKey {
+            self_ty: resolved_self_ty,
+            item_name: item_name,
+            mode: mode,
+            traits_in_scope: fcx.ccx.trait_map.get(&scope_expr_id).cloned().unwrap_or(vec![]),
+        })
+    };
+    if let Some(ref cache_key) = cache_key {
+        if let Some(pick) = fcx.method_probe_cache.borrow().get(cache_key) {
+            debug!("probe: reusing cached pick {:?}", pick);
+            return Ok(pick.clone());
+        }
+    }
+
     // FIXME(#18741) -- right now, creating the steps involves evaluating the
     // `*` operator, which registers obligations that then escape into
     // the global fulfillment context and thus has global
@@ -181,7 +217,7 @@ pub fn probe<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
 
     // this creates one big transaction so that all type variables etc
     // that we create during the probe process are removed later
-    fcx.infcx().probe(|_| {
+    let result = fcx.infcx().probe(|_| {
         let mut probe_cx = ProbeContext::new(fcx,
                                              span,
                                              mode,
@@ -191,7 +227,12 @@ pub fn probe<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         probe_cx.assemble_inherent_candidates();
         probe_cx.assemble_extension_candidates_for_traits_in_scope(scope_expr_id)?;
         probe_cx.pick()
-    })
+    });
+
+    if let (Some(cache_key), &Ok(ref pick)) = (cache_key, &result) {
+        fcx.method_probe_cache.borrow_mut().insert(cache_key, pick.clone());
+    }
+    result
 }
 
 fn create_steps<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
@@ -384,14 +425,8 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
     }
 
     fn assemble_inherent_impl_candidates_for_type(&mut self, def_id: DefId) {
-        // Read the inherent implementation candidates for this type from the
-        // metadata if necessary.
-        self.tcx().populate_inherent_implementations_for_type_if_necessary(def_id);
-
-        if let Some(impl_infos) = self.tcx().inherent_impls.borrow().get(&def_id) {
-            for &impl_def_id in impl_infos.iter() {
-                self.assemble_inherent_impl_probe(impl_def_id);
-            }
+        for &impl_def_id in self.tcx().inherent_impls_of(def_id).iter() {
+            self.assemble_inherent_impl_probe(impl_def_id);
         }
     }
 
@@ -426,7 +461,7 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
         // We can't use normalize_associated_types_in as it will pollute the
         // fcx's fulfillment context after this probe is over.
         let cause = traits::ObligationCause::misc(self.span, self.fcx.body_id);
-        let mut selcx = &mut traits::SelectionContext::new(self.fcx.infcx());
+        let mut selcx = &mut traits::SelectionContext::probing(self.fcx.infcx());
         let traits::Normalized { value: xform_self_ty, obligations } =
             traits::normalize(selcx, cause, &xform_self_ty);
         debug!("assemble_inherent_impl_probe: xform_self_ty = {:?}",
@@ -486,6 +521,7 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
                             _ => None
                         }
                     }
+                    ty::Predicate::NegativeTrait(..) |
                     ty::Predicate::Equate(..) |
                     ty::Predicate::Projection(..) |
                     ty::Predicate::RegionOutlives(..) |
@@ -670,7 +706,7 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
             // as it will pollute the fcx's fulfillment context after this probe
             // is over.
             let cause = traits::ObligationCause::misc(self.span, self.fcx.body_id);
-            let mut selcx = &mut traits::SelectionContext::new(self.fcx.infcx());
+            let mut selcx = &mut traits::SelectionContext::probing(self.fcx.infcx());
             let traits::Normalized { value: xform_self_ty, obligations } =
                 traits::normalize(selcx, cause, &xform_self_ty);
 
@@ -799,7 +835,8 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
                        projection_trait_ref,
                        bound);
 
-                if self.infcx().can_equate(&step.self_ty, &bound.self_ty()).is_ok() {
+                if self.infcx().can_eq(self.infcx().param_env(), &step.self_ty, &bound.self_ty())
+                       .is_ok() {
                     let xform_self_ty = self.xform_self_ty(&item,
                                                            bound.self_ty(),
                                                            bound.substs);
@@ -1074,7 +1111,7 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
                 }
             };
 
-            let selcx = &mut traits::SelectionContext::new(self.infcx());
+            let selcx = &mut traits::SelectionContext::probing(self.infcx());
             let cause = traits::ObligationCause::misc(self.span, self.fcx.body_id);
 
             // Check whether the impl imposes obligations we have to worry about.
@@ -1261,7 +1298,8 @@ impl<'a,'tcx> ProbeContext<'a,'tcx> {
 
         let type_vars =
             impl_pty.generics.types.map(
-                |_| self.infcx().next_ty_var());
+                |def| self.infcx().next_ty_var(
+                    TypeVariableOrigin::TypeParameterDefinition(self.span, def.name)));
 
         let region_placeholders =
             impl_pty.generics.regions.map(
@@ -16,20 +16,23 @@ use CrateCtxt;
 use astconv::AstConv;
 use check::{self, FnCtxt, UnresolvedTypeAction, autoderef};
 use rustc::hir::map as hir_map;
-use rustc::ty::{self, Ty, ToPolyTraitRef, ToPredicate, TypeFoldable};
+use rustc::ty::{self, Ty, TyCtxt, ToPolyTraitRef, ToPredicate, TypeFoldable};
 use middle::cstore::{self, CrateStore};
 use hir::def::Def;
 use hir::def_id::DefId;
 use middle::lang_items::FnOnceTraitLangItem;
-use rustc::ty::subst::Substs;
+use rustc::ty::subst::{self, Substs};
 use rustc::ty::LvaluePreference;
+use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::traits::{Obligation, SelectionContext};
-use util::nodemap::{FnvHashSet};
+use util::nodemap::{FnvHashMap, FnvHashSet};
 
 
 use syntax::ast;
 use syntax::codemap::Span;
+use syntax::parse::token;
 use syntax::errors::DiagnosticBuilder;
+use syntax::util::lev_distance::find_best_match_for_name;
 use rustc::hir::print as pprust;
 use rustc::hir;
 use rustc::hir::Expr_;
@@ -60,7 +63,8 @@ fn is_fn_ty<'a, 'tcx>(ty: &Ty<'tcx>, fcx: &FnCtxt<'a, 'tcx>, span: Span) -> bool
                                                   |ty, _| {
                     infcx.probe(|_| {
                         let fn_once_substs =
-                            Substs::new_trait(vec![infcx.next_ty_var()],
+                            Substs::new_trait(vec![infcx.next_ty_var(
+                                                  TypeVariableOrigin::MiscVariable(span))],
                                               Vec::new(),
                                               ty);
                         let trait_ref =
@@ -171,6 +175,32 @@ pub fn report_error<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                 }
             }
 
+            // The item may exist in an inherent impl for another
+            // instantiation of the receiver type, e.g. `impl Foo<i32>`
+            // when the receiver is a `Foo<u8>`.
+            if unsatisfied_predicates.is_empty() {
+                if let Some(adt_def) = fcx.resolve_type_vars_if_possible(rcvr_ty).ty_adt_def() {
+                    let impl_tys = cx.inherent_impls_of(adt_def.did).iter()
+                        .filter(|&&impl_did| {
+                            !static_sources.contains(&CandidateSource::ImplSource(impl_did)) &&
+                                impl_item(cx, impl_did, item_name).is_some()
+                        })
+                        .map(|&impl_did| format!("`{}`", cx.lookup_item_type(impl_did).ty))
+                        .collect::<Vec<_>>();
+                    if !impl_tys.is_empty() {
+                        err.fileline_note(
+                            span,
+                            &format!("the {} was found for {}",
+                                     if mode == Mode::MethodCall { "method" }
+                                     else { "associated item" },
+                                     impl_tys.join(", ")));
+                    } else if let Some(name) = similar_inherent_item(cx, adt_def.did,
+                                                                     item_name) {
+                        err.fileline_help(span, &format!("did you mean `{}`?", name));
+                    }
+                }
+            }
+
             if !static_sources.is_empty() {
                 err.fileline_note(
                     span,
@@ -181,18 +211,21 @@ pub fn report_error<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             }
 
             if !unsatisfied_predicates.is_empty() {
-                let bound_list = unsatisfied_predicates.iter()
-                    .map(|p| format!("`{} : {}`",
-                                     p.self_ty(),
-                                     p))
-                    .collect::<Vec<_>>()
-                    .join(", ");
+                let mut bounds = vec![];
+                for p in &unsatisfied_predicates {
+                    let bound = format!("`{}: {}`", p.self_ty(), p);
+                    if !bounds.contains(&bound) {
+                        bounds.push(bound);
+                    }
+                }
                 err.fileline_note(
                     span,
                     &format!("the method `{}` exists but the \
                              following trait bounds were not satisfied: {}",
                              item_name,
-                             bound_list));
+                             bounds.join(", ")));
+
+                suggest_restricting_type_params(fcx, &mut err, &unsatisfied_predicates);
             }
 
             suggest_traits_to_import(fcx, &mut err, span, rcvr_ty, item_name,
@@ -283,6 +316,62 @@ pub fn report_error<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
 }
 
 
+/// Suggests adding the unsatisfied bounds on type parameters of the
+/// enclosing item to the declarations of those parameters.
+fn suggest_restricting_type_params<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
+                                             err: &mut DiagnosticBuilder,
+                                             predicates: &[ty::TraitRef<'tcx>]) {
+    let tcx = fcx.tcx();
+
+    // The bounds wanted on each type parameter, in the order found.
+    let mut params: Vec<(ty::ParamTy, Vec<String>)> = vec![];
+    for p in predicates {
+        let param = match p.self_ty().sty {
+            // `Self` is declared by the trait, not in a list of
+            // generic parameters.
+            ty::TyParam(param) if param.space != subst::SelfSpace => param,
+            _ => continue,
+        };
+        let bound = p.to_string();
+        match params.iter().position(|&(p, _)| p == param) {
+            Some(i) => {
+                if !params[i].1.contains(&bound) {
+                    params[i].1.push(bound);
+                }
+            }
+            None => params.push((param, vec![bound])),
+        }
+    }
+    if params.is_empty() {
+        return;
+    }
+
+    let item_id = tcx.map.get_parent(fcx.body_id);
+    let generics = tcx.lookup_item_type(tcx.map.local_def_id(item_id)).generics;
+    for (param, bounds) in params {
+        let ty_param = generics.types.opt_get(param.space, param.idx as usize)
+            .and_then(|def| tcx.map.as_local_node_id(def.def_id))
+            .and_then(|id| match tcx.map.find(id) {
+                Some(hir_map::NodeTyParam(ty_param)) => Some(ty_param),
+                _ => None,
+            });
+        let ty_param = match ty_param {
+            Some(ty_param) => ty_param,
+            None => continue,
+        };
+        let bounds = bounds.join(" + ");
+        if ty_param.bounds.is_empty() {
+            err.span_suggestion(ty_param.span,
+                                &format!("consider restricting `{}`:", param.name),
+                                format!("{}: {}", param.name, bounds));
+        } else {
+            err.span_help(ty_param.span,
+                          &format!("consider adding `{}` to the bounds of `{}`",
+                                   bounds, param.name));
+        }
+    }
+}
+
 pub type AllTraitsVec = Vec<TraitInfo>;
 
 fn suggest_traits_to_import<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
@@ -322,7 +411,8 @@ fn suggest_traits_to_import<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
     // there's no implemented traits, so lets suggest some traits to
     // implement, by finding ones that have the item name, and are
     // legal to implement.
-    let mut candidates = all_traits(fcx.ccx)
+    let mut candidates = traits_defining_item(fcx.ccx, item_name)
+        .into_iter()
         .filter(|info| {
             // we approximate the coherence rules to only suggest
             // traits that are legal to implement by requiring that
@@ -330,8 +420,7 @@ fn suggest_traits_to_import<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             // this isn't perfect (that is, there are cases when
             // implementing a trait would be legal but is rejected
             // here).
-            (type_is_local || info.def_id.is_local())
-                && trait_item(tcx, info.def_id, item_name).is_some()
+            type_is_local || info.def_id.is_local()
         })
         .collect::<Vec<_>>();
 
@@ -340,26 +429,47 @@ fn suggest_traits_to_import<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         candidates.sort_by(|a, b| a.cmp(b).reverse());
         candidates.dedup();
 
-        // FIXME #21673 this help message could be tuned to the case
-        // of a type parameter: suggest adding a trait bound rather
-        // than implementing.
+        // a type parameter can't implement anything, but the caller
+        // may have meant to bound it by one of the traits
+        let param_ty = match fcx.resolve_type_vars_if_possible(rcvr_ty).sty {
+            ty::TyParam(ref param_ty) => Some(param_ty.name),
+            _ => None
+        };
+
         let msg = format!(
             "items from traits can only be used if the trait is implemented and in scope; \
              the following {traits_define} an item `{name}`, \
-             perhaps you need to implement {one_of_them}:",
+             perhaps you need to {action} {one_of_them}:",
             traits_define = if candidates.len() == 1 {"trait defines"} else {"traits define"},
+            action = if param_ty.is_some() {"restrict type parameter by"} else {"implement"},
             one_of_them = if candidates.len() == 1 {"it"} else {"one of them"},
             name = item_name);
 
         err.fileline_help(span, &msg[..]);
 
         for (i, trait_info) in candidates.iter().enumerate() {
-            err.fileline_help(span,
-                              &format!("candidate #{}: `{}`",
-                                        i + 1,
-                                        fcx.tcx().item_path_str(trait_info.def_id)));
+            let trait_path = tcx.item_path_str(trait_info.def_id);
+            let candidate = match param_ty {
+                Some(param) => format!("{}: {}", param, trait_path),
+                None => trait_path
+            };
+            err.fileline_help(span, &format!("candidate #{}: `{}`", i + 1, candidate));
+        }
+    }
+}
+
+/// Finds the inherent item of the type `adt_did` whose name is
+/// closest to `item_name`, if any is close enough to be a likely typo.
+fn similar_inherent_item(tcx: &TyCtxt, adt_did: DefId, item_name: ast::Name)
+                         -> Option<token::InternedString> {
+    let mut names = vec![];
+    for &impl_did in tcx.inherent_impls_of(adt_did).iter() {
+        let impl_items = tcx.impl_items.borrow().get(&impl_did).unwrap().clone();
+        for item in impl_items {
+            names.push(tcx.impl_or_trait_item(item.def_id()).name());
         }
     }
+    find_best_match_for_name(names.iter(), &item_name.as_str(), None)
 }
 
 /// Checks whether there is a local type somewhere in the chain of
@@ -433,6 +543,27 @@ impl Ord for TraitInfo {
     }
 }
 
+pub type TraitItemIndex = FnvHashMap<ast::Name, Vec<DefId>>;
+
+/// Retrieve the traits, in this crate or any dependent crate, that
+/// define an item named `item_name`.
+fn traits_defining_item(ccx: &CrateCtxt, item_name: ast::Name) -> Vec<TraitInfo> {
+    if ccx.trait_item_index.borrow().is_none() {
+        let mut index = FnvHashMap();
+        for info in all_traits(ccx) {
+            for item in ccx.tcx.trait_items(info.def_id).iter() {
+                index.entry(item.name()).or_insert_with(Vec::new).push(info.def_id);
+            }
+        }
+        *ccx.trait_item_index.borrow_mut() = Some(index);
+    }
+
+    let index = ccx.trait_item_index.borrow();
+    index.as_ref().unwrap().get(&item_name).map_or(vec![], |traits| {
+        traits.iter().map(|&def_id| TraitInfo::new(def_id)).collect()
+    })
+}
+
 /// Retrieve all traits in this crate and any dependent crates.
 pub fn all_traits<'a>(ccx: &'a CrateCtxt) -> AllTraits<'a> {
     if ccx.all_traits.borrow().is_none() {
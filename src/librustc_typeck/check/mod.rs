@@ -88,7 +88,9 @@ use middle::astconv_util::prohibit_type_params;
 use middle::cstore::LOCAL_CRATE;
 use hir::def::{self, Def};
 use hir::def_id::DefId;
+use hir::map as hir_map;
 use rustc::infer::{self, InferOk, TypeOrigin, TypeTrace, type_variable};
+use rustc::infer::type_variable::TypeVariableOrigin;
 use hir::pat_util::{self, pat_id_map};
 use rustc::ty::subst::{self, Subst, Substs, VecPerParamSpace, ParamSpace};
 use rustc::traits::{self, report_fulfillment_errors, ProjectionMode};
@@ -297,6 +299,10 @@ pub struct FnCtxt<'a, 'tcx: 'a> {
     inh: &'a Inherited<'a, 'tcx>,
 
     ccx: &'a CrateCtxt<'a, 'tcx>,
+
+    // Successful method probes in this body, keyed by everything the
+    // pick depends on. See `method::probe::probe`.
+    method_probe_cache: RefCell<FnvHashMap<method::ProbeCacheKey<'tcx>, method::Pick<'tcx>>>,
 }
 
 impl<'a, 'tcx> Inherited<'a, 'tcx> {
@@ -344,7 +350,8 @@ pub fn blank_fn_ctxt<'a, 'tcx>(ccx: &'a CrateCtxt<'a, 'tcx>,
         ret_ty: rty,
         ps: RefCell::new(UnsafetyState::function(hir::Unsafety::Normal, 0)),
         inh: inh,
-        ccx: ccx
+        ccx: ccx,
+        method_probe_cache: RefCell::new(FnvHashMap()),
     }
 }
 
@@ -361,6 +368,11 @@ struct CheckItemTypesVisitor<'a, 'tcx: 'a> { ccx: &'a CrateCtxt<'a, 'tcx> }
 struct CheckItemBodiesVisitor<'a, 'tcx: 'a> { ccx: &'a CrateCtxt<'a, 'tcx> }
 
 impl<'a, 'tcx> Visitor<'tcx> for CheckItemTypesVisitor<'a, 'tcx> {
+    fn visit_body_id(&mut self, id: hir::BodyId) {
+        // Array lengths in the types within bodies are checked too.
+        self.ccx.tcx.map.read_body(id);
+    }
+
     fn visit_item(&mut self, i: &'tcx hir::Item) {
         check_item_type(self.ccx, i);
         intravisit::walk_item(self, i);
@@ -386,8 +398,10 @@ impl<'a, 'tcx> Visitor<'tcx> for CheckItemBodiesVisitor<'a, 'tcx> {
 
 pub fn check_wf_new(ccx: &CrateCtxt) -> CompileResult {
     ccx.tcx.sess.track_errors(|| {
-        let mut visit = wfcheck::CheckTypeWellFormedVisitor::new(ccx);
-        ccx.tcx.visit_all_items_in_krate(DepNode::WfCheck, &mut visit);
+        let krate = ccx.tcx.dep_graph.with_ignore(|| ccx.tcx.map.krate());
+        for &item_id in krate.items.keys() {
+            wfcheck::check_item_well_formed(ccx, ccx.tcx.map.local_def_id(item_id));
+        }
     })
 }
 
@@ -470,11 +484,11 @@ struct GatherLocalsVisitor<'a, 'tcx: 'a> {
 }
 
 impl<'a, 'tcx> GatherLocalsVisitor<'a, 'tcx> {
-    fn assign(&mut self, _span: Span, nid: ast::NodeId, ty_opt: Option<Ty<'tcx>>) -> Ty<'tcx> {
+    fn assign(&mut self, span: Span, nid: ast::NodeId, ty_opt: Option<Ty<'tcx>>) -> Ty<'tcx> {
         match ty_opt {
             None => {
                 // infer the variable's type
-                let var_ty = self.fcx.infcx().next_ty_var();
+                let var_ty = self.fcx.infcx().next_ty_var(TypeVariableOrigin::TypeInference(span));
                 self.fcx.inh.locals.borrow_mut().insert(nid, var_ty);
                 var_ty
             }
@@ -585,7 +599,8 @@ fn check_fn<'a, 'tcx>(ccx: &'a CrateCtxt<'a, 'tcx>,
         ret_ty: ret_ty,
         ps: RefCell::new(UnsafetyState::function(unsafety, unsafety_id)),
         inh: inherited,
-        ccx: ccx
+        ccx: ccx,
+        method_probe_cache: RefCell::new(FnvHashMap()),
     };
 
     if let ty::FnConverging(ret_ty) = ret_ty {
@@ -596,6 +611,14 @@ fn check_fn<'a, 'tcx>(ccx: &'a CrateCtxt<'a, 'tcx>,
 
     inherited.tables.borrow_mut().liberated_fn_sigs.insert(fn_id, fn_sig.clone());
 
+    // With `unsized_locals`, a parameter bound as a whole may have an
+    // unsized type, the value being left in place by the caller. Closures
+    // are excluded, as their arguments are passed as a tuple.
+    let unsized_params = tcx.sess.features.borrow().unsized_locals && match tcx.map.find(fn_id) {
+        Some(hir_map::NodeExpr(_)) => false,
+        _ => true
+    };
+
     {
         let mut visit = GatherLocalsVisitor { fcx: &fcx, };
 
@@ -613,10 +636,16 @@ fn check_fn<'a, 'tcx>(ccx: &'a CrateCtxt<'a, 'tcx>,
             pat_util::pat_bindings(
                 &tcx.def_map,
                 &input.pat,
-                |_bm, pat_id, sp, _path| {
+                |bm, pat_id, sp, _path| {
                     let var_ty = visit.assign(sp, pat_id, None);
-                    fcx.require_type_is_sized(var_ty, sp,
-                                              traits::VariableType(pat_id));
+                    let whole_arg = pat_id == input.pat.id && match bm {
+                        hir::BindByValue(_) => true,
+                        hir::BindByRef(_) => false
+                    };
+                    if !(unsized_params && whole_arg) {
+                        fcx.require_type_is_sized(var_ty, sp,
+                                                  traits::VariableType(pat_id));
+                    }
                 });
 
             // Check the pattern.
@@ -659,8 +688,8 @@ pub fn check_item_type<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
     let _indenter = indenter();
     match it.node {
       // Consts can play a role in type-checking, so they are included here.
-      hir::ItemStatic(_, _, ref e) |
-      hir::ItemConst(_, ref e) => check_const(ccx, it.span, &e, it.id),
+      hir::ItemStatic(..) |
+      hir::ItemConst(..) => check_body(ccx, ccx.tcx.map.maybe_body_owned_by(it.id).unwrap()),
       hir::ItemEnum(ref enum_definition, _) => {
         check_enum_variants(ccx,
                             it.span,
@@ -729,55 +758,90 @@ pub fn check_item_body<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, it: &'tcx hir::Item) {
            ccx.tcx.item_path_str(ccx.tcx.map.local_def_id(it.id)));
     let _indenter = indenter();
     match it.node {
-      hir::ItemFn(ref decl, _, _, _, _, ref body) => {
-        let fn_pty = ccx.tcx.lookup_item_type(ccx.tcx.map.local_def_id(it.id));
-        let param_env = ParameterEnvironment::for_item(ccx.tcx, it.id);
-        check_bare_fn(ccx, &decl, &body, it.id, it.span, fn_pty.ty, param_env);
+      hir::ItemFn(..) => {
+        check_body(ccx, ccx.tcx.map.maybe_body_owned_by(it.id).unwrap());
       }
       hir::ItemImpl(_, _, _, _, _, ref impl_items) => {
         debug!("ItemImpl {} with id {}", it.name, it.id);
 
-        let impl_pty = ccx.tcx.lookup_item_type(ccx.tcx.map.local_def_id(it.id));
-
         for impl_item in impl_items {
-            match impl_item.node {
-                hir::ImplItemKind::Const(_, ref expr) => {
-                    check_const(ccx, impl_item.span, &expr, impl_item.id)
-                }
-                hir::ImplItemKind::Method(ref sig, ref body) => {
-                    check_method_body(ccx, &impl_pty.generics, sig, body,
-                                      impl_item.id, impl_item.span);
-                }
-                hir::ImplItemKind::Type(_) => {
-                    // Nothing to do here.
-                }
+            if let Some(body_id) = ccx.tcx.map.maybe_body_owned_by(impl_item.id) {
+                check_body(ccx, body_id);
             }
         }
       }
       hir::ItemTrait(_, _, _, ref trait_items) => {
-        let trait_def = ccx.tcx.lookup_trait_def(ccx.tcx.map.local_def_id(it.id));
         for trait_item in trait_items {
+            if let hir::MethodTraitItem(ref sig, _) = trait_item.node {
+                check_trait_fn_not_const(ccx, trait_item.span, sig.constness);
+            }
+            if let Some(body_id) = ccx.tcx.map.maybe_body_owned_by(trait_item.id) {
+                check_body(ccx, body_id);
+            }
+        }
+      }
+      _ => {/* nothing to do */ }
+    }
+}
+
+/// Type-checks a single body -- the body of a fn or method, or the
+/// initializer of a const, static or associated const -- against the
+/// signature of its owner, independently of any other body.
+pub fn check_body<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>, body_id: hir::BodyId) {
+    let tcx = ccx.tcx;
+    let owner = tcx.map.body_owner(body_id);
+    debug!("check_body(body_id={:?}, owner={})",
+           body_id,
+           tcx.item_path_str(tcx.map.local_def_id(owner)));
+
+    // The body is reached through its owner below, which only records a
+    // read of the owner's `Hir`.
+    tcx.map.read_body(body_id);
+
+    match tcx.map.get(owner) {
+        hir::map::NodeItem(item) => {
+            match item.node {
+                hir::ItemFn(ref decl, _, _, _, _, ref body) => {
+                    let fn_pty = tcx.lookup_item_type(tcx.map.local_def_id(owner));
+                    let param_env = ParameterEnvironment::for_item(tcx, owner);
+                    check_bare_fn(ccx, &decl, &body, owner, item.span, fn_pty.ty, param_env);
+                }
+                hir::ItemStatic(_, _, ref expr) |
+                hir::ItemConst(_, ref expr) => {
+                    check_const(ccx, item.span, &expr, owner)
+                }
+                _ => span_bug!(item.span, "check_body: {:?} owns no body", item)
+            }
+        }
+        hir::map::NodeTraitItem(trait_item) => {
             match trait_item.node {
                 hir::ConstTraitItem(_, Some(ref expr)) => {
-                    check_const(ccx, trait_item.span, &expr, trait_item.id)
+                    check_const(ccx, trait_item.span, &expr, owner)
                 }
                 hir::MethodTraitItem(ref sig, Some(ref body)) => {
-                    check_trait_fn_not_const(ccx, trait_item.span, sig.constness);
-
+                    let trait_def = tcx.lookup_trait_def(tcx.map.get_parent_did(owner));
                     check_method_body(ccx, &trait_def.generics, sig, body,
-                                      trait_item.id, trait_item.span);
+                                      owner, trait_item.span);
                 }
-                hir::MethodTraitItem(ref sig, None) => {
-                    check_trait_fn_not_const(ccx, trait_item.span, sig.constness);
+                _ => span_bug!(trait_item.span, "check_body: {:?} owns no body", trait_item)
+            }
+        }
+        hir::map::NodeImplItem(impl_item) => {
+            match impl_item.node {
+                hir::ImplItemKind::Const(_, ref expr) => {
+                    check_const(ccx, impl_item.span, &expr, owner)
                 }
-                hir::ConstTraitItem(_, None) |
-                hir::TypeTraitItem(..) => {
-                    // Nothing to do.
+                hir::ImplItemKind::Method(ref sig, ref body) => {
+                    let impl_pty = tcx.lookup_item_type(tcx.map.get_parent_did(owner));
+                    check_method_body(ccx, &impl_pty.generics, sig, body,
+                                      owner, impl_item.span);
+                }
+                hir::ImplItemKind::Type(_) => {
+                    span_bug!(impl_item.span, "check_body: {:?} owns no body", impl_item)
                 }
             }
         }
-      }
-      _ => {/* nothing to do */ }
+        node => bug!("check_body: unexpected owner {:?}", node)
     }
 }
 
@@ -1198,7 +1262,9 @@ impl<'a, 'tcx> AstConv<'tcx> for FnCtxt<'a, 'tcx> {
                 ty_param_def: Option<ty::TypeParameterDef<'tcx>>,
                 substs: Option<&mut subst::Substs<'tcx>>,
                 space: Option<subst::ParamSpace>,
-                span: Span) -> Ty<'tcx> {
+                origin: TypeVariableOrigin) -> Ty<'tcx> {
+        let span = origin.span();
+
         // Grab the default doing subsitution
         let default = ty_param_def.and_then(|def| {
             def.default.map(|ty| type_variable::Default {
@@ -1208,7 +1274,7 @@ impl<'a, 'tcx> AstConv<'tcx> for FnCtxt<'a, 'tcx> {
             })
         });
 
-        let ty_var = self.infcx().next_ty_var_with_default(default);
+        let ty_var = self.infcx().next_ty_var_with_default(default, origin);
 
         // Finally we add the type variable to the substs
         match substs {
@@ -1229,16 +1295,7 @@ impl<'a, 'tcx> AstConv<'tcx> for FnCtxt<'a, 'tcx> {
                 infer::LateBoundRegionConversionTime::AssocTypeProjection(item_name),
                 &poly_trait_ref);
 
-        self.normalize_associated_type(span, trait_ref, item_name)
-    }
-
-    fn projected_ty(&self,
-                    span: Span,
-                    trait_ref: ty::TraitRef<'tcx>,
-                    item_name: ast::Name)
-                    -> Ty<'tcx>
-    {
-        self.normalize_associated_type(span, trait_ref, item_name)
+        self.projected_ty(span, trait_ref, item_name)
     }
 }
 
@@ -1405,26 +1462,6 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         self.inh.normalize_associated_types_in(span, self.body_id, value)
     }
 
-    fn normalize_associated_type(&self,
-                                 span: Span,
-                                 trait_ref: ty::TraitRef<'tcx>,
-                                 item_name: ast::Name)
-                                 -> Ty<'tcx>
-    {
-        let cause = traits::ObligationCause::new(span,
-                                                 self.body_id,
-                                                 traits::ObligationCauseCode::MiscObligation);
-        self.inh
-            .fulfillment_cx
-            .borrow_mut()
-            .normalize_projection_type(self.infcx(),
-                                       ty::ProjectionTy {
-                                           trait_ref: trait_ref,
-                                           item_name: item_name,
-                                       },
-                                       cause)
-    }
-
     /// Instantiates the type in `did` with the generics in `path` and returns
     /// it (registering the necessary trait obligations along the way).
     ///
@@ -1458,6 +1495,26 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         self.instantiate_type_scheme(path.span, &substs, &type_scheme.ty)
     }
 
+    /// Returns the type `Self` stands for inside the impl that `def` comes
+    /// from, if that type is a struct. The type is expressed in the impl's
+    /// own type parameters, so nothing about it is left to inference.
+    pub fn self_struct_ty(&self, def: Def, path: &hir::Path)
+                          -> Option<(Ty<'tcx>, ty::AdtDef<'tcx>, &'tcx Substs<'tcx>)>
+    {
+        if let Def::SelfTy(_, Some(_)) = def {
+            let ty = astconv::finish_resolving_def_to_ty(self, self, path.span,
+                                                         PathParamMode::Optional,
+                                                         &def,
+                                                         None,
+                                                         &path.segments,
+                                                         &[]);
+            if let ty::TyStruct(adt, substs) = ty.sty {
+                return Some((ty, adt, substs));
+            }
+        }
+        None
+    }
+
     /// Return the dict-like variant corresponding to a given `Def`.
     pub fn def_struct_variant(&self,
                               def: Def,
@@ -1497,6 +1554,38 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         self.write_ty(node_id, self.tcx().types.err);
     }
 
+    /// Recovery point run after an expression has been checked. If the
+    /// type of one of the operands of `expr` references an error and
+    /// the type of `expr` itself is still an unresolved inference
+    /// variable, that variable is unified with `TyError`. Everything
+    /// that is later checked against it then goes through silently, so
+    /// that only independent problems are reported rather than a
+    /// cascade stemming from the first one. Errors anywhere else, such
+    /// as in the statements of a block, leave the type alone.
+    fn recover_from_errors_in(&self, expr: &hir::Expr) {
+        let ty = self.infcx().shallow_resolve(self.expr_ty(expr));
+        if !ty.is_ty_var() {
+            return;
+        }
+
+        let operand_has_error = expr_operands(expr).into_iter().any(|operand| {
+            let operand_ty = self.inh.tables.borrow().node_types.get(&operand.id).cloned();
+            operand_ty.map_or(false, |operand_ty| {
+                self.infcx().resolve_type_vars_if_possible(&operand_ty).references_error()
+            })
+        });
+        if operand_has_error {
+            debug!("recover_from_errors_in: expr {} has type {:?} after an error",
+                   expr.id, ty);
+            // Relating a type variable to `TyError` cannot fail, and any
+            // obligations it produces would only mention the error type.
+            let _ = self.infcx().eq_types(false,
+                                          TypeOrigin::Misc(expr.span),
+                                          ty,
+                                          self.tcx().types.err);
+        }
+    }
+
     pub fn require_type_meets(&self,
                               ty: Ty<'tcx>,
                               span: Span,
@@ -1554,8 +1643,24 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             .register_predicate_obligation(self.infcx(), obligation);
     }
 
+    /// Registers the obligations that a type relation deferred (see
+    /// `InferCtxt::deferring_projection_equality`), attributing them to
+    /// this body.
+    pub fn register_deferred_obligations(&self,
+                                         span: Span,
+                                         obligations: traits::PredicateObligations<'tcx>)
+    {
+        for obligation in obligations {
+            let cause = traits::ObligationCause::misc(span, self.body_id);
+            self.register_predicate(traits::Obligation::new(cause, obligation.predicate));
+        }
+    }
+
+    /// Converts a type written in the function body, normalizing the
+    /// associated types that `AstConv` leaves as projections.
     pub fn to_ty(&self, ast_t: &hir::Ty) -> Ty<'tcx> {
         let t = ast_ty_to_ty(self, self, ast_t);
+        let t = self.normalize_associated_types_in(ast_t.span, &t);
         self.register_wf_obligation(t, ast_t.span, traits::MiscObligation);
         t
     }
@@ -1675,8 +1780,7 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                                       region: ty::Region,
                                       cause: traits::ObligationCause<'tcx>)
     {
-        let mut fulfillment_cx = self.inh.fulfillment_cx.borrow_mut();
-        fulfillment_cx.register_region_obligation(ty, region, cause);
+        self.infcx().register_region_obligation(ty, region, cause);
     }
 
     /// Registers an obligation for checking later, during regionck, that the type `ty` must
@@ -1767,23 +1871,56 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
     }
 
     /// Apply "fallbacks" to some types
-    /// ! gets replaced with (), unconstrained ints with i32, and unconstrained floats with f64.
+    /// ! gets replaced with (), unconstrained ints with i32, and unconstrained floats with f64,
+    /// unless the inference context was given a different `FallbackPolicy`.
     fn default_type_parameters(&self) {
-        use rustc::ty::error::UnconstrainedNumeric::Neither;
-        use rustc::ty::error::UnconstrainedNumeric::{UnconstrainedInt, UnconstrainedFloat};
+        self.lint_diverging_fallback();
         for ty in &self.infcx().unsolved_variables() {
-            let resolved = self.infcx().resolve_type_vars_if_possible(ty);
-            if self.infcx().type_var_diverges(resolved) {
-                demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().mk_nil());
-            } else {
-                match self.infcx().type_is_unconstrained_numeric(resolved) {
-                    UnconstrainedInt => {
-                        demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().types.i32)
-                    },
-                    UnconstrainedFloat => {
-                        demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().types.f64)
+            self.infcx().fallback_if_possible(TypeOrigin::Misc(codemap::DUMMY_SP), *ty);
+        }
+    }
+
+    /// Warns about trait obligations on a diverging type variable that
+    /// is about to fall back to `()`, such as the type of
+    /// `match x { Ok(v) => v, Err(e) => return Err(e) }` when only
+    /// `Trait::method()` produces `x`. The impl selected for `()` is
+    /// not the one that will be selected once diverging variables
+    /// fall back to `!`.
+    fn lint_diverging_fallback(&self) {
+        let diverging: Vec<_> = self.infcx().unsolved_variables().into_iter().filter(|&ty| {
+            self.infcx().type_var_diverges(ty)
+        }).collect();
+        if diverging.is_empty() {
+            return;
+        }
+
+        for pending in self.inh.fulfillment_cx.borrow().pending_obligations() {
+            let obligation = &pending.obligation;
+            let trait_ref = match obligation.predicate {
+                ty::Predicate::Trait(ref data) => data.skip_binder(),
+                _ => continue
+            };
+            let self_ty = self.infcx().shallow_resolve(trait_ref.self_ty());
+            if let ty::TyInfer(ty::TyVar(_)) = self_ty.sty {
+                // The self type may only be related to a diverging
+                // variable (e.g. through the arms of a `match`), so see
+                // what it becomes once just the diverging fallback is
+                // applied.
+                let defaults_to_unit = self.infcx().probe(|_| {
+                    for &ty in &diverging {
+                        self.infcx().fallback_if_possible(TypeOrigin::Misc(codemap::DUMMY_SP), ty);
                     }
-                    Neither => { }
+                    self.infcx().shallow_resolve(self_ty).is_nil()
+                });
+                if defaults_to_unit {
+                    self.tcx().sess.add_lint(
+                        lint::builtin::RESOLVE_TRAIT_ON_DEFAULTED_UNIT,
+                        obligation.cause.body_id,
+                        obligation.cause.span,
+                        format!("code relies on type inference rules which are likely \
+                                 to change: `{}` is selected for `()` because the type \
+                                 of a diverging expression defaults to it",
+                                self.tcx().item_path_str(trait_ref.def_id())));
                 }
             }
         }
@@ -1821,6 +1958,8 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
 
             let mut conflicts = Vec::new();
 
+            self.lint_diverging_fallback();
+
             // Collect all unsolved type, integral and floating point variables.
             let unsolved_variables = self.inh.infcx.unsolved_variables();
 
@@ -1844,7 +1983,7 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
             for ty in &unsolved_variables {
                 let resolved = self.infcx().resolve_type_vars_if_possible(ty);
                 if self.infcx().type_var_diverges(resolved) {
-                    demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().mk_nil());
+                    self.infcx().fallback_if_possible(TypeOrigin::Misc(codemap::DUMMY_SP), *ty);
                 } else {
                     match self.infcx().type_is_unconstrained_numeric(resolved) {
                         UnconstrainedInt | UnconstrainedFloat => {
@@ -1900,31 +2039,20 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
 
             let _ = self.infcx().commit_if_ok(|_: &infer::CombinedSnapshot| {
                 for ty in &unbound_tyvars {
-                    if self.infcx().type_var_diverges(ty) {
-                        demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().mk_nil());
-                    } else {
-                        match self.infcx().type_is_unconstrained_numeric(ty) {
-                            UnconstrainedInt => {
-                                demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().types.i32)
+                    if self.infcx().fallback_if_possible(TypeOrigin::Misc(codemap::DUMMY_SP), *ty) {
+                        continue;
+                    }
+                    if let Some(default) = default_map.get(ty) {
+                        let default = default.clone();
+                        match infer::mk_eqty(self.infcx(), false,
+                                             TypeOrigin::Misc(default.origin_span),
+                                             ty, default.ty) {
+                            Ok(InferOk { obligations, .. }) => {
+                                // FIXME(#32730) propagate obligations
+                                assert!(obligations.is_empty())
                             },
-                            UnconstrainedFloat => {
-                                demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().types.f64)
-                            }
-                            Neither => {
-                                if let Some(default) = default_map.get(ty) {
-                                    let default = default.clone();
-                                    match infer::mk_eqty(self.infcx(), false,
-                                                         TypeOrigin::Misc(default.origin_span),
-                                                         ty, default.ty) {
-                                        Ok(InferOk { obligations, .. }) => {
-                                            // FIXME(#32730) propagate obligations
-                                            assert!(obligations.is_empty())
-                                        },
-                                        Err(_) => {
-                                            conflicts.push((*ty, default));
-                                        }
-                                    }
-                                }
+                            Err(_) => {
+                                conflicts.push((*ty, default));
                             }
                         }
                     }
@@ -1945,7 +2073,8 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                     let conflicting_default =
                         self.find_conflicting_default(&unbound_tyvars, &default_map, conflict)
                             .unwrap_or(type_variable::Default {
-                                ty: self.infcx().next_ty_var(),
+                                ty: self.infcx().next_ty_var(
+                                    TypeVariableOrigin::MiscVariable(codemap::DUMMY_SP)),
                                 origin_span: codemap::DUMMY_SP,
                                 def_id: self.tcx().map.local_def_id(0) // what do I put here?
                             });
@@ -1981,9 +2110,6 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                                 default_map: &FnvHashMap<&Ty<'tcx>, type_variable::Default<'tcx>>,
                                 conflict: Ty<'tcx>)
                                 -> Option<type_variable::Default<'tcx>> {
-        use rustc::ty::error::UnconstrainedNumeric::Neither;
-        use rustc::ty::error::UnconstrainedNumeric::{UnconstrainedInt, UnconstrainedFloat};
-
         // Ensure that we apply the conflicting default first
         let mut unbound_tyvars = Vec::with_capacity(unbound_vars.len() + 1);
         unbound_tyvars.push(conflict);
@@ -1996,29 +2122,18 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         // We also run this inside snapshot that never commits so we can do error
         // reporting for more then one conflict.
         for ty in &unbound_tyvars {
-            if self.infcx().type_var_diverges(ty) {
-                demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().mk_nil());
-            } else {
-                match self.infcx().type_is_unconstrained_numeric(ty) {
-                    UnconstrainedInt => {
-                        demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().types.i32)
-                    },
-                    UnconstrainedFloat => {
-                        demand::eqtype(self, codemap::DUMMY_SP, *ty, self.tcx().types.f64)
-                    },
-                    Neither => {
-                        if let Some(default) = default_map.get(ty) {
-                            let default = default.clone();
-                            match infer::mk_eqty(self.infcx(), false,
-                                                 TypeOrigin::Misc(default.origin_span),
-                                                 ty, default.ty) {
-                                // FIXME(#32730) propagate obligations
-                                Ok(InferOk { obligations, .. }) => assert!(obligations.is_empty()),
-                                Err(_) => {
-                                    result = Some(default);
-                                }
-                            }
-                        }
+            if self.infcx().fallback_if_possible(TypeOrigin::Misc(codemap::DUMMY_SP), *ty) {
+                continue;
+            }
+            if let Some(default) = default_map.get(ty) {
+                let default = default.clone();
+                match infer::mk_eqty(self.infcx(), false,
+                                     TypeOrigin::Misc(default.origin_span),
+                                     ty, default.ty) {
+                    // FIXME(#32730) propagate obligations
+                    Ok(InferOk { obligations, .. }) => assert!(obligations.is_empty()),
+                    Err(_) => {
+                        result = Some(default);
                     }
                 }
             }
@@ -2283,7 +2398,7 @@ fn try_index_step<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
            unsize,
            index_ty);
 
-    let input_ty = fcx.infcx().next_ty_var();
+    let input_ty = fcx.infcx().next_ty_var(TypeVariableOrigin::MiscVariable(expr.span));
 
     // First, try built-in indexing.
     match (adjusted_ty.builtin_index(), &index_ty.sty) {
@@ -2621,7 +2736,9 @@ fn write_call<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                         output: ty::FnOutput<'tcx>) {
     fcx.write_ty(call_expr.id, match output {
         ty::FnConverging(output_ty) => output_ty,
-        ty::FnDiverging => fcx.infcx().next_diverging_ty_var()
+        ty::FnDiverging => {
+            fcx.infcx().next_diverging_ty_var(TypeVariableOrigin::Diverging(call_expr.span))
+        }
     });
 }
 
@@ -2772,7 +2889,7 @@ fn expected_types_for_fn_args<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                         -> Vec<Ty<'tcx>> {
     let expected_args = expected_ret.only_has_type(fcx).and_then(|ret_ty| {
         if let ty::FnConverging(formal_ret_ty) = formal_ret {
-            fcx.infcx().commit_regions_if_ok(|| {
+            fcx.infcx().fudge_inference_if_ok(|| {
                 // Attempt to apply a subtyping relationship between the formal
                 // return type (likely containing type variables if the function
                 // is polymorphic) and the expected return type.
@@ -2911,12 +3028,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             } else {
                 fcx.infcx().commit_if_ok(|_| {
                     let trace = TypeTrace::types(origin, true, then_ty, else_ty);
-                    fcx.infcx().lub(true, trace, &then_ty, &else_ty)
-                        .map(|InferOk { value, obligations }| {
-                            // FIXME(#32730) propagate obligations
-                            assert!(obligations.is_empty());
-                            value
-                        })
+                    coercion::lub_or_sub(fcx.infcx(), true, trace, then_ty, else_ty)
                 })
             };
             (origin, then_ty, else_ty, result)
@@ -2948,6 +3060,91 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         fcx.write_ty(id, if_ty);
     }
 
+    // Check a `become` expression. The operand must be a direct call to a
+    // function or method with exactly the same signature as the enclosing
+    // function, so that the callee can take over the caller's stack frame.
+    fn check_become<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
+                              expr: &'tcx hir::Expr,
+                              call: &'tcx hir::Expr) {
+        let tcx = fcx.tcx();
+        match fcx.ret_ty {
+            ty::FnConverging(result_type) => check_expr_has_type(fcx, call, result_type),
+            ty::FnDiverging => check_expr(fcx, call),
+        }
+
+        let callee_ty = match call.node {
+            hir::ExprCall(ref callee, _) => {
+                let overloaded = fcx.inh.tables.borrow().method_map
+                                    .contains_key(&MethodCall::expr(call.id));
+                if overloaded { None } else { Some(fcx.expr_ty(callee)) }
+            }
+            hir::ExprMethodCall(..) => {
+                fcx.inh.tables.borrow().method_map
+                   .get(&MethodCall::expr(call.id))
+                   .map(|method| method.ty)
+            }
+            _ => None
+        };
+        let callee_fn = callee_ty.and_then(|ty| {
+            match fcx.resolve_type_vars_if_possible(ty).sty {
+                ty::TyFnDef(_, _, f) | ty::TyFnPtr(f) => Some(f),
+                _ => None
+            }
+        });
+        let callee_fn = match callee_fn {
+            Some(f) => f,
+            None => {
+                span_err!(tcx.sess, call.span, E0526,
+                          "`become` requires a call to a function or method");
+                return;
+            }
+        };
+
+        // Closures have their own calling convention, and anything that
+        // is not a fn item (e.g. a const initializer) cannot tail call.
+        let fn_id = tcx.map.get_parent_node(fcx.body_id);
+        let caller_fn = match tcx.map.find(fn_id) {
+            Some(hir::map::NodeItem(..)) |
+            Some(hir::map::NodeImplItem(..)) |
+            Some(hir::map::NodeTraitItem(..)) => {
+                match tcx.lookup_item_type(tcx.map.local_def_id(fn_id)).ty.sty {
+                    ty::TyFnDef(_, _, f) => Some(f),
+                    _ => None
+                }
+            }
+            _ => None
+        };
+        let caller_fn = match caller_fn {
+            Some(f) => f,
+            None => {
+                tcx.sess.span_err(expr.span, "`become` is only allowed in functions and methods");
+                return;
+            }
+        };
+
+        let erase = |f: &ty::BareFnTy<'tcx>| {
+            let sig = fcx.infcx().resolve_type_vars_if_possible(&f.sig);
+            tcx.erase_regions(&tcx.erase_late_bound_regions(&sig))
+        };
+        let (caller_sig, callee_sig) = (erase(caller_fn), erase(callee_fn));
+        if caller_fn.abi != callee_fn.abi || caller_sig != callee_sig {
+            struct_span_err!(tcx.sess, call.span, E0527,
+                             "`become` requires the callee to have the same signature \
+                              as the caller")
+                .note(&format!("the caller has signature `{}`{}",
+                               caller_sig,
+                               abi_suffix(caller_fn.abi)))
+                .note(&format!("the callee has signature `{}`{}",
+                               callee_sig,
+                               abi_suffix(callee_fn.abi)))
+                .emit();
+        }
+
+        fn abi_suffix(abi: Abi) -> String {
+            if abi == Abi::Rust { String::new() } else { format!(" with ABI `{}`", abi) }
+        }
+    }
+
     // Check field access expressions
     fn check_field<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>,
                             expr: &'tcx hir::Expr,
@@ -2957,6 +3154,8 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         check_expr_with_lvalue_pref(fcx, base, lvalue_pref);
         let expr_t = structurally_resolved_type(fcx, expr.span, fcx.expr_ty(base));
         let mut private_candidate = None;
+        // every type visited while autoderefing, for error reporting
+        let mut deref_steps = vec![];
         let (_, autoderefs, field_ty) = autoderef(fcx,
                                                   expr.span,
                                                   expr_t,
@@ -2964,6 +3163,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                                                   UnresolvedTypeAction::Error,
                                                   lvalue_pref,
                                                   |base_t, _| {
+                deref_steps.push(base_t);
                 if let ty::TyStruct(base_def, substs) = base_t.sty {
                     debug!("struct named {:?}",  base_t);
                     if let Some(field) = base_def.struct_variant().find_field_named(field.node) {
@@ -3015,8 +3215,15 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                             actual)
                 },
                 expr_t, None);
-            if let ty::TyStruct(def, _) = expr_t.sty {
-                suggest_field_names(&mut err, def.struct_variant(), field, vec![]);
+            // look for similarly named fields on every struct that
+            // autoderef reached, not just the outermost one
+            let variants: Vec<_> = deref_steps.iter().filter_map(|t| match t.sty {
+                ty::TyStruct(def, _) => Some(def.struct_variant()),
+                _ => None
+            }).collect();
+            suggest_field_names(&mut err, &variants, field, vec![]);
+            if let Some(&last_t) = deref_steps.last() {
+                suggest_field_through_wrapper(fcx, &mut err, expr, base, last_t, field);
             }
             err.emit();
             fcx.write_error(expr.id);
@@ -3025,21 +3232,25 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
 
     // displays hints about the closest matches in field names
     fn suggest_field_names<'tcx>(err: &mut DiagnosticBuilder,
-                                 variant: ty::VariantDef<'tcx>,
+                                 variants: &[ty::VariantDef<'tcx>],
                                  field: &Spanned<ast::Name>,
                                  skip : Vec<InternedString>) {
         let name = field.node.as_str();
-        let names = variant.fields
-                    .iter()
-                    .filter_map(|ref field| {
-                        // ignore already set fields and private fields from non-local crates
-                        if skip.iter().any(|x| *x == field.name.as_str()) ||
-                           (variant.did.krate != LOCAL_CRATE && field.vis != Visibility::Public) {
-                               None
-                        } else {
-                            Some(&field.name)
-                        }
-                    });
+        let names = variants.iter().flat_map(|&variant| {
+            variant.fields
+                   .iter()
+                   .filter(move |field| {
+                       // ignore private fields from non-local crates
+                       variant.did.krate == LOCAL_CRATE || field.vis == Visibility::Public
+                   })
+        }).filter_map(|ref field| {
+            // ignore already set fields
+            if skip.iter().any(|x| *x == field.name.as_str()) {
+                None
+            } else {
+                Some(&field.name)
+            }
+        });
 
         // only find fits with at least one matching letter
         if let Some(name) = find_best_match_for_name(names, &name, Some(name.len())) {
@@ -3048,6 +3259,69 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         }
     }
 
+    // When autoderef stopped at an `Option` or a raw pointer whose
+    // contents have the requested field, suggests how to get at it. The
+    // suggestion replaces the whole field access `expr`.
+    fn suggest_field_through_wrapper<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
+                                               err: &mut DiagnosticBuilder,
+                                               expr: &hir::Expr,
+                                               base: &hir::Expr,
+                                               base_t: Ty<'tcx>,
+                                               field: &Spanned<ast::Name>) {
+        let tcx = fcx.tcx();
+        let (inner_t, is_option) = match base_t.sty {
+            ty::TyEnum(def, substs) if tcx.is_diagnostic_item("Option", def.did) => {
+                (*substs.types.get(subst::TypeSpace, 0), true)
+            }
+            ty::TyRawPtr(mt) => (mt.ty, false),
+            _ => return
+        };
+
+        // strip off any references or boxes inside the wrapper
+        let mut inner_t = fcx.resolve_type_vars_if_possible(inner_t);
+        while let Some(mt) = inner_t.builtin_deref(false, NoPreference) {
+            inner_t = mt.ty;
+        }
+        let has_field = match inner_t.sty {
+            ty::TyStruct(def, _) => def.struct_variant().find_field_named(field.node).is_some(),
+            _ => false
+        };
+        if !has_field {
+            return;
+        }
+
+        let snippet = tcx.sess.codemap().span_to_snippet(base.span);
+        if is_option {
+            match snippet {
+                Ok(s) => {
+                    err.span_suggestion(expr.span,
+                                        "the field is on the value inside the `Option`; \
+                                         try accessing it with `as_ref`:",
+                                        format!("{}.as_ref().map(|x| &x.{})", s, field.node));
+                }
+                Err(_) => {
+                    err.span_help(base.span,
+                                  "the field is on the value inside the `Option`; \
+                                   try accessing it with `.as_ref().map(...)`");
+                }
+            }
+        } else {
+            match snippet {
+                Ok(s) => {
+                    err.span_suggestion(expr.span,
+                                        "raw pointers are not dereferenced automatically; \
+                                         try dereferencing it:",
+                                        format!("(*{}).{}", s, field.node));
+                }
+                Err(_) => {
+                    err.span_help(base.span,
+                                  "raw pointers are not dereferenced automatically; \
+                                   try dereferencing it");
+                }
+            }
+        }
+    }
+
     // Check tuple index expressions
     fn check_tup_field<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>,
                                 expr: &'tcx hir::Expr,
@@ -3142,7 +3416,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             None);
         // prevent all specified fields from being suggested
         let skip_fields = skip_fields.iter().map(|ref x| x.name.node.as_str());
-        suggest_field_names(&mut err, variant, &field.name, skip_fields.collect());
+        suggest_field_names(&mut err, &[variant], &field.name, skip_fields.collect());
         err.emit();
     }
 
@@ -3235,6 +3509,21 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             check_struct_fields_on_error(fcx, expr.id, fields, base_expr);
             return;
         }
+
+        // `Self { .. }` inside an impl builds the impl's self type.
+        let self_ty = fcx.self_struct_ty(def, path);
+        let def = match self_ty {
+            Some((_, adt, _)) => {
+                let def = Def::Struct(adt.did);
+                // Write back the new resolution.
+                tcx.def_map.borrow_mut().insert(expr.id, def::PathResolution {
+                    base_def: def,
+                    depth: 0,
+                });
+                def
+            }
+            None => def,
+        };
         let variant = match fcx.def_struct_variant(def, path.span) {
             Some((_, variant)) => variant,
             None => {
@@ -3246,7 +3535,10 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             }
         };
 
-        let expr_ty = fcx.instantiate_type(def.def_id(), path);
+        let expr_ty = match self_ty {
+            Some((ty, _, _)) => ty,
+            None => fcx.instantiate_type(def.def_id(), path),
+        };
         fcx.write_ty(expr.id, expr_ty);
 
         check_expr_struct_fields(fcx, expr_ty, expr.span, variant, fields,
@@ -3272,6 +3564,51 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         }
     }
 
+    // Checks `Self` used as a value inside an impl: the constructor of a
+    // tuple or unit struct, with the impl's own type parameters.
+    fn check_self_ctor<'a, 'tcx>(fcx: &FnCtxt<'a,'tcx>,
+                                 expr: &hir::Expr,
+                                 path: &hir::Path,
+                                 def: Def)
+    {
+        let tcx = fcx.tcx();
+
+        let ctor = fcx.self_struct_ty(def, path).and_then(|(_, adt, substs)| {
+            if adt.struct_variant().kind() == ty::VariantKind::Struct {
+                return None;
+            }
+            let ctor_did = match tcx.map.as_local_node_id(adt.did) {
+                Some(node_id) => match tcx.map.expect_item(node_id).node {
+                    hir::ItemStruct(ref struct_def, _) => {
+                        Some(tcx.map.local_def_id(struct_def.id()))
+                    }
+                    _ => None,
+                },
+                None => tcx.sess.cstore.struct_ctor_def_id(adt.did),
+            };
+            ctor_did.map(|did| (did, substs))
+        });
+
+        match ctor {
+            Some((ctor_did, substs)) => {
+                // Write back the new resolution.
+                tcx.def_map.borrow_mut().insert(expr.id, def::PathResolution {
+                    base_def: Def::Struct(ctor_did),
+                    depth: 0,
+                });
+                let scheme = tcx.lookup_item_type(ctor_did);
+                let ty = fcx.instantiate_type_scheme(expr.span, substs, &scheme.ty);
+                fcx.write_ty(expr.id, ty);
+                fcx.write_substs(expr.id, ty::ItemSubsts { substs: substs.clone() });
+            }
+            None => {
+                span_err!(tcx.sess, expr.span, E0528,
+                          "`Self` does not name a tuple or unit struct");
+                fcx.write_error(expr.id);
+            }
+        }
+    }
+
     type ExprCheckerWithTy = fn(&FnCtxt, &hir::Expr, Ty);
 
     let tcx = fcx.ccx.tcx;
@@ -3422,7 +3759,9 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
           if let Some((opt_ty, segments, def)) =
                   resolve_ty_and_def_ufcs(fcx, path_res, opt_self_ty, path,
                                           expr.span, expr.id) {
-              if def != Def::Err {
+              if let Def::SelfTy(..) = def {
+                  check_self_ctor(fcx, expr, path, def);
+              } else if def != Def::Err {
                   let (scheme, predicates) = type_scheme_and_predicates_for_def(fcx,
                                                                                 expr.span,
                                                                                 def);
@@ -3454,8 +3793,10 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
           }
           fcx.write_nil(id);
       }
-      hir::ExprBreak(_) => { fcx.write_ty(id, fcx.infcx().next_diverging_ty_var()); }
-      hir::ExprAgain(_) => { fcx.write_ty(id, fcx.infcx().next_diverging_ty_var()); }
+      hir::ExprBreak(_) | hir::ExprAgain(_) => {
+          fcx.write_ty(id, fcx.infcx().next_diverging_ty_var(
+              TypeVariableOrigin::Diverging(expr.span)));
+      }
       hir::ExprRet(ref expr_opt) => {
         match fcx.ret_ty {
             ty::FnConverging(result_type) => {
@@ -3480,7 +3821,13 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                     "`return` in a function declared as diverging");
             }
         }
-        fcx.write_ty(id, fcx.infcx().next_diverging_ty_var());
+        fcx.write_ty(id, fcx.infcx().next_diverging_ty_var(
+            TypeVariableOrigin::Diverging(expr.span)));
+      }
+      hir::ExprBecome(ref call) => {
+        check_become(fcx, expr, &call);
+        fcx.write_ty(id, fcx.infcx().next_diverging_ty_var(
+            TypeVariableOrigin::Diverging(expr.span)));
       }
       hir::ExprAssign(ref lhs, ref rhs) => {
         check_expr_with_lvalue_pref(fcx, &lhs, PreferMutLvalue);
@@ -3522,7 +3869,8 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
       hir::ExprLoop(ref body, _) => {
         check_block_no_value(fcx, &body);
         if !may_break(tcx, expr.id, &body) {
-            fcx.write_ty(id, fcx.infcx().next_diverging_ty_var());
+            fcx.write_ty(id, fcx.infcx().next_diverging_ty_var(
+                TypeVariableOrigin::Diverging(expr.span)));
         } else {
             fcx.write_nil(id);
         }
@@ -3594,7 +3942,7 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             }
         });
 
-        let mut unified = fcx.infcx().next_ty_var();
+        let mut unified = fcx.infcx().next_ty_var(TypeVariableOrigin::MiscVariable(expr.span));
         let coerce_to = uty.unwrap_or(unified);
 
         for (i, e) in args.iter().enumerate() {
@@ -3639,7 +3987,8 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                 (uty, uty)
             }
             None => {
-                let t: Ty = fcx.infcx().next_ty_var();
+                let t: Ty = fcx.infcx().next_ty_var(
+                    TypeVariableOrigin::MiscVariable(element.span));
                 check_expr_has_type(fcx, &element, t);
                 (fcx.expr_ty(&element), t)
             }
@@ -3740,6 +4089,8 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
        }
     }
 
+    fcx.recover_from_errors_in(expr);
+
     debug!("type of expr({}) {} is...", expr.id,
            pprust::expr_to_string(expr));
     debug!("... {:?}, expected is {:?}",
@@ -3747,6 +4098,68 @@ fn check_expr_with_expectation_and_lvalue_pref<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
            expected);
 }
 
+/// The expressions whose types the type of `expr` is computed from:
+/// its direct subexpressions, with blocks standing for their trailing
+/// expression.
+fn expr_operands(expr: &hir::Expr) -> Vec<&hir::Expr> {
+    fn block_value(block: &hir::Block) -> Option<&hir::Expr> {
+        block.expr.as_ref().map(|e| &**e)
+    }
+
+    let mut operands = vec![];
+    match expr.node {
+        hir::ExprBox(ref e) |
+        hir::ExprUnary(_, ref e) |
+        hir::ExprCast(ref e, _) |
+        hir::ExprType(ref e, _) |
+        hir::ExprField(ref e, _) |
+        hir::ExprTupField(ref e, _) |
+        hir::ExprAddrOf(_, ref e) |
+        hir::ExprBecome(ref e) |
+        hir::ExprRepeat(ref e, _) => operands.push(&**e),
+        hir::ExprRet(ref e) => operands.extend(e.as_ref().map(|e| &**e)),
+        hir::ExprBinary(_, ref a, ref b) |
+        hir::ExprAssign(ref a, ref b) |
+        hir::ExprAssignOp(_, ref a, ref b) |
+        hir::ExprIndex(ref a, ref b) => {
+            operands.push(&**a);
+            operands.push(&**b);
+        }
+        hir::ExprVec(ref es) |
+        hir::ExprTup(ref es) |
+        hir::ExprMethodCall(_, _, ref es) => operands.extend(es.iter().map(|e| &**e)),
+        hir::ExprCall(ref callee, ref args) => {
+            operands.push(&**callee);
+            operands.extend(args.iter().map(|e| &**e));
+        }
+        hir::ExprStruct(_, ref fields, ref base) => {
+            operands.extend(fields.iter().map(|field| &*field.expr));
+            operands.extend(base.as_ref().map(|e| &**e));
+        }
+        hir::ExprIf(ref cond, ref then, ref otherwise) => {
+            operands.push(&**cond);
+            operands.extend(block_value(then));
+            operands.extend(otherwise.as_ref().map(|e| &**e));
+        }
+        hir::ExprWhile(ref cond, _, _) => operands.push(&**cond),
+        hir::ExprMatch(ref discr, ref arms, _) => {
+            operands.push(&**discr);
+            operands.extend(arms.iter().map(|arm| &*arm.body));
+        }
+        hir::ExprBlock(ref block) => operands.extend(block_value(block)),
+        hir::ExprInlineAsm(_, ref outputs, ref inputs) => {
+            operands.extend(outputs.iter().chain(inputs).map(|e| &**e));
+        }
+        hir::ExprLit(_) |
+        hir::ExprPath(..) |
+        hir::ExprLoop(..) |
+        hir::ExprClosure(..) |
+        hir::ExprBreak(_) |
+        hir::ExprAgain(_) => {}
+    }
+    operands
+}
+
 pub fn resolve_ty_and_def_ufcs<'a, 'b, 'tcx>(fcx: &FnCtxt<'b, 'tcx>,
                                              path_res: def::PathResolution,
                                              opt_self_ty: Option<Ty<'tcx>>,
@@ -3771,6 +4184,7 @@ pub fn resolve_ty_and_def_ufcs<'a, 'b, 'tcx>(fcx: &FnCtxt<'b, 'tcx>,
                                                      opt_self_ty,
                                                      &ty_segments[..base_ty_end],
                                                      &ty_segments[base_ty_end..]);
+        let ty = fcx.normalize_associated_types_in(span, &ty);
         let item_segment = path.segments.last().unwrap();
         let item_name = item_segment.identifier.name;
         let def = match method::resolve_ufcs(fcx, span, item_name, ty, node_id) {
@@ -3953,7 +4367,8 @@ pub fn check_stmt<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>, stmt: &'tcx hir::Stmt)  {
       }
     }
     if saw_bot {
-        fcx.write_ty(node_id, fcx.infcx().next_diverging_ty_var());
+        fcx.write_ty(node_id, fcx.infcx().next_diverging_ty_var(
+            TypeVariableOrigin::Diverging(stmt.span)));
     }
     else if saw_err {
         fcx.write_error(node_id);
@@ -4015,7 +4430,8 @@ fn check_block_with_expected<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         None => if any_err {
             fcx.write_error(blk.id);
         } else if any_diverges {
-            fcx.write_ty(blk.id, fcx.infcx().next_diverging_ty_var());
+            fcx.write_ty(blk.id, fcx.infcx().next_diverging_ty_var(
+                TypeVariableOrigin::Diverging(blk.span)));
         } else {
             fcx.write_nil(blk.id);
         },
@@ -4043,7 +4459,8 @@ fn check_block_with_expected<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             if any_err {
                 fcx.write_error(blk.id);
             } else if any_diverges {
-                fcx.write_ty(blk.id, fcx.infcx().next_diverging_ty_var());
+                fcx.write_ty(blk.id, fcx.infcx().next_diverging_ty_var(
+                    TypeVariableOrigin::Diverging(blk.span)));
             } else {
                 fcx.write_ty(blk.id, ety);
             }
@@ -4229,6 +4646,7 @@ fn type_scheme_and_predicates_for_def<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             (fcx.tcx().lookup_item_type(id), fcx.tcx().lookup_predicates(id))
         }
         Def::Trait(_) |
+        Def::TraitAlias(_) |
         Def::Enum(..) |
         Def::TyAlias(..) |
         Def::AssociatedTy(..) |
@@ -4342,6 +4760,7 @@ pub fn instantiate_path<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
         Def::TyAlias(..) |
         Def::AssociatedTy(..) |
         Def::Trait(..) |
+        Def::TraitAlias(..) |
         Def::PrimTy(..) |
         Def::TyParam(..) => {
             // Everything but the final segment should have no
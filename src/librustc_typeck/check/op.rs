@@ -19,6 +19,7 @@ use super::{
     FnCtxt,
 };
 use hir::def_id::DefId;
+use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::ty::{Ty, TypeFoldable, PreferMutLvalue};
 use syntax::ast;
 use syntax::parse::token;
@@ -171,7 +172,7 @@ fn check_overloaded_binop<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
     // using this variable as the expected type, which sometimes lets
     // us do better coercions than we would be able to do otherwise,
     // particularly for things like `String + &String`.
-    let rhs_ty_var = fcx.infcx().next_ty_var();
+    let rhs_ty_var = fcx.infcx().next_ty_var(TypeVariableOrigin::Adjustment(rhs_expr.span));
 
     let return_ty = match lookup_op_method(fcx, expr, lhs_ty, vec![rhs_ty_var],
                                            token::intern(name), trait_def_id,
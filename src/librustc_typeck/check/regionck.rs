@@ -100,6 +100,7 @@ use rustc::ty::wf::ImpliedBound;
 use std::mem;
 use syntax::ast;
 use syntax::codemap::Span;
+use syntax::ptr::P;
 use rustc::hir::intravisit::{self, Visitor};
 use rustc::hir::{self, PatKind};
 
@@ -118,7 +119,7 @@ pub fn regionck_expr(fcx: &FnCtxt, e: &hir::Expr) {
     if fcx.err_count_since_creation() == 0 {
         // regionck assumes typeck succeeded
         rcx.visit_expr(e);
-        rcx.visit_region_obligations(e.id);
+        rcx.process_registered_region_obligations(e.id);
     }
     rcx.resolve_regions_and_report_errors();
 }
@@ -135,7 +136,7 @@ pub fn regionck_item<'a,'tcx>(fcx: &FnCtxt<'a,'tcx>,
     rcx.free_region_map
        .relate_free_regions_from_predicates(tcx, &fcx.infcx().parameter_environment.caller_bounds);
     rcx.relate_free_regions(wf_tys, item_id, span);
-    rcx.visit_region_obligations(item_id);
+    rcx.process_registered_region_obligations(item_id);
     rcx.resolve_regions_and_report_errors();
 }
 
@@ -321,7 +322,7 @@ impl<'a, 'tcx> Rcx<'a, 'tcx> {
                      self.tcx().region_maps.node_extent(body.id),
                      &fn_decl.inputs[..]);
         self.visit_block(body);
-        self.visit_region_obligations(body.id);
+        self.process_registered_region_obligations(body.id);
 
         let call_site_scope = self.call_site_scope.unwrap();
         debug!("visit_fn_body body.id {} call_site_scope: {:?}",
@@ -337,36 +338,34 @@ impl<'a, 'tcx> Rcx<'a, 'tcx> {
         self.set_call_site_scope(old_call_site_scope);
     }
 
-    fn visit_region_obligations(&mut self, node_id: ast::NodeId)
+    /// Decomposes the `T: 'a` obligations registered for `node_id`
+    /// into region constraints. By the time this runs, type inference
+    /// for the body is complete, so the types are resolved first and
+    /// only then broken into their outlives components.
+    fn process_registered_region_obligations(&mut self, node_id: ast::NodeId)
     {
-        debug!("visit_region_obligations: node_id={}", node_id);
-
-        // region checking can introduce new pending obligations
-        // which, when processed, might generate new region
-        // obligations. So make sure we process those.
-        self.fcx.select_all_obligations_or_error();
-
-        // Make a copy of the region obligations vec because we'll need
-        // to be able to borrow the fulfillment-cx below when projecting.
-        let region_obligations =
-            self.fcx
-                .inh
-                .fulfillment_cx
-                .borrow()
-                .region_obligations(node_id)
-                .to_vec();
-
-        for r_o in &region_obligations {
-            debug!("visit_region_obligations: r_o={:?} cause={:?}",
-                   r_o, r_o.cause);
-            let sup_type = self.resolve_type(r_o.sup_type);
-            let origin = self.code_to_origin(r_o.cause.span, sup_type, &r_o.cause.code);
-            type_must_outlive(self, origin, sup_type, r_o.sub_region);
-        }
+        debug!("process_registered_region_obligations: node_id={}", node_id);
+
+        loop {
+            // region checking can introduce new pending obligations
+            // which, when processed, might generate new region
+            // obligations. So make sure we process those.
+            self.fcx.select_all_obligations_or_error();
+
+            let region_obligations =
+                self.fcx.infcx().take_registered_region_obligations(node_id);
+            if region_obligations.is_empty() {
+                break;
+            }
 
-        // Processing the region obligations should not cause the list to grow further:
-        assert_eq!(region_obligations.len(),
-                   self.fcx.inh.fulfillment_cx.borrow().region_obligations(node_id).len());
+            for r_o in &region_obligations {
+                debug!("process_registered_region_obligations: r_o={:?} cause={:?}",
+                       r_o, r_o.cause);
+                let sup_type = self.resolve_type(r_o.sup_type);
+                let origin = self.code_to_origin(r_o.cause.span, sup_type, &r_o.cause.code);
+                type_must_outlive(self, origin, sup_type, r_o.sub_region);
+            }
+        }
     }
 
     fn code_to_origin(&self,
@@ -813,6 +812,28 @@ fn visit_expr(rcx: &mut Rcx, expr: &hir::Expr) {
             intravisit::walk_expr(rcx, expr);
         }
 
+        hir::ExprBecome(ref call) => {
+            // The callee of a tail call replaces our frame, so its
+            // arguments must not borrow anything that lives in it.
+            let call_site_scope = rcx.call_site_scope.unwrap();
+            type_of_node_must_outlive(rcx,
+                                      infer::CallReturn(call.span),
+                                      call.id,
+                                      ty::ReScope(call_site_scope));
+            let args: &[P<hir::Expr>] = match call.node {
+                hir::ExprCall(_, ref args) => args,
+                hir::ExprMethodCall(_, _, ref args) => &args[..],
+                _ => &[],
+            };
+            for arg in args {
+                type_of_node_must_outlive(rcx,
+                                          infer::CallArg(arg.span),
+                                          arg.id,
+                                          ty::ReScope(call_site_scope));
+            }
+            intravisit::walk_expr(rcx, expr);
+        }
+
         _ => {
             intravisit::walk_expr(rcx, expr);
         }
@@ -1592,8 +1613,13 @@ fn projection_must_outlive<'a, 'tcx>(rcx: &Rcx<'a, 'tcx>,
     // regions in this list.
     let env_bounds = projection_declared_bounds(rcx, origin.span(), projection_ty);
 
-    debug!("projection_must_outlive: env_bounds={:?}",
-           env_bounds);
+    // Bounds declared on the same projection with different regions in
+    // its trait reference may also apply, depending on how those
+    // regions get inferred.
+    let conditional_bounds = projection_conditional_bounds(rcx, projection_ty);
+
+    debug!("projection_must_outlive: env_bounds={:?} conditional_bounds={:?}",
+           env_bounds, conditional_bounds);
 
     // If we know that the projection outlives 'static, then we're
     // done here.
@@ -1620,7 +1646,7 @@ fn projection_must_outlive<'a, 'tcx>(rcx: &Rcx<'a, 'tcx>,
         projection_ty.trait_ref.substs.types.iter().any(|t| t.needs_infer()) ||
             projection_ty.trait_ref.substs.regions.iter().any(|r| r.needs_infer())
     };
-    if env_bounds.is_empty() && needs_infer {
+    if env_bounds.is_empty() && conditional_bounds.is_empty() && needs_infer {
         debug!("projection_must_outlive: no declared bounds");
 
         for &component_ty in &projection_ty.trait_ref.substs.types {
@@ -1659,7 +1685,11 @@ fn projection_must_outlive<'a, 'tcx>(rcx: &Rcx<'a, 'tcx>,
     // projection outlive; in some cases, this may add insufficient
     // edges into the inference graph, leading to inference failures
     // even though a satisfactory solution exists.
-    let verify_bound = projection_bound(rcx, origin.span(), env_bounds, projection_ty);
+    let verify_bound = projection_bound(rcx,
+                                        origin.span(),
+                                        env_bounds,
+                                        conditional_bounds,
+                                        projection_ty);
     let generic = GenericKind::Projection(projection_ty);
     rcx.fcx.infcx().verify_generic_bound(origin, generic.clone(), region, verify_bound);
 }
@@ -1671,7 +1701,8 @@ fn type_bound<'a, 'tcx>(rcx: &Rcx<'a, 'tcx>, span: Span, ty: Ty<'tcx>) -> Verify
         }
         ty::TyProjection(data) => {
             let declared_bounds = projection_declared_bounds(rcx, span, data);
-            projection_bound(rcx, span, declared_bounds, data)
+            let conditional_bounds = projection_conditional_bounds(rcx, data);
+            projection_bound(rcx, span, declared_bounds, conditional_bounds, data)
         }
         _ => {
             recursive_type_bound(rcx, span, ty)
@@ -1710,20 +1741,71 @@ fn projection_declared_bounds<'a, 'tcx>(rcx: &Rcx<'a,'tcx>,
     declared_bounds
 }
 
+/// Bounds from the environment on projections of the same associated
+/// item as `projection_ty`, whose trait references differ from its own
+/// in their regions only. A bound `<T as Trait<'x>>::Item: 'b` tells us
+/// that `<T as Trait<'y>>::Item: 'b` provided `'x == 'y`, which may not
+/// be known until region inference is done, so each such bound is
+/// returned as a `VerifyBound::IfEq`. Bounds that match exactly are
+/// found by `declared_generic_bounds_from_env` instead.
+fn projection_conditional_bounds<'a, 'tcx>(rcx: &Rcx<'a, 'tcx>,
+                                           projection_ty: ty::ProjectionTy<'tcx>)
+                                           -> Vec<VerifyBound>
+{
+    let tcx = rcx.tcx();
+    let param_env = &rcx.infcx().parameter_environment;
+
+    let mut candidates = vec![];
+    for predicate in traits::elaborate_predicates(tcx, param_env.caller_bounds.clone()) {
+        if let ty::Predicate::TypeOutlives(ty::Binder(ty::OutlivesPredicate(t, r))) = predicate {
+            if let ty::TyProjection(data) = t.sty {
+                if !r.has_escaping_regions() {
+                    candidates.push((data, r));
+                }
+            }
+        }
+    }
+    for &(r, p) in &rcx.region_bound_pairs {
+        if let GenericKind::Projection(data) = p {
+            candidates.push((data, r));
+        }
+    }
+
+    candidates.into_iter()
+              .filter_map(|(data, r)| {
+                  let a = projection_ty.trait_ref.substs;
+                  let b = data.trait_ref.substs;
+                  if data.item_name != projection_ty.item_name ||
+                      data.trait_ref.def_id != projection_ty.trait_ref.def_id ||
+                      a.types != b.types ||
+                      a.regions.len() != b.regions.len() ||
+                      a.regions == b.regions
+                  {
+                      return None;
+                  }
+                  let pairs = a.regions.iter().cloned().zip(b.regions.iter().cloned()).collect();
+                  Some(VerifyBound::IfEq(pairs, box VerifyBound::AnyRegion(vec![r])))
+              })
+              .collect()
+}
+
 fn projection_bound<'a, 'tcx>(rcx: &Rcx<'a, 'tcx>,
                               span: Span,
                               declared_bounds: Vec<ty::Region>,
+                              conditional_bounds: Vec<VerifyBound>,
                               projection_ty: ty::ProjectionTy<'tcx>)
                               -> VerifyBound {
-    debug!("projection_bound(declared_bounds={:?}, projection_ty={:?})",
-           declared_bounds, projection_ty);
+    debug!("projection_bound(declared_bounds={:?}, conditional_bounds={:?}, projection_ty={:?})",
+           declared_bounds, conditional_bounds, projection_ty);
 
     // see the extensive comment in projection_must_outlive
 
     let ty = rcx.tcx().mk_projection(projection_ty.trait_ref, projection_ty.item_name);
     let recursive_bound = recursive_type_bound(rcx, span, ty);
 
-    VerifyBound::AnyRegion(declared_bounds).or(recursive_bound)
+    VerifyBound::AnyRegion(declared_bounds)
+        .or(VerifyBound::AnyBound(conditional_bounds))
+        .or(recursive_bound)
 }
 
 fn recursive_type_bound<'a, 'tcx>(rcx: &Rcx<'a, 'tcx>,
@@ -13,6 +13,7 @@ use check::{FnCtxt, Inherited, blank_fn_ctxt, regionck};
 use constrained_type_params::{identify_constrained_type_params, Parameter};
 use CrateCtxt;
 use hir::def_id::DefId;
+use rustc::dep_graph::DepNode;
 use middle::region::{CodeExtent};
 use rustc::ty::subst::{self, TypeSpace, FnSpace, ParamSpace, SelfSpace};
 use rustc::traits;
@@ -33,6 +34,21 @@ pub struct CheckTypeWellFormedVisitor<'ccx, 'tcx:'ccx> {
     code: traits::ObligationCauseCode<'tcx>,
 }
 
+/// Checks that the item `item_def_id`, including its trait or impl
+/// items, is well-formed. This runs as a dep-graph task of its own that
+/// only reads the item's signature, so that editing a fn body or another
+/// item does not invalidate it.
+pub fn check_item_well_formed<'ccx, 'tcx>(ccx: &'ccx CrateCtxt<'ccx, 'tcx>, item_def_id: DefId) {
+    let tcx = ccx.tcx;
+    let _task = tcx.dep_graph.in_task(DepNode::WfCheck(item_def_id));
+    let node_id = tcx.map.as_local_node_id(item_def_id).unwrap();
+    // The visitor walks the bodies of the item without looking at
+    // them, so only the signature is read.
+    let item = tcx.dep_graph.with_ignore(|| tcx.map.expect_item(node_id));
+    tcx.dep_graph.read(DepNode::Hir(item_def_id));
+    CheckTypeWellFormedVisitor::new(ccx).visit_item(item);
+}
+
 impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
     pub fn new(ccx: &'ccx CrateCtxt<'ccx, 'tcx>)
                -> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
@@ -93,7 +109,8 @@ impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
                 match ccx.tcx.lang_items.to_builtin_kind(trait_ref.def_id) {
                     Some(ty::BoundSend) | Some(ty::BoundSync) => {}
                     Some(_) | None => {
-                        if !ccx.tcx.trait_has_default_impl(trait_ref.def_id) {
+                        if !ccx.tcx.trait_has_default_impl(trait_ref.def_id) &&
+                           !ccx.tcx.sess.features.borrow().negative_bounds {
                             error_192(ccx, item.span);
                         }
                     }
@@ -295,6 +295,10 @@ impl<'cx, 'tcx> WritebackCx<'cx, 'tcx> {
                         adjustment::AdjustUnsafeFnPointer
                     }
 
+                    adjustment::AdjustClosureFnPointer => {
+                        adjustment::AdjustClosureFnPointer
+                    }
+
                     adjustment::AdjustDerefRef(adj) => {
                         for autoderef in 0..adj.autoderefs {
                             let method_call = MethodCall::autoderef(id, autoderef as u32);
@@ -18,6 +18,7 @@ use rustc::traits::{self, ProjectionMode};
 use rustc::infer;
 use rustc::ty::{self, TyCtxt};
 use syntax::ast;
+use syntax::codemap::Span;
 use rustc::dep_graph::DepNode;
 use rustc::hir;
 use rustc::hir::intravisit;
@@ -41,6 +42,100 @@ struct OverlapChecker<'cx, 'tcx:'cx> {
 }
 
 impl<'cx, 'tcx> OverlapChecker<'cx, 'tcx> {
+    /// Renders the header of a trait impl, e.g. `impl<T> Foo for Vec<T>`.
+    fn impl_header_to_string(&self, impl_def_id: DefId) -> String {
+        let trait_ref = self.tcx.impl_trait_ref(impl_def_id).unwrap();
+        let generics = self.tcx.lookup_item_type(impl_def_id).generics;
+
+        let params: Vec<String> =
+            generics.regions.as_slice().iter().map(|r| r.name.to_string())
+                    .chain(generics.types.as_slice().iter().map(|t| t.name.to_string()))
+                    .collect();
+        let params = if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        };
+
+        let polarity = match self.tcx.trait_impl_polarity(impl_def_id) {
+            Some(hir::ImplPolarity::Negative) => "!",
+            _ => "",
+        };
+
+        format!("impl{} {}{} for {}", params, polarity, trait_ref, trait_ref.self_ty())
+    }
+
+    /// Describes the conflict between two impls, e.g. "conflicting
+    /// implementations of trait `Foo` for type `Box<_>`".
+    fn conflict_description(&self, overlap: &traits::OverlapError) -> String {
+        let self_type = match overlap.self_desc {
+            Some(ref ty) => format!(" for type `{}`", ty),
+            None => String::new(),
+        };
+
+        format!("conflicting implementations of trait `{}`{}", overlap.trait_desc, self_type)
+    }
+
+    /// The span of the self type of a local impl, e.g. of `Vec<T>` in
+    /// `impl<T> Foo for Vec<T>`.
+    fn self_ty_span(&self, impl_def_id: DefId) -> Option<Span> {
+        let node_id = match self.tcx.map.as_local_node_id(impl_def_id) {
+            Some(node_id) => node_id,
+            None => return None,
+        };
+        match self.tcx.map.expect_item(node_id).node {
+            hir::ItemImpl(_, _, _, _, ref self_ty, _) => Some(self_ty.span),
+            _ => None,
+        }
+    }
+
+    fn report_overlap_error(&self, overlap: traits::OverlapError) {
+        let impl_def_id = overlap.impl1;
+        let span = self.self_ty_span(impl_def_id)
+                       .unwrap_or_else(|| self.tcx.span_of_impl(impl_def_id).unwrap());
+        let mut err = struct_span_err!(
+            self.tcx.sess, span, E0119,
+            "{}:", self.conflict_description(&overlap));
+
+        err.note(&format!("this implementation is `{}`",
+                          self.impl_header_to_string(impl_def_id)));
+
+        let other_header = self.impl_header_to_string(overlap.impl2);
+        match self.tcx.span_of_impl(overlap.impl2) {
+            Ok(span) => {
+                let span = self.self_ty_span(overlap.impl2).unwrap_or(span);
+                let msg = format!("conflicting implementation is here: `{}`",
+                                  other_header);
+                err.span_note(span, &msg);
+            }
+            Err(cname) => {
+                err.note(&format!("conflicting implementation in crate `{}`: `{}`",
+                                  cname,
+                                  other_header));
+            }
+        }
+
+        if self.tcx.trait_impl_polarity(impl_def_id) !=
+            self.tcx.trait_impl_polarity(overlap.impl2) {
+            err.note("a type cannot both implement a trait and have a negative \
+                      implementation of it");
+        }
+
+        // Both impls apply to the trait reference the overlap
+        // check unified them to; anything left as `_` may be
+        // any type at all.
+        err.note(&format!("both implementations apply to `{}: {}`",
+                          overlap.self_desc.as_ref().map_or("_", |ty| &ty[..]),
+                          overlap.trait_desc));
+
+        // The impls only overlap because of impls other crates may add.
+        for cause in &overlap.intercrate_ambiguity_causes {
+            cause.add_intercrate_ambiguity_hint(&mut err);
+        }
+
+        err.emit();
+    }
+
     fn check_for_common_items_in_impls(&self, impl1: DefId, impl2: DefId) {
         #[derive(Copy, Clone, PartialEq)]
         enum Namespace { Type, Value }
@@ -138,38 +233,12 @@ impl<'cx, 'tcx,'v> intravisit::Visitor<'v> for OverlapChecker<'cx, 'tcx> {
                 // attempt to insert into the specialization graph
                 let insert_result = def.add_impl_for_specialization(self.tcx, impl_def_id);
 
-                // insertion failed due to overlap
+                // insertion failed due to overlap; this includes impls
+                // that only overlap once a downstream crate adds an impl,
+                // which have always been rejected, so they stay errors
+                // and merely get the reason attached as a note
                 if let Err(overlap) = insert_result {
-                    // only print the Self type if it has at least some outer
-                    // concrete shell; otherwise, it's not adding much
-                    // information.
-                    let self_type = {
-                        overlap.on_trait_ref.substs.self_ty().and_then(|ty| {
-                            if ty.has_concrete_skeleton() {
-                                Some(format!(" for type `{}`", ty))
-                            } else {
-                                None
-                            }
-                        }).unwrap_or(String::new())
-                    };
-
-                    let mut err = struct_span_err!(
-                        self.tcx.sess, self.tcx.span_of_impl(impl_def_id).unwrap(), E0119,
-                        "conflicting implementations of trait `{}`{}:",
-                        overlap.on_trait_ref,
-                        self_type);
-
-                    match self.tcx.span_of_impl(overlap.with_impl) {
-                        Ok(span) => {
-                            err.span_note(span, "conflicting implementation is here:");
-                        }
-                        Err(cname) => {
-                            err.note(&format!("conflicting implementation in crate `{}`",
-                                              cname));
-                        }
-                    }
-
-                    err.emit();
+                    self.report_overlap_error(overlap);
                 }
 
                 // check for overlap with the automatic `impl Trait for Trait`
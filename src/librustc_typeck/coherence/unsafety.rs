@@ -26,6 +26,7 @@ struct UnsafetyChecker<'cx, 'tcx:'cx> {
 
 impl<'cx, 'tcx, 'v> UnsafetyChecker<'cx, 'tcx> {
     fn check_unsafety_coherence(&mut self, item: &'v hir::Item,
+                                may_dangle: bool,
                                 unsafety: hir::Unsafety,
                                 polarity: hir::ImplPolarity) {
         match self.tcx.impl_trait_ref(self.tcx.map.local_def_id(item.id)) {
@@ -42,13 +43,28 @@ impl<'cx, 'tcx, 'v> UnsafetyChecker<'cx, 'tcx> {
 
             Some(trait_ref) => {
                 let trait_def = self.tcx.lookup_trait_def(trait_ref.def_id);
-                match (trait_def.unsafety, unsafety, polarity) {
+                // RFC 1327: `#[may_dangle]` on a parameter is an unchecked
+                // promise about the impl, so it makes the impl unsafe.
+                let trait_unsafety = if may_dangle {
+                    hir::Unsafety::Unsafe
+                } else {
+                    trait_def.unsafety
+                };
+                match (trait_unsafety, unsafety, polarity) {
                     (hir::Unsafety::Unsafe,
                      hir::Unsafety::Unsafe, hir::ImplPolarity::Negative) => {
                         span_err!(self.tcx.sess, item.span, E0198,
                                   "negative implementations are not unsafe");
                     }
 
+                    (hir::Unsafety::Unsafe,
+                     hir::Unsafety::Normal, hir::ImplPolarity::Positive) if may_dangle => {
+                        span_err!(self.tcx.sess, item.span, E0531,
+                                  "implementing the trait `{}` requires an `unsafe impl` \
+                                   declaration due to `#[may_dangle]` attribute",
+                                  trait_ref);
+                    }
+
                     (hir::Unsafety::Normal, hir::Unsafety::Unsafe, _) => {
                         span_err!(self.tcx.sess, item.span, E0199,
                                   "implementing the trait `{}` is not unsafe",
@@ -79,10 +95,11 @@ impl<'cx, 'tcx,'v> intravisit::Visitor<'v> for UnsafetyChecker<'cx, 'tcx> {
     fn visit_item(&mut self, item: &'v hir::Item) {
         match item.node {
             hir::ItemDefaultImpl(unsafety, _) => {
-                self.check_unsafety_coherence(item, unsafety, hir::ImplPolarity::Positive);
+                self.check_unsafety_coherence(item, false, unsafety, hir::ImplPolarity::Positive);
             }
-            hir::ItemImpl(unsafety, polarity, _, _, _, _) => {
-                self.check_unsafety_coherence(item, unsafety, polarity);
+            hir::ItemImpl(unsafety, polarity, ref generics, _, _, _) => {
+                self.check_unsafety_coherence(item, generics.carries_may_dangle(),
+                                              unsafety, polarity);
             }
             _ => { }
         }
@@ -64,9 +64,11 @@ use hir::def::Def;
 use hir::def_id::DefId;
 use constrained_type_params as ctp;
 use coherence;
+use middle::astconv_util::prohibit_projection;
 use middle::lang_items::SizedTraitLangItem;
 use middle::resolve_lifetime;
 use middle::const_val::ConstVal;
+use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc_const_eval::EvalHint::UncheckedExprHint;
 use rustc_const_eval::{eval_const_expr_partial, ConstEvalErr};
 use rustc_const_eval::ErrKind::ErroneousReferencedConstant;
@@ -370,20 +372,11 @@ impl<'a, 'tcx> AstConv<'tcx> for ItemCtxt<'a, 'tcx> {
                     _ty_param_def: Option<ty::TypeParameterDef<'tcx>>,
                     _substs: Option<&mut Substs<'tcx>>,
                     _space: Option<ParamSpace>,
-                    span: Span) -> Ty<'tcx> {
-        span_err!(self.tcx().sess, span, E0121,
+                    origin: TypeVariableOrigin) -> Ty<'tcx> {
+        span_err!(self.tcx().sess, origin.span(), E0121,
                   "the type placeholder `_` is not allowed within types on item signatures");
         self.tcx().types.err
     }
-
-    fn projected_ty(&self,
-                    _span: Span,
-                    trait_ref: ty::TraitRef<'tcx>,
-                    item_name: ast::Name)
-                    -> Ty<'tcx>
-    {
-        self.tcx().mk_projection(trait_ref, item_name)
-    }
 }
 
 /// Interface used to find the bounds on a type parameter from within
@@ -446,6 +439,7 @@ impl<'tcx> GetTypeParameterBounds<'tcx> for ty::GenericPredicates<'tcx> {
                     ty::Predicate::TypeOutlives(ref data) => {
                         data.skip_binder().0.is_param(def.space, def.index)
                     }
+                    ty::Predicate::NegativeTrait(..) |
                     ty::Predicate::Equate(..) |
                     ty::Predicate::RegionOutlives(..) |
                     ty::Predicate::WellFormed(..) |
@@ -884,6 +878,14 @@ fn convert_item(ccx: &CrateCtxt, it: &hir::Item) {
             tcx.trait_item_def_ids.borrow_mut().insert(ccx.tcx.map.local_def_id(it.id),
                                                        trait_item_def_ids);
         },
+        hir::ItemTraitAlias(..) => {
+            let trait_def = trait_def_of_item(ccx, it);
+            let def_id = trait_def.trait_ref.def_id;
+            let _: Result<(), ErrorReported> = // any error is already reported, can ignore
+                ccx.ensure_super_predicates(it.span, def_id);
+            convert_trait_predicates(ccx, it);
+            tcx.trait_item_def_ids.borrow_mut().insert(def_id, Rc::new(vec![]));
+        },
         hir::ItemStruct(ref struct_def, _) => {
             let (scheme, predicates) = convert_typed_item(ccx, it);
             write_ty_to_tcx(tcx, it.id, scheme.ty);
@@ -1165,7 +1167,8 @@ fn ensure_super_predicates_step(ccx: &CrateCtxt,
         };
 
         let (generics, bounds) = match item.node {
-            hir::ItemTrait(_, ref generics, ref supertraits, _) => (generics, supertraits),
+            hir::ItemTrait(_, ref generics, ref supertraits, _) |
+            hir::ItemTraitAlias(ref generics, ref supertraits) => (generics, supertraits),
             _ => span_bug!(item.span,
                            "ensure_super_predicates_step invoked on non-trait"),
         };
@@ -1231,8 +1234,11 @@ fn trait_def_of_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
         return def.clone();
     }
 
-    let (unsafety, generics, items) = match it.node {
-        hir::ItemTrait(unsafety, ref generics, _, ref items) => (unsafety, generics, items),
+    let (unsafety, generics, items, is_alias) = match it.node {
+        hir::ItemTrait(unsafety, ref generics, _, ref items) => {
+            (unsafety, generics, &items[..], false)
+        }
+        hir::ItemTraitAlias(ref generics, _) => (hir::Unsafety::Normal, generics, &[][..], true),
         _ => span_bug!(it.span, "trait_def_of_item invoked on non-trait"),
     };
 
@@ -1269,6 +1275,9 @@ fn trait_def_of_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                       ty_generics,
                                       trait_ref,
                                       associated_type_names);
+    if is_alias {
+        trait_def.set_is_alias();
+    }
 
     return tcx.intern_trait_def(trait_def);
 
@@ -1317,7 +1326,8 @@ fn trait_defines_associated_type_named(ccx: &CrateCtxt,
     };
 
     let trait_items = match item.node {
-        hir::ItemTrait(_, _, _, ref trait_items) => trait_items,
+        hir::ItemTrait(_, _, _, ref trait_items) => &trait_items[..],
+        hir::ItemTraitAlias(..) => &[],
         _ => bug!("trait_node_id {} is not a trait", trait_node_id)
     };
 
@@ -1336,7 +1346,8 @@ fn convert_trait_predicates<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>, it: &hir::Item)
     let def_id = ccx.tcx.map.local_def_id(it.id);
 
     let (generics, items) = match it.node {
-        hir::ItemTrait(_, ref generics, _, ref items) => (generics, items),
+        hir::ItemTrait(_, ref generics, _, ref items) => (generics, &items[..]),
+        hir::ItemTraitAlias(ref generics, _) => (generics, &[][..]),
         ref s => {
             span_bug!(
                 it.span,
@@ -1480,6 +1491,7 @@ fn compute_type_scheme_of_item<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
         }
         hir::ItemDefaultImpl(..) |
         hir::ItemTrait(..) |
+        hir::ItemTraitAlias(..) |
         hir::ItemImpl(..) |
         hir::ItemMod(..) |
         hir::ItemForeignMod(..) |
@@ -1519,6 +1531,7 @@ fn convert_typed_item<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
         }
         hir::ItemDefaultImpl(..) |
         hir::ItemTrait(..) |
+        hir::ItemTraitAlias(..) |
         hir::ItemExternCrate(..) |
         hir::ItemUse(..) |
         hir::ItemImpl(..) |
@@ -1660,6 +1673,7 @@ fn ty_generics_for_trait<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
         default_def_id: ccx.tcx.map.local_def_id(parent),
         default: None,
         object_lifetime_default: ty::ObjectLifetimeDefault::BaseDefault,
+        pure_wrt_drop: false,
     };
 
     ccx.tcx.ty_param_defs.borrow_mut().insert(param_id, def.clone());
@@ -1800,6 +1814,16 @@ fn ty_generic_predicates<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
 
                 for bound in bound_pred.bounds.iter() {
                     match bound {
+                        &hir::TyParamBound::TraitTyParamBound(
+                                ref poly_trait_ref, hir::TraitBoundModifier::Negative) => {
+                            let trait_ref =
+                                conv_negative_bound(&ccx.icx(&(base_predicates, ast_generics)),
+                                                    ty,
+                                                    poly_trait_ref);
+                            let predicate = trait_ref.to_poly_trait_predicate();
+                            result.predicates.push(space, ty::Predicate::NegativeTrait(predicate));
+                        }
+
                         &hir::TyParamBound::TraitTyParamBound(ref poly_trait_ref, _) => {
                             let mut projections = Vec::new();
 
@@ -1865,7 +1889,8 @@ fn ty_generics<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
                                            space: space,
                                            index: i as u32,
                                            def_id: ccx.tcx.map.local_def_id(l.lifetime.id),
-                                           bounds: bounds };
+                                           bounds: bounds,
+                                           pure_wrt_drop: l.pure_wrt_drop };
         result.regions.push(space, def);
     }
 
@@ -1949,6 +1974,7 @@ fn get_or_create_type_parameter_def<'a,'tcx>(ccx: &CrateCtxt<'a,'tcx>,
         default_def_id: ccx.tcx.map.local_def_id(parent),
         default: default,
         object_lifetime_default: object_lifetime_default,
+        pure_wrt_drop: param.pure_wrt_drop,
     };
 
     tcx.ty_param_defs.borrow_mut().insert(param.id, def.clone());
@@ -2079,6 +2105,10 @@ fn predicates_from_bound<'tcx>(astconv: &AstConv<'tcx>,
             let pred = ty::Binder(ty::OutlivesPredicate(param_ty, region));
             vec![ty::Predicate::TypeOutlives(pred)]
         }
+        hir::TraitTyParamBound(ref tr, hir::TraitBoundModifier::Negative) => {
+            let trait_ref = conv_negative_bound(astconv, param_ty, tr);
+            vec![ty::Predicate::NegativeTrait(trait_ref.to_poly_trait_predicate())]
+        }
         hir::TraitTyParamBound(_, hir::TraitBoundModifier::Maybe) => {
             Vec::new()
         }
@@ -2108,6 +2138,7 @@ fn conv_param_bounds<'a,'tcx>(astconv: &AstConv<'tcx>,
     let astconv::PartitionedBounds {
         builtin_bounds,
         trait_bounds,
+        negative_bounds,
         region_bounds
     } = astconv::partition_bounds(tcx, span, &ast_bounds);
 
@@ -2121,6 +2152,11 @@ fn conv_param_bounds<'a,'tcx>(astconv: &AstConv<'tcx>,
                                                      &mut projection_bounds))
                     .collect();
 
+    let negative_bounds: Vec<ty::PolyTraitRef> =
+        negative_bounds.iter()
+                       .map(|bound| conv_negative_bound(astconv, param_ty, *bound))
+                       .collect();
+
     let region_bounds: Vec<ty::Region> =
         region_bounds.into_iter()
                      .map(|r| ast_region_to_region(tcx, r))
@@ -2130,10 +2166,27 @@ fn conv_param_bounds<'a,'tcx>(astconv: &AstConv<'tcx>,
         region_bounds: region_bounds,
         builtin_bounds: builtin_bounds,
         trait_bounds: trait_bounds,
+        negative_bounds: negative_bounds,
         projection_bounds: projection_bounds,
     }
 }
 
+/// Converts the trait of a negative bound `!Trait`. It may not bind
+/// associated types: `T: !Iterator<Item=u32>` does not say whether `T`
+/// must not be an iterator at all, or just not one of `u32`s.
+fn conv_negative_bound<'tcx>(astconv: &AstConv<'tcx>,
+                             param_ty: Ty<'tcx>,
+                             trait_ref: &hir::PolyTraitRef)
+                             -> ty::PolyTraitRef<'tcx>
+{
+    let mut projections = Vec::new();
+    let poly_trait_ref = conv_poly_trait_ref(astconv, param_ty, trait_ref, &mut projections);
+    if !projections.is_empty() {
+        prohibit_projection(astconv.tcx(), trait_ref.span);
+    }
+    poly_trait_ref
+}
+
 fn compute_type_scheme_of_foreign_fn_decl<'a, 'tcx>(
     ccx: &CrateCtxt<'a, 'tcx>,
     id: DefId,
@@ -3706,6 +3706,42 @@ impl SpaceLlama for i32 {
 ```
 "##,
 
+E0526: r##"
+The operand of a `become` expression was not a call to a function or method.
+`become` replaces the current function's stack frame with that of the callee,
+so it can only be applied to a call. Erroneous code example:
+
+```compile_fail
+#![feature(explicit_tail_calls)]
+
+fn foo(x: u32) -> u32 {
+    become x + 1; // error: not a call
+}
+```
+
+Calls through closures and other overloaded calls are not supported either;
+call a function or method directly, or use `return` instead.
+"##,
+
+E0527: r##"
+The callee of a `become` expression has a different signature than the
+function it is called from. A tail call reuses the caller's stack frame, which
+is only possible if both functions take the same arguments, return the same
+type, and use the same ABI. Erroneous code example:
+
+```compile_fail
+#![feature(explicit_tail_calls)]
+
+fn bar(x: u32, y: u32) -> u32 { x + y }
+
+fn foo(x: u32) -> u32 {
+    become bar(x, 1); // error: `bar` takes two arguments, `foo` takes one
+}
+```
+
+Either change the signatures to agree, or use `return` instead of `become`.
+"##,
+
 }
 
 register_diagnostics! {
@@ -3782,5 +3818,9 @@ register_diagnostics! {
            // type `{}` was overridden
     E0436, // functional record update requires a struct
     E0513, // no type for local variable ..
-    E0521  // redundant default implementations of trait
+    E0521, // redundant default implementations of trait
+    E0528, // `Self` used as a value does not name a tuple or unit struct
+    E0529, // a trait alias cannot be implemented
+    E0530, // negative bounds cannot be used as closure or object bounds
+    E0531  // `#[may_dangle]` requires an `unsafe impl`
 }
@@ -142,6 +142,10 @@ pub struct CrateCtxt<'a, 'tcx: 'a> {
     /// error reporting, and so is lazily initialised and generally
     /// shouldn't taint the common path (hence the RefCell).
     pub all_traits: RefCell<Option<check::method::AllTraitsVec>>,
+    /// The traits in `all_traits` defining each item name, read from
+    /// the metadata of external traits. Like `all_traits`, this is
+    /// only built once an item can't be found.
+    pub trait_item_index: RefCell<Option<check::method::TraitItemIndex>>,
     pub tcx: &'a TyCtxt<'tcx>,
 }
 
@@ -334,6 +338,7 @@ pub fn check_crate(tcx: &TyCtxt, trait_map: hir::TraitMap) -> CompileResult {
     let ccx = CrateCtxt {
         trait_map: trait_map,
         all_traits: RefCell::new(None),
+        trait_item_index: RefCell::new(None),
         tcx: tcx
     };
 
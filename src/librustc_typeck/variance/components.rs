@@ -0,0 +1,133 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Finding the items whose variances are inferred together
+//!
+//! The variances of an item's parameters depend on the variances of the
+//! items its definition refers to. When the variances of one item are
+//! requested, we therefore also infer them for every local item it
+//! (transitively) refers to whose variances are not known yet. Items
+//! that refer to each other form a cycle and must be solved as a single
+//! system; all other items are solved on their own. The cycles are
+//! found with Tarjan's strongly connected components algorithm, which
+//! conveniently yields each component only after all the components it
+//! depends on, so every component is solved against the final variances
+//! of the items it uses.
+
+use rustc::ty::{self, TyCtxt};
+use std::cmp;
+use syntax::ast;
+use rustc::hir;
+use util::nodemap::{NodeMap, NodeSet};
+
+/// Returns the groups of items whose variances must be inferred before
+/// those of `item_id` are known, dependencies first. `item_id` is part of
+/// the last group.
+pub fn components_to_infer(tcx: &TyCtxt, item_id: ast::NodeId) -> Vec<Vec<ast::NodeId>> {
+    let mut finder = ComponentFinder {
+        tcx: tcx,
+        dfs_index: NodeMap(),
+        lowlink: NodeMap(),
+        stack: Vec::new(),
+        on_stack: NodeSet(),
+        components: Vec::new(),
+    };
+    finder.visit(item_id);
+    finder.components
+}
+
+struct ComponentFinder<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
+
+    // The order in which each item was first reached.
+    dfs_index: NodeMap<usize>,
+
+    // The smallest `dfs_index` reachable from each item through items
+    // that are still on the stack.
+    lowlink: NodeMap<usize>,
+
+    // Items reached whose component has not been completed yet.
+    stack: Vec<ast::NodeId>,
+    on_stack: NodeSet,
+
+    components: Vec<Vec<ast::NodeId>>,
+}
+
+impl<'a, 'tcx> ComponentFinder<'a, 'tcx> {
+    fn visit(&mut self, item_id: ast::NodeId) {
+        let index = self.dfs_index.len();
+        self.dfs_index.insert(item_id, index);
+        self.lowlink.insert(item_id, index);
+        self.stack.push(item_id);
+        self.on_stack.insert(item_id);
+
+        for dependency in self.dependencies(item_id) {
+            let reachable = if !self.dfs_index.contains_key(&dependency) {
+                self.visit(dependency);
+                self.lowlink[&dependency]
+            } else if self.on_stack.contains(&dependency) {
+                self.dfs_index[&dependency]
+            } else {
+                // Belongs to a component that is already complete.
+                continue;
+            };
+            let lowlink = cmp::min(self.lowlink[&item_id], reachable);
+            self.lowlink.insert(item_id, lowlink);
+        }
+
+        if self.lowlink[&item_id] == self.dfs_index[&item_id] {
+            let mut component = vec![];
+            loop {
+                let member = self.stack.pop().unwrap();
+                self.on_stack.remove(&member);
+                component.push(member);
+                if member == item_id {
+                    break;
+                }
+            }
+            debug!("variance component: {:?}", component);
+            self.components.push(component);
+        }
+    }
+
+    /// The local items mentioned in the definition of `item_id` whose
+    /// variances have not been inferred yet.
+    fn dependencies(&self, item_id: ast::NodeId) -> Vec<ast::NodeId> {
+        let tcx = self.tcx;
+        let did = tcx.map.local_def_id(item_id);
+
+        let tys: Vec<_> = match tcx.map.expect_item(item_id).node {
+            hir::ItemEnum(..) | hir::ItemStruct(..) => {
+                tcx.lookup_adt_def(did).all_fields().map(|f| f.unsubst_ty()).collect()
+            }
+            hir::ItemTrait(..) | hir::ItemTraitAlias(..) => {
+                tcx.lookup_trait_def(did).trait_ref.substs.types.iter().cloned().collect()
+            }
+            _ => vec![]
+        };
+
+        let mut dependencies = vec![];
+        for ty in tys.iter().flat_map(|ty| ty.walk()) {
+            let referenced = match ty.sty {
+                ty::TyEnum(def, _) | ty::TyStruct(def, _) => def.did,
+                ty::TyProjection(ref data) => data.trait_ref.def_id,
+                ty::TyTrait(ref data) => data.principal_def_id(),
+                _ => continue
+            };
+            if let Some(node_id) = tcx.map.as_local_node_id(referenced) {
+                if !tcx.item_variance_map.borrow().contains_key(&referenced) &&
+                   !dependencies.contains(&node_id) {
+                    dependencies.push(node_id);
+                }
+            }
+        }
+        dependencies
+    }
+}
@@ -11,7 +11,8 @@
 //! Constraint construction and representation
 //!
 //! The second pass over the AST determines the set of constraints.
-//! We walk the set of items and, for each member, generate new constraints.
+//! We walk the items being inferred and, for each member, generate new
+//! constraints.
 
 use dep_graph::DepTrackingMapConfig;
 use hir::def_id::DefId;
@@ -50,7 +51,8 @@ pub struct Constraint<'a> {
     pub variance: &'a VarianceTerm<'a>,
 }
 
-pub fn add_constraints_from_crate<'a, 'tcx>(terms_cx: TermsContext<'a, 'tcx>)
+pub fn add_constraints_from_items<'a, 'tcx>(terms_cx: TermsContext<'a, 'tcx>,
+                                            items: &[ast::NodeId])
                                             -> ConstraintContext<'a, 'tcx>
 {
     let tcx = terms_cx.tcx;
@@ -68,8 +70,11 @@ pub fn add_constraints_from_crate<'a, 'tcx>(terms_cx: TermsContext<'a, 'tcx>)
     };
 
     // See README.md for a discussion on dep-graph management.
-    tcx.visit_all_items_in_krate(|def_id| ItemVariances::to_dep_node(&def_id),
-                                 &mut constraint_cx);
+    for &item_id in items {
+        let def_id = tcx.map.local_def_id(item_id);
+        let _task = tcx.dep_graph.in_task(ItemVariances::to_dep_node(&def_id));
+        constraint_cx.visit_item(tcx.map.expect_item(item_id));
+    }
 
     constraint_cx
 }
@@ -97,7 +102,7 @@ impl<'a, 'tcx, 'v> Visitor<'v> for ConstraintContext<'a, 'tcx> {
                                                  self.covariant);
                 }
             }
-            hir::ItemTrait(..) => {
+            hir::ItemTrait(..) | hir::ItemTraitAlias(..) => {
                 let trait_def = tcx.lookup_trait_def(did);
                 self.add_constraints_from_trait_ref(&trait_def.generics,
                                                     trait_def.trait_ref,
@@ -187,6 +192,7 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                         hir::ItemTy(..) |
                         hir::ItemEnum(..) |
                         hir::ItemStruct(..) |
+                        hir::ItemTraitAlias(..) |
                         hir::ItemTrait(..)   => is_inferred = true,
                         hir::ItemFn(..)      => is_inferred = false,
                         _                    => cannot_happen!(),
@@ -216,15 +222,17 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                          -> VarianceTermPtr<'a> {
         assert_eq!(param_def_id.krate, item_def_id.krate);
 
-        if let Some(param_node_id) = self.tcx().map.as_local_node_id(param_def_id) {
-            // Parameter on an item defined within current crate:
-            // variance not yet inferred, so return a symbolic
+        let param_node_id = self.tcx().map.as_local_node_id(param_def_id);
+        let inferred = param_node_id.and_then(|id| self.terms_cx.inferred_map.get(&id));
+        if let Some(&InferredIndex(index)) = inferred {
+            // Parameter on an item whose variances are being inferred
+            // along with ours: not known yet, so return a symbolic
             // variance.
-            let InferredIndex(index) = self.inferred_index(param_node_id);
             self.terms_cx.inferred_infos[index].term
         } else {
-            // Parameter on an item defined within another crate:
-            // variance already inferred, just look it up.
+            // Parameter on an item defined within another crate, or on
+            // a local item that does not depend on us: variance already
+            // inferred, just look it up.
             let variances = self.tcx().item_variances(item_def_id);
             let variance = match kind {
                 TypeParam => *variances.types.get(space, index),
@@ -438,7 +446,7 @@ impl<'a, 'tcx> ConstraintContext<'a, 'tcx> {
                 // types, where we use TyError as the Self type
             }
 
-            ty::TyInfer(..) => {
+            ty::TyBound(..) | ty::TyInfer(..) => {
                 bug!("unexpected type encountered in \
                       variance inference: {}", ty);
             }
@@ -12,12 +12,18 @@
 //! parameters. See README.md for details.
 
 use arena;
+use hir::def_id::DefId;
+use rustc::hir;
+use rustc::hir::intravisit::Visitor;
 use rustc::ty::TyCtxt;
 
 /// Defines the `TermsContext` basically houses an arena where we can
 /// allocate terms.
 mod terms;
 
+/// Code to find the groups of items whose variances are inferred together.
+mod components;
+
 /// Code to gather up constraints.
 mod constraints;
 
@@ -27,11 +33,42 @@ mod solve;
 /// Code for transforming variances.
 mod xform;
 
+/// Makes variances available. Nothing is inferred up front: the variances
+/// of a local item are computed by `variances_of` the first time they are
+/// requested through `tcx.item_variances`.
 pub fn infer_variance(tcx: &TyCtxt) {
-    let mut arena = arena::TypedArena::new();
-    let terms_cx = terms::determine_parameters_to_be_inferred(tcx, &mut arena);
-    let constraints_cx = constraints::add_constraints_from_crate(terms_cx);
-    solve::solve_constraints(constraints_cx);
+    tcx.variance_provider.set(Some(variances_of));
     tcx.variance_computed.set(true);
+
+    // For unit testing: check for a special "rustc_variance"
+    // attribute and report an error with various results if found.
+    tcx.map.krate().visit_all_items(&mut VarianceTest { tcx: tcx });
+}
+
+/// Infers the variances of the local item `item_def_id`, along with those
+/// of the local items it refers to that are not known yet.
+fn variances_of(tcx: &TyCtxt, item_def_id: DefId) {
+    let item_id = tcx.map.as_local_node_id(item_def_id).unwrap();
+    debug!("variances_of(item={})", tcx.item_path_str(item_def_id));
+
+    for component in components::components_to_infer(tcx, item_id) {
+        let mut arena = arena::TypedArena::new();
+        let terms_cx = terms::determine_parameters_to_be_inferred(tcx, &mut arena, &component);
+        let constraints_cx = constraints::add_constraints_from_items(terms_cx, &component);
+        solve::solve_constraints(constraints_cx);
+    }
+}
+
+struct VarianceTest<'a, 'tcx: 'a> {
+    tcx: &'a TyCtxt<'tcx>,
 }
 
+impl<'a, 'tcx, 'v> Visitor<'v> for VarianceTest<'a, 'tcx> {
+    fn visit_item(&mut self, item: &hir::Item) {
+        let item_def_id = self.tcx.map.local_def_id(item.id);
+        if self.tcx.has_attr(item_def_id, "rustc_variance") {
+            let item_variances = self.tcx.item_variances(item_def_id);
+            span_err!(self.tcx.sess, item.span, E0208, "{:?}", item_variances);
+        }
+    }
+}
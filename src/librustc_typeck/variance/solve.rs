@@ -134,13 +134,6 @@ impl<'a, 'tcx> SolveContext<'a, 'tcx> {
                     item_variances);
 
             let item_def_id = tcx.map.local_def_id(item_id);
-
-            // For unit testing: check for a special "rustc_variance"
-            // attribute and report an error with various results if found.
-            if tcx.has_attr(item_def_id, "rustc_variance") {
-                span_err!(tcx.sess, tcx.map.span(item_id), E0208, "{:?}", item_variances);
-            }
-
             let newly_added = tcx.item_variance_map.borrow_mut()
                                  .insert(item_def_id, Rc::new(item_variances)).is_none();
             assert!(newly_added);
@@ -56,7 +56,7 @@ impl<'a> fmt::Debug for VarianceTerm<'a> {
     }
 }
 
-// The first pass over the crate simply builds up the set of inferreds.
+// The first pass over the items simply builds up the set of inferreds.
 
 pub struct TermsContext<'a, 'tcx: 'a> {
     pub tcx: &'a TyCtxt<'tcx>,
@@ -99,7 +99,8 @@ pub struct InferredInfo<'a> {
 
 pub fn determine_parameters_to_be_inferred<'a, 'tcx>(
     tcx: &'a TyCtxt<'tcx>,
-    arena: &'a mut TypedArena<VarianceTerm<'a>>)
+    arena: &'a mut TypedArena<VarianceTerm<'a>>,
+    items: &[ast::NodeId])
     -> TermsContext<'a, 'tcx>
 {
     let mut terms_cx = TermsContext {
@@ -119,8 +120,11 @@ pub fn determine_parameters_to_be_inferred<'a, 'tcx>(
     };
 
     // See README.md for a discussion on dep-graph management.
-    tcx.visit_all_items_in_krate(|def_id| ItemVariances::to_dep_node(&def_id),
-                                 &mut terms_cx);
+    for &item_id in items {
+        let def_id = tcx.map.local_def_id(item_id);
+        let _task = tcx.dep_graph.in_task(ItemVariances::to_dep_node(&def_id));
+        terms_cx.visit_item(tcx.map.expect_item(item_id));
+    }
 
     terms_cx
 }
@@ -263,7 +267,8 @@ impl<'a, 'tcx, 'v> Visitor<'v> for TermsContext<'a, 'tcx> {
             hir::ItemStruct(_, ref generics) => {
                 self.add_inferreds_for_item(item.id, false, generics);
             }
-            hir::ItemTrait(_, ref generics, _, _) => {
+            hir::ItemTrait(_, ref generics, _, _) |
+            hir::ItemTraitAlias(ref generics, _) => {
                 // Note: all inputs for traits are ultimately
                 // constrained to be invariant. See `visit_item` in
                 // the impl for `ConstraintContext` in `constraints.rs`.
@@ -230,13 +230,10 @@ fn build_type(cx: &DocContext, tcx: &TyCtxt, did: DefId) -> clean::ItemEnum {
 pub fn build_impls(cx: &DocContext,
                    tcx: &TyCtxt,
                    did: DefId) -> Vec<clean::Item> {
-    tcx.populate_inherent_implementations_for_type_if_necessary(did);
     let mut impls = Vec::new();
 
-    if let Some(i) = tcx.inherent_impls.borrow().get(&did) {
-        for &did in i.iter() {
-            build_impl(cx, tcx, did, &mut impls);
-        }
+    for &did in tcx.inherent_impls_of(did).iter() {
+        build_impl(cx, tcx, did, &mut impls);
     }
 
     // If this is the first time we've inlined something from this crate, then
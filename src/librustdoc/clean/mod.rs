@@ -843,6 +843,17 @@ impl<'a> Clean<WherePredicate> for ty::Predicate<'a> {
 
         match *self {
             Predicate::Trait(ref pred) => pred.clean(cx),
+            Predicate::NegativeTrait(ref pred) => {
+                let mut cleaned = pred.clean(cx);
+                if let WherePredicate::BoundPredicate { ref mut bounds, .. } = cleaned {
+                    for bound in bounds {
+                        if let TyParamBound::TraitBound(_, ref mut modifier) = *bound {
+                            *modifier = hir::TraitBoundModifier::Negative;
+                        }
+                    }
+                }
+                cleaned
+            }
             Predicate::Equate(ref pred) => pred.clean(cx),
             Predicate::RegionOutlives(ref pred) => pred.clean(cx),
             Predicate::TypeOutlives(ref pred) => pred.clean(cx),
@@ -1736,6 +1747,7 @@ impl<'tcx> Clean<Type> for ty::Ty<'tcx> {
             ty::TyProjection(ref data) => data.clean(cx),
 
             ty::TyParam(ref p) => Generic(p.name.to_string()),
+            ty::TyBound(_, ref bt) => Generic(bt.name.to_string()),
 
             ty::TyClosure(..) => Tuple(vec![]), // FIXME(pcwalton)
 
@@ -204,6 +204,7 @@ impl fmt::Display for clean::TyParamBound {
                 let modifier_str = match modifier {
                     hir::TraitBoundModifier::None => "",
                     hir::TraitBoundModifier::Maybe => "?",
+                    hir::TraitBoundModifier::Negative => "!",
                 };
                 write!(f, "{}{}", modifier_str, *ty)
             }
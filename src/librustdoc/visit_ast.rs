@@ -441,6 +441,8 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
             hir::ItemForeignMod(ref fm) => {
                 om.foreigns.push(fm.clone());
             }
+            // FIXME: trait aliases are not documented yet.
+            hir::ItemTraitAlias(..) => {}
         }
     }
 
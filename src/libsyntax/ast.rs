@@ -175,6 +175,7 @@ impl fmt::Debug for Lifetime {
 /// A lifetime definition, eg `'a: 'b+'c+'d`
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub struct LifetimeDef {
+    pub attrs: ThinAttributes,
     pub lifetime: Lifetime,
     pub bounds: Vec<Lifetime>
 }
@@ -377,18 +378,20 @@ pub enum TyParamBound {
     RegionTyParamBound(Lifetime)
 }
 
-/// A modifier on a bound, currently this is only used for `?Sized`, where the
-/// modifier is `Maybe`. Negative bounds should also be handled here.
+/// A modifier on a bound: `Maybe` for `?Sized`, and `Negative` for
+/// `!Trait`, which requires that the trait is not implemented.
 #[derive(Copy, Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub enum TraitBoundModifier {
     None,
     Maybe,
+    Negative,
 }
 
 pub type TyParamBounds = P<[TyParamBound]>;
 
 #[derive(Clone, PartialEq, Eq, RustcEncodable, RustcDecodable, Hash, Debug)]
 pub struct TyParam {
+    pub attrs: ThinAttributes,
     pub ident: Ident,
     pub id: NodeId,
     pub bounds: TyParamBounds,
@@ -1022,6 +1025,8 @@ pub enum ExprKind {
     Again(Option<SpannedIdent>),
     /// A `return`, with an optional value to be returned
     Ret(Option<P<Expr>>),
+    /// A `become`, i.e. a guaranteed tail call
+    Become(P<Expr>),
 
     /// Output of the `asm!()` macro
     InlineAsm(InlineAsm),
@@ -1996,6 +2001,8 @@ pub enum ItemKind {
               Generics,
               TyParamBounds,
               Vec<TraitItem>),
+    /// A trait alias, e.g. `trait Foo = Bar + Quux;`
+    TraitAlias(Generics, TyParamBounds),
 
     // Default trait implementations
     ///
@@ -2026,6 +2033,7 @@ impl ItemKind {
             ItemKind::Enum(..) => "enum",
             ItemKind::Struct(..) => "struct",
             ItemKind::Trait(..) => "trait",
+            ItemKind::TraitAlias(..) => "trait alias",
             ItemKind::Mac(..) |
             ItemKind::Impl(..) |
             ItemKind::DefaultImpl(..) => "item"
@@ -356,6 +356,38 @@ pub fn find_inline_attr(diagnostic: Option<&Handler>, attrs: &[Attribute]) -> In
     })
 }
 
+#[derive(Copy, Clone, PartialEq)]
+pub enum OptimizeAttr {
+    None,
+    Size,
+}
+
+/// Determine what `#[optimize]` attribute is present in `attrs`, if any.
+pub fn find_optimize_attr(diagnostic: Option<&Handler>, attrs: &[Attribute]) -> OptimizeAttr {
+    attrs.iter().fold(OptimizeAttr::None, |oa, attr| {
+        match attr.node.value.node {
+            MetaItemKind::List(ref n, ref items) if *n == "optimize" => {
+                mark_used(attr);
+                if items.len() != 1 {
+                    diagnostic.map(|d|{ d.span_err(attr.span, "expected one argument"); });
+                    OptimizeAttr::None
+                } else if contains_name(&items[..], "size") {
+                    OptimizeAttr::Size
+                } else {
+                    diagnostic.map(|d|{ d.span_err((*items[0]).span, "invalid argument"); });
+                    OptimizeAttr::None
+                }
+            }
+            MetaItemKind::Word(ref n) | MetaItemKind::NameValue(ref n, _) if *n == "optimize" => {
+                mark_used(attr);
+                diagnostic.map(|d|{ d.span_err(attr.span, "expected one argument"); });
+                OptimizeAttr::None
+            }
+            _ => oa
+        }
+    })
+}
+
 /// True if `#[inline]` or `#[inline(always)]` is present in `attrs`.
 pub fn requests_inline(attrs: &[Attribute]) -> bool {
     match find_inline_attr(None, attrs) {
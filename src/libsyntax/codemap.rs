@@ -20,6 +20,7 @@
 pub use self::ExpnFormat::*;
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ops::{Add, Sub};
 use std::path::Path;
 use std::rc::Rc;
@@ -424,6 +425,18 @@ pub struct NameAndSpan {
     /// features internally without forcing the whole crate to opt-in
     /// to them.
     pub allow_internal_unstable: bool,
+    /// Whether lints triggered inside the expansion are allowed when
+    /// the macro is used from another crate, since its users cannot
+    /// fix them (`#[allow_internal_lints]`).
+    pub allow_internal_lints: bool,
+    /// Whether `unsafe` blocks written inside the macro are allowed to
+    /// cover the macro's own code without the caller opting in, and
+    /// without covering the code passed in as arguments
+    /// (`#[allow_internal_unsafe]`).
+    pub allow_internal_unsafe: bool,
+    /// The crate the macro was defined in, if it was imported from
+    /// another crate.
+    pub def_crate: Option<Name>,
     /// The span of the macro definition itself. The macro may not
     /// have a sensible definition span (e.g. something defined
     /// completely inside libsyntax) in which case this is None.
@@ -732,14 +745,28 @@ impl FileLoader for RealFileLoader {
 pub struct CodeMap {
     pub files: RefCell<Vec<Rc<FileMap>>>,
     expansions: RefCell<Vec<ExpnInfo>>,
+    expanded_tokens: RefCell<ExpandedTokens>,
     file_loader: Box<FileLoader>
 }
 
+/// Where the tokens passed in to macros ended up in their expansions.
+/// Lets tools map a span found in expanded code back to the token the
+/// user wrote, and the other way around. Only filled in when the
+/// expansion config asks for it (`record_expanded_tokens`).
+#[derive(Default)]
+struct ExpandedTokens {
+    /// From a span in the output of a macro to the token it was copied from.
+    sources: HashMap<Span, Span>,
+    /// From a token passed in to a macro to every span it was copied to.
+    expansions: HashMap<Span, Vec<Span>>,
+}
+
 impl CodeMap {
     pub fn new() -> CodeMap {
         CodeMap {
             files: RefCell::new(Vec::new()),
             expansions: RefCell::new(Vec::new()),
+            expanded_tokens: RefCell::new(ExpandedTokens::default()),
             file_loader: Box::new(RealFileLoader)
         }
     }
@@ -748,6 +775,7 @@ impl CodeMap {
         CodeMap {
             files: RefCell::new(Vec::new()),
             expansions: RefCell::new(Vec::new()),
+            expanded_tokens: RefCell::new(ExpandedTokens::default()),
             file_loader: file_loader
         }
     }
@@ -1348,6 +1376,39 @@ impl CodeMap {
         }
     }
 
+    /// Record that the token at `source`, passed in to a macro, was
+    /// copied to `expanded` in the output of that macro.
+    pub fn record_expanded_token(&self, source: Span, expanded: Span) {
+        let mut tokens = self.expanded_tokens.borrow_mut();
+        if tokens.sources.insert(expanded, source).is_none() {
+            tokens.expansions.entry(source).or_insert_with(Vec::new).push(expanded);
+        }
+    }
+
+    /// If `span` is in the output of a macro and was copied there from
+    /// the macro's arguments, returns the span of the token it was
+    /// copied from. Tokens forwarded through nested macros map straight
+    /// back to where they were first written.
+    pub fn source_token(&self, span: Span) -> Option<Span> {
+        self.expanded_tokens.borrow().sources.get(&span).cloned()
+    }
+
+    /// The inverse of `source_token`: every span in the output of macros
+    /// that the token at `span` was copied to.
+    pub fn expanded_tokens(&self, span: Span) -> Vec<Span> {
+        self.expanded_tokens.borrow().expansions.get(&span).cloned().unwrap_or(vec![])
+    }
+
+    /// Every recorded `(expanded, source)` pair, in source order.
+    pub fn expanded_token_sources(&self) -> Vec<(Span, Span)> {
+        let tokens = self.expanded_tokens.borrow();
+        let mut pairs: Vec<_> = tokens.sources.iter().map(|(&expanded, &source)| {
+            (expanded, source)
+        }).collect();
+        pairs.sort_by_key(|&(sp, _)| (sp.lo, sp.hi, sp.expn_id.into_u32()));
+        pairs
+    }
+
     /// Check if a span is "internal" to a macro in which #[unstable]
     /// items can be used (that is, a macro marked with
     /// `#[allow_internal_unstable]`).
@@ -1391,6 +1452,78 @@ impl CodeMap {
         allows_unstable
     }
 
+    /// Returns the innermost expansion that `span` actually comes from,
+    /// or `NO_EXPANSION` if it was written outside of any macro. Every
+    /// span in the output of a macro is marked with that expansion,
+    /// including the tokens the user passed in as arguments, so as in
+    /// `span_allows_unstable` this looks at where the span points
+    /// rather than trusting its `expn_id`.
+    pub fn source_expn_id(&self, span: Span) -> ExpnId {
+        let mut expn_id = span.expn_id;
+        loop {
+            let call_site = self.with_expn_info(expn_id, |expninfo| {
+                expninfo.and_then(|info| {
+                    let span_comes_from_this_expansion =
+                        info.callee.span.map_or(span.source_equal(&info.call_site),
+                                                |mac_span| mac_span.contains(span));
+                    if span_comes_from_this_expansion {
+                        None
+                    } else {
+                        Some(info.call_site.expn_id)
+                    }
+                })
+            });
+            match call_site {
+                Some(id) => expn_id = id,
+                None => return expn_id,
+            }
+        }
+    }
+
+    /// If `span` comes from the expansion of a macro that was imported
+    /// from another crate, returns that expansion. Only the innermost
+    /// expansion `span` actually comes from counts, so tokens that the
+    /// user passed in as macro arguments are not considered foreign.
+    pub fn foreign_macro_expansion(&self, span: Span) -> Option<(Span, NameAndSpan)> {
+        self.with_expn_info(self.source_expn_id(span), |expninfo| {
+            expninfo.and_then(|info| {
+                if info.callee.def_crate.is_some() {
+                    Some((info.call_site, info.callee.clone()))
+                } else {
+                    None
+                }
+            })
+        })
+    }
+
+    /// Check if a span comes from a macro marked with
+    /// `#[allow_internal_unsafe]`.
+    pub fn span_allows_internal_unsafe(&self, span: Span) -> bool {
+        self.with_expn_info(self.source_expn_id(span), |expninfo| {
+            expninfo.map_or(false, |info| info.callee.allow_internal_unsafe)
+        })
+    }
+
+    /// Check if `span` was written inside the macro of the expansion
+    /// `expn_id`, either directly or in the expansion of another macro
+    /// invoked from there. Tokens passed in to that macro by its
+    /// caller do not count.
+    pub fn span_is_within_expansion(&self, span: Span, expn_id: ExpnId) -> bool {
+        let mut current = self.source_expn_id(span);
+        loop {
+            if current == expn_id {
+                return true;
+            }
+            let call_site = self.with_expn_info(current, |expninfo| {
+                expninfo.map(|info| info.call_site)
+            });
+            match call_site {
+                Some(call_site) => current = self.source_expn_id(call_site),
+                None => return false,
+            }
+        }
+    }
+
     pub fn count_lines(&self) -> usize {
         self.files.borrow().iter().fold(0, |a, f| a + f.count_lines())
     }
@@ -1662,6 +1795,9 @@ mod tests {
         let format = ExpnFormat::MacroBang(Name(0u32));
         let callee = NameAndSpan { format: format,
                                    allow_internal_unstable: false,
+                                   allow_internal_lints: false,
+                                   allow_internal_unsafe: false,
+                                   def_crate: None,
                                    span: None };
 
         let info = ExpnInfo { call_site: root, callee: callee };
@@ -1684,6 +1820,9 @@ mod tests {
         let format_root = ExpnFormat::MacroBang(Name(0u32));
         let callee_root = NameAndSpan { format: format_root,
                                         allow_internal_unstable: false,
+                                        allow_internal_lints: false,
+                                        allow_internal_unsafe: false,
+                                        def_crate: None,
                                         span: Some(root) };
 
         let info_a1 = ExpnInfo { call_site: root, callee: callee_root };
@@ -1693,6 +1832,9 @@ mod tests {
         let format_a = ExpnFormat::MacroBang(Name(1u32));
         let callee_a = NameAndSpan { format: format_a,
                                       allow_internal_unstable: false,
+                                      allow_internal_lints: false,
+                                      allow_internal_unsafe: false,
+                                      def_crate: None,
                                       span: Some(span_a1) };
 
         let info_a2 = ExpnInfo { call_site: span_a1, callee: callee_a.clone() };
@@ -1706,6 +1848,9 @@ mod tests {
         let format_b = ExpnFormat::MacroBang(Name(2u32));
         let callee_b = NameAndSpan { format: format_b,
                                      allow_internal_unstable: false,
+                                     allow_internal_lints: false,
+                                     allow_internal_unsafe: false,
+                                     def_crate: None,
                                      span: None };
 
         let info_b2 = ExpnInfo { call_site: span_b1, callee: callee_b.clone() };
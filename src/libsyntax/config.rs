@@ -14,11 +14,14 @@ use feature_gate::GatedCfgAttr;
 use fold::Folder;
 use {ast, fold, attr};
 use visit;
-use codemap::{Spanned, respan};
+use codemap::{Span, Spanned, respan};
+use parse::token::{self, InternedString};
 use ptr::P;
 
 use util::small_vector::SmallVector;
 
+use std::collections::HashMap;
+
 /// A folder that strips out items that do not belong in the current
 /// configuration.
 struct Context<'a, F> where F: FnMut(&[ast::Attribute]) -> bool {
@@ -459,6 +462,188 @@ impl<'v, 'a, 'b> visit::Visitor<'v> for StmtExprAttrFeatureVisitor<'a, 'b> {
     }
 }
 
+/// The names mentioned by code that is configured away only because of
+/// whether the crate is built with `--test`: `#[cfg(test)]` code in a
+/// normal build, or `#[cfg(not(test))]` code in a test build. That code
+/// is never resolved, so where a name is mentioned is all there is to
+/// tell which items it may be using.
+#[derive(Default)]
+pub struct OtherTestConfigNames {
+    uses: HashMap<ast::Name, Vec<NameUse>>,
+}
+
+struct NameUse {
+    /// The modules leading from the crate root to the code.
+    module: Vec<ast::Name>,
+    /// The segment before the name, if it is mentioned in a path
+    /// `qualifier::name`.
+    qualifier: Option<ast::Name>,
+}
+
+impl OtherTestConfigNames {
+    /// Whether the code may be using an item named `name`, given the
+    /// modules `module` leading from the crate root to the item and the
+    /// name of the enum, struct, trait or type the item belongs to, if
+    /// any. This holds if the code mentions `name` within the module of
+    /// the item, or in a path through that module or through `parent`.
+    pub fn may_use(&self, name: ast::Name, module: &[ast::Name], parent: Option<ast::Name>)
+                   -> bool {
+        self.uses.get(&name).map_or(false, |uses| {
+            uses.iter().any(|name_use| {
+                name_use.module.starts_with(module) ||
+                    name_use.qualifier.is_some() &&
+                    (name_use.qualifier == module.last().cloned() ||
+                     name_use.qualifier == parent)
+            })
+        })
+    }
+}
+
+/// Collects the names mentioned by code that is configured away only
+/// because of whether the crate is built with `--test`, so that the dead
+/// code lint can tell which items are used by the other configuration.
+/// It runs before configuration, which strips that code.
+pub fn other_test_config_names(krate: &ast::Crate) -> OtherTestConfigNames {
+    let other_config = if attr::contains_name(&krate.config, "test") {
+        krate.config.iter().filter(|mi| mi.name() != "test").cloned().collect()
+    } else {
+        let mut config = krate.config.clone();
+        config.push(attr::mk_word_item(InternedString::new("test")));
+        config
+    };
+    let mut v = OtherTestConfigNameCollector {
+        config: &krate.config,
+        other_config: &other_config,
+        in_other_config_code: false,
+        module: vec![],
+        names: OtherTestConfigNames::default(),
+    };
+    visit::walk_crate(&mut v, krate);
+    v.names
+}
+
+struct OtherTestConfigNameCollector<'a> {
+    config: &'a ast::CrateConfig,
+    other_config: &'a ast::CrateConfig,
+    in_other_config_code: bool,
+    module: Vec<ast::Name>,
+    names: OtherTestConfigNames,
+}
+
+impl<'a> OtherTestConfigNameCollector<'a> {
+    fn visit_configured<F>(&mut self, attrs: &[ast::Attribute], walk: F)
+        where F: FnOnce(&mut Self)
+    {
+        if self.in_other_config_code || node_survives_cfg(attrs, self.config) {
+            walk(self);
+        } else if node_survives_cfg(attrs, self.other_config) {
+            self.in_other_config_code = true;
+            walk(self);
+            self.in_other_config_code = false;
+        }
+    }
+
+    fn record(&mut self, name: ast::Name, qualifier: Option<ast::Name>) {
+        if self.in_other_config_code {
+            let name_use = NameUse { module: self.module.clone(), qualifier: qualifier };
+            self.names.uses.entry(name).or_insert(vec![]).push(name_use);
+        }
+    }
+
+    fn collect_tts(&mut self, tts: &[ast::TokenTree]) {
+        // The identifier before a `::`, which qualifies the next one.
+        let mut qualifier = None;
+        let mut last_ident = None;
+        for tt in tts {
+            match *tt {
+                ast::TokenTree::Token(_, token::Ident(ident, _)) => {
+                    self.record(ident.name, qualifier.take());
+                    last_ident = Some(ident.name);
+                    continue;
+                }
+                ast::TokenTree::Token(_, token::ModSep) => {
+                    qualifier = last_ident;
+                }
+                ast::TokenTree::Token(..) => {
+                    qualifier = None;
+                }
+                ast::TokenTree::Delimited(_, ref delimited) => {
+                    qualifier = None;
+                    self.collect_tts(&delimited.tts);
+                }
+                ast::TokenTree::Sequence(_, ref sequence) => {
+                    qualifier = None;
+                    self.collect_tts(&sequence.tts);
+                }
+            }
+            last_ident = None;
+        }
+    }
+}
+
+impl<'v, 'a> visit::Visitor<'v> for OtherTestConfigNameCollector<'a> {
+    fn visit_ident(&mut self, _: Span, ident: ast::Ident) {
+        self.record(ident.name, None);
+    }
+
+    fn visit_path(&mut self, path: &'v ast::Path, _: ast::NodeId) {
+        let mut qualifier = None;
+        for segment in &path.segments {
+            self.record(segment.identifier.name, qualifier);
+            self.visit_path_parameters(path.span, &segment.parameters);
+            qualifier = Some(segment.identifier.name);
+        }
+    }
+
+    fn visit_path_list_item(&mut self, prefix: &'v ast::Path, item: &'v ast::PathListItem) {
+        self.visit_path(prefix, item.node.id());
+        if let Some(name) = item.node.name() {
+            let qualifier = prefix.segments.last().map(|segment| segment.identifier.name);
+            self.record(name.name, qualifier);
+        }
+    }
+
+    fn visit_item(&mut self, i: &'v ast::Item) {
+        self.visit_configured(&i.attrs, |v| {
+            if let ast::ItemKind::Mod(..) = i.node {
+                v.module.push(i.ident.name);
+                visit::walk_item(v, i);
+                v.module.pop();
+            } else {
+                visit::walk_item(v, i);
+            }
+        });
+    }
+
+    fn visit_impl_item(&mut self, ii: &'v ast::ImplItem) {
+        self.visit_configured(&ii.attrs, |v| visit::walk_impl_item(v, ii));
+    }
+
+    fn visit_trait_item(&mut self, ti: &'v ast::TraitItem) {
+        self.visit_configured(&ti.attrs, |v| visit::walk_trait_item(v, ti));
+    }
+
+    fn visit_stmt(&mut self, s: &'v ast::Stmt) {
+        self.visit_configured(s.node.attrs(), |v| visit::walk_stmt(v, s));
+    }
+
+    fn visit_expr(&mut self, ex: &'v ast::Expr) {
+        self.visit_configured(ex.attrs(), |v| visit::walk_expr(v, ex));
+    }
+
+    fn visit_arm(&mut self, a: &'v ast::Arm) {
+        self.visit_configured(&a.attrs, |v| visit::walk_arm(v, a));
+    }
+
+    // This visitor runs pre expansion, so macro invocations are still
+    // around; their arguments are only tokens.
+    fn visit_mac(&mut self, mac: &'v ast::Mac) {
+        if self.in_other_config_code {
+            self.collect_tts(&mac.node.tts);
+        }
+    }
+}
+
 pub trait CfgDiag {
     fn emit_error<F>(&mut self, f: F) where F: FnMut(&Handler);
     fn flag_gated<F>(&mut self, f: F) where F: FnMut(&mut Vec<GatedCfgAttr>);
@@ -836,6 +836,24 @@ mod test {
         assert_eq!(suggest.splice_lines(&cm), expected);
     }
 
+    #[test]
+    fn test_multiline_substitute_splice() {
+        // Test that the lines of a multi-line substitution are indented
+        // like the line they are spliced into
+        let cm = CodeMap::new();
+        let inputtext = "match o {\n    A => 1,\n}\n";
+        let selection = "         \n    ^~~~~~~\n \n";
+        cm.new_filemap_and_lines("blork.rs", inputtext);
+        let sp = span_from_selection(inputtext, selection);
+        assert_eq!(&cm.span_to_snippet(sp).unwrap(), "A => 1,");
+
+        let suggest = CodeSuggestion {
+            msp: sp.into(),
+            substitutes: vec!["A => 1,\nB => 2,".to_owned()],
+        };
+        assert_eq!(suggest.splice_lines(&cm), "    A => 1,\n    B => 2,");
+    }
+
     #[test]
     fn test_multiple_span_splice() {
         // Test that a `MultiSpan` containing multiple spans splices substitions on
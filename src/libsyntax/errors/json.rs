@@ -22,7 +22,7 @@
 
 use codemap::{self, Span, MultiSpan, CodeMap};
 use diagnostics::registry::Registry;
-use errors::{Level, DiagnosticBuilder, SubDiagnostic, RenderSpan, CodeSuggestion};
+use errors::{Level, DiagnosticBuilder, SubDiagnostic, RenderSpan};
 use errors::emitter::Emitter;
 
 use std::rc::Rc;
@@ -101,6 +101,10 @@ struct DiagnosticSpan {
     column_end: usize,
     /// Source text from the start of line_start to the end of line_end.
     text: Vec<DiagnosticSpanLine>,
+    /// If we are suggesting a replacement, this will contain the text
+    /// that should be put in place of the span, laid out to fit the
+    /// surrounding code.
+    suggested_replacement: Option<String>,
 }
 
 #[derive(RustcEncodable)]
@@ -178,30 +182,40 @@ impl<'a> Diagnostic<'a> {
 }
 
 impl DiagnosticSpan {
+    fn from_span(span: &Span,
+                 suggestion: Option<String>,
+                 je: &JsonEmitter)
+                 -> DiagnosticSpan {
+        let start = je.cm.lookup_char_pos(span.lo);
+        let end = je.cm.lookup_char_pos(span.hi);
+        DiagnosticSpan {
+            file_name: start.file.name.clone(),
+            byte_start: span.lo.0,
+            byte_end: span.hi.0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+            text: DiagnosticSpanLine::from_span(span, je),
+            suggested_replacement: suggestion,
+        }
+    }
+
     fn from_multispan(msp: &MultiSpan, je: &JsonEmitter) -> Vec<DiagnosticSpan> {
-        msp.spans.iter().map(|span| {
-            let start = je.cm.lookup_char_pos(span.lo);
-            let end = je.cm.lookup_char_pos(span.hi);
-            DiagnosticSpan {
-                file_name: start.file.name.clone(),
-                byte_start: span.lo.0,
-                byte_end: span.hi.0,
-                line_start: start.line,
-                line_end: end.line,
-                column_start: start.col.0 + 1,
-                column_end: end.col.0 + 1,
-                text: DiagnosticSpanLine::from_span(span, je),
-            }
-        }).collect()
+        msp.spans.iter().map(|span| DiagnosticSpan::from_span(span, None, je)).collect()
     }
 
     fn from_render_span(rsp: &RenderSpan, je: &JsonEmitter) -> Vec<DiagnosticSpan> {
         match *rsp {
-            RenderSpan::FullSpan(ref msp) |
-            // FIXME(#30701) handle Suggestion properly
-            RenderSpan::Suggestion(CodeSuggestion { ref msp, .. }) => {
+            RenderSpan::FullSpan(ref msp) => {
                 DiagnosticSpan::from_multispan(msp, je)
             }
+            RenderSpan::Suggestion(ref suggestion) => {
+                let substitutes = suggestion.formatted_substitutes(&je.cm);
+                suggestion.msp.spans.iter().zip(substitutes).map(|(span, substitute)| {
+                    DiagnosticSpan::from_span(span, Some(substitute), je)
+                }).collect()
+            }
             RenderSpan::EndSpan(ref msp) => {
                 msp.spans.iter().map(|span| {
                     let end = je.cm.lookup_char_pos(span.hi);
@@ -214,6 +228,7 @@ impl DiagnosticSpan {
                         column_start: end.col.0 + 1,
                         column_end: end.col.0 + 1,
                         text: DiagnosticSpanLine::from_span_end(span, je),
+                        suggested_replacement: None,
                     }
                 }).collect()
             }
@@ -230,6 +245,7 @@ impl DiagnosticSpan {
                         column_start: 0,
                         column_end: 0,
                         text: DiagnosticSpanLine::from_span(span, je),
+                        suggested_replacement: None,
                     }
                 }).collect()
             }
@@ -25,6 +25,7 @@ use term;
 
 pub mod emitter;
 pub mod json;
+pub mod snippet;
 
 #[derive(Clone)]
 pub enum RenderSpan {
@@ -70,6 +71,21 @@ impl RenderSpan {
 }
 
 impl CodeSuggestion {
+    /// Returns the substitutes, laid out to fit the lines they go into.
+    /// See `snippet::format_snippet`.
+    pub fn formatted_substitutes(&self, cm: &CodeMap) -> Vec<String> {
+        use codemap::Pos;
+
+        self.msp.spans.iter().zip(self.substitutes.iter()).map(|(sp, substitute)| {
+            let lo = cm.lookup_char_pos(sp.lo);
+            let line = lo.file.get_line(lo.line - 1).unwrap_or("");
+            snippet::format_snippet(substitute,
+                                    line,
+                                    lo.col.to_usize(),
+                                    snippet::MAX_SUGGESTION_WIDTH)
+        }).collect()
+    }
+
     /// Returns the assembled code suggestion.
     pub fn splice_lines(&self, cm: &CodeMap) -> String {
         use codemap::{CharPos, Loc, Pos};
@@ -112,7 +128,8 @@ impl CodeSuggestion {
         let mut prev_line = fm.get_line(lines.lines[0].line_index);
         let mut buf = String::new();
 
-        for (sp, substitute) in self.msp.spans.iter().zip(self.substitutes.iter()) {
+        let substitutes = self.formatted_substitutes(cm);
+        for (sp, substitute) in self.msp.spans.iter().zip(substitutes.iter()) {
             let cur_lo = cm.lookup_char_pos(sp.lo);
             if prev_hi.line == cur_lo.line {
                 push_trailing(&mut buf, prev_line, &prev_hi, Some(&cur_lo));
@@ -0,0 +1,149 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Layout of the code that suggestions splice into the source, so that
+//! multi-line insertions such as new `where` clauses, `use` items or
+//! match arms line up with the code around them.
+
+/// The line width that suggested code is wrapped to.
+pub const MAX_SUGGESTION_WIDTH: usize = 100;
+
+/// Lays out `snippet` for insertion at the char column `column` of
+/// `line`, the source line the replaced span starts on.
+///
+/// Every line of a multi-line `snippet` after the first is indented by
+/// the leading whitespace of `line`, on top of the indentation it has
+/// relative to the first line. If `snippet` ends in a newline, the code
+/// that follows the insertion gets that indentation back. A single-line
+/// `snippet` that would run past `max_width` is broken after its
+/// top-level commas, continuing one level deeper than `line`.
+pub fn format_snippet(snippet: &str, line: &str, column: usize, max_width: usize) -> String {
+    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+
+    if !snippet.contains('\n') {
+        if column + snippet.chars().count() <= max_width {
+            return snippet.to_string();
+        }
+        return wrap(snippet, &indent, column, max_width);
+    }
+
+    let mut formatted = String::new();
+    for (i, snippet_line) in snippet.lines().enumerate() {
+        if i > 0 {
+            formatted.push('\n');
+            if !snippet_line.is_empty() {
+                formatted.push_str(&indent);
+            }
+        }
+        formatted.push_str(snippet_line);
+    }
+    if snippet.ends_with('\n') {
+        formatted.push('\n');
+        formatted.push_str(&indent);
+    }
+    formatted
+}
+
+/// Breaks `snippet`, which starts at `column`, after as few top-level
+/// commas as needed to stay within `max_width`.
+fn wrap(snippet: &str, indent: &str, column: usize, max_width: usize) -> String {
+    let continuation = format!("{}    ", indent);
+    let mut formatted = String::new();
+    let mut width = column;
+    for (i, item) in top_level_items(snippet).into_iter().enumerate() {
+        let item_width = item.chars().count();
+        if i > 0 {
+            if width + 1 + item_width > max_width {
+                formatted.push('\n');
+                formatted.push_str(&continuation);
+                width = continuation.chars().count();
+            } else {
+                formatted.push(' ');
+                width += 1;
+            }
+        }
+        formatted.push_str(item);
+        width += item_width;
+    }
+    formatted
+}
+
+/// Splits `snippet` after each comma that is outside of brackets and
+/// string literals. The commas stay with the item before them; the
+/// whitespace after them is dropped.
+fn top_level_items(snippet: &str) -> Vec<&str> {
+    let mut items = vec![];
+    let mut depth = 0usize;
+    let mut in_str = false;
+    let mut prev = ' ';
+    let mut start = 0;
+    for (i, c) in snippet.char_indices() {
+        match c {
+            '"' if prev != '\\' => in_str = !in_str,
+            _ if in_str => {}
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            // not the `>` of `->` or `=>`
+            '>' if prev != '-' && prev != '=' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                items.push(&snippet[start..i + 1]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    items.push(&snippet[start..]);
+
+    items.into_iter().enumerate().map(|(i, item)| {
+        if i == 0 { item } else { item.trim_left() }
+    }).filter(|item| !item.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_snippet;
+
+    #[test]
+    fn short_snippets_are_unchanged() {
+        assert_eq!(format_snippet("x.clone()", "    let y = x;", 12, 100), "x.clone()");
+    }
+
+    #[test]
+    fn continuation_lines_get_the_line_indent() {
+        let arms = "Some(x) => x,\n    None => 0,";
+        assert_eq!(format_snippet(arms, "        match o {", 16, 100),
+                   "Some(x) => x,\n            None => 0,");
+    }
+
+    #[test]
+    fn trailing_newline_restores_indent() {
+        assert_eq!(format_snippet("use std::fmt;\n", "    fn f() {}", 4, 100),
+                   "use std::fmt;\n    ");
+    }
+
+    #[test]
+    fn empty_lines_are_not_indented() {
+        assert_eq!(format_snippet("a\n\nb", "  x", 2, 100), "a\n\n  b");
+    }
+
+    #[test]
+    fn long_snippets_wrap_after_top_level_commas() {
+        let clause = "where T: Iterator<Item = (u8, u8)>, U: Fn(u8, u8) -> u8, V: Clone";
+        assert_eq!(format_snippet(clause, "    fn f<T, U, V>(t: T, u: U, v: V)", 36, 80),
+                   "where T: Iterator<Item = (u8, u8)>,\n        \
+                    U: Fn(u8, u8) -> u8, V: Clone");
+    }
+
+    #[test]
+    fn commas_in_strings_do_not_wrap() {
+        assert_eq!(format_snippet("\"a, b\", c", "", 0, 5), "\"a, b\",\n    c");
+    }
+}
@@ -156,6 +156,22 @@ pub trait TTMacroExpander {
                    span: Span,
                    token_tree: &[ast::TokenTree])
                    -> Box<MacResult+'cx>;
+
+    /// The crate this macro was defined in, if it is a `macro_rules!`
+    /// macro imported from another crate.
+    fn def_crate(&self) -> Option<Name> {
+        None
+    }
+
+    /// Whether the macro was marked `#[allow_internal_lints]`.
+    fn allow_internal_lints(&self) -> bool {
+        false
+    }
+
+    /// Whether the macro was marked `#[allow_internal_unsafe]`.
+    fn allow_internal_unsafe(&self) -> bool {
+        false
+    }
 }
 
 pub type MacroExpanderFn =
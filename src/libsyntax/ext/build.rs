@@ -449,6 +449,7 @@ impl<'a> AstBuilder for ExtCtxt<'a> {
                bounds: ast::TyParamBounds,
                default: Option<P<ast::Ty>>) -> ast::TyParam {
         ast::TyParam {
+            attrs: None,
             ident: id,
             id: ast::DUMMY_NODE_ID,
             bounds: bounds,
@@ -500,6 +501,7 @@ impl<'a> AstBuilder for ExtCtxt<'a> {
                     bounds: Vec<ast::Lifetime>)
                     -> ast::LifetimeDef {
         ast::LifetimeDef {
+            attrs: None,
             lifetime: self.lifetime(span, name),
             bounds: bounds
         }
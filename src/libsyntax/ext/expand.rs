@@ -218,6 +218,9 @@ fn expand_mac_invoc<T, F, G>(mac: ast::Mac,
                             format: MacroBang(extname),
                             span: exp_span,
                             allow_internal_unstable: allow_internal_unstable,
+                            allow_internal_lints: expandfun.allow_internal_lints(),
+                            allow_internal_unsafe: expandfun.allow_internal_unsafe(),
+                            def_crate: expandfun.def_crate(),
                         },
                     });
                 let fm = fresh_mark();
@@ -394,6 +397,9 @@ pub fn expand_item_mac(it: P<ast::Item>,
                             format: MacroBang(extname),
                             span: tt_span,
                             allow_internal_unstable: allow_internal_unstable,
+                            allow_internal_lints: expander.allow_internal_lints(),
+                            allow_internal_unsafe: expander.allow_internal_unsafe(),
+                            def_crate: expander.def_crate(),
                         }
                     });
                     // mark before expansion:
@@ -413,6 +419,9 @@ pub fn expand_item_mac(it: P<ast::Item>,
                             format: MacroBang(extname),
                             span: tt_span,
                             allow_internal_unstable: allow_internal_unstable,
+                            allow_internal_lints: false,
+                            allow_internal_unsafe: false,
+                            def_crate: None,
                         }
                     });
                     // mark before expansion:
@@ -434,6 +443,9 @@ pub fn expand_item_mac(it: P<ast::Item>,
                             // unstable (this is orthogonal to whether
                             // the macro it creates allows it)
                             allow_internal_unstable: false,
+                            allow_internal_lints: false,
+                            allow_internal_unsafe: false,
+                            def_crate: None,
                         }
                     });
                     // DON'T mark before expansion.
@@ -453,6 +465,26 @@ pub fn expand_item_mac(it: P<ast::Item>,
                             feature_gate::EXPLAIN_ALLOW_INTERNAL_UNSTABLE)
                     }
 
+                    if attr::contains_name(&attrs, "allow_internal_lints") &&
+                       !fld.cx.ecfg.enable_allow_internal_lints() {
+                        feature_gate::emit_feature_err(
+                            &fld.cx.parse_sess.span_diagnostic,
+                            "allow_internal_lints",
+                            span,
+                            feature_gate::GateIssue::Language,
+                            feature_gate::EXPLAIN_ALLOW_INTERNAL_LINTS)
+                    }
+
+                    if attr::contains_name(&attrs, "allow_internal_unsafe") &&
+                       !fld.cx.ecfg.enable_allow_internal_unsafe() {
+                        feature_gate::emit_feature_err(
+                            &fld.cx.parse_sess.span_diagnostic,
+                            "allow_internal_unsafe",
+                            span,
+                            feature_gate::GateIssue::Language,
+                            feature_gate::EXPLAIN_ALLOW_INTERNAL_UNSAFE)
+                    }
+
                     let export = attr::contains_name(&attrs, "macro_export");
                     let def = ast::MacroDef {
                         ident: ident,
@@ -777,6 +809,9 @@ fn expand_pat(p: P<ast::Pat>, fld: &mut MacroExpander) -> P<ast::Pat> {
                             format: MacroBang(extname),
                             span: tt_span,
                             allow_internal_unstable: allow_internal_unstable,
+                            allow_internal_lints: expander.allow_internal_lints(),
+                            allow_internal_unsafe: expander.allow_internal_unsafe(),
+                            def_crate: expander.def_crate(),
                         }
                     });
 
@@ -989,6 +1024,9 @@ fn expand_decorators(a: Annotatable,
                             // attributes can do whatever they like,
                             // for now.
                             allow_internal_unstable: true,
+                            allow_internal_lints: false,
+                            allow_internal_unsafe: false,
+                            def_crate: None,
                         }
                     });
 
@@ -1039,6 +1077,9 @@ fn expand_item_multi_modifier(mut it: Annotatable,
                             // attributes can do whatever they like,
                             // for now
                             allow_internal_unstable: true,
+                            allow_internal_lints: false,
+                            allow_internal_unsafe: false,
+                            def_crate: None,
                         }
                     });
                     it = mac.expand(fld.cx, attr.span, &attr.node.value, it);
@@ -1310,6 +1351,17 @@ fn new_span(cx: &ExtCtxt, sp: Span) -> Span {
         if sp.expn_id.into_u32() == 0 && env::var_os("NDM").is_some() {
             panic!("NDM");
         }
+        // Tokens that the macro was given as arguments keep pointing at
+        // where they were written; remember where they ended up.
+        if cx.ecfg.record_expanded_tokens &&
+           sp1.expn_id != codemap::NO_EXPANSION && sp1.expn_id != sp.expn_id &&
+           !sp1.source_equal(&codemap::DUMMY_SP) {
+            let source_expn_id = cx.codemap().source_expn_id(sp1);
+            if source_expn_id != sp1.expn_id {
+                let source = Span { expn_id: source_expn_id, ..sp1 };
+                cx.codemap().record_expanded_token(source, sp1);
+            }
+        }
         sp1
     }
 }
@@ -1319,6 +1371,9 @@ pub struct ExpansionConfig<'feat> {
     pub features: Option<&'feat Features>,
     pub recursion_limit: usize,
     pub trace_mac: bool,
+    /// Whether to record in the `CodeMap` where the tokens passed in to
+    /// macros end up in their expansions; only save-analysis needs it.
+    pub record_expanded_tokens: bool,
 }
 
 macro_rules! feature_tests {
@@ -1341,6 +1396,7 @@ impl<'feat> ExpansionConfig<'feat> {
             features: None,
             recursion_limit: 64,
             trace_mac: false,
+            record_expanded_tokens: false,
         }
     }
 
@@ -1351,6 +1407,8 @@ impl<'feat> ExpansionConfig<'feat> {
         fn enable_concat_idents = allow_concat_idents,
         fn enable_trace_macros = allow_trace_macros,
         fn enable_allow_internal_unstable = allow_internal_unstable,
+        fn enable_allow_internal_lints = allow_internal_lints,
+        fn enable_allow_internal_unsafe = allow_internal_unsafe,
         fn enable_custom_derive = allow_custom_derive,
         fn enable_pushpop_unsafe = allow_pushpop_unsafe,
     }
@@ -1603,6 +1661,29 @@ mod tests {
         name_finder.ident_accumulator
     }
 
+    // tokens passed through nested macros should map back to where they were written
+    #[test] fn expanded_tokens_map_to_call_site() {
+        let src = "macro_rules! inner (($x:ident) => (let $x = 1;));\
+                   macro_rules! outer (($x:ident) => (inner!($x)));\
+                   fn f() { outer!(zz); }".to_string();
+        let ps = parse::ParseSess::new();
+        let crate_ast = panictry!(string_to_parser(&ps, src.clone()).parse_crate_mod());
+        let mut gated_cfgs = vec![];
+        let mut ecfg = test_ecfg();
+        ecfg.record_expanded_tokens = true;
+        let ecx = ExtCtxt::new(&ps, vec![], ecfg, &mut gated_cfgs);
+        expand_crate(ecx, vec![], vec![], crate_ast);
+
+        let lo = src.rfind("zz").unwrap() as u32;
+        let token = codemap::mk_sp(codemap::BytePos(lo), codemap::BytePos(lo + 2));
+        let expanded = ps.codemap().expanded_tokens(token);
+        assert!(!expanded.is_empty());
+        for sp in expanded {
+            assert!(sp.expn_id != codemap::NO_EXPANSION);
+            assert_eq!(ps.codemap().source_token(sp), Some(token));
+        }
+    }
+
     #[test] fn macro_tokens_should_match(){
         expand_crate_str(
             "macro_rules! m((a)=>(13)) ;fn main(){m!(a);}".to_string());
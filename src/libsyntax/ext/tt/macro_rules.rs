@@ -8,7 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use ast::{self, TokenTree};
+use ast::{self, Name, TokenTree};
+use attr;
 use codemap::{Span, DUMMY_SP};
 use ext::base::{DummyResult, ExtCtxt, MacResult, SyntaxExtension};
 use ext::base::{NormalTT, TTMacroExpander};
@@ -133,6 +134,8 @@ impl<'a> MacResult for ParserAnyMacro<'a> {
 struct MacroRulesMacroExpander {
     name: ast::Ident,
     imported_from: Option<ast::Ident>,
+    allow_internal_lints: bool,
+    allow_internal_unsafe: bool,
     lhses: Vec<TokenTree>,
     rhses: Vec<TokenTree>,
     valid: bool,
@@ -155,6 +158,18 @@ impl TTMacroExpander for MacroRulesMacroExpander {
                           &self.lhses,
                           &self.rhses)
     }
+
+    fn def_crate(&self) -> Option<Name> {
+        self.imported_from.map(|ident| ident.name)
+    }
+
+    fn allow_internal_lints(&self) -> bool {
+        self.allow_internal_lints
+    }
+
+    fn allow_internal_unsafe(&self) -> bool {
+        self.allow_internal_unsafe
+    }
 }
 
 /// Given `lhses` and `rhses`, this is the new macro we create
@@ -319,6 +334,8 @@ pub fn compile<'cx>(cx: &'cx mut ExtCtxt,
     let exp: Box<_> = Box::new(MacroRulesMacroExpander {
         name: def.ident,
         imported_from: def.imported_from,
+        allow_internal_lints: attr::contains_name(&def.attrs, "allow_internal_lints"),
+        allow_internal_unsafe: attr::contains_name(&def.attrs, "allow_internal_unsafe"),
         lhses: lhses,
         rhses: rhses,
         valid: valid,
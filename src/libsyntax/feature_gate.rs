@@ -30,7 +30,7 @@ use abi::Abi;
 use ast::{NodeId, PatKind};
 use ast;
 use attr;
-use attr::AttrMetaMethods;
+use attr::{AttrMetaMethods, ThinAttributesExt};
 use codemap::{CodeMap, Span};
 use errors::Handler;
 use visit;
@@ -168,6 +168,17 @@ const KNOWN_FEATURES: &'static [(&'static str, &'static str, Option<u32>, Status
     // rustc internal
     ("allow_internal_unstable", "1.0.0", None, Active),
 
+    // Allows the use of #[allow_internal_lints] on macro_rules!, which
+    // allows lints in the macro's expansion when it is used from
+    // another crate. Checked before expansion, like
+    // #[allow_internal_unstable].
+    ("allow_internal_lints", "1.10.0", None, Active),
+
+    // Allows the use of #[allow_internal_unsafe] on macro_rules!, which
+    // lets `unsafe` blocks in the macro cover the macro's own code
+    // without covering the tokens its callers pass in.
+    ("allow_internal_unsafe", "1.10.0", None, Active),
+
     // #23121. Array patterns have some hazards yet.
     ("slice_patterns", "1.0.0", Some(23121), Active),
 
@@ -256,6 +267,38 @@ const KNOWN_FEATURES: &'static [(&'static str, &'static str, Option<u32>, Status
 
     // impl specialization (RFC 1210)
     ("specialization", "1.7.0", Some(31844), Active),
+
+    // `become f(..)`, a guaranteed tail call
+    ("explicit_tail_calls", "1.10.0", None, Active),
+
+    // Allows passing values of unsized types, such as `[T]` or `FnOnce()`,
+    // to functions by value
+    ("unsized_locals", "1.10.0", None, Active),
+
+    // Allows bindings in the subpattern of `x @ P`, e.g. `p @ Some(x)`.
+    ("bindings_after_at", "1.10.0", None, Active),
+
+    // Allows `#[optimize(size)]` on functions.
+    ("optimize_attribute", "1.10.0", None, Active),
+
+    // Allows `#![test_runner]` and `#[test_case]`, to run tests with a
+    // framework other than libtest.
+    ("custom_test_frameworks", "1.10.0", None, Active),
+
+    // Allows `trait Foo = Bar + Quux;`
+    ("trait_alias", "1.10.0", None, Active),
+
+    // Allows `T: !Trait` bounds, and negative impls of any trait
+    ("negative_bounds", "1.10.0", None, Active),
+
+    // Allows attributes on lifetime/type formal parameters in generics (RFC 1327)
+    ("generic_param_attrs", "1.10.0", None, Active),
+
+    // Allows `#[may_dangle]` on parameters of an `unsafe impl Drop` (RFC 1327)
+    ("dropck_eyepatch", "1.10.0", None, Active),
+
+    // Allows coercing non-capturing closures to function pointers
+    ("closure_to_fn_coercion", "1.10.0", None, Active),
 ];
 // (changing above list without updating src/doc/reference.md makes @cmr sad)
 
@@ -303,6 +346,8 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
     ("ignore", Normal, Ungated),
     ("no_implicit_prelude", Normal, Ungated),
     ("reexport_test_harness_main", Normal, Ungated),
+    ("test_case", Normal, Gated("custom_test_frameworks",
+                                "custom test frameworks are an experimental feature")),
     ("link_args", Normal, Ungated),
     ("macro_escape", Normal, Ungated),
 
@@ -344,6 +389,10 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
                                      "the `#[rustc_variance]` attribute \
                                       is just used for rustc unit tests \
                                       and will never be stable")),
+    ("rustc_layout", Whitelisted, Gated("rustc_attrs",
+                                        "the `#[rustc_layout]` attribute \
+                                         is just used for rustc unit tests \
+                                         and will never be stable")),
     ("rustc_error", Whitelisted, Gated("rustc_attrs",
                                        "the `#[rustc_error]` attribute \
                                         is just used for rustc unit tests \
@@ -380,9 +429,16 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
                                         "the `#[rustc_no_mir]` attribute \
                                          is just used to make tests pass \
                                          and will never be stable")),
+    ("rustc_diagnostic_item", Whitelisted, Gated("rustc_attrs",
+                                                 "diagnostic items are internal to the \
+                                                  compiler and will never be stable")),
 
     ("allow_internal_unstable", Normal, Gated("allow_internal_unstable",
                                               EXPLAIN_ALLOW_INTERNAL_UNSTABLE)),
+    ("allow_internal_lints", Normal, Gated("allow_internal_lints",
+                                           EXPLAIN_ALLOW_INTERNAL_LINTS)),
+    ("allow_internal_unsafe", Normal, Gated("allow_internal_unsafe",
+                                            EXPLAIN_ALLOW_INTERNAL_UNSAFE)),
 
     ("fundamental", Whitelisted, Gated("fundamental",
                                        "the `#[fundamental]` attribute \
@@ -403,6 +459,9 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
                                   is an experimental feature")),
     ("export_name", Whitelisted, Ungated),
     ("inline", Whitelisted, Ungated),
+    ("optimize", Whitelisted, Gated("optimize_attribute",
+                                    "the `#[optimize]` attribute \
+                                     is an experimental feature")),
     ("link", Whitelisted, Ungated),
     ("link_name", Whitelisted, Ungated),
     ("link_section", Whitelisted, Ungated),
@@ -423,6 +482,10 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
      Gated("dropck_parametricity",
            "unsafe_destructor_blind_to_params has unstable semantics \
             and may be removed in the future")),
+    ("may_dangle",
+     Normal,
+     Gated("dropck_eyepatch",
+           "may_dangle has unstable semantics and may be removed in the future")),
     ("unwind", Whitelisted, Gated("unwind_attributes", "#[unwind] is experimental")),
 
     // used in resolve
@@ -451,6 +514,8 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeGat
     ("no_main", CrateLevel, Ungated),
     ("no_builtins", CrateLevel, Ungated),
     ("recursion_limit", CrateLevel, Ungated),
+    ("test_runner", CrateLevel, Gated("custom_test_frameworks",
+                                      "custom test frameworks are an experimental feature")),
 ];
 
 macro_rules! cfg_fn {
@@ -583,6 +648,8 @@ pub struct Features {
     pub allow_concat_idents: bool,
     pub allow_trace_macros: bool,
     pub allow_internal_unstable: bool,
+    pub allow_internal_lints: bool,
+    pub allow_internal_unsafe: bool,
     pub allow_custom_derive: bool,
     pub allow_placement_in: bool,
     pub allow_box: bool,
@@ -608,6 +675,11 @@ pub struct Features {
     pub deprecated: bool,
     pub question_mark: bool,
     pub specialization: bool,
+    pub explicit_tail_calls: bool,
+    pub unsized_locals: bool,
+    pub bindings_after_at: bool,
+    pub negative_bounds: bool,
+    pub closure_to_fn_coercion: bool,
 }
 
 impl Features {
@@ -621,6 +693,8 @@ impl Features {
             allow_concat_idents: false,
             allow_trace_macros: false,
             allow_internal_unstable: false,
+            allow_internal_lints: false,
+            allow_internal_unsafe: false,
             allow_custom_derive: false,
             allow_placement_in: false,
             allow_box: false,
@@ -644,6 +718,11 @@ impl Features {
             deprecated: false,
             question_mark: false,
             specialization: false,
+            explicit_tail_calls: false,
+            unsized_locals: false,
+            bindings_after_at: false,
+            negative_bounds: false,
+            closure_to_fn_coercion: false,
         }
     }
 }
@@ -806,6 +885,10 @@ pub const EXPLAIN_TRACE_MACROS: &'static str =
     "`trace_macros` is not stable enough for use and is subject to change";
 pub const EXPLAIN_ALLOW_INTERNAL_UNSTABLE: &'static str =
     "allow_internal_unstable side-steps feature gating and stability checks";
+pub const EXPLAIN_ALLOW_INTERNAL_LINTS: &'static str =
+    "allow_internal_lints hides lints in the expansion of a macro from its users";
+pub const EXPLAIN_ALLOW_INTERNAL_UNSAFE: &'static str =
+    "allow_internal_unsafe lets a macro use `unsafe` blocks without its users opting in";
 
 pub const EXPLAIN_CUSTOM_DERIVE: &'static str =
     "`#[derive]` for custom traits is not stable enough for use and is subject to change";
@@ -970,6 +1053,10 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
                 }
             }
 
+            ast::ItemKind::TraitAlias(..) => {
+                self.gate_feature("trait_alias", i.span, "trait aliases are experimental");
+            }
+
             ast::ItemKind::DefaultImpl(..) => {
                 self.gate_feature("optin_builtin_traits",
                                   i.span,
@@ -979,7 +1066,7 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
 
             ast::ItemKind::Impl(_, polarity, _, _, _, _) => {
                 match polarity {
-                    ast::ImplPolarity::Negative => {
+                    ast::ImplPolarity::Negative if !self.context.has_feature("negative_bounds") => {
                         self.gate_feature("optin_builtin_traits",
                                           i.span,
                                           "negative trait bounds are not yet fully implemented; \
@@ -1045,6 +1132,10 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
             ast::ExprKind::Try(..) => {
                 self.gate_feature("question_mark", e.span, "the `?` operator is not stable");
             }
+            ast::ExprKind::Become(..) => {
+                self.gate_feature("explicit_tail_calls", e.span,
+                                  "`become` expressions are experimental");
+            }
             _ => {}
         }
         visit::walk_expr(self, e);
@@ -1137,6 +1228,31 @@ impl<'a, 'v> Visitor<'v> for PostExpansionVisitor<'a> {
         visit::walk_trait_item(self, ti);
     }
 
+    fn visit_ty_param_bound(&mut self, bound: &'v ast::TyParamBound) {
+        if let ast::TraitTyParamBound(ref ptr, ast::TraitBoundModifier::Negative) = *bound {
+            self.gate_feature("negative_bounds", ptr.span, "negative bounds are experimental");
+        }
+        visit::walk_ty_param_bound(self, bound);
+    }
+
+    fn visit_generics(&mut self, g: &'v ast::Generics) {
+        for t in g.ty_params.iter() {
+            if let Some(attr) = t.attrs.as_attr_slice().first() {
+                self.gate_feature("generic_param_attrs", attr.span,
+                                  "attributes on type parameter bindings are experimental");
+            }
+        }
+        visit::walk_generics(self, g);
+    }
+
+    fn visit_lifetime_def(&mut self, lifetime_def: &'v ast::LifetimeDef) {
+        if let Some(attr) = lifetime_def.attrs.as_attr_slice().first() {
+            self.gate_feature("generic_param_attrs", attr.span,
+                              "attributes on lifetime bindings are experimental");
+        }
+        visit::walk_lifetime_def(self, lifetime_def);
+    }
+
     fn visit_impl_item(&mut self, ii: &'v ast::ImplItem) {
         if ii.defaultness == ast::Defaultness::Default {
             self.gate_feature("specialization",
@@ -1233,6 +1349,8 @@ fn check_crate_inner<F>(cm: &CodeMap, span_handler: &Handler,
         allow_concat_idents: cx.has_feature("concat_idents"),
         allow_trace_macros: cx.has_feature("trace_macros"),
         allow_internal_unstable: cx.has_feature("allow_internal_unstable"),
+        allow_internal_lints: cx.has_feature("allow_internal_lints"),
+        allow_internal_unsafe: cx.has_feature("allow_internal_unsafe"),
         allow_custom_derive: cx.has_feature("custom_derive"),
         allow_placement_in: cx.has_feature("placement_in_syntax"),
         allow_box: cx.has_feature("box_syntax"),
@@ -1256,6 +1374,11 @@ fn check_crate_inner<F>(cm: &CodeMap, span_handler: &Handler,
         deprecated: cx.has_feature("deprecated"),
         question_mark: cx.has_feature("question_mark"),
         specialization: cx.has_feature("specialization"),
+        explicit_tail_calls: cx.has_feature("explicit_tail_calls"),
+        unsized_locals: cx.has_feature("unsized_locals"),
+        bindings_after_at: cx.has_feature("bindings_after_at"),
+        negative_bounds: cx.has_feature("negative_bounds"),
+        closure_to_fn_coercion: cx.has_feature("closure_to_fn_coercion"),
     }
 }
 
@@ -54,6 +54,7 @@ use parse::token::{self, intern, MatchNt, SubstNt, SpecialVarNt, InternedString}
 use parse::token::{keywords, special_idents, SpecialMacroVar};
 use parse::{new_sub_parser_from_file, ParseSess};
 use util::parser::{AssocOp, Fixity};
+use util::stack;
 use print::pprust;
 use ptr::P;
 use parse::PResult;
@@ -1097,6 +1098,16 @@ impl<'a> Parser<'a> {
     pub fn bug(&self, m: &str) -> ! {
         self.sess.span_diagnostic.span_bug(self.span, m)
     }
+
+    /// Called at the recursive entry points of the parser; reports an
+    /// error rather than overflowing the stack on deeply nested input.
+    fn check_stack(&self) -> PResult<'a, ()> {
+        if stack::in_red_zone() {
+            return Err(self.fatal("reached the recursion limit while parsing: \
+                                   input is nested too deeply"));
+        }
+        Ok(())
+    }
     pub fn warn(&self, m: &str) {
         self.sess.span_diagnostic.span_warn(self.span, m)
     }
@@ -1271,7 +1282,7 @@ impl<'a> Parser<'a> {
             let lo = p.span.lo;
 
             let (name, node) = if p.eat_keyword(keywords::Type) {
-                let TyParam {ident, bounds, default, ..} = p.parse_ty_param()?;
+                let TyParam {ident, bounds, default, ..} = p.parse_ty_param(Vec::new())?;
                 p.expect(&token::Semi)?;
                 (ident, TraitItemKind::Type(bounds, default))
             } else if p.is_const_item() {
@@ -1418,6 +1429,7 @@ impl<'a> Parser<'a> {
     /// Parse a type.
     pub fn parse_ty(&mut self) -> PResult<'a, P<Ty>> {
         maybe_whole!(no_clone self, NtTy);
+        self.check_stack()?;
 
         let lo = self.span.lo;
 
@@ -1971,9 +1983,22 @@ impl<'a> Parser<'a> {
     /// Parses `lifetime_defs = [ lifetime_defs { ',' lifetime_defs } ]` where `lifetime_def  =
     /// lifetime [':' lifetimes]`
     pub fn parse_lifetime_defs(&mut self) -> PResult<'a, Vec<ast::LifetimeDef>> {
+        let (res, attrs) = self.parse_lifetime_defs_with_attrs()?;
+        if !attrs.is_empty() {
+            let span = attrs[0].span;
+            return Err(self.span_fatal(span, "trailing attribute after lifetime parameters"));
+        }
+        Ok(res)
+    }
 
+    /// Like `parse_lifetime_defs`, but each lifetime may be preceded by outer
+    /// attributes. Attributes that are not followed by a lifetime belong to
+    /// the first type parameter and are returned alongside the lifetimes.
+    fn parse_lifetime_defs_with_attrs(&mut self)
+                                      -> PResult<'a, (Vec<ast::LifetimeDef>, Vec<Attribute>)> {
         let mut res = Vec::new();
         loop {
+            let attrs = self.parse_outer_attributes()?;
             match self.token {
                 token::Lifetime(_) => {
                     let lifetime = self.parse_lifetime()?;
@@ -1983,19 +2008,20 @@ impl<'a> Parser<'a> {
                         } else {
                             Vec::new()
                         };
-                    res.push(ast::LifetimeDef { lifetime: lifetime,
+                    res.push(ast::LifetimeDef { attrs: attrs.into_thin_attrs(),
+                                                lifetime: lifetime,
                                                 bounds: bounds });
                 }
 
                 _ => {
-                    return Ok(res);
+                    return Ok((res, attrs));
                 }
             }
 
             match self.token {
                 token::Comma => { self.bump();}
-                token::Gt => { return Ok(res); }
-                token::BinOp(token::Shr) => { return Ok(res); }
+                token::Gt => { return Ok((res, Vec::new())); }
+                token::BinOp(token::Shr) => { return Ok((res, Vec::new())); }
                 _ => {
                     let this_token_str = self.this_token_to_string();
                     let msg = format!("expected `,` or `>` after lifetime \
@@ -2340,6 +2366,10 @@ impl<'a> Parser<'a> {
                     } else {
                         ex = ExprKind::Ret(None);
                     }
+                } else if self.eat_keyword(keywords::Become) {
+                    let e = self.parse_expr()?;
+                    hi = e.span.hi;
+                    ex = ExprKind::Become(e);
                 } else if self.eat_keyword(keywords::Break) {
                     if self.token.is_lifetime() {
                         ex = ExprKind::Break(Some(Spanned {
@@ -2978,6 +3008,7 @@ impl<'a> Parser<'a> {
                                  min_prec: usize,
                                  lhs: LhsExpr)
                                  -> PResult<'a, P<Expr>> {
+        self.check_stack()?;
         let mut lhs = if let LhsExpr::AlreadyParsed(expr) = lhs {
             expr
         } else {
@@ -3621,6 +3652,7 @@ impl<'a> Parser<'a> {
     /// Parse a pattern.
     pub fn parse_pat(&mut self) -> PResult<'a, P<Pat>> {
         maybe_whole!(self, NtPat);
+        self.check_stack()?;
 
         let lo = self.span.lo;
         let pat;
@@ -4256,7 +4288,7 @@ impl<'a> Parser<'a> {
     // matches bounds    = ( boundseq )?
     // where   boundseq  = ( polybound + boundseq ) | polybound
     // and     polybound = ( 'for' '<' 'region '>' )? bound
-    // and     bound     = 'region | trait_ref
+    // and     bound     = 'region | ( '?' | '!' )? trait_ref
     fn parse_ty_param_bounds(&mut self,
                              mode: BoundParsingMode)
                              -> PResult<'a, TyParamBounds>
@@ -4265,12 +4297,17 @@ impl<'a> Parser<'a> {
         loop {
             let question_span = self.span;
             let ate_question = self.eat(&token::Question);
+            let ate_not = !ate_question && self.eat(&token::Not);
             match self.token {
                 token::Lifetime(lifetime) => {
                     if ate_question {
                         self.span_err(question_span,
                                       "`?` may only modify trait bounds, not lifetime bounds");
                     }
+                    if ate_not {
+                        self.span_err(question_span,
+                                      "`!` may only modify trait bounds, not lifetime bounds");
+                    }
                     result.push(RegionTyParamBound(ast::Lifetime {
                         id: ast::DUMMY_NODE_ID,
                         span: self.span,
@@ -4288,6 +4325,8 @@ impl<'a> Parser<'a> {
                                           "unexpected `?`");
                             TraitBoundModifier::None
                         }
+                    } else if ate_not {
+                        TraitBoundModifier::Negative
                     } else {
                         TraitBoundModifier::None
                     };
@@ -4305,7 +4344,7 @@ impl<'a> Parser<'a> {
     }
 
     /// Matches typaram = IDENT (`?` unbound)? optbounds ( EQ ty )?
-    fn parse_ty_param(&mut self) -> PResult<'a, TyParam> {
+    fn parse_ty_param(&mut self, preceding_attrs: Vec<Attribute>) -> PResult<'a, TyParam> {
         let span = self.span;
         let ident = self.parse_ident()?;
 
@@ -4319,6 +4358,7 @@ impl<'a> Parser<'a> {
         };
 
         Ok(TyParam {
+            attrs: preceding_attrs.into_thin_attrs(),
             ident: ident,
             id: ast::DUMMY_NODE_ID,
             bounds: bounds,
@@ -4338,11 +4378,17 @@ impl<'a> Parser<'a> {
         maybe_whole!(self, NtGenerics);
 
         if self.eat(&token::Lt) {
-            let lifetime_defs = self.parse_lifetime_defs()?;
+            let (lifetime_defs, mut pending_attrs) = self.parse_lifetime_defs_with_attrs()?;
+            if !pending_attrs.is_empty() && self.token.is_like_gt() {
+                let span = pending_attrs[0].span;
+                return Err(self.span_fatal(span, "trailing attribute after generic parameters"));
+            }
             let mut seen_default = false;
             let ty_params = self.parse_seq_to_gt(Some(token::Comma), |p| {
+                let mut attrs = mem::replace(&mut pending_attrs, Vec::new());
+                attrs.extend(p.parse_outer_attributes()?);
                 p.forbid_lifetime()?;
-                let ty_param = p.parse_ty_param()?;
+                let ty_param = p.parse_ty_param(attrs)?;
                 if ty_param.default.is_some() {
                     seen_default = true;
                 } else if seen_default {
@@ -5045,12 +5091,23 @@ impl<'a> Parser<'a> {
         }
     }
 
-    /// Parse trait Foo { ... }
+    /// Parse trait Foo { ... } or trait Foo = Bar + Quux;
     fn parse_item_trait(&mut self, unsafety: Unsafety) -> PResult<'a, ItemInfo> {
-
+        let lo = self.last_span.lo;
         let ident = self.parse_ident()?;
         let mut tps = self.parse_generics()?;
 
+        if self.eat(&token::Eq) {
+            // It's a trait alias.
+            let bounds = self.parse_ty_param_bounds(BoundParsingMode::Bare)?;
+            tps.where_clause = self.parse_where_clause()?;
+            self.expect(&token::Semi)?;
+            if unsafety != Unsafety::Normal {
+                self.span_err(mk_sp(lo, self.last_span.hi), "trait aliases cannot be `unsafe`");
+            }
+            return Ok((ident, ItemKind::TraitAlias(tps, bounds), None));
+        }
+
         // Parse supertrait bounds.
         let bounds = self.parse_colon_then_ty_param_bounds(BoundParsingMode::Bare)?;
 
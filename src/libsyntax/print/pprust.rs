@@ -1313,6 +1313,19 @@ impl<'a> State<'a> {
                 }
                 self.bclose(item.span)?;
             }
+            ast::ItemKind::TraitAlias(ref generics, ref bounds) => {
+                self.ibox(INDENT_UNIT)?;
+                self.ibox(0)?;
+                self.word_nbsp(&visibility_qualified(&item.vis, "trait"))?;
+                self.print_ident(item.ident)?;
+                self.print_generics(generics)?;
+                self.end()?; // end the inner ibox
+
+                self.print_bounds(" =", &bounds[..])?;
+                self.print_where_clause(&generics.where_clause)?;
+                word(&mut self.s, ";")?;
+                self.end()?; // end the outer ibox
+            }
             ast::ItemKind::Mac(codemap::Spanned { ref node, .. }) => {
                 self.print_visibility(&item.vis)?;
                 self.print_path(&node.path, false, 0)?;
@@ -2201,6 +2214,11 @@ impl<'a> State<'a> {
                     _ => ()
                 }
             }
+            ast::ExprKind::Become(ref expr) => {
+                word(&mut self.s, "become")?;
+                word(&mut self.s, " ")?;
+                self.print_expr(&expr)?;
+            }
             ast::ExprKind::InlineAsm(ref a) => {
                 word(&mut self.s, "asm!")?;
                 self.popen()?;
@@ -2768,6 +2786,10 @@ impl<'a> State<'a> {
                         word(&mut self.s, "?")?;
                         self.print_poly_trait_ref(tref)
                     }
+                    TraitTyParamBound(ref tref, TraitBoundModifier::Negative) => {
+                        word(&mut self.s, "!")?;
+                        self.print_poly_trait_ref(tref)
+                    }
                     RegionTyParamBound(ref lt) => {
                         self.print_lifetime(lt)
                     }
@@ -2790,6 +2812,7 @@ impl<'a> State<'a> {
                               lifetime: &ast::LifetimeDef)
                               -> io::Result<()>
     {
+        self.print_outer_attributes_inline(lifetime.attrs.as_attr_slice())?;
         self.print_lifetime(&lifetime.lifetime)?;
         let mut sep = ":";
         for v in &lifetime.bounds {
@@ -2832,6 +2855,7 @@ impl<'a> State<'a> {
     }
 
     pub fn print_ty_param(&mut self, param: &ast::TyParam) -> io::Result<()> {
+        self.print_outer_attributes_inline(param.attrs.as_attr_slice())?;
         self.print_ident(param.ident)?;
         self.print_bounds(":", &param.bounds)?;
         match param.default {
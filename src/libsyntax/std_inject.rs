@@ -29,6 +29,9 @@ fn ignored_span(sess: &ParseSess, sp: Span) -> Span {
             format: MacroAttribute(intern("std_inject")),
             span: None,
             allow_internal_unstable: true,
+            allow_internal_lints: false,
+            allow_internal_unsafe: false,
+            def_crate: None,
         }
     };
     let expn_id = sess.codemap().record_expansion(info);
@@ -58,6 +58,11 @@ struct TestCtxt<'a> {
     ext_cx: ExtCtxt<'a>,
     testfns: Vec<Test>,
     reexport_test_harness_main: Option<InternedString>,
+    // the function named by `#![test_runner(..)]`, which the generated
+    // main hands the test cases to instead of libtest's console runner
+    test_runner: Option<ast::Ident>,
+    // paths to the test cases, when there is a custom test runner
+    test_cases: Vec<Vec<ast::Ident>>,
     is_test_crate: bool,
     config: ast::CrateConfig,
 
@@ -84,8 +89,12 @@ pub fn modify_for_testing(sess: &ParseSess,
         attr::first_attr_value_str_by_name(&krate.attrs,
                                            "reexport_test_harness_main");
 
+    // Likewise for #![test_runner(some_fn)].
+    let test_runner = find_test_runner(span_diagnostic, &krate);
+
     if should_test {
-        generate_test_harness(sess, reexport_test_harness_main, krate, cfg, span_diagnostic)
+        generate_test_harness(sess, reexport_test_harness_main, test_runner,
+                              krate, cfg, span_diagnostic)
     } else {
         strip_test_functions(span_diagnostic, krate)
     }
@@ -121,7 +130,25 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
         }
         debug!("current path: {}", path_name_i(&self.cx.path));
 
-        let i = if is_test_fn(&self.cx, &i) || is_bench_fn(&self.cx, &i) {
+        let i = if self.cx.test_runner.is_some() {
+            if is_test_case(&self.cx, &i) {
+                debug!("this is a test case");
+                self.cx.test_cases.push(self.cx.path.clone());
+                self.tests.push(i.ident);
+
+                // Make the test case public, as the tests are below.
+                i.map(|mut i| {
+                    i.vis = ast::Visibility::Public;
+                    i
+                })
+            } else {
+                i
+            }
+        } else if attr::contains_name(&i.attrs, "test_case") {
+            self.cx.span_diagnostic.span_err(i.span, "`#[test_case]` items can only be run \
+                                                      by a custom `#![test_runner]`");
+            i
+        } else if is_test_fn(&self.cx, &i) || is_bench_fn(&self.cx, &i) {
             match i.node {
                 ast::ItemKind::Fn(_, ast::Unsafety::Unsafe, _, _, _, _) => {
                     let diag = self.cx.span_diagnostic;
@@ -268,8 +295,28 @@ fn mk_reexport_mod(cx: &mut TestCtxt, tests: Vec<ast::Ident>,
     (it, sym)
 }
 
+fn find_test_runner(diagnostic: &errors::Handler, krate: &ast::Crate) -> Option<ast::Ident> {
+    let attr = match krate.attrs.iter().find(|attr| attr.check_name("test_runner")) {
+        Some(attr) => attr,
+        None => return None,
+    };
+    match attr.meta_item_list() {
+        Some(items) if items.len() == 1 => {
+            if let ast::MetaItemKind::Word(ref name) = items[0].node {
+                return Some(token::str_to_ident(name));
+            }
+        }
+        _ => {}
+    }
+    diagnostic.struct_span_err(attr.span, "malformed `test_runner` attribute")
+        .help("name a function at the crate root, e.g. `#![test_runner(my_runner)]`")
+        .emit();
+    None
+}
+
 fn generate_test_harness(sess: &ParseSess,
                          reexport_test_harness_main: Option<InternedString>,
+                         test_runner: Option<ast::Ident>,
                          krate: ast::Crate,
                          cfg: &ast::CrateConfig,
                          sd: &errors::Handler) -> ast::Crate {
@@ -287,6 +334,8 @@ fn generate_test_harness(sess: &ParseSess,
         path: Vec::new(),
         testfns: Vec::new(),
         reexport_test_harness_main: reexport_test_harness_main,
+        test_runner: test_runner,
+        test_cases: Vec::new(),
         is_test_crate: is_test_crate(&krate),
         config: krate.config.clone(),
         toplevel_reexport: None,
@@ -299,6 +348,9 @@ fn generate_test_harness(sess: &ParseSess,
             format: MacroAttribute(intern("test")),
             span: None,
             allow_internal_unstable: false,
+            allow_internal_lints: false,
+            allow_internal_unsafe: false,
+            def_crate: None,
         }
     });
 
@@ -318,7 +370,8 @@ fn strip_test_functions(diagnostic: &errors::Handler, krate: ast::Crate)
     // #[test] functions
     config::strip_items(diagnostic, krate, |attrs| {
         !attr::contains_name(&attrs[..], "test") &&
-        !attr::contains_name(&attrs[..], "bench")
+        !attr::contains_name(&attrs[..], "bench") &&
+        !attr::contains_name(&attrs[..], "test_case")
     })
 }
 
@@ -332,6 +385,9 @@ fn ignored_span(cx: &TestCtxt, sp: Span) -> Span {
             format: MacroAttribute(intern("test")),
             span: None,
             allow_internal_unstable: true,
+            allow_internal_lints: false,
+            allow_internal_unsafe: false,
+            def_crate: None,
         }
     };
     let expn_id = cx.sess.codemap().record_expansion(info);
@@ -414,6 +470,17 @@ fn is_bench_fn(cx: &TestCtxt, i: &ast::Item) -> bool {
     return has_bench_attr && has_test_signature(i);
 }
 
+/// With a custom test runner, `#[test]` functions are test cases just
+/// like `#[test_case]` items are; benchmarks need libtest's `Bencher`.
+fn is_test_case(cx: &TestCtxt, i: &ast::Item) -> bool {
+    if attr::contains_name(&i.attrs, "bench") {
+        cx.span_diagnostic.span_err(i.span, "`#[bench]` functions cannot be run \
+                                             by a custom `#![test_runner]`");
+        return false;
+    }
+    attr::contains_name(&i.attrs, "test_case") || is_test_fn(cx, i)
+}
+
 fn is_ignored(i: &ast::Item) -> bool {
     i.attrs.iter().any(|attr| attr.check_name("ignore"))
 }
@@ -475,17 +542,36 @@ fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
     //            test::test_main_static(::std::os::args().as_slice(), TESTS);
     //        }
 
+    //
+    // or, with a custom test runner:
+    //        pub fn main() {
+    //            #![main]
+    //            ::runner(&[&::__test_reexports::foo::case, ...]);
+    //        }
+
     let sp = ignored_span(cx, DUMMY_SP);
     let ecx = &cx.ext_cx;
 
-    // test::test_main_static
-    let test_main_path = ecx.path(sp, vec![token::str_to_ident("test"),
-                                           token::str_to_ident("test_main_static")]);
-    // test::test_main_static(...)
-    let test_main_path_expr = ecx.expr_path(test_main_path);
-    let tests_ident_expr = ecx.expr_ident(sp, token::str_to_ident("TESTS"));
-    let call_test_main = ecx.expr_call(sp, test_main_path_expr,
-                                       vec![tests_ident_expr]);
+    let call_test_main = match cx.test_runner {
+        Some(runner) => {
+            let cases = cx.test_cases.iter().map(|path| {
+                let case_path = ecx.path_global(sp, visible_path(cx, path));
+                ecx.expr_addr_of(sp, ecx.expr_path(case_path))
+            }).collect();
+            // ::runner(&[...])
+            ecx.expr_call(sp, ecx.expr_path(ecx.path_global(sp, vec![runner])),
+                          vec![ecx.expr_vec_slice(sp, cases)])
+        }
+        None => {
+            // test::test_main_static
+            let test_main_path = ecx.path(sp, vec![token::str_to_ident("test"),
+                                                   token::str_to_ident("test_main_static")]);
+            // test::test_main_static(...)
+            let test_main_path_expr = ecx.expr_path(test_main_path);
+            let tests_ident_expr = ecx.expr_ident(sp, token::str_to_ident("TESTS"));
+            ecx.expr_call(sp, test_main_path_expr, vec![tests_ident_expr])
+        }
+    };
     let call_test_main = ecx.stmt_expr(call_test_main);
     // #![main]
     let main_meta = ecx.meta_word(sp, token::intern_and_get_ident("main"));
@@ -510,19 +596,27 @@ fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
 }
 
 fn mk_test_module(cx: &mut TestCtxt) -> (P<ast::Item>, Option<P<ast::Item>>) {
-    // Link to test crate
-    let import = mk_std(cx);
+    let items = if cx.test_runner.is_some() {
+        // A custom test runner needs neither libtest nor its test
+        // descriptors, just the synthesized main function that calls it.
+        vec![mk_main(cx)]
+    } else {
+        // Link to test crate
+        let import = mk_std(cx);
+
+        // A constant vector of test descriptors.
+        let tests = mk_tests(cx);
 
-    // A constant vector of test descriptors.
-    let tests = mk_tests(cx);
+        // The synthesized main function which will call the console test runner
+        // with our list of tests
+        let mainfn = mk_main(cx);
 
-    // The synthesized main function which will call the console test runner
-    // with our list of tests
-    let mainfn = mk_main(cx);
+        vec![import, mainfn, tests]
+    };
 
     let testmod = ast::Mod {
         inner: DUMMY_SP,
-        items: vec![import, mainfn, tests],
+        items: items,
     };
     let item_ = ast::ItemKind::Mod(testmod);
 
@@ -573,6 +667,20 @@ fn path_node(ids: Vec<ast::Ident> ) -> ast::Path {
     }
 }
 
+/// The path to a test through the top-level re-export module, which
+/// makes it visible from the `__test` module.
+fn visible_path(cx: &TestCtxt, path: &[ast::Ident]) -> Vec<ast::Ident> {
+    let mut visible_path = match cx.toplevel_reexport {
+        Some(id) => vec![id],
+        None => {
+            let diag = cx.span_diagnostic;
+            diag.bug("expected to find top-level re-export name, but found None");
+        }
+    };
+    visible_path.extend_from_slice(path);
+    visible_path
+}
+
 fn path_name_i(idents: &[ast::Ident]) -> String {
     // FIXME: Bad copies (#2543 -- same for everything else that says "bad")
     idents.iter().map(|i| i.to_string()).collect::<Vec<String>>().join("::")
@@ -685,16 +793,7 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
              field("should_panic", fail_expr)]);
 
 
-    let mut visible_path = match cx.toplevel_reexport {
-        Some(id) => vec![id],
-        None => {
-            let diag = cx.span_diagnostic;
-            diag.bug("expected to find top-level re-export name, but found None");
-        }
-    };
-    visible_path.extend(path);
-
-    let fn_expr = ecx.expr_path(ecx.path_global(span, visible_path));
+    let fn_expr = ecx.expr_path(ecx.path_global(span, visible_path(cx, &path)));
 
     let variant_name = if test.bench { "StaticBenchFn" } else { "StaticTestFn" };
     // self::test::$variant_name($fn_expr)
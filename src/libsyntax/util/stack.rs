@@ -0,0 +1,76 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Explicit red-zone checks for the deeply recursive parts of the
+//! compiler (the parser, type folding, trait selection).
+//!
+//! The thread running the compiler records how much stack it was
+//! given with `record_stack_size`. Recursion points then check
+//! `in_red_zone` before recursing further, and report a recursion-limit
+//! error once fewer than `RED_ZONE` bytes of stack remain. This turns
+//! what would be a SIGSEGV on pathologically nested input into an
+//! ordinary diagnostic.
+//!
+//! All targets we support have downward-growing stacks; this module
+//! assumes as much. On threads that never called `record_stack_size`
+//! no checking is done.
+
+use std::cell::Cell;
+
+/// Amount of stack, in bytes, that must remain available before a
+/// recursive step is allowed to proceed.
+pub const RED_ZONE: usize = 256 * 1024;
+
+thread_local!(static STACK_LIMIT: Cell<Option<usize>> = Cell::new(None));
+
+#[inline(always)]
+fn approximate_stack_pointer() -> usize {
+    let marker = 0u8;
+    &marker as *const u8 as usize
+}
+
+/// Records that the current thread has `size` bytes of stack,
+/// starting (approximately) at the caller's frame.
+pub fn record_stack_size(size: usize) {
+    let limit = approximate_stack_pointer().saturating_sub(size);
+    STACK_LIMIT.with(|l| l.set(Some(limit)));
+}
+
+/// Returns the approximate amount of stack remaining on the current
+/// thread, if its size has been recorded.
+pub fn remaining_stack() -> Option<usize> {
+    STACK_LIMIT.with(|l| l.get())
+               .map(|limit| approximate_stack_pointer().saturating_sub(limit))
+}
+
+/// True if fewer than `RED_ZONE` bytes of stack remain.
+pub fn in_red_zone() -> bool {
+    remaining_stack().map_or(false, |remaining| remaining < RED_ZONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn red_zone_follows_recorded_size() {
+        thread::spawn(|| {
+            assert_eq!(remaining_stack(), None);
+            assert!(!in_red_zone());
+
+            record_stack_size(RED_ZONE / 2);
+            assert!(in_red_zone());
+
+            record_stack_size(RED_ZONE * 4);
+            assert!(!in_red_zone());
+        }).join().unwrap();
+    }
+}
@@ -243,6 +243,9 @@ pub fn expand_asm<'cx>(cx: &'cx mut ExtCtxt, sp: Span, tts: &[ast::TokenTree])
             format: codemap::MacroBang(intern("asm")),
             span: None,
             allow_internal_unstable: false,
+            allow_internal_lints: false,
+            allow_internal_unsafe: false,
+            def_crate: None,
         },
     });
 
@@ -1421,6 +1421,9 @@ impl<'a> TraitDef<'a> {
                 format: codemap::MacroAttribute(intern(&format!("derive({})", trait_name))),
                 span: Some(self.span),
                 allow_internal_unstable: false,
+                allow_internal_lints: false,
+                allow_internal_unsafe: false,
+                def_crate: None,
             }
         });
         to_set
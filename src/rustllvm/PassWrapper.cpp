@@ -23,6 +23,7 @@
 #endif
 #include "llvm/Target/TargetMachine.h"
 #include "llvm/Target/TargetSubtargetInfo.h"
+#include "llvm/MC/MCSubtargetInfo.h"
 #include "llvm/Transforms/IPO/PassManagerBuilder.h"
 
 
@@ -146,6 +147,103 @@ LLVMRustDisposeTargetMachine(LLVMTargetMachineRef TM) {
     delete unwrap(TM);
 }
 
+// The tables of CPUs and features a target knows about are only exposed
+// through accessors that our fork of LLVM adds to `MCSubtargetInfo`. When
+// building against any other LLVM we report empty tables; the compiler then
+// still validates names with the queries below, but cannot suggest the
+// closest one or list the features a CPU implies.
+#if LLVM_RUSTLLVM
+static ArrayRef<SubtargetFeatureKV>
+getCPUTable(LLVMTargetMachineRef TM) {
+    return unwrap(TM)->getMCSubtargetInfo()->getCPUTable();
+}
+
+static ArrayRef<SubtargetFeatureKV>
+getFeatureTable(LLVMTargetMachineRef TM) {
+    return unwrap(TM)->getMCSubtargetInfo()->getFeatureTable();
+}
+
+extern "C" size_t
+LLVMRustGetTargetCPUsCount(LLVMTargetMachineRef TM) {
+    return getCPUTable(TM).size();
+}
+
+extern "C" void
+LLVMRustGetTargetCPU(LLVMTargetMachineRef TM, size_t Index,
+                     const char** Name, const char** Desc) {
+    const SubtargetFeatureKV &CPU = getCPUTable(TM)[Index];
+    *Name = CPU.Key;
+    *Desc = CPU.Desc;
+}
+
+extern "C" size_t
+LLVMRustGetTargetFeaturesCount(LLVMTargetMachineRef TM) {
+    return getFeatureTable(TM).size();
+}
+
+extern "C" void
+LLVMRustGetTargetFeature(LLVMTargetMachineRef TM, size_t Index,
+                         const char** Name, const char** Desc) {
+    const SubtargetFeatureKV &Feature = getFeatureTable(TM)[Index];
+    *Name = Feature.Key;
+    *Desc = Feature.Desc;
+}
+#else
+extern "C" size_t
+LLVMRustGetTargetCPUsCount(LLVMTargetMachineRef) {
+    return 0;
+}
+
+extern "C" void
+LLVMRustGetTargetCPU(LLVMTargetMachineRef, size_t, const char**, const char**) {
+    llvm_unreachable("no target CPUs are known to this LLVM");
+}
+
+extern "C" size_t
+LLVMRustGetTargetFeaturesCount(LLVMTargetMachineRef) {
+    return 0;
+}
+
+extern "C" void
+LLVMRustGetTargetFeature(LLVMTargetMachineRef, size_t, const char**, const char**) {
+    llvm_unreachable("no target features are known to this LLVM");
+}
+#endif
+
+// Any LLVM can tell whether it knows a given CPU or feature, though. A
+// feature is known if toggling it on a fresh subtarget changes any feature
+// bits.
+extern "C" bool
+LLVMRustHasTargetCPU(LLVMTargetMachineRef TM, const char *CPU) {
+    return unwrap(TM)->getMCSubtargetInfo()->isCPUStringValid(CPU);
+}
+
+static std::unique_ptr<MCSubtargetInfo>
+createSubtargetInfo(LLVMTargetMachineRef TM, StringRef Features) {
+    TargetMachine *Target = unwrap(TM);
+    return std::unique_ptr<MCSubtargetInfo>(Target->getTarget().createMCSubtargetInfo(
+        Target->getTargetTriple().getTriple(), Target->getTargetCPU(), Features));
+}
+
+extern "C" bool
+LLVMRustHasTargetFeature(LLVMTargetMachineRef TM, const char *Feature) {
+    std::unique_ptr<MCSubtargetInfo> STI = createSubtargetInfo(TM, "");
+    FeatureBitset Before = STI->getFeatureBits();
+    return STI->ToggleFeature(Feature) != Before;
+}
+
+// Whether `Feature` is enabled for the target machine's CPU and features,
+// including by implication. Toggling an enabled feature only clears bits,
+// while toggling a disabled one only sets them.
+extern "C" bool
+LLVMRustIsTargetFeatureEnabled(LLVMTargetMachineRef TM, const char *Feature) {
+    std::unique_ptr<MCSubtargetInfo> STI =
+        createSubtargetInfo(TM, unwrap(TM)->getTargetFeatureString());
+    FeatureBitset Before = STI->getFeatureBits();
+    FeatureBitset After = STI->ToggleFeature(Feature);
+    return After != Before && (After & ~Before).none();
+}
+
 // Unfortunately, LLVM doesn't expose a C API to add the corresponding analysis
 // passes for a target to a pass manager. We export that functionality through
 // this function.
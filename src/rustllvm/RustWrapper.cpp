@@ -120,6 +120,10 @@ extern "C" void LLVMAddDereferenceableCallSiteAttr(LLVMValueRef Instr, unsigned
                                                          idx, B)));
 }
 
+extern "C" void LLVMRustSetMustTailCall(LLVMValueRef Instr) {
+  unwrap<CallInst>(Instr)->setTailCallKind(CallInst::TCK_MustTail);
+}
+
 extern "C" void LLVMAddFunctionAttribute(LLVMValueRef Fn, unsigned index,
                                          uint64_t Val) {
   Function *A = unwrap<Function>(Fn);
@@ -959,6 +963,25 @@ LLVMUnpackInlineAsmDiagnostic(
     *instruction_out = wrap(ia->getInstruction());
 }
 
+extern "C" void
+LLVMRustUnpackStackSizeDiagnostic(
+    LLVMDiagnosticInfoRef di,
+    LLVMValueRef *function_out,
+    uint64_t *stack_size_out)
+{
+    // Undefined to call this not on a stack size diagnostic!
+    llvm::DiagnosticInfoStackSize *ss
+        = static_cast<llvm::DiagnosticInfoStackSize*>(unwrap(di));
+
+    *function_out = wrap(&ss->getFunction());
+    *stack_size_out = ss->getStackSize();
+}
+
+extern "C" void
+LLVMRustAppendModuleInlineAsm(LLVMModuleRef M, const char *Asm) {
+    unwrap(M)->appendModuleInlineAsm(StringRef(Asm));
+}
+
 extern "C" void LLVMWriteDiagnosticInfoToString(LLVMDiagnosticInfoRef di, RustStringRef str) {
     raw_rust_string_ostream os(str);
     DiagnosticPrinterRawOStream dp(os);
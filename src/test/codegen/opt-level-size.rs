@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C opt-level=z
+
+#![crate_type = "lib"]
+
+// -C opt-level=z asks every function to be optimized for size, not just
+// the pass manager.
+
+// CHECK: Function Attrs: minsize{{.*}}optsize
+// CHECK-NEXT: define i32 @add_one
+#[no_mangle]
+pub fn add_one(x: i32) -> i32 {
+    x.wrapping_add(1)
+}
@@ -30,6 +30,9 @@ fn main() {
         callee: syntax::codemap::NameAndSpan {
             format: syntax::codemap::MacroBang(parse::token::intern("")),
             allow_internal_unstable: false,
+            allow_internal_lints: false,
+            allow_internal_unsafe: false,
+            def_crate: None,
             span: None,
         }
     });
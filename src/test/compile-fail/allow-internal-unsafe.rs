@@ -0,0 +1,46 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// An `unsafe` block inside an `#[allow_internal_unsafe]` macro covers the
+// macro's own code, but not the expressions its caller passes in.
+
+#![feature(allow_internal_unsafe)]
+#![deny(unsafe_code)]
+
+#[allow_internal_unsafe]
+macro_rules! read {
+    ($p:expr) => {{
+        let p: *const u8 = $p;
+        unsafe { *p }
+    }}
+}
+
+#[allow_internal_unsafe]
+macro_rules! with_unsafe {
+    ($e:expr) => { unsafe { $e } }
+}
+
+#[allow_internal_unsafe]
+macro_rules! read_twice {
+    ($p:expr) => { read!($p) + read!($p) }
+}
+
+fn main() {
+    let x = 1u8;
+    let p = &x as *const u8;
+    let _ = read!(p);
+    let _ = read_twice!(p);
+    let _ = with_unsafe!(*p); //~ ERROR dereference of raw pointer requires unsafe
+}
+
+#[allow(unsafe_code)]
+fn nested(p: *const u8) -> u8 {
+    with_unsafe!(unsafe { *p })
+}
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Normalizing either projection requires normalizing the other, which
+// is reported as an overflow rather than recursing forever.
+
+trait Foo {
+    type A;
+}
+
+trait Bar {
+    type B;
+}
+
+struct S;
+
+impl Foo for S {
+//~^ ERROR overflow evaluating the requirement
+    type A = <S as Bar>::B;
+}
+
+impl Bar for S {
+    type B = <S as Foo>::A;
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+// Errors that are only found when translating a monomorphized function
+// explain which instantiations led to it.
+
+use std::intrinsics;
+
+#[derive(Copy, Clone)]
+struct Foo(i64);
+
+unsafe fn count<T>(v: T) -> T {
+    intrinsics::cttz(v)
+    //~^ ERROR `cttz` intrinsic: expected basic integer type, found `Foo`
+}
+
+fn outer<T>(v: T) -> T {
+    unsafe { count(v) } //~ NOTE required by the instantiation of `count::<Foo>`
+}
+
+fn main() {
+    outer(Foo(0)); //~ NOTE in `main`
+}
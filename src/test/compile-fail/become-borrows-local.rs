@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(explicit_tail_calls)]
+
+fn len(v: &[u32]) -> usize { v.len() }
+
+fn borrows_local(_: &[u32]) -> usize {
+    let local = [1, 2, 3];
+    become len(&local) //~ ERROR `local` does not live long enough
+}
+
+fn main() {
+    borrows_local(&[]);
+}
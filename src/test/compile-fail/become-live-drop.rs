@@ -0,0 +1,37 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(explicit_tail_calls)]
+
+fn consume(v: Vec<u32>) -> usize { v.len() }
+
+fn keeps_local(v: Vec<u32>) -> usize {
+    let other = vec![1, 2, 3]; //~ NOTE this value would have to be dropped
+    become consume(v) //~ ERROR `become` would leave a value with a destructor live
+}
+
+fn drops_on_one_path(v: Vec<u32>, early: bool) -> usize {
+    let other = vec![1, 2, 3]; //~ NOTE this value would have to be dropped
+    if early {
+        drop(other);
+    }
+    become consume(v) //~ ERROR `become` would leave a value with a destructor live
+}
+
+fn passes_local() -> usize {
+    let other = vec![1, 2, 3];
+    become consume(other)
+}
+
+fn main() {
+    keeps_local(vec![]);
+    drops_on_one_path(vec![], true);
+    passes_local();
+}
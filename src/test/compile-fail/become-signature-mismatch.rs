@@ -0,0 +1,36 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(explicit_tail_calls)]
+
+fn one(x: u32) -> u32 { x }
+
+fn two(x: u32, y: u32) -> u32 { x + y }
+
+fn not_a_call(x: u32) -> u32 {
+    become x //~ ERROR E0526
+}
+
+fn wrong_arity(x: u32) -> u32 {
+    become two(x, x) //~ ERROR E0527
+}
+
+fn right_arity(x: u32, y: u32) -> u32 {
+    become two(y, x)
+}
+
+fn same(x: u32) -> u32 {
+    become one(x)
+}
+
+fn main() {
+    right_arity(1, 2);
+    same(1);
+}
@@ -0,0 +1,51 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Bindings after `@` that would take ownership of, or conflicting borrows
+// of, the same value are rejected.
+
+#![feature(bindings_after_at)]
+
+fn move_and_move(x: Option<String>) {
+    match x {
+        a @ Some(b) => {}
+        //~^ ERROR cannot bind by-move with sub-bindings
+        //~| ERROR use of partially moved value
+        None => {}
+    }
+}
+
+fn move_and_ref(x: Option<String>) {
+    match x {
+        a @ Some(ref b) => {}
+        //~^ ERROR cannot bind by-move and by-ref in the same pattern
+        //~| ERROR cannot move out of `x` because it is borrowed
+        None => {}
+    }
+}
+
+fn ref_and_move(x: Option<String>) {
+    match x {
+        ref a @ Some(b) => {}
+        //~^ ERROR cannot bind by-move and by-ref in the same pattern
+        //~| ERROR use of partially moved value
+        None => {}
+    }
+}
+
+fn ref_mut_and_ref(mut x: Option<u32>) {
+    match x {
+        ref mut a @ Some(ref b) => {}
+        //~^ ERROR cannot borrow
+        None => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that E0282 points at the closure argument whose type could
+// not be inferred.
+
+fn main() {
+    let _f = |x| ();
+    //~^ ERROR unable to infer enough type information about `_`
+    //~| NOTE cannot infer the type of the closure argument introduced here
+}
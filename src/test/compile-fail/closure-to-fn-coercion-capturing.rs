@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Closures that capture their environment do not coerce to fn pointers.
+
+#![feature(closure_to_fn_coercion)]
+
+fn main() {
+    let n = 1;
+    let f: fn(u8) -> u8 = |x| x + n;
+    //~^ ERROR mismatched types
+    //~| expected fn pointer
+    //~| found closure
+    f(0);
+}
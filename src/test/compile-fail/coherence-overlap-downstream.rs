@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that an overlap which only exists if a downstream crate adds
+// an impl is still an error, and says so.
+
+pub trait Bar {}
+pub trait Foo {}
+
+impl<T: Bar> Foo for T {}
+//~^ NOTE conflicting implementation is here
+
+impl<T> Foo for Box<T> {}
+//~^ ERROR conflicting implementations of trait `Foo` for type `Box<_>`
+//~| NOTE this implementation is `impl<T> Foo for Box<T>`
+//~| NOTE both implementations apply to `Box<_>: Foo`
+//~| NOTE downstream crates may implement trait `Bar` for type `Box<_>`
+
+fn main() {}
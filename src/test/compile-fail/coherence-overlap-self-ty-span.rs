@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The overlap error points at the self types of the conflicting impls.
+
+trait Foo {}
+
+impl<T: Copy> Foo for T {}
+//~^ NOTE conflicting implementation is here: `impl<T> Foo for T`
+
+impl Foo
+    for (u8, u16) {}
+//~^ ERROR conflicting implementations of trait `Foo` for type `(u8, u16)`
+//~| NOTE this implementation is `impl Foo for (u8, u16)`
+//~| NOTE both implementations apply to `(u8, u16): Foo`
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that an overlap which relies on an impl an upstream crate
+// could add in a future version says so.
+
+pub trait Foo {}
+
+impl<T: Copy> Foo for T {}
+//~^ NOTE conflicting implementation is here: `impl<T> Foo for T`
+
+impl<U> Foo for Vec<U> {}
+//~^ ERROR conflicting implementations of trait `Foo` for type `std::vec::Vec<_>`
+//~| NOTE this implementation is `impl<U> Foo for std::vec::Vec<U>`
+//~| NOTE both implementations apply to `std::vec::Vec<_>: Foo`
+//~| NOTE upstream crates may add a new impl of trait `std::marker::Copy`
+
+fn main() {}
@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that overlapping impls report both impl headers and the
+// trait reference they both apply to.
+
+trait Foo<X> {}
+
+impl<T, U> Foo<U> for Vec<T> {}
+//~^ NOTE conflicting implementation is here: `impl<T, U> Foo<U> for std::vec::Vec<T>`
+
+impl<V> Foo<u8> for Vec<V> {}
+//~^ ERROR conflicting implementations of trait `Foo<u8>` for type `std::vec::Vec<_>`
+//~| NOTE this implementation is `impl<V> Foo<u8> for std::vec::Vec<V>`
+//~| NOTE both implementations apply to `std::vec::Vec<_>: Foo<u8>`
+
+fn main() {}
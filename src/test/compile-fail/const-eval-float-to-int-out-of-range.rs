@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z unsaturated-float-casts
+
+#![deny(const_err)]
+
+// Without saturation, float to integer casts that the target type cannot
+// represent have no portable value, so constant evaluation rejects them.
+
+const IN_RANGE: u8 = 255.9 as u8;
+const NEGATIVE_TO_UNSIGNED: u8 = -1.0 as u8;
+//~^ ERROR constant evaluation error
+const TOO_LARGE: i32 = 2147483648.0 as i32;
+//~^ ERROR constant evaluation error
+const NAN: u32 = (0.0 / 0.0) as u32;
+//~^ ERROR constant evaluation error
+
+fn main() {
+    let _ = (IN_RANGE, NEGATIVE_TO_UNSIGNED, TOO_LARGE, NAN);
+}
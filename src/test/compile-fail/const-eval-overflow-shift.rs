@@ -0,0 +1,48 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![allow(unused_imports, exceeding_bitshifts)]
+
+// Shifts, division and remainder in constants follow the same overflow
+// rules whatever the width of the type.
+
+use std::fmt;
+use std::{i8, i64};
+
+const VALS_I8: (i8, i8, i8, i8) =
+    (1i8 << 8,
+     //~^ ERROR attempted to shift left with overflow
+     1i8 >> 8,
+     //~^ ERROR attempted to shift right with overflow
+     i8::MIN / -1,
+     //~^ ERROR attempted to divide with overflow
+     i8::MIN % -1,
+     //~^ ERROR attempted to calculate the remainder with overflow
+     );
+
+const VALS_I64: (i64, i64, i64, i64) =
+    (1i64 << 64,
+     //~^ ERROR attempted to shift left with overflow
+     1i64 >> 64,
+     //~^ ERROR attempted to shift right with overflow
+     i64::MIN / -1,
+     //~^ ERROR attempted to divide with overflow
+     i64::MIN % -1,
+     //~^ ERROR attempted to calculate the remainder with overflow
+     );
+
+fn main() {
+    foo(VALS_I8);
+    foo(VALS_I64);
+}
+
+fn foo<T:fmt::Debug>(x: T) {
+    println!("{:?}", x);
+}
@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags: --test
+
+// Items that are only used by `#[cfg(not(test))]` code are not dead in
+// a test build.
+
+#![deny(dead_code)]
+
+fn run() {}
+
+fn unused() {} //~ ERROR function is never used
+
+#[cfg(not(test))]
+fn main() {
+    run();
+}
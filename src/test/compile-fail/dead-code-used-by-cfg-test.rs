@@ -0,0 +1,54 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Items that are only used by `#[cfg(test)]` code are not dead in a
+// normal build. As that code is not resolved, an item counts as used if
+// its name is mentioned within its module or in a path through it.
+
+#![deny(dead_code)]
+
+struct Fixture {
+    value: u32,
+}
+
+fn setup() -> u32 {
+    1
+}
+
+fn helper() -> u32 {
+    setup() + 1
+}
+
+fn unused() {} //~ ERROR function is never used
+
+mod fixtures {
+    pub fn make() -> u32 {
+        2
+    }
+}
+
+mod other {
+    // Named like an item that the tests use, but not in scope there.
+    pub fn helper() -> u32 { //~ ERROR function is never used
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn it_works() {
+        let f = super::Fixture { value: ::fixtures::make() };
+        assert_eq!(super::helper(), f.value);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(generic_param_attrs)]
+#![feature(dropck_eyepatch)]
+
+// `#[may_dangle]` is an unchecked promise about the destructor, so
+// the impl carrying it must be declared `unsafe`.
+
+struct Pt<A>(A);
+impl<#[may_dangle] A> Drop for Pt<A> {
+    //~^ ERROR requires an `unsafe impl` declaration due to `#[may_dangle]` attribute
+    fn drop(&mut self) { }
+}
+
+struct Pr<'a>(&'a u8);
+impl<#[may_dangle] 'a> Drop for Pr<'a> {
+    //~^ ERROR requires an `unsafe impl` declaration due to `#[may_dangle]` attribute
+    fn drop(&mut self) { }
+}
+
+fn main() {}
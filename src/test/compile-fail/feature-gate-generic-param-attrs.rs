@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that attributes on generic parameters are rejected if the
+// `generic_param_attrs` feature gate is absent.
+
+#![feature(dropck_eyepatch)]
+
+struct Pt<A>(A);
+unsafe impl<#[may_dangle] A> Drop for Pt<A> {
+    //~^ ERROR attributes on type parameter bindings are experimental
+    fn drop(&mut self) { }
+}
+
+struct Pr<'a>(&'a u8);
+unsafe impl<#[may_dangle] 'a> Drop for Pr<'a> {
+    //~^ ERROR attributes on lifetime bindings are experimental
+    fn drop(&mut self) { }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn f(x: [u8]) -> usize {
+    //~^ ERROR `[u8]: std::marker::Sized` is not satisfied
+    x.len()
+}
+
+fn main() {
+    let b: Box<FnOnce() -> i32> = Box::new(|| 1);
+    (*b)(); //~ ERROR cannot move a value of type std::ops::FnOnce() -> i32
+}
@@ -0,0 +1,41 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Deref;
+
+struct Inner {
+    len: usize,
+}
+
+struct Outer {
+    inner: Inner,
+}
+
+impl Deref for Outer {
+    type Target = Inner;
+    fn deref(&self) -> &Inner { &self.inner }
+}
+
+fn main() {
+    let o = Outer { inner: Inner { len: 0 } };
+    o.lenght;
+    //~^ ERROR attempted access of field `lenght`
+    //~| HELP did you mean `len`?
+
+    let opt = Some(Inner { len: 0 });
+    opt.len;
+    //~^ ERROR attempted access of field `len`
+    //~| HELP try accessing it with `as_ref`
+
+    let p = &o.inner as *const Inner;
+    p.len;
+    //~^ ERROR attempted access of field `len`
+    //~| HELP try dereferencing it
+}
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that higher-ranked fn pointer types with a different binding
+// structure are not equal, in either direction.
+
+fn foo<'a>(_: &'a u8, _: &'a u8) {}
+fn bar<'a, 'b>(_: &'a u8, _: &'b u8) {}
+
+fn main() {
+    let mut f: for<'x> fn(&'x u8, &'x u8) = foo;
+    let _: &mut for<'x, 'y> fn(&'x u8, &'y u8) = &mut f;
+    //~^ ERROR mismatched types
+
+    let mut g: for<'x, 'y> fn(&'x u8, &'y u8) = bar;
+    let _: &mut for<'x> fn(&'x u8, &'x u8) = &mut g;
+    //~^ ERROR mismatched types
+}
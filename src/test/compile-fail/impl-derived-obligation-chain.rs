@@ -0,0 +1,47 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+
+// Check that an unsatisfied bound deep inside nested generics is
+// reported along with the impls that required it.
+
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+struct Wrapper<T>(T);
+struct Pair<A, B>(A, B);
+
+impl<T: Describe> Describe for Wrapper<T> {
+//~^ NOTE required because of the requirements on the impl of `Describe` for `Wrapper<NoDescribe>`
+    fn describe(&self) -> String { self.0.describe() }
+}
+
+impl<A: Describe, B: Describe> Describe for Pair<A, B> {
+//~^ NOTE required because of the requirements on the impl of `Describe` for `Pair<u8, Wrapper<NoDescribe>>`
+    fn describe(&self) -> String { self.0.describe() + &self.1.describe() }
+}
+
+impl Describe for u8 {
+    fn describe(&self) -> String { self.to_string() }
+}
+
+struct NoDescribe;
+
+fn show<T: Describe>(t: T) -> String {
+    t.describe()
+}
+
+fn main() {
+    show(Pair(0u8, Wrapper(NoDescribe)));
+    //~^ ERROR the trait bound `NoDescribe: Describe` is not satisfied
+    //~| NOTE required by `show`
+}
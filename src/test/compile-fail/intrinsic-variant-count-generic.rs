@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::variant_count;
+
+fn generic<T>() -> usize {
+    let table = [0u8; unsafe { variant_count::<T>() }];
+    //~^ ERROR expected constant integer for repeat count
+    table.len()
+}
+
+fn main() {
+    generic::<Option<u8>>();
+}
@@ -0,0 +1,31 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Array lengths in signatures are evaluated before the type argument of
+// `variant_count` is known unless it names the type's definition; such
+// calls must not count zero variants.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::variant_count;
+
+enum Color { Red, Green, Blue }
+
+type Alias = Color;
+
+static BY_ALIAS: [u8; unsafe { variant_count::<Alias>() }] = [0; 3];
+//~^ ERROR `variant_count` of a generic or unknown type
+
+static BY_REF: [u8; unsafe { variant_count::<&'static Color>() }] = [0; 0];
+//~^ ERROR `variant_count` of a generic or unknown type
+
+fn main() {
+    let _ = (Color::Red, Color::Green, Color::Blue);
+}
@@ -15,7 +15,14 @@ trait Trait<'a> {
 
 fn foo<'a, T: Trait<'a>>(value: T::A) {
     let new: T::B = unsafe { std::mem::transmute(value) };
-//~^ ERROR: cannot transmute to or from a type that contains unsubstituted type parameters [E0139]
+//~^ ERROR: transmute called with differently sized types: u32 (32 bits) to u8 (8 bits) [E0512]
 }
 
-fn main() { }
+impl<'a> Trait<'a> for () {
+    type A = u32;
+    type B = u8;
+}
+
+fn main() {
+    foo::<()>(0); //~ NOTE required by the instantiation of `foo::<()>` in `main`
+}
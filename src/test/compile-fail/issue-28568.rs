@@ -11,12 +11,14 @@
 struct MyStruct;
 
 impl Drop for MyStruct {
-//~^ NOTE conflicting implementation is here
+//~^ NOTE conflicting implementation is here: `impl std::ops::Drop for MyStruct`
     fn drop(&mut self) { }
 }
 
 impl Drop for MyStruct {
 //~^ ERROR conflicting implementations of trait
+//~| NOTE this implementation is `impl std::ops::Drop for MyStruct`
+//~| NOTE both implementations apply to `MyStruct: std::ops::Drop`
     fn drop(&mut self) { }
 }
 
@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a method defined in an inherent impl for another
+// instantiation of the receiver type is pointed out.
+
+struct Foo<T>(T);
+
+impl Foo<i32> {
+    fn bar(&self) {}
+}
+
+impl Foo<bool> {
+    fn bar(&self) {}
+}
+
+fn main() {
+    Foo(1u8).bar();
+    //~^ ERROR no method named `bar` found for type `Foo<u8>` in the current scope
+    //~| NOTE the method was found for `Foo<i32>`, `Foo<bool>`
+}
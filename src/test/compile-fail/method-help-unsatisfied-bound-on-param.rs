@@ -0,0 +1,40 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-tidy-linelength
+
+// Check that when a method exists but the bounds of its impl are not
+// satisfied by a type parameter, the parameter is pointed out along with
+// the bounds it needs.
+
+struct Wrapper<T>(T);
+
+impl<T: Clone + Default> Wrapper<T> {
+    fn duplicate(&self) -> (T, T) {
+        (self.0.clone(), self.0.clone())
+    }
+}
+
+fn unbounded<T>(w: Wrapper<T>) {
+    //~^ HELP consider restricting `T`:
+    w.duplicate();
+    //~^ ERROR no method named `duplicate` found for type `Wrapper<T>` in the current scope
+    //~| NOTE the method `duplicate` exists but the following trait bounds were not satisfied: `T: std::clone::Clone`, `T: std::default::Default`
+}
+
+fn bounded<T: Clone>(w: Wrapper<T>) {
+    //~^ HELP consider adding `std::default::Default` to the bounds of `T`
+    w.duplicate();
+    //~^ ERROR no method named `duplicate` found for type `Wrapper<T>` in the current scope
+    //~| NOTE the method `duplicate` exists but the following trait bounds were not satisfied: `T: std::default::Default`
+}
+
+fn main() {}
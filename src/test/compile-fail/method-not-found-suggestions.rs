@@ -0,0 +1,32 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Frob {
+    fn frob(&self);
+}
+
+fn generic<T>(t: T) {
+    t.frob();
+    //~^ ERROR no method named `frob` found for type `T` in the current scope
+    //~| HELP perhaps you need to restrict type parameter by it
+    //~| HELP candidate #1: `T: Frob`
+}
+
+struct Counter;
+
+impl Counter {
+    fn increment(&self) {}
+}
+
+fn main() {
+    Counter.incremnt();
+    //~^ ERROR no method named `incremnt` found for type `Counter` in the current scope
+    //~| HELP did you mean `increment`?
+}
@@ -0,0 +1,31 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// That a type has no impl of a foreign trait does not prove a negative
+// bound on it, since the crate of the trait could add an impl; only a
+// negative impl does.
+
+#![feature(negative_bounds)]
+
+use std::fmt::Display;
+
+fn not_display<T: !Display>(_: T) {}
+
+struct NoImpl;
+
+struct NegativeImpl;
+
+impl !Display for NegativeImpl {}
+
+fn main() {
+    not_display(NegativeImpl);
+    not_display(NoImpl);
+    //~^ ERROR the requirement `NoImpl: !std::fmt::Display` is not satisfied
+}
@@ -0,0 +1,59 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z nll
+
+// Loans whose references are still live are checked against
+// conflicting accesses under liveness-based regions.
+
+fn assign_while_borrowed() {
+    let mut x = 1;
+    let r = &mut x;
+    x = 2; //~ ERROR cannot assign to `x` because it is borrowed
+    *r = 3;
+}
+
+fn borrow_while_mutably_borrowed() {
+    let mut v = vec![1];
+    let first = &mut v[0];
+    let n = v.len(); //~ ERROR cannot borrow `v` as immutable because it is also borrowed as mutable
+    *first = n as i32;
+}
+
+fn two_mutable_borrows() {
+    let mut x = 1;
+    let a = &mut x;
+    let b = &mut x; //~ ERROR cannot borrow `x` as mutable more than once at a time
+    *a += *b;
+}
+
+fn move_while_borrowed() {
+    let s = String::new();
+    let r = &s;
+    let t = s; //~ ERROR cannot move out of `s` because it is borrowed
+    r.len();
+}
+
+fn use_while_mutably_borrowed() {
+    let mut x = 1;
+    let r = &mut x;
+    let y = x; //~ ERROR cannot use `x` because it was mutably borrowed
+    *r = y;
+}
+
+fn borrow_live_across_back_edge(n: usize) {
+    let mut x = 0;
+    let mut v = Vec::new();
+    for _ in 0..n {
+        v.push(&mut x); //~ ERROR cannot borrow `x` as mutable more than once at a time
+    }
+}
+
+fn main() {}
@@ -14,6 +14,8 @@
 
 trait Bar {
     fn bar<T>(&self, t: T);
+    //~^ NOTE method `bar` has generic type parameters
+    //~| NOTE method `bar` has generic type parameters
 }
 
 trait Quux {
@@ -23,13 +25,11 @@ trait Quux {
 
 fn make_bar<T:Bar>(t: &T) -> &Bar {
         //~^ ERROR E0038
-        //~| NOTE method `bar` has generic type parameters
     t
 }
 
 fn make_bar_explicit<T:Bar>(t: &T) -> &Bar {
     //~^ ERROR E0038
-    //~^^ NOTE method `bar` has generic type parameters
     t as &Bar
 }
 
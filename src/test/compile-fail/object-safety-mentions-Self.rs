@@ -14,10 +14,12 @@
 
 trait Bar {
     fn bar(&self, x: &Self);
+    //~^ NOTE method `bar` references the `Self` type in its arguments or return type
 }
 
 trait Baz {
     fn bar(&self) -> Self;
+    //~^ NOTE method `bar` references the `Self` type in its arguments or return type
 }
 
 trait Quux {
@@ -26,13 +28,11 @@ trait Quux {
 
 fn make_bar<T:Bar>(t: &T) -> &Bar {
         //~^ ERROR E0038
-        //~| NOTE method `bar` references the `Self` type in its arguments or return type
     loop { }
 }
 
 fn make_baz<T:Baz>(t: &T) -> &Baz {
         //~^ ERROR E0038
-        //~| NOTE method `bar` references the `Self` type in its arguments or return type
     t
 }
 
@@ -0,0 +1,34 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-tidy-linelength
+
+// Check that each method that keeps a trait from being made into an
+// object is pointed at, along with how it could be fixed.
+
+trait Foo {
+    fn new() -> Self;
+    //~^ NOTE method `new` has no receiver
+    //~| HELP consider giving `new` a `&self` receiver, or adding `where Self: Sized` to it so that it is not required on trait objects
+    fn same(&self, other: &Self) -> bool;
+    //~^ NOTE method `same` references the `Self` type in its arguments or return type
+    //~| HELP consider adding `where Self: Sized` to `same` so that it is not callable on trait objects
+    fn map<T>(&self, t: T) -> T;
+    //~^ NOTE method `map` has generic type parameters
+    //~| HELP consider adding `where Self: Sized` to `map` so that it is not callable on trait objects
+    fn fine(&self) -> u32;
+    fn exempt(&self, other: Self) where Self: Sized;
+}
+
+fn foo(_: &Foo) {}
+//~^ ERROR E0038
+
+fn main() {}
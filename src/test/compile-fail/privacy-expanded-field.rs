@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A private field is no more accessible through a macro than it is
+// when written out by hand.
+
+#[macro_use]
+mod a {
+    pub struct S {
+        x: i32,
+    }
+
+    pub fn s() -> S {
+        S { x: 0 }
+    }
+
+    macro_rules! x_of {
+        ($e:expr) => { $e.x } //~ ERROR field `x` of struct `a::S` is private
+    }
+}
+
+fn main() {
+    let s = a::s();
+    x_of!(s);
+    s.x; //~ ERROR field `x` of struct `a::S` is private
+}
@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Relating a projection that cannot be normalized to another type
+// records the equality as an obligation instead of failing on the spot,
+// so the error is reported when that obligation is processed.
+
+trait Tr {
+    type Out;
+}
+
+fn f<T: Tr>(x: T::Out) {
+    match x {
+        0u32 => {} //~ ERROR type mismatch resolving `<T as Tr>::Out == u32`
+        _ => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Anonymous bound regions are reported under synthesized names rather
+// than as internal region representations.
+
+fn first(x: &'static u32, _: &u32) -> &'static u32 { x }
+
+fn named<'a>(x: &'a u32) -> &'a u32 { x }
+
+fn main() {
+    let _: fn(&u32, &u32) -> &'static u32 = first;
+    //~^ ERROR mismatched types
+    //~| expected bound lifetime parameter `'1`, found concrete lifetime
+    //~| NOTE concrete lifetime that was found is the static lifetime
+
+    let _: fn(&'static u32) -> &'static u32 = named;
+}
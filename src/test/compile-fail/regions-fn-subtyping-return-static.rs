@@ -58,7 +58,7 @@ fn supply_G() {
     //~| expected `fn(&'cx S) -> &'static S`
     //~| found `fn(&S) -> &S {baz}`
     //~| expected concrete lifetime
-    //~| found bound lifetime parameter 'cx
+    //~| found bound lifetime parameter `'cx`
 }
 
 pub fn main() {
@@ -0,0 +1,38 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the `#[rustc_layout]` attribute, which reports the layout computed
+// for a type. The types used here have the same layout on all targets.
+
+#![feature(rustc_attrs)]
+#![allow(dead_code)]
+
+#[rustc_layout]
+struct Padded { a: u8, b: u32, c: u16 }
+//~^ ERROR layout of `Padded`: size 12, align 4, univariant
+//~| NOTE variant 0: field offsets [0, 4, 8]
+
+#[rustc_layout]
+#[repr(packed)]
+struct Packed { a: u8, b: u32, c: u16 }
+//~^ ERROR layout of `Packed`: size 7, align 1, univariant
+//~| NOTE variant 0: field offsets [0, 1, 5]
+
+#[rustc_layout]
+enum CLike { A, B, C }
+//~^ ERROR layout of `CLike`: size 1, align 1, c-like enum (u8, discriminants 0 to 2)
+
+#[rustc_layout]
+enum Tagged { A(u8), B(u32) }
+//~^ ERROR layout of `Tagged`: size 8, align 4, general enum (u8 discriminant)
+//~| NOTE variant 0: field offsets [0, 1]
+//~| NOTE variant 1: field offsets [0, 4]
+
+fn main() {}
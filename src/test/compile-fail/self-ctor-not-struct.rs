@@ -0,0 +1,35 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Braced {
+    x: u8,
+}
+
+impl Braced {
+    fn new() -> Self {
+        Self(0) //~ ERROR `Self` does not name a tuple or unit struct
+    }
+
+    fn literal() -> Self {
+        Self { x: 0 }
+    }
+}
+
+enum Choice {
+    A,
+}
+
+impl Choice {
+    fn new() -> Self {
+        Self { } //~ ERROR `Self` does not name a structure
+    }
+}
+
+fn main() {}
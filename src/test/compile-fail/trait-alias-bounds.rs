@@ -0,0 +1,48 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(trait_alias)]
+
+use std::rc::Rc;
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+trait SendShape = Shape + Send;
+trait EqAlias = PartialEq;
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Rc<Square> {
+    fn area(&self) -> f64 {
+        (**self).area()
+    }
+}
+
+fn total_area<T: SendShape>(shapes: &[T]) -> f64 {
+    shapes.iter().fold(0.0, |sum, shape| sum + shape.area())
+}
+
+fn main() {
+    // Each bound the alias stands for must hold.
+    total_area(&[Rc::new(Square(1.0))]);
+    //~^ ERROR `std::rc::Rc<Square>: std::marker::Send` is not satisfied
+
+    // Aliases follow the same object safety rules as traits.
+    let _: Box<EqAlias>; //~ ERROR E0038
+}
@@ -0,0 +1,24 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(trait_alias)]
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+trait SendShape = Shape + Send;
+
+// A trait alias cannot be implemented; implement the traits it names.
+struct Square(f64);
+impl SendShape for Square {} //~ ERROR `SendShape` is a trait alias and cannot be implemented
+
+fn main() {}
@@ -0,0 +1,32 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a trait evaluation cached for one region does not answer a
+// higher-ranked evaluation of the same trait, whose result depends on
+// which regions are involved.
+
+trait Tr<'a> {}
+impl Tr<'static> for u8 {}
+
+fn needs_static<T: Tr<'static>>() {}
+fn needs_any<T: for<'a> Tr<'a>>() {}
+
+fn generic_static<T: Tr<'static>>() {
+    needs_static::<T>();
+}
+
+fn generic_any<T: Tr<'static>>() {
+    needs_any::<T>(); //~ ERROR E0277
+}
+
+fn main() {
+    needs_static::<u8>();
+    needs_any::<u8>(); //~ ERROR E0277
+}
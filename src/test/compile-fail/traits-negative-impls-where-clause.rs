@@ -0,0 +1,30 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A negative impl disqualifies an obligation even when a where-clause
+// or another candidate would otherwise have satisfied it.
+
+#![feature(optin_builtin_traits)]
+
+struct TestType;
+
+impl !Send for TestType {}
+
+fn is_send<T: Send>() {}
+
+fn with_where_clause() where TestType: Send {
+    is_send::<TestType>();
+    //~^ ERROR `TestType: std::marker::Send` is not satisfied
+}
+
+fn main() {
+    with_where_clause();
+    //~^ ERROR `TestType: std::marker::Send` is not satisfied
+}
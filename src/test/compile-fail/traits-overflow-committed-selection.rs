@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that overflow is still an error once selection commits to a
+// candidate that overflows, even though method lookup recovers from
+// it while probing.
+
+trait Never {
+    fn method(&self) -> u32 { 1 }
+}
+
+struct Wrap<T>(T);
+
+impl<T> Never for T where Wrap<T>: Never {}
+
+struct S;
+
+fn main() {
+    Never::method(&S); //~ ERROR E0275
+}
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// Tests that are conservative around thin/fat pointer mismatches.
+// Tests that thin/fat pointer mismatches are caught for each instantiation.
 
 #![allow(dead_code)]
 
@@ -38,4 +38,15 @@ fn f<T, U: ?Sized>(x: &T) -> &U {
     unsafe { transmute(x) } //~ ERROR transmute called with differently sized types
 }
 
-fn main() { }
+fn main() {
+    a::<u8, u8>(&[1]);
+    b::<str, u8>("hello");
+    c::<u8, u16>(&1);
+    d::<u8, u16>(&[1]);
+    e::<[u8], u8>(&[1]);
+    f::<u8, str>(&1);
+
+    // Pointers to unsized types are fine when both sides are fat.
+    a::<u8, str>(&[1]);
+    b::<[u8], str>(&[1]);
+}
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// Tests that are conservative around thin/fat pointer mismatches.
+// Tests that thin/fat pointer mismatches are caught for each instantiation.
 
 #![allow(dead_code)]
 
@@ -20,14 +20,18 @@ struct Foo<T: ?Sized> {
 
 impl<T: ?Sized> Foo<T> {
     fn m(x: &T) -> &isize where T : Sized {
-        // OK here, because T : Sized is in scope.
+        // OK, because T : Sized is required.
         unsafe { transmute(x) }
     }
 
     fn n(x: &T) -> &isize {
-        // Not OK here, because T : Sized is not in scope.
+        // Not OK when instantiated with an unsized T.
         unsafe { transmute(x) } //~ ERROR transmute called with differently sized types
     }
 }
 
-fn main() { }
+fn main() {
+    Foo::<u8>::m(&1);
+    Foo::<u8>::n(&1);
+    Foo::<str>::n("hello");
+}
@@ -8,20 +8,20 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// Tests that `transmute` cannot be called on type parameters.
+// Tests that `transmute` on type parameters is checked for each instantiation.
 
 use std::mem::transmute;
 
 unsafe fn f<T>(x: T) {
-    let _: isize = transmute(x);  //~ ERROR cannot transmute
+    let _: isize = transmute(x);  //~ ERROR transmute called with differently sized types
 }
 
 unsafe fn g<T>(x: (T, isize)) {
-    let _: isize = transmute(x);  //~ ERROR cannot transmute
+    let _: isize = transmute(x);  //~ ERROR transmute called with differently sized types
 }
 
 unsafe fn h<T>(x: [T; 10]) {
-    let _: isize = transmute(x);  //~ ERROR cannot transmute
+    let _: isize = transmute(x);  //~ ERROR transmute called with differently sized types
 }
 
 struct Bad<T> {
@@ -29,7 +29,7 @@ struct Bad<T> {
 }
 
 unsafe fn i<T>(x: Bad<T>) {
-    let _: isize = transmute(x);  //~ ERROR cannot transmute
+    let _: isize = transmute(x);  //~ ERROR transmute called with differently sized types
 }
 
 enum Worse<T> {
@@ -38,11 +38,30 @@ enum Worse<T> {
 }
 
 unsafe fn j<T>(x: Worse<T>) {
-    let _: isize = transmute(x);  //~ ERROR cannot transmute
+    let _: isize = transmute(x);  //~ ERROR transmute called with differently sized types
 }
 
 unsafe fn k<T>(x: Option<T>) {
-    let _: isize = transmute(x);  //~ ERROR cannot transmute
+    let _: isize = transmute(x);  //~ ERROR transmute called with differently sized types
 }
 
-fn main() {}
+unsafe fn l<T: ?Sized>(x: &T) {
+    let _: i8 = transmute(x);
+    //~^ ERROR transmute called with differently sized types
+}
+
+fn main() {
+    unsafe {
+        f(0u8);
+        g((0u8, 0));
+        h([0u8; 10]);
+        i(Bad { f: 0u8 });
+        j(Worse::A(0u8));
+        k(Some(0u8));
+        l("hello");
+
+        // Instantiations for which the sizes match are fine.
+        f(0usize);
+        i(Bad { f: 0isize });
+    }
+}
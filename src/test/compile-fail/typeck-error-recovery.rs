@@ -0,0 +1,41 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a type error in one expression does not cascade into
+// bogus errors in the expressions that depend on it, while independent
+// errors are still reported.
+
+struct S { a: u32 }
+
+fn main() {
+    let s = S { a: 0 };
+    let x = s.b; //~ ERROR no field `b` on type `S`
+    let y = x.no_such_method();
+    let _: String = y;
+    y.another_missing_method();
+
+    let v = Vec::new();
+    v.push(1u32.no_such_method()); //~ ERROR no method named `no_such_method` found
+    let _: &str = v[0];
+
+    // The type of the match is still unknown after the error in its
+    // scrutinee, and would otherwise be reported as such below.
+    let z = match s.c { _ => Default::default() }; //~ ERROR no field `c` on type `S`
+    z.len();
+
+    // An error elsewhere in a block does not affect its value.
+    let w = {
+        let _: u32 = "statement"; //~ ERROR mismatched types
+        Default::default()
+    };
+    w.len(); //~ ERROR the type of this value must be known in this context
+
+    let _: u32 = "independent"; //~ ERROR mismatched types
+}
@@ -0,0 +1,34 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that variances are inferred correctly for items that refer to
+// each other, and for items that use such a cycle, whichever of them
+// is asked for first.
+
+#![feature(rustc_attrs)]
+
+#[rustc_variance]
+struct Uses<'a, T: 'a> { //~ ERROR types=[[o];[];[]], regions=[[-];[];[]]
+    a: A<'a, T>,
+}
+
+#[rustc_variance]
+struct A<'a, T: 'a> { //~ ERROR types=[[o];[];[]], regions=[[-];[];[]]
+    x: &'a T,
+    b: Option<Box<B<'a, T>>>,
+}
+
+#[rustc_variance]
+struct B<'a, T: 'a> { //~ ERROR types=[[o];[];[]], regions=[[-];[];[]]
+    f: fn(T),
+    a: Option<Box<A<'a, T>>>,
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// revisions: rpass1 rpass2
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// Only the body of `x::x` changes, so only the passes that look at
+// that body are redone. The passes that only look at its signature,
+// and the passes over its callers, can reuse their results.
+
+fn main() { }
+
+mod x {
+    #[rustc_clean(label="CollectItem", cfg="rpass2")]
+    #[rustc_clean(label="WfCheck", cfg="rpass2")]
+    #[rustc_dirty(label="TypeckItemBody", cfg="rpass2")]
+    #[rustc_dirty(label="BorrowCheck", cfg="rpass2")]
+    #[rustc_dirty(label="TransCrateItem", cfg="rpass2")]
+    pub fn x() -> usize {
+        #[cfg(rpass1)]
+        let n = 1;
+        #[cfg(rpass2)]
+        let n = 2;
+        n
+    }
+}
+
+mod y {
+    use x;
+
+    #[rustc_clean(label="CollectItem", cfg="rpass2")]
+    #[rustc_clean(label="TypeckItemBody", cfg="rpass2")]
+    #[rustc_clean(label="BorrowCheck", cfg="rpass2")]
+    #[rustc_clean(label="TransCrateItem", cfg="rpass2")]
+    pub fn y() -> usize {
+        x::x()
+    }
+}
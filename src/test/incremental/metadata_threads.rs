@@ -0,0 +1,36 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:cci_class.rs
+// revisions: rpass1 rpass2
+// compile-flags: -Z metadata-threads=2
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+// The dep-graph saved in rpass1 refers to items of `cci_class`. To reuse
+// it in rpass2, those items are looked up in the def-path table of
+// `cci_class`, which is decoded on a metadata worker thread. If the
+// lookup failed, the typeck of `x::x` would have to be redone.
+
+extern crate cci_class;
+
+fn main() {
+    assert_eq!(x::x(), 99);
+}
+
+mod x {
+    use cci_class::kitties::cat;
+
+    #[rustc_clean(label="TypeckItemBody", cfg="rpass2")]
+    pub fn x() -> isize {
+        cat(52, 99).how_hungry
+    }
+}
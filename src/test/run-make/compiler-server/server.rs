@@ -0,0 +1,91 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate syntax;
+
+use rustc_driver::server::{CompilerServer, Diagnostic};
+use syntax::errors::Level;
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+
+fn received(rx: &Receiver<Diagnostic>) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    while let Ok(diagnostic) = rx.try_recv() {
+        diagnostics.push(diagnostic);
+    }
+    diagnostics
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let tmpdir = args[1].clone();
+    let mut sysroot = PathBuf::from(&args[2]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let rustc_args = vec!["rustc".to_string(),
+                          "lib.rs".to_string(),
+                          "--crate-type=lib".to_string(),
+                          "--out-dir".to_string(), tmpdir,
+                          "--sysroot".to_string(), sysroot.display().to_string(),
+                          "--cfg".to_string(), "second".to_string()];
+    let (tx, rx) = channel();
+    let mut server = CompilerServer::new(&rustc_args, tx).unwrap();
+    let lib = Path::new("lib.rs");
+
+    // Unsaved contents are compiled instead of the file on disk.
+    server.file_changed(lib, Some("pub fn f() -> u32 { \"one\" }".to_string()));
+    assert!(server.compile().is_err());
+    let diagnostics = received(&rx);
+    assert!(diagnostics.iter().any(|d| {
+        d.level == Level::Error && d.message.contains("mismatched types") &&
+            d.span.as_ref().map_or(false, |span| span.starts_with("lib.rs:1:"))
+    }));
+
+    // Nothing changed, so the last result is returned again.
+    assert!(server.compile().is_err());
+    assert!(received(&rx).is_empty());
+
+    server.file_changed(lib, Some("pub fn f() -> u32 { 1 }".to_string()));
+    assert!(server.compile().is_ok());
+    assert!(received(&rx).is_empty());
+
+    // Nothing changed, so nothing is compiled.
+    assert!(server.compile().is_ok());
+    assert!(received(&rx).is_empty());
+
+    // Back to the file on disk.
+    server.file_changed(lib, None);
+    assert!(server.compile().is_ok());
+    let diagnostics = received(&rx);
+    assert!(diagnostics.iter().any(|d| {
+        d.level == Level::Warning && d.message.contains("function is never used")
+    }));
+
+    // Only the body of `g` changes, so the type-check of `f` done by the
+    // previous compilation is reused. If it was not, the `rustc_clean`
+    // annotation would make the compilation fail.
+    server.file_changed(lib, Some("#![feature(rustc_attrs)] \
+                                   pub fn f() -> u32 { 1 } \
+                                   pub fn g() -> u32 { 1 }".to_string()));
+    assert!(server.compile().is_ok());
+    server.file_changed(lib, Some("#![feature(rustc_attrs)] \
+                                   #[rustc_clean(label=\"TypeckItemBody\", cfg=\"second\")] \
+                                   pub fn f() -> u32 { 1 } \
+                                   #[rustc_dirty(label=\"TypeckItemBody\", cfg=\"second\")] \
+                                   pub fn g() -> u32 { 2 }".to_string()));
+    assert!(server.compile().is_ok());
+    assert!(received(&rx).is_empty());
+}
@@ -0,0 +1,27 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Prints a program whose only expression is nested in a million
+// parentheses.
+
+const DEPTH: usize = 1_000_000;
+
+fn main() {
+    let mut src = String::from("fn main() { let _ = ");
+    for _ in 0..DEPTH {
+        src.push('(');
+    }
+    src.push('1');
+    for _ in 0..DEPTH {
+        src.push(')');
+    }
+    src.push_str("; }");
+    println!("{}", src);
+}
@@ -0,0 +1,80 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+#![crate_type = "rlib"]
+
+pub fn unchanged(x: u32) -> u32 { x }
+
+#[cfg(old)]
+pub fn changed(x: u32) -> u32 { x }
+
+#[cfg(new)]
+pub fn changed(x: u64) -> u64 { x }
+
+#[cfg(old)]
+pub fn removed() {}
+
+#[cfg(new)]
+pub struct Added;
+
+#[cfg(old)]
+pub struct Fields {
+    pub a: u32,
+}
+
+#[cfg(new)]
+pub struct Fields {
+    pub a: u32,
+    b: u32,
+}
+
+#[cfg(old)]
+fn private() {}
+
+pub trait Convert {
+    type Output;
+}
+
+impl Convert for u32 {
+    type Output = u32;
+}
+
+#[cfg(old)]
+impl Convert for Fields {
+    type Output = u32;
+}
+
+#[cfg(new)]
+impl Convert for Fields {
+    type Output = u64;
+}
+
+#[cfg(new)]
+impl Convert for Added {
+    type Output = Added;
+}
+
+#[cfg(old)]
+impl Convert for u8 {
+    type Output = u8;
+}
+
+#[cfg(new)]
+impl<T: Clone> Convert for Option<T> {
+    type Output = T;
+}
+
+struct Private;
+
+#[cfg(new)]
+impl Convert for Private {
+    type Output = Private;
+}
@@ -0,0 +1,22 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[inline(never)]
+fn big_frame(n: usize) -> u8 {
+    let mut buf = [0u8; 256];
+    for (i, b) in buf.iter_mut().enumerate() {
+        *b = (i * n) as u8;
+    }
+    buf[n % 256]
+}
+
+fn main() {
+    assert_eq!(big_frame(3), 9);
+}
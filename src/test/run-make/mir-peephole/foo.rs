@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub fn eq_true(x: bool) -> bool { x == true }
+
+pub fn eq_false(x: bool) -> bool { x == false }
+
+pub fn ne_false(x: bool) -> bool { x != false }
+
+pub fn reborrow(p: &u32) -> &u32 { &*p }
+
+pub fn reborrow_mut(p: &mut u32) -> &u32 { &*p }
+
+pub fn noop_cast(a: u8) -> u8 { a as u8 }
+
+pub fn real_cast(a: u8) -> i8 { a as i8 }
+
+fn main() {}
@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub fn classify(x: u8) -> u32 {
+    match x {
+        0 => 0,
+        5 | 7 => 2,
+        1...4 => 1,
+        6 => 3,
+        _ => 4,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(3), 1);
+}
@@ -0,0 +1,36 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+// Compiled for MSVC from any host, so there is no standard library.
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[lang = "copy"]
+trait Copy {}
+
+pub struct Pair {
+    pub tuple: (i32, u8),
+}
+
+pub enum Shape {
+    Circle(u32),
+    Square(u8),
+}
+
+impl Pair {
+    pub fn shape(&self) -> Shape {
+        let _tuple = &self.tuple;
+        Shape::Circle(1)
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::env;
+
+trait Shape {
+    fn area(&self) -> u32;
+    fn never_called(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    #[inline(never)]
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+
+    #[inline(never)]
+    fn never_called(&self) -> u32 {
+        self.0 + 1
+    }
+}
+
+fn main() {
+    // Keep the vtable out of the optimizer's sight.
+    let shapes: Vec<Box<Shape>> = env::args().map(|a| {
+        Box::new(Square(a.len() as u32)) as Box<Shape>
+    }).collect();
+    let total = shapes.iter().fold(0, |total, s| total + s.area());
+    assert!(total > 0);
+}
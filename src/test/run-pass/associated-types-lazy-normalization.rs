@@ -0,0 +1,51 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Projections written in a function body are kept as projections while
+// the type is converted and normalized afterwards, including nested ones
+// and those in closure signatures.
+
+trait Node {
+    type Next: Node;
+    fn next(self) -> Self::Next;
+    fn name(&self) -> &'static str;
+}
+
+struct A;
+struct B;
+
+impl Node for A {
+    type Next = B;
+    fn next(self) -> B { B }
+    fn name(&self) -> &'static str { "A" }
+}
+
+impl Node for B {
+    type Next = A;
+    fn next(self) -> A { A }
+    fn name(&self) -> &'static str { "B" }
+}
+
+fn two_steps<T: Node>(t: T) -> <<T as Node>::Next as Node>::Next {
+    let next: T::Next = t.next();
+    let after: <T::Next as Node>::Next = next.next();
+    after
+}
+
+fn main() {
+    let a: <<A as Node>::Next as Node>::Next = A.next().next();
+    assert_eq!(a.name(), "A");
+
+    let step = |x: <A as Node>::Next| -> <B as Node>::Next { x.next() };
+    assert_eq!(step(B).name(), "A");
+
+    assert_eq!(<<A as Node>::Next as Node>::name(&B), "B");
+    assert_eq!(two_steps(B).name(), "B");
+}
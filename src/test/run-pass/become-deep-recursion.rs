@@ -0,0 +1,41 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tail calls made with `become` reuse the caller's frame, so this
+// recursion runs in constant stack space.
+
+#![feature(explicit_tail_calls)]
+
+fn count_down(n: u64, acc: u64) -> u64 {
+    if n == 0 {
+        return acc;
+    }
+    become count_down(n - 1, acc + 1)
+}
+
+fn is_even(n: u64) -> bool {
+    if n == 0 {
+        return true;
+    }
+    become is_odd(n - 1)
+}
+
+fn is_odd(n: u64) -> bool {
+    if n == 0 {
+        return false;
+    }
+    become is_even(n - 1)
+}
+
+fn main() {
+    assert_eq!(count_down(10_000_000, 0), 10_000_000);
+    assert!(is_even(1_000_000));
+    assert!(is_odd(1_000_001));
+}
@@ -0,0 +1,38 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Values dropped or moved elsewhere on every path to a `become` need no
+// drop after the call, so they do not keep it from being a tail call.
+
+#![feature(explicit_tail_calls)]
+
+fn consume(v: Vec<u32>) -> usize { v.len() }
+
+fn drops_beforehand(v: Vec<u32>) -> usize {
+    let x = vec![1, 2, 3];
+    drop(x);
+    become consume(v)
+}
+
+fn moves_on_every_path(v: Vec<u32>, early: bool) -> usize {
+    let x = vec![4, 5];
+    if early {
+        drop(x);
+    } else {
+        let _y = x;
+    }
+    become consume(v)
+}
+
+fn main() {
+    assert_eq!(drops_beforehand(vec![1]), 1);
+    assert_eq!(moves_on_every_path(vec![1, 2], true), 2);
+    assert_eq!(moves_on_every_path(vec![1, 2, 3], false), 3);
+}
@@ -0,0 +1,59 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Bindings after `@` that do not conflict over ownership of the value.
+
+#![feature(bindings_after_at)]
+
+#[derive(Debug, PartialEq)]
+struct Named {
+    name: String,
+    id: u32,
+}
+
+fn id_of(named @ Named { id, .. }: Named) -> (Named, u32) {
+    (named, id)
+}
+
+fn main() {
+    // A copy can be bound both as a whole and in parts.
+    match Some(1) {
+        whole @ Some(part) => assert_eq!((whole, part), (Some(1), 1)),
+        None => panic!(),
+    }
+
+    // By-ref bindings on both sides of the `@`.
+    let pair = (String::from("a"), String::from("b"));
+    match pair {
+        ref both @ (ref first, _) => {
+            assert_eq!(both.1, "b");
+            assert_eq!(first, "a");
+        }
+    }
+
+    // A by-ref binding of a value whose copied fields are also bound.
+    let named = Named { name: String::from("n"), id: 7 };
+    let ref whole @ Named { id, .. } = named;
+    assert_eq!(whole.name, "n");
+    assert_eq!(id, 7);
+
+    // A by-move binding of a value whose copied fields are also bound.
+    let (named, id) = id_of(Named { name: String::from("m"), id: 3 });
+    assert_eq!(named, Named { name: String::from("m"), id: 3 });
+    assert_eq!(id, 3);
+
+    let v = vec![1, 2, 3];
+    match (v, 4) {
+        moved @ (_, n) => {
+            assert_eq!(moved.0, [1, 2, 3]);
+            assert_eq!(n, 4);
+        }
+    }
+}
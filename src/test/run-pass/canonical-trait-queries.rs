@@ -0,0 +1,60 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Normalization of projections that mention inference variables and
+// regions is answered as a canonical query, and the answer applied
+// back to the caller's inference variables. Check that this works for
+// integer variables, type variables and regions, and that answers
+// cached for one set of variables are correct for another.
+
+trait Convert {
+    type Output;
+    fn convert(self) -> Self::Output;
+}
+
+impl<'a, T: Clone> Convert for &'a [T] {
+    type Output = Vec<T>;
+    fn convert(self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+impl<T> Convert for Option<T> {
+    type Output = Result<T, ()>;
+    fn convert(self) -> Result<T, ()> {
+        self.ok_or(())
+    }
+}
+
+fn first<'a, I: IntoIterator<Item=&'a u8>>(iter: I) -> Option<u8> {
+    iter.into_iter().next().cloned()
+}
+
+fn main() {
+    let a = [1, 2, 3];
+    let v = (&a[..]).convert();
+    assert_eq!(v, vec![1, 2, 3]);
+
+    let b: [u64; 2] = [4, 5];
+    let w = (&b[..]).convert();
+    assert_eq!(w.len(), 2);
+    assert_eq!(w[1], 5u64);
+
+    let x = Some(1.5).convert();
+    assert_eq!(x, Ok(1.5));
+
+    let y = None.convert();
+    let z: Result<&str, ()> = y;
+    assert_eq!(z, Err(()));
+
+    let bytes = vec![7u8, 8];
+    assert_eq!(first(&bytes), Some(7));
+    assert_eq!(first(bytes.iter().skip(1)), Some(8));
+}
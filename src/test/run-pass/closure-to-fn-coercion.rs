@@ -0,0 +1,57 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Non-capturing closures coerce to fn pointers, both in old trans and
+// in MIR, and behave like the closure when called through the pointer.
+
+#![feature(closure_to_fn_coercion, rustc_attrs)]
+
+const ADD: fn(i32, i32) -> i32 = |a, b| a + b;
+
+fn apply(f: fn(u32) -> u32, x: u32) -> u32 {
+    f(x)
+}
+
+fn callback(cb: fn(&mut Vec<u8>, u8), v: &mut Vec<u8>) {
+    cb(v, 1);
+    cb(v, 2);
+}
+
+fn make_pair() -> fn() -> (String, u64) {
+    || ("pair".to_string(), 64)
+}
+
+#[rustc_mir]
+fn mir_apply() -> u32 {
+    let double: fn(u32) -> u32 = |x| x * 2;
+    apply(double, 21)
+}
+
+fn generic<T: Clone>() -> fn(T) -> (T, T) {
+    |x: T| (x.clone(), x)
+}
+
+fn main() {
+    let f: fn(u32) -> u32 = |x| x + 1;
+    assert_eq!(f(1), 2);
+    assert_eq!(apply(|x| x * 3, 3), 9);
+    assert_eq!(ADD(2, 3), 5);
+
+    let mut v = vec![];
+    callback(|v, x| v.push(x), &mut v);
+    assert_eq!(v, [1, 2]);
+
+    assert_eq!(make_pair()(), ("pair".to_string(), 64));
+    assert_eq!(mir_apply(), 42);
+    assert_eq!(generic::<&str>()("x"), ("x", "x"));
+
+    let unit: fn() = || {};
+    unit();
+}
@@ -0,0 +1,35 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Constant folding of floats must agree with the same operations at
+// runtime, rounding once to the type of the expression.
+
+const F32_SUM: f32 = 16777216.0 + 1.0 + 1.0;
+const F32_LIT: f32 = 1.00000017881393432617187499;
+const F64_DIV: f64 = 1.0 / 3.0;
+const TINY: f64 = 2.2250738585072014e-308 / 3.0;
+const REM: f64 = -7.5 % 2.0;
+const CANCEL: f64 = 1.5 + -1.5;
+const CANCEL_SUB: f32 = -2.5 - -2.5;
+
+fn id<T>(x: T) -> T { x }
+
+fn main() {
+    assert_eq!(F32_SUM, id(16777216.0f32) + id(1.0) + id(1.0));
+    assert_eq!(F32_SUM, 16777216.0);
+    assert_eq!(F32_LIT, "1.00000017881393432617187499".parse::<f32>().unwrap());
+    assert_eq!(F64_DIV, id(1.0f64) / id(3.0));
+    assert_eq!(TINY, id(2.2250738585072014e-308f64) / id(3.0));
+    assert_eq!(REM, -1.5);
+    assert_eq!(1.0 / CANCEL, 1.0 / (id(1.5f64) + id(-1.5)));
+    assert_eq!(1.0 / CANCEL, ::std::f64::INFINITY);
+    assert_eq!(1.0 / CANCEL_SUB, ::std::f32::INFINITY);
+    assert!((0.0f64 / 0.0) != (0.0f64 / 0.0));
+}
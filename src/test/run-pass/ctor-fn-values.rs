@@ -0,0 +1,46 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tuple struct and variant constructors can be used as function values
+// and coerced to function pointers.
+
+#[derive(Debug, PartialEq)]
+struct Wrapper(u32);
+
+#[derive(Debug, PartialEq)]
+struct Pair<T>(T, T);
+
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle(u32),
+    Rect(u32, u32),
+}
+
+fn apply<A, B, F: Fn(A) -> B>(f: F, a: A) -> B {
+    f(a)
+}
+
+fn main() {
+    let wrapped: Vec<_> = vec![1, 2].into_iter().map(Wrapper).collect();
+    assert_eq!(wrapped, [Wrapper(1), Wrapper(2)]);
+
+    let some = Some as fn(i32) -> Option<i32>;
+    assert_eq!(some(3), Some(3));
+
+    let pair: fn(u8, u8) -> Pair<u8> = Pair;
+    assert_eq!(pair(1, 2), Pair(1, 2));
+
+    let rect: fn(u32, u32) -> Shape = Shape::Rect;
+    assert_eq!(rect(2, 3), Shape::Rect(2, 3));
+    assert_eq!(apply(Shape::Circle, 4), Shape::Circle(4));
+
+    let ok: fn(&str) -> Result<&str, ()> = Ok;
+    assert_eq!(ok("x"), Ok("x"));
+}
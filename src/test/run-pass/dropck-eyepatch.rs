@@ -0,0 +1,56 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(generic_param_attrs)]
+#![feature(dropck_eyepatch)]
+
+// A parameter marked `#[may_dangle]` may be instantiated with data
+// that does not strictly outlive the value being dropped, as long as
+// the destructor only touches the other parameters.
+
+use std::cell::Cell;
+
+struct Pt<'r, A>(A, &'r Cell<u32>);
+unsafe impl<'r, #[may_dangle] A> Drop for Pt<'r, A> {
+    fn drop(&mut self) { self.1.set(self.1.get() + 1); }
+}
+
+struct Pr<'a, 'r>(&'a Cell<u32>, &'r Cell<u32>);
+unsafe impl<#[may_dangle] 'a, 'r> Drop for Pr<'a, 'r> {
+    fn drop(&mut self) { self.1.set(self.1.get() + 1); }
+}
+
+// A container whose elements may refer to each other, as with the
+// standard collections.
+struct Bag<T>(Vec<T>, Cell<u32>);
+unsafe impl<#[may_dangle] T> Drop for Bag<T> {
+    fn drop(&mut self) { self.1.set(self.0.len() as u32); }
+}
+
+struct Node<'a> { next: Cell<Option<&'a Node<'a>>> }
+
+fn main() {
+    let drops = Cell::new(0);
+    {
+        let (c, pt, pr);
+        c = Cell::new(1);
+        pt = Pt(&c, &drops);
+        pr = Pr(&c, &drops);
+        assert_eq!(pt.0.get() + pr.0.get(), 2);
+    }
+    assert_eq!(drops.get(), 2);
+
+    let (a, b, bag);
+    a = Node { next: Cell::new(None) };
+    b = Node { next: Cell::new(Some(&a)) };
+    a.next.set(Some(&b));
+    bag = Bag(vec![&a, &b], Cell::new(0));
+    assert_eq!(bag.0.len(), 2);
+}
@@ -0,0 +1,50 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Type variables instantiated by subtyping are generalized according
+// to the variance of each position: regions in covariant and
+// contravariant positions get fresh variables, while regions in
+// invariant positions are kept as they are.
+
+use std::cell::Cell;
+
+fn covariant<'a>(x: &'a u32) -> u32 {
+    let short = 5;
+    let mut r = Some(x);
+    // `r` may hold a reference shorter than `'a`.
+    if *x == 0 {
+        r = Some(&short);
+    }
+    *r.unwrap()
+}
+
+fn invariant<'a>(cell: &Cell<&'a u32>, x: &'a u32) {
+    let slot = Some(cell);
+    slot.unwrap().set(x);
+}
+
+fn contravariant<'a>(f: fn(&'a u32) -> u32, x: &'a u32) -> u32 {
+    let g = Some(f);
+    g.unwrap()(x)
+}
+
+fn double(x: &u32) -> u32 { *x * 2 }
+
+fn main() {
+    let x = 3;
+    assert_eq!(covariant(&x), 3);
+
+    let y = 4;
+    let cell = Cell::new(&x);
+    invariant(&cell, &y);
+    assert_eq!(*cell.get(), 4);
+
+    assert_eq!(contravariant(double, &x), 6);
+}
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test equating higher-ranked fn pointer and trait object types, which
+// happens whenever they appear in an invariant position.
+
+fn foo<'a>(_: &'a u8, _: &'a u8) {}
+fn bar<'a, 'b>(_: &'a u8, _: &'b u8) {}
+
+trait Tr<'a> {}
+impl<'a> Tr<'a> for () {}
+
+fn main() {
+    // Bound regions are matched up by position, whatever their names.
+    let mut f: for<'x> fn(&'x u8, &'x u8) = foo;
+    let g: &mut for<'y> fn(&'y u8, &'y u8) = &mut f;
+    (*g)(&1, &2);
+
+    let mut h: for<'x, 'y> fn(&'x u8, &'y u8) = bar;
+    let k: &mut for<'y, 'x> fn(&'y u8, &'x u8) = &mut h;
+    (*k)(&1, &2);
+
+    let mut o: Box<for<'x> Tr<'x>> = Box::new(());
+    let p: &mut Box<for<'y> Tr<'y>> = &mut o;
+    let _ = p;
+}
@@ -0,0 +1,41 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics, const_fn)]
+
+use std::intrinsics::variant_count;
+
+enum Color { Red, Green, Blue }
+
+const COLORS: usize = unsafe { variant_count::<Color>() };
+
+static NAMES: [&'static str; unsafe { variant_count::<Color>() }] = ["red", "green", "blue"];
+
+const fn double(n: usize) -> usize { n * 2 }
+
+fn name(c: Color) -> &'static str {
+    NAMES[c as usize]
+}
+
+fn main() {
+    assert_eq!(COLORS, 3);
+    assert_eq!(name(Color::Red), "red");
+    assert_eq!(name(Color::Green), "green");
+    assert_eq!(name(Color::Blue), "blue");
+
+    let table = [0u8; double(COLORS)];
+    assert_eq!(table.len(), 6);
+
+    let pairs: [u32; double(2)] = [7; double(2)];
+    assert_eq!(pairs, [7, 7, 7, 7]);
+
+    assert_eq!(unsafe { variant_count::<Option<u8>>() }, 2);
+    assert_eq!(unsafe { variant_count::<u32>() }, 0);
+}
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The common type of function pointers whose types are not fully known
+// yet is found by falling back to subtyping.
+
+fn id<T>(t: T) -> T { t }
+
+fn double(x: u32) -> u32 { x * 2 }
+
+fn first<'a>(s: &'a str) -> &'a str { &s[..1] }
+
+fn main() {
+    let f = if true { id as fn(_) -> _ } else { double as fn(u32) -> u32 };
+    assert_eq!(f(3), 3);
+
+    // `fn(_) -> _` cannot be a subtype of the higher-ranked fn pointer
+    // type, so the fallback has to make it the supertype instead.
+    for i in 0..2 {
+        let g = match i {
+            0 => first as for<'a> fn(&'a str) -> &'a str,
+            _ => id as fn(_) -> _,
+        };
+        assert_eq!(g("abc"), if i == 0 { "a" } else { "abc" });
+    }
+}
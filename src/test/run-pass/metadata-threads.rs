@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:cci_class.rs
+// compile-flags: -Z metadata-threads=2
+
+// Crates whose def-path tables are decoded on metadata worker threads
+// are usable just as well as ones decoded on first use.
+
+extern crate cci_class;
+
+use cci_class::kitties::cat;
+
+fn main() {
+    let nyan: cat = cat(52, 99);
+    assert_eq!(nyan.how_hungry, 99);
+    let v = vec![1, 2, 3];
+    assert_eq!(v.iter().fold(0, |a, b| a + b), 6);
+}
@@ -0,0 +1,60 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Method picks are reused within a body; make sure repeated calls
+// still resolve according to the traits in scope and the receiver
+// type at each call site.
+
+mod a {
+    pub trait Describe { fn describe(&self) -> &'static str { "a" } }
+    impl Describe for u8 {}
+}
+
+mod b {
+    pub trait Describe { fn describe(&self) -> &'static str { "b" } }
+    impl Describe for u8 {}
+}
+
+#[derive(Default)]
+struct Builder { width: u32, height: u32, name: &'static str }
+
+impl Builder {
+    fn width(mut self, width: u32) -> Builder { self.width = width; self }
+    fn height(mut self, height: u32) -> Builder { self.height = height; self }
+    fn name(mut self, name: &'static str) -> Builder { self.name = name; self }
+}
+
+trait Area { fn area(&self) -> u32; }
+impl Area for Builder { fn area(&self) -> u32 { self.width * self.height } }
+
+fn main() {
+    let x = 0u8;
+    {
+        use a::Describe;
+        assert_eq!(x.describe(), "a");
+    }
+    {
+        use b::Describe;
+        assert_eq!(x.describe(), "b");
+    }
+
+    let b = Builder::default().width(2).height(3).name("b").width(4).height(5);
+    assert_eq!((b.width, b.height, b.name), (4, 5, "b"));
+    assert_eq!(b.area(), 20);
+    assert_eq!((&b).area(), 20);
+
+    // The receiver of the first `push` is only known once inference
+    // has seen the argument.
+    let mut v = Vec::new();
+    v.push(1);
+    v.push(2u16);
+    assert_eq!(v.len(), 2);
+    assert_eq!(v.iter().map(|x| x.count_ones()).fold(0, |a, b| a + b), 2);
+}
@@ -0,0 +1,35 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that method lookup treats a candidate whose where-clauses
+// overflow the recursion limit as one that does not apply, rather
+// than aborting compilation.
+
+trait Never {
+    fn method(&self) -> u32 { 1 }
+}
+
+trait Always {
+    fn method(&self) -> u32 { 2 }
+}
+
+struct Wrap<T>(T);
+
+// Proving `S: Never` requires `Wrap<S>: Never`, which requires
+// `Wrap<Wrap<S>>: Never`, and so on until the recursion limit.
+impl<T> Never for T where Wrap<T>: Never {}
+
+struct S;
+
+impl Always for S {}
+
+fn main() {
+    assert_eq!(S.method(), 2);
+}
@@ -0,0 +1,82 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Arithmetic, division, remainder, negation and shifts are checked in
+// MIR just as in the old trans, including through compound assignment.
+
+// compile-flags: -C debug-assertions
+// ignore-emscripten no threads support
+
+#![feature(rustc_attrs)]
+
+use std::thread;
+
+#[rustc_mir]
+fn add(a: u8, b: u8) -> u8 { a + b }
+
+#[rustc_mir]
+fn sub(a: i16, b: i16) -> i16 { a - b }
+
+#[rustc_mir]
+fn mul_assign(mut a: isize, b: isize) -> isize { a *= b; a }
+
+#[rustc_mir]
+fn div(a: i32, b: i32) -> i32 { a / b }
+
+#[rustc_mir]
+fn rem(a: i8, b: i8) -> i8 { a % b }
+
+#[rustc_mir]
+fn neg(a: i64) -> i64 { -a }
+
+#[rustc_mir]
+fn shl(a: u8, b: i32) -> u8 { a << b }
+
+#[rustc_mir]
+fn shr_assign(mut a: isize, b: u64) -> isize { a >>= b; a }
+
+#[rustc_mir]
+fn div_assign(mut a: usize, b: usize) -> usize { a /= b; a }
+
+fn panics<F: FnOnce() + Send + 'static>(f: F) -> bool {
+    thread::spawn(f).join().is_err()
+}
+
+fn main() {
+    assert_eq!(add(200, 55), 255);
+    assert!(panics(|| { add(200, 56); }));
+
+    assert_eq!(sub(-32767, 1), -32768);
+    assert!(panics(|| { sub(-32768, 1); }));
+
+    assert_eq!(mul_assign(-4, 5), -20);
+    assert!(panics(|| { mul_assign(isize::max_value(), 2); }));
+
+    assert_eq!(div(7, 2), 3);
+    assert!(panics(|| { div(1, 0); }));
+    assert!(panics(|| { div(i32::min_value(), -1); }));
+
+    assert_eq!(rem(-7, 2), -1);
+    assert!(panics(|| { rem(1, 0); }));
+    assert!(panics(|| { rem(i8::min_value(), -1); }));
+
+    assert_eq!(neg(5), -5);
+    assert!(panics(|| { neg(i64::min_value()); }));
+
+    assert_eq!(shl(1, 7), 128);
+    assert!(panics(|| { shl(1, 8); }));
+    assert!(panics(|| { shl(1, -1); }));
+
+    assert_eq!(shr_assign(-8, 2), -2);
+    assert!(panics(|| { shr_assign(1, 64); }));
+
+    assert_eq!(div_assign(9, 3), 3);
+    assert!(panics(|| { div_assign(1, 0); }));
+}
@@ -0,0 +1,63 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_attrs, box_syntax)]
+
+use std::panic;
+
+struct Big {
+    data: [u64; 1024],
+    tag: u8,
+}
+
+fn make_big(tag: u8) -> Big {
+    Big { data: [tag as u64; 1024], tag: tag }
+}
+
+#[rustc_mir]
+fn box_call(tag: u8) -> Box<Big> {
+    box make_big(tag)
+}
+
+#[rustc_mir]
+fn box_aggregate(tag: u8) -> Box<Big> {
+    box Big { data: [7; 1024], tag: tag }
+}
+
+#[rustc_mir]
+fn box_block(tag: u8) -> Box<Big> {
+    box {
+        let mut b = make_big(0);
+        b.tag = tag;
+        b
+    }
+}
+
+#[rustc_mir]
+fn box_panics() -> Box<Big> {
+    box { panic!("initializer panicked") }
+}
+
+fn main() {
+    let b = box_call(3);
+    assert_eq!(b.tag, 3);
+    assert_eq!(b.data[1023], 3);
+
+    let b = box_aggregate(4);
+    assert_eq!(b.tag, 4);
+    assert_eq!(b.data[0], 7);
+
+    let b = box_block(5);
+    assert_eq!(b.tag, 5);
+    assert_eq!(b.data[512], 0);
+
+    // The allocation is freed on the unwind path.
+    assert!(panic::catch_unwind(|| box_panics()).is_err());
+}
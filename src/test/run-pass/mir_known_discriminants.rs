@@ -0,0 +1,95 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=2
+
+// Nested matches over the same enum reuse what the outer switch
+// learned, without going wrong when the value changes in between.
+
+#![feature(rustc_attrs)]
+
+#[derive(Copy, Clone)]
+enum E { A(u32), B, C }
+
+#[rustc_mir]
+fn nested(x: Option<u32>) -> u32 {
+    match x {
+        Some(_) => match x { Some(y) => y, None => 0 },
+        None => 1,
+    }
+}
+
+#[rustc_mir]
+fn constructed(b: bool) -> u32 {
+    let e = if b { E::A(7) } else { E::B };
+    match e {
+        E::A(n) => n,
+        E::B => 2,
+        E::C => 3,
+    }
+}
+
+#[rustc_mir]
+fn reassigned(mut e: E) -> u32 {
+    match e {
+        E::A(_) => {
+            e = E::C;
+            match e { E::A(n) => n, E::B => 10, E::C => 11 }
+        }
+        _ => match e { E::B => 12, _ => 13 },
+    }
+}
+
+fn set_b(e: &mut E) {
+    *e = E::B;
+}
+
+#[rustc_mir]
+fn borrowed(mut e: E) -> u32 {
+    match e {
+        E::A(_) => {
+            set_b(&mut e);
+            match e { E::A(n) => n, E::B => 20, E::C => 21 }
+        }
+        _ => 22,
+    }
+}
+
+#[rustc_mir]
+fn looped(mut e: E) -> u32 {
+    let mut steps = 0;
+    loop {
+        match e {
+            E::A(0) => return steps,
+            E::A(n) => e = E::A(n - 1),
+            E::B => e = E::C,
+            E::C => e = E::A(2),
+        }
+        steps += 1;
+    }
+}
+
+fn main() {
+    assert_eq!(nested(Some(5)), 5);
+    assert_eq!(nested(None), 1);
+
+    assert_eq!(constructed(true), 7);
+    assert_eq!(constructed(false), 2);
+
+    assert_eq!(reassigned(E::A(1)), 11);
+    assert_eq!(reassigned(E::B), 12);
+    assert_eq!(reassigned(E::C), 13);
+
+    assert_eq!(borrowed(E::A(1)), 20);
+    assert_eq!(borrowed(E::C), 22);
+
+    assert_eq!(looped(E::A(1)), 1);
+    assert_eq!(looped(E::B), 4);
+}
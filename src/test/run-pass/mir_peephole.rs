@@ -0,0 +1,70 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=2
+
+// Exercise the MIR peephole rules and check that the rewritten code
+// still computes the same results.
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir]
+fn eq_true(x: bool) -> bool { x == true }
+
+#[rustc_mir]
+fn true_eq(x: bool) -> bool { true == x }
+
+#[rustc_mir]
+fn eq_false(x: bool) -> bool { x == false }
+
+#[rustc_mir]
+fn ne_true(x: bool) -> bool { x != true }
+
+#[rustc_mir]
+fn ne_false(x: bool) -> bool { x != false }
+
+#[rustc_mir]
+fn reborrow(p: &u32) -> &u32 { &*p }
+
+#[rustc_mir]
+fn reborrow_mut(p: &mut u32) -> &u32 { &*p }
+
+#[rustc_mir]
+fn reborrow_box(p: &Box<u32>) -> &u32 { &**p }
+
+#[rustc_mir]
+fn noop_casts(a: u8, b: i64, c: f32) -> (u8, i64, f32) {
+    (a as u8, b as i64, c as f32)
+}
+
+#[rustc_mir]
+fn real_casts(a: u8, b: i64) -> (i8, u32) {
+    (a as i8, b as u32)
+}
+
+fn main() {
+    for &x in &[true, false] {
+        assert_eq!(eq_true(x), x);
+        assert_eq!(true_eq(x), x);
+        assert_eq!(eq_false(x), !x);
+        assert_eq!(ne_true(x), !x);
+        assert_eq!(ne_false(x), x);
+    }
+
+    let x = 7;
+    assert_eq!(reborrow(&x) as *const u32, &x as *const u32);
+    let mut y = 8;
+    assert_eq!(*reborrow_mut(&mut y), 8);
+    let b = Box::new(9);
+    assert_eq!(*reborrow_box(&b), 9);
+
+    assert_eq!(noop_casts(1, -2, 3.5), (1, -2, 3.5));
+    assert_eq!(real_casts(255, -1), (-1, 4294967295));
+}
@@ -0,0 +1,86 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Matches mixing constants and small integer/char ranges are lowered to
+// a single sorted `SwitchInt`; large ranges still use a range test.
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir]
+fn classify(x: u8) -> u32 {
+    match x {
+        0 => 0,
+        1...4 => 1,
+        5 | 7 => 2,
+        6 => 3,
+        8...200 => 4,
+        _ => 5,
+    }
+}
+
+#[rustc_mir]
+fn signed(x: i32) -> i32 {
+    match x {
+        -3...-1 => -1,
+        0 => 0,
+        1...3 => 1,
+        _ => 2,
+    }
+}
+
+#[rustc_mir]
+fn letter(c: char) -> u32 {
+    match c {
+        'a'...'e' => 0,
+        'x' => 1,
+        'f'...'z' => 2,
+        _ => 3,
+    }
+}
+
+#[rustc_mir]
+fn guarded(x: u16, b: bool) -> u32 {
+    match x {
+        1...3 if b => 0,
+        2 => 1,
+        1...3 => 2,
+        _ => 3,
+    }
+}
+
+fn main() {
+    let expected = [0, 1, 1, 1, 1, 2, 3, 2];
+    for (i, &e) in expected.iter().enumerate() {
+        assert_eq!(classify(i as u8), e);
+    }
+    assert_eq!(classify(8), 4);
+    assert_eq!(classify(200), 4);
+    assert_eq!(classify(201), 5);
+    assert_eq!(classify(255), 5);
+
+    assert_eq!(signed(-4), 2);
+    assert_eq!(signed(-3), -1);
+    assert_eq!(signed(-1), -1);
+    assert_eq!(signed(0), 0);
+    assert_eq!(signed(3), 1);
+    assert_eq!(signed(4), 2);
+
+    assert_eq!(letter('a'), 0);
+    assert_eq!(letter('e'), 0);
+    assert_eq!(letter('f'), 2);
+    assert_eq!(letter('x'), 1);
+    assert_eq!(letter('z'), 2);
+    assert_eq!(letter('A'), 3);
+
+    assert_eq!(guarded(2, true), 0);
+    assert_eq!(guarded(2, false), 1);
+    assert_eq!(guarded(3, false), 2);
+    assert_eq!(guarded(4, true), 3);
+}
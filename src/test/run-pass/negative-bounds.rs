@@ -0,0 +1,42 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Negative bounds make otherwise overlapping impls disjoint. That a type
+// has no impl of a local trait is enough for a negative bound on it.
+
+#![feature(negative_bounds)]
+
+trait Small {}
+
+impl Small for u32 {}
+
+trait Describe {
+    fn describe(&self) -> &'static str;
+}
+
+impl<T: Small> Describe for T {
+    fn describe(&self) -> &'static str { "small" }
+}
+
+impl<T: !Small> Describe for T {
+    fn describe(&self) -> &'static str { "large" }
+}
+
+struct Large;
+
+fn describe_large<T: !Small>(t: T) -> &'static str {
+    t.describe()
+}
+
+fn main() {
+    assert_eq!(1u32.describe(), "small");
+    assert_eq!(Large.describe(), "large");
+    assert_eq!(describe_large(Large), "large");
+}
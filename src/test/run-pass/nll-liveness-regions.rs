@@ -0,0 +1,65 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z nll
+
+// With liveness-based regions, a borrow is only in effect until the
+// last use of the reference it created, rather than until the end of
+// the enclosing block.
+
+struct Pair {
+    a: i32,
+    b: i32,
+}
+
+fn last_use(mut x: i32) -> i32 {
+    let r = &mut x;
+    *r += 1;
+    let y = &x;
+    *y
+}
+
+fn reassigned() -> (i32, i32) {
+    let mut a = 1;
+    let mut b = 2;
+    let mut r = &mut a;
+    *r += 1;
+    r = &mut b;
+    a += 1;
+    *r += a;
+    (a, b)
+}
+
+fn index_in_loop() -> Vec<i32> {
+    let mut v = vec![1, 2, 3];
+    let mut total = 0;
+    for i in 0..3 {
+        let elem = &mut v[i];
+        *elem += 1;
+        total += v.len();
+    }
+    assert_eq!(total, 9);
+    v
+}
+
+fn disjoint_fields() -> i32 {
+    let mut p = Pair { a: 1, b: 2 };
+    let ra = &mut p.a;
+    p.b += 1;
+    *ra += p.b;
+    p.a
+}
+
+fn main() {
+    assert_eq!(last_use(1), 2);
+    assert_eq!(reassigned(), (3, 5));
+    assert_eq!(index_in_loop(), [2, 3, 4]);
+    assert_eq!(disjoint_fields(), 4);
+}
@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C opt-level=z -Z force-overflow-checks=on -Z no-panic-messages
+// ignore-emscripten no threads support
+
+// Panics inserted by the compiler still unwind under -Z no-panic-messages,
+// but carry an empty message.
+
+use std::thread;
+
+fn add(a: u8, b: u8) -> u8 {
+    a + b
+}
+
+fn main() {
+    let err = thread::spawn(|| add(255, 1)).join().unwrap_err();
+    assert_eq!(err.downcast_ref::<String>().map(|s| &s[..]), Some(""));
+}
@@ -0,0 +1,33 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z opportunistic-resolve
+
+// Inference results must not change when committed snapshots shorten
+// the variable chains they created.
+
+fn pick<T>(a: T, b: T, first: bool) -> T {
+    if first { a } else { b }
+}
+
+fn main() {
+    let mut v = Vec::new();
+    v.push(pick(1, 2, false));
+    v.push(3);
+    let total: u8 = v.iter().fold(0, |acc, &x| acc + x);
+    assert_eq!(total, 5);
+
+    let x = pick(1.5, 2.0, true);
+    let y: f32 = x * 2.0;
+    assert_eq!(y, 3.0);
+
+    let closure = |a, b| pick(a, b, true);
+    assert_eq!(closure(Some(7i64), None), Some(7));
+}
@@ -0,0 +1,83 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Derived impls and macro-expanded items are subject to the same privacy
+// rules as hand-written code at the place they expand into.
+
+#[macro_use]
+mod a {
+    #[derive(Clone, Debug, PartialEq, Default)]
+    struct Private {
+        x: i32,
+        y: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct Public(Private);
+
+    macro_rules! make_private {
+        ($name:ident, $x:expr) => {
+            fn $name() -> Private { Private { x: $x, y: vec![] } }
+        }
+    }
+
+    make_private!(one, 1);
+
+    pub fn public() -> Public {
+        Public(one())
+    }
+
+    pub fn check() {
+        let p = one();
+        assert_eq!(p.clone(), p);
+        assert_eq!(Public(p.clone()).clone().0.x, 1);
+        assert_eq!(Private::default().y, vec![]);
+    }
+
+    macro_rules! first {
+        ($e:expr) => { $e.0 }
+    }
+
+    impl Public {
+        pub fn x(&self) -> i32 {
+            first!(self).x
+        }
+    }
+}
+
+#[macro_use]
+mod b {
+    #[derive(Clone, Copy)]
+    pub struct Point {
+        pub x: i32,
+        y: i32,
+    }
+
+    pub fn origin() -> Point {
+        Point { x: 0, y: 0 }
+    }
+
+    macro_rules! x_of {
+        ($e:expr) => { $e.x }
+    }
+
+    pub fn y_of(p: Point) -> i32 {
+        p.clone().y
+    }
+}
+
+fn main() {
+    a::check();
+    let p = a::public();
+    assert_eq!(p.clone(), p);
+    assert_eq!(p.x(), 1);
+    assert_eq!(x_of!(b::origin()), 0);
+    assert_eq!(b::y_of(b::origin()), 0);
+}
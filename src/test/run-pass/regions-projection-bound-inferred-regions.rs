@@ -0,0 +1,43 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Bounds on a projection known from the environment also apply to
+// the same projection when the regions in its trait reference are
+// only determined by region inference, as in the calls to `hold`.
+
+trait Trait<'a> {
+    type Out;
+}
+
+impl<'a> Trait<'a> for () {
+    type Out = &'a str;
+}
+
+fn hold<'b, 'x, T>(o: &'b <T as Trait<'x>>::Out) -> &'b <T as Trait<'x>>::Out
+    where T: Trait<'x>, <T as Trait<'x>>::Out: 'b
+{
+    o
+}
+
+fn implied<'a, 'b, T: Trait<'a>>(o: &'b <T as Trait<'a>>::Out) -> &'b <T as Trait<'a>>::Out {
+    hold::<T>(o)
+}
+
+fn declared<'a, 'b, T>(o: &'b <T as Trait<'a>>::Out) -> Option<&'b <T as Trait<'a>>::Out>
+    where T: Trait<'a>, <T as Trait<'a>>::Out: 'b
+{
+    Some(hold::<T>(o))
+}
+
+fn main() {
+    let s = "hi";
+    assert_eq!(*implied::<()>(&s), "hi");
+    assert_eq!(*declared::<()>(&s).unwrap(), "hi");
+}
@@ -0,0 +1,40 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Float to integer casts saturate at the bounds of the integer type, and
+// NaN becomes zero, both at runtime and in constants.
+
+use std::{f32, f64};
+
+const CONST_NEG: u8 = -1.0 as u8;
+const CONST_BIG: i32 = 1e10 as i32;
+const CONST_NAN: u32 = f64::NAN as u32;
+
+fn id<T>(x: T) -> T { x }
+
+fn main() {
+    assert_eq!(id(300.0f64) as u8, 255);
+    assert_eq!(id(-1.0f64) as u8, 0);
+    assert_eq!(id(255.9f64) as u8, 255);
+    assert_eq!(id(-0.9f64) as u8, 0);
+    assert_eq!(id(128.0f32) as i8, 127);
+    assert_eq!(id(-129.0f32) as i8, -128);
+    assert_eq!(id(f64::INFINITY) as i64, i64::max_value());
+    assert_eq!(id(f64::NEG_INFINITY) as i64, i64::min_value());
+    assert_eq!(id(f32::INFINITY) as u64, u64::max_value());
+    assert_eq!(id(f64::NAN) as i32, 0);
+    assert_eq!(id(f32::NAN) as usize, 0);
+    assert_eq!(id(9.3e18f64) as i64, i64::max_value());
+    assert_eq!(id(-9.3e18f64) as i64, i64::min_value());
+
+    assert_eq!(CONST_NEG, 0);
+    assert_eq!(CONST_BIG, i32::max_value());
+    assert_eq!(CONST_NAN, 0);
+}
@@ -0,0 +1,76 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `Self` names the impl's self type in struct literals, constructor
+// calls and paths to associated items.
+
+#![feature(associated_consts)]
+
+#[derive(PartialEq, Debug)]
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+impl<T: Copy> Point<T> {
+    fn new(x: T, y: T) -> Self {
+        Self { x: x, y: y }
+    }
+
+    fn swapped(&self) -> Self {
+        Self::new(self.y, self.x)
+    }
+
+    fn with_x(&self, x: T) -> Self {
+        Self { x: x, ..*self }
+    }
+}
+
+impl Point<u8> {
+    const ORIGIN: Point<u8> = Point { x: 0, y: 0 };
+
+    fn origin() -> Self {
+        Self::ORIGIN
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Wrapper<T>(T);
+
+impl<T> Wrapper<T> {
+    fn new(t: T) -> Self {
+        Self(t)
+    }
+
+    fn map_same(self, f: fn(T) -> T) -> Self {
+        let ctor = Self;
+        ctor(f(self.0))
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Unit;
+
+impl Unit {
+    fn new() -> Self {
+        Self
+    }
+}
+
+fn main() {
+    let p = Point::new(1, 2);
+    assert_eq!(p.swapped(), Point { x: 2, y: 1 });
+    assert_eq!(p.with_x(5), Point { x: 5, y: 2 });
+    assert_eq!(Point::origin(), Point { x: 0, y: 0 });
+
+    fn double(x: u32) -> u32 { x * 2 }
+    assert_eq!(Wrapper::new(3).map_same(double), Wrapper(6));
+    assert_eq!(Unit::new(), Unit);
+}
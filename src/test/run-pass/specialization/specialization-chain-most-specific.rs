@@ -0,0 +1,41 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(specialization)]
+
+// Selection prefers the most specific applicable impl in a three-level
+// chain, also when the same pairs of impls are compared many times.
+
+trait Name {
+    fn name(&self) -> &'static str;
+}
+
+impl<T> Name for T {
+    default fn name(&self) -> &'static str { "any" }
+}
+
+impl<T: Clone> Name for T {
+    default fn name(&self) -> &'static str { "clone" }
+}
+
+impl Name for u8 {
+    fn name(&self) -> &'static str { "u8" }
+}
+
+struct NotClone;
+
+fn main() {
+    for _ in 0..3 {
+        assert_eq!(NotClone.name(), "any");
+        assert_eq!(String::new().name(), "clone");
+        assert_eq!(0u8.name(), "u8");
+        assert_eq!(0u16.name(), "clone");
+    }
+}
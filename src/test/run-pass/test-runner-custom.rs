@@ -0,0 +1,45 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test
+
+#![feature(custom_test_frameworks)]
+#![test_runner(runner)]
+
+use std::cell::Cell;
+
+thread_local!(static RAN: Cell<usize> = Cell::new(0));
+
+fn runner(cases: &[&Fn()]) {
+    assert_eq!(cases.len(), 3);
+    for case in cases {
+        case();
+    }
+    assert_eq!(RAN.with(|ran| ran.get()), 3);
+}
+
+fn count() {
+    RAN.with(|ran| ran.set(ran.get() + 1));
+}
+
+#[test]
+fn plain_test() {
+    count();
+}
+
+#[test_case]
+static FROM_STATIC: fn() = count;
+
+mod nested {
+    #[test_case]
+    fn in_module() {
+        super::count();
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// aux-build:trait_alias.rs
+
+extern crate trait_alias;
+
+use trait_alias::{GreetClone, GreetSend};
+
+fn greet_twice<T: GreetClone>(t: &T) -> String {
+    let copy = t.clone();
+    format!("{}, {}", t.greet(), copy.greet())
+}
+
+fn main() {
+    assert_eq!(greet_twice(&7u32), "hello 7, hello 7");
+
+    let boxed: Box<GreetSend> = Box::new(8u32);
+    assert_eq!(boxed.greet(), "hello 8");
+}
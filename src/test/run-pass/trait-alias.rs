@@ -0,0 +1,65 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Trait aliases can be used as bounds and as object types, and the
+// bounds they stand for can be relied upon through them.
+
+#![feature(trait_alias)]
+
+use std::fmt::Debug;
+use std::thread;
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+trait SendShape = Shape + Send;
+trait DebugShape = Shape + Debug;
+trait Slice<T> = AsRef<[T]> where T: Clone;
+
+#[derive(Debug)]
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+fn total_area<T: SendShape>(shapes: &[T]) -> f64 {
+    shapes.iter().fold(0.0, |sum, shape| sum + shape.area())
+}
+
+fn describe<T>(shape: &T) -> String where T: DebugShape {
+    format!("{:?} has area {}", shape, shape.area())
+}
+
+fn first<T, S: Slice<T>>(slice: &S) -> T where T: Clone {
+    slice.as_ref()[0].clone()
+}
+
+fn main() {
+    let squares = [Square(1.0), Square(2.0)];
+    assert_eq!(total_area(&squares), 5.0);
+    assert_eq!(describe(&Square(1.5)), "Square(1.5) has area 2.25");
+    assert_eq!(first(&vec![3, 4]), 3);
+
+    // An alias naming a single trait plus builtin traits is an object type.
+    let shape: Box<SendShape> = Box::new(Square(4.0));
+    let area = thread::spawn(move || shape.area()).join().unwrap();
+    assert_eq!(area, 16.0);
+
+    // As is an alias naming several traits, as long as all of them are
+    // object safe.
+    let shape: &DebugShape = &Square(0.5);
+    assert_eq!(format!("{:?}", shape), "Square(0.5)");
+    assert_eq!(shape.area(), 0.25);
+}
@@ -0,0 +1,43 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Transmutes involving type parameters are accepted as long as every
+// instantiation of them is between types of the same size.
+
+use std::mem::transmute;
+
+struct Wrapper<T>(T);
+
+fn unwrap<T>(w: Wrapper<T>) -> T {
+    unsafe { transmute(w) }
+}
+
+struct Foo<T>(Vec<T>);
+
+fn to_foo<T>(v: Vec<T>) -> Foo<T> {
+    unsafe { transmute(v) }
+}
+
+fn cast<T: ?Sized, U: ?Sized>(x: &T) -> &U {
+    unsafe { transmute(x) }
+}
+
+fn main() {
+    assert_eq!(unwrap(Wrapper(5u32)), 5);
+    assert_eq!(unwrap(Wrapper("hello")), "hello");
+
+    let Foo(v) = to_foo(vec![1, 2, 3]);
+    assert_eq!(v, [1, 2, 3]);
+
+    let bytes: &[u8] = cast("hi");
+    assert_eq!(bytes, b"hi");
+    let n: &i32 = cast(&7u32);
+    assert_eq!(*n, 7);
+}
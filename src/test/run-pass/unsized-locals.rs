@@ -0,0 +1,59 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(unsized_locals)]
+
+use std::cell::Cell;
+
+struct Bump<'a>(&'a Cell<u32>);
+
+impl<'a> Drop for Bump<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn len(x: [u8]) -> usize {
+    x.len()
+}
+
+trait Consume {
+    fn consume(self) -> String;
+}
+
+impl Consume for String {
+    fn consume(self) -> String {
+        self
+    }
+}
+
+fn main() {
+    let b: Box<[u8]> = Box::new([1, 2, 3]);
+    assert_eq!(len(*b), 3);
+
+    let f: Box<FnOnce() -> i32> = Box::new(|| 1);
+    assert_eq!((*f)(), 1);
+
+    let s = String::from("hello");
+    let f: Box<FnOnce(&str) -> String> = Box::new(move |t| s + t);
+    assert_eq!((*f)(" world"), "hello world");
+
+    let c: Box<Consume> = Box::new(String::from("consumed"));
+    assert_eq!(c.consume(), "consumed");
+
+    let drops = Cell::new(0);
+    {
+        let bump = Bump(&drops);
+        let f: Box<FnOnce()> = Box::new(move || drop(bump));
+        (*f)();
+        assert_eq!(drops.get(), 1);
+    }
+    assert_eq!(drops.get(), 1);
+}